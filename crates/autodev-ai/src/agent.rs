@@ -1,16 +1,27 @@
 use async_trait::async_trait;
 use autodev_core::Task;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::prompts::{self, PromptVars};
+use crate::schema::SecurityReport;
+use crate::stream::StreamSink;
+use crate::structured;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub enum AgentType {
+    #[default]
     Claude,
+    OpenAI,
+    Ollama,
 }
 
 impl std::fmt::Display for AgentType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             AgentType::Claude => write!(f, "claude"),
+            AgentType::OpenAI => write!(f, "openai"),
+            AgentType::Ollama => write!(f, "ollama"),
         }
     }
 }
@@ -23,14 +34,71 @@ impl std::str::FromStr for AgentType {
             "claude" | "claude-code" | "claude-3" | "claude-opus" | "claude-sonnet" => {
                 Ok(AgentType::Claude)
             }
-            _ => Err(format!("Unsupported agent type: {}. Only Claude is supported.", s)),
+            "openai" | "gpt-4" | "gpt-4o" | "gpt" => Ok(AgentType::OpenAI),
+            "ollama" | "local" => Ok(AgentType::Ollama),
+            _ => Err(format!("Unsupported agent type: {}. Supported: claude, openai, ollama.", s)),
+        }
+    }
+}
+
+/// Operation an agent may support
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// `execute_task`: edit code for a task
+    ExecuteTask,
+    /// `review_code_changes`
+    ReviewCode,
+    /// `fix_ci_failures`
+    FixCi,
+    /// `generate_commit_message`
+    CommitMessage,
+    /// `analyze_security`
+    SecurityAnalysis,
+    /// `chat_json` / `chat_structured`: decomposition, domain detection, ...
+    Chat,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 6] = [
+        Capability::ExecuteTask,
+        Capability::ReviewCode,
+        Capability::FixCi,
+        Capability::CommitMessage,
+        Capability::SecurityAnalysis,
+        Capability::Chat,
+    ];
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Capability::ExecuteTask => write!(f, "execute"),
+            Capability::ReviewCode => write!(f, "review"),
+            Capability::FixCi => write!(f, "fix_ci"),
+            Capability::CommitMessage => write!(f, "commit_message"),
+            Capability::SecurityAnalysis => write!(f, "security"),
+            Capability::Chat => write!(f, "chat"),
         }
     }
 }
 
-impl Default for AgentType {
-    fn default() -> Self {
-        AgentType::Claude
+impl std::str::FromStr for Capability {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "execute" | "execute_task" | "code" => Ok(Capability::ExecuteTask),
+            "review" | "review_code" => Ok(Capability::ReviewCode),
+            "fix_ci" | "ci" => Ok(Capability::FixCi),
+            "commit_message" | "commit" => Ok(Capability::CommitMessage),
+            "security" | "security_analysis" => Ok(Capability::SecurityAnalysis),
+            "chat" | "decompose" | "plan" => Ok(Capability::Chat),
+            _ => Err(format!(
+                "Unknown operation: {}. Supported: execute, review, fix_ci, commit_message, security, chat.",
+                s
+            )),
+        }
     }
 }
 
@@ -55,6 +123,18 @@ pub trait AIAgent: Send + Sync {
     /// Get agent type
     fn agent_type(&self) -> AgentType;
 
+    /// Model the agent talks to
+    fn model(&self) -> &str;
+
+    /// Operations the agent supports (the others return an error)
+    fn capabilities(&self) -> Vec<Capability> {
+        Capability::ALL.to_vec()
+    }
+
+    fn supports(&self, capability: Capability) -> bool {
+        self.capabilities().contains(&capability)
+    }
+
     /// Execute a task
     async fn execute_task(
         &self,
@@ -95,6 +175,115 @@ pub trait AIAgent: Send + Sync {
         system_prompt: &str,
         user_prompt: &str,
     ) -> crate::Result<String>;
+
+    /// Chat for a JSON response following the JSON Schema `schema`
+    ///
+    /// Agents that support it enforce the schema at the API level; the others
+    /// fall back to JSON mode. See [`crate::structured::chat_structured`].
+    async fn chat_structured(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        _schema: &serde_json::Value,
+    ) -> crate::Result<String> {
+        self.chat_json(system_prompt, user_prompt).await
+    }
+
+    /// Report that the `chat_structured` response to these prompts was rejected
+    ///
+    /// Agents that cache responses drop it so the next call asks the model again.
+    async fn reject_response(&self, _system_prompt: &str, _user_prompt: &str, _schema: &serde_json::Value) {}
+
+    /// Chat with JSON mode, passing the response text to `on_text` as it is generated
+    ///
+    /// Agents that cannot stream pass the whole response once it is complete.
+    async fn chat_stream(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        on_text: StreamSink,
+    ) -> crate::Result<String> {
+        crate::stream::stream_or_emit(on_text, self.chat_json(system_prompt, user_prompt)).await
+    }
+
+    /// Check that the provider accepts the agent's credentials, without
+    /// generating anything (no tokens are used)
+    ///
+    /// Ok(false) when the agent has nothing it can check this way.
+    async fn check_credentials(&self) -> crate::Result<bool> {
+        Ok(false)
+    }
+}
+
+/// [`AIAgent::check_credentials`] of every agent; true if any was checked
+pub(crate) async fn check_all_credentials(agents: &[&Arc<dyn AIAgent>]) -> crate::Result<bool> {
+    let mut checked = false;
+    for agent in agents {
+        checked |= agent.check_credentials().await?;
+    }
+    Ok(checked)
+}
+
+/// Review a diff split into chunks, one request per chunk
+///
+/// Results are merged; the review succeeds only if every chunk does.
+pub async fn review_diff_chunks<A: AIAgent + ?Sized>(
+    agent: &A,
+    chunks: &[String],
+    review_comments: &[String],
+) -> crate::Result<ReviewResult> {
+    if chunks.len() <= 1 {
+        let diff = chunks.first().map(String::as_str).unwrap_or_default();
+        return agent.review_code_changes(diff, review_comments).await;
+    }
+
+    let mut merged = ReviewResult {
+        success: true,
+        changes_made: Vec::new(),
+        comments: Vec::new(),
+    };
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        tracing::info!("Reviewing diff part {}/{}", i + 1, chunks.len());
+
+        // 모델이 전체 diff의 일부만 보고 있다는 것을 알 수 있도록 표시
+        let mut comments = review_comments.to_vec();
+        comments.push(format!("(diff part {} of {})", i + 1, chunks.len()));
+
+        let result = agent.review_code_changes(chunk, &comments).await?;
+        merged.success &= result.success;
+        merged.changes_made.extend(result.changes_made);
+        merged.comments.extend(result.comments);
+    }
+
+    Ok(merged)
+}
+
+/// Analyze `code` for security issues, asking for a [`SecurityReport`]
+///
+/// Shared by the agents' [`AIAgent::analyze_security`].
+pub(crate) async fn analyze_security_structured(
+    agent: &dyn AIAgent,
+    code: &str,
+    language: &str,
+) -> crate::Result<Vec<SecurityIssue>> {
+    let prompts = prompts::global();
+    let vars = PromptVars::new().set("code", code).set("language", language);
+    let system_prompt = prompts.render("security_analysis_system.txt", &vars)?;
+    // system 프롬프트는 따로 보내므로 사용자 프롬프트에는 넣지 않음
+    let user_prompt = prompts.render("security_analysis.txt", &vars.set("system_prompt", ""))?;
+
+    let report: SecurityReport = structured::chat_structured(
+        agent,
+        &system_prompt,
+        user_prompt.trim_start(),
+        &SecurityReport::json_schema(),
+        structured::DEFAULT_ATTEMPTS,
+        SecurityReport::validate,
+    )
+    .await?;
+
+    Ok(report.issues)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,20 +291,40 @@ pub struct SecurityIssue {
     pub severity: SecuritySeverity,
     pub title: String,
     pub description: String,
+    #[serde(default)]
     pub file: Option<String>,
+    #[serde(default)]
     pub line: Option<u32>,
     pub recommendation: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SecuritySeverity {
+    #[serde(alias = "critical")]
     Critical,
+    #[serde(alias = "high")]
     High,
+    #[serde(alias = "medium")]
     Medium,
+    #[serde(alias = "low")]
     Low,
+    #[serde(alias = "info")]
     Info,
 }
 
+impl std::fmt::Display for SecuritySeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SecuritySeverity::Critical => "Critical",
+            SecuritySeverity::High => "High",
+            SecuritySeverity::Medium => "Medium",
+            SecuritySeverity::Low => "Low",
+            SecuritySeverity::Info => "Info",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 /// Base implementation for common agent functionality
 pub struct BaseAgent {
     pub agent_type: AgentType,
@@ -132,36 +341,39 @@ impl BaseAgent {
         }
     }
 
-    /// Build prompt for task execution
-    pub fn build_task_prompt(&self, task: &Task, repo_path: &str) -> String {
-        let system_prompt = include_str!("../prompts/task_execution_system.txt");
+    /// Render `{name}_system.txt` and `{name}.txt`, the system prompt
+    /// becoming `{{system_prompt}}` of the latter
+    fn build_prompt(&self, name: &str, vars: PromptVars) -> crate::Result<String> {
+        let prompts = prompts::global();
+        let system_prompt = prompts.render(&format!("{}_system.txt", name), &vars)?;
+        prompts.render(&format!("{}.txt", name), &vars.set("system_prompt", system_prompt))
+    }
 
-        format!(
-            "{}\n\n## 작업 정보\n\n작업명: {}\n설명: {}\n저장소 경로: {}\n\n상세 지침:\n{}",
-            system_prompt, task.title, task.description, repo_path, task.prompt
-        )
+    /// Build prompt for task execution
+    pub fn build_task_prompt(&self, task: &Task, repo_path: &str) -> crate::Result<String> {
+        let vars = PromptVars::new().with_task(task).set("repo_context", repo_path);
+        self.build_prompt("task_execution", vars)
     }
 
     /// Build prompt for code review
-    pub fn build_review_prompt(&self, pr_diff: &str, comments: &[String]) -> String {
-        let system_prompt = include_str!("../prompts/code_review_system.txt");
-
-        format!(
-            "{}\n\n## 코드 변경사항\n\n```diff\n{}\n```\n\n## 리뷰 코멘트\n\n{}",
-            system_prompt,
-            pr_diff,
-            comments.join("\n")
-        )
+    pub fn build_review_prompt(&self, pr_diff: &str, comments: &[String]) -> crate::Result<String> {
+        let vars = PromptVars::new().set("diff", pr_diff).set("comments", comments.join("\n"));
+        self.build_prompt("code_review", vars)
     }
 
     /// Build prompt for CI fix
-    pub fn build_ci_fix_prompt(&self, ci_logs: &str) -> String {
-        let system_prompt = include_str!("../prompts/ci_fix_system.txt");
-
-        format!(
-            "{}\n\n## CI 실패 로그\n\n```\n{}\n```",
-            system_prompt,
-            ci_logs
-        )
+    pub fn build_ci_fix_prompt(&self, ci_logs: &str) -> crate::Result<String> {
+        self.build_prompt("ci_fix", PromptVars::new().set("ci_logs", ci_logs))
+    }
+
+    /// Build prompt for a commit message
+    pub fn build_commit_message_prompt(&self, changes: &str) -> crate::Result<String> {
+        self.build_prompt("commit_message", PromptVars::new().set("changes", changes))
+    }
+
+    /// Build prompt for security analysis
+    pub fn build_security_prompt(&self, code: &str, language: &str) -> crate::Result<String> {
+        let vars = PromptVars::new().set("code", code).set("language", language);
+        self.build_prompt("security_analysis", vars)
     }
 }
\ No newline at end of file
@@ -0,0 +1,147 @@
+//! Spending limits on AI calls
+//!
+//! A budget caps the tokens and/or cost of one scope: a task, a composite
+//! task or the current month. Budgets are checked before every API call (see
+//! [`crate::usage::check_budget`]), so the call that crosses a limit still
+//! completes and the next one is refused.
+
+use crate::usage::TokenUsage;
+use crate::{Error, Result};
+
+pub const TASK_BUDGET_USD_ENV: &str = "AUTODEV_BUDGET_TASK_USD";
+pub const TASK_BUDGET_TOKENS_ENV: &str = "AUTODEV_BUDGET_TASK_TOKENS";
+pub const COMPOSITE_BUDGET_USD_ENV: &str = "AUTODEV_BUDGET_COMPOSITE_USD";
+pub const COMPOSITE_BUDGET_TOKENS_ENV: &str = "AUTODEV_BUDGET_COMPOSITE_TOKENS";
+pub const MONTHLY_BUDGET_USD_ENV: &str = "AUTODEV_BUDGET_MONTHLY_USD";
+pub const MONTHLY_BUDGET_TOKENS_ENV: &str = "AUTODEV_BUDGET_MONTHLY_TOKENS";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetScope {
+    Task,
+    CompositeTask,
+    /// Everything spent since the start of the month (UTC)
+    Monthly,
+}
+
+impl std::fmt::Display for BudgetScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BudgetScope::Task => write!(f, "task"),
+            BudgetScope::CompositeTask => write!(f, "composite task"),
+            BudgetScope::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+/// Token and cost caps (None: unlimited)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BudgetLimit {
+    pub max_tokens: Option<u64>,
+    pub max_cost_usd: Option<f64>,
+}
+
+impl BudgetLimit {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_tokens.is_none() && self.max_cost_usd.is_none()
+    }
+
+    /// Why `used` has reached this limit, if it has
+    fn exceeded_by(&self, used: &TokenUsage) -> Option<String> {
+        if let Some(max) = self.max_tokens.filter(|max| used.total_tokens() >= *max) {
+            return Some(format!("{} of {} tokens used", used.total_tokens(), max));
+        }
+        if let Some(max) = self.max_cost_usd.filter(|max| used.cost_usd >= *max) {
+            return Some(format!("${:.2} of ${:.2} spent", used.cost_usd, max));
+        }
+        None
+    }
+
+    fn from_env(usd_env: &str, tokens_env: &str) -> Self {
+        let env = |name: &str| std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        Self {
+            max_tokens: env(tokens_env).and_then(|v| v.parse().ok()),
+            max_cost_usd: env(usd_env).and_then(|v| v.parse().ok()).filter(|usd: &f64| *usd >= 0.0),
+        }
+    }
+}
+
+/// A limit together with what its scope spent before the tracked work started
+#[derive(Debug, Clone, PartialEq)]
+pub struct Budget {
+    pub scope: BudgetScope,
+    pub limit: BudgetLimit,
+    pub spent: TokenUsage,
+}
+
+impl Budget {
+    pub fn new(scope: BudgetScope, limit: BudgetLimit, spent: TokenUsage) -> Self {
+        Self { scope, limit, spent }
+    }
+
+    /// Fails once `spent` plus the tracked `usage` reaches the limit
+    pub fn check(&self, usage: &TokenUsage) -> Result<()> {
+        let mut used = self.spent;
+        used.add(usage);
+
+        match self.limit.exceeded_by(&used) {
+            Some(reason) => Err(Error::BudgetExceeded(format!("{} budget exhausted: {}", self.scope, reason))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Configured limits of every scope
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BudgetConfig {
+    pub task: BudgetLimit,
+    pub composite_task: BudgetLimit,
+    pub monthly: BudgetLimit,
+}
+
+impl BudgetConfig {
+    /// AUTODEV_BUDGET_{TASK,COMPOSITE,MONTHLY}_{USD,TOKENS}, unset means unlimited
+    pub fn from_env() -> Self {
+        Self {
+            task: BudgetLimit::from_env(TASK_BUDGET_USD_ENV, TASK_BUDGET_TOKENS_ENV),
+            composite_task: BudgetLimit::from_env(COMPOSITE_BUDGET_USD_ENV, COMPOSITE_BUDGET_TOKENS_ENV),
+            monthly: BudgetLimit::from_env(MONTHLY_BUDGET_USD_ENV, MONTHLY_BUDGET_TOKENS_ENV),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.task.is_unlimited() && self.composite_task.is_unlimited() && self.monthly.is_unlimited()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(tokens: u64, cost_usd: f64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: tokens,
+            output_tokens: 0,
+            cost_usd,
+        }
+    }
+
+    #[test]
+    fn test_budget_check() {
+        let budget = Budget::new(
+            BudgetScope::Task,
+            BudgetLimit {
+                max_tokens: Some(1000),
+                max_cost_usd: Some(1.0),
+            },
+            usage(400, 0.5),
+        );
+
+        assert!(budget.check(&usage(500, 0.4)).is_ok());
+        assert!(matches!(budget.check(&usage(600, 0.1)), Err(Error::BudgetExceeded(_))));
+        assert!(matches!(budget.check(&usage(10, 0.5)), Err(Error::BudgetExceeded(_))));
+
+        let unlimited = Budget::new(BudgetScope::Monthly, BudgetLimit::default(), usage(1_000_000, 100.0));
+        assert!(unlimited.check(&usage(1_000_000, 100.0)).is_ok());
+    }
+}
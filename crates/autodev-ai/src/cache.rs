@@ -0,0 +1,420 @@
+//! Response cache for structured AI calls
+//!
+//! `chat_json`/`chat_structured` responses (task decomposition, domain detection, example
+//! selection) are cached by a hash of (model, system prompt, user prompt) for
+//! AUTODEV_AI_CACHE_TTL seconds. Entries live in a bounded in-memory LRU and,
+//! with a [`CacheStore`], in the database so other processes reuse them.
+//! Unset or 0 TTL disables the cache.
+
+use async_trait::async_trait;
+use autodev_core::Task;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::agent::{AIAgent, AgentResult, AgentType, Capability, ReviewResult, SecurityIssue};
+use crate::structured::extract_json;
+use crate::Result;
+
+pub const CACHE_TTL_ENV: &str = "AUTODEV_AI_CACHE_TTL";
+pub const CACHE_SIZE_ENV: &str = "AUTODEV_AI_CACHE_SIZE";
+
+const DEFAULT_CAPACITY: usize = 256;
+
+/// Persistent second tier of the cache
+#[async_trait]
+pub trait CacheStore: Send + Sync {
+    /// Unexpired response stored under `key`
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+
+    async fn put(&self, key: &str, model: &str, response: &str, expires_at: DateTime<Utc>) -> Result<()>;
+
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Cache lookups since the process started (all caches)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CacheStats {
+    pub memory_hits: u64,
+    pub store_hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    pub fn hits(&self) -> u64 {
+        self.memory_hits + self.store_hits
+    }
+
+    /// Share of lookups answered from the cache (None before the first lookup)
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.hits() + self.misses;
+        (lookups > 0).then(|| self.hits() as f64 / lookups as f64)
+    }
+}
+
+static MEMORY_HITS: AtomicU64 = AtomicU64::new(0);
+static STORE_HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+pub fn stats() -> CacheStats {
+    CacheStats {
+        memory_hits: MEMORY_HITS.load(Ordering::Relaxed),
+        store_hits: STORE_HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+    }
+}
+
+struct Entry {
+    response: String,
+    expires_at: DateTime<Utc>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, Entry>,
+    clock: u64,
+}
+
+pub struct ResponseCache {
+    ttl: Duration,
+    capacity: usize,
+    memory: Mutex<Lru>,
+    store: Option<Arc<dyn CacheStore>>,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity: capacity.max(1),
+            memory: Mutex::new(Lru::default()),
+            store: None,
+        }
+    }
+
+    /// Cache configured by AUTODEV_AI_CACHE_TTL / AUTODEV_AI_CACHE_SIZE (None: disabled)
+    pub fn from_env() -> Option<Self> {
+        let env = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+
+        let ttl = env(CACHE_TTL_ENV).filter(|ttl| *ttl > 0)?;
+        let capacity = env(CACHE_SIZE_ENV).map_or(DEFAULT_CAPACITY, |size| size as usize);
+        Some(Self::new(Duration::from_secs(ttl), capacity))
+    }
+
+    pub fn with_store(mut self, store: Option<Arc<dyn CacheStore>>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// SHA-256 of the model and both prompts
+    pub fn key(model: &str, system_prompt: &str, user_prompt: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in [model, system_prompt, user_prompt] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    pub async fn get(&self, key: &str) -> Option<String> {
+        if let Some(response) = self.get_memory(key) {
+            MEMORY_HITS.fetch_add(1, Ordering::Relaxed);
+            return Some(response);
+        }
+
+        if let Some(ref store) = self.store {
+            match store.get(key).await {
+                Ok(Some(response)) => {
+                    STORE_HITS.fetch_add(1, Ordering::Relaxed);
+                    self.put_memory(key, &response);
+                    return Some(response);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to read cached AI response: {}", e),
+            }
+        }
+
+        MISSES.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    pub async fn put(&self, key: &str, model: &str, response: &str) {
+        self.put_memory(key, response);
+
+        if let Some(ref store) = self.store {
+            if let Err(e) = store.put(key, model, response, self.expires_at()).await {
+                tracing::warn!("Failed to store AI response: {}", e);
+            }
+        }
+    }
+
+    /// Drop the response stored under `key`
+    pub async fn remove(&self, key: &str) {
+        self.memory.lock().unwrap().entries.remove(key);
+
+        if let Some(ref store) = self.store {
+            if let Err(e) = store.delete(key).await {
+                tracing::warn!("Failed to drop cached AI response: {}", e);
+            }
+        }
+    }
+
+    fn expires_at(&self) -> DateTime<Utc> {
+        chrono::Duration::from_std(self.ttl)
+            .ok()
+            .and_then(|ttl| Utc::now().checked_add_signed(ttl))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    fn get_memory(&self, key: &str) -> Option<String> {
+        let mut lru = self.memory.lock().unwrap();
+        lru.clock += 1;
+        let clock = lru.clock;
+
+        match lru.entries.get_mut(key) {
+            Some(entry) if entry.expires_at > Utc::now() => {
+                entry.last_used = clock;
+                Some(entry.response.clone())
+            }
+            Some(_) => {
+                lru.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn put_memory(&self, key: &str, response: &str) {
+        let expires_at = self.expires_at();
+        let mut lru = self.memory.lock().unwrap();
+        lru.clock += 1;
+        let clock = lru.clock;
+
+        if !lru.entries.contains_key(key) && lru.entries.len() >= self.capacity {
+            let oldest = lru
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                lru.entries.remove(&oldest);
+            }
+        }
+
+        lru.entries.insert(
+            key.to_string(),
+            Entry {
+                response: response.to_string(),
+                expires_at,
+                last_used: clock,
+            },
+        );
+    }
+}
+
+/// Agent whose `chat_json` and `chat_structured` responses are cached; other calls change the
+/// repository and always go to the model
+pub struct CachedAgent {
+    inner: Arc<dyn AIAgent>,
+    cache: ResponseCache,
+}
+
+impl CachedAgent {
+    pub fn new(inner: Arc<dyn AIAgent>, cache: ResponseCache) -> Self {
+        Self { inner, cache }
+    }
+
+    fn structured_key(model: &str, system_prompt: &str, user_prompt: &str, schema: &serde_json::Value) -> String {
+        // 같은 프롬프트라도 스키마가 다르면 다른 응답
+        let system = format!("{}\n{}", system_prompt, schema);
+        ResponseCache::key(model, &system, user_prompt)
+    }
+}
+
+#[async_trait]
+impl AIAgent for CachedAgent {
+    fn agent_type(&self) -> AgentType {
+        self.inner.agent_type()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        self.inner.capabilities()
+    }
+
+    async fn execute_task(&self, task: &Task, repo_path: &str) -> Result<AgentResult> {
+        self.inner.execute_task(task, repo_path).await
+    }
+
+    async fn review_code_changes(&self, pr_diff: &str, review_comments: &[String]) -> Result<ReviewResult> {
+        self.inner.review_code_changes(pr_diff, review_comments).await
+    }
+
+    async fn fix_ci_failures(&self, ci_logs: &str) -> Result<ReviewResult> {
+        self.inner.fix_ci_failures(ci_logs).await
+    }
+
+    async fn generate_commit_message(&self, changes: &str) -> Result<String> {
+        self.inner.generate_commit_message(changes).await
+    }
+
+    async fn analyze_security(&self, code: &str, language: &str) -> Result<Vec<SecurityIssue>> {
+        self.inner.analyze_security(code, language).await
+    }
+
+    async fn chat_json(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        let key = ResponseCache::key(self.inner.model(), system_prompt, user_prompt);
+
+        if let Some(response) = self.cache.get(&key).await {
+            tracing::debug!("AI response cache hit ({})", &key[..12]);
+            return Ok(response);
+        }
+
+        let response = self.inner.chat_json(system_prompt, user_prompt).await?;
+        // 파싱할 수 없는 응답은 캐시하지 않음
+        if extract_json(&response).is_some() {
+            self.cache.put(&key, self.inner.model(), &response).await;
+        }
+        Ok(response)
+    }
+
+    async fn chat_structured(&self, system_prompt: &str, user_prompt: &str, schema: &serde_json::Value) -> Result<String> {
+        let key = Self::structured_key(self.inner.model(), system_prompt, user_prompt, schema);
+
+        if let Some(response) = self.cache.get(&key).await {
+            tracing::debug!("AI response cache hit ({})", &key[..12]);
+            return Ok(response);
+        }
+
+        let response = self.inner.chat_structured(system_prompt, user_prompt, schema).await?;
+        if extract_json(&response).is_some() {
+            self.cache.put(&key, self.inner.model(), &response).await;
+        }
+        Ok(response)
+    }
+
+    async fn reject_response(&self, system_prompt: &str, user_prompt: &str, schema: &serde_json::Value) {
+        let key = Self::structured_key(self.inner.model(), system_prompt, user_prompt, schema);
+        self.cache.remove(&key).await;
+        self.inner.reject_response(system_prompt, user_prompt, schema).await;
+    }
+
+    async fn check_credentials(&self) -> Result<bool> {
+        self.inner.check_credentials().await
+    }
+}
+
+/// `agent` behind the response cache configured in the environment
+/// (unchanged when the cache is disabled)
+pub fn with_response_cache(agent: Arc<dyn AIAgent>, store: Option<Arc<dyn CacheStore>>) -> Arc<dyn AIAgent> {
+    match ResponseCache::from_env() {
+        Some(cache) => {
+            tracing::info!("Caching AI responses for {}s", cache.ttl.as_secs());
+            Arc::new(CachedAgent::new(agent, cache.with_store(store)))
+        }
+        None => agent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OllamaAgent;
+
+    #[test]
+    fn test_key() {
+        let key = ResponseCache::key("gpt-4o", "system", "user");
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, ResponseCache::key("gpt-4o", "system", "user"));
+        assert_ne!(key, ResponseCache::key("gpt-4o-mini", "system", "user"));
+        // Moving text between the prompts changes the key
+        assert_ne!(key, ResponseCache::key("gpt-4o", "systemuser", ""));
+    }
+
+    #[tokio::test]
+    async fn test_lru_eviction_and_expiry() {
+        let cache = ResponseCache::new(Duration::from_secs(60), 2);
+        cache.put("a", "m", "1").await;
+        cache.put("b", "m", "2").await;
+        assert_eq!(cache.get("a").await.as_deref(), Some("1"));
+
+        // "b" is the least recently used
+        cache.put("c", "m", "3").await;
+        assert!(cache.get("b").await.is_none());
+        assert_eq!(cache.get("a").await.as_deref(), Some("1"));
+        assert_eq!(cache.get("c").await.as_deref(), Some("3"));
+
+        let expired = ResponseCache::new(Duration::ZERO, 2);
+        expired.put("a", "m", "1").await;
+        assert!(expired.get("a").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cached_agent() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/chat")
+            .with_status(200)
+            .with_body(serde_json::json!({ "message": { "content": "{\"domain\": \"Feature\"}" } }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let inner = Arc::new(OllamaAgent::new(server.url(), "llama3.1"));
+        let agent = CachedAgent::new(inner, ResponseCache::new(Duration::from_secs(60), 8));
+
+        let first = agent.chat_json("Detect the domain", "Add login").await.unwrap();
+        let second = agent.chat_json("Detect the domain", "Add login").await.unwrap();
+
+        assert_eq!(first, second);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_ttl_beyond_the_calendar() {
+        let cache = ResponseCache::new(Duration::from_secs(u64::MAX), 2);
+        assert_eq!(cache.expires_at(), DateTime::<Utc>::MAX_UTC);
+
+        cache.put("a", "m", "1").await;
+        assert_eq!(cache.get("a").await.as_deref(), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_responses_are_not_reused() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Answer {
+            value: i32,
+        }
+
+        let reply = |content: &str| serde_json::json!({ "message": { "content": content } }).to_string();
+
+        let mut server = mockito::Server::new_async().await;
+        let rejected = server
+            .mock("POST", "/api/chat")
+            .with_status(200)
+            .with_body(reply(r#"{"value": -1}"#))
+            .expect(2)
+            .create_async()
+            .await;
+
+        let inner = Arc::new(OllamaAgent::new(server.url(), "llama3.1"));
+        let agent = CachedAgent::new(inner, ResponseCache::new(Duration::from_secs(60), 8));
+        let validate = |answer: &Answer| (answer.value > 0).then_some(()).ok_or_else(|| "value must be positive".to_string());
+        let schema = serde_json::json!({});
+
+        // Each call asks the model again instead of replaying the rejected response
+        for _ in 0..2 {
+            let result: Result<Answer> =
+                crate::structured::chat_structured(&agent, "Answer", "Pick a number", &schema, 1, validate).await;
+            assert!(result.is_err());
+        }
+        rejected.assert_async().await;
+    }
+}
@@ -6,7 +6,10 @@ use async_trait::async_trait;
 use autodev_core::Task;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
+
+/// 구조화된 응답을 받기 위해 강제로 호출시키는 도구 이름
+const STRUCTURED_TOOL: &str = "respond";
 
 pub struct ClaudeAgent {
     base: BaseAgent,
@@ -53,32 +56,84 @@ impl ClaudeAgent {
         }
     }
 
+    /// Messages API 호환 엔드포인트 사용 (프록시, 테스트 서버 등)
+    pub fn with_base_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     async fn call_api(&self, messages: Vec<Message>) -> Result<String> {
+        self.send(json!({
+            "model": &self.base.model,
+            "messages": messages,
+            "max_tokens": 4096,
+            "temperature": 0.7,
+        }))
+        .await
+    }
+
+    /// Messages API 요청 (스트림 수신 중이면 SSE로 받아 텍스트를 바로 전달)
+    ///
+    /// 도구 호출로 끝난 응답은 도구 입력 JSON을 텍스트로 반환합니다.
+    async fn send(&self, mut body: Value) -> Result<String> {
+        crate::usage::check_budget()?;
+
+        let streaming = crate::stream::is_streaming();
+        if streaming {
+            body["stream"] = json!(true);
+        }
+
         let response = self
             .client
             .post(format!("{}/messages", self.api_url))
             .header("x-api-key", &self.base.api_key)
             .header("anthropic-version", "2023-06-01")
             .header("content-type", "application/json")
-            .json(&json!({
-                "model": &self.base.model,
-                "messages": messages,
-                "max_tokens": 4096,
-                "temperature": 0.7,
-            }))
+            .json(&body)
             .send()
             .await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            return Err(crate::Error::ApiError(format!(
-                "Claude API error: {}",
-                error_text
-            )));
+        let mut response = crate::retry::check_response("Claude", response).await?;
+
+        if !streaming {
+            let result: ClaudeResponse = response.json().await?;
+            result.record_usage(&self.base.model);
+            return Ok(result.content.into_iter().find_map(Content::into_output).unwrap_or_default());
         }
 
-        let result: ClaudeResponse = response.json().await?;
-        Ok(result.content.first().map(|c| c.text.clone()).unwrap_or_default())
+        let mut parser = crate::stream::SseParser::new();
+        let mut text = String::new();
+        let mut usage = ClaudeUsage::default();
+
+        while let Some(chunk) = response.chunk().await? {
+            for data in parser.push(&chunk) {
+                let Ok(event) = serde_json::from_str::<StreamEvent>(&data) else {
+                    continue;
+                };
+
+                match event {
+                    StreamEvent::MessageStart { message } => usage.input_tokens = message.usage.input_tokens,
+                    StreamEvent::ContentBlockDelta { delta } => {
+                        if let Some(delta) = delta.text.or(delta.partial_json) {
+                            crate::stream::emit(&delta);
+                            text.push_str(&delta);
+                        }
+                    }
+                    StreamEvent::MessageDelta { usage: delta } => usage.output_tokens = delta.output_tokens,
+                    StreamEvent::Error { error } => {
+                        return Err(crate::Error::Unavailable {
+                            status: 529,
+                            message: format!("Claude stream error ({}): {}", error.kind, error.message),
+                            retry_after: None,
+                        })
+                    }
+                    StreamEvent::Other => {}
+                }
+            }
+        }
+
+        crate::usage::record(&self.base.model, usage.input_tokens, usage.output_tokens);
+        Ok(text)
     }
 
     /// JSON 추출 헬퍼 (마크다운 코드 블록 제거)
@@ -109,10 +164,14 @@ impl AIAgent for ClaudeAgent {
         self.base.agent_type.clone()
     }
 
+    fn model(&self) -> &str {
+        &self.base.model
+    }
+
     async fn execute_task(&self, task: &Task, repo_path: &str) -> Result<AgentResult> {
         tracing::info!("Claude executing task: {}", task.title);
 
-        let prompt = self.base.build_task_prompt(task, repo_path);
+        let prompt = self.base.build_task_prompt(task, repo_path)?;
 
         let messages = vec![Message {
             role: "user".to_string(),
@@ -141,7 +200,7 @@ impl AIAgent for ClaudeAgent {
     ) -> Result<ReviewResult> {
         tracing::info!("Claude reviewing code changes");
 
-        let prompt = self.base.build_review_prompt(pr_diff, review_comments);
+        let prompt = self.base.build_review_prompt(pr_diff, review_comments)?;
 
         let messages = vec![Message {
             role: "user".to_string(),
@@ -160,7 +219,7 @@ impl AIAgent for ClaudeAgent {
     async fn fix_ci_failures(&self, ci_logs: &str) -> Result<ReviewResult> {
         tracing::info!("Claude fixing CI failures");
 
-        let prompt = self.base.build_ci_fix_prompt(ci_logs);
+        let prompt = self.base.build_ci_fix_prompt(ci_logs)?;
 
         let messages = vec![Message {
             role: "user".to_string(),
@@ -177,11 +236,7 @@ impl AIAgent for ClaudeAgent {
     }
 
     async fn generate_commit_message(&self, changes: &str) -> Result<String> {
-        let system_prompt = include_str!("../prompts/commit_message_system.txt");
-        let prompt = format!(
-            "{}\n\n## 코드 변경사항\n\n{}",
-            system_prompt, changes
-        );
+        let prompt = self.base.build_commit_message_prompt(changes)?;
 
         let messages = vec![Message {
             role: "user".to_string(),
@@ -192,35 +247,15 @@ impl AIAgent for ClaudeAgent {
     }
 
     async fn analyze_security(&self, code: &str, language: &str) -> Result<Vec<SecurityIssue>> {
-        let system_prompt = include_str!("../prompts/security_analysis_system.txt");
-        let prompt = format!(
-            "{}\n\n## 분석 대상 코드\n\n언어: {}\n\n```{}\n{}\n```",
-            system_prompt, language, language, code
-        );
-
-        let messages = vec![Message {
-            role: "user".to_string(),
-            content: prompt,
-        }];
-
-        let _response = self.call_api(messages).await?;
-
-        // Parse response into security issues
-        // This is a simplified version
-        Ok(vec![])
+        crate::agent::analyze_security_structured(self, code, language).await
     }
 
     async fn chat_json(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
         tracing::info!("Claude chat with JSON mode");
 
         // Claude API는 system 메시지를 별도로 지원
-        let response = self
-            .client
-            .post(format!("{}/messages", self.api_url))
-            .header("x-api-key", &self.base.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&json!({
+        let json_text = self
+            .send(json!({
                 "model": &self.base.model,
                 "system": system_prompt,
                 "messages": [
@@ -232,25 +267,52 @@ impl AIAgent for ClaudeAgent {
                 "max_tokens": 8192,
                 "temperature": 0.3, // 낮은 temperature로 더 일관된 JSON 출력
             }))
-            .send()
             .await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            return Err(crate::Error::ApiError(format!(
-                "Claude API error: {}",
-                error_text
-            )));
-        }
-
-        let result: ClaudeResponse = response.json().await?;
-        let json_text = result.content.first().map(|c| c.text.clone()).unwrap_or_default();
-
         // JSON 추출 (마크다운 코드 블록 제거)
         let cleaned = self.extract_json(&json_text);
 
         Ok(cleaned)
     }
+
+    async fn chat_structured(&self, system_prompt: &str, user_prompt: &str, schema: &Value) -> Result<String> {
+        tracing::info!("Claude chat with structured output");
+
+        // 스키마를 입력으로 갖는 도구를 반드시 호출하게 해 응답 형식을 강제
+        self.send(json!({
+            "model": &self.base.model,
+            "system": system_prompt,
+            "messages": [
+                {
+                    "role": "user",
+                    "content": user_prompt
+                }
+            ],
+            "tools": [
+                {
+                    "name": STRUCTURED_TOOL,
+                    "description": "Return the response in the required format",
+                    "input_schema": schema,
+                }
+            ],
+            "tool_choice": { "type": "tool", "name": STRUCTURED_TOOL },
+            "max_tokens": 8192,
+            "temperature": 0.3,
+        }))
+        .await
+    }
+
+    async fn check_credentials(&self) -> Result<bool> {
+        let response = self
+            .client
+            .get(format!("{}/models", self.api_url))
+            .header("x-api-key", &self.base.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .send()
+            .await?;
+        crate::retry::check_response("Claude", response).await?;
+        Ok(true)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -262,9 +324,135 @@ struct Message {
 #[derive(Debug, Deserialize)]
 struct ClaudeResponse {
     content: Vec<Content>,
+    #[serde(default)]
+    usage: Option<ClaudeUsage>,
+}
+
+impl ClaudeResponse {
+    fn record_usage(&self, model: &str) {
+        if let Some(ref usage) = self.usage {
+            crate::usage::record(model, usage.input_tokens, usage.output_tokens);
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ClaudeUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
 }
 
+/// Messages API 스트림 이벤트 (필요한 것만)
 #[derive(Debug, Deserialize)]
-struct Content {
-    text: String,
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    MessageStart { message: StreamMessage },
+    ContentBlockDelta { delta: StreamDelta },
+    MessageDelta { usage: ClaudeUsage },
+    Error { error: StreamError },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMessage {
+    #[serde(default)]
+    usage: ClaudeUsage,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamDelta {
+    /// text_delta의 텍스트
+    #[serde(default)]
+    text: Option<String>,
+    /// input_json_delta의 도구 입력 조각
+    #[serde(default)]
+    partial_json: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamError {
+    #[serde(rename = "type")]
+    kind: String,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Content {
+    Text { text: String },
+    ToolUse { input: Value },
+    #[serde(other)]
+    Other,
+}
+
+impl Content {
+    fn into_output(self) -> Option<String> {
+        match self {
+            Content::Text { text } => Some(text),
+            Content::ToolUse { input } => Some(input.to_string()),
+            Content::Other => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_chat_structured_uses_tool() {
+        let schema = json!({ "type": "object", "properties": { "value": { "type": "integer" } } });
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/messages")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "tools": [{ "name": STRUCTURED_TOOL, "input_schema": schema }],
+                "tool_choice": { "type": "tool", "name": STRUCTURED_TOOL },
+            })))
+            .with_status(200)
+            .with_body(
+                json!({
+                    "content": [{ "type": "tool_use", "id": "toolu_1", "name": STRUCTURED_TOOL, "input": { "value": 7 } }],
+                    "usage": { "input_tokens": 10, "output_tokens": 5 }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let agent = ClaudeAgent::new("test-key".to_string()).with_base_url(server.url());
+        let response = agent.chat_structured("Answer", "Pick a number", &schema).await.unwrap();
+
+        assert_eq!(response, r#"{"value":7}"#);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_check_credentials() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/models")
+            .match_header("x-api-key", "valid-key")
+            .with_status(200)
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/models")
+            .match_header("x-api-key", "revoked-key")
+            .with_status(401)
+            .with_body(r#"{"error": {"type": "authentication_error"}}"#)
+            .create_async()
+            .await;
+
+        let valid = ClaudeAgent::new("valid-key".to_string()).with_base_url(server.url());
+        assert!(valid.check_credentials().await.unwrap());
+
+        let revoked = ClaudeAgent::new("revoked-key".to_string()).with_base_url(server.url());
+        assert!(revoked.check_credentials().await.is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,345 @@
+//! AI 실행 컨테이너 풀 (warm start)
+//!
+//! 프롬프트마다 컨테이너를 만들고 지우는 대신 `sleep`으로 대기하는 컨테이너를
+//! 미리 띄워 두고 `docker exec`로 Claude CLI를 실행한다. 쉬는 컨테이너는
+//! idle timeout이 지나면 정리되고, 재사용 전에 실행 중인지 확인한다.
+
+use bollard::container::{Config, CreateContainerOptions, RemoveContainerOptions};
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use bollard::Docker;
+use futures_util::StreamExt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use crate::Result;
+
+/// 대기시켜 둘 컨테이너 수, 0이면 풀을 쓰지 않고 호출마다 컨테이너 생성
+pub const AI_POOL_SIZE_ENV: &str = "AUTODEV_AI_POOL_SIZE";
+/// 쉬는 컨테이너를 정리하기까지의 시간(초)
+pub const AI_POOL_IDLE_TIMEOUT_ENV: &str = "AUTODEV_AI_POOL_IDLE_TIMEOUT";
+
+pub const DEFAULT_POOL_SIZE: usize = 2;
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 컨테이너 수명 상한: 프로세스가 비정상 종료해도 이 시간 뒤 스스로 종료·삭제됨
+const CONTAINER_LIFETIME: Duration = Duration::from_secs(3600);
+/// 수명이 이만큼 남지 않은 컨테이너는 재사용하지 않음 (긴 프롬프트 도중 종료 방지)
+const LIFETIME_MARGIN: Duration = Duration::from_secs(900);
+/// 쉬는 컨테이너 정리 주기
+const REAP_INTERVAL: Duration = Duration::from_secs(30);
+
+const POOL_LABEL: &str = "autodev.role";
+const POOL_LABEL_VALUE: &str = "ai-pool";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolConfig {
+    /// 대기시켜 둘 최대 컨테이너 수
+    pub size: usize,
+    pub idle_timeout: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            size: DEFAULT_POOL_SIZE,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+        }
+    }
+}
+
+impl PoolConfig {
+    /// AUTODEV_AI_POOL_* 설정, 크기가 0이면 None
+    pub fn from_env() -> Option<Self> {
+        let defaults = Self::default();
+        let size = std::env::var(AI_POOL_SIZE_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(defaults.size);
+        let idle_timeout = std::env::var(AI_POOL_IDLE_TIMEOUT_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map_or(defaults.idle_timeout, Duration::from_secs);
+
+        (size > 0).then_some(Self { size, idle_timeout })
+    }
+}
+
+struct WarmContainer {
+    id: String,
+    created: Instant,
+    last_used: Instant,
+}
+
+impl WarmContainer {
+    /// idle timeout과 수명 기준으로 다시 쓸 수 있는지
+    fn reusable(&self, idle_timeout: Duration, now: Instant) -> bool {
+        now.duration_since(self.last_used) < idle_timeout
+            && now.duration_since(self.created) + LIFETIME_MARGIN < CONTAINER_LIFETIME
+    }
+}
+
+pub(crate) struct ContainerPool {
+    docker: Docker,
+    config: PoolConfig,
+    /// 새 컨테이너 설정 (이미지, 환경 변수, 샌드박스)
+    template: Config<String>,
+    idle: Mutex<Vec<WarmContainer>>,
+    reaper_started: AtomicBool,
+}
+
+impl ContainerPool {
+    pub(crate) fn new(docker: Docker, config: PoolConfig, template: Config<String>) -> Self {
+        let mut template = template;
+        template.entrypoint = Some(vec!["sleep".to_string()]);
+        template.cmd = Some(vec![CONTAINER_LIFETIME.as_secs().to_string()]);
+        template
+            .labels
+            .get_or_insert_with(Default::default)
+            .insert(POOL_LABEL.to_string(), POOL_LABEL_VALUE.to_string());
+        template.host_config.get_or_insert_with(Default::default).auto_remove = Some(true);
+
+        Self {
+            docker,
+            config,
+            template,
+            idle: Mutex::new(Vec::new()),
+            reaper_started: AtomicBool::new(false),
+        }
+    }
+
+    /// 풀의 컨테이너에서 `cmd` 실행, 출력(stdout + stderr) 반환
+    pub(crate) async fn exec(self: &Arc<Self>, cmd: Vec<String>) -> Result<String> {
+        let container = self.checkout().await?;
+
+        match self.run(&container.id, cmd).await {
+            Ok(output) => {
+                self.checkin(container);
+                Ok(output)
+            }
+            Err(e) => {
+                self.discard(&container.id).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// 쓸 수 있는 컨테이너를 꺼내거나 새로 생성
+    async fn checkout(&self) -> Result<WarmContainer> {
+        loop {
+            let candidate = self.idle.lock().unwrap().pop();
+            let Some(container) = candidate else {
+                break;
+            };
+
+            if !container.reusable(self.config.idle_timeout, Instant::now()) {
+                self.discard(&container.id).await;
+                continue;
+            }
+
+            // health check: 컨테이너가 아직 실행 중인지 확인
+            let running = self
+                .docker
+                .inspect_container(&container.id, None)
+                .await
+                .ok()
+                .and_then(|info| info.state)
+                .and_then(|state| state.running)
+                .unwrap_or(false);
+            if running {
+                tracing::debug!("Reusing warm AI container {}", container.id);
+                return Ok(container);
+            }
+
+            tracing::debug!("Warm AI container {} is not running, discarding", container.id);
+            self.discard(&container.id).await;
+        }
+
+        self.create().await
+    }
+
+    async fn create(&self) -> Result<WarmContainer> {
+        let name = format!("autodev-ai-{}", uuid::Uuid::new_v4());
+
+        let container = self
+            .docker
+            .create_container(
+                Some(CreateContainerOptions {
+                    name: name.clone(),
+                    ..Default::default()
+                }),
+                self.template.clone(),
+            )
+            .await
+            .map_err(|e| crate::Error::ApiError(format!("Failed to create container: {}", e)))?;
+
+        self.docker
+            .start_container::<String>(&container.id, None)
+            .await
+            .map_err(|e| crate::Error::ApiError(format!("Failed to start container: {}", e)))?;
+
+        tracing::debug!("Started warm AI container {} ({})", name, container.id);
+
+        let now = Instant::now();
+        Ok(WarmContainer {
+            id: container.id,
+            created: now,
+            last_used: now,
+        })
+    }
+
+    async fn run(&self, container_id: &str, cmd: Vec<String>) -> Result<String> {
+        let exec = self
+            .docker
+            .create_exec(
+                container_id,
+                CreateExecOptions {
+                    cmd: Some(cmd),
+                    attach_stdout: Some(true),
+                    attach_stderr: Some(true),
+                    tty: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| crate::Error::ApiError(format!("Failed to create exec: {}", e)))?;
+
+        let mut output = String::new();
+        match self
+            .docker
+            .start_exec(&exec.id, None)
+            .await
+            .map_err(|e| crate::Error::ApiError(format!("Failed to start exec: {}", e)))?
+        {
+            StartExecResults::Attached { output: mut stream, .. } => {
+                while let Some(log) = stream.next().await {
+                    let log = log.map_err(|e| crate::Error::ApiError(format!("Failed to read exec output: {}", e)))?;
+                    output.push_str(&log.to_string());
+                }
+            }
+            StartExecResults::Detached => {}
+        }
+
+        if let Ok(inspect) = self.docker.inspect_exec(&exec.id).await {
+            tracing::debug!("Exec exit code in {}: {:?}", container_id, inspect.exit_code);
+        }
+
+        Ok(output)
+    }
+
+    /// 실행이 끝난 컨테이너를 풀에 반환 (풀이 가득 차면 삭제)
+    fn checkin(self: &Arc<Self>, mut container: WarmContainer) {
+        container.last_used = Instant::now();
+
+        let overflow = {
+            let mut idle = self.idle.lock().unwrap();
+            if idle.len() < self.config.size {
+                idle.push(container);
+                None
+            } else {
+                Some(container)
+            }
+        };
+
+        if let Some(container) = overflow {
+            let pool = self.clone();
+            tokio::spawn(async move { pool.discard(&container.id).await });
+        }
+
+        self.start_reaper();
+    }
+
+    /// idle timeout이 지난 컨테이너를 주기적으로 정리 (풀이 drop되면 종료)
+    fn start_reaper(self: &Arc<Self>) {
+        if self.reaper_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let pool: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REAP_INTERVAL);
+            loop {
+                interval.tick().await;
+                let Some(pool) = pool.upgrade() else {
+                    break;
+                };
+
+                let now = Instant::now();
+                let expired: Vec<WarmContainer> = {
+                    let mut idle = pool.idle.lock().unwrap();
+                    let (keep, expired) = std::mem::take(&mut *idle)
+                        .into_iter()
+                        .partition(|c| c.reusable(pool.config.idle_timeout, now));
+                    *idle = keep;
+                    expired
+                };
+
+                for container in expired {
+                    tracing::debug!("Removing idle AI container {}", container.id);
+                    pool.discard(&container.id).await;
+                }
+            }
+        });
+    }
+
+    async fn discard(&self, container_id: &str) {
+        if let Err(e) = self
+            .docker
+            .remove_container(
+                container_id,
+                Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                }),
+            )
+            .await
+        {
+            tracing::debug!("Failed to remove AI container {}: {}", container_id, e);
+        }
+    }
+}
+
+impl Drop for ContainerPool {
+    fn drop(&mut self) {
+        let ids: Vec<String> = self.idle.get_mut().unwrap().drain(..).map(|c| c.id).collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        // 런타임이 살아 있으면 정리, 아니면 컨테이너 수명이 다할 때 스스로 삭제됨
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let docker = self.docker.clone();
+            handle.spawn(async move {
+                for id in ids {
+                    let options = RemoveContainerOptions {
+                        force: true,
+                        ..Default::default()
+                    };
+                    docker.remove_container(&id, Some(options)).await.ok();
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reusable() {
+        // Instant는 음수가 될 수 없으므로 기준 시각을 미래로 잡음
+        let now = Instant::now() + CONTAINER_LIFETIME;
+        let idle_timeout = Duration::from_secs(60);
+        let container = |age: u64, idle: u64| WarmContainer {
+            id: "c".to_string(),
+            created: now - Duration::from_secs(age),
+            last_used: now - Duration::from_secs(idle),
+        };
+
+        assert!(container(120, 10).reusable(idle_timeout, now));
+        assert!(!container(120, 61).reusable(idle_timeout, now));
+        // 수명이 얼마 남지 않은 컨테이너는 재사용하지 않음
+        assert!(!container(CONTAINER_LIFETIME.as_secs() - 60, 0).reusable(idle_timeout, now));
+    }
+}
@@ -1,27 +1,80 @@
-use crate::{agent::AIAgent, examples::ExampleDatabase, schema::TaskDecompositionResponse, Result};
+use crate::{
+    agent::AIAgent,
+    embeddings::VectorStore,
+    examples::{ExampleDatabase, FewShotExample},
+    prompts::{self, PromptRegistry, PromptVars},
+    structured,
+    schema::{ComplexityEstimate, TaskDecompositionResponse, TaskDomain, TaskSchema},
+    Result,
+};
 use autodev_core::Task;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct TaskDecomposer {
     agent: Arc<dyn AIAgent>,
     example_db: ExampleDatabase,
-    system_prompt: String,
+    prompts: PromptRegistry,
+    /// 수정하면 안 되는 경로 (저장소의 .autodev.yml)
+    protected_paths: Vec<String>,
+    /// 응답이 스키마/검증을 통과하지 못할 때 재요청을 포함한 최대 시도 횟수
+    max_attempts: u32,
 }
 
 impl TaskDecomposer {
     pub fn new(agent: Arc<dyn AIAgent>) -> Self {
-        let system_prompt = include_str!("../prompts/task_decomposition_system.txt").to_string();
         let example_db = ExampleDatabase::with_agent(agent.clone());
 
         Self {
             agent,
             example_db,
-            system_prompt,
+            prompts: prompts::global().clone(),
+            protected_paths: Vec::new(),
+            max_attempts: structured::DEFAULT_ATTEMPTS,
         }
     }
 
+    /// 프롬프트 템플릿 지정 (기본: AUTODEV_PROMPTS_DIR)
+    pub fn with_prompts(mut self, prompts: PromptRegistry) -> Self {
+        self.example_db = self.example_db.with_prompts(prompts.clone());
+        self.prompts = prompts;
+        self
+    }
+
+    /// 사용자가 등록한 few-shot 예제를 내장 예제와 함께 사용
+    pub fn with_custom_examples(mut self, examples: Vec<FewShotExample>) -> Self {
+        self.example_db = self.example_db.with_custom_examples(examples);
+        self
+    }
+
+    /// 예제 임베딩을 저장하고 검색할 벡터 저장소 지정
+    pub fn with_vector_store(mut self, store: Option<Arc<dyn VectorStore>>) -> Self {
+        self.example_db = self.example_db.with_vector_store(store);
+        self
+    }
+
+    /// 분해된 작업이 건드리면 안 되는 경로 지정
+    pub fn with_protected_paths(mut self, protected_paths: Vec<String>) -> Self {
+        self.protected_paths = protected_paths;
+        self
+    }
+
+    /// 분해 응답의 최대 시도 횟수 지정 (기본 3회)
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
     /// AI 기반 작업 분해 (완전히 새로운 구현)
     pub async fn decompose(&self, composite_prompt: &str) -> Result<Vec<Task>> {
+        let decomposition = self.plan(composite_prompt).await?;
+
+        // TaskSchema → Task 변환
+        Ok(self.convert_to_tasks(decomposition.tasks))
+    }
+
+    /// 검증된 분해 계획만 생성 (Task 변환 전에 계획을 검토/수정할 때 사용)
+    pub async fn plan(&self, composite_prompt: &str) -> Result<TaskDecompositionResponse> {
         tracing::info!("AI-based task decomposition started");
 
         // 1. AI 기반 도메인 감지 (한글/영어 모두 지원, 의미론적 분류)
@@ -35,26 +88,42 @@ impl TaskDecomposer {
         let few_shot_prompt = self.build_few_shot_prompt(&relevant_examples);
 
         // 4. 최종 사용자 프롬프트 구성
-        let full_user_prompt = format!(
-            "{}\n\n---\n\n사용자 요청:\n{}",
-            few_shot_prompt, composite_prompt
-        );
-
-        // 5. AI 호출 (JSON mode)
-        let json_response = self
-            .agent
-            .chat_json(&self.system_prompt, &full_user_prompt)
-            .await?;
-
-        tracing::debug!("AI JSON response: {}", json_response);
-
-        // 6. JSON 파싱
-        let decomposition: TaskDecompositionResponse = serde_json::from_str(&json_response)
-            .map_err(|e| {
-                crate::Error::ParseError(format!("Failed to parse AI response: {}. Response: {}", e, json_response))
-            })?;
-
-        // 7. 검증
+        let constraints = if self.protected_paths.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "\n\n제약 조건: 다음 경로의 파일은 생성/수정/삭제하는 작업을 만들지 마세요: {}",
+                self.protected_paths.join(", ")
+            )
+        };
+        let vars = PromptVars::new()
+            .set("examples", few_shot_prompt)
+            .set("request", composite_prompt)
+            .set("constraints", constraints);
+        let system_prompt = self.prompts.render("task_decomposition_system.txt", &vars)?;
+        let full_user_prompt = self.prompts.render("task_decomposition.txt", &vars)?;
+
+        // 5. AI 호출 (JSON Schema 강제, 파싱/검증에 실패하면 오류를 알려주고 재요청)
+        //    AI에 접근할 수 없으면 키워드 템플릿으로 대체
+        let decomposition = match structured::chat_structured(
+            self.agent.as_ref(),
+            &system_prompt,
+            &full_user_prompt,
+            &TaskDecompositionResponse::json_schema(),
+            self.max_attempts,
+            TaskDecompositionResponse::validate,
+        )
+        .await
+        {
+            Ok(decomposition) => decomposition,
+            Err(e @ crate::Error::ValidationError(_)) => return Err(e),
+            Err(e) => {
+                tracing::warn!("AI decomposition unavailable, using keyword templates: {}", e);
+                self.template_decomposition(composite_prompt)
+            }
+        };
+
+        // 6. 검증 (키워드 템플릿 분해 포함)
         decomposition.validate().map_err(|e| {
             crate::Error::ValidationError(format!("Task decomposition validation failed: {}", e))
         })?;
@@ -65,10 +134,7 @@ impl TaskDecomposer {
             decomposition.parallel_batches.len()
         );
 
-        // 8. TaskSchema → Task 변환
-        let tasks = self.convert_to_tasks(decomposition.tasks);
-
-        Ok(tasks)
+        Ok(decomposition)
     }
 
     /// Few-shot 프롬프트 구성
@@ -98,27 +164,116 @@ impl TaskDecomposer {
     }
 
     /// TaskSchema를 Task로 변환
-    fn convert_to_tasks(&self, schemas: Vec<crate::schema::TaskSchema>) -> Vec<Task> {
-        schemas
-            .into_iter()
+    ///
+    /// AI가 만든 ID("task_1" 등)는 composite 간에 겹치므로 새 UUID를 발급하고
+    /// 의존성도 새 ID로 다시 연결한다.
+    pub fn convert_to_tasks(&self, schemas: Vec<TaskSchema>) -> Vec<Task> {
+        let tasks: Vec<Task> = schemas
+            .iter()
             .map(|schema| {
                 let mut task = Task::new(
-                    schema.title,
+                    schema.title.clone(),
                     schema.description.clone(),
-                    schema.description,
-                );
-
-                // ID 덮어쓰기 (AI가 생성한 ID 사용)
-                task.id = schema.id;
+                    schema.description.clone(),
+                )
+                .with_tags(&schema.tags);
+                task.estimated_minutes = Some(schema.estimated_duration_minutes).filter(|m| *m > 0);
+                task
+            })
+            .collect();
 
-                // 의존성 설정
-                task.dependencies = schema.dependencies;
+        let id_map: HashMap<&str, String> = schemas
+            .iter()
+            .zip(&tasks)
+            .map(|(schema, task)| (schema.id.as_str(), task.id.clone()))
+            .collect();
 
+        tasks
+            .into_iter()
+            .zip(&schemas)
+            .map(|(mut task, schema)| {
+                // 의존성 설정 (validate()에서 존재 여부를 이미 검증함)
+                task.dependencies = schema
+                    .dependencies
+                    .iter()
+                    .filter_map(|dep| id_map.get(dep.as_str()).cloned())
+                    .collect();
                 task
             })
             .collect()
     }
 
+    /// 키워드 템플릿 기반 분해 (오프라인 fallback)
+    ///
+    /// AI 호출이 불가능할 때만 사용한다. 도메인별 고정 단계에 사용자 요청을 채워 넣는다.
+    pub fn template_decomposition(&self, composite_prompt: &str) -> TaskDecompositionResponse {
+        let domain = self.example_db.detect_domain_fallback(composite_prompt);
+
+        // (id, title, dependencies, minutes)
+        let steps: &[(&str, &str, &[&str], u32)] = match domain {
+            TaskDomain::Translation => &[
+                ("extract", "Extract translatable content", &[], 20),
+                ("translate", "Translate content", &["extract"], 60),
+                ("review", "Review translations and wire up locales", &["translate"], 30),
+            ],
+            TaskDomain::Security => &[
+                ("audit", "Audit code for vulnerabilities", &[], 45),
+                ("fix", "Fix identified vulnerabilities", &["audit"], 60),
+                ("tests", "Add security regression tests", &["fix"], 30),
+            ],
+            TaskDomain::Refactoring => &[
+                ("analyze", "Analyze code to refactor", &[], 30),
+                ("refactor", "Refactor code", &["analyze"], 60),
+                ("tests", "Update tests for refactored code", &["refactor"], 30),
+            ],
+            TaskDomain::Testing => &[
+                ("gaps", "Identify untested code paths", &[], 20),
+                ("tests", "Write missing tests", &["gaps"], 60),
+                ("ci", "Run tests in CI and fix failures", &["tests"], 20),
+            ],
+            TaskDomain::Documentation => &[
+                ("outline", "Outline documentation structure", &[], 20),
+                ("write", "Write documentation", &["outline"], 60),
+                ("review", "Review documentation for accuracy", &["write"], 20),
+            ],
+            TaskDomain::Bugfix => &[
+                ("reproduce", "Reproduce the bug", &[], 30),
+                ("fix", "Fix the bug", &["reproduce"], 45),
+                ("tests", "Add regression test", &["fix"], 20),
+            ],
+            TaskDomain::Feature | TaskDomain::Generic => &[
+                ("design", "Design the change", &[], 30),
+                ("implement", "Implement the change", &["design"], 90),
+                ("tests", "Add tests", &["implement"], 30),
+                ("docs", "Update documentation", &["implement"], 20),
+            ],
+        };
+
+        let tasks: Vec<TaskSchema> = steps
+            .iter()
+            .map(|(id, title, deps, minutes)| TaskSchema {
+                id: id.to_string(),
+                title: title.to_string(),
+                description: format!("{}\n\n원래 요청:\n{}", title, composite_prompt),
+                dependencies: deps.iter().map(|d| d.to_string()).collect(),
+                estimated_duration_minutes: *minutes,
+                tags: vec![],
+            })
+            .collect();
+
+        let total_estimated_minutes = tasks.iter().map(|t| t.estimated_duration_minutes).sum();
+
+        TaskDecompositionResponse {
+            analysis: "Template-based decomposition (AI unavailable)".to_string(),
+            domain,
+            estimated_complexity: ComplexityEstimate::Medium,
+            parallel_batches: vec![],
+            critical_path: vec![],
+            total_estimated_minutes,
+            tasks,
+        }
+    }
+
     /// 레거시 메서드: 하위 호환성 유지 (내부적으로 AI 분해 사용)
     #[deprecated(note = "Use decompose() instead. This method now uses AI-based decomposition internally.")]
     pub async fn decompose_translation(&self, prompt: &str) -> Result<Vec<Task>> {
@@ -157,6 +312,58 @@ impl TaskDecomposer {
 mod tests {
     use super::*;
     use crate::claude::ClaudeAgent;
+    use crate::ollama::OllamaAgent;
+
+    #[tokio::test]
+    async fn test_decomposition_remaps_ids_and_keeps_estimates() {
+        let decomposition = serde_json::json!({
+            "analysis": "Two step change",
+            "domain": "Feature",
+            "estimated_complexity": "low",
+            "tasks": [
+                { "id": "task_1", "title": "Add model", "description": "Add model",
+                  "dependencies": [], "estimated_duration_minutes": 30 },
+                { "id": "task_2", "title": "Add API", "description": "Add API",
+                  "dependencies": ["task_1"], "estimated_duration_minutes": 45, "tags": ["API"] }
+            ],
+            "parallel_batches": [["task_1"], ["task_2"]],
+            "critical_path": ["task_1", "task_2"],
+            "total_estimated_minutes": 75
+        });
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/chat")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({ "message": { "content": decomposition.to_string() } }).to_string(),
+            )
+            .create_async()
+            .await;
+
+        let decomposer = TaskDecomposer::new(Arc::new(OllamaAgent::new(server.url(), "llama3.1")));
+        let tasks = decomposer.decompose("Add a users API").await.unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_ne!(tasks[0].id, "task_1");
+        assert_eq!(tasks[1].dependencies, vec![tasks[0].id.clone()]);
+        assert_eq!(tasks[1].estimated_minutes, Some(45));
+        assert_eq!(tasks[1].tags, vec!["api"]);
+    }
+
+    #[tokio::test]
+    async fn test_offline_fallback_uses_templates() {
+        // Nothing listens on port 1, so every AI call fails
+        let agent = Arc::new(OllamaAgent::new("http://127.0.0.1:1", "llama3.1"));
+        let decomposer = TaskDecomposer::new(agent);
+
+        let tasks = decomposer.decompose("Fix crash when saving settings").await.unwrap();
+
+        assert_eq!(tasks.len(), 3);
+        assert!(tasks[0].dependencies.is_empty());
+        assert_eq!(tasks[1].dependencies, vec![tasks[0].id.clone()]);
+        assert!(tasks.iter().all(|t| t.prompt.contains("Fix crash when saving settings")));
+    }
 
     #[tokio::test]
     #[ignore] // API 키 필요
@@ -1,13 +1,19 @@
 use crate::{
-    agent::{AIAgent, AgentResult, AgentType, ReviewResult},
+    agent::{AIAgent, AgentResult, AgentType, Capability, ReviewResult},
     Result,
 };
 use async_trait::async_trait;
-use autodev_core::Task;
+use autodev_core::{ContainerSandbox, Task};
 use bollard::container::{Config, CreateContainerOptions, LogsOptions, RemoveContainerOptions, WaitContainerOptions};
 use bollard::Docker;
 use futures_util::StreamExt;
 use serde::Deserialize;
+use std::sync::{Arc, OnceLock};
+
+use crate::container_pool::{ContainerPool, PoolConfig};
+
+/// 읽기 전용 루트 파일시스템에서도 쓰기가 필요한 경로 (claude-executor 이미지는 root로 실행)
+const EXECUTOR_WRITABLE_PATHS: [&str; 3] = ["/tmp", "/workspace", "/root"];
 
 /// 마크다운 코드 블록 제거 헬퍼 함수
 /// ```json\n{...}\n``` 또는 ```\n{...}\n``` 패턴을 순수 JSON으로 변환
@@ -32,10 +38,16 @@ fn strip_markdown_code_block(text: &str) -> &str {
 
 /// Docker 컨테이너 기반 AI Executor
 /// Claude Code CLI를 Docker 컨테이너에서 실행하여 OAuth 토큰으로 인증
+///
+/// 풀이 켜져 있으면 (기본값) 대기 중인 컨테이너에서 `docker exec`로 실행
 pub struct DockerAIExecutor {
     docker: Docker,
     oauth_token: String,
     image: String,
+    sandbox: ContainerSandbox,
+    pool_config: Option<PoolConfig>,
+    /// 첫 호출 때 생성 (샌드박스 설정이 확정된 뒤)
+    pool: OnceLock<Arc<ContainerPool>>,
 }
 
 impl DockerAIExecutor {
@@ -47,9 +59,41 @@ impl DockerAIExecutor {
             docker,
             oauth_token,
             image: "autodev-claude-executor:latest".to_string(),
+            sandbox: ContainerSandbox::from_env(),
+            pool_config: PoolConfig::from_env(),
+            pool: OnceLock::new(),
         })
     }
 
+    /// AUTODEV_AI_POOL_* 대신 지정한 풀 설정 사용 (None이면 호출마다 컨테이너 생성)
+    pub fn with_pool(mut self, pool_config: Option<PoolConfig>) -> Self {
+        self.pool_config = pool_config;
+        self
+    }
+
+    /// AUTODEV_CONTAINER_* 대신 지정한 컨테이너 제한 사용
+    pub fn with_sandbox(mut self, sandbox: ContainerSandbox) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// 컨테이너 설정 (이미지, 인증, 샌드박스)
+    fn container_config(&self, cmd: Option<Vec<String>>) -> Config<String> {
+        Config {
+            image: Some(self.image.clone()),
+            cmd,
+            env: Some(vec![
+                format!("CLAUDE_CODE_OAUTH_TOKEN={}", self.oauth_token),
+            ]),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            tty: Some(false),
+            host_config: Some(self.sandbox.host_config(&EXECUTOR_WRITABLE_PATHS)),
+            network_disabled: Some(self.sandbox.network_disabled()),
+            ..Default::default()
+        }
+    }
+
     /// Docker 컨테이너에서 Claude Code 실행
     async fn execute_in_container(
         &self,
@@ -85,24 +129,33 @@ impl DockerAIExecutor {
 
         cmd.push(full_prompt);
 
-        // 3. 컨테이너 설정
-        let container_name = format!("autodev-ai-{}", uuid::Uuid::new_v4());
-
-        let config = Config {
-            image: Some(self.image.clone()),
-            cmd: Some(cmd),
-            env: Some(vec![
-                format!("CLAUDE_CODE_OAUTH_TOKEN={}", self.oauth_token),
-            ]),
-            attach_stdout: Some(true),
-            attach_stderr: Some(true),
-            tty: Some(false),
-            ..Default::default()
+        // 3. 실행: 풀의 컨테이너에서 exec, 풀이 꺼져 있으면 일회용 컨테이너
+        let output = match self.pool_config {
+            Some(config) => {
+                let pool = self.pool.get_or_init(|| {
+                    Arc::new(ContainerPool::new(self.docker.clone(), config, self.container_config(None)))
+                });
+                pool.exec(cmd).await?
+            }
+            None => self.run_once(cmd).await?,
         };
 
+        // JSON 모드일 때 Claude CLI 래퍼 JSON에서 실제 응답 추출
+        if json_mode {
+            parse_cli_json(&output)
+        } else {
+            Ok(output.trim().to_string())
+        }
+    }
+
+    /// 일회용 컨테이너에서 `cmd`를 실행하고 출력 반환
+    async fn run_once(&self, cmd: Vec<String>) -> Result<String> {
+        let container_name = format!("autodev-ai-{}", uuid::Uuid::new_v4());
+        let config = self.container_config(Some(cmd));
+
         tracing::debug!("Creating Docker container for AI task: {}", container_name);
 
-        // 4. 컨테이너 생성
+        // 컨테이너 생성
         let container = self
             .docker
             .create_container(
@@ -115,7 +168,7 @@ impl DockerAIExecutor {
             .await
             .map_err(|e| crate::Error::ApiError(format!("Failed to create container: {}", e)))?;
 
-        // 5. 컨테이너 시작
+        // 컨테이너 시작
         self.docker
             .start_container::<String>(&container.id, None)
             .await
@@ -123,7 +176,7 @@ impl DockerAIExecutor {
 
         tracing::debug!("Container started: {}", container.id);
 
-        // 6. 로그 수집
+        // 로그 수집
         let mut output = String::new();
         let mut logs_stream = self.docker.logs(
             &container.id,
@@ -147,7 +200,7 @@ impl DockerAIExecutor {
             }
         }
 
-        // 7. 컨테이너 대기
+        // 컨테이너 대기
         let wait_result = self
             .docker
             .wait_container(&container.id, None::<WaitContainerOptions<String>>);
@@ -165,7 +218,7 @@ impl DockerAIExecutor {
             }
         }
 
-        // 8. 컨테이너 삭제
+        // 컨테이너 삭제
         self.docker
             .remove_container(
                 &container.id,
@@ -182,47 +235,47 @@ impl DockerAIExecutor {
 
         tracing::debug!("Container removed: {}", container.id);
 
-        // JSON 모드일 때 Claude CLI 래퍼 JSON에서 실제 응답 추출
-        if json_mode {
-            #[derive(Deserialize)]
-            struct ClaudeCliResponse {
-                result: String,
-            }
+        Ok(output)
+    }
+}
 
-            let parsed: ClaudeCliResponse = serde_json::from_str(output.trim())
-                .map_err(|e| {
-                    tracing::error!("Failed to parse Claude CLI JSON wrapper: {}\nRaw output: {}", e, output);
-                    crate::Error::ParseError(format!("Failed to parse Claude CLI response: {}", e))
-                })?;
+/// Claude CLI의 JSON 래퍼에서 응답(JSON)을 추출하고 검증
+fn parse_cli_json(output: &str) -> Result<String> {
+    #[derive(Deserialize)]
+    struct ClaudeCliResponse {
+        result: String,
+    }
 
-            tracing::debug!("Extracted result from Claude CLI wrapper: {} chars", parsed.result.len());
+    let parsed: ClaudeCliResponse = serde_json::from_str(output.trim())
+        .map_err(|e| {
+            tracing::error!("Failed to parse Claude CLI JSON wrapper: {}\nRaw output: {}", e, output);
+            crate::Error::ParseError(format!("Failed to parse Claude CLI response: {}", e))
+        })?;
 
-            // 마크다운 코드 블록 제거 (방어적)
-            let clean_result = strip_markdown_code_block(&parsed.result);
+    tracing::debug!("Extracted result from Claude CLI wrapper: {} chars", parsed.result.len());
 
-            if clean_result != parsed.result.trim() {
-                tracing::debug!("Stripped markdown code block from result");
-            }
+    // 마크다운 코드 블록 제거 (방어적)
+    let clean_result = strip_markdown_code_block(&parsed.result);
 
-            // JSON 유효성 검증
-            serde_json::from_str::<serde_json::Value>(clean_result)
-                .map_err(|e| {
-                    tracing::error!("❌ Invalid JSON in result field: {}", e);
-                    tracing::error!("Raw result (first 500 chars): {}",
-                        &parsed.result.chars().take(500).collect::<String>());
-                    tracing::error!("Cleaned result: {}", clean_result);
-                    crate::Error::ParseError(format!(
-                        "Claude returned invalid JSON despite prompt: {}",
-                        e
-                    ))
-                })?;
-
-            tracing::debug!("✓ Valid JSON extracted: {} chars", clean_result.len());
-            Ok(clean_result.to_string())
-        } else {
-            Ok(output.trim().to_string())
-        }
+    if clean_result != parsed.result.trim() {
+        tracing::debug!("Stripped markdown code block from result");
     }
+
+    // JSON 유효성 검증
+    serde_json::from_str::<serde_json::Value>(clean_result)
+        .map_err(|e| {
+            tracing::error!("❌ Invalid JSON in result field: {}", e);
+            tracing::error!("Raw result (first 500 chars): {}",
+                &parsed.result.chars().take(500).collect::<String>());
+            tracing::error!("Cleaned result: {}", clean_result);
+            crate::Error::ParseError(format!(
+                "Claude returned invalid JSON despite prompt: {}",
+                e
+            ))
+        })?;
+
+    tracing::debug!("✓ Valid JSON extracted: {} chars", clean_result.len());
+    Ok(clean_result.to_string())
 }
 
 #[async_trait]
@@ -231,6 +284,16 @@ impl AIAgent for DockerAIExecutor {
         AgentType::Claude
     }
 
+    fn model(&self) -> &str {
+        // 컨테이너의 Claude Code가 기본 모델을 선택
+        "claude-code"
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        // 컨테이너에서는 JSON 응답만 받음 (코드 수정은 별도 Docker executor가 담당)
+        vec![Capability::Chat]
+    }
+
     async fn chat_json(&self, system: &str, user: &str) -> Result<String> {
         self.execute_in_container(system, user, true).await
     }
@@ -0,0 +1,349 @@
+//! Text embeddings for example retrieval
+//!
+//! AUTODEV_EMBEDDINGS_PROVIDER selects the provider (openai, voyage/anthropic,
+//! ollama); unset disables embeddings. Anthropic has no embeddings endpoint of
+//! its own and recommends Voyage AI, so `anthropic` uses Voyage.
+//!
+//! Vectors are cached per process and, with a [`VectorStore`], in the
+//! database, so each example is embedded once per model.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::ollama::DEFAULT_OLLAMA_URL;
+use crate::{Error, Result};
+
+pub const EMBEDDINGS_PROVIDER_ENV: &str = "AUTODEV_EMBEDDINGS_PROVIDER";
+pub const EMBEDDINGS_MODEL_ENV: &str = "AUTODEV_EMBEDDINGS_MODEL";
+
+const DEFAULT_OPENAI_MODEL: &str = "text-embedding-3-small";
+const DEFAULT_OPENAI_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_VOYAGE_MODEL: &str = "voyage-3";
+const DEFAULT_VOYAGE_URL: &str = "https://api.voyageai.com/v1";
+const DEFAULT_OLLAMA_MODEL: &str = "nomic-embed-text";
+
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Model the vectors come from (vectors of different models do not compare)
+    fn model(&self) -> &str;
+
+    /// One vector per text, in order
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Persistent vectors, keyed by model and text
+#[async_trait]
+pub trait VectorStore: Send + Sync {
+    /// Stored vectors of `texts` (texts without one are left out)
+    async fn load(&self, model: &str, texts: &[String]) -> Result<HashMap<String, Vec<f32>>>;
+
+    async fn save(&self, model: &str, vectors: &[(String, Vec<f32>)]) -> Result<()>;
+
+    /// `candidates` ordered by similarity to `query`, closest first
+    async fn nearest(&self, model: &str, candidates: &[String], query: &[f32], limit: usize) -> Result<Vec<String>>;
+}
+
+/// OpenAI (or compatible) `/embeddings` endpoint
+pub struct OpenAIEmbeddings {
+    client: Client,
+    api_key: String,
+    api_url: String,
+    model: String,
+}
+
+impl OpenAIEmbeddings {
+    pub fn new(api_key: String, model: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            api_url: DEFAULT_OPENAI_URL.to_string(),
+            model: model.unwrap_or_else(|| DEFAULT_OPENAI_MODEL.to_string()),
+        }
+    }
+
+    /// Voyage AI, the embeddings provider Anthropic recommends (same API shape)
+    pub fn voyage(api_key: String, model: Option<String>) -> Self {
+        Self {
+            api_url: DEFAULT_VOYAGE_URL.to_string(),
+            model: model.unwrap_or_else(|| DEFAULT_VOYAGE_MODEL.to_string()),
+            ..Self::new(api_key, None)
+        }
+    }
+
+    pub fn with_base_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+    #[serde(default)]
+    usage: Option<EmbeddingsUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingsUsage {
+    #[serde(alias = "prompt_tokens")]
+    total_tokens: u64,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAIEmbeddings {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        crate::usage::check_budget()?;
+
+        let response = self
+            .client
+            .post(format!("{}/embeddings", self.api_url))
+            .bearer_auth(&self.api_key)
+            .json(&json!({ "model": &self.model, "input": texts }))
+            .send()
+            .await?;
+
+        let response = crate::retry::check_response("Embeddings", response).await?;
+
+        let mut result: EmbeddingsResponse = response.json().await?;
+        if let Some(usage) = result.usage {
+            crate::usage::record(&self.model, usage.total_tokens, 0);
+        }
+
+        result.data.sort_by_key(|data| data.index);
+        expect_count(texts, result.data.into_iter().map(|data| data.embedding).collect())
+    }
+}
+
+/// Ollama `/api/embed` endpoint
+pub struct OllamaEmbeddings {
+    client: Client,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaEmbeddings {
+    pub fn new(base_url: impl Into<String>, model: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            model: model.unwrap_or_else(|| DEFAULT_OLLAMA_MODEL.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddings {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        crate::usage::check_budget()?;
+
+        let response = self
+            .client
+            .post(format!("{}/api/embed", self.base_url))
+            .json(&json!({ "model": &self.model, "input": texts }))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(Error::ModelNotAvailable(self.model.clone()));
+        }
+        let response = crate::retry::check_response("Ollama embed", response).await?;
+
+        let result: OllamaEmbedResponse = response.json().await?;
+        expect_count(texts, result.embeddings)
+    }
+}
+
+fn expect_count(texts: &[String], vectors: Vec<Vec<f32>>) -> Result<Vec<Vec<f32>>> {
+    if vectors.len() != texts.len() {
+        return Err(Error::ParseError(format!(
+            "Expected {} embeddings, got {}",
+            texts.len(),
+            vectors.len()
+        )));
+    }
+    Ok(vectors)
+}
+
+/// Provider configured by AUTODEV_EMBEDDINGS_PROVIDER (None when unset)
+pub fn provider_from_env() -> Result<Option<Arc<dyn EmbeddingProvider>>> {
+    let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+    let Some(provider) = env(EMBEDDINGS_PROVIDER_ENV) else {
+        return Ok(None);
+    };
+    let model = env(EMBEDDINGS_MODEL_ENV);
+    let api_key = |name: &str| {
+        env(name).ok_or_else(|| Error::ConfigError(format!("{} must be set for {} embeddings", name, provider)))
+    };
+
+    let provider: Arc<dyn EmbeddingProvider> = match provider.to_lowercase().as_str() {
+        "openai" => {
+            let mut embeddings = OpenAIEmbeddings::new(api_key("OPENAI_API_KEY")?, model);
+            if let Some(base_url) = env("OPENAI_BASE_URL") {
+                embeddings = embeddings.with_base_url(base_url);
+            }
+            Arc::new(embeddings)
+        }
+        "voyage" | "anthropic" => Arc::new(OpenAIEmbeddings::voyage(api_key("VOYAGE_API_KEY")?, model)),
+        "ollama" | "local" => {
+            let base_url = env("OLLAMA_BASE_URL").unwrap_or_else(|| DEFAULT_OLLAMA_URL.to_string());
+            Arc::new(OllamaEmbeddings::new(base_url, model))
+        }
+        other => return Err(Error::ConfigError(format!("Unknown embeddings provider: {}", other))),
+    };
+
+    tracing::info!("Using {} embeddings for example retrieval", provider.model());
+    Ok(Some(provider))
+}
+
+/// Process-wide provider configured from the environment
+pub fn global() -> Option<Arc<dyn EmbeddingProvider>> {
+    static GLOBAL: OnceLock<Option<Arc<dyn EmbeddingProvider>>> = OnceLock::new();
+    GLOBAL
+        .get_or_init(|| {
+            provider_from_env().unwrap_or_else(|e| {
+                tracing::warn!("Embeddings disabled: {}", e);
+                None
+            })
+        })
+        .clone()
+}
+
+type VectorCache = Mutex<HashMap<(String, String), Vec<f32>>>;
+
+fn cache() -> &'static VectorCache {
+    static CACHE: OnceLock<VectorCache> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Vectors of `texts`, embedding only the ones neither cached nor stored
+pub async fn embed_cached(
+    provider: &dyn EmbeddingProvider,
+    store: Option<&dyn VectorStore>,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>> {
+    let model = provider.model().to_string();
+    let mut found: HashMap<String, Vec<f32>> = {
+        let cache = cache().lock().unwrap();
+        texts
+            .iter()
+            .filter_map(|text| cache.get(&(model.clone(), text.clone())).map(|v| (text.clone(), v.clone())))
+            .collect()
+    };
+
+    let mut missing: Vec<String> = texts.iter().filter(|t| !found.contains_key(*t)).cloned().collect();
+    let mut seen = HashSet::new();
+    missing.retain(|text| seen.insert(text.clone()));
+
+    if let (Some(store), false) = (store, missing.is_empty()) {
+        match store.load(&model, &missing).await {
+            Ok(stored) => {
+                remember(&model, &stored);
+                found.extend(stored);
+                missing.retain(|text| !found.contains_key(text));
+            }
+            Err(e) => tracing::warn!("Failed to load stored embeddings: {}", e),
+        }
+    }
+
+    if !missing.is_empty() {
+        let vectors: Vec<(String, Vec<f32>)> =
+            missing.iter().cloned().zip(provider.embed(&missing).await?).collect();
+
+        if let Some(store) = store {
+            if let Err(e) = store.save(&model, &vectors).await {
+                tracing::warn!("Failed to store embeddings: {}", e);
+            }
+        }
+        remember(&model, &vectors.iter().cloned().collect());
+        found.extend(vectors);
+    }
+
+    Ok(texts.iter().map(|text| found[text].clone()).collect())
+}
+
+fn remember(model: &str, vectors: &HashMap<String, Vec<f32>>) {
+    let mut cache = cache().lock().unwrap();
+    for (text, vector) in vectors {
+        cache.insert((model.to_string(), text.clone()), vector.clone());
+    }
+}
+
+/// Cosine similarity (0 when either vector is zero or the lengths differ)
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert!((cosine_similarity(&[1.0, 1.0], &[-1.0, -1.0]) + 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_embed_cached() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/embed")
+            .with_status(200)
+            .with_body(json!({ "embeddings": [[1.0, 0.0], [0.0, 1.0]] }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let model = format!("test-{}", uuid::Uuid::new_v4());
+        let provider = OllamaEmbeddings::new(server.url(), Some(model));
+        let texts = vec!["add login".to_string(), "fix crash".to_string()];
+
+        let first = embed_cached(&provider, None, &texts).await.unwrap();
+        // The second call is answered from the cache
+        let second = embed_cached(&provider, None, &texts).await.unwrap();
+
+        assert_eq!(first, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert_eq!(first, second);
+        mock.assert_async().await;
+    }
+}
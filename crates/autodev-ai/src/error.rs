@@ -1,3 +1,5 @@
+use autodev_core::ErrorClass;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,6 +13,15 @@ pub enum Error {
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
 
+    /// Rate limited, overloaded (529) or a server error: worth retrying
+    #[error("AI provider unavailable (HTTP {status}): {message}")]
+    Unavailable {
+        status: u16,
+        message: String,
+        /// Delay the provider asked for (Retry-After)
+        retry_after: Option<Duration>,
+    },
+
     #[error("Model not available: {0}")]
     ModelNotAvailable(String),
 
@@ -26,6 +37,9 @@ pub enum Error {
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    #[error("AI budget exceeded: {0}")]
+    BudgetExceeded(String),
+
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
@@ -39,4 +53,41 @@ pub enum Error {
     Other(#[from] anyhow::Error),
 }
 
+impl Error {
+    /// Whether an AI call was refused because a budget ran out
+    pub fn is_budget_exceeded(&self) -> bool {
+        matches!(self, Error::BudgetExceeded(_))
+    }
+
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            Error::InvalidApiKey => ErrorClass::AuthError,
+            Error::RateLimitExceeded => ErrorClass::RateLimited { retry_after: None },
+            Error::Unavailable { status: 429, retry_after, .. } => ErrorClass::RateLimited {
+                retry_after: *retry_after,
+            },
+            Error::Unavailable { .. } => ErrorClass::Transient,
+            Error::ModelNotAvailable(_) => ErrorClass::NotFound,
+            Error::Request(e) if e.is_timeout() || e.is_connect() => ErrorClass::Transient,
+            Error::Request(e) => e
+                .status()
+                .map_or(ErrorClass::Permanent, |status| ErrorClass::from_status(status.as_u16(), None)),
+            _ => ErrorClass::Permanent,
+        }
+    }
+
+    /// Whether the same call may succeed later (or on another model)
+    pub fn is_transient(&self) -> bool {
+        self.class().is_retryable()
+    }
+
+    /// Delay the provider asked for before retrying
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::Unavailable { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
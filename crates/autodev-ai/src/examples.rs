@@ -1,4 +1,6 @@
 use crate::agent::AIAgent;
+use crate::embeddings::{self, EmbeddingProvider, VectorStore};
+use crate::prompts::{self, PromptRegistry, PromptVars};
 use crate::schema::{DomainDetectionResponse, ExampleRankingResponse, TaskDomain, TaskDecompositionResponse};
 use crate::Result;
 use serde::{Deserialize, Serialize};
@@ -13,42 +15,110 @@ pub struct FewShotExample {
     pub assistant_response: TaskDecompositionResponse,
 }
 
+impl FewShotExample {
+    /// 사용자가 등록한 예제 검증 (도메인 이름, 분해 결과 JSON)
+    pub fn parse(domain: &str, user_prompt: &str, assistant_response: &str) -> Result<Self> {
+        let domain: TaskDomain = serde_json::from_value(serde_json::Value::String(domain.to_string()))
+            .map_err(|_| crate::Error::ValidationError(format!("Unknown domain: {}", domain)))?;
+        let assistant_response: TaskDecompositionResponse = serde_json::from_str(assistant_response)
+            .map_err(|e| crate::Error::ParseError(format!("Invalid assistant_response: {}", e)))?;
+        assistant_response
+            .validate()
+            .map_err(|e| crate::Error::ValidationError(format!("Invalid assistant_response: {}", e)))?;
+
+        if user_prompt.trim().is_empty() {
+            return Err(crate::Error::ValidationError("user_prompt is empty".to_string()));
+        }
+
+        Ok(Self {
+            domain,
+            user_prompt: user_prompt.to_string(),
+            assistant_response,
+        })
+    }
+}
+
 /// Few-shot 예제 데이터베이스
 pub struct ExampleDatabase {
     examples: Vec<FewShotExample>,
     domain_index: HashMap<TaskDomain, Vec<usize>>,
     agent: Option<Arc<dyn AIAgent>>,
+    prompts: PromptRegistry,
+    /// 사용자가 등록한 예제 (내장 예제보다 앞에 둠)
+    custom: Vec<FewShotExample>,
+    /// 임베딩 기반 예제 검색 (None이면 AI/키워드 검색만 사용)
+    embeddings: Option<Arc<dyn EmbeddingProvider>>,
+    vector_store: Option<Arc<dyn VectorStore>>,
 }
 
 impl ExampleDatabase {
     /// 내장된 예제로 데이터베이스 초기화
     pub fn new() -> Self {
-        let examples = Self::load_builtin_examples();
-        let domain_index = Self::build_domain_index(&examples);
-
-        Self {
-            examples,
-            domain_index,
-            agent: None,
-        }
+        Self::load(None, prompts::global().clone())
     }
 
     /// AI agent와 함께 데이터베이스 초기화
     pub fn with_agent(agent: Arc<dyn AIAgent>) -> Self {
-        let examples = Self::load_builtin_examples();
+        Self::load(Some(agent), prompts::global().clone())
+    }
+
+    /// 다른 프롬프트 템플릿으로 다시 로드 (예제도 다시 읽음)
+    pub fn with_prompts(self, prompts: PromptRegistry) -> Self {
+        let mut db = Self::load(self.agent, prompts).with_custom_examples(self.custom);
+        db.embeddings = self.embeddings;
+        db.vector_store = self.vector_store;
+        db
+    }
+
+    /// 임베딩 제공자 지정 (기본값: AUTODEV_EMBEDDINGS_PROVIDER 설정)
+    pub fn with_embeddings(mut self, embeddings: Option<Arc<dyn EmbeddingProvider>>) -> Self {
+        self.embeddings = embeddings;
+        self
+    }
+
+    /// 예제 벡터를 저장하고 유사도 검색에 사용할 저장소 (pgvector 등)
+    pub fn with_vector_store(mut self, store: Option<Arc<dyn VectorStore>>) -> Self {
+        self.vector_store = store;
+        self
+    }
+
+    /// 사용자 예제를 내장 예제와 함께 사용
+    ///
+    /// 사용자 예제가 먼저 오므로 키워드 점수가 같으면 사용자 예제가 선택된다.
+    pub fn with_custom_examples(mut self, custom: Vec<FewShotExample>) -> Self {
+        let builtin = self.examples.split_off(self.custom.len());
+        self.examples = custom.iter().cloned().chain(builtin).collect();
+        self.domain_index = Self::build_domain_index(&self.examples);
+        self.custom = custom;
+        self
+    }
+
+    fn load(agent: Option<Arc<dyn AIAgent>>, prompts: PromptRegistry) -> Self {
+        let examples = Self::load_examples(&prompts);
         let domain_index = Self::build_domain_index(&examples);
 
         Self {
             examples,
             domain_index,
-            agent: Some(agent),
+            agent,
+            prompts,
+            custom: Vec::new(),
+            embeddings: embeddings::global(),
+            vector_store: None,
         }
     }
 
-    /// 내장 예제 로드
-    fn load_builtin_examples() -> Vec<FewShotExample> {
-        let json_data = include_str!("../prompts/few_shot_examples.json");
-        serde_json::from_str(json_data).expect("Failed to parse few_shot_examples.json")
+    /// 예제 로드 (재정의한 few_shot_examples.json이 잘못되었으면 내장 예제 사용)
+    fn load_examples(prompts: &PromptRegistry) -> Vec<FewShotExample> {
+        let parsed = prompts
+            .template("few_shot_examples.json")
+            .and_then(|json_data| serde_json::from_str(&json_data).map_err(Into::into));
+
+        parsed.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load few_shot_examples.json, using built-in examples: {}", e);
+            let json_data = PromptRegistry::default_template("few_shot_examples.json").unwrap_or("[]");
+            serde_json::from_str(json_data).expect("Failed to parse few_shot_examples.json")
+        })
     }
 
     /// 도메인별 인덱스 구축
@@ -58,7 +128,7 @@ impl ExampleDatabase {
         for (i, example) in examples.iter().enumerate() {
             index
                 .entry(example.domain.clone())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(i);
         }
 
@@ -84,7 +154,6 @@ impl ExampleDatabase {
             crate::Error::ConfigError("ExampleDatabase initialized without AI agent".to_string())
         })?;
 
-        let system_prompt = include_str!("../prompts/example_selection_system.txt").to_string();
 
         // 예제 목록 포맷팅
         let examples_list = self
@@ -100,10 +169,12 @@ impl ExampleDatabase {
             .collect::<Vec<_>>()
             .join("\n");
 
-        let user_message = format!(
-            "사용자 요청: {}\n\n제공된 예제:\n{}\n\n가장 유사한 {}개의 예제를 선택하세요.",
-            user_prompt, examples_list, limit
-        );
+        let vars = PromptVars::new()
+            .set("request", user_prompt)
+            .set("examples", examples_list)
+            .set("limit", limit.to_string());
+        let system_prompt = self.prompts.render("example_selection_system.txt", &vars)?;
+        let user_message = self.prompts.render("example_selection.txt", &vars)?;
 
         tracing::debug!("AI 예제 선택 시작");
 
@@ -144,7 +215,7 @@ impl ExampleDatabase {
             .collect();
 
         // 점수 내림차순 정렬
-        scored_examples.sort_by(|a, b| b.0.cmp(&a.0));
+        scored_examples.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
 
         scored_examples
             .into_iter()
@@ -153,8 +224,58 @@ impl ExampleDatabase {
             .collect()
     }
 
-    /// 예제 선택 (AI 우선, 실패 시 fallback)
+    /// 임베딩 코사인 유사도로 예제 선택
+    ///
+    /// 예제 벡터는 한 번만 계산해 캐시하며, 벡터 저장소가 있으면 저장소에서 검색한다.
+    pub async fn find_relevant_examples_with_embeddings(&self, user_prompt: &str, limit: usize) -> Result<Vec<&FewShotExample>> {
+        let provider = self.embeddings.as_ref().ok_or_else(|| {
+            crate::Error::ConfigError("ExampleDatabase initialized without embeddings".to_string())
+        })?;
+        let store = self.vector_store.as_deref();
+
+        let texts: Vec<String> = self.examples.iter().map(|ex| ex.user_prompt.clone()).collect();
+        let vectors = embeddings::embed_cached(provider.as_ref(), store, &texts).await?;
+        let query = provider
+            .embed(&[user_prompt.to_string()])
+            .await?
+            .pop()
+            .unwrap_or_default();
+
+        if let Some(store) = store {
+            match store.nearest(provider.model(), &texts, &query, limit).await {
+                Ok(nearest) => {
+                    return Ok(nearest
+                        .iter()
+                        .flat_map(|text| self.examples.iter().filter(move |ex| &ex.user_prompt == text))
+                        .take(limit)
+                        .collect())
+                }
+                Err(e) => tracing::warn!("벡터 저장소 검색 실패, 메모리에서 계산: {}", e),
+            }
+        }
+
+        let mut scored: Vec<(f32, &FewShotExample)> = self
+            .examples
+            .iter()
+            .zip(&vectors)
+            .map(|(example, vector)| (embeddings::cosine_similarity(&query, vector), example))
+            .collect();
+
+        // 안정 정렬이므로 점수가 같으면 사용자 예제가 먼저 온다
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        Ok(scored.into_iter().take(limit).map(|(_, example)| example).collect())
+    }
+
+    /// 예제 선택 (임베딩 → AI → 키워드 순으로 시도)
     pub async fn find_relevant_examples(&self, user_prompt: &str, limit: usize) -> Vec<&FewShotExample> {
+        if self.embeddings.is_some() {
+            match self.find_relevant_examples_with_embeddings(user_prompt, limit).await {
+                Ok(examples) => return examples,
+                Err(e) => tracing::warn!("임베딩 예제 선택 실패, AI 선택 사용: {}", e),
+            }
+        }
+
         match self.find_relevant_examples_with_ai(user_prompt, limit).await {
             Ok(examples) => examples,
             Err(e) => {
@@ -176,7 +297,7 @@ impl ExampleDatabase {
         // 공통 단어 개수 계산
         let mut score = 0;
         for user_word in &user_words {
-            if example_words.contains(&user_word) {
+            if example_words.contains(user_word) {
                 score += 1;
             }
         }
@@ -190,8 +311,9 @@ impl ExampleDatabase {
             crate::Error::ConfigError("ExampleDatabase initialized without AI agent".to_string())
         })?;
 
-        let system_prompt = include_str!("../prompts/domain_detection_system.txt").to_string();
-        let user_message = format!("사용자 요청:\n{}", user_prompt);
+        let vars = PromptVars::new().set("request", user_prompt);
+        let system_prompt = self.prompts.render("domain_detection_system.txt", &vars)?;
+        let user_message = self.prompts.render("domain_detection.txt", &vars)?;
 
         tracing::debug!("AI 도메인 감지 시작: {}", user_prompt);
 
@@ -212,37 +334,7 @@ impl ExampleDatabase {
 
     /// 도메인 감지 (키워드 기반 fallback)
     pub fn detect_domain_fallback(&self, user_prompt: &str) -> TaskDomain {
-        let prompt_lower = user_prompt.to_lowercase();
-
-        // 한글 + 영어 키워드 매칭
-        if prompt_lower.contains("translate") || prompt_lower.contains("translation")
-            || prompt_lower.contains("번역") || prompt_lower.contains("다국어") {
-            TaskDomain::Translation
-        } else if prompt_lower.contains("security") || prompt_lower.contains("audit")
-            || prompt_lower.contains("vulnerability") || prompt_lower.contains("보안")
-            || prompt_lower.contains("취약점") || prompt_lower.contains("인증") {
-            TaskDomain::Security
-        } else if prompt_lower.contains("refactor") || prompt_lower.contains("refactoring")
-            || prompt_lower.contains("리팩토링") || prompt_lower.contains("재구성") {
-            TaskDomain::Refactoring
-        } else if prompt_lower.contains("test") || prompt_lower.contains("testing")
-            || prompt_lower.contains("coverage") || prompt_lower.contains("테스트")
-            || prompt_lower.contains("커버리지") {
-            TaskDomain::Testing
-        } else if prompt_lower.contains("document") || prompt_lower.contains("documentation")
-            || prompt_lower.contains("readme") || prompt_lower.contains("문서") {
-            TaskDomain::Documentation
-        } else if prompt_lower.contains("fix") || prompt_lower.contains("bug")
-            || prompt_lower.contains("crash") || prompt_lower.contains("leak")
-            || prompt_lower.contains("수정") || prompt_lower.contains("버그") {
-            TaskDomain::Bugfix
-        } else if prompt_lower.contains("feature") || prompt_lower.contains("implement")
-            || prompt_lower.contains("add") || prompt_lower.contains("기능")
-            || prompt_lower.contains("추가") || prompt_lower.contains("구현") {
-            TaskDomain::Feature
-        } else {
-            TaskDomain::Generic
-        }
+        TaskDomain::from_keywords(user_prompt)
     }
 
     /// 도메인 감지 (AI 우선, 실패 시 fallback)
@@ -289,42 +381,105 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_detect_domain() {
+    #[tokio::test]
+    async fn test_detect_domain() {
         let db = ExampleDatabase::new();
 
         assert_eq!(
-            db.detect_domain("Translate all pages to Korean"),
+            db.detect_domain("Translate all pages to Korean").await,
             TaskDomain::Translation
         );
 
         assert_eq!(
-            db.detect_domain("Perform security audit on API"),
+            db.detect_domain("Perform security audit on API").await,
             TaskDomain::Security
         );
 
         assert_eq!(
-            db.detect_domain("Refactor authentication service"),
+            db.detect_domain("Refactor authentication service").await,
             TaskDomain::Refactoring
         );
 
         assert_eq!(
-            db.detect_domain("Increase test coverage to 90%"),
+            db.detect_domain("Increase test coverage to 90%").await,
             TaskDomain::Testing
         );
 
         assert_eq!(
-            db.detect_domain("Fix memory leak in WebSocket handler"),
+            db.detect_domain("Fix memory leak in WebSocket handler").await,
             TaskDomain::Bugfix
         );
     }
 
-    #[test]
-    fn test_find_relevant_examples() {
+    #[tokio::test]
+    async fn test_find_relevant_examples() {
         let db = ExampleDatabase::new();
-        let examples = db.find_relevant_examples("Translate documentation to multiple languages", 3);
+        let examples = db.find_relevant_examples("Translate documentation to multiple languages", 3).await;
 
         assert!(!examples.is_empty());
         assert!(examples.len() <= 3);
     }
+
+    /// 보안 관련 문장이면 [1, 0], 아니면 [0, 1]
+    struct KeywordEmbeddings(String);
+
+    #[async_trait::async_trait]
+    impl EmbeddingProvider for KeywordEmbeddings {
+        fn model(&self) -> &str {
+            &self.0
+        }
+
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    if text.contains("security") || text.contains("보안") || text.contains("vulnerab") {
+                        vec![1.0, 0.0]
+                    } else {
+                        vec![0.0, 1.0]
+                    }
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_relevant_examples_with_embeddings() {
+        let model = format!("keyword-{}", uuid::Uuid::new_v4());
+        let db = ExampleDatabase::new().with_embeddings(Some(Arc::new(KeywordEmbeddings(model))));
+
+        let examples = db
+            .find_relevant_examples_with_embeddings("Check the API for security holes", 2)
+            .await
+            .unwrap();
+
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].domain, TaskDomain::Security);
+
+        let without = ExampleDatabase::new().with_embeddings(None);
+        assert!(without.find_relevant_examples_with_embeddings("anything", 2).await.is_err());
+    }
+
+    #[test]
+    fn test_custom_examples() {
+        let builtin = ExampleDatabase::new().all_examples().len();
+        let response = serde_json::to_string(&ExampleDatabase::new().all_examples()[0].assistant_response).unwrap();
+
+        let custom = FewShotExample::parse("bugfix", "Fix flaky payment webhook retries", &response).unwrap();
+        assert_eq!(custom.domain, TaskDomain::Bugfix);
+        assert!(FewShotExample::parse("astrology", "Fix it", &response).is_err());
+        assert!(FewShotExample::parse("bugfix", "Fix it", "{}").is_err());
+
+        let db = ExampleDatabase::new().with_custom_examples(vec![custom.clone()]);
+        assert_eq!(db.all_examples().len(), builtin + 1);
+        assert_eq!(
+            db.find_relevant_examples_fallback("Fix flaky payment webhook retries", 1)[0].user_prompt,
+            custom.user_prompt
+        );
+
+        // Replacing the custom examples keeps the built-in ones
+        let db = db.with_custom_examples(Vec::new());
+        assert_eq!(db.all_examples().len(), builtin);
+    }
 }
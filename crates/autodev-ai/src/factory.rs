@@ -0,0 +1,392 @@
+use std::sync::Arc;
+
+use crate::{
+    ollama::{DEFAULT_OLLAMA_MODEL, DEFAULT_OLLAMA_URL},
+    retry::{FallbackAgent, RetryPolicy},
+    router::AgentRouter,
+    schema::TaskDomain,
+    AIAgent, AgentType, Capability, ClaudeAgent, DockerAIExecutor, OllamaAgent, OpenAIAgent, Result,
+};
+
+/// Comma-separated fallback chain, e.g. `haiku-4.5,openai:gpt-4o-mini`
+pub const FALLBACK_ENV: &str = "AI_FALLBACK_MODELS";
+
+/// Comma-separated routing table, e.g. `commit_message=haiku-4.5,execute/security=claude:opus`
+pub const ROUTES_ENV: &str = "AI_ROUTES";
+
+/// Settings used to pick and build an AI agent
+///
+/// Unset fields fall back to the backend's environment variables
+/// (ANTHROPIC_API_KEY, OPENAI_API_KEY, OLLAMA_BASE_URL, ...).
+#[derive(Debug, Clone, Default)]
+pub struct AgentConfig {
+    /// claude / claude-code / docker / openai / gpt-4 / ollama / gemini
+    pub agent_type: String,
+    /// Model override (backend-specific name or alias)
+    pub model: Option<String>,
+    /// API key override
+    pub api_key: Option<String>,
+    /// Base URL override (OpenAI-compatible endpoint, Ollama server)
+    pub base_url: Option<String>,
+    /// Prefer the Docker executor with the Claude subscription OAuth token when available
+    pub allow_oauth: bool,
+    /// Agents tried in order when this one stays unavailable after retries
+    /// (an empty agent_type means the same backend as this agent)
+    pub fallbacks: Vec<AgentConfig>,
+    /// Agents for specific operations; everything else goes to this agent
+    pub routes: Vec<RouteConfig>,
+}
+
+/// Entry of the routing table
+#[derive(Debug, Clone)]
+pub struct RouteConfig {
+    pub capability: Capability,
+    /// Only tasks of this domain (code edits are the only operation with a domain)
+    pub domain: Option<TaskDomain>,
+    /// An empty agent_type means the same backend as the default agent
+    pub agent: AgentConfig,
+}
+
+impl AgentConfig {
+    pub fn new(agent_type: impl Into<String>) -> Self {
+        Self {
+            agent_type: agent_type.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Read AI_AGENT_TYPE, AI_MODEL, AI_FALLBACK_MODELS and AI_ROUTES from the environment
+    pub fn from_env() -> Self {
+        Self {
+            agent_type: std::env::var("AI_AGENT_TYPE").unwrap_or_else(|_| "claude-code".to_string()),
+            model: std::env::var("AI_MODEL").ok(),
+            fallbacks: Self::fallbacks_from_env(),
+            routes: Self::routes_from_env(),
+            ..Default::default()
+        }
+    }
+
+    /// Routing table from AI_ROUTES
+    pub fn routes_from_env() -> Vec<RouteConfig> {
+        std::env::var(ROUTES_ENV)
+            .map(|table| Self::parse_routes(&table))
+            .unwrap_or_default()
+    }
+
+    /// `operation[/domain]=agent` entries, the agent in the AI_FALLBACK_MODELS
+    /// format; invalid entries are skipped with a warning
+    pub fn parse_routes(table: &str) -> Vec<RouteConfig> {
+        table
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let route = entry
+                    .split_once('=')
+                    .ok_or_else(|| "expected operation=agent".to_string())
+                    .and_then(|(operation, agent)| {
+                        let (capability, domain) = match operation.split_once('/') {
+                            Some((capability, domain)) => (capability, Some(domain.trim().parse::<TaskDomain>()?)),
+                            None => (operation, None),
+                        };
+                        Ok(RouteConfig {
+                            capability: capability.trim().parse()?,
+                            domain,
+                            agent: Self::parse_agent(agent.trim()),
+                        })
+                    });
+
+                route
+                    .map_err(|e| tracing::warn!("Ignoring AI route '{}': {}", entry, e))
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Fallback chain from AI_FALLBACK_MODELS
+    pub fn fallbacks_from_env() -> Vec<AgentConfig> {
+        std::env::var(FALLBACK_ENV)
+            .map(|chain| Self::parse_fallbacks(&chain))
+            .unwrap_or_default()
+    }
+
+    /// `backend:model`, a backend (`openai`) or a model of the same backend (`haiku-4.5`)
+    pub fn parse_fallbacks(chain: &str) -> Vec<AgentConfig> {
+        chain
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(Self::parse_agent)
+            .collect()
+    }
+
+    fn parse_agent(entry: &str) -> AgentConfig {
+        match entry.split_once(':') {
+            Some((agent_type, model)) => Self::new(agent_type.trim()).with_model(model.trim()),
+            None if entry.parse::<AgentType>().is_ok() || entry.eq_ignore_ascii_case("docker") => Self::new(entry),
+            None => Self::new("").with_model(entry),
+        }
+    }
+
+    /// `other` with this agent's backend and credentials when it names no
+    /// other backend
+    fn inherit_backend(&self, other: &AgentConfig) -> AgentConfig {
+        let mut other = other.clone();
+        if other.agent_type.is_empty() || other.agent_type.eq_ignore_ascii_case(&self.agent_type) {
+            other.agent_type = self.agent_type.clone();
+            other.api_key = other.api_key.or_else(|| self.api_key.clone());
+            other.base_url = other.base_url.or_else(|| self.base_url.clone());
+        }
+        other
+    }
+
+    pub fn with_route(mut self, capability: Capability, domain: Option<TaskDomain>, agent: AgentConfig) -> Self {
+        self.routes.push(RouteConfig {
+            capability,
+            domain,
+            agent,
+        });
+        self
+    }
+
+    pub fn with_fallback(mut self, fallback: AgentConfig) -> Self {
+        self.fallbacks.push(fallback);
+        self
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    pub fn with_oauth(mut self, allow_oauth: bool) -> Self {
+        self.allow_oauth = allow_oauth;
+        self
+    }
+
+    /// Model override, falling back to a backend-specific env var
+    fn model_or_env(&self, env_var: &str) -> Option<String> {
+        self.model.clone().or_else(|| std::env::var(env_var).ok())
+    }
+
+    fn api_key_or_env(&self, env_var: &str) -> Result<String> {
+        self.api_key
+            .clone()
+            .or_else(|| std::env::var(env_var).ok())
+            .ok_or_else(|| crate::Error::ConfigError(format!(
+                "{} must be set for {} agent",
+                env_var, self.agent_type
+            )))
+    }
+}
+
+/// Build the AI agent selected by `config`
+///
+/// Transient failures are retried (AUTODEV_AI_MAX_RETRIES) and then handed to
+/// the fallbacks; a fallback that cannot be built (e.g. missing API key) is
+/// skipped with a warning. With routes, operations are sent to their agents
+/// through an [`AgentRouter`]; routes whose agent cannot be built are skipped.
+pub fn create_agent(config: &AgentConfig) -> Result<Arc<dyn AIAgent>> {
+    let policy = RetryPolicy::from_env();
+    let mut chain = vec![create_single_agent(config)?];
+
+    for fallback in &config.fallbacks {
+        // Same backend: reuse its credentials
+        let fallback = config.inherit_backend(fallback);
+
+        match create_single_agent(&fallback) {
+            Ok(agent) => chain.push(agent),
+            Err(e) => tracing::warn!("Skipping fallback agent {}: {}", fallback.agent_type, e),
+        }
+    }
+
+    if chain.len() > 1 {
+        tracing::info!(
+            "AI fallback chain: {}",
+            chain.iter().map(|agent| agent.model()).collect::<Vec<_>>().join(" → ")
+        );
+    }
+    let agent: Arc<dyn AIAgent> = Arc::new(FallbackAgent::new(chain, policy));
+
+    if config.routes.is_empty() {
+        return Ok(agent);
+    }
+
+    let mut router = AgentRouter::new(agent);
+    for route in &config.routes {
+        let route_agent = config.inherit_backend(&route.agent);
+
+        match create_single_agent(&route_agent) {
+            Ok(route_agent) => {
+                tracing::info!(
+                    "AI route: {}{} → {}",
+                    route.capability,
+                    route.domain.as_ref().map(|d| format!("/{:?}", d)).unwrap_or_default(),
+                    route_agent.model()
+                );
+                router = router.route(
+                    route.capability,
+                    route.domain.clone(),
+                    Arc::new(FallbackAgent::new(vec![route_agent], policy)),
+                );
+            }
+            Err(e) => tracing::warn!("Skipping AI route for {}: {}", route.capability, e),
+        }
+    }
+    Ok(Arc::new(router))
+}
+
+fn create_single_agent(config: &AgentConfig) -> Result<Arc<dyn AIAgent>> {
+    match config.agent_type.to_lowercase().as_str() {
+        "openai" | "gpt-4" | "gpt-4o" | "gpt" => {
+            let api_key = config.api_key_or_env("OPENAI_API_KEY")?;
+            let model = config.model_or_env("OPENAI_MODEL").unwrap_or_default();
+            let mut agent = OpenAIAgent::with_model(api_key, &model);
+
+            if let Some(base_url) = config.base_url.clone().or_else(|| std::env::var("OPENAI_BASE_URL").ok()) {
+                agent = agent.with_base_url(base_url);
+            }
+
+            tracing::info!("Using OpenAI agent");
+            Ok(Arc::new(agent))
+        }
+        "ollama" | "local" => {
+            let base_url = config
+                .base_url
+                .clone()
+                .or_else(|| std::env::var("OLLAMA_BASE_URL").ok())
+                .unwrap_or_else(|| DEFAULT_OLLAMA_URL.to_string());
+            let model = config
+                .model_or_env("OLLAMA_MODEL")
+                .unwrap_or_else(|| DEFAULT_OLLAMA_MODEL.to_string());
+
+            tracing::info!("Using Ollama agent ({} at {})", model, base_url);
+            Ok(Arc::new(OllamaAgent::new(base_url, model)))
+        }
+        "docker" | "claude-docker" | "oauth" => {
+            let oauth_token = std::env::var("CLAUDE_CODE_OAUTH_TOKEN").map_err(|_| {
+                crate::Error::ConfigError("CLAUDE_CODE_OAUTH_TOKEN must be set for docker agent".to_string())
+            })?;
+
+            tracing::info!("Using Docker-based AI executor with Claude subscription OAuth token");
+            Ok(Arc::new(DockerAIExecutor::new(oauth_token)?))
+        }
+        "gemini" => Err(crate::Error::ConfigError(
+            "Gemini agent is not available yet; use claude, openai or ollama".to_string(),
+        )),
+        other => {
+            if !matches!(other, "claude" | "claude-code" | "") && !other.starts_with("claude-") {
+                tracing::warn!("Unknown AI agent type: {}, using Claude", other);
+            }
+
+            // OAuth token first (Claude subscription), fallback to API key
+            if config.allow_oauth && config.api_key.is_none() {
+                if let Ok(oauth_token) = std::env::var("CLAUDE_CODE_OAUTH_TOKEN") {
+                    tracing::info!("Using Docker-based AI executor with Claude subscription OAuth token");
+                    return Ok(Arc::new(DockerAIExecutor::new(oauth_token)?));
+                }
+            }
+
+            let api_key = config.api_key_or_env("ANTHROPIC_API_KEY").map_err(|e| {
+                if config.allow_oauth {
+                    crate::Error::ConfigError(
+                        "Either CLAUDE_CODE_OAUTH_TOKEN or ANTHROPIC_API_KEY must be set".to_string(),
+                    )
+                } else {
+                    e
+                }
+            })?;
+
+            tracing::info!("Using HTTP API-based AI agent with API key");
+            Ok(match config.model_or_env("CLAUDE_MODEL") {
+                Some(model) => Arc::new(ClaudeAgent::with_model(api_key, &model)),
+                None => Arc::new(ClaudeAgent::new(api_key)),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explicit_settings() {
+        let agent = create_agent(&AgentConfig::new("openai").with_api_key("key")).unwrap();
+        assert!(matches!(agent.agent_type(), AgentType::OpenAI));
+
+        let agent = create_agent(&AgentConfig::new("ollama").with_model("qwen2.5-coder")).unwrap();
+        assert!(matches!(agent.agent_type(), AgentType::Ollama));
+
+        let agent = create_agent(&AgentConfig::new("claude").with_api_key("key")).unwrap();
+        assert!(matches!(agent.agent_type(), AgentType::Claude));
+    }
+
+    #[test]
+    fn test_parse_fallbacks() {
+        let fallbacks = AgentConfig::parse_fallbacks("haiku-4.5, openai:gpt-4o-mini,ollama,");
+
+        assert_eq!(fallbacks.len(), 3);
+        assert_eq!(fallbacks[0].agent_type, "");
+        assert_eq!(fallbacks[0].model.as_deref(), Some("haiku-4.5"));
+        assert_eq!(fallbacks[1].agent_type, "openai");
+        assert_eq!(fallbacks[1].model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(fallbacks[2].agent_type, "ollama");
+        assert_eq!(fallbacks[2].model, None);
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        let config = AgentConfig::new("claude")
+            .with_api_key("key")
+            .with_fallback(AgentConfig::new("").with_model("haiku-4.5"))
+            // Backends that cannot be built are skipped
+            .with_fallback(AgentConfig::new("gemini"));
+        let agent = create_agent(&config).unwrap();
+
+        assert!(matches!(agent.agent_type(), AgentType::Claude));
+        assert_eq!(agent.model(), "claude-sonnet-4-5-20250929");
+    }
+
+    #[test]
+    fn test_parse_routes() {
+        let routes = AgentConfig::parse_routes("commit=haiku-4.5, execute/security=claude:opus,chat=docker,bogus=openai");
+
+        assert_eq!(routes.len(), 3);
+        assert_eq!(routes[0].capability, Capability::CommitMessage);
+        assert_eq!(routes[0].agent.model.as_deref(), Some("haiku-4.5"));
+        assert_eq!(routes[1].capability, Capability::ExecuteTask);
+        assert_eq!(routes[1].domain, Some(TaskDomain::Security));
+        assert_eq!(routes[1].agent.agent_type, "claude");
+        assert_eq!(routes[2].agent.agent_type, "docker");
+    }
+
+    #[test]
+    fn test_routes() {
+        let config = AgentConfig::new("claude")
+            .with_api_key("key")
+            .with_route(Capability::CommitMessage, None, AgentConfig::new("").with_model("haiku-4.5"));
+        let agent = create_agent(&config).unwrap();
+
+        assert_eq!(agent.model(), "claude-sonnet-4-5-20250929");
+        assert!(agent.supports(Capability::ExecuteTask));
+    }
+
+    #[test]
+    fn test_unsupported_backend() {
+        assert!(matches!(
+            create_agent(&AgentConfig::new("gemini")),
+            Err(crate::Error::ConfigError(_))
+        ));
+    }
+}
@@ -1,16 +1,52 @@
 pub mod agent;
+pub mod budget;
+pub mod cache;
 pub mod claude;
+pub mod container_pool;
+pub mod openai;
+pub mod ollama;
 pub mod decomposer;
+pub mod merge_review;
+pub mod plan_review;
+pub mod pr_description;
+pub mod embeddings;
 pub mod docker_ai_executor;
 pub mod error;
+pub mod factory;
+pub mod prompts;
+pub mod retry;
+pub mod router;
 pub mod schema;
+pub mod stream;
+pub mod structured;
 pub mod examples;
+pub mod usage;
 
 // Re-exports
-pub use agent::{AIAgent, AgentResult, AgentType, ReviewResult};
+pub use agent::{
+    review_diff_chunks, AIAgent, AgentResult, AgentType, Capability, ReviewResult, SecurityIssue, SecuritySeverity,
+};
 pub use claude::ClaudeAgent;
+pub use openai::OpenAIAgent;
+pub use ollama::OllamaAgent;
 pub use decomposer::TaskDecomposer;
+pub use merge_review::MergeReviewer;
+pub use plan_review::{PlanReviewer, DEFAULT_MIN_CONFIDENCE};
+pub use pr_description::PrDescriptionGenerator;
+pub use container_pool::PoolConfig;
 pub use docker_ai_executor::DockerAIExecutor;
 pub use error::{Error, Result};
-pub use schema::{TaskDecompositionResponse, TaskSchema, TaskDomain, ComplexityEstimate};
-pub use examples::{ExampleDatabase, FewShotExample};
\ No newline at end of file
+pub use factory::{create_agent, AgentConfig, RouteConfig};
+pub use prompts::{PromptRegistry, PromptVars};
+pub use retry::{FallbackAgent, RetryPolicy};
+pub use router::AgentRouter;
+pub use stream::{with_stream, StreamSink};
+pub use schema::{
+    CodeReview, ComplexityEstimate, PlanFinding, PlanFindingKind, PlanReview, PrDescription, ReviewFinding,
+    SecurityReport, TaskDecompositionResponse, TaskDomain, TaskSchema,
+};
+pub use examples::{ExampleDatabase, FewShotExample};
+pub use embeddings::{EmbeddingProvider, OllamaEmbeddings, OpenAIEmbeddings, VectorStore};
+pub use usage::{track_usage, track_usage_within, TokenUsage};
+pub use budget::{Budget, BudgetConfig, BudgetLimit, BudgetScope};
+pub use cache::{with_response_cache, CacheStats, CacheStore, CachedAgent, ResponseCache};
\ No newline at end of file
@@ -0,0 +1,130 @@
+//! 병합 전 코드 리뷰
+//!
+//! [`MergeReviewer`]는 하위 작업 PR을 부모 브랜치에 자동 병합하기 전에 diff를
+//! 검토해 심각도가 매겨진 지적([`CodeReview`])을 돌려준다. 호출자는 기준
+//! 심각도 이상의 지적이 있으면 병합을 보류한다.
+
+use std::sync::Arc;
+
+use crate::{
+    agent::AIAgent,
+    prompts::{self, PromptRegistry, PromptVars},
+    schema::CodeReview,
+    structured, Error, Result,
+};
+
+pub struct MergeReviewer {
+    agent: Arc<dyn AIAgent>,
+    prompts: PromptRegistry,
+    /// 응답이 스키마/검증을 통과하지 못할 때 재요청을 포함한 최대 시도 횟수
+    max_attempts: u32,
+}
+
+impl MergeReviewer {
+    pub fn new(agent: Arc<dyn AIAgent>) -> Self {
+        Self {
+            agent,
+            prompts: prompts::global().clone(),
+            max_attempts: structured::DEFAULT_ATTEMPTS,
+        }
+    }
+
+    /// 프롬프트 템플릿 지정 (기본: AUTODEV_PROMPTS_DIR)
+    pub fn with_prompts(mut self, prompts: PromptRegistry) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// 리뷰 응답의 최대 시도 횟수 지정 (기본 3회)
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// `chunks`로 나뉜 diff를 검토 (조각마다 한 번씩 요청하고 결과를 합침)
+    pub async fn review(&self, title: &str, description: &str, chunks: &[String]) -> Result<CodeReview> {
+        if chunks.iter().all(|chunk| chunk.trim().is_empty()) {
+            return Err(Error::ValidationError("Cannot review an empty diff".to_string()));
+        }
+
+        let system_prompt = self.prompts.render("merge_review_system.txt", &PromptVars::new())?;
+        let mut merged = CodeReview::default();
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let part = if chunks.len() > 1 {
+                tracing::info!("Reviewing diff part {}/{} before merge", i + 1, chunks.len());
+                format!("전체 diff 중 {}/{} 부분", i + 1, chunks.len())
+            } else {
+                "전체 diff".to_string()
+            };
+            let vars = PromptVars::new()
+                .set("title", title)
+                .set("description", description)
+                .set("part", part)
+                .set("diff", chunk.as_str());
+            let user_prompt = self.prompts.render("merge_review.txt", &vars)?;
+
+            let review: CodeReview = structured::chat_structured(
+                self.agent.as_ref(),
+                &system_prompt,
+                &user_prompt,
+                &CodeReview::json_schema(),
+                self.max_attempts,
+                CodeReview::validate,
+            )
+            .await?;
+
+            merged = merged.merge(review);
+        }
+
+        tracing::info!(
+            "Merge review: {} findings (max severity: {})",
+            merged.findings.len(),
+            merged.max_severity().map(|s| s.as_str()).unwrap_or("none")
+        );
+
+        Ok(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OllamaAgent;
+    use autodev_core::ReviewSeverity;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_review() {
+        let reply = json!({
+            "message": {
+                "content": json!({
+                    "summary": "만료 검사에 버그가 있습니다",
+                    "findings": [
+                        { "severity": "low", "file": null, "line": null, "message": "주석 오타" },
+                        { "severity": "high", "file": "src/auth.rs", "line": 42, "message": "만료된 토큰이 통과합니다" }
+                    ]
+                })
+                .to_string()
+            }
+        });
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/chat")
+            .with_status(200)
+            .with_body(reply.to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let reviewer = MergeReviewer::new(Arc::new(OllamaAgent::new(server.url(), "llama3.1")));
+        let review = reviewer.review("토큰 갱신", "", &["diff --git a/src/auth.rs".to_string()]).await.unwrap();
+        mock.assert_async().await;
+
+        assert_eq!(review.max_severity(), Some(ReviewSeverity::High));
+        assert_eq!(review.blocking(ReviewSeverity::High).len(), 1);
+        assert!(review.blocking(ReviewSeverity::Critical).is_empty());
+        assert!(review.to_markdown().contains("- **high** `src/auth.rs:42` 만료된 토큰이 통과합니다\n- **low**"));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{
+    agent::{AIAgent, AgentResult, AgentType, BaseAgent, ReviewResult, SecurityIssue},
+    Result,
+};
+use async_trait::async_trait;
+use autodev_core::Task;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+pub const DEFAULT_OLLAMA_MODEL: &str = "llama3.1";
+
+/// Local LLM agent backed by an Ollama server (no API key, works air-gapped)
+pub struct OllamaAgent {
+    base: BaseAgent,
+    client: Client,
+    base_url: String,
+}
+
+impl OllamaAgent {
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            base: BaseAgent::new(AgentType::Ollama, String::new(), model.into()),
+            client: Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn call_api(&self, messages: Vec<Message>, json_mode: bool) -> Result<String> {
+        crate::usage::check_budget()?;
+
+        let mut body = json!({
+            "model": &self.base.model,
+            "messages": messages,
+            "stream": false,
+            "options": {
+                "temperature": if json_mode { 0.2 } else { 0.7 },
+            },
+        });
+
+        // Ollama의 format=json은 문법 수준에서 JSON 출력을 강제
+        if json_mode {
+            body["format"] = json!("json");
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/api/chat", self.base_url))
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(crate::Error::ModelNotAvailable(format!(
+                "{} (run `ollama pull {}`)",
+                self.base.model, self.base.model
+            )));
+        }
+        let response = crate::retry::check_response("Ollama", response).await?;
+
+        let result: ChatResponse = response.json().await?;
+        crate::usage::record(&self.base.model, result.prompt_eval_count, result.eval_count);
+        Ok(result.message.content)
+    }
+
+    fn user_message(content: String) -> Vec<Message> {
+        vec![Message {
+            role: "user".to_string(),
+            content,
+        }]
+    }
+}
+
+#[async_trait]
+impl AIAgent for OllamaAgent {
+    fn agent_type(&self) -> AgentType {
+        self.base.agent_type.clone()
+    }
+
+    fn model(&self) -> &str {
+        &self.base.model
+    }
+
+    async fn execute_task(&self, task: &Task, repo_path: &str) -> Result<AgentResult> {
+        tracing::info!("Ollama ({}) executing task: {}", self.base.model, task.title);
+
+        let prompt = self.base.build_task_prompt(task, repo_path)?;
+        let response = self.call_api(Self::user_message(prompt), false).await?;
+
+        Ok(AgentResult {
+            success: true,
+            files_changed: vec![],
+            pr_branch: format!("autodev/task-{}", task.id),
+            commit_message: format!("feat: {}", task.title),
+            output: Some(response),
+        })
+    }
+
+    async fn review_code_changes(
+        &self,
+        pr_diff: &str,
+        review_comments: &[String],
+    ) -> Result<ReviewResult> {
+        tracing::info!("Ollama reviewing code changes");
+
+        let prompt = self.base.build_review_prompt(pr_diff, review_comments)?;
+        let response = self.call_api(Self::user_message(prompt), false).await?;
+
+        Ok(ReviewResult {
+            success: true,
+            changes_made: vec![],
+            comments: vec![response],
+        })
+    }
+
+    async fn fix_ci_failures(&self, ci_logs: &str) -> Result<ReviewResult> {
+        tracing::info!("Ollama fixing CI failures");
+
+        let prompt = self.base.build_ci_fix_prompt(ci_logs)?;
+        let response = self.call_api(Self::user_message(prompt), false).await?;
+
+        Ok(ReviewResult {
+            success: true,
+            changes_made: vec![],
+            comments: vec![response],
+        })
+    }
+
+    async fn generate_commit_message(&self, changes: &str) -> Result<String> {
+        let prompt = self.base.build_commit_message_prompt(changes)?;
+
+        self.call_api(Self::user_message(prompt), false).await
+    }
+
+    async fn analyze_security(&self, code: &str, language: &str) -> Result<Vec<SecurityIssue>> {
+        crate::agent::analyze_security_structured(self, code, language).await
+    }
+
+    async fn chat_json(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        tracing::info!("Ollama ({}) chat with JSON mode", self.base.model);
+
+        // 작은 로컬 모델은 지시를 잘 놓치므로 system 프롬프트에 JSON 전용 응답을 명시
+        let messages = vec![
+            Message {
+                role: "system".to_string(),
+                content: format!(
+                    "{}\n\nIMPORTANT: Respond ONLY with valid JSON. \
+                     Do not include explanations, markdown, or code fences.",
+                    system_prompt
+                ),
+            },
+            Message {
+                role: "user".to_string(),
+                content: user_prompt.to_string(),
+            },
+        ];
+
+        let content = self.call_api(messages, true).await?;
+
+        // 로컬 모델은 JSON 앞뒤에 설명을 붙이는 경우가 많아 첫 객체/배열만 추출
+        crate::structured::extract_json(&content).ok_or_else(|| {
+            crate::Error::ParseError(format!(
+                "Ollama returned invalid JSON: {}",
+                content.chars().take(200).collect::<String>()
+            ))
+        })
+    }
+
+    /// Ollama has no credentials; checks that the server answers
+    async fn check_credentials(&self) -> Result<bool> {
+        let response = self.client.get(format!("{}/api/tags", self.base_url)).send().await?;
+        crate::retry::check_response("Ollama", response).await?;
+        Ok(true)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Message {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    message: ResponseMessage,
+    /// Input tokens
+    #[serde(default)]
+    prompt_eval_count: u64,
+    /// Output tokens
+    #[serde(default)]
+    eval_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseMessage {
+    content: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::SecuritySeverity;
+    use mockito::Matcher;
+
+    fn chat_response(content: &str) -> String {
+        json!({
+            "model": "llama3.1",
+            "message": { "role": "assistant", "content": content },
+            "done": true
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_chat_json_forces_json_format() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/chat")
+            .match_body(Matcher::PartialJson(json!({
+                "model": "llama3.1",
+                "stream": false,
+                "format": "json"
+            })))
+            .with_status(200)
+            .with_body(chat_response(r#"{"subtasks": []}"#))
+            .create_async()
+            .await;
+
+        let agent = OllamaAgent::new(server.url(), DEFAULT_OLLAMA_MODEL);
+        let result = agent.chat_json("Decompose", "Add login").await.unwrap();
+
+        assert_eq!(result, r#"{"subtasks": []}"#);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_analyze_security_parses_issues() {
+        let issues = json!({
+            "issues": [{
+                "severity": "Critical",
+                "title": "SQL 인젝션",
+                "description": "사용자 입력이 쿼리 문자열에 그대로 들어갑니다",
+                "file": "src/db.rs",
+                "line": 12,
+                "recommendation": "바인딩 파라미터를 사용하세요"
+            }]
+        });
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/chat")
+            .match_body(Matcher::Regex("언어: rust".to_string()))
+            .with_status(200)
+            .with_body(chat_response(&issues.to_string()))
+            .create_async()
+            .await;
+
+        let agent = OllamaAgent::new(server.url(), DEFAULT_OLLAMA_MODEL);
+        let result = agent.analyze_security("+ format!(\"SELECT {}\", id)", "rust").await.unwrap();
+        mock.assert_async().await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].severity, SecuritySeverity::Critical);
+        assert_eq!(result[0].file.as_deref(), Some("src/db.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_model() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/chat")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let agent = OllamaAgent::new(server.url(), "missing-model");
+        let result = agent.generate_commit_message("diff").await;
+
+        assert!(matches!(result, Err(crate::Error::ModelNotAvailable(_))));
+    }
+}
@@ -0,0 +1,430 @@
+use crate::{
+    agent::{AIAgent, AgentResult, AgentType, BaseAgent, ReviewResult, SecurityIssue},
+    Result,
+};
+use async_trait::async_trait;
+use autodev_core::Task;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const DEFAULT_MODEL: &str = "gpt-4o";
+const DEFAULT_API_URL: &str = "https://api.openai.com/v1";
+
+pub struct OpenAIAgent {
+    base: BaseAgent,
+    client: Client,
+    api_url: String,
+}
+
+impl OpenAIAgent {
+    pub fn new(api_key: String) -> Self {
+        Self::with_model(api_key, DEFAULT_MODEL)
+    }
+
+    /// 특정 모델을 지정하여 생성 (gpt-4o, gpt-4o-mini, gpt-4-turbo 등)
+    pub fn with_model(api_key: String, model: &str) -> Self {
+        let model_id = match model.to_lowercase().as_str() {
+            "gpt-4" | "4o" | "gpt4o" => DEFAULT_MODEL.to_string(),
+            "mini" | "4o-mini" => "gpt-4o-mini".to_string(),
+            "turbo" | "4-turbo" => "gpt-4-turbo".to_string(),
+            "" => DEFAULT_MODEL.to_string(),
+            other => other.to_string(),
+        };
+
+        Self {
+            base: BaseAgent::new(AgentType::OpenAI, api_key, model_id),
+            client: Client::new(),
+            api_url: DEFAULT_API_URL.to_string(),
+        }
+    }
+
+    /// OpenAI 호환 엔드포인트 사용 (Azure OpenAI 프록시, 테스트 서버 등)
+    pub fn with_base_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// `response_format`: JSON mode 또는 JSON Schema (None이면 자유 텍스트)
+    async fn call_api(&self, messages: Vec<Message>, response_format: Option<Value>) -> Result<String> {
+        crate::usage::check_budget()?;
+
+        let mut body = json!({
+            "model": &self.base.model,
+            "messages": messages,
+            "max_tokens": 4096,
+            "temperature": if response_format.is_some() { 0.3 } else { 0.7 },
+        });
+
+        if let Some(response_format) = response_format {
+            body["response_format"] = response_format;
+        }
+
+        let streaming = crate::stream::is_streaming();
+        if streaming {
+            body["stream"] = json!(true);
+            body["stream_options"] = json!({ "include_usage": true });
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/chat/completions", self.api_url))
+            .bearer_auth(&self.base.api_key)
+            .json(&body)
+            .send()
+            .await?;
+
+        let mut response = crate::retry::check_response("OpenAI", response).await?;
+
+        if streaming {
+            let mut parser = crate::stream::SseParser::new();
+            let mut text = String::new();
+
+            while let Some(chunk) = response.chunk().await? {
+                for data in parser.push(&chunk) {
+                    let Ok(chunk) = serde_json::from_str::<ChatCompletionChunk>(&data) else {
+                        continue; // [DONE]
+                    };
+
+                    if let Some(usage) = chunk.usage {
+                        crate::usage::record(&self.base.model, usage.prompt_tokens, usage.completion_tokens);
+                    }
+                    for delta in chunk.choices.into_iter().filter_map(|c| c.delta.content) {
+                        crate::stream::emit(&delta);
+                        text.push_str(&delta);
+                    }
+                }
+            }
+
+            return Ok(text);
+        }
+
+        let result: ChatCompletionResponse = response.json().await?;
+        if let Some(usage) = result.usage {
+            crate::usage::record(&self.base.model, usage.prompt_tokens, usage.completion_tokens);
+        }
+        Ok(result
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .unwrap_or_default())
+    }
+
+    fn user_message(content: String) -> Vec<Message> {
+        vec![Message {
+            role: "user".to_string(),
+            content,
+        }]
+    }
+}
+
+#[async_trait]
+impl AIAgent for OpenAIAgent {
+    fn agent_type(&self) -> AgentType {
+        self.base.agent_type.clone()
+    }
+
+    fn model(&self) -> &str {
+        &self.base.model
+    }
+
+    async fn execute_task(&self, task: &Task, repo_path: &str) -> Result<AgentResult> {
+        tracing::info!("OpenAI executing task: {}", task.title);
+
+        let prompt = self.base.build_task_prompt(task, repo_path)?;
+        let response = self.call_api(Self::user_message(prompt), None).await?;
+
+        Ok(AgentResult {
+            success: true,
+            files_changed: vec![],
+            pr_branch: format!("autodev/task-{}", task.id),
+            commit_message: format!("feat: {}", task.title),
+            output: Some(response),
+        })
+    }
+
+    async fn review_code_changes(
+        &self,
+        pr_diff: &str,
+        review_comments: &[String],
+    ) -> Result<ReviewResult> {
+        tracing::info!("OpenAI reviewing code changes");
+
+        let prompt = self.base.build_review_prompt(pr_diff, review_comments)?;
+        let response = self.call_api(Self::user_message(prompt), None).await?;
+
+        Ok(ReviewResult {
+            success: true,
+            changes_made: vec![],
+            comments: vec![response],
+        })
+    }
+
+    async fn fix_ci_failures(&self, ci_logs: &str) -> Result<ReviewResult> {
+        tracing::info!("OpenAI fixing CI failures");
+
+        let prompt = self.base.build_ci_fix_prompt(ci_logs)?;
+        let response = self.call_api(Self::user_message(prompt), None).await?;
+
+        Ok(ReviewResult {
+            success: true,
+            changes_made: vec![],
+            comments: vec![response],
+        })
+    }
+
+    async fn generate_commit_message(&self, changes: &str) -> Result<String> {
+        let prompt = self.base.build_commit_message_prompt(changes)?;
+
+        self.call_api(Self::user_message(prompt), None).await
+    }
+
+    async fn analyze_security(&self, code: &str, language: &str) -> Result<Vec<SecurityIssue>> {
+        crate::agent::analyze_security_structured(self, code, language).await
+    }
+
+    async fn chat_json(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        tracing::info!("OpenAI chat with JSON mode");
+
+        // json_object 모드는 메시지 어딘가에 "JSON"이라는 단어가 있어야 함
+        let system_content = if system_prompt.contains("JSON") {
+            system_prompt.to_string()
+        } else {
+            format!("{}\n\nRespond with a single valid JSON object.", system_prompt)
+        };
+
+        let messages = vec![
+            Message {
+                role: "system".to_string(),
+                content: system_content,
+            },
+            Message {
+                role: "user".to_string(),
+                content: user_prompt.to_string(),
+            },
+        ];
+
+        // JSON mode: 응답이 항상 유효한 JSON 객체가 되도록 강제
+        let content = self
+            .call_api(messages, Some(json!({ "type": "json_object" })))
+            .await?;
+
+        // JSON mode should already guarantee this, but fail early with a clear error
+        serde_json::from_str::<Value>(&content)
+            .map_err(|e| crate::Error::ParseError(format!("OpenAI returned invalid JSON: {}", e)))?;
+
+        Ok(content)
+    }
+
+    async fn chat_structured(&self, system_prompt: &str, user_prompt: &str, schema: &Value) -> Result<String> {
+        tracing::info!("OpenAI chat with structured output");
+
+        let messages = vec![
+            Message {
+                role: "system".to_string(),
+                content: system_prompt.to_string(),
+            },
+            Message {
+                role: "user".to_string(),
+                content: user_prompt.to_string(),
+            },
+        ];
+
+        // strict 모드: 응답이 스키마를 정확히 따르도록 디코딩 단계에서 강제
+        let response_format = json!({
+            "type": "json_schema",
+            "json_schema": { "name": "response", "schema": schema, "strict": true },
+        });
+        self.call_api(messages, Some(response_format)).await
+    }
+
+    async fn check_credentials(&self) -> Result<bool> {
+        let response = self
+            .client
+            .get(format!("{}/models", self.api_url))
+            .bearer_auth(&self.base.api_key)
+            .send()
+            .await?;
+        crate::retry::check_response("OpenAI", response).await?;
+        Ok(true)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Message {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<CompletionUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: ResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseMessage {
+    content: Option<String>,
+}
+
+/// 스트리밍 응답 조각 (마지막 조각에만 usage가 있음)
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    #[serde(default)]
+    usage: Option<CompletionUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    delta: ResponseMessage,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn completion(content: &str) -> String {
+        json!({
+            "choices": [
+                { "message": { "role": "assistant", "content": content } }
+            ],
+            "usage": { "prompt_tokens": 1200, "completion_tokens": 300, "total_tokens": 1500 }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_chat_json_uses_json_mode() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/chat/completions")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(Matcher::PartialJson(json!({
+                "model": "gpt-4o",
+                "response_format": { "type": "json_object" }
+            })))
+            .with_status(200)
+            .with_body(completion(r#"{"subtasks": []}"#))
+            .create_async()
+            .await;
+
+        let agent = OpenAIAgent::new("test-key".to_string()).with_base_url(server.url());
+        let result = agent.chat_json("Decompose the task", "Add login").await.unwrap();
+
+        assert_eq!(result, r#"{"subtasks": []}"#);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_usage_is_tracked() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/chat/completions")
+            .with_status(200)
+            .with_body(completion("feat: add login"))
+            .create_async()
+            .await;
+
+        let agent = OpenAIAgent::new("test-key".to_string()).with_base_url(server.url());
+        let (result, usage) = crate::usage::track_usage(agent.generate_commit_message("diff")).await;
+
+        assert_eq!(result.unwrap(), "feat: add login");
+        assert_eq!(usage.input_tokens, 1200);
+        assert_eq!(usage.output_tokens, 300);
+        assert!((usage.cost_usd - 0.006).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_chat_json_rejects_invalid_json() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/chat/completions")
+            .with_status(200)
+            .with_body(completion("not json"))
+            .create_async()
+            .await;
+
+        let agent = OpenAIAgent::new("test-key".to_string()).with_base_url(server.url());
+        let result = agent.chat_json("Return JSON", "hello").await;
+
+        assert!(matches!(result, Err(crate::Error::ParseError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_api_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/chat/completions")
+            .with_status(401)
+            .create_async()
+            .await;
+
+        let agent = OpenAIAgent::new("bad-key".to_string()).with_base_url(server.url());
+        let result = agent.generate_commit_message("diff").await;
+
+        assert!(matches!(result, Err(crate::Error::InvalidApiKey)));
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream() {
+        let events = [
+            json!({ "choices": [{ "delta": { "role": "assistant", "content": "" } }] }),
+            json!({ "choices": [{ "delta": { "content": "{\"subtasks\"" } }] }),
+            json!({ "choices": [{ "delta": { "content": ": []}" } }] }),
+            json!({ "choices": [], "usage": { "prompt_tokens": 100, "completion_tokens": 20 } }),
+        ];
+        let body: String = events
+            .iter()
+            .map(|event| format!("data: {}\n\n", event))
+            .chain(std::iter::once("data: [DONE]\n\n".to_string()))
+            .collect();
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/chat/completions")
+            .match_body(Matcher::PartialJson(json!({ "stream": true })))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let agent = OpenAIAgent::new("test-key".to_string()).with_base_url(server.url());
+        let deltas = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink: crate::stream::StreamSink = {
+            let deltas = deltas.clone();
+            std::sync::Arc::new(move |text: &str| deltas.lock().unwrap().push(text.to_string()))
+        };
+
+        let (result, usage) =
+            crate::usage::track_usage(agent.chat_stream("Decompose", "Add login", sink)).await;
+
+        assert_eq!(result.unwrap(), r#"{"subtasks": []}"#);
+        assert_eq!(*deltas.lock().unwrap(), vec![r#"{"subtasks""#, ": []}"]);
+        assert_eq!(usage.input_tokens, 100);
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_model_aliases() {
+        let agent = OpenAIAgent::with_model("key".to_string(), "mini");
+        assert_eq!(agent.base.model, "gpt-4o-mini");
+        assert!(matches!(agent.agent_type(), AgentType::OpenAI));
+    }
+}
@@ -0,0 +1,151 @@
+//! 분해 계획 검토
+//!
+//! [`PlanReviewer`]는 실행 전에 두 번째 AI 호출로 [`TaskDecompositionResponse`]를
+//! 비판적으로 검토한다. 빠진 단계, 충돌할 수 있는 병렬 작업, 지나치게 잘게
+//! 쪼갠 작업을 경고/제안으로 돌려주고, 신뢰도가 낮으면 호출자가 자동 승인을
+//! 막을 수 있다.
+
+use std::sync::Arc;
+
+use crate::{
+    agent::AIAgent,
+    prompts::{self, PromptRegistry, PromptVars},
+    schema::{PlanReview, TaskDecompositionResponse},
+    structured, Result,
+};
+
+/// 이 신뢰도 미만이면 계획을 사람이 검토하도록 권장
+pub const DEFAULT_MIN_CONFIDENCE: f64 = 0.6;
+
+pub struct PlanReviewer {
+    agent: Arc<dyn AIAgent>,
+    prompts: PromptRegistry,
+    /// 응답이 스키마/검증을 통과하지 못할 때 재요청을 포함한 최대 시도 횟수
+    max_attempts: u32,
+}
+
+impl PlanReviewer {
+    pub fn new(agent: Arc<dyn AIAgent>) -> Self {
+        Self {
+            agent,
+            prompts: prompts::global().clone(),
+            max_attempts: structured::DEFAULT_ATTEMPTS,
+        }
+    }
+
+    /// 프롬프트 템플릿 지정 (기본: AUTODEV_PROMPTS_DIR)
+    pub fn with_prompts(mut self, prompts: PromptRegistry) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// 검토 응답의 최대 시도 횟수 지정 (기본 3회)
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// `request`를 분해한 `plan`을 검토
+    ///
+    /// 지적의 task_ids는 계획의 작업 ID("task_1" 등)이다.
+    pub async fn review(&self, request: &str, plan: &TaskDecompositionResponse) -> Result<PlanReview> {
+        let plan_json = serde_json::to_string_pretty(plan)
+            .map_err(|e| crate::Error::ParseError(format!("Failed to serialize plan: {}", e)))?;
+        let vars = PromptVars::new().set("request", request).set("plan", plan_json);
+        let system_prompt = self.prompts.render("plan_review_system.txt", &vars)?;
+        let user_prompt = self.prompts.render("plan_review.txt", &vars)?;
+
+        let review: PlanReview = structured::chat_structured(
+            self.agent.as_ref(),
+            &system_prompt,
+            &user_prompt,
+            &PlanReview::json_schema(),
+            self.max_attempts,
+            |review: &PlanReview| review.validate(plan),
+        )
+        .await?;
+
+        tracing::info!(
+            "Plan review: confidence {:.2}, {} warnings, {} suggestions",
+            review.confidence,
+            review.warnings.len(),
+            review.suggestions.len()
+        );
+
+        Ok(review)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{ComplexityEstimate, PlanFindingKind, TaskDomain, TaskSchema};
+    use crate::OllamaAgent;
+    use serde_json::json;
+
+    fn plan() -> TaskDecompositionResponse {
+        let task = |id: &str, dependencies: Vec<String>| TaskSchema {
+            id: id.to_string(),
+            title: format!("Do {}", id),
+            description: String::new(),
+            dependencies,
+            estimated_duration_minutes: 10,
+            tags: vec![],
+        };
+
+        TaskDecompositionResponse {
+            analysis: "Test".to_string(),
+            domain: TaskDomain::Feature,
+            estimated_complexity: ComplexityEstimate::Low,
+            tasks: vec![task("task_1", vec![]), task("task_2", vec![])],
+            parallel_batches: vec![vec!["task_1".to_string(), "task_2".to_string()]],
+            critical_path: vec!["task_1".to_string()],
+            total_estimated_minutes: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_review() {
+        let reply = |content: serde_json::Value| json!({ "message": { "content": content.to_string() } }).to_string();
+        let finding = |task_id: &str| {
+            json!({ "kind": "risky_parallelism", "task_ids": [task_id], "message": "같은 파일을 수정합니다" })
+        };
+
+        let mut server = mockito::Server::new_async().await;
+        // Unknown task IDs are sent back for correction
+        let rejected = server
+            .mock("POST", "/api/chat")
+            .with_status(200)
+            .with_body(reply(json!({
+                "summary": "충돌 위험", "confidence": 0.4, "warnings": [finding("task_9")], "suggestions": []
+            })))
+            .expect(1)
+            .create_async()
+            .await;
+        let corrected = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::Regex("unknown task".to_string()))
+            .with_status(200)
+            .with_body(reply(json!({
+                "summary": "충돌 위험", "confidence": 0.4, "warnings": [finding("task_2")], "suggestions": []
+            })))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let reviewer = PlanReviewer::new(Arc::new(OllamaAgent::new(server.url(), "llama3.1")));
+        let review = reviewer.review("로그인 추가", &plan()).await.unwrap();
+        rejected.assert_async().await;
+        corrected.assert_async().await;
+
+        assert_eq!(review.warnings[0].kind, PlanFindingKind::RiskyParallelism);
+        assert!(!review.is_confident(DEFAULT_MIN_CONFIDENCE));
+
+        let tasks = vec![
+            autodev_core::Task::new("1".to_string(), "".to_string(), "".to_string()),
+            autodev_core::Task::new("2".to_string(), "".to_string(), "".to_string()),
+        ];
+        let review = review.with_task_ids(&plan(), &tasks);
+        assert_eq!(review.warnings[0].task_ids, vec![tasks[1].id.clone()]);
+    }
+}
@@ -0,0 +1,141 @@
+//! PR 설명 생성
+//!
+//! [`PrDescriptionGenerator`]는 작업 제목/설명이 아니라 PR의 실제 diff를 읽고
+//! 요약, 주요 변경, 테스트 메모, 위험 요소를 담은 [`PrDescription`]을 만든다.
+//! 큰 diff는 조각마다 한 번씩 요청하고 결과를 합친다.
+
+use std::sync::Arc;
+
+use crate::{
+    agent::AIAgent,
+    prompts::{self, PromptRegistry, PromptVars},
+    schema::PrDescription,
+    structured, Error, Result,
+};
+
+pub struct PrDescriptionGenerator {
+    agent: Arc<dyn AIAgent>,
+    prompts: PromptRegistry,
+    /// 응답이 스키마/검증을 통과하지 못할 때 재요청을 포함한 최대 시도 횟수
+    max_attempts: u32,
+}
+
+impl PrDescriptionGenerator {
+    pub fn new(agent: Arc<dyn AIAgent>) -> Self {
+        Self {
+            agent,
+            prompts: prompts::global().clone(),
+            max_attempts: structured::DEFAULT_ATTEMPTS,
+        }
+    }
+
+    /// 프롬프트 템플릿 지정 (기본: AUTODEV_PROMPTS_DIR)
+    pub fn with_prompts(mut self, prompts: PromptRegistry) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// 응답의 최대 시도 횟수 지정 (기본 3회)
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// `chunks`로 나뉜 diff를 설명
+    ///
+    /// `chunks`는 `get_pr_diff_chunks` 등으로 나눈 diff이며, 비어 있으면 실패한다.
+    pub async fn describe(&self, title: &str, description: &str, chunks: &[String]) -> Result<PrDescription> {
+        if chunks.iter().all(|chunk| chunk.trim().is_empty()) {
+            return Err(Error::ValidationError("Cannot describe an empty diff".to_string()));
+        }
+
+        let system_prompt = self.prompts.render("pr_description_system.txt", &PromptVars::new())?;
+        let mut merged = PrDescription::default();
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            if chunks.len() > 1 {
+                tracing::info!("Describing diff part {}/{}", i + 1, chunks.len());
+            }
+
+            let part = if chunks.len() > 1 {
+                format!("전체 diff 중 {}/{} 부분", i + 1, chunks.len())
+            } else {
+                "전체 diff".to_string()
+            };
+            let vars = PromptVars::new()
+                .set("title", title)
+                .set("description", description)
+                .set("part", part)
+                .set("diff", chunk.as_str());
+            let user_prompt = self.prompts.render("pr_description.txt", &vars)?;
+
+            let description: PrDescription = structured::chat_structured(
+                self.agent.as_ref(),
+                &system_prompt,
+                &user_prompt,
+                &PrDescription::json_schema(),
+                self.max_attempts,
+                PrDescription::validate,
+            )
+            .await?;
+
+            merged = merged.merge(description);
+        }
+
+        Ok(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OllamaAgent;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_describe_chunks() {
+        let reply = |content: serde_json::Value| json!({ "message": { "content": content.to_string() } }).to_string();
+
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::Regex("1/2".to_string()))
+            .with_status(200)
+            .with_body(reply(json!({
+                "summary": "토큰 갱신 추가",
+                "highlights": ["`src/auth.rs`: 갱신 로직"],
+                "testing_notes": [],
+                "risks": ["마이그레이션 필요"]
+            })))
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::Regex("2/2".to_string()))
+            .with_status(200)
+            .with_body(reply(json!({
+                "summary": "갱신 테스트 추가",
+                "highlights": [],
+                "testing_notes": ["갱신 단위 테스트"],
+                "risks": ["마이그레이션 필요"]
+            })))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let generator = PrDescriptionGenerator::new(Arc::new(OllamaAgent::new(server.url(), "llama3.1")));
+        let chunks = vec!["diff --git a/src/auth.rs".to_string(), "diff --git a/tests/auth.rs".to_string()];
+        let description = generator.describe("토큰 갱신", "", &chunks).await.unwrap();
+        first.assert_async().await;
+        second.assert_async().await;
+
+        assert_eq!(description.summary, "토큰 갱신 추가\n\n갱신 테스트 추가");
+        assert_eq!(description.risks, vec!["마이그레이션 필요".to_string()]);
+
+        let body = description.to_markdown();
+        assert!(body.contains("## Testing\n- 갱신 단위 테스트"));
+
+        assert!(generator.describe("토큰 갱신", "", &[]).await.is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Prompt templates
+//!
+//! Every prompt is a template file named after its purpose (e.g.
+//! `task_execution.txt`). Files in AUTODEV_PROMPTS_DIR override the defaults
+//! embedded from `prompts/`; templates missing there fall back to the
+//! defaults. Templates are read on every use, so edits apply without a restart.
+//!
+//! `{{name}}` is replaced with the variable `name` (e.g. `{{task.title}}`,
+//! `{{repo_context}}`); an unknown variable is an error.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use autodev_core::Task;
+
+use crate::{Error, Result};
+
+/// Directory with prompt overrides
+pub const PROMPTS_DIR_ENV: &str = "AUTODEV_PROMPTS_DIR";
+
+const DEFAULTS: &[(&str, &str)] = &[
+    ("task_execution_system.txt", include_str!("../prompts/task_execution_system.txt")),
+    ("task_execution.txt", include_str!("../prompts/task_execution.txt")),
+    ("code_review_system.txt", include_str!("../prompts/code_review_system.txt")),
+    ("code_review.txt", include_str!("../prompts/code_review.txt")),
+    ("merge_review_system.txt", include_str!("../prompts/merge_review_system.txt")),
+    ("merge_review.txt", include_str!("../prompts/merge_review.txt")),
+    ("ci_fix_system.txt", include_str!("../prompts/ci_fix_system.txt")),
+    ("ci_fix.txt", include_str!("../prompts/ci_fix.txt")),
+    ("commit_message_system.txt", include_str!("../prompts/commit_message_system.txt")),
+    ("commit_message.txt", include_str!("../prompts/commit_message.txt")),
+    ("security_analysis_system.txt", include_str!("../prompts/security_analysis_system.txt")),
+    ("security_analysis.txt", include_str!("../prompts/security_analysis.txt")),
+    ("task_decomposition_system.txt", include_str!("../prompts/task_decomposition_system.txt")),
+    ("task_decomposition.txt", include_str!("../prompts/task_decomposition.txt")),
+    ("plan_review_system.txt", include_str!("../prompts/plan_review_system.txt")),
+    ("plan_review.txt", include_str!("../prompts/plan_review.txt")),
+    ("pr_description_system.txt", include_str!("../prompts/pr_description_system.txt")),
+    ("pr_description.txt", include_str!("../prompts/pr_description.txt")),
+    ("example_selection_system.txt", include_str!("../prompts/example_selection_system.txt")),
+    ("example_selection.txt", include_str!("../prompts/example_selection.txt")),
+    ("domain_detection_system.txt", include_str!("../prompts/domain_detection_system.txt")),
+    ("domain_detection.txt", include_str!("../prompts/domain_detection.txt")),
+    ("few_shot_examples.json", include_str!("../prompts/few_shot_examples.json")),
+];
+
+/// Variables available to a template
+#[derive(Debug, Clone, Default)]
+pub struct PromptVars(HashMap<String, String>);
+
+impl PromptVars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.0.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// `task.id`, `task.title`, `task.description` and `task.prompt`
+    pub fn with_task(self, task: &Task) -> Self {
+        self.set("task.id", task.id.as_str())
+            .set("task.title", task.title.as_str())
+            .set("task.description", task.description.as_str())
+            .set("task.prompt", task.prompt.as_str())
+    }
+}
+
+/// Prompt templates: overrides from a directory, embedded defaults otherwise
+#[derive(Debug, Clone, Default)]
+pub struct PromptRegistry {
+    dir: Option<PathBuf>,
+}
+
+impl PromptRegistry {
+    /// Registry reading overrides from `dir` (None: embedded defaults only)
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+
+    /// Registry configured by AUTODEV_PROMPTS_DIR
+    pub fn from_env() -> Self {
+        let dir = std::env::var(PROMPTS_DIR_ENV)
+            .ok()
+            .filter(|dir| !dir.trim().is_empty())
+            .map(PathBuf::from);
+        Self::new(dir)
+    }
+
+    /// Embedded default of template `name`
+    pub fn default_template(name: &str) -> Option<&'static str> {
+        DEFAULTS.iter().find(|(n, _)| *n == name).map(|(_, template)| *template)
+    }
+
+    /// Raw template `name`, from the override directory when it has one
+    pub fn template(&self, name: &str) -> Result<String> {
+        let default = Self::default_template(name)
+            .ok_or_else(|| Error::ConfigError(format!("Unknown prompt template: {}", name)))?;
+
+        if let Some(ref dir) = self.dir {
+            let path = dir.join(name);
+            match std::fs::read_to_string(&path) {
+                Ok(template) => return Ok(template),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(Error::ConfigError(format!(
+                        "Failed to read prompt template {}: {}",
+                        path.display(),
+                        e
+                    )))
+                }
+            }
+        }
+
+        Ok(default.to_string())
+    }
+
+    /// Template `name` with its variables filled in
+    pub fn render(&self, name: &str, vars: &PromptVars) -> Result<String> {
+        interpolate(&self.template(name)?, vars)
+            .map_err(|variable| Error::ConfigError(format!("Unknown variable {{{{{}}}}} in prompt template {}", variable, name)))
+    }
+}
+
+/// Process-wide registry configured from AUTODEV_PROMPTS_DIR
+pub fn global() -> &'static PromptRegistry {
+    static GLOBAL: OnceLock<PromptRegistry> = OnceLock::new();
+    GLOBAL.get_or_init(PromptRegistry::from_env)
+}
+
+/// Replace `{{name}}` placeholders, failing with the first unknown name
+fn interpolate(template: &str, vars: &PromptVars) -> std::result::Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+
+        let name = rest[start + 2..start + 2 + end].trim();
+        let value = vars.0.get(name).ok_or_else(|| name.to_string())?;
+
+        output.push_str(&rest[..start]);
+        output.push_str(value);
+        rest = &rest[start + 2 + end + 2..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        let vars = PromptVars::new().set("task.title", "Add login").set("repo_context", "myorg/app");
+
+        assert_eq!(
+            interpolate("{{task.title}} in {{ repo_context }}", &vars).unwrap(),
+            "Add login in myorg/app"
+        );
+        // Values are not interpolated again
+        let vars = vars.set("task.title", "{{repo_context}}");
+        assert_eq!(interpolate("{{task.title}}", &vars).unwrap(), "{{repo_context}}");
+        assert_eq!(interpolate("{{missing}}", &vars), Err("missing".to_string()));
+        assert_eq!(interpolate("no {{ end", &vars).unwrap(), "no {{ end");
+    }
+
+    #[test]
+    fn test_override_directory() {
+        let dir = std::env::temp_dir().join(format!("autodev-prompts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ci_fix.txt"), "Fix this:\n{{ci_logs}}").unwrap();
+
+        let registry = PromptRegistry::new(Some(dir.clone()));
+        let vars = PromptVars::new().set("system_prompt", "").set("ci_logs", "error[E0308]");
+
+        assert_eq!(registry.render("ci_fix.txt", &vars).unwrap(), "Fix this:\nerror[E0308]");
+        // Templates without an override use the default
+        assert_eq!(
+            registry.template("ci_fix_system.txt").unwrap(),
+            PromptRegistry::default_template("ci_fix_system.txt").unwrap()
+        );
+        assert!(registry.template("unknown.txt").is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,349 @@
+//! Retries and model fallback for AI calls
+//!
+//! Transient provider errors (429, 529 overloaded, 5xx, timeouts) are retried
+//! with exponential backoff and jitter, waiting at least as long as the
+//! provider's Retry-After. When a model stays unavailable the call moves on to
+//! the next agent of the fallback chain (e.g. sonnet → haiku → gpt-4o-mini).
+
+use async_trait::async_trait;
+use autodev_core::Task;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::agent::{AIAgent, AgentResult, AgentType, Capability, ReviewResult, SecurityIssue};
+use crate::{Error, Result};
+
+pub const MAX_RETRIES_ENV: &str = "AUTODEV_AI_MAX_RETRIES";
+pub const RETRY_BASE_DELAY_MS_ENV: &str = "AUTODEV_AI_RETRY_BASE_DELAY_MS";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt (0: fail on the first error)
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    /// Upper bound on a single wait, Retry-After included
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Defaults overridden by AUTODEV_AI_MAX_RETRIES / AUTODEV_AI_RETRY_BASE_DELAY_MS
+    pub fn from_env() -> Self {
+        let env = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        let default = Self::default();
+
+        Self {
+            max_retries: env(MAX_RETRIES_ENV).map_or(default.max_retries, |n| n as u32),
+            base_delay: env(RETRY_BASE_DELAY_MS_ENV).map_or(default.base_delay, Duration::from_millis),
+            ..default
+        }
+    }
+
+    /// Wait before retry `attempt` (0-based): exponential with jitter, or the
+    /// provider's Retry-After when that is longer
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        // Jitter between half and the full delay so clients do not retry in lockstep
+        let half = exponential / 2;
+        let jittered = half + Duration::from_millis(fastrand::u64(0..=half.as_millis() as u64));
+
+        jittered.max(retry_after.unwrap_or_default()).min(self.max_delay)
+    }
+}
+
+/// Run `op` until it succeeds, fails permanently or runs out of retries
+pub async fn retry<T, F, Fut>(policy: &RetryPolicy, label: &str, mut op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Err(e) if e.is_transient() && attempt < policy.max_retries => {
+                let delay = policy.delay(attempt, e.retry_after());
+                tracing::warn!(
+                    "{} failed ({}), retrying in {:.1}s ({}/{})",
+                    label,
+                    e,
+                    delay.as_secs_f64(),
+                    attempt + 1,
+                    policy.max_retries
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Turn an error status into the matching error (transient ones carry Retry-After)
+pub async fn check_response(provider: &str, response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(Error::InvalidApiKey);
+    }
+
+    // 529: Anthropic "overloaded"
+    let transient = status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 529 || status.is_server_error();
+    let retry_after = retry_after(response.headers());
+    let message = response.text().await.unwrap_or_default();
+
+    if transient {
+        Err(Error::Unavailable {
+            status: status.as_u16(),
+            message: format!("{} API error: {}", provider, message),
+            retry_after,
+        })
+    } else {
+        Err(Error::ApiError(format!("{} API error: {}", provider, message)))
+    }
+}
+
+/// Retry-After (seconds or HTTP date), or OpenAI's retry-after-ms
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.parse::<f64>().ok()) {
+        return Some(Duration::from_secs_f64(ms.max(0.0) / 1000.0));
+    }
+
+    let value = header("retry-after")?;
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Some(Duration::from_secs_f64(seconds.max(0.0)));
+    }
+    chrono::DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|at| (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default())
+}
+
+/// Agent that retries transient failures and falls back along a chain of agents
+///
+/// Reports the type and model of the first agent in the chain.
+pub struct FallbackAgent {
+    chain: Vec<Arc<dyn AIAgent>>,
+    policy: RetryPolicy,
+}
+
+impl FallbackAgent {
+    /// `chain` must not be empty; the first agent is the primary one
+    pub fn new(chain: Vec<Arc<dyn AIAgent>>, policy: RetryPolicy) -> Self {
+        assert!(!chain.is_empty(), "FallbackAgent needs at least one agent");
+        Self { chain, policy }
+    }
+
+    async fn call<T, F, Fut>(&self, op: F) -> Result<T>
+    where
+        F: Fn(Arc<dyn AIAgent>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_error = None;
+
+        for (i, agent) in self.chain.iter().enumerate() {
+            if let Some(ref e) = last_error {
+                tracing::warn!("{} is unavailable ({}), falling back to {}", self.chain[i - 1].model(), e, agent.model());
+            }
+
+            match retry(&self.policy, agent.model(), || op(agent.clone())).await {
+                Err(e) if e.is_transient() => last_error = Some(e),
+                result => return result,
+            }
+        }
+
+        Err(last_error.expect("chain is not empty"))
+    }
+}
+
+#[async_trait]
+impl AIAgent for FallbackAgent {
+    fn agent_type(&self) -> AgentType {
+        self.chain[0].agent_type()
+    }
+
+    fn model(&self) -> &str {
+        self.chain[0].model()
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        self.chain[0].capabilities()
+    }
+
+    async fn execute_task(&self, task: &Task, repo_path: &str) -> Result<AgentResult> {
+        self.call(|agent| async move { agent.execute_task(task, repo_path).await }).await
+    }
+
+    async fn review_code_changes(&self, pr_diff: &str, review_comments: &[String]) -> Result<ReviewResult> {
+        self.call(|agent| async move { agent.review_code_changes(pr_diff, review_comments).await })
+            .await
+    }
+
+    async fn fix_ci_failures(&self, ci_logs: &str) -> Result<ReviewResult> {
+        self.call(|agent| async move { agent.fix_ci_failures(ci_logs).await }).await
+    }
+
+    async fn generate_commit_message(&self, changes: &str) -> Result<String> {
+        self.call(|agent| async move { agent.generate_commit_message(changes).await }).await
+    }
+
+    async fn analyze_security(&self, code: &str, language: &str) -> Result<Vec<SecurityIssue>> {
+        self.call(|agent| async move { agent.analyze_security(code, language).await }).await
+    }
+
+    async fn chat_json(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        self.call(|agent| async move { agent.chat_json(system_prompt, user_prompt).await }).await
+    }
+
+    async fn chat_structured(&self, system_prompt: &str, user_prompt: &str, schema: &serde_json::Value) -> Result<String> {
+        self.call(|agent| async move { agent.chat_structured(system_prompt, user_prompt, schema).await })
+            .await
+    }
+
+    async fn check_credentials(&self) -> Result<bool> {
+        crate::agent::check_all_credentials(&self.chain.iter().collect::<Vec<_>>()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OllamaAgent;
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        }
+    }
+
+    #[test]
+    fn test_delay() {
+        let policy = RetryPolicy::default();
+
+        for attempt in 0..8 {
+            let delay = policy.delay(attempt, None);
+            let full = (policy.base_delay * 2u32.pow(attempt)).min(policy.max_delay);
+            assert!(delay >= full / 2 && delay <= full, "attempt {}: {:?}", attempt, delay);
+        }
+
+        // Retry-After wins when longer, but not beyond max_delay
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(30))), Duration::from_secs(30));
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(600))), policy.max_delay);
+    }
+
+    #[test]
+    fn test_retry_after_header() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert("retry-after", "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert("retry-after-ms", "250".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(250)));
+    }
+
+    #[test]
+    fn test_error_class() {
+        use autodev_core::ErrorClass;
+
+        let overloaded = Error::Unavailable {
+            status: 529,
+            message: "Overloaded".to_string(),
+            retry_after: Some(Duration::from_secs(5)),
+        };
+        assert_eq!(overloaded.class(), ErrorClass::Transient);
+        assert_eq!(overloaded.retry_after(), Some(Duration::from_secs(5)));
+
+        let throttled = Error::Unavailable {
+            status: 429,
+            message: "Too many requests".to_string(),
+            retry_after: Some(Duration::from_secs(10)),
+        };
+        assert_eq!(
+            throttled.class(),
+            ErrorClass::RateLimited { retry_after: Some(Duration::from_secs(10)) }
+        );
+
+        assert_eq!(Error::InvalidApiKey.class(), ErrorClass::AuthError);
+        assert!(!Error::BudgetExceeded("monthly".to_string()).is_transient());
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_on_permanent_error() {
+        let mut calls = 0;
+        let result: Result<()> = retry(&fast_policy(), "test", || {
+            calls += 1;
+            async { Err(Error::InvalidApiKey) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(Error::InvalidApiKey)));
+        assert_eq!(calls, 1);
+
+        let mut calls = 0;
+        let result = retry(&fast_policy(), "test", || {
+            calls += 1;
+            let attempt = calls;
+            async move {
+                if attempt < 3 {
+                    Err(Error::RateLimitExceeded)
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_to_next_model() {
+        let mut server = mockito::Server::new_async().await;
+        let overloaded = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "model": "big" })))
+            .with_status(503)
+            .with_body("overloaded")
+            .expect(3)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "model": "small" })))
+            .with_status(200)
+            .with_body(serde_json::json!({ "message": { "content": "{}" } }).to_string())
+            .create_async()
+            .await;
+
+        let agent = FallbackAgent::new(
+            vec![
+                Arc::new(OllamaAgent::new(server.url(), "big")),
+                Arc::new(OllamaAgent::new(server.url(), "small")),
+            ],
+            fast_policy(),
+        );
+
+        assert_eq!(agent.chat_json("system", "user").await.unwrap(), "{}");
+        assert_eq!(agent.model(), "big");
+        overloaded.assert_async().await;
+    }
+}
@@ -0,0 +1,183 @@
+//! Routing AI operations to different agents
+//!
+//! [`AgentRouter`] sends each operation to the agent configured for it, e.g.
+//! commit messages to haiku, decomposition to sonnet and code edits of
+//! security tasks to opus. Tasks are classified by keywords
+//! ([`TaskDomain::from_keywords`]) for domain-specific routes. An agent that
+//! does not support an operation is never picked for it: the router moves on
+//! to the next candidate (the default agent, then the other routes).
+
+use async_trait::async_trait;
+use autodev_core::Task;
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::agent::{AIAgent, AgentResult, AgentType, Capability, ReviewResult, SecurityIssue};
+use crate::schema::TaskDomain;
+use crate::{Error, Result};
+
+pub struct AgentRouter {
+    default: Arc<dyn AIAgent>,
+    routes: Vec<Route>,
+}
+
+struct Route {
+    capability: Capability,
+    /// None: every domain
+    domain: Option<TaskDomain>,
+    agent: Arc<dyn AIAgent>,
+}
+
+impl AgentRouter {
+    /// Router sending everything to `default` until routes are added
+    pub fn new(default: Arc<dyn AIAgent>) -> Self {
+        Self {
+            default,
+            routes: Vec::new(),
+        }
+    }
+
+    /// Send `capability` (for tasks of `domain` only, when given) to `agent`
+    pub fn route(mut self, capability: Capability, domain: Option<TaskDomain>, agent: Arc<dyn AIAgent>) -> Self {
+        self.routes.push(Route {
+            capability,
+            domain,
+            agent,
+        });
+        self
+    }
+
+    /// Agent that handles `capability` for a task of `domain`
+    ///
+    /// Candidates in order: routes for the domain, routes for every domain,
+    /// the default agent, any other agent; the first one supporting the
+    /// operation wins.
+    pub fn agent_for(&self, capability: Capability, domain: Option<&TaskDomain>) -> Result<&Arc<dyn AIAgent>> {
+        let routed = |for_domain: bool| {
+            self.routes
+                .iter()
+                .filter(move |route| route.capability == capability)
+                .filter(move |route| match (&route.domain, domain) {
+                    (Some(route_domain), Some(domain)) => for_domain && route_domain == domain,
+                    (None, _) => !for_domain,
+                    (Some(_), None) => false,
+                })
+                .map(|route| &route.agent)
+        };
+
+        routed(true)
+            .chain(routed(false))
+            .chain(std::iter::once(&self.default))
+            .chain(self.routes.iter().map(|route| &route.agent))
+            .find(|agent| agent.supports(capability))
+            .ok_or_else(|| Error::ConfigError(format!("No AI agent supports the {} operation", capability)))
+    }
+}
+
+#[async_trait]
+impl AIAgent for AgentRouter {
+    fn agent_type(&self) -> AgentType {
+        self.default.agent_type()
+    }
+
+    fn model(&self) -> &str {
+        self.default.model()
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        Capability::ALL
+            .into_iter()
+            .filter(|capability| self.agent_for(*capability, None).is_ok())
+            .collect()
+    }
+
+    async fn execute_task(&self, task: &Task, repo_path: &str) -> Result<AgentResult> {
+        let domain = TaskDomain::from_keywords(&format!("{}\n{}\n{}", task.title, task.description, task.prompt));
+        let agent = self.agent_for(Capability::ExecuteTask, Some(&domain))?;
+        tracing::debug!("Executing task {} ({:?}) with {}", task.id, domain, agent.model());
+
+        agent.execute_task(task, repo_path).await
+    }
+
+    async fn review_code_changes(&self, pr_diff: &str, review_comments: &[String]) -> Result<ReviewResult> {
+        self.agent_for(Capability::ReviewCode, None)?
+            .review_code_changes(pr_diff, review_comments)
+            .await
+    }
+
+    async fn fix_ci_failures(&self, ci_logs: &str) -> Result<ReviewResult> {
+        self.agent_for(Capability::FixCi, None)?.fix_ci_failures(ci_logs).await
+    }
+
+    async fn generate_commit_message(&self, changes: &str) -> Result<String> {
+        self.agent_for(Capability::CommitMessage, None)?
+            .generate_commit_message(changes)
+            .await
+    }
+
+    async fn analyze_security(&self, code: &str, language: &str) -> Result<Vec<SecurityIssue>> {
+        self.agent_for(Capability::SecurityAnalysis, None)?
+            .analyze_security(code, language)
+            .await
+    }
+
+    async fn chat_json(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        self.agent_for(Capability::Chat, None)?.chat_json(system_prompt, user_prompt).await
+    }
+
+    async fn chat_structured(&self, system_prompt: &str, user_prompt: &str, schema: &Value) -> Result<String> {
+        self.agent_for(Capability::Chat, None)?
+            .chat_structured(system_prompt, user_prompt, schema)
+            .await
+    }
+
+    async fn check_credentials(&self) -> Result<bool> {
+        let agents: Vec<&Arc<dyn AIAgent>> = std::iter::once(&self.default)
+            .chain(self.routes.iter().map(|route| &route.agent))
+            .collect();
+        crate::agent::check_all_credentials(&agents).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DockerAIExecutor, OllamaAgent};
+
+    fn ollama(model: &str) -> Arc<dyn AIAgent> {
+        Arc::new(OllamaAgent::new("http://localhost:11434", model))
+    }
+
+    #[test]
+    fn test_agent_for() {
+        let router = AgentRouter::new(ollama("default"))
+            .route(Capability::CommitMessage, None, ollama("small"))
+            .route(Capability::ExecuteTask, Some(TaskDomain::Security), ollama("big"));
+
+        let model = |capability, domain: Option<TaskDomain>| {
+            router.agent_for(capability, domain.as_ref()).unwrap().model().to_string()
+        };
+
+        assert_eq!(model(Capability::CommitMessage, None), "small");
+        assert_eq!(model(Capability::ExecuteTask, Some(TaskDomain::Security)), "big");
+        assert_eq!(model(Capability::ExecuteTask, Some(TaskDomain::Feature)), "default");
+        assert_eq!(model(Capability::Chat, None), "default");
+    }
+
+    #[test]
+    fn test_skips_agents_without_capability() {
+        // Docker 연결은 첫 호출 때 이루어지므로 데몬 없이도 생성 가능
+        let docker: Arc<dyn AIAgent> = Arc::new(DockerAIExecutor::new("token".to_string()).unwrap().with_pool(None));
+
+        // Code edits routed to an agent that cannot make them go to the default
+        let router = AgentRouter::new(ollama("default")).route(Capability::ExecuteTask, None, docker.clone());
+        assert_eq!(router.agent_for(Capability::ExecuteTask, None).unwrap().model(), "default");
+
+        let docker_only = AgentRouter::new(docker);
+        assert_eq!(docker_only.capabilities(), vec![Capability::Chat]);
+        assert!(matches!(
+            docker_only.agent_for(Capability::ExecuteTask, None),
+            Err(Error::ConfigError(_))
+        ));
+    }
+}
@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::collections::{HashMap, HashSet};
 
+use autodev_core::ReviewSeverity;
+
+use crate::agent::SecurityIssue;
+
 /// 작업 도메인 타입
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "PascalCase")]
@@ -23,6 +28,51 @@ pub enum TaskDomain {
     Generic,
 }
 
+impl TaskDomain {
+    /// 키워드 기반 도메인 분류 (한글/영어)
+    pub fn from_keywords(text: &str) -> TaskDomain {
+        let lower = text.to_lowercase();
+
+        // 한글 + 영어 키워드 매칭
+        if lower.contains("translate") || lower.contains("translation")
+            || lower.contains("번역") || lower.contains("다국어") {
+            TaskDomain::Translation
+        } else if lower.contains("security") || lower.contains("audit")
+            || lower.contains("vulnerability") || lower.contains("보안")
+            || lower.contains("취약점") || lower.contains("인증") {
+            TaskDomain::Security
+        } else if lower.contains("refactor") || lower.contains("refactoring")
+            || lower.contains("리팩토링") || lower.contains("재구성") {
+            TaskDomain::Refactoring
+        } else if lower.contains("test") || lower.contains("testing")
+            || lower.contains("coverage") || lower.contains("테스트")
+            || lower.contains("커버리지") {
+            TaskDomain::Testing
+        } else if lower.contains("document") || lower.contains("documentation")
+            || lower.contains("readme") || lower.contains("문서") {
+            TaskDomain::Documentation
+        } else if lower.contains("fix") || lower.contains("bug")
+            || lower.contains("crash") || lower.contains("leak")
+            || lower.contains("수정") || lower.contains("버그") {
+            TaskDomain::Bugfix
+        } else if lower.contains("feature") || lower.contains("implement")
+            || lower.contains("add") || lower.contains("기능")
+            || lower.contains("추가") || lower.contains("구현") {
+            TaskDomain::Feature
+        } else {
+            TaskDomain::Generic
+        }
+    }
+}
+
+impl std::str::FromStr for TaskDomain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_string())).map_err(|_| format!("Unknown domain: {}", s))
+    }
+}
+
 /// AI 도메인 감지 응답
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DomainDetectionResponse {
@@ -109,6 +159,62 @@ pub struct TaskSchema {
 }
 
 impl TaskDecompositionResponse {
+    /// 응답 형식을 API 수준에서 강제하기 위한 JSON Schema
+    ///
+    /// OpenAI strict 모드 제약에 맞춰 모든 필드를 required로,
+    /// 추가 필드를 금지합니다.
+    pub fn json_schema() -> serde_json::Value {
+        let string_list = json!({ "type": "array", "items": { "type": "string" } });
+        let domains = [
+            TaskDomain::Translation,
+            TaskDomain::Security,
+            TaskDomain::Refactoring,
+            TaskDomain::Testing,
+            TaskDomain::Documentation,
+            TaskDomain::Feature,
+            TaskDomain::Bugfix,
+            TaskDomain::Generic,
+        ];
+
+        json!({
+            "type": "object",
+            "properties": {
+                "analysis": { "type": "string", "description": "작업 분석 요약" },
+                "domain": { "type": "string", "enum": domains },
+                "estimated_complexity": { "type": "string", "enum": ["low", "medium", "high"] },
+                "tasks": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "string", "description": "작업 ID (예: task_1)" },
+                            "title": { "type": "string", "description": "동사로 시작하는 작업 제목" },
+                            "description": { "type": "string" },
+                            "dependencies": string_list,
+                            "estimated_duration_minutes": { "type": "integer" },
+                            "tags": string_list,
+                        },
+                        "required": ["id", "title", "description", "dependencies", "estimated_duration_minutes", "tags"],
+                        "additionalProperties": false,
+                    },
+                },
+                "parallel_batches": { "type": "array", "items": string_list },
+                "critical_path": string_list,
+                "total_estimated_minutes": { "type": "integer" },
+            },
+            "required": [
+                "analysis",
+                "domain",
+                "estimated_complexity",
+                "tasks",
+                "parallel_batches",
+                "critical_path",
+                "total_estimated_minutes",
+            ],
+            "additionalProperties": false,
+        })
+    }
+
     /// 순환 의존성 검증
     pub fn validate_no_circular_dependencies(&self) -> Result<(), String> {
         use std::collections::{HashSet, HashMap};
@@ -185,6 +291,362 @@ impl TaskDecompositionResponse {
     }
 }
 
+/// 분해 계획 검토에서 지적한 문제 유형
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanFindingKind {
+    /// 요청을 완료하는 데 빠진 단계
+    MissingStep,
+    /// 같은 파일/모듈을 건드려 충돌할 수 있는 병렬 작업
+    RiskyParallelism,
+    /// 하나로 합쳐도 되는 지나치게 잘게 쪼갠 작업
+    OverGranular,
+    Other,
+}
+
+/// 분해 계획에 대한 개별 지적
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlanFinding {
+    pub kind: PlanFindingKind,
+
+    /// 관련된 작업 ID (계획 전체에 대한 지적이면 비어 있음)
+    #[serde(default)]
+    pub task_ids: Vec<String>,
+
+    pub message: String,
+}
+
+/// AI가 반환하는 분해 계획 검토 결과
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlanReview {
+    /// 검토 요약
+    pub summary: String,
+
+    /// 계획을 그대로 실행해도 된다는 신뢰도 (0.0 ~ 1.0)
+    pub confidence: f64,
+
+    /// 실행 전에 고쳐야 할 문제
+    #[serde(default)]
+    pub warnings: Vec<PlanFinding>,
+
+    /// 선택적인 개선 제안
+    #[serde(default)]
+    pub suggestions: Vec<PlanFinding>,
+}
+
+impl PlanReview {
+    /// 응답 형식을 API 수준에서 강제하기 위한 JSON Schema
+    pub fn json_schema() -> serde_json::Value {
+        let finding = json!({
+            "type": "object",
+            "properties": {
+                "kind": {
+                    "type": "string",
+                    "enum": ["missing_step", "risky_parallelism", "over_granular", "other"],
+                },
+                "task_ids": { "type": "array", "items": { "type": "string" } },
+                "message": { "type": "string" },
+            },
+            "required": ["kind", "task_ids", "message"],
+            "additionalProperties": false,
+        });
+
+        json!({
+            "type": "object",
+            "properties": {
+                "summary": { "type": "string", "description": "검토 요약" },
+                "confidence": { "type": "number", "description": "0.0 ~ 1.0" },
+                "warnings": { "type": "array", "items": finding },
+                "suggestions": { "type": "array", "items": finding },
+            },
+            "required": ["summary", "confidence", "warnings", "suggestions"],
+            "additionalProperties": false,
+        })
+    }
+
+    /// 신뢰도 범위와 지적한 작업 ID가 `plan`에 있는지 검증
+    pub fn validate(&self, plan: &TaskDecompositionResponse) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.confidence) {
+            return Err(format!("confidence must be between 0.0 and 1.0, got {}", self.confidence));
+        }
+
+        let ids: HashSet<&str> = plan.tasks.iter().map(|t| t.id.as_str()).collect();
+        for finding in self.warnings.iter().chain(&self.suggestions) {
+            if let Some(unknown) = finding.task_ids.iter().find(|id| !ids.contains(id.as_str())) {
+                return Err(format!("finding refers to unknown task '{}'", unknown));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 신뢰도가 `min_confidence` 이상인지
+    pub fn is_confident(&self, min_confidence: f64) -> bool {
+        self.confidence >= min_confidence
+    }
+
+    /// 계획의 작업 ID("task_1" 등)를 변환된 Task의 ID로 교체
+    ///
+    /// `tasks`는 `TaskDecomposer::convert_to_tasks`가 `plan.tasks`와 같은 순서로 만든 작업이다.
+    pub fn with_task_ids(mut self, plan: &TaskDecompositionResponse, tasks: &[autodev_core::Task]) -> Self {
+        let ids: HashMap<&str, &str> = plan
+            .tasks
+            .iter()
+            .zip(tasks)
+            .map(|(schema, task)| (schema.id.as_str(), task.id.as_str()))
+            .collect();
+
+        for finding in self.warnings.iter_mut().chain(&mut self.suggestions) {
+            for id in &mut finding.task_ids {
+                if let Some(task_id) = ids.get(id.as_str()) {
+                    *id = task_id.to_string();
+                }
+            }
+        }
+
+        self
+    }
+}
+
+/// AI가 실제 diff로 작성한 PR 설명
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct PrDescription {
+    /// 변경 전체 요약
+    pub summary: String,
+
+    /// 리뷰어가 알아야 할 주요 변경
+    #[serde(default)]
+    pub highlights: Vec<String>,
+
+    /// 추가/수정된 테스트와 수동 확인 항목
+    #[serde(default)]
+    pub testing_notes: Vec<String>,
+
+    /// 리뷰 시 특히 확인해야 할 위험 요소
+    #[serde(default)]
+    pub risks: Vec<String>,
+}
+
+impl PrDescription {
+    /// 응답 형식을 API 수준에서 강제하기 위한 JSON Schema
+    pub fn json_schema() -> serde_json::Value {
+        let list = json!({ "type": "array", "items": { "type": "string" } });
+
+        json!({
+            "type": "object",
+            "properties": {
+                "summary": { "type": "string", "description": "변경 전체 요약" },
+                "highlights": list,
+                "testing_notes": list,
+                "risks": list,
+            },
+            "required": ["summary", "highlights", "testing_notes", "risks"],
+            "additionalProperties": false,
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.summary.trim().is_empty() {
+            return Err("summary must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// 나눠서 작성한 diff 부분의 설명을 이어 붙임 (중복 항목은 제외)
+    pub fn merge(mut self, other: PrDescription) -> Self {
+        if self.summary.is_empty() {
+            self.summary = other.summary;
+        } else if !other.summary.is_empty() {
+            self.summary = format!("{}\n\n{}", self.summary, other.summary);
+        }
+
+        for (items, more) in [
+            (&mut self.highlights, other.highlights),
+            (&mut self.testing_notes, other.testing_notes),
+            (&mut self.risks, other.risks),
+        ] {
+            for item in more {
+                if !items.contains(&item) {
+                    items.push(item);
+                }
+            }
+        }
+
+        self
+    }
+
+    /// PR 본문 Markdown (비어 있는 섹션은 생략)
+    pub fn to_markdown(&self) -> String {
+        let mut body = format!("## Summary\n{}\n", self.summary.trim());
+
+        for (heading, items) in [
+            ("Highlights", &self.highlights),
+            ("Testing", &self.testing_notes),
+            ("⚠️ Risks", &self.risks),
+        ] {
+            if items.is_empty() {
+                continue;
+            }
+            body.push_str(&format!("\n## {}\n", heading));
+            for item in items {
+                body.push_str(&format!("- {}\n", item));
+            }
+        }
+
+        body
+    }
+}
+
+/// 병합 전 코드 리뷰의 개별 지적
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReviewFinding {
+    pub severity: ReviewSeverity,
+
+    #[serde(default)]
+    pub file: Option<String>,
+
+    #[serde(default)]
+    pub line: Option<u32>,
+
+    pub message: String,
+}
+
+/// AI가 반환하는 병합 전 코드 리뷰 결과
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct CodeReview {
+    pub summary: String,
+
+    #[serde(default)]
+    pub findings: Vec<ReviewFinding>,
+}
+
+impl CodeReview {
+    /// 응답 형식을 API 수준에서 강제하기 위한 JSON Schema
+    pub fn json_schema() -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "summary": { "type": "string", "description": "검토 요약" },
+                "findings": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "severity": {
+                                "type": "string",
+                                "enum": ["info", "low", "medium", "high", "critical"],
+                            },
+                            "file": { "type": ["string", "null"] },
+                            "line": { "type": ["integer", "null"] },
+                            "message": { "type": "string" },
+                        },
+                        "required": ["severity", "file", "line", "message"],
+                        "additionalProperties": false,
+                    },
+                },
+            },
+            "required": ["summary", "findings"],
+            "additionalProperties": false,
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.summary.trim().is_empty() {
+            return Err("summary must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// 가장 심각한 지적의 심각도 (지적이 없으면 None)
+    pub fn max_severity(&self) -> Option<ReviewSeverity> {
+        self.findings.iter().map(|f| f.severity).max()
+    }
+
+    /// `threshold` 이상인 지적
+    pub fn blocking(&self, threshold: ReviewSeverity) -> Vec<&ReviewFinding> {
+        self.findings.iter().filter(|f| f.severity >= threshold).collect()
+    }
+
+    /// 나눠서 검토한 diff 부분의 결과를 이어 붙임
+    pub fn merge(mut self, other: CodeReview) -> Self {
+        if self.summary.is_empty() {
+            self.summary = other.summary;
+        } else if !other.summary.is_empty() {
+            self.summary = format!("{}\n\n{}", self.summary, other.summary);
+        }
+        self.findings.extend(other.findings);
+        self
+    }
+
+    /// PR 리뷰 본문 Markdown (심각한 지적부터)
+    pub fn to_markdown(&self) -> String {
+        let mut body = format!("## AutoDev Review\n\n{}\n", self.summary.trim());
+
+        let mut findings: Vec<&ReviewFinding> = self.findings.iter().collect();
+        findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+
+        if !findings.is_empty() {
+            body.push_str("\n### Findings\n");
+        }
+        for finding in findings {
+            let location = match (&finding.file, finding.line) {
+                (Some(file), Some(line)) => format!(" `{}:{}`", file, line),
+                (Some(file), None) => format!(" `{}`", file),
+                _ => String::new(),
+            };
+            body.push_str(&format!("- **{}**{} {}\n", finding.severity, location, finding.message));
+        }
+
+        body
+    }
+}
+
+/// AI가 반환하는 보안 분석 결과
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SecurityReport {
+    #[serde(default)]
+    pub issues: Vec<SecurityIssue>,
+}
+
+impl SecurityReport {
+    /// 응답 형식을 API 수준에서 강제하기 위한 JSON Schema
+    pub fn json_schema() -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "issues": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "severity": {
+                                "type": "string",
+                                "enum": ["Critical", "High", "Medium", "Low", "Info"],
+                            },
+                            "title": { "type": "string" },
+                            "description": { "type": "string" },
+                            "file": { "type": ["string", "null"] },
+                            "line": { "type": ["integer", "null"] },
+                            "recommendation": { "type": "string" },
+                        },
+                        "required": ["severity", "title", "description", "file", "line", "recommendation"],
+                        "additionalProperties": false,
+                    },
+                },
+            },
+            "required": ["issues"],
+            "additionalProperties": false,
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(i) = self.issues.iter().position(|issue| issue.title.trim().is_empty()) {
+            return Err(format!("issues[{}].title must not be empty", i));
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -253,6 +715,35 @@ mod tests {
         assert!(response.validate().is_err());
     }
 
+    #[test]
+    fn test_json_schema_matches_response() {
+        let schema = TaskDecompositionResponse::json_schema();
+        assert_eq!(schema["properties"]["domain"]["enum"][0], "Translation");
+
+        // 스키마의 required 필드만으로 응답을 파싱할 수 있어야 함
+        let required = schema["required"].as_array().unwrap();
+        assert_eq!(required.len(), schema["properties"].as_object().unwrap().len());
+
+        let response: TaskDecompositionResponse = serde_json::from_value(json!({
+            "analysis": "a",
+            "domain": "Feature",
+            "estimated_complexity": "medium",
+            "tasks": [{
+                "id": "task_1",
+                "title": "Add login",
+                "description": "d",
+                "dependencies": [],
+                "estimated_duration_minutes": 30,
+                "tags": ["auth"]
+            }],
+            "parallel_batches": [["task_1"]],
+            "critical_path": ["task_1"],
+            "total_estimated_minutes": 30
+        }))
+        .unwrap();
+        assert_eq!(response.tasks.len(), 1);
+    }
+
     #[test]
     fn test_missing_dependency_detected() {
         let response = TaskDecompositionResponse {
@@ -0,0 +1,136 @@
+//! Streaming AI output
+//!
+//! Work wrapped in [`with_stream`] gets the text of AI responses as it is
+//! generated: agents that can stream (Claude, OpenAI) switch to SSE and pass
+//! every text delta to the sink. Agents that cannot stream still return the
+//! full response as usual.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Receives text deltas as they arrive
+pub type StreamSink = Arc<dyn Fn(&str) + Send + Sync>;
+
+tokio::task_local! {
+    static CURRENT_SINK: StreamSink;
+}
+
+/// Run `work`, passing the text AI calls inside it generate to `sink`
+///
+/// Like usage tracking, only calls on the same tokio task are streamed.
+pub async fn with_stream<F: Future>(sink: StreamSink, work: F) -> F::Output {
+    CURRENT_SINK.scope(sink, work).await
+}
+
+/// Whether the current AI call should stream (called by the agents)
+pub fn is_streaming() -> bool {
+    CURRENT_SINK.try_with(|_| ()).is_ok()
+}
+
+/// Pass a text delta to the current sink (called by the agents)
+pub fn emit(text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    let _ = CURRENT_SINK.try_with(|sink| sink(text));
+}
+
+/// Run `work` streaming to `sink`; when nothing was streamed (the agent cannot
+/// stream, or the response came from a cache) the whole output is sent at once
+pub async fn stream_or_emit<F>(sink: StreamSink, work: F) -> crate::Result<String>
+where
+    F: Future<Output = crate::Result<String>>,
+{
+    let streamed = Arc::new(AtomicBool::new(false));
+    let tracking: StreamSink = {
+        let streamed = streamed.clone();
+        let sink = sink.clone();
+        Arc::new(move |text: &str| {
+            streamed.store(true, Ordering::Relaxed);
+            sink(text)
+        })
+    };
+
+    let output = with_stream(tracking, work).await?;
+    if !streamed.load(Ordering::Relaxed) {
+        sink(&output);
+    }
+    Ok(output)
+}
+
+/// Splits a `text/event-stream` body into the `data:` payloads of its events
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: String,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a chunk of the body, returning the payloads of completed events
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.push_str(&String::from_utf8_lossy(chunk).replace("\r\n", "\n"));
+
+        let mut payloads = Vec::new();
+        while let Some(end) = self.buffer.find("\n\n") {
+            let event: String = self.buffer.drain(..end + 2).collect();
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect();
+
+            if !data.is_empty() {
+                payloads.push(data.join("\n"));
+            }
+        }
+        payloads
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_sse_parser() {
+        let mut parser = SseParser::new();
+
+        assert!(parser.push(b"event: content_block_delta\ndata: {\"a\"").is_empty());
+        assert_eq!(parser.push(b": 1}\n\ndata: [DONE]\n\n"), vec!["{\"a\": 1}", "[DONE]"]);
+        // Events without data (comments, pings) are skipped
+        assert!(parser.push(b": ping\r\n\r\n").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_or_emit() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink: StreamSink = {
+            let received = received.clone();
+            Arc::new(move |text: &str| received.lock().unwrap().push(text.to_string()))
+        };
+
+        // Streamed deltas are passed through as they come
+        let output = stream_or_emit(sink.clone(), async {
+            assert!(is_streaming());
+            emit("Hel");
+            emit("lo");
+            Ok("Hello".to_string())
+        })
+        .await
+        .unwrap();
+        assert_eq!(output, "Hello");
+        assert_eq!(*received.lock().unwrap(), vec!["Hel", "lo"]);
+
+        // A response that was not streamed is sent whole
+        received.lock().unwrap().clear();
+        stream_or_emit(sink, async { Ok("cached".to_string()) }).await.unwrap();
+        assert_eq!(*received.lock().unwrap(), vec!["cached"]);
+
+        assert!(!is_streaming());
+    }
+}
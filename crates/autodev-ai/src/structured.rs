@@ -0,0 +1,163 @@
+//! Structured output
+//!
+//! [`chat_structured`] asks the agent for a response following a JSON Schema
+//! (tool use on Claude, `json_schema` response format on OpenAI), parses and
+//! validates it, and re-prompts with the error when the response is rejected.
+//! Pulling a JSON object out of surrounding text is only the last resort.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::agent::AIAgent;
+use crate::{Error, Result};
+
+/// Attempts before a structured call gives up (the first one included)
+pub const DEFAULT_ATTEMPTS: u32 = 3;
+
+/// Longest previous response quoted back to the model when re-prompting
+const MAX_QUOTED_RESPONSE: usize = 4000;
+
+/// Ask `agent` for a `T` following `schema`, re-prompting up to `max_attempts`
+/// times while the response does not parse or `validate` rejects it
+///
+/// Errors of the agent itself (network, budget, ...) are returned as they are;
+/// running out of attempts is a [`Error::ValidationError`].
+pub async fn chat_structured<T, V>(
+    agent: &dyn AIAgent,
+    system_prompt: &str,
+    user_prompt: &str,
+    schema: &Value,
+    max_attempts: u32,
+    validate: V,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    V: Fn(&T) -> std::result::Result<(), String>,
+{
+    let max_attempts = max_attempts.max(1);
+    let mut prompt = user_prompt.to_string();
+    let mut last_error = String::new();
+
+    for attempt in 1..=max_attempts {
+        let response = agent.chat_structured(system_prompt, &prompt, schema).await?;
+
+        match parse::<T>(&response).and_then(|value| validate(&value).map(|_| value)) {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                tracing::warn!("Structured AI response rejected ({}/{}): {}", attempt, max_attempts, e);
+                agent.reject_response(system_prompt, &prompt, schema).await;
+                prompt = reprompt(user_prompt, &response, &e);
+                last_error = e;
+            }
+        }
+    }
+
+    Err(Error::ValidationError(format!(
+        "No valid response after {} attempts: {}",
+        max_attempts, last_error
+    )))
+}
+
+/// Parse `response`, falling back to the first JSON value inside it
+fn parse<T: DeserializeOwned>(response: &str) -> std::result::Result<T, String> {
+    serde_json::from_str(response).or_else(|e| {
+        extract_json(response)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .ok_or_else(|| format!("invalid JSON: {}", e))
+    })
+}
+
+fn reprompt(user_prompt: &str, response: &str, error: &str) -> String {
+    let quoted: String = response.chars().take(MAX_QUOTED_RESPONSE).collect();
+    format!(
+        "{}\n\n이전 응답이 요구한 형식에 맞지 않았습니다: {}\n\n이전 응답:\n{}\n\n\
+         문제를 고쳐 스키마에 맞는 JSON 객체 하나로만 다시 응답하세요.",
+        user_prompt, error, quoted
+    )
+}
+
+/// First JSON object/array in `text` (models often wrap JSON in prose or code fences)
+pub fn extract_json(text: &str) -> Option<String> {
+    let trimmed = text.trim();
+    if serde_json::from_str::<Value>(trimmed).is_ok() {
+        return Some(trimmed.to_string());
+    }
+
+    let start = trimmed.find(['{', '['])?;
+    let end = trimmed.rfind(['}', ']'])?;
+    if end <= start {
+        return None;
+    }
+
+    let candidate = &trimmed[start..=end];
+    serde_json::from_str::<Value>(candidate)
+        .ok()
+        .map(|_| candidate.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OllamaAgent;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize)]
+    struct Answer {
+        value: i32,
+    }
+
+    #[test]
+    fn test_extract_json() {
+        assert_eq!(
+            extract_json("Sure! Here it is:\n```json\n{\"a\": 1}\n```").as_deref(),
+            Some("{\"a\": 1}")
+        );
+        assert_eq!(extract_json("[1, 2]").as_deref(), Some("[1, 2]"));
+        assert!(extract_json("no json here").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reprompts_on_validation_failure() {
+        let reply = |content: &str| json!({ "message": { "content": content } }).to_string();
+
+        let mut server = mockito::Server::new_async().await;
+        let rejected = server
+            .mock("POST", "/api/chat")
+            .with_status(200)
+            .with_body(reply(r#"{"value": -1}"#))
+            .expect(1)
+            .create_async()
+            .await;
+        let corrected = server
+            .mock("POST", "/api/chat")
+            .match_body(mockito::Matcher::Regex("value must be positive".to_string()))
+            .with_status(200)
+            .with_body(reply(r#"{"value": 7}"#))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let agent = OllamaAgent::new(server.url(), "llama3.1");
+        let validate = |answer: &Answer| {
+            if answer.value > 0 {
+                Ok(())
+            } else {
+                Err("value must be positive".to_string())
+            }
+        };
+
+        let answer: Answer = chat_structured(&agent, "Answer", "Pick a number", &json!({}), 3, validate)
+            .await
+            .unwrap();
+
+        assert_eq!(answer.value, 7);
+        rejected.assert_async().await;
+        corrected.assert_async().await;
+
+        // Out of attempts
+        let result: Result<Answer> =
+            chat_structured(&agent, "Answer", "Pick a number", &json!({}), 1, |_: &Answer| Err("no".to_string())).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+}
@@ -0,0 +1,209 @@
+//! Token usage and cost of AI calls
+//!
+//! Agents report the usage of every API response with [`record`]. Wrapping
+//! work in [`track_usage`] collects what the calls inside it used, so callers
+//! can attribute the tokens and cost to the task they are running.
+//! [`track_usage_within`] also enforces budgets on those calls.
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use crate::budget::Budget;
+use crate::Result;
+
+/// USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+}
+
+/// Prices by model ID prefix; more specific prefixes come first
+const PRICES: &[(&str, ModelPrice)] = &[
+    ("claude-opus-4", ModelPrice { input_per_mtok: 15.0, output_per_mtok: 75.0 }),
+    ("claude-sonnet-4", ModelPrice { input_per_mtok: 3.0, output_per_mtok: 15.0 }),
+    ("claude-haiku-4", ModelPrice { input_per_mtok: 1.0, output_per_mtok: 5.0 }),
+    ("claude-3-opus", ModelPrice { input_per_mtok: 15.0, output_per_mtok: 75.0 }),
+    ("claude-3-5-sonnet", ModelPrice { input_per_mtok: 3.0, output_per_mtok: 15.0 }),
+    ("claude-3-5-haiku", ModelPrice { input_per_mtok: 0.8, output_per_mtok: 4.0 }),
+    ("claude-3-haiku", ModelPrice { input_per_mtok: 0.25, output_per_mtok: 1.25 }),
+    ("gpt-4o-mini", ModelPrice { input_per_mtok: 0.15, output_per_mtok: 0.6 }),
+    ("gpt-4o", ModelPrice { input_per_mtok: 2.5, output_per_mtok: 10.0 }),
+    ("gpt-4-turbo", ModelPrice { input_per_mtok: 10.0, output_per_mtok: 30.0 }),
+    ("gpt-4", ModelPrice { input_per_mtok: 30.0, output_per_mtok: 60.0 }),
+    ("gpt-3.5-turbo", ModelPrice { input_per_mtok: 0.5, output_per_mtok: 1.5 }),
+    // Embeddings (input only)
+    ("text-embedding-3-small", ModelPrice { input_per_mtok: 0.02, output_per_mtok: 0.0 }),
+    ("text-embedding-3-large", ModelPrice { input_per_mtok: 0.13, output_per_mtok: 0.0 }),
+    ("text-embedding-ada-002", ModelPrice { input_per_mtok: 0.1, output_per_mtok: 0.0 }),
+    ("voyage-3-lite", ModelPrice { input_per_mtok: 0.02, output_per_mtok: 0.0 }),
+    ("voyage-3-large", ModelPrice { input_per_mtok: 0.18, output_per_mtok: 0.0 }),
+    ("voyage-code-3", ModelPrice { input_per_mtok: 0.18, output_per_mtok: 0.0 }),
+    ("voyage-3", ModelPrice { input_per_mtok: 0.06, output_per_mtok: 0.0 }),
+];
+
+/// Price of `model`, or None when it is not in the pricing table
+pub fn model_price(model: &str) -> Option<ModelPrice> {
+    let model = model.to_lowercase();
+    PRICES
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map(|(_, price)| *price)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Estimated cost in USD (0 for models without a price, e.g. local models)
+    pub cost_usd: f64,
+}
+
+impl TokenUsage {
+    /// Usage of one call to `model`, priced from the pricing table
+    pub fn priced(model: &str, input_tokens: u64, output_tokens: u64) -> Self {
+        let cost_usd = model_price(model).map_or(0.0, |price| {
+            (input_tokens as f64 * price.input_per_mtok + output_tokens as f64 * price.output_per_mtok)
+                / 1_000_000.0
+        });
+
+        Self {
+            input_tokens,
+            output_tokens,
+            cost_usd,
+        }
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_tokens() == 0
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cost_usd += other.cost_usd;
+    }
+}
+
+#[derive(Default)]
+struct Tracker {
+    usage: TokenUsage,
+    budgets: Vec<Budget>,
+}
+
+tokio::task_local! {
+    static CURRENT_USAGE: Arc<Mutex<Tracker>>;
+}
+
+/// Run `work` and return its output with the AI usage of the calls it made
+///
+/// Only calls made on the same tokio task count; work spawned onto other
+/// tasks is not tracked.
+pub async fn track_usage<F: Future>(work: F) -> (F::Output, TokenUsage) {
+    track_usage_within(Vec::new(), work).await
+}
+
+/// Like [`track_usage`], refusing AI calls once one of `budgets` is exhausted
+pub async fn track_usage_within<F: Future>(budgets: Vec<Budget>, work: F) -> (F::Output, TokenUsage) {
+    let tracker = Arc::new(Mutex::new(Tracker {
+        usage: TokenUsage::default(),
+        budgets,
+    }));
+    let output = CURRENT_USAGE.scope(tracker.clone(), work).await;
+    let total = tracker.lock().unwrap().usage;
+    (output, total)
+}
+
+/// Fail with [`crate::Error::BudgetExceeded`] when the current scope has no budget left
+/// (called by the agents before each API call)
+pub fn check_budget() -> Result<()> {
+    CURRENT_USAGE
+        .try_with(|current| {
+            let tracker = current.lock().unwrap();
+            tracker.budgets.iter().try_for_each(|budget| budget.check(&tracker.usage))
+        })
+        .unwrap_or(Ok(()))
+}
+
+/// Report the usage of one API response (called by the agents)
+pub fn record(model: &str, input_tokens: u64, output_tokens: u64) {
+    let usage = TokenUsage::priced(model, input_tokens, output_tokens);
+    tracing::debug!(
+        "{} used {} input / {} output tokens (${:.4})",
+        model,
+        input_tokens,
+        output_tokens,
+        usage.cost_usd
+    );
+
+    let _ = CURRENT_USAGE.try_with(|current| current.lock().unwrap().usage.add(&usage));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_price() {
+        assert_eq!(model_price("claude-sonnet-4-5-20250929").unwrap().input_per_mtok, 3.0);
+        assert_eq!(model_price("gpt-4o-mini-2024-07-18").unwrap().output_per_mtok, 0.6);
+        assert_eq!(model_price("gpt-4o-2024-08-06").unwrap().output_per_mtok, 10.0);
+        assert!(model_price("llama3.1").is_none());
+    }
+
+    #[test]
+    fn test_priced() {
+        let usage = TokenUsage::priced("claude-sonnet-4-5-20250929", 1_000_000, 100_000);
+        assert_eq!(usage.total_tokens(), 1_100_000);
+        assert!((usage.cost_usd - 4.5).abs() < 1e-9);
+
+        assert_eq!(TokenUsage::priced("llama3.1", 10, 10).cost_usd, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_track_usage() {
+        // Outside a tracking scope usage is dropped
+        record("gpt-4o", 100, 100);
+
+        let (value, usage) = track_usage(async {
+            record("gpt-4o", 1000, 200);
+            record("gpt-4o", 500, 100);
+            7
+        })
+        .await;
+
+        assert_eq!(value, 7);
+        assert_eq!(usage.input_tokens, 1500);
+        assert_eq!(usage.output_tokens, 300);
+        assert!(usage.cost_usd > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_check_budget() {
+        use crate::budget::{BudgetLimit, BudgetScope};
+
+        assert!(check_budget().is_ok());
+
+        let limit = BudgetLimit {
+            max_tokens: Some(1000),
+            max_cost_usd: None,
+        };
+        let budgets = vec![Budget::new(BudgetScope::Task, limit, TokenUsage::default())];
+
+        let (checks, usage) = track_usage_within(budgets, async {
+            let before = check_budget();
+            record("gpt-4o", 800, 200);
+            (before, check_budget())
+        })
+        .await;
+
+        assert!(checks.0.is_ok());
+        assert!(matches!(checks.1, Err(crate::Error::BudgetExceeded(_))));
+        assert_eq!(usage.total_tokens(), 1000);
+    }
+}
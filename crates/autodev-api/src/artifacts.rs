@@ -0,0 +1,340 @@
+//! Files executors upload for a task: container logs and result.json
+//!
+//! Executors post them to `POST /tasks/:id/artifacts` so they stay reachable
+//! when the API runs in a container or on another host than the executor.
+//! `AUTODEV_ARTIFACT_STORE` selects where they are kept: `disk` (default)
+//! under `AUTODEV_ARTIFACT_DIR`, or `s3` for S3-compatible storage (AWS S3,
+//! MinIO, R2...) addressed path-style and signed with AWS Signature V4.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub const ARTIFACT_STORE_ENV: &str = "AUTODEV_ARTIFACT_STORE";
+/// Directory of the disk store (default: $AUTODEV_WORKSPACE_DIR/artifacts)
+pub const ARTIFACT_DIR_ENV: &str = "AUTODEV_ARTIFACT_DIR";
+pub const S3_BUCKET_ENV: &str = "AUTODEV_S3_BUCKET";
+/// Endpoint of S3-compatible storage (default: AWS S3 of the region)
+pub const S3_ENDPOINT_ENV: &str = "AUTODEV_S3_ENDPOINT";
+pub const S3_REGION_ENV: &str = "AUTODEV_S3_REGION";
+
+/// Largest accepted upload, all files together
+pub const MAX_UPLOAD_BYTES: usize = 64 * 1024 * 1024;
+
+const DEFAULT_S3_REGION: &str = "us-east-1";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactInfo {
+    pub name: String,
+    pub size: u64,
+}
+
+/// Where uploaded artifacts are kept, by task
+#[async_trait]
+pub trait ArtifactStore: Send + Sync {
+    /// Store `content` as `name`, replacing an artifact of the same name
+    async fn put(&self, task_id: &str, name: &str, content: Vec<u8>) -> Result<()>;
+
+    /// Artifacts of a task, by name
+    async fn list(&self, task_id: &str) -> Result<Vec<ArtifactInfo>>;
+
+    /// Content of an artifact, None when it does not exist
+    async fn get(&self, task_id: &str, name: &str) -> Result<Option<Vec<u8>>>;
+}
+
+/// Whether `name` can be used as an artifact (or task) name: a plain file
+/// name of letters, digits, `.`, `_` and `-`
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 255
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Store selected by AUTODEV_ARTIFACT_STORE
+pub fn store_from_env() -> Result<Arc<dyn ArtifactStore>> {
+    let kind = std::env::var(ARTIFACT_STORE_ENV).unwrap_or_else(|_| "disk".to_string());
+
+    match kind.trim().to_lowercase().as_str() {
+        "disk" => {
+            let root = std::env::var(ARTIFACT_DIR_ENV).map(PathBuf::from).unwrap_or_else(|_| {
+                let workspace = std::env::var("AUTODEV_WORKSPACE_DIR")
+                    .unwrap_or_else(|_| "/tmp/autodev-workspace".to_string());
+                PathBuf::from(workspace).join("artifacts")
+            });
+            tracing::info!("Storing task artifacts in {:?}", root);
+            Ok(Arc::new(DiskStore::new(root)))
+        }
+        "s3" => {
+            let store = S3Store::from_env()?;
+            tracing::info!("Storing task artifacts in bucket {} at {}", store.bucket, store.endpoint);
+            Ok(Arc::new(store))
+        }
+        other => Err(anyhow!("{}: unknown artifact store '{}' (disk, s3)", ARTIFACT_STORE_ENV, other)),
+    }
+}
+
+/// Artifacts as files under `<root>/<task_id>/`
+pub struct DiskStore {
+    root: PathBuf,
+}
+
+impl DiskStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for DiskStore {
+    async fn put(&self, task_id: &str, name: &str, content: Vec<u8>) -> Result<()> {
+        let dir = self.root.join(task_id);
+        tokio::fs::create_dir_all(&dir).await?;
+
+        // Written aside and renamed so readers never see a partial file
+        let partial = dir.join(format!(".{}.partial", name));
+        tokio::fs::write(&partial, content).await?;
+        tokio::fs::rename(&partial, dir.join(name)).await?;
+        Ok(())
+    }
+
+    async fn list(&self, task_id: &str) -> Result<Vec<ArtifactInfo>> {
+        let mut entries = match tokio::fs::read_dir(self.root.join(task_id)).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut artifacts = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let metadata = entry.metadata().await?;
+            if metadata.is_file() && valid_name(&name) {
+                artifacts.push(ArtifactInfo { name, size: metadata.len() });
+            }
+        }
+
+        artifacts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(artifacts)
+    }
+
+    async fn get(&self, task_id: &str, name: &str) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.root.join(task_id).join(name)).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Artifacts as objects `tasks/<task_id>/<name>` of an S3 bucket
+///
+/// Credentials come from AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY.
+pub struct S3Store {
+    client: reqwest::Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+impl S3Store {
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+                .ok_or_else(|| anyhow!("{} must be set for the s3 artifact store", name))
+        };
+
+        let region = std::env::var(S3_REGION_ENV).unwrap_or_else(|_| DEFAULT_S3_REGION.to_string());
+        let endpoint = std::env::var(S3_ENDPOINT_ENV)
+            .unwrap_or_else(|_| format!("https://s3.{}.amazonaws.com", region));
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: var(S3_BUCKET_ENV)?,
+            region,
+            access_key: var("AWS_ACCESS_KEY_ID")?,
+            secret_key: var("AWS_SECRET_ACCESS_KEY")?,
+        })
+    }
+
+    fn object_path(&self, task_id: &str, name: &str) -> String {
+        format!("/{}/tasks/{}/{}", self.bucket, task_id, name)
+    }
+
+    /// Send a request signed with AWS Signature V4
+    ///
+    /// `path` and `query` must already be URI-encoded, with the query
+    /// parameters sorted by name.
+    async fn send(&self, method: reqwest::Method, path: &str, query: &str, body: Vec<u8>) -> Result<reqwest::Response> {
+        let host = self
+            .endpoint
+            .split_once("://")
+            .map_or(self.endpoint.as_str(), |(_, host)| host)
+            .to_string();
+        let now = chrono::Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let authorization = sigv4_authorization(&SignedRequest {
+            method: method.as_str(),
+            host: &host,
+            path,
+            query,
+            payload_hash: &payload_hash,
+            amz_date: &amz_date,
+            region: &self.region,
+            access_key: &self.access_key,
+            secret_key: &self.secret_key,
+        });
+
+        let url = if query.is_empty() {
+            format!("{}{}", self.endpoint, path)
+        } else {
+            format!("{}{}?{}", self.endpoint, path, query)
+        };
+
+        let response = self
+            .client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .body(body)
+            .send()
+            .await?;
+        Ok(response)
+    }
+}
+
+/// Error for an S3 response that is not a success
+async fn s3_error(action: &str, response: reqwest::Response) -> anyhow::Error {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    anyhow!("S3 {} failed with {}: {}", action, status, body.trim())
+}
+
+#[async_trait]
+impl ArtifactStore for S3Store {
+    async fn put(&self, task_id: &str, name: &str, content: Vec<u8>) -> Result<()> {
+        let response = self
+            .send(reqwest::Method::PUT, &self.object_path(task_id, name), "", content)
+            .await?;
+        if !response.status().is_success() {
+            return Err(s3_error("upload", response).await);
+        }
+        Ok(())
+    }
+
+    async fn list(&self, task_id: &str) -> Result<Vec<ArtifactInfo>> {
+        let prefix = format!("tasks/{}/", task_id);
+        let query = format!("list-type=2&prefix={}", prefix.replace('/', "%2F"));
+        let response = self
+            .send(reqwest::Method::GET, &format!("/{}", self.bucket), &query, Vec::new())
+            .await?;
+        if !response.status().is_success() {
+            return Err(s3_error("listing", response).await);
+        }
+
+        let body = response.text().await?;
+        parse_list_objects(&body, &prefix).context("Unexpected S3 listing")
+    }
+
+    async fn get(&self, task_id: &str, name: &str) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .send(reqwest::Method::GET, &self.object_path(task_id, name), "", Vec::new())
+            .await?;
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(response.bytes().await?.to_vec())),
+            _ => Err(s3_error("download", response).await),
+        }
+    }
+}
+
+/// Objects of a ListObjectsV2 response under `prefix`
+fn parse_list_objects(xml: &str, prefix: &str) -> Result<Vec<ArtifactInfo>> {
+    let element = |block: &str, tag: &str| -> Option<String> {
+        let start = block.find(&format!("<{}>", tag))? + tag.len() + 2;
+        let end = block[start..].find(&format!("</{}>", tag))? + start;
+        Some(block[start..end].to_string())
+    };
+
+    let mut artifacts = Vec::new();
+    for block in xml.split("<Contents>").skip(1) {
+        let key = element(block, "Key").ok_or_else(|| anyhow!("object without a Key"))?;
+        let size = element(block, "Size")
+            .and_then(|size| size.parse().ok())
+            .ok_or_else(|| anyhow!("object {} without a Size", key))?;
+
+        if let Some(name) = key.strip_prefix(prefix).filter(|name| valid_name(name)) {
+            artifacts.push(ArtifactInfo { name: name.to_string(), size });
+        }
+    }
+
+    artifacts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(artifacts)
+}
+
+struct SignedRequest<'a> {
+    method: &'a str,
+    host: &'a str,
+    path: &'a str,
+    query: &'a str,
+    payload_hash: &'a str,
+    /// `YYYYMMDDTHHMMSSZ`
+    amz_date: &'a str,
+    region: &'a str,
+    access_key: &'a str,
+    secret_key: &'a str,
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Authorization header of an S3 request (AWS Signature V4)
+fn sigv4_authorization(request: &SignedRequest) -> String {
+    const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+    let date = &request.amz_date[..8];
+    let scope = format!("{}/{}/s3/aws4_request", date, request.region);
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        request.method,
+        request.path,
+        request.query,
+        request.host,
+        request.payload_hash,
+        request.amz_date,
+        SIGNED_HEADERS,
+        request.payload_hash,
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        request.amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = [date, request.region, "s3", "aws4_request"]
+        .iter()
+        .fold(format!("AWS4{}", request.secret_key).into_bytes(), |key, part| hmac_sha256(&key, part));
+    let signature = hex::encode(hmac_sha256(&key, &string_to_sign));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        request.access_key, scope, SIGNED_HEADERS, signature
+    )
+}
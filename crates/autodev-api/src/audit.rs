@@ -0,0 +1,120 @@
+//! Audit trail of requests that change something
+//!
+//! Every request other than GET/HEAD and the health checks is recorded once
+//! answered, attributed to the API key's user (`api:<user>`), the webhook
+//! delivery (`webhook:<X-GitHub-Delivery>`) or the workflow callback
+//! (`workflow`). JSON bodies up to [`MAX_AUDITED_BODY`] bytes are kept as the
+//! event's parameters, except secret values. Requests rejected by
+//! [`crate::auth::authorize`] are not recorded.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{MatchedPath, Request, State},
+    http::{header, Method},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::{json, Value};
+
+use autodev_db::NewAuditEvent;
+
+use crate::auth::Caller;
+use crate::state::ApiState;
+
+/// Largest request body stored with an event
+pub const MAX_AUDITED_BODY: usize = 64 * 1024;
+
+fn header_value<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request.headers().get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Who made the request and the action recorded for it
+fn actor_and_action(request: &Request) -> (String, String) {
+    let path = request.uri().path();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(path, |matched| matched.as_str());
+
+    if path.starts_with("/webhook/") {
+        let delivery = header_value(request, "x-github-delivery").unwrap_or("-");
+        let event = header_value(request, "x-github-event").unwrap_or("unknown");
+        return (format!("webhook:{}", delivery), format!("webhook.{}", event));
+    }
+
+    let actor = if path.starts_with("/callbacks/") {
+        "workflow".to_string()
+    } else {
+        let user = request
+            .extensions()
+            .get::<Caller>()
+            .and_then(|caller| caller.user.as_deref())
+            .unwrap_or("anonymous");
+        format!("api:{}", user)
+    };
+
+    (actor, format!("{} {}", request.method(), route))
+}
+
+/// Repository named in a request body (`repository_owner`/`repository_name`)
+fn body_repository(body: &Value) -> Option<(&str, &str)> {
+    Some((body["repository_owner"].as_str()?, body["repository_name"].as_str()?))
+}
+
+/// Middleware: record the request and its response status in the audit trail
+///
+/// Runs inside `authorize`, so the caller is known.
+pub async fn record_request(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let audited = state.db.is_some()
+        && request.method() != Method::GET
+        && request.method() != Method::HEAD
+        && !request.uri().path().starts_with("/health")
+        // Heartbeats only extend a lease and arrive every few minutes per running task
+        && !request.uri().path().ends_with("/heartbeat");
+    if !audited {
+        return next.run(request).await;
+    }
+
+    let (actor, action) = actor_and_action(&request);
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().map(str::to_string);
+
+    // Webhook payloads are GitHub's; only API and callback parameters are kept
+    let keep_body = !path.starts_with("/webhook/")
+        && !path.contains("/secrets")
+        && header_value(&request, header::CONTENT_TYPE.as_str()).is_some_and(|t| t.starts_with("application/json"))
+        && header_value(&request, header::CONTENT_LENGTH.as_str())
+            .and_then(|length| length.parse::<usize>().ok())
+            .is_some_and(|length| length <= MAX_AUDITED_BODY);
+
+    let (request, body) = if keep_body {
+        let (parts, body) = request.into_parts();
+        match to_bytes(body, MAX_AUDITED_BODY).await {
+            Ok(bytes) => {
+                let body = serde_json::from_slice::<Value>(&bytes).ok();
+                (Request::from_parts(parts, Body::from(bytes)), body)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read the body of {} for the audit trail: {}", path, e);
+                (Request::from_parts(parts, Body::empty()), None)
+            }
+        }
+    } else {
+        (request, None)
+    };
+
+    let response = next.run(request).await;
+
+    let mut event = NewAuditEvent::new(actor, action).with_target(path);
+    if let Some((owner, name)) = body.as_ref().and_then(body_repository) {
+        event = event.with_repository(owner, name);
+    }
+    let event = event.with_payload(json!({
+        "query": query,
+        "body": body,
+        "status": response.status().as_u16(),
+    }));
+    autodev_executor::audit::record(&state.db, event).await;
+
+    response
+}
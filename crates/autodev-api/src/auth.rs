@@ -0,0 +1,340 @@
+//! API keys and roles
+//!
+//! Keys are configured in `AUTODEV_API_KEYS` as comma-separated
+//! `user:role:key` entries and sent as `Authorization: Bearer <key>` or
+//! `X-API-Key: <key>`. Without the variable the API stays open and requests
+//! are not attributed to anyone.
+//!
+//! `user@org:role:key` limits a key to the repositories of one organization
+//! (repository owner): other organizations' tasks, composite tasks and
+//! schedules are hidden from it. Such keys need the database, where the
+//! organization of stored tasks is looked up.
+//!
+//! - viewer: reads (GET)
+//! - operator: creating, executing, cancelling, approving and rolling back
+//!   tasks, schedules
+//! - admin: everything, including few-shot examples (they shape every
+//!   decomposition), repository secrets and the audit trail
+//!
+//! Webhooks, workflow callbacks and heartbeats, task payloads and secrets
+//! fetched by workflows and the health checks are not behind a key. Payloads
+//! need the token they were dispatched with (or an operator key), secrets a
+//! one-time token.
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use autodev_db::Database;
+use autodev_github::org_id;
+
+use crate::state::ApiState;
+
+pub const API_KEYS_ENV: &str = "AUTODEV_API_KEYS";
+
+/// Roles in increasing order of permissions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Operator,
+    Admin,
+}
+
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "viewer" => Ok(Role::Viewer),
+            "operator" => Ok(Role::Operator),
+            "admin" => Ok(Role::Admin),
+            other => Err(anyhow::anyhow!("Unknown role '{}' (viewer, operator, admin)", other)),
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Role::Viewer => "viewer",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        })
+    }
+}
+
+/// Who is making a request, added to the request extensions by [`authorize`]
+#[derive(Debug, Clone)]
+pub struct Caller {
+    /// None when no API keys are configured
+    pub user: Option<String>,
+    pub role: Role,
+    /// Organization the key is limited to; None for every organization
+    pub org: Option<String>,
+}
+
+impl Caller {
+    /// Caller when authentication is off: everything is allowed
+    pub fn anonymous() -> Self {
+        Self {
+            user: None,
+            role: Role::Admin,
+            org: None,
+        }
+    }
+
+    /// Whether the caller may use repositories of `owner`; Err is the message
+    pub fn check_access(&self, owner: &str) -> Result<(), String> {
+        match self.org {
+            Some(ref org) if *org != org_id(owner) => {
+                Err(format!("This API key is limited to organization {}", org))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Organization a listing is limited to: the key's own, otherwise `requested`
+    ///
+    /// Err (the message) when a limited key asks for another organization.
+    pub fn org_filter(&self, requested: Option<String>) -> Result<Option<String>, String> {
+        match (&self.org, requested) {
+            (Some(org), Some(requested)) => {
+                self.check_access(&requested)?;
+                Ok(Some(org.clone()))
+            }
+            (Some(org), None) => Ok(Some(org.clone())),
+            (None, requested) => Ok(requested),
+        }
+    }
+}
+
+/// Configured API keys
+#[derive(Debug, Default)]
+pub struct ApiKeys {
+    keys: HashMap<String, Caller>,
+}
+
+impl ApiKeys {
+    /// Parse `user[@org]:role:key` entries separated by commas
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        let mut keys = HashMap::new();
+
+        for entry in value.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let mut parts = entry.splitn(3, ':');
+            let (Some(user), Some(role), Some(key)) = (parts.next(), parts.next(), parts.next()) else {
+                anyhow::bail!("{}: expected comma-separated user:role:key entries", API_KEYS_ENV);
+            };
+            let (user, org) = match user.split_once('@') {
+                Some((user, org)) => (user, Some(org_id(org))),
+                None => (user, None),
+            };
+            if user.is_empty() || key.is_empty() || org.as_deref() == Some("") {
+                anyhow::bail!("{}: user, organization and key must not be empty", API_KEYS_ENV);
+            }
+
+            let caller = Caller {
+                user: Some(user.to_string()),
+                role: role.parse()?,
+                org,
+            };
+            if keys.insert(key.to_string(), caller).is_some() {
+                anyhow::bail!("{}: the key of '{}' is used more than once", API_KEYS_ENV, user);
+            }
+        }
+
+        Ok(Self { keys })
+    }
+
+    /// Keys from AUTODEV_API_KEYS, None when unset (authentication off)
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        match std::env::var(API_KEYS_ENV) {
+            Ok(value) if !value.trim().is_empty() => Ok(Some(Self::parse(&value)?)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn authenticate(&self, key: &str) -> Option<Caller> {
+        self.keys.get(key).cloned()
+    }
+
+    /// Caller of the key sent with a request, None without a valid key
+    pub fn authenticate_request(&self, headers: &HeaderMap) -> Option<Caller> {
+        request_key(headers).and_then(|key| self.authenticate(key))
+    }
+}
+
+/// Role a request needs, None for endpoints that stay open
+pub fn required_role(method: &Method, path: &str) -> Option<Role> {
+    // Exact segments: artifacts may be named "payload" or "secrets" too
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    let workflow_route = match segments.as_slice() {
+        ["tasks", _, "payload" | "secrets"] => method == Method::GET,
+        ["tasks", _, "heartbeat"] => method == Method::POST,
+        _ => false,
+    };
+    let public = path == "/health"
+        || path == "/health/ready"
+        || path.starts_with("/webhook/")
+        || path.starts_with("/callbacks/")
+        || workflow_route;
+    if public {
+        return None;
+    }
+
+    let secrets = path.starts_with("/repos/") && path.split('/').nth(4) == Some("secrets");
+    if path == "/audit" || path.starts_with("/audit/") || secrets {
+        Some(Role::Admin)
+    } else if method == Method::GET || method == Method::HEAD {
+        Some(Role::Viewer)
+    } else if path == "/examples" || path.starts_with("/examples/") {
+        Some(Role::Admin)
+    } else {
+        Some(Role::Operator)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+fn error(status: StatusCode, error: String) -> Response {
+    (status, Json(ErrorResponse { error })).into_response()
+}
+
+fn request_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get("x-api-key").and_then(|value| value.to_str().ok()) {
+        return Some(key.trim());
+    }
+
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Middleware: authenticate the request and check its role
+pub async fn authorize(State(state): State<ApiState>, mut request: Request, next: Next) -> Response {
+    let Some(ref api_keys) = state.api_keys else {
+        request.extensions_mut().insert(Caller::anonymous());
+        return next.run(request).await;
+    };
+
+    let Some(required) = required_role(request.method(), request.uri().path()) else {
+        request.extensions_mut().insert(Caller {
+            user: None,
+            role: Role::Viewer,
+            org: None,
+        });
+        return next.run(request).await;
+    };
+
+    let Some(caller) = api_keys.authenticate_request(request.headers()) else {
+        return error(StatusCode::UNAUTHORIZED, "Missing or invalid API key".to_string());
+    };
+
+    if caller.role < required {
+        tracing::warn!(
+            "{} ({}) denied {} {}",
+            caller.user.as_deref().unwrap_or("-"),
+            caller.role,
+            request.method(),
+            request.uri().path()
+        );
+        return error(
+            StatusCode::FORBIDDEN,
+            format!("This operation requires the {} role (you are {})", required, caller.role),
+        );
+    }
+
+    if let Some(ref org) = caller.org {
+        let Some(ref db) = state.db else {
+            return error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "API keys limited to an organization require a database".to_string(),
+            );
+        };
+
+        match path_org(db, request.uri().path()).await {
+            Ok(Some(Some(owner))) if owner != *org => {
+                // 다른 조직의 리소스는 존재 여부도 드러내지 않음
+                return error(StatusCode::NOT_FOUND, "Not found".to_string());
+            }
+            Ok(Some(None)) if request.method() != Method::GET && request.method() != Method::HEAD => {
+                return error(
+                    StatusCode::FORBIDDEN,
+                    "Shared examples can only be changed with keys not limited to an organization".to_string(),
+                );
+            }
+            Ok(_) => {}
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)),
+        }
+    }
+
+    request.extensions_mut().insert(caller);
+    next.run(request).await
+}
+
+/// Organization of the stored task, composite task, schedule or example a
+/// path refers to
+///
+/// None when the path names no stored resource; Some(None) for examples
+/// shared by every repository.
+async fn path_org(db: &Database, path: &str) -> autodev_db::Result<Option<Option<String>>> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    let org = match segments.as_slice() {
+        ["tasks", "decompose"] => None,
+        ["tasks", id, ..] => match db.get_task(id).await? {
+            Some(record) => Some(record.org_id),
+            // POST /tasks/:composite_task_id/orchestrate
+            None => db.get_composite_task(id).await?.map(|record| record.org_id),
+        },
+        ["composite-tasks", id, ..] => db.get_composite_task(id).await?.map(|record| record.org_id),
+        ["repos", owner, ..] => Some(org_id(owner)),
+        ["schedules", id] => match id.parse() {
+            Ok(id) => db.get_schedule(id).await?.map(|record| org_id(&record.repository_owner)),
+            Err(_) => None,
+        },
+        ["examples", id] => {
+            let example = match id.parse() {
+                Ok(id) => db.get_example(id).await?,
+                Err(_) => None,
+            };
+            return Ok(example.map(|record| record.repository_owner.map(|owner| org_id(&owner))));
+        }
+        _ => None,
+    };
+
+    Ok(org.map(Some))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workflow_routes_are_public() {
+        assert_eq!(required_role(&Method::GET, "/tasks/task-1/payload"), None);
+        assert_eq!(required_role(&Method::GET, "/tasks/task-1/secrets"), None);
+        assert_eq!(required_role(&Method::POST, "/tasks/task-1/heartbeat"), None);
+        assert_eq!(required_role(&Method::DELETE, "/tasks/task-1/payload"), Some(Role::Operator));
+    }
+
+    #[test]
+    fn test_artifact_routes_require_a_key() {
+        assert_eq!(required_role(&Method::GET, "/tasks/task-1/artifacts/payload"), Some(Role::Viewer));
+        assert_eq!(required_role(&Method::GET, "/tasks/task-1/artifacts/secrets"), Some(Role::Viewer));
+        assert_eq!(required_role(&Method::POST, "/tasks/task-1/artifacts/heartbeat"), Some(Role::Operator));
+    }
+}
@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Multipart, Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::artifacts::{valid_name, ArtifactInfo};
+use crate::state::ApiState;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArtifactListResponse {
+    pub task_id: String,
+    pub artifacts: Vec<ArtifactInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+type HandlerError = (StatusCode, Json<ErrorResponse>);
+
+fn error(status: StatusCode, error: String) -> HandlerError {
+    (status, Json(ErrorResponse { error }))
+}
+
+fn store_error(e: anyhow::Error) -> HandlerError {
+    error(StatusCode::INTERNAL_SERVER_ERROR, format!("Artifact store error: {}", e))
+}
+
+async fn require_task(state: &ApiState, task_id: &str) -> Result<(), HandlerError> {
+    if !valid_name(task_id) || state.engine.get_task(task_id).await.is_none() {
+        return Err(error(StatusCode::NOT_FOUND, format!("Task not found: {}", task_id)));
+    }
+    Ok(())
+}
+
+/// Store the files of a multipart upload as artifacts of the task
+///
+/// Each part is stored under its file name (its field name when it has none),
+/// replacing an artifact of the same name.
+pub async fn upload_artifacts(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<ArtifactListResponse>), HandlerError> {
+    require_task(&state, &task_id).await?;
+
+    let mut uploaded = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| error(StatusCode::BAD_REQUEST, format!("Invalid multipart body: {}", e)))?
+    {
+        let name = field
+            .file_name()
+            .or(field.name())
+            .unwrap_or_default()
+            .to_string();
+        if !valid_name(&name) {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid artifact name '{}': use letters, digits, '.', '_' and '-'", name),
+            ));
+        }
+
+        let content = field
+            .bytes()
+            .await
+            .map_err(|e| error(StatusCode::BAD_REQUEST, format!("Failed to read {}: {}", name, e)))?;
+        let size = content.len() as u64;
+
+        state.artifacts.put(&task_id, &name, content.to_vec()).await.map_err(store_error)?;
+        uploaded.push(ArtifactInfo { name, size });
+    }
+
+    if uploaded.is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "No files in the upload".to_string()));
+    }
+
+    tracing::info!(
+        "Stored artifacts of task {}: {}",
+        task_id,
+        uploaded.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ")
+    );
+    Ok((StatusCode::CREATED, Json(ArtifactListResponse { task_id, artifacts: uploaded })))
+}
+
+/// List the artifacts of a task
+pub async fn list_artifacts(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<ArtifactListResponse>, HandlerError> {
+    require_task(&state, &task_id).await?;
+
+    let artifacts = state.artifacts.list(&task_id).await.map_err(store_error)?;
+    Ok(Json(ArtifactListResponse { task_id, artifacts }))
+}
+
+/// Download an artifact of a task
+pub async fn get_artifact(
+    State(state): State<ApiState>,
+    Path((task_id, name)): Path<(String, String)>,
+) -> Result<Response, HandlerError> {
+    require_task(&state, &task_id).await?;
+    if !valid_name(&name) {
+        return Err(error(StatusCode::NOT_FOUND, format!("Artifact not found: {}", name)));
+    }
+
+    let Some(content) = state.artifacts.get(&task_id, &name).await.map_err(store_error)? else {
+        return Err(error(StatusCode::NOT_FOUND, format!("Artifact not found: {}", name)));
+    };
+
+    let content_type = match name.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("json") => "application/json",
+        Some("txt" | "log") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    };
+    Ok(([(header::CONTENT_TYPE, content_type)], content).into_response())
+}
@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::Caller;
+use crate::handlers::task::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::state::ApiState;
+use autodev_db::{AuditEvent, AuditQuery, Database};
+
+#[derive(Debug, Deserialize)]
+pub struct ListAuditQuery {
+    /// `api:<user>`, `webhook:<delivery>`, `cli:<user>`, `system:<component>` or `workflow`
+    pub actor: Option<String>,
+    /// e.g. `POST /tasks`, `branch.create`, `merge_request.merge`
+    pub action: Option<String>,
+    /// Path, branch, merge request number or task ID the action applied to
+    pub target: Option<String>,
+    /// `owner/name`
+    pub repository: Option<String>,
+    /// RFC 3339; events at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// RFC 3339; events before this time
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditListResponse {
+    pub events: Vec<AuditEvent>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditVerifyResponse {
+    pub intact: bool,
+    /// First event that was changed or follows a deleted one
+    pub first_broken_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+type HandlerError = (StatusCode, Json<ErrorResponse>);
+
+fn error(status: StatusCode, error: String) -> HandlerError {
+    (status, Json(ErrorResponse { error }))
+}
+
+/// The audit trail spans every organization, so keys limited to one cannot read it
+fn require_db<'a>(state: &'a ApiState, caller: &Caller) -> Result<&'a Database, HandlerError> {
+    if caller.org.is_some() {
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "The audit trail can only be read with keys not limited to an organization".to_string(),
+        ));
+    }
+
+    state
+        .db
+        .as_deref()
+        .ok_or_else(|| error(StatusCode::SERVICE_UNAVAILABLE, "The audit trail requires a database".to_string()))
+}
+
+fn db_error(e: autodev_db::Error) -> HandlerError {
+    error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e))
+}
+
+/// List audit events, newest first
+pub async fn list_audit_events(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Query(query): Query<ListAuditQuery>,
+) -> Result<Json<AuditListResponse>, HandlerError> {
+    let db = require_db(&state, &caller)?;
+
+    let query = AuditQuery {
+        actor: query.actor,
+        action: query.action,
+        target: query.target,
+        repository: query.repository,
+        since: query.since,
+        until: query.until,
+        limit: query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+        offset: query.offset.unwrap_or(0).max(0),
+    };
+
+    let (events, total) = db.list_audit_events(&query).await.map_err(db_error)?;
+
+    Ok(Json(AuditListResponse {
+        events,
+        total,
+        limit: query.limit,
+        offset: query.offset,
+    }))
+}
+
+/// Recompute the hash chain to find events changed or deleted after the fact
+pub async fn verify_audit_chain(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+) -> Result<Json<AuditVerifyResponse>, HandlerError> {
+    let db = require_db(&state, &caller)?;
+
+    let first_broken_id = db.verify_audit_chain().await.map_err(db_error)?;
+    if let Some(id) = first_broken_id {
+        tracing::error!("Audit chain is broken at event {}", id);
+    }
+
+    Ok(Json(AuditVerifyResponse {
+        intact: first_broken_id.is_none(),
+        first_broken_id,
+    }))
+}
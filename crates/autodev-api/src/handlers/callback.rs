@@ -6,7 +6,10 @@ use axum::{
 use serde::{Deserialize, Serialize};
 
 use crate::state::ApiState;
+use autodev_db::TaskArtifact;
+use autodev_executor::checks;
 use autodev_github::Repository;
+use autodev_notify::NotificationEvent;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WorkflowCompleteRequest {
@@ -18,6 +21,34 @@ pub struct WorkflowCompleteRequest {
     pub pr_url: Option<String>,
     pub success: bool,
     pub error: Option<String>,
+    /// What the task changed, passed on to the tasks depending on it
+    #[serde(default)]
+    pub files_changed: Vec<String>,
+    /// `git diff --stat` of the changes
+    #[serde(default)]
+    pub diff_stat: Option<String>,
+    /// Summary of the work (the agent's final message)
+    #[serde(default)]
+    pub summary: Option<String>,
+}
+
+impl WorkflowCompleteRequest {
+    /// Artifact reported with a successful run (None when nothing was reported)
+    fn artifact(&self, title: &str) -> Option<TaskArtifact> {
+        if !self.success || (self.files_changed.is_empty() && self.diff_stat.is_none() && self.summary.is_none()) {
+            return None;
+        }
+
+        Some(TaskArtifact {
+            task_id: self.task_id.clone(),
+            title: title.to_string(),
+            summary: self.summary.clone(),
+            files_changed: self.files_changed.clone(),
+            diff_stat: self.diff_stat.clone(),
+            pr_url: self.pr_url.clone(),
+            created_at: chrono::Utc::now(),
+        })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,6 +62,73 @@ pub struct ErrorResponse {
     pub error: String,
 }
 
+/// Rewrite a PR body from its diff without holding up the callback
+fn describe_in_background(
+    state: &ApiState,
+    repo: Repository,
+    pr_number: u64,
+    title: String,
+    description: String,
+    footer: String,
+) {
+    let agent = state.ai_agent.clone();
+    let vcs = state.vcs.clone();
+    state.background.spawn(async move {
+        if let Err(e) = autodev_executor::describe_merge_request(
+            agent,
+            &vcs,
+            &repo,
+            pr_number,
+            &title,
+            &description,
+            &footer,
+        ).await {
+            tracing::warn!("Keeping the generated body of PR #{}: {}", pr_number, e);
+        }
+    });
+}
+
+/// Security-scan the draft PR of a completed composite task if the repository opted in
+fn scan_in_background(state: &ApiState, repo: Repository, composite_task: autodev_core::CompositeTask, pr_number: u64) {
+    let agent = state.ai_agent.clone();
+    let vcs = state.vcs.clone();
+    state.background.spawn(async move {
+        match vcs.load_repo_config(&repo).await {
+            Ok(config) if config.security_scan => {}
+            Ok(_) => return,
+            Err(e) => {
+                tracing::warn!("Failed to load {} of {}: {}", autodev_github::CONFIG_FILE, repo.full_name(), e);
+                return;
+            }
+        }
+
+        if let Err(e) = autodev_executor::scan_composite_pr(&agent, &vcs, &repo, &composite_task, pr_number).await {
+            tracing::error!("Security scan of PR #{} failed: {}", pr_number, e);
+        }
+    });
+}
+
+/// Whether the task ran on one of this server's local executors
+///
+/// Subtasks run on their composite task's backend.
+async fn ran_locally(state: &ApiState, payload: &WorkflowCompleteRequest, task: Option<&autodev_core::Task>) -> bool {
+    if state.router.local_executor().is_none() {
+        return false;
+    }
+
+    let requested = match state.engine.get_composite_task(&payload.composite_task_id).await {
+        Some(composite_task) => composite_task.execution_backend,
+        None => task.and_then(|task| task.execution_backend),
+    };
+    let repo = Repository::new(payload.repository_owner.clone(), payload.repository_name.clone());
+    let config = state.vcs.load_repo_config(&repo).await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to load {} of {}: {}", autodev_github::CONFIG_FILE, repo.full_name(), e);
+        autodev_github::RepoConfig::default()
+    });
+
+    state.router.resolve(requested, &config).is_local()
+}
+
 /// Handle workflow completion callback
 pub async fn workflow_complete(
     State(state): State<ApiState>,
@@ -49,67 +147,163 @@ pub async fn workflow_complete(
         autodev_core::TaskStatus::Failed
     };
 
+    // A failed run re-run from the Actions UI reports success for a failed task
+    let current = state.engine.get_task(&payload.task_id).await.map(|task| task.status);
+    if payload.success && current == Some(autodev_core::TaskStatus::Failed) {
+        if let Err(e) = state
+            .engine
+            .update_task_status(&payload.task_id, autodev_core::TaskStatus::InProgress, None)
+            .await
+        {
+            tracing::error!("Failed to update task status: {}", e);
+        }
+    }
+
+    // The engine rejects invalid transitions (e.g. a late failure of a
+    // cancelled task) and writes accepted ones through to the database
     if let Err(e) = state
         .engine
         .update_task_status(&payload.task_id, status, payload.error.clone())
         .await
     {
-        tracing::error!("Failed to update task status: {}", e);
+        tracing::warn!("Not updating task {} to {:?}: {}", payload.task_id, status, e);
     }
 
+    // Wake up any executor waiting on this task
+    let conclusion = if payload.success { "success" } else { "failure" };
+    state
+        .notifier
+        .workflow_completed(&payload.task_id, conclusion, payload.pr_number);
+
     // Update PR URL if available
-    if let Some(_task) = state.engine.get_task(&payload.task_id).await {
-        if let Some(ref pr_url) = payload.pr_url {
-            // Store PR URL in task
-            // Note: We need to add pr_url field update capability to the engine
-            tracing::info!("Task {} PR created: {}", payload.task_id, pr_url);
+    if let Some(ref pr_url) = payload.pr_url {
+        tracing::info!("Task {} PR created: {}", payload.task_id, pr_url);
+        if let Err(e) = state.engine.set_task_pr(&payload.task_id, payload.pr_number, pr_url.clone()).await {
+            tracing::error!("Failed to record PR URL: {}", e);
         }
     }
 
-    // Update database if available
     if let Some(ref db) = state.db {
-        let _ = db
-            .update_task_status(&payload.task_id, status, payload.error.clone())
-            .await;
+        // A failed run may be re-run from the Actions UI and fetch it again
+        if payload.success {
+            if let Err(e) = db.delete_task_payload(&payload.task_id).await {
+                tracing::warn!("Failed to delete payload of task {}: {}", payload.task_id, e);
+            }
+        }
+    }
+
+    let task = state.engine.get_task(&payload.task_id).await;
+    let ran_locally = ran_locally(&state, &payload, task.as_ref()).await;
+
+    // Local runs report here too, but their executor saves their metrics
+    if let (Some(task), false) = (&task, ran_locally) {
+        let db = state.db.clone();
+        let vcs = state.vcs.clone();
+        let repo = Repository::new(payload.repository_owner.clone(), payload.repository_name.clone());
+        let elapsed = autodev_executor::metrics::run_time(task);
+        let task_id = payload.task_id.clone();
+        let pr_number = payload.pr_number.filter(|_| payload.success);
+        state.background.spawn(async move {
+            autodev_executor::save_merge_request_metrics(&db, vcs.as_ref(), &repo, &task_id, pr_number, elapsed).await;
+        });
+    }
+
+    let title = task
+        .map(|task| task.title)
+        .unwrap_or_else(|| payload.task_id.clone());
+
+    // Dependent tasks are dispatched with what this one changed
+    if let Some(artifact) = payload.artifact(&title) {
+        autodev_executor::artifacts::save_artifact(&state.db, artifact).await;
+    }
+
+    // Failed subtasks fail their batch; standalone tasks are notified once no retry is left
+    if !payload.success && payload.composite_task_id != "standalone" {
+        autodev_notify::notify(NotificationEvent::TaskFailed {
+            task_id: payload.task_id.clone(),
+            title: title.clone(),
+            error: payload.error.clone(),
+        });
+
+        if let Some(composite_task) = state.engine.get_composite_task(&payload.composite_task_id).await {
+            let error = format!(
+                "Subtask {} failed: {}",
+                title,
+                payload.error.as_deref().unwrap_or("unknown error")
+            );
+            let repo = Repository::new(payload.repository_owner.clone(), payload.repository_name.clone());
+            checks::report_composite_check(&state.vcs, &repo, &composite_task.id, &checks::failed(&composite_task, &error)).await;
+        }
+    }
+
+    // Standalone PRs are merged by a human
+    if payload.success && payload.composite_task_id == "standalone" {
+        if let Some(ref pr_url) = payload.pr_url {
+            autodev_notify::notify(NotificationEvent::PrAwaitingReview {
+                task_id: payload.task_id.clone(),
+                title: title.clone(),
+                pr_url: pr_url.clone(),
+            });
+        }
     }
 
-    // If the task succeeded and has PR, auto-merge if it's a subtask
-    if payload.success && payload.pr_number.is_some() && payload.composite_task_id != "standalone" {
+    // Standalone tasks are re-dispatched according to their retry policy
+    if !payload.success && payload.composite_task_id == "standalone" {
+        let task_id = payload.task_id.clone();
         let repo = Repository::new(
             payload.repository_owner.clone(),
             payload.repository_name.clone(),
         );
+        let engine = state.engine.clone();
+        let vcs = state.vcs.clone();
+        let db = state.db.clone();
+        let error = payload.error.clone();
+        let title = title.clone();
 
-        // Auto-merge subtask PR to parent branch
-        if let Some(pr_number) = payload.pr_number {
-            tracing::info!(
-                "Auto-merging subtask PR #{} for task {} to parent branch",
-                pr_number,
-                payload.task_id
-            );
-
-            match state.github_client.merge_pull_request(&repo, pr_number).await {
-                Ok(_) => {
-                    tracing::info!(
-                        "✓ Subtask PR #{} auto-merged to parent branch",
-                        pr_number
-                    );
-                }
-                Err(e) => {
-                    tracing::error!(
-                        "Failed to auto-merge subtask PR #{}: {}",
-                        pr_number,
-                        e
-                    );
+        state.background.spawn(async move {
+            match autodev_executor::retry_failed_task(
+                &task_id,
+                autodev_core::FailureKind::WorkflowFailure,
+                &repo,
+                &engine,
+                &vcs,
+                &db,
+            ).await {
+                Ok(Some(run_id)) => tracing::info!("Task {} retried with workflow {}", task_id, run_id),
+                Ok(None) => {
+                    autodev_notify::notify(NotificationEvent::TaskFailed {
+                        task_id,
+                        title,
+                        error,
+                    });
                 }
+                Err(e) => tracing::error!("Failed to retry task {}: {}", task_id, e),
             }
+        });
+    }
+
+    // The worker image opens the PR with a minimal body; describe its actual diff
+    if let (true, Some(pr_number)) = (payload.success, payload.pr_number) {
+        if autodev_executor::pr_descriptions_enabled() {
+            let repo = Repository::new(payload.repository_owner.clone(), payload.repository_name.clone());
+            let description = state
+                .engine
+                .get_task(&payload.task_id)
+                .await
+                .map(|task| task.description)
+                .unwrap_or_default();
+            let footer = format!("---\n**AutoDev task:** `{}`\n\n🤖 Generated by AutoDev", payload.task_id);
+            describe_in_background(&state, repo, pr_number, title.clone(), description, footer);
         }
     }
 
+    // Subtask PRs are merged by the executor running the composite task, which
+    // waits for them after dispatch; merging here as well would race it
+
     // If the task succeeded, check if we can start dependent tasks
     let mut next_tasks = Vec::new();
 
-    if payload.success {
+    if payload.success && !ran_locally {
         // Get composite task
         if let Some(composite_task) = state.engine.get_composite_task(&payload.composite_task_id).await {
             let repo = Repository::new(
@@ -140,7 +334,7 @@ pub async fn workflow_complete(
 
                 // Create branch for this subtask
                 if let Err(e) = state
-                    .github_client
+                    .vcs
                     .create_branch(&repo, &task_branch, &parent_branch)
                     .await
                 {
@@ -148,18 +342,29 @@ pub async fn workflow_complete(
                     continue;
                 }
 
-                // Dispatch workflow
-                let mut inputs = std::collections::HashMap::new();
-                inputs.insert("task_id".to_string(), task.id.clone());
-                inputs.insert("composite_task_id".to_string(), composite_task.id.clone());
-                inputs.insert("task_title".to_string(), task.title.clone());
-                inputs.insert("prompt".to_string(), task.prompt.clone());
-                inputs.insert("base_branch".to_string(), task_branch.clone());
-                inputs.insert("target_branch".to_string(), parent_branch.clone());
+                // Dispatch workflow (with what its dependencies changed)
+                let prompt = autodev_executor::prompt_with_dependencies(&task, &state.db).await;
+                let inputs = match autodev_executor::dispatch_inputs(
+                    &task,
+                    &repo,
+                    Some(&composite_task.id),
+                    &prompt,
+                    &task_branch,
+                    &parent_branch,
+                    &state.db,
+                )
+                .await
+                {
+                    Ok(inputs) => inputs,
+                    Err(e) => {
+                        tracing::error!("Failed to prepare inputs for subtask {}: {}", task.id, e);
+                        continue;
+                    }
+                };
 
                 match state
-                    .github_client
-                    .trigger_workflow(&repo, "autodev-subtask.yml", inputs)
+                    .vcs
+                    .trigger_pipeline(&repo, "autodev-subtask.yml", &task_branch, inputs)
                     .await
                 {
                     Ok(workflow_run_id) => {
@@ -182,60 +387,53 @@ pub async fn workflow_complete(
                 }
             }
 
+            // Show progress as the AutoDev check on the parent branch
+            let report = if composite_task.is_completed() {
+                checks::completed(&composite_task)
+            } else {
+                let done = composite_task
+                    .subtasks
+                    .iter()
+                    .filter(|t| t.status == autodev_core::TaskStatus::Completed)
+                    .count();
+                checks::subtasks_merged(&composite_task, done)
+            };
+            checks::report_composite_check(&state.vcs, &repo, &composite_task.id, &report).await;
+
             // Check if all tasks in composite task are complete
             if composite_task.is_completed() {
+                if let Err(e) = state.engine.complete_composite_task(&composite_task.id).await {
+                    tracing::warn!("Failed to mark composite task {} completed: {}", composite_task.id, e);
+                }
+
                 tracing::info!(
                     "Composite task {} is fully completed! Creating DRAFT PR to main for user review.",
                     composite_task.id
                 );
 
                 // Create DRAFT PR from parent branch to main (requires user approval)
-                let parent_branch = format!("autodev/{}", composite_task.id);
-                let pr_body = format!(
-                    "# {}\n\n## ⚠️ Review Required\n\
-                    This is an automatically generated composite task PR. Please review all changes before merging.\n\n\
-                    ## Description\n{}\n\n\
-                    ## Subtasks Completed\n{}\n\n\
-                    ## Review Checklist\n\
-                    - [ ] All subtask PRs reviewed and verified\n\
-                    - [ ] Code quality meets standards\n\
-                    - [ ] Tests passing\n\
-                    - [ ] No security issues\n\n\
-                    ## Next Steps\n\
-                    1. Review all changes in this PR\n\
-                    2. If satisfied, mark as \"Ready for Review\"\n\
-                    3. Merge when approved\n\n\
-                    ---\n\
-                    🤖 Generated by AutoDev\n\
-                    Co-Authored-By: Claude <noreply@anthropic.com>",
-                    composite_task.title,
-                    composite_task.description,
-                    composite_task
-                        .subtasks
-                        .iter()
-                        .map(|t| format!("- [x] {} ({})", t.title, t.id))
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                );
-
-                match state
-                    .github_client
-                    .create_pull_request(
-                        &repo,
-                        format!("[AutoDev] {}", composite_task.title),
-                        pr_body,
-                        parent_branch,
-                        "main".to_string(),
-                        true,  // draft = true (requires user approval)
-                    )
-                    .await
-                {
+                match autodev_executor::composite_pr::open_draft_pr(&composite_task, &repo, "main", &state.vcs).await {
                     Ok(pr) => {
-                        tracing::info!(
-                            "Created DRAFT PR #{} for composite task {} - awaiting user review",
-                            pr.number,
-                            composite_task.id
-                        );
+                        if autodev_executor::pr_descriptions_enabled() {
+                            describe_in_background(
+                                &state,
+                                repo.clone(),
+                                pr.number,
+                                composite_task.title.clone(),
+                                composite_task.description.clone(),
+                                autodev_executor::composite_pr::footer(&composite_task),
+                            );
+                        }
+
+                        scan_in_background(&state, repo.clone(), composite_task.clone(), pr.number);
+
+                        if let Some(pr_url) = pr.url {
+                            autodev_notify::notify(NotificationEvent::PrAwaitingReview {
+                                task_id: composite_task.id.clone(),
+                                title: composite_task.title.clone(),
+                                pr_url,
+                            });
+                        }
                     }
                     Err(e) => {
                         tracing::error!(
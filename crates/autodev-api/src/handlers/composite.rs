@@ -1,10 +1,11 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use serde::{Deserialize, Serialize};
 
+use crate::auth::Caller;
 use crate::state::ApiState;
 use autodev_github::Repository;
 
@@ -15,7 +16,62 @@ pub struct CreateCompositeTaskRequest {
     pub title: String,
     pub description: String,
     pub composite_prompt: String,
-    pub auto_approve: bool,
+    /// Approve every batch (the repository's .autodev.yml or off when unset)
+    #[serde(default)]
+    pub auto_approve: Option<bool>,
+    /// Start execution right away; otherwise the decomposition waits for
+    /// `POST /composite-tasks/:id/approve`
+    #[serde(default)]
+    pub execute: bool,
+    /// Cap on subtasks running at once (AUTODEV_MAX_PARALLEL_TASKS still applies)
+    #[serde(default)]
+    pub max_parallel_tasks: Option<usize>,
+    /// How subtask PRs are merged (the repository's .autodev.yml or merge when unset)
+    #[serde(default)]
+    pub merge_method: Option<autodev_core::MergeMethod>,
+    /// What happens to the dependents of a failed subtask (block when unset)
+    #[serde(default)]
+    pub failure_policy: autodev_core::FailurePolicy,
+    /// Where the subtasks run (the repository's .autodev.yml or the server default when unset)
+    #[serde(default)]
+    pub execution_backend: Option<autodev_core::ExecutionBackend>,
+    /// Critique the decomposition with a second AI pass
+    #[serde(default)]
+    pub review_plan: bool,
+    /// Hold the plan for review and turn auto-approve off when the critique's
+    /// confidence is below this (implies `review_plan`)
+    #[serde(default)]
+    pub min_plan_confidence: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RollbackRequest {
+    /// Delete the parent branch instead of reverting merged subtask PRs on it
+    #[serde(default)]
+    pub delete_parent_branch: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackResponse {
+    pub composite_task_id: String,
+    pub closed_prs: Vec<u64>,
+    pub reverted_prs: Vec<u64>,
+    pub revert_commits: Vec<String>,
+    pub parent_branch_deleted: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CancelCompositeRequest {
+    /// Close the open subtask PRs with a comment explaining the cancellation
+    #[serde(default)]
+    pub close_prs: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancelCompositeResponse {
+    pub composite_task_id: String,
+    pub cancelled_tasks: Vec<String>,
+    pub closed_prs: Vec<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,6 +80,38 @@ pub struct CompositeTaskResponse {
     pub title: String,
     pub subtasks: Vec<crate::handlers::task::TaskResponse>,
     pub batches: Vec<Vec<String>>, // Task IDs in each batch
+    pub review_state: String,
+    pub estimated_minutes: Option<u32>,
+    /// Batches (1-based) approved to start; the first batch never needs approval
+    pub approved_batches: Vec<usize>,
+    pub max_parallel_tasks: Option<usize>,
+    pub merge_method: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub failure_policy: autodev_core::FailurePolicy,
+    #[serde(default)]
+    pub execution_backend: Option<String>,
+    /// AI critique of the decomposition, when one was requested
+    #[serde(default)]
+    pub plan_review: Option<autodev_ai::PlanReview>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompositeProgressResponse {
+    pub id: String,
+    pub review_state: String,
+    /// Completed subtasks in percent
+    pub progress: f32,
+    pub batches: Vec<autodev_core::BatchProgress>,
+    /// Branch the subtask PRs are merged into
+    pub parent_branch: String,
+    /// Draft PR of the parent branch, opened once every subtask is merged
+    pub draft_pr_url: Option<String>,
+    /// Completed, failed, skipped and blocked subtasks
+    pub summary: autodev_core::CompletionSummary,
+    /// Nothing is left to run, but some subtasks failed or were skipped
+    pub partial: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -31,31 +119,71 @@ pub struct ErrorResponse {
     pub error: String,
 }
 
-/// Create a composite task and execute it immediately
+/// Create a composite task
+///
+/// The decomposition is held for review unless `execute` is set, so no branch
+/// or workflow is created before the plan has been inspected and approved.
 pub async fn create_composite_task(
     State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
     Json(payload): Json<CreateCompositeTaskRequest>,
 ) -> Result<Json<CompositeTaskResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if let Err(error) = caller.check_access(&payload.repository_owner) {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse { error })));
+    }
+
     let repo = Repository::new(
         payload.repository_owner.clone(),
         payload.repository_name.clone(),
     );
 
+    let config = state.vcs.load_repo_config(&repo).await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to load {} of {}: {}", autodev_github::CONFIG_FILE, repo.full_name(), e);
+        autodev_github::RepoConfig::default()
+    });
+
     // Use AI to decompose the task
-    let decomposer = autodev_ai::TaskDecomposer::new(state.ai_agent.clone());
-
-    match decomposer.decompose(&payload.composite_prompt).await {
-        Ok(subtasks) => {
-            match state
-                .engine
-                .create_composite_task(
-                    payload.title,
-                    payload.description,
-                    subtasks,
-                    payload.auto_approve,
-                )
-                .await
-            {
+    let examples = crate::handlers::examples::repository_examples(&state.db, &repo).await;
+    let decomposer = autodev_ai::TaskDecomposer::new(state.ai_agent.clone())
+        .with_protected_paths(config.protected_paths.clone())
+        .with_custom_examples(examples)
+        .with_vector_store(autodev_executor::example_vector_store(&state.db));
+
+    match decomposer.plan(&payload.composite_prompt).await {
+        Ok(plan) => {
+            let subtasks = decomposer.convert_to_tasks(plan.tasks.clone());
+            let plan_review = if payload.review_plan || payload.min_plan_confidence.is_some() {
+                review_plan(&state, &payload.composite_prompt, &plan, &subtasks).await
+            } else {
+                None
+            };
+
+            // A doubtful plan waits for a human instead of running on its own
+            let confident = match (&plan_review, payload.min_plan_confidence) {
+                (Some(review), Some(min_confidence)) => review.is_confident(min_confidence),
+                _ => true,
+            };
+            if !confident {
+                tracing::warn!(
+                    "Plan review confidence is below {:?}; holding \"{}\" for review",
+                    payload.min_plan_confidence,
+                    payload.title
+                );
+            }
+            let execute = payload.execute && confident;
+
+            let composite_task =
+                autodev_core::CompositeTask::new(payload.title, payload.description, subtasks)
+                    .with_auto_approve(autodev_executor::repo_auto_approve(payload.auto_approve, &config) && confident)
+                    .with_max_parallel_tasks(payload.max_parallel_tasks)
+                    .with_merge_method(payload.merge_method)
+                    .with_failure_policy(payload.failure_policy)
+                    .with_execution_backend(payload.execution_backend)
+                    .with_review_required(!execute)
+                    .with_created_by(caller.user);
+            let composite_task = autodev_executor::with_repo_defaults(&composite_task, &config);
+
+            match state.engine.register_composite_task(composite_task).await {
                 Ok(composite_task) => {
                     // Save to database if available
                     if let Some(ref db) = state.db {
@@ -67,53 +195,27 @@ pub async fn create_composite_task(
                         }
                     }
 
-                    // Execute composite task immediately in background
-                    let composite_clone = composite_task.clone();
-                    let repo_clone = repo.clone();
-                    let engine_clone = state.engine.clone();
-                    let github_clone = state.github_client.clone();
-                    let db_clone = state.db.clone();
-                    let use_local = state.use_local_executor;
-                    let docker_exec = state.docker_executor.clone();
-
-                    tokio::spawn(async move {
-                        if use_local && docker_exec.is_some() {
-                            // Use Docker-based local execution
-                            tracing::info!("🐳 Executing composite task with Docker executor");
-                            let executor = docker_exec.unwrap();
-                            if let Err(e) = autodev_executor::execute_composite_task_docker(
-                                &composite_clone,
-                                &repo_clone,
-                                &executor,
-                                &engine_clone,
-                                &github_clone,
-                                &db_clone,
-                            ).await {
-                                tracing::error!("Failed to execute composite task {}: {}", composite_clone.id, e);
-                            }
-                        } else {
-                            // Use GitHub Actions execution
-                            tracing::info!("☁️  Executing composite task with GitHub Actions");
-                            if let Err(e) = autodev_executor::execute_composite_task(
-                                &composite_clone,
-                                &repo_clone,
-                                &engine_clone,
-                                &github_clone,
-                                &db_clone,
-                            ).await {
-                                tracing::error!("Failed to execute composite task {}: {}", composite_clone.id, e);
-                            }
-                        }
-                    });
+                    if execute {
+                        spawn_composite_execution(&state, composite_task.clone(), repo);
+                    } else {
+                        tracing::info!(
+                            "Composite task {} is waiting for review before execution",
+                            composite_task.id
+                        );
+                    }
 
-                    Ok(Json(composite_task_to_response(&composite_task)))
+                    let mut response = composite_task_to_response(&composite_task);
+                    response.plan_review = plan_review;
+                    Ok(Json(response))
+                }
+                Err(e) => {
+                    // The decomposition is unusable as is; the error lists the cycle's task IDs
+                    let status = match e {
+                        autodev_core::Error::DependencyCycle(_) => StatusCode::UNPROCESSABLE_ENTITY,
+                        _ => StatusCode::INTERNAL_SERVER_ERROR,
+                    };
+                    Err((status, Json(ErrorResponse { error: e.to_string() })))
                 }
-                Err(e) => Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: e.to_string(),
-                    }),
-                )),
             }
         }
         Err(e) => Err((
@@ -125,6 +227,324 @@ pub async fn create_composite_task(
     }
 }
 
+/// Approve a composite task held for review and start executing it
+///
+/// A composite task held for a fix of its parent branch is already executing;
+/// approving it lets that execution re-run the tests and continue.
+pub async fn approve_composite_task(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<CompositeTaskResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let fix_approved = state
+        .engine
+        .get_composite_task(&task_id)
+        .await
+        .is_some_and(|c| c.is_fix_required());
+
+    let composite_task = state
+        .engine
+        .approve_composite_task(&task_id)
+        .await
+        .map_err(|e| {
+            let status = match e {
+                autodev_core::Error::TaskNotFound(_) => StatusCode::NOT_FOUND,
+                autodev_core::Error::InvalidTaskState(_) => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(ErrorResponse { error: e.to_string() }))
+        })?;
+
+    if !fix_approved {
+        let repo = composite_repository(&state, &task_id).await;
+        spawn_composite_execution(&state, composite_task.clone(), repo);
+    }
+
+    Ok(Json(composite_task_to_response(&composite_task)))
+}
+
+/// Approve batch `batch` (1-based) of a composite task so execution can continue
+pub async fn approve_batch(
+    State(state): State<ApiState>,
+    Path((task_id, batch)): Path<(String, usize)>,
+) -> Result<Json<CompositeTaskResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let composite_task = state
+        .engine
+        .approve_batch(&task_id, batch)
+        .await
+        .map_err(|e| {
+            let status = match e {
+                autodev_core::Error::TaskNotFound(_) => StatusCode::NOT_FOUND,
+                autodev_core::Error::InvalidTaskState(_) => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(ErrorResponse { error: e.to_string() }))
+        })?;
+
+    Ok(Json(composite_task_to_response(&composite_task)))
+}
+
+/// Pause a composite task: no further subtask starts until it is resumed
+///
+/// Subtasks already running finish; their PRs are still merged.
+pub async fn pause_composite_task(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<CompositeTaskResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let composite_task = state
+        .engine
+        .pause_composite_task(&task_id)
+        .await
+        .map_err(|e| {
+            let status = match e {
+                autodev_core::Error::TaskNotFound(_) => StatusCode::NOT_FOUND,
+                autodev_core::Error::InvalidTaskState(_) => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(ErrorResponse { error: e.to_string() }))
+        })?;
+
+    Ok(Json(composite_task_to_response(&composite_task)))
+}
+
+/// Resume a paused composite task, or a stopped one from its first unfinished batch
+///
+/// A paused composite task continues where its execution waits. For a
+/// stopped one, subtasks with merged PRs are skipped; failed or cancelled
+/// ones run again.
+pub async fn resume_composite_task(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<CompositeTaskResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if state
+        .engine
+        .get_composite_task(&task_id)
+        .await
+        .is_some_and(|c| c.is_paused())
+    {
+        let composite_task = state
+            .engine
+            .unpause_composite_task(&task_id)
+            .await
+            .map_err(|e| (StatusCode::CONFLICT, Json(ErrorResponse { error: e.to_string() })))?;
+        return Ok(Json(composite_task_to_response(&composite_task)));
+    }
+
+    let repo = composite_repository(&state, &task_id).await;
+
+    let composite_task = autodev_executor::prepare_composite_resume(
+        &task_id,
+        &repo,
+        &state.engine,
+        &state.vcs,
+        &state.db,
+    )
+    .await
+    .map_err(|e| (super::error_status(&e), Json(ErrorResponse { error: e.to_string() })))?;
+
+    spawn_composite_execution(&state, composite_task.clone(), repo);
+
+    Ok(Json(composite_task_to_response(&composite_task)))
+}
+
+/// Cancel a composite task: no further batch starts, in-flight subtask
+/// workflow runs or containers are stopped and unfinished subtasks cancelled
+pub async fn cancel_composite_task(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+    payload: Option<Json<CancelCompositeRequest>>,
+) -> Result<Json<CancelCompositeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let Json(payload) = payload.unwrap_or_default();
+    let repo = composite_repository(&state, &task_id).await;
+
+    let local_exec = state
+        .local_executor
+        .as_ref()
+        .filter(|_| state.use_local_executor);
+
+    let report = autodev_executor::cancel_composite_task(
+        &task_id,
+        &repo,
+        &state.engine,
+        &state.vcs,
+        &state.db,
+        local_exec,
+        &autodev_executor::CancelOptions {
+            close_prs: payload.close_prs,
+        },
+    )
+    .await
+    .map_err(|e| (super::error_status(&e), Json(ErrorResponse { error: e.to_string() })))?;
+
+    Ok(Json(CancelCompositeResponse {
+        composite_task_id: task_id,
+        cancelled_tasks: report.cancelled_tasks,
+        closed_prs: report.closed_prs,
+    }))
+}
+
+/// Split a subtask that turned out too big into smaller subtasks
+///
+/// The decomposer runs on the subtask's prompt; the resulting tasks replace the
+/// subtask in its composite task, taking over its dependencies and dependents.
+pub async fn redecompose_subtask(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Path(task_id): Path<String>,
+) -> Result<Json<CompositeTaskResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let task = state.engine.get_task(&task_id).await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("Task not found: {}", task_id),
+            }),
+        )
+    })?;
+
+    let (repo_owner, repo_name) = match state.db {
+        Some(ref db) => match db.get_task(&task_id).await {
+            Ok(Some(record)) => (record.repository_owner, record.repository_name),
+            _ => ("myorg".to_string(), "myproject".to_string()),
+        },
+        None => ("myorg".to_string(), "myproject".to_string()),
+    };
+    let repo = Repository::new(repo_owner, repo_name);
+
+    if let Err(error) = caller.check_access(&repo.owner) {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse { error })));
+    }
+
+    let config = state.vcs.load_repo_config(&repo).await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to load {} of {}: {}", autodev_github::CONFIG_FILE, repo.full_name(), e);
+        autodev_github::RepoConfig::default()
+    });
+
+    let examples = crate::handlers::examples::repository_examples(&state.db, &repo).await;
+    let decomposer = autodev_ai::TaskDecomposer::new(state.ai_agent.clone())
+        .with_protected_paths(config.protected_paths.clone())
+        .with_custom_examples(examples)
+        .with_vector_store(autodev_executor::example_vector_store(&state.db));
+
+    let replacements = decomposer.decompose(&task.prompt).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to decompose task: {}", e),
+            }),
+        )
+    })?;
+
+    let composite_task = state
+        .engine
+        .split_subtask(&task_id, replacements)
+        .await
+        .map_err(|e| {
+            let status = match e {
+                autodev_core::Error::TaskNotFound(_) => StatusCode::NOT_FOUND,
+                autodev_core::Error::InvalidTaskState(_) => StatusCode::CONFLICT,
+                autodev_core::Error::DependencyCycle(_) => StatusCode::UNPROCESSABLE_ENTITY,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(ErrorResponse { error: e.to_string() }))
+        })?;
+
+    // The engine's store writes the new subtasks without their repository
+    if let Some(ref db) = state.db {
+        if let Err(e) = db.save_composite_task(&composite_task, &repo.owner, &repo.name).await {
+            tracing::error!("Failed to save composite task to database: {}", e);
+        }
+    }
+
+    Ok(Json(composite_task_to_response(&composite_task)))
+}
+
+/// Roll back a failed composite task: close open subtask PRs and revert
+/// merged ones on the parent branch (or delete the parent branch)
+pub async fn rollback_composite_task(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+    payload: Option<Json<RollbackRequest>>,
+) -> Result<Json<RollbackResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let Json(payload) = payload.unwrap_or_default();
+
+    let composite_task = state
+        .engine
+        .get_composite_task(&task_id)
+        .await
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: format!("Composite task not found: {}", task_id),
+                }),
+            )
+        })?;
+
+    let repo = composite_repository(&state, &task_id).await;
+
+    let report = autodev_executor::rollback_composite_task(
+        &composite_task,
+        &repo,
+        &state.engine,
+        &state.vcs,
+        &state.db,
+        &autodev_executor::RollbackOptions {
+            delete_parent_branch: payload.delete_parent_branch,
+        },
+    )
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: e.to_string() }),
+        )
+    })?;
+
+    Ok(Json(RollbackResponse {
+        composite_task_id: task_id,
+        closed_prs: report.closed_prs,
+        reverted_prs: report.reverted_prs,
+        revert_commits: report.revert_commits,
+        parent_branch_deleted: report.parent_branch_deleted,
+    }))
+}
+
+/// Run a composite task in the background on its execution backend
+pub(crate) fn spawn_composite_execution(
+    state: &ApiState,
+    composite_task: autodev_core::CompositeTask,
+    repo: Repository,
+) {
+    let engine = state.engine.clone();
+    let vcs = state.vcs.clone();
+    let ai_agent = state.ai_agent.clone();
+    let db = state.db.clone();
+    let router = state.router.clone();
+    let notifier = state.notifier.clone();
+
+    state.background.spawn(async move {
+        if let Err(e) = router
+            .execute_composite_task(&composite_task, &repo, &engine, &vcs, &ai_agent, &db, &notifier)
+            .await
+        {
+            tracing::error!("Failed to execute composite task {}: {}", composite_task.id, e);
+        }
+    });
+}
+
+/// Repository a composite task was created for
+async fn composite_repository(state: &ApiState, task_id: &str) -> Repository {
+    let (repo_owner, repo_name) = if let Some(ref db) = state.db {
+        match db.get_composite_task(task_id).await {
+            Ok(Some(record)) => (record.repository_owner, record.repository_name),
+            _ => ("myorg".to_string(), "myproject".to_string()),
+        }
+    } else {
+        ("myorg".to_string(), "myproject".to_string())
+    };
+
+    Repository::new(repo_owner, repo_name)
+}
+
 /// Get composite task
 pub async fn get_composite_task(
     State(state): State<ApiState>,
@@ -138,21 +558,31 @@ pub async fn get_composite_task(
                 if let Ok(Some(record)) = db.get_composite_task(&task_id).await {
                     // Get subtasks
                     if let Ok(subtasks) = db.get_composite_subtasks(&task_id).await {
-                        let subtask_responses: Vec<crate::handlers::task::TaskResponse> =
-                            subtasks.iter().map(|t| crate::handlers::task::TaskResponse {
-                                id: t.id.clone(),
-                                title: t.title.clone(),
-                                status: t.status.clone(),
-                                pr_url: t.pr_url.clone(),
-                                created_at: t.created_at.to_rfc3339(),
-                                completed_at: t.completed_at.map(|dt| dt.to_rfc3339()),
-                            }).collect();
+                        let subtask_responses: Vec<crate::handlers::task::TaskResponse> = subtasks
+                            .into_iter()
+                            .map(crate::handlers::task::record_to_response)
+                            .collect();
 
                         return Ok(Json(CompositeTaskResponse {
                             id: record.id,
                             title: record.title,
                             subtasks: subtask_responses,
                             batches: vec![],
+                            review_state: record.review_state,
+                            estimated_minutes: None,
+                            approved_batches: record
+                                .approved_batches
+                                .iter()
+                                .filter_map(|batch| usize::try_from(*batch).ok())
+                                .collect(),
+                            max_parallel_tasks: record
+                                .max_parallel_tasks
+                                .and_then(|n| usize::try_from(n).ok()),
+                            merge_method: record.merge_method,
+                            created_by: record.created_by,
+                            failure_policy: record.failure_policy.parse().unwrap_or_default(),
+                            execution_backend: record.execution_backend,
+                            plan_review: None,
                         }));
                     }
                 }
@@ -168,6 +598,117 @@ pub async fn get_composite_task(
     }
 }
 
+/// Live progress of a composite task: the state of each batch, the parent
+/// branch and its draft PR
+pub async fn get_composite_progress(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<CompositeProgressResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let Some(composite_task) = load_composite_task(&state, &task_id).await? else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Composite task not found".to_string(),
+            }),
+        ));
+    };
+
+    // Draft PR은 모든 하위 작업이 병합된 뒤에만 생성됨
+    let parent_branch = format!("autodev/{}", composite_task.id);
+    let mut draft_pr_url = None;
+    if composite_task.is_completed() {
+        let repo = composite_repository(&state, &task_id).await;
+        match state.vcs.find_merge_request_by_branch(&repo, &parent_branch).await {
+            Ok(pr) => draft_pr_url = pr.map(|number| state.vcs.merge_request_url(&repo, number)),
+            Err(e) => tracing::warn!("Failed to look up the draft PR of {}: {}", parent_branch, e),
+        }
+    }
+
+    let summary = composite_task.completion_summary();
+    Ok(Json(CompositeProgressResponse {
+        id: composite_task.id.clone(),
+        review_state: format!("{:?}", composite_task.review_state),
+        progress: composite_task.get_progress(),
+        batches: composite_task.batch_progress(),
+        parent_branch,
+        draft_pr_url,
+        partial: summary.is_partial(),
+        summary,
+    }))
+}
+
+/// When each subtask waited, ran and finished, and where batches began and ended
+pub async fn get_composite_timeline(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<autodev_core::Timeline>, (StatusCode, Json<ErrorResponse>)> {
+    let Some(composite_task) = load_composite_task(&state, &task_id).await? else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Composite task not found".to_string(),
+            }),
+        ));
+    };
+
+    let events = match state.db {
+        Some(ref db) => db
+            .get_composite_execution_logs(&task_id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e.to_string() })))?
+            .into_iter()
+            .map(|log| autodev_core::LoggedEvent {
+                task_id: log.task_id,
+                event_type: log.event_type,
+                timestamp: log.timestamp,
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(Json(autodev_core::Timeline::build(&composite_task, &events)))
+}
+
+/// A composite task with the current state of its subtasks, from the engine
+/// or else the database
+async fn load_composite_task(
+    state: &ApiState,
+    task_id: &str,
+) -> Result<Option<autodev_core::CompositeTask>, (StatusCode, Json<ErrorResponse>)> {
+    let internal_error = |e: String| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e }));
+
+    let composite_task = match state.engine.get_composite_task(task_id).await {
+        Some(mut composite_task) => {
+            // CompositeTask의 하위 작업 사본은 갱신되지 않으므로 엔진의 상태를 사용
+            for subtask in &mut composite_task.subtasks {
+                if let Some(task) = state.engine.get_task(&subtask.id).await {
+                    *subtask = task;
+                }
+            }
+            Some(composite_task)
+        }
+        None => match state.db {
+            Some(ref db) => match db.get_composite_task(task_id).await.map_err(|e| internal_error(e.to_string()))? {
+                Some(record) => {
+                    let subtasks = db
+                        .get_composite_subtasks(task_id)
+                        .await
+                        .map_err(|e| internal_error(e.to_string()))?
+                        .iter()
+                        .map(|record| record.to_task())
+                        .collect::<autodev_core::Result<Vec<_>>>()
+                        .map_err(|e| internal_error(e.to_string()))?;
+                    Some(record.to_composite_task(subtasks).map_err(|e| internal_error(e.to_string()))?)
+                }
+                None => None,
+            },
+            None => None,
+        },
+    };
+
+    Ok(composite_task)
+}
+
 /// Execute composite task
 pub async fn execute_composite_task(
     State(state): State<ApiState>,
@@ -186,27 +727,31 @@ pub async fn execute_composite_task(
         }
     };
 
-    // Get repository info
-    let (repo_owner, repo_name) = if let Some(ref db) = state.db {
-        match db.get_composite_task(&task_id).await {
-            Ok(Some(record)) => (record.repository_owner, record.repository_name),
-            _ => ("myorg".to_string(), "myproject".to_string()),
-        }
-    } else {
-        ("myorg".to_string(), "myproject".to_string())
-    };
+    if composite_task.is_pending_review() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!(
+                    "Composite task {} is pending review; approve it first",
+                    task_id
+                ),
+            }),
+        ));
+    }
 
-    let repo = Repository::new(repo_owner, repo_name);
+    // Get repository info
+    let repo = composite_repository(&state, &task_id).await;
 
     // Execute composite task asynchronously
     let engine = state.engine.clone();
     let composite_clone = composite_task.clone();
     let repo_clone = repo.clone();
-    let github = state.github_client.clone();
+    let vcs = state.vcs.clone();
     let ai = state.ai_agent.clone();
     let db = state.db.clone();
+    let limiter = autodev_executor::TaskLimiter::for_composite(&composite_task);
 
-    tokio::spawn(async move {
+    state.background.spawn(async move {
         let batches = composite_clone.get_parallel_batches();
 
         for (i, batch) in batches.iter().enumerate() {
@@ -224,19 +769,57 @@ pub async fn execute_composite_task(
                 let engine = engine.clone();
                 let task = task.clone();
                 let repo = repo_clone.clone();
-                let github = github.clone();
+                let vcs = vcs.clone();
                 let ai = ai.clone();
+                let db = db.clone();
+                let limiter = limiter.clone();
 
                 let handle = tokio::spawn(async move {
-                    // Execute task with AI
-                    if let Ok(result) = ai.execute_task(&task, &repo.full_name()).await {
-                        // Trigger GitHub workflow
+                    let _permit = limiter.acquire().await;
+
+                    if let Err(e) = engine
+                        .update_task_status(&task.id, autodev_core::TaskStatus::InProgress, None)
+                        .await
+                    {
+                        tracing::error!("Failed to start task {}: {}", task.id, e);
+                        return;
+                    }
+
+                    // Build on what the task's dependencies changed
+                    let task = autodev_core::Task {
+                        prompt: autodev_executor::prompt_with_dependencies(&task, &db).await,
+                        ..task
+                    };
+
+                    // Execute task with AI (a task over budget is paused)
+                    let result = autodev_executor::run_within_budget(
+                        &task,
+                        &engine,
+                        &db,
+                        ai.execute_task(&task, &repo.full_name()),
+                    )
+                    .await;
+                    if let Ok(result) = result {
+                        autodev_executor::artifacts::save_artifact(
+                            &db,
+                            autodev_db::TaskArtifact {
+                                task_id: task.id.clone(),
+                                title: task.title.clone(),
+                                summary: result.output.clone(),
+                                files_changed: result.files_changed.clone(),
+                                created_at: chrono::Utc::now(),
+                                ..Default::default()
+                            },
+                        )
+                        .await;
+
+                        // Trigger CI pipeline
                         let mut inputs = std::collections::HashMap::new();
                         inputs.insert("task_id".to_string(), task.id.clone());
                         inputs.insert("branch".to_string(), result.pr_branch);
                         inputs.insert("commit_message".to_string(), result.commit_message);
 
-                        let _ = github.trigger_workflow(&repo, "autodev.yml", inputs).await;
+                        let _ = vcs.trigger_pipeline(&repo, "autodev.yml", &repo.branch, inputs).await;
 
                         // Update status
                         let _ = engine
@@ -300,5 +883,32 @@ fn composite_task_to_response(composite_task: &autodev_core::CompositeTask) -> C
         title: composite_task.title.clone(),
         subtasks,
         batches,
+        review_state: format!("{:?}", composite_task.review_state),
+        estimated_minutes: composite_task.estimated_minutes(),
+        approved_batches: composite_task.approved_batches.clone(),
+        max_parallel_tasks: composite_task.max_parallel_tasks,
+        merge_method: composite_task.merge_method.map(|m| m.to_string()),
+        created_by: composite_task.created_by.clone(),
+        failure_policy: composite_task.failure_policy,
+        execution_backend: composite_task.execution_backend.map(|b| b.to_string()),
+        plan_review: None,
+    }
+}
+
+/// Critique a decomposition with a second AI pass, None when the review fails
+///
+/// Finding task IDs refer to `tasks`, converted from `plan` in the same order.
+pub(crate) async fn review_plan(
+    state: &ApiState,
+    request: &str,
+    plan: &autodev_ai::TaskDecompositionResponse,
+    tasks: &[autodev_core::Task],
+) -> Option<autodev_ai::PlanReview> {
+    match autodev_ai::PlanReviewer::new(state.ai_agent.clone()).review(request, plan).await {
+        Ok(review) => Some(review.with_task_ids(plan, tasks)),
+        Err(e) => {
+            tracing::warn!("Plan review failed: {}", e);
+            None
+        }
     }
 }
\ No newline at end of file
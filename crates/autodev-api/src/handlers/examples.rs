@@ -0,0 +1,238 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::auth::Caller;
+use crate::state::ApiState;
+use autodev_ai::FewShotExample;
+use autodev_db::{Database, ExampleRecord, NewExample};
+use autodev_github::Repository;
+
+/// Few-shot example for task decomposition
+///
+/// Examples without a repository are used for every repository.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExampleRequest {
+    #[serde(default)]
+    pub repository_owner: Option<String>,
+    #[serde(default)]
+    pub repository_name: Option<String>,
+    pub domain: String,
+    pub user_prompt: String,
+    /// Decomposition in the TaskDecompositionResponse format
+    pub assistant_response: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExampleResponse {
+    pub id: i64,
+    pub repository_owner: Option<String>,
+    pub repository_name: Option<String>,
+    pub domain: String,
+    pub user_prompt: String,
+    pub assistant_response: serde_json::Value,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListExamplesQuery {
+    /// With `repo`: the examples used for that repository
+    pub owner: Option<String>,
+    pub repo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+type HandlerError = (StatusCode, Json<ErrorResponse>);
+
+fn error(status: StatusCode, error: String) -> HandlerError {
+    (status, Json(ErrorResponse { error }))
+}
+
+fn require_db(state: &ApiState) -> Result<&Arc<Database>, HandlerError> {
+    state
+        .db
+        .as_ref()
+        .ok_or_else(|| error(StatusCode::SERVICE_UNAVAILABLE, "Examples require a database".to_string()))
+}
+
+fn db_error(e: autodev_db::Error) -> HandlerError {
+    error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e))
+}
+
+/// Keys limited to an organization only change examples of its repositories
+fn check_owner(caller: &Caller, owner: Option<&str>) -> Result<(), HandlerError> {
+    match owner {
+        Some(owner) => caller.check_access(owner).map_err(|e| error(StatusCode::FORBIDDEN, e)),
+        None if caller.org.is_some() => Err(error(
+            StatusCode::FORBIDDEN,
+            "Shared examples can only be changed with keys not limited to an organization".to_string(),
+        )),
+        None => Ok(()),
+    }
+}
+
+impl ExampleRequest {
+    /// Validate the example the same way the decomposer will load it
+    fn to_new_example(&self) -> Result<NewExample, HandlerError> {
+        if self.repository_owner.is_some() != self.repository_name.is_some() {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "repository_owner and repository_name must be set together".to_string(),
+            ));
+        }
+
+        let assistant_response = self.assistant_response.to_string();
+        FewShotExample::parse(&self.domain, &self.user_prompt, &assistant_response)
+            .map_err(|e| error(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+        Ok(NewExample {
+            repository_owner: self.repository_owner.clone(),
+            repository_name: self.repository_name.clone(),
+            domain: self.domain.clone(),
+            user_prompt: self.user_prompt.clone(),
+            assistant_response,
+        })
+    }
+}
+
+fn example_to_response(record: ExampleRecord) -> ExampleResponse {
+    let assistant_response = serde_json::from_str(&record.assistant_response)
+        .unwrap_or(serde_json::Value::String(record.assistant_response));
+
+    ExampleResponse {
+        id: record.id,
+        repository_owner: record.repository_owner,
+        repository_name: record.repository_name,
+        domain: record.domain,
+        user_prompt: record.user_prompt,
+        assistant_response,
+        created_at: record.created_at.to_rfc3339(),
+        updated_at: record.updated_at.to_rfc3339(),
+    }
+}
+
+/// List examples, all of them or the ones used for `owner`/`repo`
+pub async fn list_examples(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Query(query): Query<ListExamplesQuery>,
+) -> Result<Json<Vec<ExampleResponse>>, HandlerError> {
+    let db = require_db(&state)?;
+
+    let repository = match (query.owner.as_deref(), query.repo.as_deref()) {
+        (Some(owner), Some(repo)) => Some((owner, repo)),
+        (None, None) => None,
+        _ => {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "owner and repo must be given together".to_string(),
+            ))
+        }
+    };
+
+    let records = db.list_examples(repository).await.map_err(db_error)?;
+    Ok(Json(
+        records
+            .into_iter()
+            .filter(|record| {
+                record
+                    .repository_owner
+                    .as_deref()
+                    .is_none_or(|owner| caller.check_access(owner).is_ok())
+            })
+            .map(example_to_response)
+            .collect(),
+    ))
+}
+
+pub async fn create_example(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Json(payload): Json<ExampleRequest>,
+) -> Result<(StatusCode, Json<ExampleResponse>), HandlerError> {
+    let db = require_db(&state)?;
+    check_owner(&caller, payload.repository_owner.as_deref())?;
+    let example = payload.to_new_example()?;
+
+    let record = db.create_example(&example).await.map_err(db_error)?;
+    tracing::info!("Created few-shot example {} ({})", record.id, record.domain);
+
+    Ok((StatusCode::CREATED, Json(example_to_response(record))))
+}
+
+pub async fn get_example(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ExampleResponse>, HandlerError> {
+    let db = require_db(&state)?;
+
+    match db.get_example(id).await.map_err(db_error)? {
+        Some(record) => Ok(Json(example_to_response(record))),
+        None => Err(error(StatusCode::NOT_FOUND, format!("Example not found: {}", id))),
+    }
+}
+
+pub async fn update_example(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+    Extension(caller): Extension<Caller>,
+    Json(payload): Json<ExampleRequest>,
+) -> Result<Json<ExampleResponse>, HandlerError> {
+    let db = require_db(&state)?;
+    check_owner(&caller, payload.repository_owner.as_deref())?;
+    let example = payload.to_new_example()?;
+
+    match db.update_example(id, &example).await.map_err(db_error)? {
+        Some(record) => Ok(Json(example_to_response(record))),
+        None => Err(error(StatusCode::NOT_FOUND, format!("Example not found: {}", id))),
+    }
+}
+
+pub async fn delete_example(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, HandlerError> {
+    let db = require_db(&state)?;
+
+    if db.delete_example(id).await.map_err(db_error)? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(error(StatusCode::NOT_FOUND, format!("Example not found: {}", id)))
+    }
+}
+
+/// Custom examples for decomposing tasks of `repo`
+///
+/// Records that no longer validate are skipped, so a bad example never
+/// blocks decomposition.
+pub async fn repository_examples(db: &Option<Arc<Database>>, repo: &Repository) -> Vec<FewShotExample> {
+    let Some(db) = db else {
+        return Vec::new();
+    };
+
+    let records = match db.list_examples(Some((&repo.owner, &repo.name))).await {
+        Ok(records) => records,
+        Err(e) => {
+            tracing::warn!("Failed to load few-shot examples of {}: {}", repo.full_name(), e);
+            return Vec::new();
+        }
+    };
+
+    records
+        .into_iter()
+        .filter_map(|record| {
+            FewShotExample::parse(&record.domain, &record.user_prompt, &record.assistant_response)
+                .map_err(|e| tracing::warn!("Skipping few-shot example {}: {}", record.id, e))
+                .ok()
+        })
+        .collect()
+}
@@ -1,10 +1,218 @@
+use axum::extract::State;
+use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
+use serde::Serialize;
 use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-pub async fn health_check() -> impl IntoResponse {
+use crate::state::ApiState;
+
+/// Upper bound of each readiness check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long an AI credential check is reused (probes run every few seconds)
+const AI_CHECK_TTL: Duration = Duration::from_secs(300);
+
+/// How long the GitHub quota shown by `/health` is reused
+const RATE_LIMIT_TTL: Duration = Duration::from_secs(60);
+
+/// Last AI credential check
+static AI_CHECK: Mutex<Option<(Instant, DependencyCheck)>> = Mutex::new(None);
+
+/// Last GitHub quota shown by `/health`
+static RATE_LIMIT: Mutex<Option<(Instant, serde_json::Value)>> = Mutex::new(None);
+
+/// Liveness: the process answers; dependencies are checked by `/health/ready`
+pub async fn health_check(State(state): State<ApiState>) -> impl IntoResponse {
     Json(json!({
         "status": "healthy",
-        "service": "autodev-api"
+        "service": "autodev-api",
+        "github_rate_limit": github_rate_limit(&state).await
     }))
-}
\ No newline at end of file
+}
+
+/// GitHub quota, fetched at most once per [`RATE_LIMIT_TTL`] so that frequent
+/// liveness probes don't each call the GitHub API
+async fn github_rate_limit(state: &ApiState) -> serde_json::Value {
+    if let Some((checked_at, ref status)) = *RATE_LIMIT.lock().unwrap() {
+        if checked_at.elapsed() < RATE_LIMIT_TTL {
+            return status.clone();
+        }
+    }
+
+    // 쿼터 조회 실패는 서버 상태와 무관하므로 에러 메시지만 포함
+    let status = match tokio::time::timeout(CHECK_TIMEOUT, state.github_client.rate_limit_status()).await {
+        Ok(Ok(status)) => json!(status),
+        Ok(Err(e)) => json!({ "error": e.to_string() }),
+        Err(_) => json!({ "error": format!("No answer within {}s", CHECK_TIMEOUT.as_secs()) }),
+    };
+
+    *RATE_LIMIT.lock().unwrap() = Some((Instant::now(), status.clone()));
+    status
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Error,
+    /// Not configured or not checkable; does not affect readiness
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyCheck {
+    pub status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Extra information, e.g. the remaining GitHub quota
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl DependencyCheck {
+    fn skipped(message: &str) -> Self {
+        Self {
+            status: CheckStatus::Skipped,
+            latency_ms: None,
+            message: Some(message.to_string()),
+            details: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    /// "ready" or "not_ready"
+    pub status: &'static str,
+    pub checks: BTreeMap<String, DependencyCheck>,
+}
+
+/// Run `check` with a timeout, timing it
+async fn run_check<T, E: Display>(check: impl Future<Output = Result<T, E>>) -> (DependencyCheck, Option<T>) {
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, check).await;
+    let latency_ms = Some(started.elapsed().as_millis() as u64);
+
+    let (status, message, value) = match result {
+        Ok(Ok(value)) => (CheckStatus::Ok, None, Some(value)),
+        // Octocrab 에러는 backtrace까지 포함하므로 첫 줄만 사용
+        Ok(Err(e)) => (CheckStatus::Error, e.to_string().lines().next().map(str::to_string), None),
+        Err(_) => (
+            CheckStatus::Error,
+            Some(format!("No answer within {}s", CHECK_TIMEOUT.as_secs())),
+            None,
+        ),
+    };
+
+    let check = DependencyCheck {
+        status,
+        latency_ms,
+        message,
+        details: None,
+    };
+    (check, value)
+}
+
+async fn check_database(state: &ApiState) -> DependencyCheck {
+    match state.db {
+        Some(ref db) => run_check(db.ping()).await.0,
+        None => DependencyCheck::skipped("DATABASE_URL is not set"),
+    }
+}
+
+async fn check_executor(state: &ApiState) -> DependencyCheck {
+    if !state.use_local_executor {
+        return DependencyCheck::skipped("Tasks run in GitHub Actions");
+    }
+
+    match state.local_executor {
+        Some(ref executor) => run_check(executor.ping()).await.0,
+        None => DependencyCheck {
+            status: CheckStatus::Error,
+            latency_ms: None,
+            message: Some("The local executor failed to initialize".to_string()),
+            details: None,
+        },
+    }
+}
+
+/// The API answers and the credentials are accepted; an exhausted quota is
+/// reported in the details but does not make the server unready
+async fn check_github(client: &autodev_github::GitHubClient) -> DependencyCheck {
+    let (mut check, status) = run_check(client.rate_limit_status()).await;
+    check.details = status.map(|status| json!(status));
+    check
+}
+
+async fn check_ai(state: &ApiState) -> DependencyCheck {
+    if let Some((checked_at, ref check)) = *AI_CHECK.lock().unwrap() {
+        if checked_at.elapsed() < AI_CHECK_TTL {
+            return check.clone();
+        }
+    }
+
+    let (mut check, checked) = run_check(state.ai_agent.check_credentials()).await;
+    if checked == Some(false) {
+        check = DependencyCheck::skipped("The AI agent has no credentials that can be checked");
+    }
+    check.details = Some(json!({ "agent": state.ai_agent.agent_type().to_string(), "model": state.ai_agent.model() }));
+
+    *AI_CHECK.lock().unwrap() = Some((Instant::now(), check.clone()));
+    check
+}
+
+/// Readiness: actively checks the database, the local executor, GitHub and
+/// the AI credentials; 503 when any of them fails
+pub async fn readiness_check(State(state): State<ApiState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let github_checks = async {
+        let mut checks = Vec::new();
+        if state.vcs.name() != "github" {
+            checks.push(("github".to_string(), DependencyCheck::skipped("The VCS provider is not GitHub")));
+            return checks;
+        }
+
+        checks.push(("github".to_string(), check_github(&state.github_client).await));
+        for (org, client) in state.orgs.orgs() {
+            checks.push((format!("github:{}", org), check_github(client).await));
+        }
+        checks
+    };
+
+    let (database, executor, github, ai) = tokio::join!(
+        check_database(&state),
+        check_executor(&state),
+        github_checks,
+        check_ai(&state),
+    );
+
+    let mut checks = BTreeMap::from([
+        ("database".to_string(), database),
+        ("executor".to_string(), executor),
+        ("ai".to_string(), ai),
+    ]);
+    checks.extend(github);
+
+    let ready = checks.values().all(|check| check.status != CheckStatus::Error);
+    if !ready {
+        let failed: Vec<&str> = checks
+            .iter()
+            .filter(|(_, check)| check.status == CheckStatus::Error)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        tracing::warn!("Not ready: {} failed", failed.join(", "));
+    }
+
+    let (status, label) = match ready {
+        true => (StatusCode::OK, "ready"),
+        false => (StatusCode::SERVICE_UNAVAILABLE, "not_ready"),
+    };
+    (status, Json(ReadinessResponse { status: label, checks }))
+}
@@ -0,0 +1,387 @@
+//! `autodev` commands posted as issue comments
+
+use autodev_core::{Task, TaskStatus};
+use autodev_github::webhook::{CommentPayload, IssuePayload, RepositoryPayload};
+use autodev_github::{CommandOptions, GitHubUrls, IssueCommand, Repository};
+
+use crate::handlers::composite::spawn_composite_execution;
+use crate::state::ApiState;
+
+const USAGE: &str = "사용법:\n```\nautodev: <프롬프트> [--branch <브랜치>]\nautodev decompose: <프롬프트> [--branch <브랜치>] [--auto-approve]\nautodev status <task_id>\nautodev cancel <task_id>\nautodev retry [task_id]\n```";
+
+/// Description of tasks started from an issue; `autodev retry` only re-runs these
+const ISSUE_TASK_PREFIX: &str = "Triggered from Issue #";
+
+pub async fn handle_issue_comment(
+    state: ApiState,
+    comment: CommentPayload,
+    issue: IssuePayload,
+    repo: RepositoryPayload,
+) {
+    let github_repo = Repository::new(repo.owner.login.clone(), repo.name.clone());
+
+    let command = IssueCommand::parse(&comment.body);
+    if matches!(command, Ok(None)) {
+        return;
+    }
+
+    // Anyone can comment on a public issue; only maintainers may run commands
+    if let Some(reply) = rejection(&comment) {
+        tracing::warn!(
+            "Ignoring autodev command on issue #{} from {} ({})",
+            issue.number,
+            comment.author(),
+            comment.author_association
+        );
+        post_comment(&state, &github_repo, issue.number, &reply).await;
+        return;
+    }
+
+    let command = match command {
+        Ok(Some(command)) => command,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("Invalid autodev command on issue #{}: {}", issue.number, e);
+            let reply = format!("❌ AutoDev 오류: {}\n\n{}", e, USAGE);
+            post_comment(&state, &github_repo, issue.number, &reply).await;
+            return;
+        }
+    };
+
+    tracing::info!("Handling autodev command on issue #{}: {:?}", issue.number, command);
+
+    // Started tasks are reported on the issue by the issue sync
+    let reply = match command {
+        IssueCommand::Run { prompt, options } => run_prompt(&state, &github_repo, &issue, &prompt, &options).await,
+        IssueCommand::Decompose { prompt, options } => {
+            spawn_decompose(&state, github_repo.clone(), issue.number, prompt, options);
+            Some("🧩 AutoDev가 작업을 하위 작업으로 분해하고 있습니다. 분해가 끝나면 계획을 댓글로 남깁니다.".to_string())
+        }
+        IssueCommand::Status { task_id } => Some(task_status(&state, &github_repo, &task_id).await),
+        IssueCommand::Cancel { task_id } => Some(cancel(&state, &github_repo, &task_id).await),
+        IssueCommand::Retry { task_id } => retry(&state, &github_repo, issue.number, task_id).await,
+    };
+
+    if let Some(reply) = reply {
+        post_comment(&state, &github_repo, issue.number, &reply).await;
+    }
+}
+
+/// Reply to a command from someone who may not run it, or None when they may
+fn rejection(comment: &CommentPayload) -> Option<String> {
+    (!comment.is_from_maintainer()).then(|| {
+        format!(
+            "❌ @{} AutoDev 명령은 저장소 소유자, 조직 멤버, 협업자만 실행할 수 있습니다.",
+            comment.author()
+        )
+    })
+}
+
+async fn post_comment(state: &ApiState, repo: &Repository, issue_number: u32, body: &str) {
+    if let Err(e) = state.github(repo).create_issue_comment(repo, issue_number, body).await {
+        tracing::error!("Failed to comment on issue #{}: {}", issue_number, e);
+    }
+}
+
+/// Register the prompt as a task and dispatch it like any other standalone task
+/// so the repository's .autodev.yml (branch, workflow, protected paths) applies
+async fn run_prompt(
+    state: &ApiState,
+    repo: &Repository,
+    issue: &IssuePayload,
+    prompt: &str,
+    options: &CommandOptions,
+) -> Option<String> {
+    let issue_url = issue
+        .html_url
+        .clone()
+        .unwrap_or_else(|| format!("{}/issues/{}", repo.web_url(state.github_client.host()), issue.number));
+    // Redelivered webhooks and repeated comments must not run the prompt twice
+    if let Some(existing) = autodev_executor::find_duplicate_task(&state.engine, &state.db, repo, prompt).await {
+        tracing::info!("Prompt from issue #{} duplicates task {}", issue.number, existing.id);
+        return Some(format!(
+            "ℹ️ 같은 프롬프트의 작업이 이미 진행 중입니다: `{}` ({:?})",
+            existing.id, existing.status
+        ));
+    }
+
+    let task = autodev_core::Task::new(
+        format!("AutoDev: {}", prompt),
+        format!("{}{}", ISSUE_TASK_PREFIX, issue.number),
+        prompt.to_string(),
+    )
+    .with_issue(u64::from(issue.number), issue_url)
+    .with_repository(repo.owner.clone(), repo.name.clone());
+
+    let task = match state.engine.register_task(task).await {
+        Ok(task) => task,
+        Err(e) => {
+            tracing::error!("Failed to create task: {}", e);
+            return Some(format!("❌ AutoDev 작업을 만들지 못했습니다.\n\n**오류:** {}", e));
+        }
+    };
+
+    if let Some(ref db) = state.db {
+        if let Err(e) = db.save_task(&task, &repo.owner, &repo.name).await {
+            tracing::error!("Failed to store task in database: {}", e);
+        }
+    }
+
+    match autodev_executor::execute_simple_task(
+        &task,
+        repo,
+        &state.engine,
+        &state.vcs,
+        &state.db,
+        options.branch.as_deref(),
+        None,
+    )
+    .await
+    {
+        Ok(workflow_run_id) => {
+            tracing::info!("Workflow triggered successfully: {}", workflow_run_id);
+            None
+        }
+        Err(e) => {
+            tracing::error!("Failed to trigger workflow: {}", e);
+
+            Some(format!(
+                "❌ AutoDev 워크플로우 트리거 실패\n\n**오류:** {}\n\n다음을 확인해주세요:\n\n- AutoDev 워크플로가 설치되어 있는지 (`autodev setup-repo`)\n- `.autodev.yml` 설정이 올바른지\n- `ANTHROPIC_API_KEY` secret이 설정되어 있는지\n- GitHub Actions가 활성화되어 있는지",
+                e
+            ))
+        }
+    }
+}
+
+/// Decompose the prompt and run the subtasks in the background
+///
+/// Decomposition calls the AI agent, so it runs after the webhook has been answered.
+fn spawn_decompose(
+    state: &ApiState,
+    repo: Repository,
+    issue_number: u32,
+    prompt: String,
+    options: CommandOptions,
+) {
+    let state = state.clone();
+
+    state.background.clone().spawn(async move {
+        let config = state.vcs.load_repo_config(&repo).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load {} of {}: {}", autodev_github::CONFIG_FILE, repo.full_name(), e);
+            autodev_github::RepoConfig::default()
+        });
+
+        let examples = crate::handlers::examples::repository_examples(&state.db, &repo).await;
+        let decomposer = autodev_ai::TaskDecomposer::new(state.ai_agent.clone())
+            .with_protected_paths(config.protected_paths.clone())
+            .with_custom_examples(examples)
+            .with_vector_store(autodev_executor::example_vector_store(&state.db));
+
+        let subtasks = match decomposer.decompose(&prompt).await {
+            Ok(subtasks) => subtasks,
+            Err(e) => {
+                tracing::error!("Failed to decompose task from issue #{}: {}", issue_number, e);
+                let reply = format!("❌ AutoDev 작업 분해 실패\n\n**오류:** {}", e);
+                post_comment(&state, &repo, issue_number, &reply).await;
+                return;
+            }
+        };
+
+        let title = prompt.lines().next().unwrap_or_default();
+        let composite_task = autodev_core::CompositeTask::new(
+            format!("AutoDev: {}", title),
+            format!("{}{}", ISSUE_TASK_PREFIX, issue_number),
+            subtasks,
+        )
+        .with_auto_approve(autodev_executor::repo_auto_approve(options.auto_approve.then_some(true), &config))
+        .with_base_branch(options.branch);
+        let composite_task = autodev_executor::with_repo_defaults(&composite_task, &config);
+
+        let composite_task = match state.engine.register_composite_task(composite_task).await {
+            Ok(composite_task) => composite_task,
+            Err(e) => {
+                tracing::error!("Failed to register composite task: {}", e);
+                let reply = format!("❌ AutoDev 복합 작업을 만들지 못했습니다.\n\n**오류:** {}", e);
+                post_comment(&state, &repo, issue_number, &reply).await;
+                return;
+            }
+        };
+
+        if let Some(ref db) = state.db {
+            if let Err(e) = db.save_composite_task(&composite_task, &repo.owner, &repo.name).await {
+                tracing::error!("Failed to save composite task to database: {}", e);
+            }
+        }
+
+        let plan: Vec<String> = composite_task
+            .get_parallel_batches()
+            .iter()
+            .enumerate()
+            .flat_map(|(batch, tasks)| {
+                tasks
+                    .iter()
+                    .map(move |task| format!("- [배치 {}] {} (`{}`)", batch + 1, task.title, task.id))
+            })
+            .collect();
+        let merge = if composite_task.auto_approve {
+            "하위 작업 PR은 자동으로 병합됩니다."
+        } else {
+            "하위 작업 PR은 검토 후 병합해주세요."
+        };
+        let reply = format!(
+            "🧩 AutoDev 복합 작업이 시작되었습니다.\n\n**작업 ID:** `{}`\n**브랜치:** `autodev/{}`\n\n{}\n\n{}",
+            composite_task.id,
+            composite_task.id,
+            plan.join("\n"),
+            merge
+        );
+        post_comment(&state, &repo, issue_number, &reply).await;
+
+        spawn_composite_execution(&state, composite_task, repo);
+    });
+}
+
+/// Task `task_id` if it belongs to `repo`
+async fn find_task(state: &ApiState, repo: &Repository, task_id: &str) -> Option<Task> {
+    let record = match state.db {
+        Some(ref db) => match db.get_task(task_id).await {
+            Ok(Some(record))
+                if record.repository_owner == repo.owner && record.repository_name == repo.name =>
+            {
+                Some(record)
+            }
+            Ok(_) => return None,
+            Err(e) => {
+                tracing::warn!("Failed to load task {}: {}", task_id, e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    match state.engine.get_task(task_id).await {
+        Some(task) => Some(task),
+        None => record.and_then(|r| r.to_task().ok()),
+    }
+}
+
+async fn task_status(state: &ApiState, repo: &Repository, task_id: &str) -> String {
+    let Some(task) = find_task(state, repo, task_id).await else {
+        return format!("❌ 작업 `{}`을(를) 찾을 수 없습니다.", task_id);
+    };
+
+    let mut lines = vec![
+        "📋 AutoDev 작업 상태".to_string(),
+        String::new(),
+        format!("**작업:** {} (`{}`)", task.title, task.id),
+        format!("**상태:** {:?}", task.status),
+        format!("**시도 횟수:** {}", task.attempts),
+    ];
+    if let Some(ref pr_url) = task.pr_url {
+        lines.push(format!("**PR:** {}", pr_url));
+    }
+    if let Some(ref error) = task.error {
+        lines.push(format!("**오류:** {}", error));
+    }
+
+    lines.join("\n")
+}
+
+async fn cancel(state: &ApiState, repo: &Repository, task_id: &str) -> String {
+    if find_task(state, repo, task_id).await.is_none() {
+        return format!("❌ 작업 `{}`을(를) 찾을 수 없습니다.", task_id);
+    }
+
+    let local_exec = state
+        .local_executor
+        .as_ref()
+        .filter(|_| state.use_local_executor);
+
+    match autodev_executor::cancel_task(task_id, repo, &state.engine, &state.vcs, &state.db, local_exec).await {
+        Ok(task) => format!("🛑 작업 `{}`이(가) 취소되었습니다. ({})", task.id, task.title),
+        Err(e) => {
+            tracing::warn!("Failed to cancel task {}: {}", task_id, e);
+            format!("❌ 작업 `{}`을(를) 취소하지 못했습니다.\n\n**오류:** {}", task_id, e)
+        }
+    }
+}
+
+/// Re-run a failed (or budget-paused) task started from an issue
+async fn retry(state: &ApiState, repo: &Repository, issue_number: u32, task_id: Option<String>) -> Option<String> {
+    let task = match task_id {
+        Some(ref id) => find_task(state, repo, id).await,
+        None => {
+            let description = format!("{}{}", ISSUE_TASK_PREFIX, issue_number);
+            state
+                .engine
+                .list_active_tasks()
+                .await
+                .into_iter()
+                .filter(|t| {
+                    t.description == description
+                        && matches!(t.status, TaskStatus::Failed | TaskStatus::BudgetExceeded)
+                })
+                .max_by_key(|t| t.created_at)
+        }
+    };
+
+    let Some(task) = task else {
+        return Some(match task_id {
+            Some(id) => format!("❌ 작업 `{}`을(를) 찾을 수 없습니다.", id),
+            None => "❌ 이 이슈에서 재시도할 실패한 작업이 없습니다.".to_string(),
+        });
+    };
+
+    // Subtasks of a composite task must be re-run on their parent branch
+    if !task.description.starts_with(ISSUE_TASK_PREFIX) {
+        return Some(format!(
+            "❌ 작업 `{}`은(는) 이슈에서 시작한 작업이 아닙니다. 복합 작업은 `POST /composite-tasks/{{id}}/resume`으로 재개해주세요.",
+            task.id
+        ));
+    }
+
+    let task = match state.engine.reset_for_retry(&task.id).await {
+        Ok(task) => task,
+        Err(e) => return Some(format!("❌ 작업 `{}`을(를) 재시도할 수 없습니다.\n\n**오류:** {}", task.id, e)),
+    };
+
+    match autodev_executor::execute_simple_task(&task, repo, &state.engine, &state.vcs, &state.db, None, None).await {
+        Ok(run_id) => {
+            tracing::info!("Retried task {} (run {})", task.id, run_id);
+            // Linked tasks get their start reported by the issue sync
+            task.issue_number.is_none().then(|| format!("🔁 작업 `{}`을(를) 다시 실행합니다.", task.id))
+        }
+        Err(e) => {
+            tracing::error!("Failed to retry task {}: {}", task.id, e);
+            Some(format!("❌ 작업 `{}` 재실행 실패\n\n**오류:** {}", task.id, e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(author_association: &str) -> CommentPayload {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "body": "autodev: delete the repository",
+            "created_at": "2024-01-01T00:00:00Z",
+            "author_association": author_association,
+            "user": { "login": "someone" }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_commands_from_outsiders_are_rejected() {
+        for outsider in ["NONE", "CONTRIBUTOR", "FIRST_TIME_CONTRIBUTOR", ""] {
+            let reply = rejection(&comment(outsider)).expect(outsider);
+            assert!(reply.contains("@someone"));
+        }
+
+        for maintainer in ["OWNER", "MEMBER", "COLLABORATOR"] {
+            assert_eq!(rejection(&comment(maintainer)), None);
+        }
+    }
+}
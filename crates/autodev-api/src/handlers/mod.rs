@@ -1,6 +1,18 @@
+pub mod artifacts;
+pub mod audit;
 pub mod callback;
 pub mod composite;
+pub mod examples;
 pub mod health;
+pub mod issue_command;
+pub mod schedules;
+pub mod secrets;
 pub mod stats;
 pub mod task;
-pub mod webhook;
\ No newline at end of file
+pub mod webhook;
+/// HTTP status for an error returned by the executor, from its class
+pub(crate) fn error_status(error: &anyhow::Error) -> axum::http::StatusCode {
+    autodev_executor::errors::classify(error)
+        .and_then(|class| axum::http::StatusCode::from_u16(class.http_status()).ok())
+        .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR)
+}
@@ -0,0 +1,226 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::auth::Caller;
+use crate::state::ApiState;
+use autodev_core::CronSchedule;
+use autodev_db::{Database, NewSchedule, ScheduleRecord};
+
+/// Recurring task: the worker creates a task from the template whenever the
+/// cron expression (UTC) is due
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScheduleRequest {
+    pub cron_expression: String,
+    pub repository_owner: String,
+    pub repository_name: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub prompt: String,
+    #[serde(default)]
+    pub auto_approve: bool,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScheduleResponse {
+    pub id: i64,
+    pub cron_expression: String,
+    pub repository_owner: String,
+    pub repository_name: String,
+    pub title: String,
+    pub description: String,
+    pub prompt: String,
+    pub auto_approve: bool,
+    pub enabled: bool,
+    pub next_run_at: Option<String>,
+    pub last_run_at: Option<String>,
+    pub last_task_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListSchedulesQuery {
+    /// With `repo`: only the schedules of that repository
+    pub owner: Option<String>,
+    pub repo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+type HandlerError = (StatusCode, Json<ErrorResponse>);
+
+fn error(status: StatusCode, error: String) -> HandlerError {
+    (status, Json(ErrorResponse { error }))
+}
+
+fn require_db(state: &ApiState) -> Result<&Arc<Database>, HandlerError> {
+    state
+        .db
+        .as_ref()
+        .ok_or_else(|| error(StatusCode::SERVICE_UNAVAILABLE, "Schedules require a database".to_string()))
+}
+
+fn db_error(e: autodev_db::Error) -> HandlerError {
+    error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e))
+}
+
+impl ScheduleRequest {
+    /// Validate the schedule, returning it with its first run (None when disabled)
+    fn to_new_schedule(&self) -> Result<(NewSchedule, Option<chrono::DateTime<chrono::Utc>>), HandlerError> {
+        if self.title.trim().is_empty() || self.prompt.trim().is_empty() {
+            return Err(error(StatusCode::BAD_REQUEST, "title and prompt must not be empty".to_string()));
+        }
+
+        let cron: CronSchedule = self
+            .cron_expression
+            .parse()
+            .map_err(|e: autodev_core::Error| error(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+        let next_run_at = match self.enabled {
+            true => Some(cron.next_after(chrono::Utc::now()).ok_or_else(|| {
+                error(
+                    StatusCode::BAD_REQUEST,
+                    format!("'{}' never matches a date", self.cron_expression),
+                )
+            })?),
+            false => None,
+        };
+
+        let schedule = NewSchedule {
+            cron_expression: cron.to_string(),
+            repository_owner: self.repository_owner.clone(),
+            repository_name: self.repository_name.clone(),
+            title: self.title.clone(),
+            description: self.description.clone(),
+            prompt: self.prompt.clone(),
+            auto_approve: self.auto_approve,
+            enabled: self.enabled,
+        };
+
+        Ok((schedule, next_run_at))
+    }
+}
+
+fn schedule_to_response(record: ScheduleRecord) -> ScheduleResponse {
+    ScheduleResponse {
+        id: record.id,
+        cron_expression: record.cron_expression,
+        repository_owner: record.repository_owner,
+        repository_name: record.repository_name,
+        title: record.title,
+        description: record.description,
+        prompt: record.prompt,
+        auto_approve: record.auto_approve,
+        enabled: record.enabled,
+        next_run_at: record.next_run_at.map(|t| t.to_rfc3339()),
+        last_run_at: record.last_run_at.map(|t| t.to_rfc3339()),
+        last_task_id: record.last_task_id,
+        created_at: record.created_at.to_rfc3339(),
+        updated_at: record.updated_at.to_rfc3339(),
+    }
+}
+
+/// List schedules, all of them or the ones of `owner`/`repo`
+pub async fn list_schedules(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Query(query): Query<ListSchedulesQuery>,
+) -> Result<Json<Vec<ScheduleResponse>>, HandlerError> {
+    let db = require_db(&state)?;
+
+    let repository = match (query.owner.as_deref(), query.repo.as_deref()) {
+        (Some(owner), Some(repo)) => Some((owner, repo)),
+        (None, None) => None,
+        _ => {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "owner and repo must be given together".to_string(),
+            ))
+        }
+    };
+
+    let records = db.list_schedules(repository).await.map_err(db_error)?;
+    Ok(Json(
+        records
+            .into_iter()
+            .filter(|record| caller.check_access(&record.repository_owner).is_ok())
+            .map(schedule_to_response)
+            .collect(),
+    ))
+}
+
+pub async fn create_schedule(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Json(payload): Json<ScheduleRequest>,
+) -> Result<(StatusCode, Json<ScheduleResponse>), HandlerError> {
+    let db = require_db(&state)?;
+    caller
+        .check_access(&payload.repository_owner)
+        .map_err(|e| error(StatusCode::FORBIDDEN, e))?;
+    let (schedule, next_run_at) = payload.to_new_schedule()?;
+
+    let record = db.create_schedule(&schedule, next_run_at).await.map_err(db_error)?;
+    tracing::info!("Created schedule {} ({}): {}", record.id, record.cron_expression, record.title);
+
+    Ok((StatusCode::CREATED, Json(schedule_to_response(record))))
+}
+
+pub async fn get_schedule(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ScheduleResponse>, HandlerError> {
+    let db = require_db(&state)?;
+
+    match db.get_schedule(id).await.map_err(db_error)? {
+        Some(record) => Ok(Json(schedule_to_response(record))),
+        None => Err(error(StatusCode::NOT_FOUND, format!("Schedule not found: {}", id))),
+    }
+}
+
+/// Replace a schedule; its next run is computed again from now
+pub async fn update_schedule(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+    Extension(caller): Extension<Caller>,
+    Json(payload): Json<ScheduleRequest>,
+) -> Result<Json<ScheduleResponse>, HandlerError> {
+    let db = require_db(&state)?;
+    caller
+        .check_access(&payload.repository_owner)
+        .map_err(|e| error(StatusCode::FORBIDDEN, e))?;
+    let (schedule, next_run_at) = payload.to_new_schedule()?;
+
+    match db.update_schedule(id, &schedule, next_run_at).await.map_err(db_error)? {
+        Some(record) => Ok(Json(schedule_to_response(record))),
+        None => Err(error(StatusCode::NOT_FOUND, format!("Schedule not found: {}", id))),
+    }
+}
+
+pub async fn delete_schedule(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, HandlerError> {
+    let db = require_db(&state)?;
+
+    if db.delete_schedule(id).await.map_err(db_error)? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(error(StatusCode::NOT_FOUND, format!("Schedule not found: {}", id)))
+    }
+}
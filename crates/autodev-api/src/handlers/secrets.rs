@@ -0,0 +1,163 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::auth::Caller;
+use crate::state::ApiState;
+use autodev_db::{Database, SecretCipher, SecretRecord, SECRETS_KEY_ENV};
+
+/// Header the workflow sends its one-time secrets token in
+pub const SECRETS_TOKEN_HEADER: &str = "x-autodev-secrets-token";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetSecretRequest {
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecretListResponse {
+    /// Names only; values are never returned to API callers
+    pub secrets: Vec<SecretRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskSecretsResponse {
+    pub secrets: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+type HandlerError = (StatusCode, Json<ErrorResponse>);
+
+fn error(status: StatusCode, error: String) -> HandlerError {
+    (status, Json(ErrorResponse { error }))
+}
+
+fn require_db(state: &ApiState) -> Result<&Arc<Database>, HandlerError> {
+    state
+        .db
+        .as_ref()
+        .ok_or_else(|| error(StatusCode::SERVICE_UNAVAILABLE, "Secrets require a database".to_string()))
+}
+
+fn require_cipher(state: &ApiState) -> Result<&SecretCipher, HandlerError> {
+    state.secrets.as_ref().ok_or_else(|| {
+        error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("Secrets cannot be written or read without {}", SECRETS_KEY_ENV),
+        )
+    })
+}
+
+fn db_error(e: autodev_db::Error) -> HandlerError {
+    match e {
+        autodev_db::Error::Secret(message) => error(StatusCode::BAD_REQUEST, message),
+        e => error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)),
+    }
+}
+
+fn check_access(caller: &Caller, owner: &str) -> Result<(), HandlerError> {
+    caller.check_access(owner).map_err(|e| error(StatusCode::FORBIDDEN, e))
+}
+
+/// List the names of a repository's secrets
+pub async fn list_secrets(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Path((owner, repo)): Path<(String, String)>,
+) -> Result<Json<SecretListResponse>, HandlerError> {
+    check_access(&caller, &owner)?;
+    let db = require_db(&state)?;
+
+    let secrets = db.list_secrets(&owner, &repo).await.map_err(db_error)?;
+    Ok(Json(SecretListResponse { secrets }))
+}
+
+/// Store or replace a secret
+pub async fn set_secret(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Path((owner, repo, name)): Path<(String, String, String)>,
+    Json(request): Json<SetSecretRequest>,
+) -> Result<StatusCode, HandlerError> {
+    check_access(&caller, &owner)?;
+    let db = require_db(&state)?;
+    let cipher = require_cipher(&state)?;
+
+    db.set_secret(cipher, &owner, &repo, &name, &request.value)
+        .await
+        .map_err(db_error)?;
+
+    tracing::info!("Secret {} of {}/{} set", name, owner, repo);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Delete a secret
+pub async fn delete_secret(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Path((owner, repo, name)): Path<(String, String, String)>,
+) -> Result<StatusCode, HandlerError> {
+    check_access(&caller, &owner)?;
+    let db = require_db(&state)?;
+
+    if !db.delete_secret(&owner, &repo, &name).await.map_err(db_error)? {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            format!("Secret {} of {}/{} not found", name, owner, repo),
+        ));
+    }
+
+    tracing::info!("Secret {} of {}/{} deleted", name, owner, repo);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Secrets of a task's repository, fetched once by its workflow run
+///
+/// The one-time token was dispatched as the `secrets_token` input and is
+/// used up by this request.
+pub async fn get_task_secrets(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<TaskSecretsResponse>, HandlerError> {
+    let db = require_db(&state)?;
+    let cipher = require_cipher(&state)?;
+
+    let token = headers
+        .get(SECRETS_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| error(StatusCode::UNAUTHORIZED, "Missing secrets token".to_string()))?;
+
+    let Some((owner, repo)) = db.redeem_secret_grant(&task_id, token).await.map_err(db_error)? else {
+        tracing::warn!("Rejected secrets request for task {}", task_id);
+        return Err(error(
+            StatusCode::FORBIDDEN,
+            "Invalid, expired or already used secrets token".to_string(),
+        ));
+    };
+
+    let secrets = db
+        .get_repository_secrets(cipher, &owner, &repo)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    tracing::info!("Task {} fetched {} secrets of {}/{}", task_id, secrets.len(), owner, repo);
+    let mut names: Vec<&String> = secrets.keys().collect();
+    names.sort();
+    let event = autodev_db::NewAuditEvent::new("workflow", "secret.fetch")
+        .with_target(task_id.clone())
+        .with_repository(&owner, &repo)
+        .with_payload(serde_json::json!({ "names": names }));
+    autodev_executor::audit::record(&state.db, event).await;
+
+    Ok(Json(TaskSecretsResponse { secrets }))
+}
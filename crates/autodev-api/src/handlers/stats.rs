@@ -1,12 +1,62 @@
-use axum::{extract::State, Json};
-use serde::Serialize;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
 
+use crate::auth::Caller;
 use crate::state::ApiState;
+use autodev_db::{StatsFilter, StatsGranularity, DEFAULT_TIMESERIES_DAYS};
+
+/// Query parameters of `GET /stats` (database statistics only)
+#[derive(Debug, Default, Deserialize)]
+pub struct StatsQuery {
+    pub owner: Option<String>,
+    pub repo: Option<String>,
+    /// Organization (repository owner)
+    pub org: Option<String>,
+    /// Only count tasks with this tag
+    pub tag: Option<String>,
+    /// Only count tasks created in the last N days
+    pub days: Option<u32>,
+}
+
+/// Query parameters of `GET /stats/timeseries`
+#[derive(Debug, Default, Deserialize)]
+pub struct TimeseriesQuery {
+    pub owner: Option<String>,
+    pub repo: Option<String>,
+    /// Organization (repository owner)
+    pub org: Option<String>,
+    /// Only tasks with this tag
+    pub tag: Option<String>,
+    /// `day` (default) or `week`
+    #[serde(default)]
+    pub granularity: StatsGranularity,
+    /// Length of the series in days (30 by default)
+    pub days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
 
 #[derive(Debug, Serialize)]
 pub struct StatsResponse {
     pub engine_stats: EngineStats,
     pub db_stats: Option<DbStats>,
+    pub ai_cache: AiCacheStats,
+}
+
+/// AI response cache lookups since the server started
+#[derive(Debug, Serialize)]
+pub struct AiCacheStats {
+    pub memory_hits: u64,
+    pub store_hits: u64,
+    pub misses: u64,
+    pub hit_rate: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -25,12 +75,97 @@ pub struct DbStats {
     pub failed_tasks: i64,
     pub avg_execution_time_ms: Option<f64>,
     pub total_files_changed: Option<i64>,
+    pub total_lines_added: Option<i64>,
+    pub total_lines_removed: Option<i64>,
+    pub total_tokens_used: Option<i64>,
+    /// Estimated AI cost in USD
+    pub total_cost_usd: Option<f64>,
+    pub success_rate: Option<f64>,
+    pub repositories: Vec<RepositoryStats>,
+    pub tags: Vec<autodev_db::TagStats>,
+    pub daily: Vec<autodev_db::DailyStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepositoryStats {
+    pub repository: String,
+    pub total_tasks: i64,
+    pub completed_tasks: i64,
+    pub failed_tasks: i64,
+    pub success_rate: Option<f64>,
+    pub avg_execution_time_ms: Option<f64>,
+    pub total_files_changed: Option<i64>,
+    pub total_lines_added: Option<i64>,
+    pub total_lines_removed: Option<i64>,
     pub total_tokens_used: Option<i64>,
+    pub total_cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeseriesResponse {
+    pub granularity: StatsGranularity,
+    pub days: u32,
+    pub points: Vec<TimeseriesPoint>,
 }
 
-pub async fn get_statistics(State(state): State<ApiState>) -> Json<StatsResponse> {
+/// Tasks finished in one day or week
+#[derive(Debug, Serialize)]
+pub struct TimeseriesPoint {
+    /// First day of the bucket (UTC)
+    pub date: chrono::NaiveDate,
+    pub total_tasks: i64,
+    pub completed_tasks: i64,
+    pub failed_tasks: i64,
+    pub success_rate: Option<f64>,
+    pub avg_execution_time_ms: Option<f64>,
+    pub total_tokens_used: Option<i64>,
+    pub total_cost_usd: Option<f64>,
+}
+
+/// Longest window of `GET /stats/timeseries`
+const MAX_TIMESERIES_DAYS: u32 = 366;
+
+/// Share of finished tasks that completed (None until a task has finished)
+fn success_rate(completed: i64, failed: i64) -> Option<f64> {
+    let finished = completed + failed;
+    (finished > 0).then(|| completed as f64 / finished as f64)
+}
+
+/// Start of the `days` window of a statistics query
+fn since(days: Option<u32>) -> Result<Option<chrono::DateTime<chrono::Utc>>, (StatusCode, Json<ErrorResponse>)> {
+    days.map(|days| {
+        StatsFilter::since_days(days).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("days is too large: {}", days),
+                }),
+            )
+        })
+    })
+    .transpose()
+}
+
+/// Engine statistics cover the tasks tracked in memory; database statistics
+/// can be narrowed with `owner`, `repo`, `org`, `tag` and `days`
+///
+/// Keys limited to an organization only see that organization's database
+/// statistics; the engine statistics (which span every organization) are zero.
+pub async fn get_statistics(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Query(query): Query<StatsQuery>,
+) -> Result<Json<StatsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let org_id = caller
+        .org_filter(query.org)
+        .map_err(|error| (StatusCode::FORBIDDEN, Json(ErrorResponse { error })))?;
+    let since = since(query.days)?;
+
     // Get engine statistics
-    let engine_stats_raw = state.engine.get_statistics().await;
+    let engine_stats_raw = match caller.org {
+        Some(_) => Default::default(),
+        None => state.engine.get_statistics().await,
+    };
     let engine_stats = EngineStats {
         total_tasks: engine_stats_raw.total_tasks,
         completed_tasks: engine_stats_raw.completed_tasks,
@@ -40,15 +175,53 @@ pub async fn get_statistics(State(state): State<ApiState>) -> Json<StatsResponse
     };
 
     // Get database statistics if available
+    let filter = StatsFilter {
+        repository_owner: query.owner,
+        repository_name: query.repo,
+        org_id,
+        since,
+        tag: query.tag,
+    };
+
     let db_stats = if let Some(ref db) = state.db {
-        match db.get_aggregate_stats().await {
-            Ok(stats) => Some(DbStats {
+        let stats = async {
+            let totals = db.get_aggregate_stats(&filter).await?;
+            let repositories = db.get_repository_stats(&filter).await?;
+            let tags = db.get_tag_stats(&filter).await?;
+            let daily = db.get_daily_stats(&filter).await?;
+            Ok::<_, autodev_db::Error>((totals, repositories, tags, daily))
+        };
+
+        match stats.await {
+            Ok((stats, repositories, tags, daily)) => Some(DbStats {
                 total_tasks: stats.total_tasks,
                 completed_tasks: stats.completed_tasks,
                 failed_tasks: stats.failed_tasks,
                 avg_execution_time_ms: stats.avg_execution_time_ms,
                 total_files_changed: stats.total_files_changed,
+                total_lines_added: stats.total_lines_added,
+                total_lines_removed: stats.total_lines_removed,
                 total_tokens_used: stats.total_tokens_used,
+                total_cost_usd: stats.total_cost_usd,
+                success_rate: success_rate(stats.completed_tasks, stats.failed_tasks),
+                repositories: repositories
+                    .into_iter()
+                    .map(|repo| RepositoryStats {
+                        repository: format!("{}/{}", repo.repository_owner, repo.repository_name),
+                        total_tasks: repo.total_tasks,
+                        completed_tasks: repo.completed_tasks,
+                        failed_tasks: repo.failed_tasks,
+                        success_rate: success_rate(repo.completed_tasks, repo.failed_tasks),
+                        avg_execution_time_ms: repo.avg_execution_time_ms,
+                        total_files_changed: repo.total_files_changed,
+                        total_lines_added: repo.total_lines_added,
+                        total_lines_removed: repo.total_lines_removed,
+                        total_tokens_used: repo.total_tokens_used,
+                        total_cost_usd: repo.total_cost_usd,
+                    })
+                    .collect(),
+                tags,
+                daily,
             }),
             Err(e) => {
                 tracing::error!("Failed to get database stats: {}", e);
@@ -59,8 +232,93 @@ pub async fn get_statistics(State(state): State<ApiState>) -> Json<StatsResponse
         None
     };
 
-    Json(StatsResponse {
+    let cache = autodev_ai::cache::stats();
+    let ai_cache = AiCacheStats {
+        memory_hits: cache.memory_hits,
+        store_hits: cache.store_hits,
+        misses: cache.misses,
+        hit_rate: cache.hit_rate(),
+    };
+
+    Ok(Json(StatsResponse {
         engine_stats,
         db_stats,
-    })
-}
\ No newline at end of file
+        ai_cache,
+    }))
+}
+/// Tasks completed, success rate, duration and AI usage per day or week
+///
+/// Every bucket of the window is returned, empty ones with zero counts.
+pub async fn get_timeseries(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Query(query): Query<TimeseriesQuery>,
+) -> Result<Json<TimeseriesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, error: String| (status, Json(ErrorResponse { error }));
+
+    let org_id = caller
+        .org_filter(query.org)
+        .map_err(|e| error(StatusCode::FORBIDDEN, e))?;
+    let days = query.days.unwrap_or(DEFAULT_TIMESERIES_DAYS as u32);
+    if days == 0 || days > MAX_TIMESERIES_DAYS {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            format!("days must be between 1 and {}", MAX_TIMESERIES_DAYS),
+        ));
+    }
+    let db = state
+        .db
+        .as_ref()
+        .ok_or_else(|| error(StatusCode::SERVICE_UNAVAILABLE, "Time series require a database".to_string()))?;
+
+    let filter = StatsFilter {
+        repository_owner: query.owner,
+        repository_name: query.repo,
+        org_id,
+        since: since(Some(days))?,
+        tag: query.tag,
+    };
+    let stats = db
+        .get_timeseries_stats(&filter, query.granularity)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?;
+
+    let points = stats
+        .into_iter()
+        .map(|point| TimeseriesPoint {
+            date: point.date,
+            total_tasks: point.total_tasks,
+            completed_tasks: point.completed_tasks,
+            failed_tasks: point.failed_tasks,
+            success_rate: success_rate(point.completed_tasks, point.failed_tasks),
+            avg_execution_time_ms: point.avg_execution_time_ms,
+            total_tokens_used: point.total_tokens_used,
+            total_cost_usd: point.total_cost_usd,
+        })
+        .collect();
+
+    Ok(Json(TimeseriesResponse {
+        granularity: query.granularity,
+        days,
+        points,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_since() {
+        assert!(since(None).unwrap().is_none());
+
+        let week = since(Some(7)).unwrap().unwrap();
+        let expected = chrono::Utc::now() - chrono::Duration::days(7);
+        assert!((expected - week).num_seconds().abs() < 5);
+
+        // Windows reaching before the earliest representable time are rejected
+        let (status, Json(body)) = since(Some(u32::MAX)).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.error.contains("days"));
+    }
+}
@@ -1,10 +1,18 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    Json,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Extension, Json,
 };
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use tokio_stream::wrappers::{BroadcastStream, ReceiverStream};
+use tokio_stream::{Stream, StreamExt};
 
+use crate::auth::Caller;
 use crate::state::ApiState;
 use autodev_github::Repository;
 
@@ -15,6 +23,19 @@ pub struct CreateTaskRequest {
     pub title: String,
     pub description: String,
     pub prompt: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Where the task runs (the repository's .autodev.yml or the server default when unset)
+    #[serde(default)]
+    pub execution_backend: Option<autodev_core::ExecutionBackend>,
+    /// Create the task even if an unfinished task of the repository has the same prompt
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetTagsRequest {
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -23,8 +44,35 @@ pub struct TaskResponse {
     pub title: String,
     pub status: String,
     pub pr_url: Option<String>,
+    #[serde(default)]
+    pub pr_number: Option<u64>,
+    /// CI run executing the task
+    #[serde(default)]
+    pub workflow_run_id: Option<String>,
     pub created_at: String,
     pub completed_at: Option<String>,
+    #[serde(default)]
+    pub estimated_minutes: Option<u32>,
+    #[serde(default)]
+    pub issue_number: Option<u64>,
+    #[serde(default)]
+    pub issue_url: Option<String>,
+    /// User whose API key created the task
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// Failed dependency that blocked or skipped the task
+    #[serde(default)]
+    pub blocked_by: Option<String>,
+    #[serde(default)]
+    pub blocked_reason: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Backend requested for the task (None: the default when it runs)
+    #[serde(default)]
+    pub execution_backend: Option<String>,
+    /// Set when creation returned this existing task instead of a duplicate
+    #[serde(default)]
+    pub duplicate_of: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,17 +81,35 @@ pub struct ErrorResponse {
 }
 
 /// Create a simple task and execute it immediately
+///
+/// An unfinished task of the same repository with the same prompt is returned
+/// (with `duplicate_of` set) instead, unless `force` is set.
 pub async fn create_task(
     State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
     Json(payload): Json<CreateTaskRequest>,
 ) -> Result<Json<TaskResponse>, (StatusCode, Json<ErrorResponse>)> {
+    caller.check_access(&payload.repository_owner).map_err(forbidden)?;
     let repo = Repository::new(payload.repository_owner.clone(), payload.repository_name.clone());
 
-    match state
-        .engine
-        .create_simple_task(payload.title, payload.description, payload.prompt)
-        .await
-    {
+    if !payload.force {
+        if let Some(existing) =
+            autodev_executor::find_duplicate_task(&state.engine, &state.db, &repo, &payload.prompt).await
+        {
+            tracing::info!("Task for {} duplicates {}; returning it", repo, existing.id);
+            let mut response = task_to_response(&existing);
+            response.duplicate_of = Some(existing.id);
+            return Ok(Json(response));
+        }
+    }
+
+    let task = autodev_core::Task::new(payload.title, payload.description, payload.prompt)
+        .with_created_by(caller.user)
+        .with_tags(&payload.tags)
+        .with_execution_backend(payload.execution_backend)
+        .with_repository(repo.owner.clone(), repo.name.clone());
+
+    match state.engine.register_task(task).await {
         Ok(task) => {
             // Save to database if available
             if let Some(ref db) = state.db {
@@ -55,23 +121,19 @@ pub async fn create_task(
                 }
             }
 
-            // Execute task immediately in background using executor module
+            // Execute task immediately in background on its execution backend
             let task_clone = task.clone();
             let repo_clone = repo.clone();
             let engine_clone = state.engine.clone();
-            let github_clone = state.github_client.clone();
+            let vcs_clone = state.vcs.clone();
             let db_clone = state.db.clone();
+            let router = state.router.clone();
 
-            tokio::spawn(async move {
-                if let Err(e) = autodev_executor::execute_simple_task(
-                    &task_clone,
-                    &repo_clone,
-                    &engine_clone,
-                    &github_clone,
-                    &db_clone,
-                    None,  // No parent branch for simple tasks
-                    None,  // No composite task ID
-                ).await {
+            state.background.spawn(async move {
+                if let Err(e) = router
+                    .execute_task(&task_clone, &repo_clone, &engine_clone, &vcs_clone, &db_clone)
+                    .await
+                {
                     tracing::error!("Failed to execute task {}: {}", task_clone.id, e);
                 }
             });
@@ -121,22 +183,51 @@ pub async fn execute_task(
     let engine = state.engine.clone();
     let task_clone = task.clone();
     let repo_clone = repo.clone();
-    let github = state.github_client.clone();
+    let vcs = state.vcs.clone();
     let ai = state.ai_agent.clone();
     let db = state.db.clone();
+    let router = state.router.clone();
+
+    state.background.spawn(async move {
+        // Tasks on a local backend run entirely on this server's executor
+        let config = vcs.load_repo_config(&repo_clone).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load {} of {}: {}", autodev_github::CONFIG_FILE, repo_clone.full_name(), e);
+            autodev_github::RepoConfig::default()
+        });
+        if router.resolve(task_clone.execution_backend, &config).is_local() {
+            if let Err(e) = router.execute_task(&task_clone, &repo_clone, &engine, &vcs, &db).await {
+                tracing::error!("Failed to execute task {}: {}", task_clone.id, e);
+            }
+            return;
+        }
 
-    tokio::spawn(async move {
-        // Execute with AI agent
-        match ai.execute_task(&task_clone, &repo_clone.full_name()).await {
+        if let Err(e) = engine
+            .update_task_status(&task_clone.id, autodev_core::TaskStatus::InProgress, None)
+            .await
+        {
+            tracing::error!("Failed to start task {}: {}", task_clone.id, e);
+            return;
+        }
+
+        // Execute with AI agent within the task's budgets
+        let result = autodev_executor::run_within_budget(
+            &task_clone,
+            &engine,
+            &db,
+            ai.execute_task(&task_clone, &repo_clone.full_name()),
+        )
+        .await;
+
+        match result {
             Ok(result) => {
-                // Trigger GitHub workflow
+                // Trigger CI pipeline
                 let mut inputs = std::collections::HashMap::new();
                 inputs.insert("task_id".to_string(), task_clone.id.clone());
                 inputs.insert("branch".to_string(), result.pr_branch);
                 inputs.insert("commit_message".to_string(), result.commit_message);
 
-                if let Ok(run_id) = github
-                    .trigger_workflow(&repo_clone, "autodev.yml", inputs)
+                if let Ok(run_id) = vcs
+                    .trigger_pipeline(&repo_clone, "autodev.yml", &repo_clone.branch, inputs)
                     .await
                 {
                     // Update task status
@@ -151,18 +242,13 @@ pub async fn execute_task(
                         tracing::error!("Failed to update task status: {}", e);
                     }
 
-                    // Update database
-                    if let Some(db) = db {
-                        let _ = db.update_task_status(
-                            &task_clone.id,
-                            autodev_core::TaskStatus::Completed,
-                            None,
-                        ).await;
-                    }
-
                     tracing::info!("Task {} completed with workflow {}", task_clone.id, run_id);
                 }
             }
+            // Paused by run_within_budget
+            Err(e) if e.is_budget_exceeded() => {
+                tracing::warn!("Task {} paused: {}", task_clone.id, e);
+            }
             Err(e) => {
                 tracing::error!("Task execution failed: {}", e);
                 let _ = engine
@@ -179,6 +265,176 @@ pub async fn execute_task(
     Ok(Json(task_to_response(&task)))
 }
 
+/// Cancel a pending or running task
+pub async fn cancel_task(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<TaskResponse>, (StatusCode, Json<ErrorResponse>)> {
+    // Get repository info from database or use default
+    let (repo_owner, repo_name) = if let Some(ref db) = state.db {
+        match db.get_task(&task_id).await {
+            Ok(Some(record)) => (record.repository_owner, record.repository_name),
+            _ => ("myorg".to_string(), "myproject".to_string()),
+        }
+    } else {
+        ("myorg".to_string(), "myproject".to_string())
+    };
+
+    let repo = Repository::new(repo_owner, repo_name);
+
+    let local_exec = state
+        .local_executor
+        .as_ref()
+        .filter(|_| state.use_local_executor);
+
+    match autodev_executor::cancel_task(
+        &task_id,
+        &repo,
+        &state.engine,
+        &state.vcs,
+        &state.db,
+        local_exec,
+    )
+    .await
+    {
+        Ok(task) => Ok(Json(task_to_response(&task))),
+        Err(e) => Err((
+            super::error_status(&e),
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )),
+    }
+}
+
+/// Replace the tags of a task
+///
+/// Works for tasks the engine no longer tracks as long as they are stored in
+/// the database.
+pub async fn set_task_tags(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Path(task_id): Path<String>,
+    Json(payload): Json<SetTagsRequest>,
+) -> Result<Json<TaskResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, error: String| (status, Json(ErrorResponse { error }));
+    let not_found = || error(StatusCode::NOT_FOUND, format!("Task not found: {}", task_id));
+    let db_error = |e: autodev_db::Error| error(StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e));
+
+    let record = match state.db {
+        Some(ref db) => db.get_task(&task_id).await.map_err(db_error)?,
+        None => None,
+    };
+    if let Some(ref record) = record {
+        caller.check_access(&record.repository_owner).map_err(forbidden)?;
+    }
+
+    let task = match state.engine.set_task_tags(&task_id, &payload.tags).await {
+        Ok(task) => Some(task),
+        Err(autodev_core::Error::TaskNotFound(_)) => None,
+        Err(e) => return Err(error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    };
+    if let Some(ref db) = state.db {
+        db.set_task_tags(&task_id, &payload.tags).await.map_err(db_error)?;
+    }
+
+    match (task, record) {
+        (Some(task), _) => Ok(Json(task_to_response(&task))),
+        (None, Some(mut record)) => {
+            record.tags = autodev_core::normalize_tags(&payload.tags);
+            Ok(Json(record_to_response(record)))
+        }
+        (None, None) => Err(not_found()),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApproveMergeResponse {
+    pub task_id: String,
+    pub pr_number: u64,
+    pub merged: bool,
+    /// What still keeps the PR from being merged (branch protection, conflicts)
+    pub blocker: Option<String>,
+}
+
+/// Merge a subtask PR the AI review gate held back
+///
+/// The review is not run again; branch protection still applies.
+pub async fn approve_merge(
+    State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
+    Path(task_id): Path<String>,
+) -> Result<Json<ApproveMergeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, error: String| (status, Json(ErrorResponse { error }));
+
+    let task = state
+        .engine
+        .get_task(&task_id)
+        .await
+        .ok_or_else(|| error(StatusCode::NOT_FOUND, format!("Task not found: {}", task_id)))?;
+    let pr_number = task
+        .pr_number
+        .ok_or_else(|| error(StatusCode::CONFLICT, format!("Task {} has no PR to merge", task_id)))?;
+
+    let (repo_owner, repo_name) = match state.db {
+        Some(ref db) => match db.get_task(&task_id).await {
+            Ok(Some(record)) => (record.repository_owner, record.repository_name),
+            _ => ("myorg".to_string(), "myproject".to_string()),
+        },
+        None => ("myorg".to_string(), "myproject".to_string()),
+    };
+    let repo = Repository::new(repo_owner, repo_name);
+
+    if let Err(e) = caller.check_access(&repo.owner) {
+        return Err(error(StatusCode::FORBIDDEN, e));
+    }
+
+    let config = state.vcs.load_repo_config(&repo).await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to load {} of {}: {}", autodev_github::CONFIG_FILE, repo.full_name(), e);
+        autodev_github::RepoConfig::default()
+    });
+    let merge_method = state
+        .engine
+        .composite_of(&task_id)
+        .await
+        .and_then(|c| c.merge_method)
+        .or(config.merge_method)
+        .unwrap_or_default();
+    let gate = autodev_executor::MergeGate::new(merge_method, &config);
+    let options = autodev_executor::subtask_merge_options(&task, pr_number, merge_method);
+
+    let message = format!(
+        "Merge of PR #{} approved by {}",
+        pr_number,
+        caller.user.as_deref().unwrap_or("anonymous")
+    );
+    state.engine.log_event(&task_id, "MERGE_APPROVED", &message);
+    if let Some(ref db) = state.db {
+        if let Err(e) = db.add_execution_log(&task_id, "MERGE_APPROVED", &message).await {
+            tracing::warn!("Failed to log merge approval of task {}: {}", task_id, e);
+        }
+    }
+
+    let outcome = autodev_executor::try_merge(&repo, pr_number, &state.vcs, &options, &gate)
+        .await
+        .map_err(|e| error(super::error_status(&e), format!("Failed to merge PR #{}: {}", pr_number, e)))?;
+
+    let blocker = match outcome {
+        autodev_executor::MergeOutcome::Merged => {
+            tracing::info!("✓ Approved PR #{} of task {} merged", pr_number, task_id);
+            None
+        }
+        autodev_executor::MergeOutcome::Blocked(blocker) => Some(blocker.to_string()),
+    };
+
+    Ok(Json(ApproveMergeResponse {
+        task_id,
+        pr_number,
+        merged: blocker.is_none(),
+        blocker,
+    }))
+}
+
 /// Get task status
 pub async fn get_task_status(
     State(state): State<ApiState>,
@@ -190,14 +446,7 @@ pub async fn get_task_status(
             // Try database
             if let Some(ref db) = state.db {
                 if let Ok(Some(record)) = db.get_task(&task_id).await {
-                    return Ok(Json(TaskResponse {
-                        id: record.id,
-                        title: record.title,
-                        status: record.status,
-                        pr_url: record.pr_url,
-                        created_at: record.created_at.to_rfc3339(),
-                        completed_at: record.completed_at.map(|dt| dt.to_rfc3339()),
-                    }));
+                    return Ok(Json(record_to_response(record)));
                 }
             }
 
@@ -211,13 +460,420 @@ pub async fn get_task_status(
     }
 }
 
-/// List all active tasks
+/// Header the workflow sends its `payload_token` input in
+pub const PAYLOAD_TOKEN_HEADER: &str = "x-autodev-payload-token";
+
+/// Dispatch payload of a task, fetched by the AutoDev workflow
+///
+/// Only exists when the server has a database; otherwise the prompt is passed
+/// as a workflow input. Task IDs are public (they name the task branches), so
+/// the payload needs the token it was dispatched with, or an operator key
+/// when API keys are configured.
+pub async fn get_task_payload(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+    headers: axum::http::HeaderMap,
+) -> Result<Json<autodev_db::TaskPayload>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, error: String| (status, Json(ErrorResponse { error }));
+    let not_found = || error(StatusCode::NOT_FOUND, "Task payload not found".to_string());
+
+    let Some(ref db) = state.db else {
+        return Err(not_found());
+    };
+
+    let token = headers.get(PAYLOAD_TOKEN_HEADER).and_then(|value| value.to_str().ok());
+    let key_accepted = match state.api_keys {
+        Some(ref api_keys) => api_keys
+            .authenticate_request(&headers)
+            .is_some_and(|caller| caller.role >= crate::auth::Role::Operator),
+        None => true,
+    };
+
+    let payload = match token {
+        Some(token) => db.get_task_payload_with_token(&task_id, token).await,
+        None if key_accepted => db.get_task_payload(&task_id).await,
+        None => {
+            tracing::warn!("Rejected payload request for task {} without a token", task_id);
+            return Err(error(StatusCode::UNAUTHORIZED, "Missing payload token".to_string()));
+        }
+    }
+    .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    payload.map(Json).ok_or_else(not_found)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeartbeatResponse {
+    pub task_id: String,
+    pub lease_expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Extend the lease on a running task (sent by its workflow run or container)
+///
+/// A task that stops heartbeating loses its lease and is requeued by a worker;
+/// tasks that never heartbeat only time out by age.
+pub async fn heartbeat_task(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<HeartbeatResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let error = |status: StatusCode, error: String| (status, Json(ErrorResponse { error }));
+
+    // Leases live in the database; without one there is nothing to extend
+    let Some(ref db) = state.db else {
+        return Err(error(StatusCode::NOT_FOUND, format!("No lease on task {}", task_id)));
+    };
+
+    match db.heartbeat_running_task(&task_id, autodev_core::lease::lease_duration()).await {
+        Ok(Some(lease_expires_at)) => Ok(Json(HeartbeatResponse { task_id, lease_expires_at })),
+        Ok(None) => Err(error(StatusCode::CONFLICT, format!("Task {} is not running", task_id))),
+        Err(e) => Err(error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+/// Stream live updates for a task as Server-Sent Events
+///
+/// The first event is the current status; after that status transitions,
+/// execution-log entries and PR URLs are pushed as they happen.
+pub async fn task_events(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<ErrorResponse>)> {
+    // Subscribe before reading the snapshot so no transition is missed in between
+    let receiver = state.engine.subscribe();
+
+    let task = state.engine.get_task(&task_id).await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Task not found".to_string(),
+            }),
+        )
+    })?;
+
+    let initial = autodev_core::TaskEvent::StatusChanged {
+        task_id: task.id.clone(),
+        status: task.status,
+        error: task.error.clone(),
+        attempts: task.attempts,
+        timestamp: chrono::Utc::now(),
+    };
+
+    let updates = BroadcastStream::new(receiver).filter_map(move |event| match event {
+        Ok(event) if event.task_id() == task_id => Some(event),
+        Ok(_) => None,
+        Err(e) => {
+            // Slow client: skip what was dropped and keep streaming
+            tracing::warn!("Task event stream lagged: {}", e);
+            None
+        }
+    });
+
+    let stream = tokio_stream::once(initial)
+        .chain(updates)
+        .map(|event| Ok(to_sse_event(&event)));
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Most stored log lines returned at once
+const MAX_LOG_LINES: i64 = 1000;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TaskLogsQuery {
+    /// Keep the response open and stream new lines as Server-Sent Events
+    #[serde(default)]
+    pub follow: bool,
+    /// Only lines after this line ID
+    pub after: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskLogsResponse {
+    pub task_id: String,
+    pub lines: Vec<autodev_db::TaskLog>,
+    /// Pass as `after` to get the lines written since
+    pub next_after: i64,
+}
+
+/// Container output of a locally executed task
+///
+/// Stored lines are returned as JSON. With `follow=true` every stored line is
+/// sent, `limit` lines per `output` event, followed by live `output` and
+/// `status_changed` events.
+pub async fn task_logs(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+    Query(params): Query<TaskLogsQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    // Subscribe before reading stored lines so no batch is missed in between
+    let receiver = state.engine.subscribe();
+
+    let after = params.after.unwrap_or(0);
+    let limit = params.limit.unwrap_or(MAX_LOG_LINES).clamp(1, MAX_LOG_LINES);
+    let stored = match state.db {
+        Some(ref db) => db.get_task_logs(&task_id, after, limit).await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+        })?,
+        None if params.follow => Vec::new(),
+        None => {
+            return Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse {
+                    error: "Task logs are only stored with a database; use follow=true".to_string(),
+                }),
+            ))
+        }
+    };
+
+    if !params.follow {
+        let next_after = stored.last().map_or(after, |log| log.id);
+        return Ok(Json(TaskLogsResponse {
+            task_id,
+            lines: stored,
+            next_after,
+        })
+        .into_response());
+    }
+
+    // A full page may have more lines behind it
+    let (pages_tx, pages_rx) = tokio::sync::mpsc::channel(1);
+    if let (Some(db), Some(last)) = (state.db.clone(), stored.last()) {
+        if stored.len() as i64 == limit {
+            tokio::spawn(send_stored_logs(db, task_id.clone(), last.id, limit, pages_tx));
+        }
+    }
+
+    let initial = (!stored.is_empty()).then(|| output_event(&task_id, stored));
+
+    let updates = BroadcastStream::new(receiver).filter_map(move |event| match event {
+        Ok(event @ (autodev_core::TaskEvent::Output { .. } | autodev_core::TaskEvent::StatusChanged { .. }))
+            if event.task_id() == task_id =>
+        {
+            Some(event)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("Task log stream lagged: {}", e);
+            None
+        }
+    });
+
+    let stream = tokio_stream::iter(initial)
+        .chain(ReceiverStream::new(pages_rx))
+        .chain(updates)
+        .map(|event| Ok::<_, Infallible>(to_sse_event(&event)));
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()).into_response())
+}
+
+/// Send the stored lines after `after` as `output` events, `limit` lines each
+async fn send_stored_logs(
+    db: std::sync::Arc<autodev_db::Database>,
+    task_id: String,
+    mut after: i64,
+    limit: i64,
+    events: tokio::sync::mpsc::Sender<autodev_core::TaskEvent>,
+) {
+    loop {
+        let page = match db.get_task_logs(&task_id, after, limit).await {
+            Ok(page) => page,
+            Err(e) => {
+                tracing::warn!("Failed to read stored output of task {}: {}", task_id, e);
+                return;
+            }
+        };
+        let Some(last) = page.last() else {
+            return;
+        };
+        after = last.id;
+
+        let full = page.len() as i64 == limit;
+        // 클라이언트가 연결을 끊으면 중단
+        if events.send(output_event(&task_id, page)).await.is_err() || !full {
+            return;
+        }
+    }
+}
+
+fn output_event(task_id: &str, logs: Vec<autodev_db::TaskLog>) -> autodev_core::TaskEvent {
+    autodev_core::TaskEvent::Output {
+        task_id: task_id.to_string(),
+        lines: logs.into_iter().map(|log| log.line).collect(),
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+fn to_sse_event(event: &autodev_core::TaskEvent) -> Event {
+    Event::default()
+        .event(event.name())
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().event(event.name()))
+}
+
+pub(crate) const DEFAULT_PAGE_SIZE: i64 = 50;
+pub(crate) const MAX_PAGE_SIZE: i64 = 200;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ListTasksQuery {
+    /// Task status (InProgress, in_progress, ...)
+    pub status: Option<String>,
+    /// Repository as `owner/name`
+    pub repository: Option<String>,
+    /// Issue the task was started from
+    pub issue: Option<u64>,
+    /// Organization (repository owner)
+    pub org: Option<String>,
+    /// Only tasks with this tag
+    pub tag: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub sort: Option<autodev_db::TaskSort>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskListResponse {
+    pub tasks: Vec<TaskResponse>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+fn bad_request(error: String) -> (StatusCode, Json<ErrorResponse>) {
+    (StatusCode::BAD_REQUEST, Json(ErrorResponse { error }))
+}
+
+fn forbidden(error: String) -> (StatusCode, Json<ErrorResponse>) {
+    (StatusCode::FORBIDDEN, Json(ErrorResponse { error }))
+}
+
+impl ListTasksQuery {
+    fn to_task_query(&self) -> Result<autodev_db::TaskQuery, (StatusCode, Json<ErrorResponse>)> {
+        let status = match self.status.as_deref() {
+            Some(status) => Some(
+                status
+                    .parse::<autodev_core::TaskStatus>()
+                    .map_err(|_| bad_request(format!("Invalid status: {}", status)))?,
+            ),
+            None => None,
+        };
+
+        let repository = match self.repository.as_deref() {
+            Some(repo) => match repo.split_once('/') {
+                Some((owner, name)) if !owner.is_empty() && !name.is_empty() => {
+                    Some((owner.to_string(), name.to_string()))
+                }
+                _ => {
+                    return Err(bad_request(format!(
+                        "Invalid repository (expected owner/name): {}",
+                        repo
+                    )))
+                }
+            },
+            None => None,
+        };
+
+        Ok(autodev_db::TaskQuery {
+            status,
+            repository,
+            issue_number: self.issue,
+            org_id: self.org.clone(),
+            tag: self.tag.clone(),
+            limit: self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+            offset: self.offset.unwrap_or(0).max(0),
+            sort: self.sort.unwrap_or_default(),
+        })
+    }
+}
+
+/// List tasks with filtering, sorting and pagination
+///
+/// Reads from the database when available; otherwise pages through the
+/// engine's in-memory tasks (which do not know their repository).
 pub async fn list_tasks(
     State(state): State<ApiState>,
-) -> Result<Json<Vec<TaskResponse>>, (StatusCode, Json<ErrorResponse>)> {
-    let tasks = state.engine.list_active_tasks().await;
-    let responses: Vec<TaskResponse> = tasks.iter().map(task_to_response).collect();
-    Ok(Json(responses))
+    Extension(caller): Extension<Caller>,
+    Query(params): Query<ListTasksQuery>,
+) -> Result<Json<TaskListResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let mut query = params.to_task_query()?;
+    query.org_id = caller.org_filter(query.org_id.take()).map_err(forbidden)?;
+
+    if let Some(ref db) = state.db {
+        match db.list_tasks(&query).await {
+            Ok((records, total)) => {
+                return Ok(Json(TaskListResponse {
+                    tasks: records.into_iter().map(record_to_response).collect(),
+                    total,
+                    limit: query.limit,
+                    offset: query.offset,
+                }));
+            }
+            Err(e) => tracing::error!("Failed to list tasks from database: {}", e),
+        }
+    }
+
+    if query.repository.is_some() || query.org_id.is_some() {
+        return Err(bad_request("Filtering by repository or organization requires a database".to_string()));
+    }
+
+    let mut tasks: Vec<autodev_core::Task> = state
+        .engine
+        .list_active_tasks()
+        .await
+        .into_iter()
+        .filter(|t| query.status.is_none_or(|status| t.status == status))
+        .filter(|t| query.issue_number.is_none_or(|issue| t.issue_number == Some(issue)))
+        .filter(|t| {
+            query
+                .tag
+                .as_ref()
+                .is_none_or(|tag| t.tags.contains(&tag.trim().to_lowercase()))
+        })
+        .collect();
+
+    sort_tasks(&mut tasks, query.sort);
+
+    let total = tasks.len() as i64;
+    let responses = tasks
+        .iter()
+        .skip(query.offset as usize)
+        .take(query.limit as usize)
+        .map(task_to_response)
+        .collect();
+
+    Ok(Json(TaskListResponse {
+        tasks: responses,
+        total,
+        limit: query.limit,
+        offset: query.offset,
+    }))
+}
+
+/// In-memory equivalent of `TaskSort::order_by`
+fn sort_tasks(tasks: &mut [autodev_core::Task], sort: autodev_db::TaskSort) {
+    use autodev_db::TaskSort;
+
+    match sort {
+        TaskSort::Newest => {
+            tasks.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)))
+        }
+        TaskSort::Oldest => {
+            tasks.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)))
+        }
+        TaskSort::Title => {
+            tasks.sort_by(|a, b| a.title.cmp(&b.title).then_with(|| b.created_at.cmp(&a.created_at)))
+        }
+        TaskSort::Status => tasks.sort_by(|a, b| {
+            format!("{:?}", a.status)
+                .cmp(&format!("{:?}", b.status))
+                .then_with(|| b.created_at.cmp(&a.created_at))
+        }),
+    }
 }
 
 /// Decompose composite task into subtasks
@@ -228,6 +884,9 @@ pub struct DecomposeTaskRequest {
     pub title: String,
     pub description: String,
     pub composite_prompt: String,
+    /// Critique the decomposition with a second AI pass
+    #[serde(default)]
+    pub review_plan: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -235,20 +894,35 @@ pub struct DecomposeTaskResponse {
     pub composite_task_id: String,
     pub subtasks: Vec<TaskResponse>,
     pub parallel_batches: Vec<Vec<String>>,
+    /// Wall-clock estimate: the slowest subtask of each batch, summed
     pub total_estimated_minutes: u64,
+    /// Subtask IDs along the longest chain by estimated duration
+    #[serde(default)]
+    pub critical_path: Vec<String>,
+    #[serde(default)]
+    pub critical_path_minutes: u32,
+    /// AI critique of the decomposition, when one was requested
+    #[serde(default)]
+    pub plan_review: Option<autodev_ai::PlanReview>,
 }
 
 pub async fn decompose_task(
     State(state): State<ApiState>,
+    Extension(caller): Extension<Caller>,
     Json(payload): Json<DecomposeTaskRequest>,
 ) -> Result<Json<DecomposeTaskResponse>, (StatusCode, Json<ErrorResponse>)> {
+    caller.check_access(&payload.repository_owner).map_err(forbidden)?;
     tracing::info!("Decomposing task: {}", payload.title);
 
     // Use TaskDecomposer to decompose the task
-    let decomposer = autodev_ai::TaskDecomposer::new(state.ai_agent.clone());
+    let repo = Repository::new(payload.repository_owner.clone(), payload.repository_name.clone());
+    let examples = crate::handlers::examples::repository_examples(&state.db, &repo).await;
+    let decomposer = autodev_ai::TaskDecomposer::new(state.ai_agent.clone())
+        .with_custom_examples(examples)
+        .with_vector_store(autodev_executor::example_vector_store(&state.db));
 
-    let subtasks = match decomposer.decompose(&payload.composite_prompt).await {
-        Ok(tasks) => tasks,
+    let plan = match decomposer.plan(&payload.composite_prompt).await {
+        Ok(plan) => plan,
         Err(e) => {
             return Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -259,6 +933,13 @@ pub async fn decompose_task(
         }
     };
 
+    let subtasks = decomposer.convert_to_tasks(plan.tasks.clone());
+    let plan_review = if payload.review_plan {
+        crate::handlers::composite::review_plan(&state, &payload.composite_prompt, &plan, &subtasks).await
+    } else {
+        None
+    };
+
     // Create composite task
     let composite_task = match state
         .engine
@@ -272,8 +953,12 @@ pub async fn decompose_task(
     {
         Ok(task) => task,
         Err(e) => {
+            let status = match e {
+                autodev_core::Error::DependencyCycle(_) => StatusCode::UNPROCESSABLE_ENTITY,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
             return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
+                status,
                 Json(ErrorResponse {
                     error: format!("Failed to create composite task: {}", e),
                 }),
@@ -302,13 +987,17 @@ pub async fn decompose_task(
         .map(|batch| batch.iter().map(|t| t.id.clone()).collect())
         .collect();
 
-    let total_minutes = composite_task.estimate_total_time(30); // 30 min per task estimate
+    let total_minutes = composite_task.estimate_total_time(u64::from(autodev_core::DEFAULT_TASK_MINUTES) * 60) / 60;
+    let (critical_path, critical_path_minutes) = composite_task.critical_path();
 
     Ok(Json(DecomposeTaskResponse {
         composite_task_id: composite_task.id,
         subtasks: subtasks.iter().map(task_to_response).collect(),
         parallel_batches: batch_ids,
         total_estimated_minutes: total_minutes,
+        critical_path,
+        critical_path_minutes,
+        plan_review,
     }))
 }
 
@@ -352,27 +1041,47 @@ pub async fn orchestrate_task(
         payload.repository_name.clone(),
     );
 
-    // Check execution mode: Docker local or GitHub Actions
-    if state.use_local_executor && state.docker_executor.is_some() {
-        // Use Docker-based local execution
-        tracing::info!("🐳 Orchestrating with Docker executor (local execution)");
+    // Check execution mode: local (Docker or process) or GitHub Actions
+    let config = state.vcs.load_repo_config(&repo).await.unwrap_or_else(|e| {
+        tracing::warn!("Failed to load {} of {}: {}", autodev_github::CONFIG_FILE, repo.full_name(), e);
+        autodev_github::RepoConfig::default()
+    });
+    let backend = state.router.resolve_composite(&composite_task, &config);
+    let local_exec = match state.router.executor(backend) {
+        Ok(executor) => executor.cloned(),
+        Err(e) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            ));
+        }
+    };
+
+    if let Some(local_exec) = local_exec {
+        // Use local execution
+        tracing::info!("🖥️  Orchestrating with local executor ({})", backend);
 
         let composite_clone = composite_task.clone();
         let repo_clone = repo.clone();
         let engine_clone = state.engine.clone();
-        let github_clone = state.github_client.clone();
+        let vcs_clone = state.vcs.clone();
         let db_clone = state.db.clone();
-        let docker_exec = state.docker_executor.clone().unwrap();
+        let notifier = state.notifier.clone();
+        let agent_clone = state.ai_agent.clone();
 
         // Execute in background
-        tokio::spawn(async move {
+        state.background.spawn(async move {
             if let Err(e) = autodev_executor::execute_composite_task_docker(
                 &composite_clone,
                 &repo_clone,
-                &docker_exec,
+                &local_exec,
                 &engine_clone,
-                &github_clone,
+                &vcs_clone,
+                &agent_clone,
                 &db_clone,
+                &notifier,
             ).await {
                 tracing::error!("Failed to execute composite task {}: {}", composite_clone.id, e);
             }
@@ -382,7 +1091,7 @@ pub async fn orchestrate_task(
             composite_task_id: composite_task.id.clone(),
             started_subtasks: composite_task.subtasks.iter().map(|t| t.id.clone()).collect(),
             message: format!(
-                "Started Docker local execution with {} subtasks",
+                "Started local execution with {} subtasks",
                 composite_task.subtasks.len()
             ),
         }))
@@ -394,7 +1103,7 @@ pub async fn orchestrate_task(
         let parent_branch = format!("autodev/{}", composite_task.id);
 
         if let Err(e) = state
-            .github_client
+            .vcs
             .create_branch(&repo, &parent_branch, &payload.base_branch)
             .await
         {
@@ -413,7 +1122,7 @@ pub async fn orchestrate_task(
 
             // Create branch for this subtask
             if let Err(e) = state
-                .github_client
+                .vcs
                 .create_branch(&repo, &task_branch, &parent_branch)
                 .await
             {
@@ -421,18 +1130,29 @@ pub async fn orchestrate_task(
                 continue;
             }
 
-            // Dispatch workflow
-            let mut inputs = std::collections::HashMap::new();
-            inputs.insert("task_id".to_string(), task.id.clone());
-            inputs.insert("composite_task_id".to_string(), composite_task.id.clone());
-            inputs.insert("task_title".to_string(), task.title.clone());
-            inputs.insert("prompt".to_string(), task.prompt.clone());
-            inputs.insert("base_branch".to_string(), task_branch.clone());
-            inputs.insert("target_branch".to_string(), parent_branch.clone());
+            // Dispatch workflow (with what its dependencies changed)
+            let prompt = autodev_executor::prompt_with_dependencies(task, &state.db).await;
+            let inputs = match autodev_executor::dispatch_inputs(
+                task,
+                &repo,
+                Some(&composite_task.id),
+                &prompt,
+                &task_branch,
+                &parent_branch,
+                &state.db,
+            )
+            .await
+            {
+                Ok(inputs) => inputs,
+                Err(e) => {
+                    tracing::error!("Failed to prepare inputs for subtask {}: {}", task.id, e);
+                    continue;
+                }
+            };
 
             match state
-                .github_client
-                .trigger_workflow(&repo, "autodev.yml", inputs)
+                .vcs
+                .trigger_pipeline(&repo, "autodev.yml", &task_branch, inputs)
                 .await
             {
                 Ok(workflow_run_id) => {
@@ -472,7 +1192,40 @@ pub fn task_to_response(task: &autodev_core::Task) -> TaskResponse {
         title: task.title.clone(),
         status: format!("{:?}", task.status),
         pr_url: task.pr_url.clone(),
+        pr_number: task.pr_number,
+        workflow_run_id: task.workflow_run_id.clone(),
         created_at: task.created_at.to_rfc3339(),
         completed_at: task.completed_at.map(|dt| dt.to_rfc3339()),
+        estimated_minutes: task.estimated_minutes,
+        issue_number: task.issue_number,
+        issue_url: task.issue_url.clone(),
+        created_by: task.created_by.clone(),
+        blocked_by: task.blocked_by.clone(),
+        blocked_reason: task.blocked_by.as_ref().and(task.error.clone()),
+        tags: task.tags.clone(),
+        execution_backend: task.execution_backend.map(|b| b.to_string()),
+        duplicate_of: None,
+    }
+}
+
+pub fn record_to_response(record: autodev_db::TaskRecord) -> TaskResponse {
+    TaskResponse {
+        id: record.id,
+        title: record.title,
+        status: record.status,
+        pr_url: record.pr_url,
+        pr_number: record.pr_number.and_then(|n| u64::try_from(n).ok()),
+        workflow_run_id: record.workflow_run_id,
+        created_at: record.created_at.to_rfc3339(),
+        completed_at: record.completed_at.map(|dt| dt.to_rfc3339()),
+        estimated_minutes: record.estimated_minutes.map(|m| m.max(0) as u32),
+        issue_number: record.issue_number.and_then(|n| u64::try_from(n).ok()),
+        issue_url: record.issue_url,
+        blocked_reason: record.blocked_by.as_ref().and(record.error),
+        blocked_by: record.blocked_by,
+        created_by: record.created_by,
+        tags: record.tags,
+        execution_backend: record.execution_backend,
+        duplicate_of: None,
     }
-}
\ No newline at end of file
+}
@@ -3,10 +3,19 @@ use axum::{
     http::{HeaderMap, StatusCode},
     response::IntoResponse,
 };
+use autodev_github::GitHubUrls;
 use serde_json::Value;
 
+use std::collections::HashMap;
+
 use crate::state::ApiState;
 
+/// Fixes pushed per PR branch before giving up (AUTODEV_CI_FIX_MAX_ATTEMPTS)
+const DEFAULT_CI_FIX_MAX_ATTEMPTS: u32 = 3;
+
+/// CI log excerpt in the agent prompt (passed through a container env var)
+const CI_FIX_PROMPT_LOG_CHARS: usize = 20_000;
+
 pub async fn handle_github_webhook(
     State(state): State<ApiState>,
     headers: HeaderMap,
@@ -26,6 +35,16 @@ pub async fn handle_github_webhook(
 
     tracing::info!("Received GitHub webhook event: {}", event_type);
 
+    // Only accept deliveries from the configured GitHub instance
+    let enterprise_host = headers
+        .get("x-github-enterprise-host")
+        .and_then(|v| v.to_str().ok());
+
+    if !autodev_github::WebhookHandler::verify_host(state.github_client.host(), enterprise_host) {
+        tracing::warn!("Webhook from unexpected GitHub instance: {:?}", enterprise_host);
+        return StatusCode::UNAUTHORIZED;
+    }
+
     // Verify signature (if webhook secret is configured)
     if let Ok(webhook_secret) = std::env::var("GITHUB_WEBHOOK_SECRET") {
         let payload_bytes = serde_json::to_vec(&payload).unwrap_or_default();
@@ -53,6 +72,15 @@ pub async fn handle_github_webhook(
                         pull_request.title
                     );
 
+                    // A fork's branch of the same name is never a task's PR
+                    if pull_request.head_in_repository(&repository.full_name) {
+                        state.notifier.pr_opened(
+                            &repository.full_name,
+                            &pull_request.head.ref_,
+                            pull_request.number as u64,
+                        );
+                    }
+
                     // Handle new PR
                     handle_pr_opened(state, pull_request, repository).await;
                 }
@@ -75,6 +103,10 @@ pub async fn handle_github_webhook(
 
                     // Handle workflow completion
                     if workflow_run.status == "completed" {
+                        if let Some(ref conclusion) = workflow_run.conclusion {
+                            state.notifier.workflow_run_completed(workflow_run.id, conclusion);
+                        }
+
                         handle_workflow_completion(state, workflow_run, repository).await;
                     }
                 }
@@ -85,9 +117,56 @@ pub async fn handle_github_webhook(
                         comment.body.chars().take(50).collect::<String>()
                     );
 
-                    // Check if comment starts with "autodev:"
-                    if comment.body.trim().starts_with("autodev:") {
-                        handle_issue_comment(state, comment, issue, repository).await;
+                    // Comments that are not autodev commands are ignored
+                    super::issue_command::handle_issue_comment(state, comment, issue, repository).await;
+                }
+                WebhookEvent::Push { push, repository } => {
+                    tracing::info!("Push to {} by {}", push.ref_, push.pusher.name);
+
+                    handle_push(state, push, repository).await;
+                }
+                WebhookEvent::CheckSuite { check_suite, repository } => {
+                    if check_suite.status.as_deref() == Some("completed") {
+                        handle_check_suite_completion(state, check_suite, repository).await;
+                    }
+                }
+                WebhookEvent::CheckRun { check_run, repository } => {
+                    tracing::info!(
+                        "Check run: {} - {} ({:?})",
+                        check_run.name,
+                        check_run.status,
+                        check_run.conclusion
+                    );
+
+                    // Failed GitHub Actions workflows are fixed from their workflow_run event
+                    let from_actions = check_run.app.as_ref().and_then(|app| app.slug.as_deref()) == Some("github-actions");
+                    let failed = check_run.status == "completed"
+                        && check_run.conclusion.as_deref().is_some_and(autodev_github::webhook::is_failing_conclusion);
+
+                    if failed && !from_actions && check_run.name != autodev_github::CHECK_RUN_NAME {
+                        state.background.spawn(fix_failed_check(state.clone(), check_run, repository));
+                    }
+                }
+                WebhookEvent::PullRequestClosed { pull_request, repository } => {
+                    tracing::info!(
+                        "PR closed: #{} (merged: {})",
+                        pull_request.number,
+                        pull_request.merged
+                    );
+
+                    let repo = autodev_github::Repository::new(repository.owner.login.clone(), repository.name.clone());
+                    cleanup_closed_branch(&state, &repo, &pull_request).await;
+
+                    if pull_request.merged && pull_request.head_in_repository(&repository.full_name) {
+                        state.notifier.pr_merged(
+                            &repository.full_name,
+                            &pull_request.head.ref_,
+                            pull_request.number as u64,
+                        );
+
+                        if let Some(task_id) = autodev_github::webhook::branch_task_id(&pull_request.head.ref_) {
+                            crate::issue_sync::pr_merged(&state, task_id, pull_request.number as u64).await;
+                        }
                     }
                 }
                 _ => {
@@ -104,6 +183,42 @@ pub async fn handle_github_webhook(
     }
 }
 
+/// Delete the AutoDev branch of a closed PR: any branch once merged, and the
+/// parent branch of a composite task whose PR was closed unmerged (abandoned)
+///
+/// PRs from forks are skipped: their head branch is not the one in `repo`.
+async fn cleanup_closed_branch(
+    state: &ApiState,
+    repo: &autodev_github::Repository,
+    pull_request: &autodev_github::webhook::PullRequestPayload,
+) {
+    if !pull_request.head_in_repository(&repo.full_name()) {
+        return;
+    }
+
+    let branch = &pull_request.head.ref_;
+    let Some(id) = autodev_github::webhook::branch_task_id(branch) else {
+        return;
+    };
+
+    if !pull_request.merged && !is_composite_task(state, id).await {
+        return;
+    }
+
+    autodev_executor::branches::delete_task_branch(repo, branch, state.vcs.as_ref()).await;
+}
+
+async fn is_composite_task(state: &ApiState, id: &str) -> bool {
+    if state.engine.get_composite_task(id).await.is_some() {
+        return true;
+    }
+
+    match &state.db {
+        Some(db) => matches!(db.get_composite_task(id).await, Ok(Some(_))),
+        None => false,
+    }
+}
+
 async fn handle_pr_opened(
     state: ApiState,
     pr: autodev_github::webhook::PullRequestPayload,
@@ -112,14 +227,14 @@ async fn handle_pr_opened(
     tracing::info!("Handling PR opened: #{} in {}", pr.number, repo.full_name);
 
     // Check if this is an AutoDev PR
-    if pr.title.contains("[AutoDev]") || pr.body.as_ref().map_or(false, |b| b.contains("autodev")) {
+    if pr.title.contains("[AutoDev]") || pr.body.as_ref().is_some_and(|b| b.contains("autodev")) {
         // Add a comment
         let github_repo = autodev_github::Repository::new(
             repo.owner.login.clone(),
             repo.name.clone(),
         );
 
-        if let Err(e) = state.github_client
+        if let Err(e) = state.github(&github_repo)
             .create_pr_comment(
                 &github_repo,
                 pr.number,
@@ -142,19 +257,36 @@ async fn handle_pr_review(
 
     // If review requests changes, handle with AI
     if review.state == "changes_requested" {
-        if let Some(review_body) = review.body {
+        // Inline comment threads are fixed file by file in the background
+        if autodev_github::webhook::branch_task_id(&pr.head.ref_).is_some() {
+            state.background.spawn(address_review_threads(
+                state.clone(),
+                review.id,
+                pr.clone(),
+                repo.clone(),
+            ));
+        }
+
+        if let Some(review_body) = review.body.filter(|body| !body.trim().is_empty()) {
             let github_repo = autodev_github::Repository::new(
                 repo.owner.login.clone(),
                 repo.name.clone(),
             );
 
-            // Get PR diff (simplified - in real implementation, fetch from GitHub)
-            let pr_diff = ""; // Would fetch actual diff
+            // Large diffs are reviewed in chunks
+            let chunks = match state.github(&github_repo)
+                .get_pr_diff_chunks(&github_repo, pr.number as u64, autodev_github::DEFAULT_CHUNK_CHARS)
+                .await
+            {
+                Ok(chunks) => chunks,
+                Err(e) => {
+                    tracing::error!("Failed to fetch diff of PR #{}: {}", pr.number, e);
+                    return;
+                }
+            };
 
             // Use AI to address review comments
-            match state.ai_agent
-                .review_code_changes(pr_diff, &[review_body])
-                .await
+            match autodev_ai::review_diff_chunks(state.ai_agent.as_ref(), &chunks, &[review_body]).await
             {
                 Ok(result) => {
                     let comment = format!(
@@ -166,7 +298,7 @@ async fn handle_pr_review(
                             .join("\n")
                     );
 
-                    if let Err(e) = state.github_client
+                    if let Err(e) = state.github(&github_repo)
                         .create_pr_comment(&github_repo, pr.number, &comment)
                         .await
                     {
@@ -181,170 +313,664 @@ async fn handle_pr_review(
     }
 }
 
-async fn handle_workflow_completion(
+/// Address the inline comment threads of a review on an AutoDev PR
+///
+/// Threads are grouped by file. For each file the agent reviews the file's
+/// diff against its threads; with the local executor it then pushes a fix
+/// commit to the PR branch. Every thread gets a reply saying what was done.
+async fn address_review_threads(
     state: ApiState,
-    workflow: autodev_github::webhook::WorkflowRunPayload,
-    _repo: autodev_github::webhook::RepositoryPayload,
+    review_id: u64,
+    pr: autodev_github::webhook::PullRequestPayload,
+    repo: autodev_github::webhook::RepositoryPayload,
 ) {
+    use autodev_github::review_comments::{review_threads, threads_by_file};
+
+    let github_repo = autodev_github::Repository::new(
+        repo.owner.login.clone(),
+        repo.name.clone(),
+    );
+    let pr_number = pr.number as u64;
+
+    let comments = match state.github(&github_repo).get_pr_review_comments(&github_repo, pr_number).await {
+        Ok(comments) => comments,
+        Err(e) => {
+            tracing::error!("Failed to fetch review comments of PR #{}: {}", pr_number, e);
+            return;
+        }
+    };
+
+    let threads: Vec<_> = review_threads(&comments)
+        .into_iter()
+        .filter(|thread| thread.root.pull_request_review_id == Some(review_id))
+        .collect();
+    if threads.is_empty() {
+        return;
+    }
+
+    let patches: HashMap<String, String> = match state.github(&github_repo).get_pr_files(&github_repo, pr_number).await {
+        Ok(files) => files
+            .into_iter()
+            .filter_map(|file| file.patch.map(|patch| (file.filename, patch)))
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to fetch files of PR #{}: {}", pr_number, e);
+            HashMap::new()
+        }
+    };
+
+    let files = threads_by_file(threads);
     tracing::info!(
-        "Handling workflow completion: {} - {:?}",
-        workflow.name,
-        workflow.conclusion
+        "Addressing review {} on PR #{}: {} files",
+        review_id,
+        pr_number,
+        files.len()
     );
 
-    // If workflow failed, try to fix with AI
-    if workflow.conclusion == Some("failure".to_string()) {
-        // In real implementation, fetch workflow logs
-        let ci_logs = "Build failed: syntax error in main.rs";
+    for (index, (path, threads)) in files.iter().enumerate() {
+        // Only the file's hunks: the whole PR diff would bury the threads
+        let file_diff = match patches.get(path) {
+            Some(patch) => format!("diff --git a/{0} b/{0}
+--- a/{0}
++++ b/{0}
+{1}", path, patch),
+            None => threads.iter().map(|t| t.root.diff_hunk.clone()).collect::<Vec<_>>().join("
+"),
+        };
+        let thread_prompts: Vec<String> = threads.iter().map(|t| t.to_prompt()).collect();
+
+        let analysis = match state.ai_agent.review_code_changes(&file_diff, &thread_prompts).await {
+            Ok(result) => result.comments.join("
+
+"),
+            Err(e) => {
+                tracing::error!("Failed to review {} with AI: {}", path, e);
+                continue;
+            }
+        };
+
+        let reply = match state.local_executor {
+            Some(ref local_executor) => {
+                let prompt = format!(
+                    "A reviewer left comments on `{}` in this pull request. Address every comment thread below by changing `{}` (and only what else is needed).
+
+## Threads
 
-        match state.ai_agent.fix_ci_failures(ci_logs).await {
-            Ok(result) => {
-                tracing::info!(
-                    "AI suggested fixes for CI failure: {:?}",
-                    result.changes_made
+{}
+
+## Analysis
+
+{}",
+                    path,
+                    path,
+                    thread_prompts.join("
+
+"),
+                    analysis
                 );
 
-                // Would create a new commit with fixes
-                // This is simplified - real implementation would:
-                // 1. Clone the repo
-                // 2. Apply fixes
-                // 3. Commit and push
-                // 4. Update the PR
+                let result = local_executor
+                    .fix_branch(
+                        &format!("review-{}-{}", review_id, index + 1),
+                        &github_repo,
+                        &pr.head.ref_,
+                        &format!("Address review comments on {}", path),
+                        &prompt,
+                    )
+                    .await;
+
+                match result {
+                    Ok(result) if result.success && result.has_changes => format!(
+                        "🔧 Addressed in {}.
+
+{}",
+                        result.commit_sha.as_deref().unwrap_or("a new commit"),
+                        analysis
+                    ),
+                    Ok(result) if result.success => format!("🔍 No code changes were needed.
+
+{}", analysis),
+                    Ok(result) => format!(
+                        "⚠️ Could not address this automatically: {}
+
+{}",
+                        result.error.unwrap_or_default(),
+                        analysis
+                    ),
+                    Err(e) => {
+                        tracing::error!("Review fix for {} on PR #{} failed: {}", path, pr_number, e);
+                        format!("⚠️ Could not address this automatically: {}
+
+{}", e, analysis)
+                    }
+                }
             }
-            Err(e) => {
-                tracing::error!("Failed to fix CI with AI: {}", e);
+            // GitHub Actions mode has no container to apply the fix in
+            None => format!(
+                "🔍 Suggested change:
+
+{}
+
+_Set `AUTODEV_LOCAL_EXECUTOR=true` to apply review fixes automatically._",
+                analysis
+            ),
+        };
+
+        for thread in threads {
+            if let Err(e) = state
+                .github_client
+                .reply_to_review_comment(&github_repo, pr_number, thread.root.id, &reply)
+                .await
+            {
+                tracing::error!("Failed to reply to review comment {}: {}", thread.root.id, e);
             }
         }
     }
+}
 
-    // Update task status in database
-    if let Some(ref db) = state.db {
-        // Extract task ID from workflow name or inputs
-        // This is simplified - real implementation would parse properly
-        if let Some(task_id) = extract_task_id(&workflow.name) {
-            let status = if workflow.conclusion == Some("success".to_string()) {
-                autodev_core::TaskStatus::Completed
-            } else {
-                autodev_core::TaskStatus::Failed
-            };
+async fn handle_workflow_completion(
+    state: ApiState,
+    workflow: autodev_github::webhook::WorkflowRunPayload,
+    repo: autodev_github::webhook::RepositoryPayload,
+) {
+    tracing::info!(
+        "Handling workflow completion: {} - {:?}",
+        workflow.name,
+        workflow.conclusion
+    );
 
-            if let Err(e) = db.update_task_status(&task_id, status, None).await {
-                tracing::error!("Failed to update task status: {}", e);
-            }
+    // If CI failed on an AutoDev branch, try to fix it with AI (AutoDev's own
+    // workflow failing is a task failure, handled by the retry logic)
+    let autodev_branch = workflow
+        .head_branch
+        .as_deref()
+        .and_then(autodev_github::webhook::branch_task_id)
+        .is_some();
+    if workflow.conclusion.as_deref() == Some("failure") && autodev_branch && !workflow.is_autodev_workflow() {
+        // Runs in the background: the fix can take minutes, webhooks time out after 10s
+        state.background.spawn(fix_ci_failure(state.clone(), workflow.clone(), repo));
+    }
+
+    // The engine rejects invalid transitions (e.g. a late failure of a
+    // cancelled task) and writes accepted ones through to the database
+    if let Some(task_id) = extract_task_id(&workflow) {
+        let status = match workflow.conclusion.as_deref() {
+            Some("success") => autodev_core::TaskStatus::Completed,
+            Some("cancelled") => autodev_core::TaskStatus::Cancelled,
+            _ => autodev_core::TaskStatus::Failed,
+        };
+
+        if let Err(e) = state.engine.update_task_status(&task_id, status, None).await {
+            tracing::warn!("Not updating task {} to {:?}: {}", task_id, status, e);
         }
     }
 }
 
-fn extract_task_id(workflow_name: &str) -> Option<String> {
-    // Extract task ID from workflow name
-    // Format: "AutoDev - Task {task_id}"
-    if workflow_name.starts_with("AutoDev - Task ") {
-        Some(workflow_name.replace("AutoDev - Task ", ""))
-    } else {
-        None
+/// Count a fix attempt on the task of an AutoDev branch, so a fix that keeps
+/// failing CI does not loop forever
+///
+/// Attempts are stored with the task, so they survive restarts and are shared
+/// by every API instance; without a database no fixes are attempted.
+async fn claim_ci_fix_attempt(state: &ApiState, branch: &str) -> bool {
+    let Some(task_id) = autodev_github::webhook::branch_task_id(branch) else {
+        return false;
+    };
+    let Some(ref db) = state.db else {
+        tracing::warn!("Not fixing CI on {}: counting fix attempts needs a database", branch);
+        return false;
+    };
+
+    let max_attempts = std::env::var("AUTODEV_CI_FIX_MAX_ATTEMPTS")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_CI_FIX_MAX_ATTEMPTS);
+
+    match db.claim_ci_fix_attempt(task_id, max_attempts).await {
+        Ok(claimed) => claimed,
+        Err(e) => {
+            tracing::error!("Failed to count CI fix attempt for {}: {}", branch, e);
+            false
+        }
     }
 }
 
-async fn handle_issue_comment(
+/// Fix a failed CI run on a PR branch
+///
+/// The AI analyzes the run's logs, the agent applies the fix in a worker
+/// container and pushes it to the PR branch, and the PR gets a comment with
+/// what was changed. Without the local executor only the analysis is posted.
+async fn fix_ci_failure(
     state: ApiState,
-    comment: autodev_github::webhook::CommentPayload,
-    issue: autodev_github::webhook::IssuePayload,
+    workflow: autodev_github::webhook::WorkflowRunPayload,
     repo: autodev_github::webhook::RepositoryPayload,
 ) {
-    tracing::info!("Handling issue comment with autodev command: #{}", issue.number);
+    let github_repo = autodev_github::Repository::new(
+        repo.owner.login.clone(),
+        repo.name.clone(),
+    );
 
-    // Parse "autodev:" prompt
-    let prompt = match comment.body.trim().strip_prefix("autodev:") {
-        Some(p) => p.trim(),
-        None => {
-            tracing::warn!("Comment does not start with 'autodev:' prefix");
-            return;
-        }
+    let Some(branch) = workflow.head_branch.clone() else {
+        return;
     };
 
-    if prompt.is_empty() {
-        tracing::warn!("Empty prompt after 'autodev:' prefix");
+    let pr_number = match workflow.pull_requests.first() {
+        Some(pr) => Some(pr.number),
+        None => state.github(&github_repo)
+            .find_pr_by_head(
+                &github_repo,
+                workflow.head_repository.as_ref().map(|head| head.owner.login.as_str()),
+                &branch,
+            )
+            .await
+            .map(|pr| pr.map(|pr| pr.number))
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to look up PR for branch {}: {}", branch, e);
+                None
+            }),
+    };
 
-        // Send error comment to issue
-        let github_repo = autodev_github::Repository::new(
-            repo.owner.login.clone(),
-            repo.name.clone(),
-        );
+    let Some(pr_number) = pr_number else {
+        tracing::info!("Workflow run {} on {} has no PR, not fixing", workflow.id, branch);
+        return;
+    };
 
-        let error_msg = "❌ AutoDev 오류: 프롬프트가 비어있습니다.\n\n사용 예시:\n```\nautodev: Add Google OAuth authentication\n```";
+    if !claim_ci_fix_attempt(&state, &branch).await {
+        tracing::warn!("CI fix attempts exhausted for {} ({}), leaving it to a human", branch, github_repo.full_name());
+        return;
+    }
 
-        if let Err(e) = state.github_client
-            .create_issue_comment(&github_repo, issue.number, error_msg)
-            .await
-        {
-            tracing::error!("Failed to post error comment: {}", e);
+    // Logs of the failed steps, truncated for the AI prompt
+    let ci_logs = match state.github(&github_repo)
+        .get_workflow_run_logs(&github_repo, workflow.id, autodev_github::DEFAULT_MAX_LOG_CHARS)
+        .await
+    {
+        Ok(ci_logs) => ci_logs,
+        Err(e) => {
+            tracing::error!("Failed to fetch logs of workflow run {}: {}", workflow.id, e);
+            return;
         }
+    };
+
+    let url = format!(
+        "{}/actions/runs/{}",
+        github_repo.web_url(state.github_client.host()),
+        workflow.id
+    );
 
+    fix_ci(
+        &state,
+        &github_repo,
+        CiFailure {
+            name: workflow.name,
+            run_id: format!("cifix-{}", workflow.id),
+            url,
+            branch,
+            pr_number,
+            logs: ci_logs,
+        },
+    )
+    .await;
+}
+
+/// Fix a failed required check reported by a CI other than GitHub Actions
+///
+/// Only checks on AutoDev branches that branch protection requires are
+/// handled; the check run's output stands in for the CI logs.
+async fn fix_failed_check(
+    state: ApiState,
+    check_run: autodev_github::webhook::CheckRunPayload,
+    repo: autodev_github::webhook::RepositoryPayload,
+) {
+    let Some(branch) = check_run.head_branch().map(str::to_string) else {
+        return;
+    };
+    if autodev_github::webhook::branch_task_id(&branch).is_none() {
         return;
     }
 
-    tracing::info!("Parsed prompt: {}", prompt);
-
     let github_repo = autodev_github::Repository::new(
         repo.owner.login.clone(),
         repo.name.clone(),
     );
 
-    // Post acknowledgment comment
-    let ack_msg = format!(
-        "🤖 AutoDev 작업이 시작되었습니다.\n\n**작업 내용:** {}\n\n워크플로우 실행 상태는 [Actions 탭](https://github.com/{}/actions)에서 확인하실 수 있습니다.",
-        prompt,
-        repo.full_name
-    );
+    let pr_number = match check_run.pull_requests.first() {
+        Some(pr) => Some(pr.number),
+        None => state.github(&github_repo)
+            .find_pr_by_branch(&github_repo, &branch)
+            .await
+            .map(|pr| pr.map(|pr| pr.number))
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to look up PR for branch {}: {}", branch, e);
+                None
+            }),
+    };
 
-    if let Err(e) = state.github_client
-        .create_issue_comment(&github_repo, issue.number, &ack_msg)
-        .await
-    {
-        tracing::error!("Failed to post acknowledgment comment: {}", e);
+    let Some(pr_number) = pr_number else {
+        tracing::debug!("Check run {} on {} has no PR, not fixing", check_run.id, branch);
+        return;
+    };
+
+    let required = match state.github(&github_repo).get_pr_mergeability(&github_repo, pr_number).await {
+        Ok(mergeability) => mergeability.required_checks.iter().any(|c| c.name == check_run.name),
+        Err(e) => {
+            tracing::warn!("Failed to read required checks of PR #{}: {}", pr_number, e);
+            return;
+        }
+    };
+    if !required {
+        tracing::info!("Check {} on PR #{} is not required, not fixing", check_run.name, pr_number);
+        return;
+    }
+
+    record_branch_event(
+        &state,
+        &branch,
+        "CHECK_FAILED",
+        &format!(
+            "Required check {} concluded {} on {}",
+            check_run.name,
+            check_run.conclusion.as_deref().unwrap_or("unknown"),
+            check_run.head_sha
+        ),
+    )
+    .await;
+
+    if !claim_ci_fix_attempt(&state, &branch).await {
+        tracing::warn!("CI fix attempts exhausted for {} ({}), leaving it to a human", branch, github_repo.full_name());
+        return;
     }
 
-    // Trigger workflow via GitHub Actions
-    let mut inputs = std::collections::HashMap::new();
-    inputs.insert("prompt".to_string(), prompt.to_string());
-    inputs.insert("task_title".to_string(), format!("AutoDev: {}", prompt));
-    inputs.insert("base_branch".to_string(), "main".to_string()); // TODO: Make configurable
+    let output = check_run.output.clone().unwrap_or_default();
+    let logs = [output.title, output.summary, output.text]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let logs = if logs.is_empty() {
+        format!("Check {} failed without reporting any output.", check_run.name)
+    } else {
+        autodev_github::logs::truncate_log(&logs, autodev_github::DEFAULT_MAX_LOG_CHARS)
+    };
 
-    match state.github_client
-        .trigger_workflow(&github_repo, "autodev.yml", inputs)
-        .await
-    {
-        Ok(workflow_run_id) => {
-            tracing::info!("Workflow triggered successfully: {}", workflow_run_id);
-
-            // Optionally: Store task in database if available
-            if let Some(ref db) = state.db {
-                let task = autodev_core::Task::new(
-                    format!("AutoDev: {}", prompt),
-                    format!("Triggered from Issue #{}", issue.number),
-                    prompt.to_string(),
-                );
+    let url = check_run.html_url.clone().unwrap_or_else(|| {
+        format!("{}/pull/{}/checks", github_repo.web_url(state.github_client.host()), pr_number)
+    });
+
+    fix_ci(
+        &state,
+        &github_repo,
+        CiFailure {
+            name: check_run.name,
+            run_id: format!("cifix-check-{}", check_run.id),
+            url,
+            branch,
+            pr_number,
+            logs,
+        },
+    )
+    .await;
+}
+
+/// A failed CI run on a PR branch
+struct CiFailure {
+    /// Workflow or check name
+    name: String,
+    /// Names the fix run and its logs
+    run_id: String,
+    url: String,
+    branch: String,
+    pr_number: u64,
+    logs: String,
+}
+
+/// Analyze a CI failure, push a fix with the local executor and comment on the PR
+async fn fix_ci(state: &ApiState, github_repo: &autodev_github::Repository, failure: CiFailure) {
+    let CiFailure { name, run_id, url: run_url, branch, pr_number, logs: ci_logs } = failure;
+
+    let analysis = match state.ai_agent.fix_ci_failures(&ci_logs).await {
+        Ok(result) => result.comments.join("\n\n"),
+        Err(e) => {
+            tracing::error!("Failed to fix CI with AI: {}", e);
+            return;
+        }
+    };
 
-                if let Err(e) = db.save_task(&task, &repo.owner.login, &repo.name).await {
-                    tracing::error!("Failed to store task in database: {}", e);
+    let config = state.github(github_repo)
+        .load_repo_config(github_repo)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load {} of {}: {}", autodev_github::CONFIG_FILE, github_repo.full_name(), e);
+            autodev_github::RepoConfig::default()
+        });
+
+    let comment = match state.local_executor {
+        Some(ref local_executor) => {
+            let prompt = format!(
+                "The CI workflow \"{}\" failed on this branch. Fix the failure so CI passes, changing only what is needed.\n\n## Analysis\n\n{}\n\n## CI logs\n\n```\n{}\n```",
+                name,
+                analysis,
+                // 컨테이너 환경 변수로 전달되므로 더 짧게 유지
+                autodev_github::logs::truncate_log(&ci_logs, CI_FIX_PROMPT_LOG_CHARS)
+            );
+            let prompt = config.apply_to_prompt(&prompt);
+
+            let result = local_executor
+                .fix_branch(
+                    &run_id,
+                    github_repo,
+                    &branch,
+                    &format!("Fix CI failure in {}", name),
+                    &prompt,
+                )
+                .await;
+
+            match result {
+                Ok(result) if result.success && result.has_changes => {
+                    let protected = config.protected_changes(&result.files_changed);
+                    let warning = if protected.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            "\n\n⚠️ **Touches protected paths, review carefully:** {}",
+                            protected.iter().map(|f| format!("`{}`", f)).collect::<Vec<_>>().join(", ")
+                        )
+                    };
+
+                    format!(
+                        "🔧 Pushed a fix for the [failed CI run]({}) in {}.\n\n**Changed files:**\n{}{}\n\n<details><summary>Analysis</summary>\n\n{}\n\n</details>",
+                        run_url,
+                        result.commit_sha.as_deref().unwrap_or("a new commit"),
+                        result.files_changed.iter()
+                            .map(|f| format!("- `{}`", f))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                        warning,
+                        analysis
+                    )
+                }
+                Ok(result) if result.success => format!(
+                    "🔍 Analyzed the [failed CI run]({}), but no code changes were needed.\n\n{}",
+                    run_url, analysis
+                ),
+                Ok(result) => format!(
+                    "⚠️ Could not fix the [failed CI run]({}): {}\n\n{}",
+                    run_url,
+                    result.error.unwrap_or_default(),
+                    analysis
+                ),
+                Err(e) => {
+                    tracing::error!("CI fix container failed for {}: {}", branch, e);
+                    format!("⚠️ Could not fix the [failed CI run]({}): {}\n\n{}", run_url, e, analysis)
                 }
             }
         }
-        Err(e) => {
-            tracing::error!("Failed to trigger workflow: {}", e);
+        // GitHub Actions mode has no container to apply the fix in
+        None => format!(
+            "🔍 Suggested fix for the [failed CI run]({}):\n\n{}\n\n_Set `AUTODEV_LOCAL_EXECUTOR=true` to apply fixes automatically._",
+            run_url, analysis
+        ),
+    };
 
-            // Post error comment
-            let error_msg = format!(
-                "❌ AutoDev 워크플로우 트리거 실패\n\n**오류:** {}\n\n다음을 확인해주세요:\n\n- `.github/workflows/autodev.yml` 파일이 존재하는지\n- `ANTHROPIC_API_KEY` secret이 설정되어 있는지\n- GitHub Actions가 활성화되어 있는지",
-                e
-            );
+    if let Err(e) = state.github(github_repo)
+        .create_pr_comment(github_repo, pr_number as u32, &comment)
+        .await
+    {
+        tracing::error!("Failed to comment on PR #{}: {}", pr_number, e);
+    }
+}
+
+/// React to a push to an AutoDev branch made outside of AutoDev's own flow
+///
+/// Every push is recorded on the task. A deleted branch fails the task if it
+/// is still running, and a force push is flagged since it rewrites what the
+/// task (or a composite task's merged subtasks) produced.
+async fn handle_push(
+    state: ApiState,
+    push: autodev_github::webhook::PushPayload,
+    repo: autodev_github::webhook::RepositoryPayload,
+) {
+    let Some(branch) = push.branch() else {
+        return;
+    };
+    let Some(task_id) = autodev_github::webhook::branch_task_id(branch) else {
+        return;
+    };
 
-            if let Err(e) = state.github_client
-                .create_issue_comment(&github_repo, issue.number, &error_msg)
+    let short = |sha: &str| sha.chars().take(7).collect::<String>();
+
+    if push.deleted {
+        record_branch_event(
+            &state,
+            branch,
+            "BRANCH_DELETED",
+            &format!("{} deleted {} in {}", push.pusher.name, branch, repo.full_name),
+        )
+        .await;
+
+        let running = state.engine.get_task(task_id).await.is_some_and(|task| {
+            matches!(
+                task.status,
+                autodev_core::TaskStatus::Pending
+                    | autodev_core::TaskStatus::WaitingDependencies
+                    | autodev_core::TaskStatus::InProgress
+            )
+        });
+        if running {
+            tracing::warn!("Branch {} of unfinished task {} was deleted", branch, task_id);
+
+            let error = format!("Branch {} was deleted by {}", branch, push.pusher.name);
+            if let Err(e) = state
+                .engine
+                .update_task_status(task_id, autodev_core::TaskStatus::Failed, Some(error))
                 .await
             {
-                tracing::error!("Failed to post error comment: {}", e);
+                tracing::error!("Failed to fail task {}: {}", task_id, e);
+            }
+        }
+        return;
+    }
+
+    let message = if push.forced {
+        tracing::warn!("{} force-pushed {} ({}..{})", push.pusher.name, branch, short(&push.before), short(&push.after));
+        format!(
+            "{} force-pushed {} ({}..{}), rewriting its history",
+            push.pusher.name,
+            branch,
+            short(&push.before),
+            short(&push.after)
+        )
+    } else {
+        format!(
+            "{} pushed {} commit(s) to {} ({}..{})",
+            push.pusher.name,
+            push.commits.len(),
+            branch,
+            short(&push.before),
+            short(&push.after)
+        )
+    };
+
+    record_branch_event(
+        &state,
+        branch,
+        if push.forced { "BRANCH_FORCE_PUSHED" } else { "BRANCH_PUSHED" },
+        &message,
+    )
+    .await;
+}
+
+/// Record the outcome of a check suite on an AutoDev branch on its task
+async fn handle_check_suite_completion(
+    state: ApiState,
+    check_suite: autodev_github::webhook::CheckSuitePayload,
+    repo: autodev_github::webhook::RepositoryPayload,
+) {
+    let Some(branch) = check_suite.head_branch.as_deref() else {
+        return;
+    };
+    if autodev_github::webhook::branch_task_id(branch).is_none() {
+        return;
+    }
+
+    let conclusion = check_suite.conclusion.as_deref().unwrap_or("unknown");
+    let app = check_suite.app.as_ref().map_or("unknown app", |app| app.name.as_str());
+
+    tracing::info!(
+        "Check suite of {} on {} in {} concluded {}",
+        app,
+        branch,
+        repo.full_name,
+        conclusion
+    );
+
+    let event_type = if autodev_github::webhook::is_failing_conclusion(conclusion) {
+        "CHECKS_FAILED"
+    } else {
+        "CHECKS_COMPLETED"
+    };
+
+    record_branch_event(
+        &state,
+        branch,
+        event_type,
+        &format!("Checks from {} concluded {} on {}", app, conclusion, check_suite.head_sha),
+    )
+    .await;
+}
+
+/// Record an event on the task an AutoDev branch belongs to
+///
+/// Composite tasks only get the live event; execution logs belong to tasks.
+async fn record_branch_event(state: &ApiState, branch: &str, event_type: &str, message: &str) {
+    let Some(task_id) = autodev_github::webhook::branch_task_id(branch) else {
+        return;
+    };
+
+    state.engine.log_event(task_id, event_type, message);
+
+    if let Some(ref db) = state.db {
+        if state.engine.get_task(task_id).await.is_some() {
+            if let Err(e) = db.add_execution_log(task_id, event_type, message).await {
+                tracing::error!("Failed to log {} for task {}: {}", event_type, task_id, e);
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Task ID of an AutoDev run: the `[task_id]` at the end of its run-name
+fn extract_task_id(workflow: &autodev_github::webhook::WorkflowRunPayload) -> Option<String> {
+    // Other workflows' titles (e.g. PR titles) may end in brackets too
+    if workflow.name == "AutoDev" || workflow.name == "AutoDev Subtask" {
+        if let Some(task_id) = workflow
+            .display_title
+            .as_deref()
+            .and_then(autodev_github::workflow::correlation_id)
+        {
+            return Some(task_id.to_string());
+        }
+    }
+
+    // Legacy format: "AutoDev - Task {task_id}"
+    workflow.name.strip_prefix("AutoDev - Task ").map(str::to_string)
+}
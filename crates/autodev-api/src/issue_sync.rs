@@ -0,0 +1,179 @@
+//! Progress comments on the issues tasks were started from
+//!
+//! Tasks created by an `autodev:` issue comment remember their issue. Their
+//! start, failure and pull request are reported back on the issue, and the
+//! issue is closed once the pull request is merged.
+
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+
+use autodev_core::{Task, TaskEvent, TaskStatus};
+use autodev_github::Repository;
+
+use crate::state::ApiState;
+
+/// Follow the engine's task events and comment on linked issues until aborted
+pub fn spawn(state: &ApiState) -> JoinHandle<()> {
+    let state = state.clone();
+    let mut events = state.engine.subscribe();
+
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => on_event(&state, &event).await,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Issue sync missed {} task events", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+async fn on_event(state: &ApiState, event: &TaskEvent) {
+    let task_id = match event {
+        TaskEvent::StatusChanged { task_id, .. } | TaskEvent::PrCreated { task_id, .. } => task_id,
+        _ => return,
+    };
+
+    let Some(body) = progress_comment(event) else {
+        return;
+    };
+    let Some(task) = find_task(state, task_id).await else {
+        return;
+    };
+    let Some((repo, issue_number)) = issue_of(&task) else {
+        return;
+    };
+
+    let body = format!("{}\n\n**작업:** {} (`{}`)", body, task.title, task.id);
+    if let Err(e) = state.github(&repo).create_issue_comment(&repo, issue_number, &body).await {
+        tracing::warn!("Failed to report task {} on issue #{}: {}", task.id, issue_number, e);
+    }
+}
+
+fn progress_comment(event: &TaskEvent) -> Option<String> {
+    match event {
+        TaskEvent::StatusChanged { task_id, status: TaskStatus::InProgress, attempts, .. } => Some(format!(
+            "🤖 AutoDev 작업이 시작되었습니다. (시도 {})\n\n`autodev status {}`로 상태를 조회하거나 `autodev cancel {}`로 취소할 수 있습니다.",
+            attempts, task_id, task_id
+        )),
+        TaskEvent::StatusChanged { status: TaskStatus::Failed, error, .. } => Some(format!(
+            "❌ AutoDev 작업이 실패했습니다.\n\n**오류:** {}\n\n`autodev retry`로 다시 실행할 수 있습니다.",
+            error.as_deref().unwrap_or("알 수 없는 오류")
+        )),
+        TaskEvent::StatusChanged { status: TaskStatus::BudgetExceeded, error, .. } => Some(format!(
+            "💸 AI 예산이 소진되어 작업을 일시 중지했습니다.\n\n**사유:** {}\n\n예산을 늘린 뒤 `autodev retry`로 다시 실행할 수 있습니다.",
+            error.as_deref().unwrap_or("알 수 없음")
+        )),
+        TaskEvent::PrCreated { pr_url, .. } => Some(format!(
+            "🔀 PR이 열렸습니다: {}\n\nPR이 병합되면 이 Issue를 닫습니다.",
+            pr_url
+        )),
+        _ => None,
+    }
+}
+
+/// Report the merged pull request of a task and close its issue
+pub async fn pr_merged(state: &ApiState, task_id: &str, pr_number: u64) {
+    let Some(task) = find_task(state, task_id).await else {
+        return;
+    };
+    let Some((repo, issue_number)) = issue_of(&task) else {
+        return;
+    };
+
+    let body = format!("✅ PR #{}이(가) 병합되어 Issue를 닫습니다.\n\n**작업:** {} (`{}`)", pr_number, task.title, task.id);
+    if let Err(e) = state.github(&repo).create_issue_comment(&repo, issue_number, &body).await {
+        tracing::warn!("Failed to report merge of task {} on issue #{}: {}", task.id, issue_number, e);
+    }
+
+    if let Err(e) = state.github(&repo).close_issue(&repo, issue_number).await {
+        tracing::warn!("Failed to close issue #{}: {}", issue_number, e);
+    }
+}
+
+/// The engine only keeps open tasks after a restart, so fall back to the database
+async fn find_task(state: &ApiState, task_id: &str) -> Option<Task> {
+    if let Some(task) = state.engine.get_task(task_id).await {
+        return Some(task);
+    }
+
+    let db = state.db.as_ref()?;
+    match db.get_task(task_id).await {
+        Ok(record) => record.and_then(|r| r.to_task().ok()),
+        Err(e) => {
+            tracing::warn!("Failed to load task {}: {}", task_id, e);
+            None
+        }
+    }
+}
+
+/// Issue `task` was started from, in the repository the task works on
+///
+/// The issue URL is only for display; issues always live in the task's repository.
+fn issue_of(task: &Task) -> Option<(Repository, u32)> {
+    let number = u32::try_from(task.issue_number?).ok()?;
+    let repo = Repository::new(task.repository_owner.clone()?, task.repository_name.clone()?);
+    Some((repo, number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn issue_task() -> Task {
+        Task::new("AutoDev: Add login".to_string(), String::new(), "Add login".to_string())
+            .with_issue(42, "https://github.com/other/fork/issues/7".to_string())
+            .with_repository("myorg".to_string(), "myrepo".to_string())
+    }
+
+    fn status_changed(status: TaskStatus, error: Option<&str>) -> TaskEvent {
+        TaskEvent::StatusChanged {
+            task_id: "t1".to_string(),
+            status,
+            error: error.map(str::to_string),
+            attempts: 2,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_issue_of() {
+        // The repository comes from the task, not from the (display) URL
+        let (repo, number) = issue_of(&issue_task()).unwrap();
+        assert_eq!(repo.full_name(), "myorg/myrepo");
+        assert_eq!(number, 42);
+
+        let unlinked = Task::new("T".to_string(), String::new(), String::new());
+        assert!(issue_of(&unlinked).is_none());
+
+        let unknown_repository = Task { repository_owner: None, ..issue_task() };
+        assert!(issue_of(&unknown_repository).is_none());
+
+        let too_large = Task { issue_number: Some(u64::MAX), ..issue_task() };
+        assert!(issue_of(&too_large).is_none());
+    }
+
+    #[test]
+    fn test_progress_comment() {
+        let comment = progress_comment(&status_changed(TaskStatus::InProgress, None)).unwrap();
+        assert!(comment.contains("시도 2"));
+        assert!(comment.contains("`autodev cancel t1`"));
+
+        let comment = progress_comment(&status_changed(TaskStatus::Failed, Some("tests failed"))).unwrap();
+        assert!(comment.contains("**오류:** tests failed"));
+        let comment = progress_comment(&status_changed(TaskStatus::Failed, None)).unwrap();
+        assert!(comment.contains("알 수 없는 오류"));
+
+        let pr = TaskEvent::PrCreated {
+            task_id: "t1".to_string(),
+            pr_url: "https://github.com/myorg/myrepo/pull/9".to_string(),
+            timestamp: Utc::now(),
+        };
+        assert!(progress_comment(&pr).unwrap().contains("https://github.com/myorg/myrepo/pull/9"));
+
+        assert_eq!(progress_comment(&status_changed(TaskStatus::Completed, None)), None);
+    }
+}
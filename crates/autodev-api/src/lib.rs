@@ -1,6 +1,12 @@
+pub mod artifacts;
+pub mod audit;
+pub mod auth;
 pub mod handlers;
+pub mod issue_sync;
 pub mod routes;
+pub mod server;
 pub mod state;
 
 pub use routes::create_router;
+pub use server::serve;
 pub use state::ApiState;
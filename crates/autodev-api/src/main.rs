@@ -3,8 +3,13 @@ use std::env;
 use std::sync::Arc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+mod artifacts;
+mod audit;
+mod auth;
 mod handlers;
+mod issue_sync;
 mod routes;
+mod server;
 mod state;
 
 use autodev_core::AutoDevEngine;
@@ -20,60 +25,61 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Load environment variables
+    // Load environment variables (.env, then the config file for unset ones)
     dotenv::dotenv().ok();
+    if let Some(path) = autodev_core::config::load_into_env(None)? {
+        tracing::info!("Loaded configuration from {}", path.display());
+    }
 
     // Get configuration
     let port = env::var("API_PORT")
         .unwrap_or_else(|_| "3000".to_string())
         .parse::<u16>()?;
 
-    let github_token = env::var("GITHUB_TOKEN")
-        .expect("GITHUB_TOKEN must be set");
-
-    let ai_agent_type = env::var("AI_AGENT_TYPE")
-        .unwrap_or_else(|_| "claude-code".to_string());
-
-    // Initialize engine
-    let engine = Arc::new(AutoDevEngine::new());
-
     // Initialize database (optional)
     let db = if let Ok(db_url) = env::var("DATABASE_URL") {
         let database = autodev_db::Database::new(&db_url).await?;
-        database.init_schema().await?;
+        let applied = database.migrate().await?;
+        if !applied.is_empty() {
+            tracing::info!("Applied database migrations: {:?}", applied);
+        }
         Some(Arc::new(database))
     } else {
         tracing::warn!("No DATABASE_URL provided, running without persistence");
         None
     };
 
-    // Initialize GitHub client
+    // Initialize engine (database-backed when available so state survives restarts)
+    let engine = match db {
+        Some(ref database) => {
+            let engine = AutoDevEngine::with_store(database.clone());
+            engine.hydrate().await?;
+            Arc::new(engine)
+        }
+        None => Arc::new(AutoDevEngine::new()),
+    };
+
+    // Initialize GitHub client (GitHub App when configured, otherwise GITHUB_TOKEN)
     let github_client = Arc::new(
-        autodev_github::GitHubClient::new(github_token)?
+        autodev_github::GitHubClient::from_env().await?
     );
+    // Organizations in AUTODEV_ORGS use their own token or App installation
+    let orgs = Arc::new(autodev_github::OrgClients::from_env(github_client.clone())?);
 
     // Initialize AI agent
-    // Try OAuth token first (Claude subscription), fallback to API key
-    let ai_agent: Arc<dyn autodev_ai::AIAgent> =
-        if let Ok(oauth_token) = env::var("CLAUDE_CODE_OAUTH_TOKEN") {
-            tracing::info!("Using Docker-based AI executor with Claude subscription OAuth token");
-            Arc::new(
-                autodev_ai::DockerAIExecutor::new(oauth_token)
-                    .expect("Failed to initialize Docker AI executor")
-            )
-        } else if let Ok(api_key) = env::var("ANTHROPIC_API_KEY") {
-            tracing::info!("Using HTTP API-based AI agent with API key");
-            Arc::new(autodev_ai::ClaudeAgent::new(api_key))
-        } else {
-            panic!("Either CLAUDE_CODE_OAUTH_TOKEN or ANTHROPIC_API_KEY must be set");
-        };
+    // AI_AGENT_TYPE selects the backend; Claude tries the OAuth token first (Claude subscription), then the API key
+    // AUTODEV_AI_CACHE_TTL caches structured responses (decomposition, domain detection)
+    let ai_agent: Arc<dyn autodev_ai::AIAgent> = autodev_executor::with_response_cache(
+        autodev_ai::create_agent(&autodev_ai::AgentConfig::from_env().with_oauth(true))?,
+        &db,
+    );
 
-    // Initialize Docker executor if local execution is enabled
-    let use_local_executor = env::var("AUTODEV_LOCAL_EXECUTOR")
-        .unwrap_or_else(|_| "false".to_string())
-        .to_lowercase() == "true";
+    // Initialize the local executor (AUTODEV_EXECUTOR: docker or process) if local execution is enabled
+    // or the default execution backend is local
+    let local_kind = autodev_executor::routing::local_executor_kind();
+    let use_local_executor = local_kind.is_some();
 
-    let docker_executor = if use_local_executor {
+    let local_executor = if let Some(kind) = local_kind {
         let workspace_dir = env::var("AUTODEV_WORKSPACE_DIR")
             .unwrap_or_else(|_| "/tmp/autodev-workspace".to_string());
 
@@ -92,18 +98,23 @@ async fn main() -> Result<()> {
         let autodev_server_url = env::var("AUTODEV_SERVER_URL")
             .ok();
 
-        match autodev_local_executor::DockerExecutor::new(
+        let log_sink = autodev_executor::TaskLogSink::new(engine.clone(), db.clone());
+
+        match autodev_local_executor::create_executor(
+            kind,
             anthropic_api_key,
             github_token,
             autodev_server_url,
             std::path::PathBuf::from(workspace_dir),
+            Some(Arc::new(log_sink)),
+            autodev_executor::secret_source(&db),
         ).await {
             Ok(executor) => {
-                tracing::info!("✓ Docker executor initialized for local execution");
-                Some(Arc::new(executor))
+                tracing::info!("✓ {} executor initialized for local execution", kind);
+                Some(executor)
             }
             Err(e) => {
-                tracing::error!("Failed to initialize Docker executor: {}", e);
+                tracing::error!("Failed to initialize {} executor: {}", kind, e);
                 tracing::warn!("Falling back to GitHub Actions mode");
                 None
             }
@@ -114,24 +125,35 @@ async fn main() -> Result<()> {
     };
 
     // Create app state
+    let vcs = state::vcs_provider_from_env(&orgs, &db)?;
     let state = state::ApiState {
         engine,
         db,
+        vcs,
         github_client,
+        orgs,
         ai_agent,
-        docker_executor,
+        router: Arc::new(autodev_executor::ExecutionRouter::from_env(local_kind.zip(local_executor.clone()))),
+        local_executor,
         use_local_executor,
+        notifier: Arc::new(autodev_executor::CompletionNotifier::new(
+            autodev_executor::completion::EVENT_FALLBACK_POLL_INTERVAL,
+        )),
+        background: tokio_util::task::TaskTracker::new(),
+        api_keys: auth::ApiKeys::from_env()?.map(Arc::new),
+        secrets: autodev_db::SecretCipher::from_env()?,
+        artifacts: artifacts::store_from_env()?,
     };
-
-    // Build router
-    let app = routes::create_router(state);
+    if state.api_keys.is_none() {
+        tracing::warn!("AUTODEV_API_KEYS is not set: the API accepts requests without a key");
+    }
 
     // Start server
     let addr = format!("0.0.0.0:{}", port);
     tracing::info!("🚀 AutoDev API Server running on http://{}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    server::serve(listener, state).await?;
 
     Ok(())
 }
\ No newline at end of file
@@ -1,31 +1,84 @@
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, post, put},
     Router,
 };
 use tower_http::cors::CorsLayer;
 
-use crate::{handlers, state::ApiState};
+use crate::{artifacts, audit, auth, handlers, state::ApiState};
 
 pub fn create_router(state: ApiState) -> Router {
     Router::new()
         // Health check
         .route("/health", get(handlers::health::health_check))
+        .route("/health/ready", get(handlers::health::readiness_check))
 
         // Task endpoints
         .route("/tasks", post(handlers::task::create_task))
         .route("/tasks", get(handlers::task::list_tasks))
         .route("/tasks/:task_id", get(handlers::task::get_task_status))
         .route("/tasks/:task_id/execute", post(handlers::task::execute_task))
+        .route("/tasks/:task_id/cancel", post(handlers::task::cancel_task))
+        .route("/tasks/:task_id/tags", put(handlers::task::set_task_tags))
+        .route("/tasks/:task_id/approve-merge", post(handlers::task::approve_merge))
+        .route("/tasks/:task_id/payload", get(handlers::task::get_task_payload))
+        .route("/tasks/:task_id/heartbeat", post(handlers::task::heartbeat_task))
+        .route("/tasks/:task_id/secrets", get(handlers::secrets::get_task_secrets))
+        .route("/tasks/:task_id/events", get(handlers::task::task_events))
+        .route("/tasks/:task_id/logs", get(handlers::task::task_logs))
+        .route(
+            "/tasks/:task_id/artifacts",
+            post(handlers::artifacts::upload_artifacts).layer(DefaultBodyLimit::max(artifacts::MAX_UPLOAD_BYTES)),
+        )
+        .route("/tasks/:task_id/artifacts", get(handlers::artifacts::list_artifacts))
+        .route("/tasks/:task_id/artifacts/:name", get(handlers::artifacts::get_artifact))
+        .route("/tasks/:task_id/redecompose", post(handlers::composite::redecompose_subtask))
         .route("/tasks/decompose", post(handlers::task::decompose_task))
         .route("/tasks/:composite_task_id/orchestrate", post(handlers::task::orchestrate_task))
 
         // Composite task endpoints
         .route("/composite-tasks", post(handlers::composite::create_composite_task))
         .route("/composite-tasks/:task_id", get(handlers::composite::get_composite_task))
+        .route("/composite-tasks/:task_id/progress", get(handlers::composite::get_composite_progress))
+        .route("/composite-tasks/:task_id/timeline", get(handlers::composite::get_composite_timeline))
         .route("/composite-tasks/:task_id/execute", post(handlers::composite::execute_composite_task))
+        .route("/composite-tasks/:task_id/approve", post(handlers::composite::approve_composite_task))
+        .route("/composite-tasks/:task_id/pause", post(handlers::composite::pause_composite_task))
+        .route("/composite-tasks/:task_id/resume", post(handlers::composite::resume_composite_task))
+        .route("/composite-tasks/:task_id/cancel", post(handlers::composite::cancel_composite_task))
+        .route("/composite-tasks/:task_id/rollback", post(handlers::composite::rollback_composite_task))
+        .route(
+            "/composite-tasks/:task_id/batches/:batch/approve",
+            post(handlers::composite::approve_batch),
+        )
+
+        // Few-shot examples for task decomposition
+        .route("/examples", get(handlers::examples::list_examples))
+        .route("/examples", post(handlers::examples::create_example))
+        .route("/examples/:id", get(handlers::examples::get_example))
+        .route("/examples/:id", put(handlers::examples::update_example))
+        .route("/examples/:id", delete(handlers::examples::delete_example))
+
+        // Recurring tasks
+        .route("/schedules", get(handlers::schedules::list_schedules))
+        .route("/schedules", post(handlers::schedules::create_schedule))
+        .route("/schedules/:id", get(handlers::schedules::get_schedule))
+        .route("/schedules/:id", put(handlers::schedules::update_schedule))
+        .route("/schedules/:id", delete(handlers::schedules::delete_schedule))
+
+        // Repository secrets
+        .route("/repos/:owner/:repo/secrets", get(handlers::secrets::list_secrets))
+        .route("/repos/:owner/:repo/secrets/:name", put(handlers::secrets::set_secret))
+        .route("/repos/:owner/:repo/secrets/:name", delete(handlers::secrets::delete_secret))
 
         // Statistics
         .route("/stats", get(handlers::stats::get_statistics))
+        .route("/stats/timeseries", get(handlers::stats::get_timeseries))
+
+        // Audit trail
+        .route("/audit", get(handlers::audit::list_audit_events))
+        .route("/audit/verify", get(handlers::audit::verify_audit_chain))
 
         // GitHub webhook
         .route("/webhook/github", post(handlers::webhook::handle_github_webhook))
@@ -33,6 +86,12 @@ pub fn create_router(state: ApiState) -> Router {
         // Callbacks
         .route("/callbacks/workflow-complete", post(handlers::callback::workflow_complete))
 
+        // Audit trail of changes (runs after authorization)
+        .layer(middleware::from_fn_with_state(state.clone(), audit::record_request))
+
+        // API keys and roles
+        .layer(middleware::from_fn_with_state(state.clone(), auth::authorize))
+
         // Add state
         .with_state(state)
 
@@ -0,0 +1,98 @@
+//! Running the API server until a shutdown signal
+
+use std::future::IntoFuture;
+use tokio::sync::watch;
+use tokio::time::{timeout_at, Instant};
+
+use autodev_core::TaskStatus;
+
+use crate::issue_sync;
+use crate::routes::create_router;
+use crate::state::ApiState;
+
+/// Serve the API on `listener` until SIGTERM or Ctrl-C, then shut down gracefully
+///
+/// After the signal no new requests are accepted. Open connections and
+/// background work (task runs, merges) get the grace period to finish; local
+/// runs still going after that are stopped and marked interrupted. The
+/// database pool is closed last.
+pub async fn serve(listener: tokio::net::TcpListener, state: ApiState) -> anyhow::Result<()> {
+    let (stop_tx, stop_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        autodev_core::shutdown::signal().await;
+        let _ = stop_tx.send(true);
+    });
+
+    let issue_sync = issue_sync::spawn(&state);
+    let notifications = autodev_executor::EventNotifier::spawn(&state.engine);
+
+    let mut stopped = stop_rx.clone();
+    let server = axum::serve(listener, create_router(state.clone()))
+        .with_graceful_shutdown(async move {
+            let _ = stopped.wait_for(|stop| *stop).await;
+        })
+        .into_future();
+    tokio::pin!(server);
+
+    let mut stopped = stop_rx;
+    tokio::select! {
+        result = &mut server => {
+            // The server only returns on its own when it fails
+            result?;
+        }
+        _ = stopped.wait_for(|stop| *stop) => {}
+    }
+
+    let grace = autodev_core::shutdown::grace_period();
+    let deadline = Instant::now() + grace;
+    tracing::info!("Shutting down, waiting up to {:?} for in-flight work", grace);
+
+    // Streaming connections (SSE) never finish on their own
+    match timeout_at(deadline, &mut server).await {
+        Ok(result) => result?,
+        Err(_) => tracing::warn!("Closing connections still open after {:?}", grace),
+    }
+
+    state.background.close();
+    if timeout_at(deadline, state.background.wait()).await.is_err() {
+        tracing::warn!(
+            "{} background tasks still running after {:?}",
+            state.background.len(),
+            grace
+        );
+        interrupt_local_runs(&state).await;
+    }
+    issue_sync.abort();
+    notifications.shutdown().await;
+
+    if let Some(ref db) = state.db {
+        db.close().await;
+    }
+
+    tracing::info!("AutoDev API Server stopped");
+    Ok(())
+}
+
+/// Stop local runs that are still in progress and mark their tasks interrupted
+async fn interrupt_local_runs(state: &ApiState) {
+    // GitHub Actions runs keep going remotely and report back through callbacks
+    let Some(executor) = state.local_executor.as_ref().filter(|_| state.use_local_executor) else {
+        return;
+    };
+
+    let in_progress = state
+        .engine
+        .list_active_tasks()
+        .await
+        .into_iter()
+        .filter(|task| task.status == TaskStatus::InProgress);
+
+    for task in in_progress {
+        if let Err(e) = executor.stop_task(&task.id).await {
+            tracing::warn!("Failed to stop local run of task {}: {}", task.id, e);
+        }
+        if let Err(e) = state.engine.interrupt_task(&task.id).await {
+            tracing::warn!("Failed to mark task {} interrupted: {}", task.id, e);
+        }
+    }
+}
@@ -4,8 +4,56 @@ use std::sync::Arc;
 pub struct ApiState {
     pub engine: Arc<autodev_core::AutoDevEngine>,
     pub db: Option<Arc<autodev_db::Database>>,
+    /// Default client; use [`ApiState::github`] for repository calls
     pub github_client: Arc<autodev_github::GitHubClient>,
+    /// Clients of organizations with their own GitHub credentials
+    pub orgs: Arc<autodev_github::OrgClients>,
+    /// Provider pipelines, branches and merge requests go through (GitHub or GitLab)
+    pub vcs: Arc<dyn autodev_github::VcsProvider>,
     pub ai_agent: Arc<dyn autodev_ai::AIAgent>,
-    pub docker_executor: Option<Arc<autodev_local_executor::DockerExecutor>>,
+    pub local_executor: Option<Arc<dyn autodev_local_executor::LocalExecutor>>,
+    /// Whether a local executor was requested (it may have failed to start)
     pub use_local_executor: bool,
-}
\ No newline at end of file
+    /// Picks each task's execution backend
+    pub router: Arc<autodev_executor::ExecutionRouter>,
+    pub notifier: Arc<autodev_executor::CompletionNotifier>,
+    /// Background work spawned by handlers, drained on shutdown
+    pub background: tokio_util::task::TaskTracker,
+    /// None: no API keys configured, requests are not authenticated
+    pub api_keys: Option<Arc<crate::auth::ApiKeys>>,
+    /// Logs and results uploaded by executors
+    pub artifacts: Arc<dyn crate::artifacts::ArtifactStore>,
+    /// None: AUTODEV_SECRETS_KEY is unset, secrets cannot be written or read
+    pub secrets: Option<autodev_db::SecretCipher>,
+}
+
+impl ApiState {
+    /// GitHub client with the credentials of the repository's organization
+    pub fn github(&self, repo: &autodev_github::Repository) -> &autodev_github::GitHubClient {
+        self.orgs.client(repo)
+    }
+}
+/// Select the VCS provider from AUTODEV_VCS_PROVIDER ("github" by default, or "gitlab")
+///
+/// GitLab reads its token from GITLAB_TOKEN and its instance from GITLAB_URL.
+/// GitHub calls use the credentials of each repository's organization.
+/// With a database, branch, pipeline and merge request changes are recorded
+/// in the audit trail.
+pub fn vcs_provider_from_env(
+    orgs: &Arc<autodev_github::OrgClients>,
+    db: &Option<Arc<autodev_db::Database>>,
+) -> anyhow::Result<Arc<dyn autodev_github::VcsProvider>> {
+    let provider = std::env::var("AUTODEV_VCS_PROVIDER").unwrap_or_else(|_| "github".to_string());
+
+    let vcs: Arc<dyn autodev_github::VcsProvider> = match provider.to_lowercase().as_str() {
+        "github" => orgs.clone(),
+        "gitlab" => {
+            let token = std::env::var("GITLAB_TOKEN")
+                .map_err(|_| anyhow::anyhow!("GITLAB_TOKEN must be set for the GitLab provider"))?;
+            Arc::new(autodev_gitlab::GitLabClient::new(token)?)
+        }
+        other => return Err(anyhow::anyhow!("Unknown VCS provider: {}", other)),
+    };
+
+    Ok(autodev_executor::audited(vcs, db, "system:api"))
+}
@@ -1,5 +1,7 @@
 use clap::{Parser, Subcommand};
 
+use crate::output::OutputFormat;
+
 #[derive(Parser)]
 #[command(name = "autodev")]
 #[command(about = "AutoDev - Automated AI Development Platform", long_about = None)]
@@ -8,17 +10,25 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
-    /// GitHub token
+    /// GitHub token (not needed when GITHUB_APP_ID and GITHUB_APP_PRIVATE_KEY_PATH are set)
     #[arg(long, env = "GITHUB_TOKEN")]
-    pub github_token: String,
+    pub github_token: Option<String>,
 
-    /// AI agent type (claude-code, codex, etc.)
-    #[arg(long, default_value = "claude-code")]
+    /// AI agent type (claude-code, openai, ollama)
+    #[arg(long, env = "AI_AGENT_TYPE", default_value = "claude-code")]
     pub agent_type: String,
 
     /// Database URL
     #[arg(long, env = "DATABASE_URL")]
     pub database_url: Option<String>,
+
+    /// Output format of task, composite, status, list and stats
+    #[arg(long, global = true, value_enum, default_value = "table")]
+    pub output: OutputFormat,
+
+    /// Profile of the config file (~/.config/autodev/config.toml) to use
+    #[arg(long, global = true, env = "AUTODEV_PROFILE")]
+    pub profile: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -45,9 +55,21 @@ pub enum Commands {
         #[arg(long)]
         prompt: String,
 
+        /// Tag the task (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Where the task runs: actions, docker, k8s or process (.autodev.yml or the default when unset)
+        #[arg(long)]
+        backend: Option<autodev_core::ExecutionBackend>,
+
         /// Execute immediately
         #[arg(long)]
         execute: bool,
+
+        /// Create the task even if an unfinished task of the repository has the same prompt
+        #[arg(long)]
+        force: bool,
     },
 
     /// Create a composite task
@@ -72,9 +94,29 @@ pub enum Commands {
         #[arg(long)]
         prompt: String,
 
-        /// Auto-approve subtasks
+        /// Auto-approve subtasks (--auto-approve=false overrides the repository's .autodev.yml)
+        #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+        auto_approve: Option<bool>,
+
+        /// Edit the decomposed plan in $EDITOR before creating the task
         #[arg(long)]
-        auto_approve: bool,
+        edit: bool,
+
+        /// Maximum subtasks running at once (AUTODEV_MAX_PARALLEL_TASKS still applies)
+        #[arg(long)]
+        max_parallel: Option<usize>,
+
+        /// How subtask PRs are merged: merge, squash or rebase (.autodev.yml when unset)
+        #[arg(long)]
+        merge_method: Option<autodev_core::MergeMethod>,
+
+        /// What happens to the dependents of a failed subtask: skip, block or run-anyway
+        #[arg(long, default_value = "block")]
+        failure_policy: autodev_core::FailurePolicy,
+
+        /// Where the subtasks run: actions, docker, k8s or process (.autodev.yml or the default when unset)
+        #[arg(long)]
+        backend: Option<autodev_core::ExecutionBackend>,
 
         /// Execute immediately
         #[arg(long)]
@@ -95,10 +137,129 @@ pub enum Commands {
         repo: String,
     },
 
+    /// Cancel a pending or running task, or a composite task with --composite
+    Cancel {
+        /// Task ID (composite task ID with --composite)
+        task_id: String,
+
+        /// Cancel a composite task: no further batch starts and its unfinished subtasks are cancelled
+        #[arg(long)]
+        composite: bool,
+
+        /// Close the open subtask PRs of the cancelled composite task
+        #[arg(long, requires = "composite")]
+        close_prs: bool,
+
+        /// Repository owner (defaults to the one stored with the task)
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Repository name (defaults to the one stored with the task)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Approve the next batch of a composite task (when auto-approve is off)
+    ApproveBatch {
+        /// Composite task ID
+        composite_task_id: String,
+
+        /// Batch number (1-based)
+        batch: usize,
+    },
+
+    /// Pause a composite task: no further subtask starts until it is resumed
+    Pause {
+        /// Composite task ID
+        composite_task_id: String,
+    },
+
+    /// Resume a paused composite task, or a stopped one from its first unfinished batch
+    Resume {
+        /// Composite task ID
+        composite_task_id: String,
+
+        /// Repository owner (defaults to the one stored with the task)
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Repository name (defaults to the one stored with the task)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Roll back a failed composite task (close open PRs, revert merged ones)
+    Rollback {
+        /// Composite task ID
+        composite_task_id: String,
+
+        /// Delete the parent branch instead of reverting merged PRs on it
+        #[arg(long)]
+        delete_branch: bool,
+
+        /// Repository owner (defaults to the one stored with the task)
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Repository name (defaults to the one stored with the task)
+        #[arg(long)]
+        repo: Option<String>,
+    },
+
+    /// Add tags to a task, or remove them with --remove
+    Tag {
+        /// Task ID
+        task_id: String,
+
+        /// Tags to add (or remove)
+        #[arg(required = true)]
+        tags: Vec<String>,
+
+        /// Remove the tags instead of adding them
+        #[arg(long)]
+        remove: bool,
+    },
+
     /// Show task status
     Status {
+        /// Task ID (composite task ID with --timeline)
+        task_id: String,
+
+        /// Show when each subtask of a composite task waited and ran
+        #[arg(long)]
+        timeline: bool,
+    },
+
+    /// Show the output or execution events of a task
+    ///
+    /// Reads the database when DATABASE_URL is set, otherwise the log file of the
+    /// local executor in AUTODEV_WORKSPACE_DIR.
+    Logs {
         /// Task ID
         task_id: String,
+
+        /// Keep printing new lines until the task finishes
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Only print the last N lines (events)
+        #[arg(short = 'n', long, value_name = "N")]
+        tail: Option<usize>,
+
+        /// Show execution events (STARTED, FAILED, ...) instead of the output
+        #[arg(short, long)]
+        events: bool,
+
+        /// Only show execution events of this type, e.g. FAILED (implies --events)
+        #[arg(long)]
+        event_type: Option<String>,
+    },
+
+    /// Live dashboard of tasks, composite-task batches and output (DATABASE_URL required)
+    Watch {
+        /// Seconds between refreshes
+        #[arg(long, default_value = "2")]
+        interval: u64,
     },
 
     /// List all active tasks
@@ -107,6 +268,10 @@ pub enum Commands {
         #[arg(long)]
         status: Option<String>,
 
+        /// Only tasks with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
         /// Limit number of results
         #[arg(long, default_value = "20")]
         limit: usize,
@@ -119,9 +284,241 @@ pub enum Commands {
         port: u16,
     },
 
+    /// Build the worker image used by the local Docker executor
+    BuildImage {
+        /// Build context containing the worker Dockerfile
+        #[arg(long, env = "AUTODEV_WORKER_CONTEXT", default_value = "docker/worker")]
+        context: std::path::PathBuf,
+
+        /// Build without the Docker layer cache
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// Install the AutoDev workflows into a repository
+    SetupRepo {
+        /// Repository owner
+        #[arg(long)]
+        owner: String,
+
+        /// Repository name
+        #[arg(long)]
+        repo: String,
+
+        /// Branch to commit the workflows to
+        #[arg(long, default_value = "main")]
+        branch: String,
+
+        /// Callback URL baked into the workflows
+        #[arg(long, env = "AUTODEV_SERVER_URL")]
+        server_url: Option<String>,
+
+        /// Overwrite workflows that already exist
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Delete stale autodev/* branches of a repository
+    ///
+    /// Branches with an open PR or belonging to an unfinished task are kept.
+    Gc {
+        /// Repository owner
+        #[arg(long)]
+        owner: String,
+
+        /// Repository name
+        #[arg(long)]
+        repo: String,
+
+        /// Delete branches without commits for this many days
+        #[arg(long, default_value = "30")]
+        days: u32,
+
+        /// Only list the branches that would be deleted
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Show statistics
-    Stats,
+    Stats {
+        /// Only tasks of this repository owner
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Only tasks of this repository name
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Only tasks with this tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Only tasks created in the last N days (with --history: length of the
+        /// series, 30 by default)
+        #[arg(long)]
+        days: Option<u32>,
+
+        /// Show trends per day or week instead of totals
+        #[arg(long)]
+        history: bool,
+
+        /// Bucket size of --history (day or week)
+        #[arg(long, default_value = "day")]
+        granularity: autodev_db::StatsGranularity,
+    },
+
+    /// Write a composite task (or a task) as a plan file for `autodev import`
+    Export {
+        /// Composite task or task ID
+        id: String,
+
+        /// Plan file to write (JSON for .json, YAML otherwise); stdout when omitted
+        #[arg(short = 'o', long = "file")]
+        file: Option<std::path::PathBuf>,
+    },
+
+    /// Create the tasks of a plan file written by `autodev export`
+    Import {
+        /// Plan file (YAML or JSON)
+        file: std::path::PathBuf,
+
+        /// Repository owner (default: the repository recorded in the plan)
+        #[arg(long, requires = "repo")]
+        owner: Option<String>,
+
+        /// Repository name (default: the repository recorded in the plan)
+        #[arg(long, requires = "owner")]
+        repo: Option<String>,
 
-    /// Initialize database
+        /// Execute immediately
+        #[arg(long)]
+        execute: bool,
+    },
+
+    /// Manage recurring tasks (DATABASE_URL required)
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleCommands,
+    },
+
+    /// Manage repository secrets passed to task runs (DATABASE_URL and AUTODEV_SECRETS_KEY required)
+    Secret {
+        #[command(subcommand)]
+        action: SecretCommands,
+    },
+
+    /// Manage the config file (~/.config/autodev/config.toml, or AUTODEV_CONFIG)
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommands,
+    },
+
+    /// Initialize database (applies all migrations)
     InitDb,
-}
\ No newline at end of file
+
+    /// Apply pending database schema migrations
+    Migrate {
+        /// Only show applied and pending migrations
+        #[arg(long)]
+        status: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Create the config file from the current environment (into --profile when given)
+    Init {
+        /// Overwrite an existing file
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show the effective settings and where they come from
+    Show,
+
+    /// Set a key (e.g. github_token, or env.AUTODEV_MAX_PARALLEL_TASKS) in the file
+    /// or in --profile
+    Set {
+        key: String,
+        value: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ScheduleCommands {
+    /// Create a task from a template whenever a cron expression (UTC) is due
+    Add {
+        /// Repository owner
+        #[arg(long)]
+        owner: String,
+
+        /// Repository name
+        #[arg(long)]
+        repo: String,
+
+        /// Cron expression, e.g. "0 3 * * *" or @weekly
+        #[arg(long)]
+        cron: String,
+
+        /// Task title
+        #[arg(long)]
+        title: String,
+
+        /// Task description
+        #[arg(long, default_value = "")]
+        description: String,
+
+        /// AI agent prompt
+        #[arg(long)]
+        prompt: String,
+
+        /// Merge the created tasks' PRs without approval
+        #[arg(long)]
+        auto_approve: bool,
+    },
+
+    /// List schedules
+    List {
+        /// Only schedules of this repository owner (with --repo)
+        #[arg(long, requires = "repo")]
+        owner: Option<String>,
+
+        /// Only schedules of this repository name (with --owner)
+        #[arg(long, requires = "owner")]
+        repo: Option<String>,
+    },
+
+    /// Delete a schedule
+    Remove {
+        /// Schedule ID
+        id: i64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SecretCommands {
+    /// Store or replace a secret; the value is read from stdin so it stays out
+    /// of the shell history
+    Set {
+        /// Repository as OWNER/REPO
+        repository: String,
+
+        /// Environment variable name, e.g. NPM_TOKEN
+        name: String,
+    },
+
+    /// List the names of a repository's secrets
+    List {
+        /// Repository as OWNER/REPO
+        repository: String,
+    },
+
+    /// Delete a secret
+    Remove {
+        /// Repository as OWNER/REPO
+        repository: String,
+
+        /// Secret name
+        name: String,
+    },
+}
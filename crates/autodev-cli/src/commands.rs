@@ -1,12 +1,12 @@
 use anyhow::Result;
 use std::sync::Arc;
 
-use crate::cli::Commands;
-use autodev_core::{AutoDevEngine, CompositeTask, Task, TaskStatus};
-use autodev_github::{GitHubClient, Repository};
+use crate::cli::{Commands, ScheduleCommands, SecretCommands};
+use crate::output::{self, say};
+use autodev_core::{AutoDevEngine, CompositeTask, Plan, Task, TaskStatus};
+use autodev_github::{GitHubClient, GitHubUrls, Repository};
 use autodev_ai::AIAgent;
 use autodev_db::Database;
-use autodev_executor;
 
 pub async fn execute(
     command: Commands,
@@ -15,6 +15,13 @@ pub async fn execute(
     github_client: Arc<GitHubClient>,
     ai_agent: Arc<dyn AIAgent>,
 ) -> Result<()> {
+    if let Some(action) = audit_action(&command) {
+        let actor = format!("cli:{}", local_user().unwrap_or_else(|| "unknown".to_string()));
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let event = autodev_db::NewAuditEvent::new(actor, action).with_payload(serde_json::json!({ "args": args }));
+        autodev_executor::audit::record(&db, event).await;
+    }
+
     match command {
         Commands::Task {
             owner,
@@ -22,32 +29,56 @@ pub async fn execute(
             title,
             description,
             prompt,
+            tags,
+            backend,
             execute,
+            force,
         } => {
-            println!("Creating simple task...");
+            say!("Creating simple task...");
             let repository = Repository::new(owner.clone(), repo.clone());
 
+            if !force {
+                if let Some(existing) = autodev_executor::find_duplicate_task(&engine, &db, &repository, &prompt).await {
+                    say!("⚠ An unfinished task with the same prompt already exists: {}", existing.id);
+                    say!("  Title: {}", existing.title);
+                    say!("  Status: {:?}", existing.status);
+                    say!("  Use --force to create another one.");
+                    output::print(&output::TaskOutput::from_task(&existing, output::repository(&owner, &repo)))?;
+                    return Ok(());
+                }
+            }
+
             let task = engine
-                .create_simple_task(title, description, prompt)
+                .register_task(
+                    Task::new(title, description, prompt)
+                        .with_created_by(local_user())
+                        .with_tags(&tags)
+                        .with_execution_backend(backend)
+                        .with_repository(owner.clone(), repo.clone()),
+                )
                 .await?;
 
-            println!("✓ Task created: {}", task.id);
-            println!("  Title: {}", task.title);
-            println!("  Status: {:?}", task.status);
+            say!("✓ Task created: {}", task.id);
+            say!("  Title: {}", task.title);
+            say!("  Status: {:?}", task.status);
 
             // Save to database
             if let Some(db) = &db {
                 db.save_task(&task, &owner, &repo).await?;
-                println!("  Saved to database");
+                say!("  Saved to database");
             }
 
             if execute {
-                println!("\nExecuting task...");
-                let _run_id = execute_task(&task, &repository, &engine, &github_client, &ai_agent, &db, None, None).await?;
-                println!();
-                println!("⏳ Note: The task will complete asynchronously in GitHub Actions.");
-                println!("   You can close this terminal - the workflow will continue running.");
+                say!("\nExecuting task...");
+                if execute_task(&task, &repository, &engine, &github_client, &ai_agent, &db).await?.is_some() {
+                    say!();
+                    say!("⏳ Note: The task will complete asynchronously in GitHub Actions.");
+                    say!("   You can close this terminal - the workflow will continue running.");
+                }
             }
+
+            let task = engine.get_task(&task.id).await.unwrap_or(task);
+            output::print(&output::TaskOutput::from_task(&task, output::repository(&owner, &repo)))?;
         }
 
         Commands::Composite {
@@ -57,42 +88,218 @@ pub async fn execute(
             description,
             prompt,
             auto_approve,
+            edit,
+            max_parallel,
+            merge_method,
+            failure_policy,
+            backend,
             execute,
         } => {
-            println!("Creating composite task...");
+            say!("Creating composite task...");
             let repository = Repository::new(owner.clone(), repo.clone());
+            let config = github_client.load_repo_config(&repository).await?;
 
             // Decompose task using AI
-            let decomposer = autodev_ai::TaskDecomposer::new(ai_agent.clone());
-            let subtasks = decomposer.decompose(&prompt).await?;
+            let examples = autodev_api::handlers::examples::repository_examples(&db, &repository).await;
+            let decomposer = autodev_ai::TaskDecomposer::new(ai_agent.clone())
+                .with_protected_paths(config.protected_paths.clone())
+                .with_custom_examples(examples)
+                .with_vector_store(autodev_executor::example_vector_store(&db));
+            let mut plan = plan_with_progress(&decomposer, &prompt).await?;
+
+            if edit {
+                say!("Opening the plan ({} subtasks) in your editor...", plan.tasks.len());
+                plan = crate::edit::edit_plan(&plan)?;
+                say!("✓ Edited plan accepted: {} subtasks", plan.tasks.len());
+            }
+
+            let subtasks = decomposer.convert_to_tasks(plan.tasks);
 
+            let composite_task = CompositeTask::new(title, description, subtasks)
+                .with_auto_approve(autodev_executor::repo_auto_approve(auto_approve, &config))
+                .with_max_parallel_tasks(max_parallel)
+                .with_merge_method(merge_method)
+                .with_failure_policy(failure_policy)
+                .with_execution_backend(backend)
+                .with_created_by(local_user());
             let composite_task = engine
-                .create_composite_task(title, description, subtasks, auto_approve)
+                .register_composite_task(autodev_executor::with_repo_defaults(&composite_task, &config))
                 .await?;
 
-            println!("✓ Composite task created: {}", composite_task.id);
-            println!("  Title: {}", composite_task.title);
-            println!("  Subtasks: {}", composite_task.subtasks.len());
-            println!("  Auto-approve: {}", composite_task.auto_approve);
+            say!("✓ Composite task created: {}", composite_task.id);
+            say!("  Title: {}", composite_task.title);
+            say!("  Subtasks: {}", composite_task.subtasks.len());
+            say!("  Auto-approve: {}", composite_task.auto_approve);
+            if let Some(max_parallel) = composite_task.max_parallel_tasks {
+                say!("  Max parallel tasks: {}", max_parallel);
+            }
+            if let Some(merge_method) = composite_task.merge_method {
+                say!("  Merge method: {}", merge_method);
+            }
+            say!("  Failure policy: {}", composite_task.failure_policy);
 
             // Display parallel batches
             let batches = composite_task.get_parallel_batches();
-            println!("  Parallel execution plan: {} batches", batches.len());
+            say!("  Parallel execution plan: {} batches", batches.len());
             for (i, batch) in batches.iter().enumerate() {
                 let titles: Vec<&str> = batch.iter().map(|t| t.title.as_str()).collect();
-                println!("    Batch {}: {:?}", i + 1, titles);
+                say!("    Batch {}: {:?}", i + 1, titles);
             }
+            let (critical_path, critical_minutes) = composite_task.critical_path();
+            say!(
+                "  Estimated time: ~{} min (critical path: {} subtasks, {} min)",
+                composite_task.estimate_total_time(u64::from(autodev_core::DEFAULT_TASK_MINUTES) * 60) / 60,
+                critical_path.len(),
+                critical_minutes
+            );
 
             // Save to database
             if let Some(db) = &db {
                 db.save_composite_task(&composite_task, &owner, &repo).await?;
-                println!("  Saved to database");
+                say!("  Saved to database");
             }
 
             if execute {
-                println!("\nExecuting composite task...");
+                say!("\nExecuting composite task...");
                 execute_composite_task(&composite_task, &repository, &engine, &github_client, &ai_agent, &db).await?;
             }
+
+            let mut composite_task = engine
+                .get_composite_task(&composite_task.id)
+                .await
+                .unwrap_or(composite_task);
+            for subtask in &mut composite_task.subtasks {
+                if let Some(current) = engine.get_task(&subtask.id).await {
+                    *subtask = current;
+                }
+            }
+            output::print(&output::CompositeOutput::new(&composite_task, output::repository(&owner, &repo)))?;
+        }
+
+        Commands::Export { id, file } => {
+            let plan = match engine.get_composite_task(&id).await {
+                Some(composite_task) => {
+                    let repository = match &db {
+                        Some(db) => db
+                            .get_composite_task(&id)
+                            .await?
+                            .and_then(|record| output::repository(&record.repository_owner, &record.repository_name)),
+                        None => None,
+                    };
+                    Plan::from_composite(&composite_task, repository)
+                }
+                None => {
+                    let task = engine
+                        .get_task(&id)
+                        .await
+                        .ok_or_else(|| anyhow::anyhow!("No composite task or task with ID {}", id))?;
+                    let repository = match &db {
+                        Some(db) => db
+                            .get_task(&id)
+                            .await?
+                            .and_then(|record| output::repository(&record.repository_owner, &record.repository_name)),
+                        None => None,
+                    };
+                    Plan::from_task(&task, repository)
+                }
+            };
+
+            let json = match &file {
+                Some(path) => path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")),
+                None => output::format() == output::OutputFormat::Json,
+            };
+            let content = match json {
+                true => serde_json::to_string_pretty(&plan)? + "\n",
+                false => serde_yaml::to_string(&plan)?,
+            };
+
+            match file {
+                Some(path) => {
+                    std::fs::write(&path, content)
+                        .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+                    println!("✓ Exported {} tasks to {}", plan.tasks.len(), path.display());
+                }
+                None => print!("{}", content),
+            }
+        }
+
+        Commands::Import { file, owner, repo, execute } => {
+            let content = std::fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file.display(), e))?;
+            // YAML 파서는 JSON도 읽음
+            let plan: Plan = serde_yaml::from_str(&content)
+                .map_err(|e| anyhow::anyhow!("Invalid plan file {}: {}", file.display(), e))?;
+            plan.validate()?;
+
+            let (owner, repo) = match (owner, repo) {
+                (Some(owner), Some(repo)) => (owner, repo),
+                _ => match plan.repository.as_deref().and_then(|r| r.split_once('/')) {
+                    Some((owner, repo)) => (owner.to_string(), repo.to_string()),
+                    None => anyhow::bail!("The plan has no repository; pass --owner and --repo"),
+                },
+            };
+            let repository = Repository::new(owner.clone(), repo.clone());
+
+            match plan.to_composite()? {
+                Some(composite_task) => {
+                    let composite_task = composite_task.with_created_by(local_user());
+                    let config = github_client.load_repo_config(&repository).await?;
+                    let composite_task = engine
+                        .register_composite_task(autodev_executor::with_repo_defaults(&composite_task, &config))
+                        .await?;
+
+                    say!("✓ Imported composite task: {}", composite_task.id);
+                    say!("  Title: {}", composite_task.title);
+                    say!("  Subtasks: {}", composite_task.subtasks.len());
+                    say!("  Repository: {}/{}", owner, repo);
+
+                    if let Some(db) = &db {
+                        db.save_composite_task(&composite_task, &owner, &repo).await?;
+                        say!("  Saved to database");
+                    }
+
+                    if execute {
+                        say!("\nExecuting composite task...");
+                        execute_composite_task(&composite_task, &repository, &engine, &github_client, &ai_agent, &db).await?;
+                    }
+
+                    let mut composite_task = engine
+                        .get_composite_task(&composite_task.id)
+                        .await
+                        .unwrap_or(composite_task);
+                    for subtask in &mut composite_task.subtasks {
+                        if let Some(current) = engine.get_task(&subtask.id).await {
+                            *subtask = current;
+                        }
+                    }
+                    output::print(&output::CompositeOutput::new(&composite_task, output::repository(&owner, &repo)))?;
+                }
+                None => {
+                    let mut imported = Vec::new();
+                    for task in plan.to_tasks()? {
+                        let task = engine.register_task(task.with_created_by(local_user())).await?;
+                        say!("✓ Imported task: {} ({})", task.id, task.title);
+
+                        if let Some(db) = &db {
+                            db.save_task(&task, &owner, &repo).await?;
+                        }
+                        imported.push(task);
+                    }
+
+                    if execute {
+                        for task in &imported {
+                            execute_task(task, &repository, &engine, &github_client, &ai_agent, &db).await?;
+                        }
+                    }
+
+                    let mut tasks = Vec::new();
+                    for task in imported {
+                        let task = engine.get_task(&task.id).await.unwrap_or(task);
+                        tasks.push(output::TaskOutput::from_task(&task, output::repository(&owner, &repo)));
+                    }
+                    output::print(&tasks)?;
+                }
+            }
         }
 
         Commands::Execute {
@@ -106,19 +313,253 @@ pub async fn execute(
                 .ok_or_else(|| anyhow::anyhow!("Task not found"))?;
 
             let repository = Repository::new(owner, repo);
-            let _run_id = execute_task(&task, &repository, &engine, &github_client, &ai_agent, &db, None, None).await?;
+            let _run_id = execute_task(&task, &repository, &engine, &github_client, &ai_agent, &db).await?;
             println!();
             println!("⏳ Note: The task will complete asynchronously in GitHub Actions.");
             println!("   You can close this terminal - the workflow will continue running.");
         }
 
-        Commands::Status { task_id } => {
+        Commands::Cancel {
+            task_id,
+            composite: true,
+            close_prs,
+            owner,
+            repo,
+        } => {
+            let repository = composite_repository(&task_id, owner, repo, &db).await?;
+            let local_executor = local_executor().await;
+
+            println!("Cancelling composite task: {}", task_id);
+
+            let report = autodev_executor::cancel_composite_task(
+                &task_id,
+                &repository,
+                &engine,
+                &github_client,
+                &db,
+                local_executor.as_ref(),
+                &autodev_executor::CancelOptions { close_prs },
+            ).await?;
+
+            println!("✓ Composite task cancelled: {}", task_id);
+            println!("  Cancelled subtasks: {}", report.cancelled_tasks.len());
+            if close_prs {
+                println!("  Closed PRs: {:?}", report.closed_prs);
+            }
+        }
+
+        Commands::Cancel {
+            task_id,
+            owner,
+            repo,
+            ..
+        } => {
+            println!("Cancelling task: {}", task_id);
+
+            // Fall back to the repository stored with the task
+            let record = match &db {
+                Some(db) => db.get_task(&task_id).await?,
+                None => None,
+            };
+            let owner = owner
+                .or_else(|| record.as_ref().map(|r| r.repository_owner.clone()))
+                .filter(|o| !o.is_empty())
+                .ok_or_else(|| anyhow::anyhow!("Repository owner unknown, pass --owner"))?;
+            let repo = repo
+                .or_else(|| record.as_ref().map(|r| r.repository_name.clone()))
+                .filter(|r| !r.is_empty())
+                .ok_or_else(|| anyhow::anyhow!("Repository name unknown, pass --repo"))?;
+            let repository = Repository::new(owner, repo);
+
+            let local_executor = local_executor().await;
+
+            let task = autodev_executor::cancel_task(
+                &task_id,
+                &repository,
+                &engine,
+                &github_client,
+                &db,
+                local_executor.as_ref(),
+            ).await?;
+
+            println!("✓ Task cancelled: {}", task.id);
+            println!("  Title: {}", task.title);
+            if let Some(run_id) = &task.workflow_run_id {
+                println!("  Workflow run {} cancellation requested", run_id);
+            }
+        }
+
+        Commands::ApproveBatch {
+            composite_task_id,
+            batch,
+        } => {
+            if db.is_none() {
+                println!("⚠ No database configured: the approval only reaches executors in this process");
+            }
+
+            let composite_task = engine.approve_batch(&composite_task_id, batch).await?;
+
+            println!("✓ Batch {} approved: {}", batch, composite_task.id);
+            println!("  Title: {}", composite_task.title);
+            println!("  Approved batches: {:?}", composite_task.approved_batches);
+        }
+
+        Commands::Pause { composite_task_id } => {
+            if db.is_none() {
+                println!("⚠ No database configured: the pause only reaches executors in this process");
+            }
+
+            let composite_task = engine.pause_composite_task(&composite_task_id).await?;
+
+            println!("⏸ Composite task paused: {}", composite_task.id);
+            println!("  Title: {}", composite_task.title);
+            println!("  Running subtasks finish; resume with: autodev resume {}", composite_task.id);
+        }
+
+        Commands::Resume {
+            composite_task_id,
+            ..
+        } if engine
+            .get_composite_task(&composite_task_id)
+            .await
+            .is_some_and(|c| c.is_paused()) =>
+        {
+            let composite_task = engine.unpause_composite_task(&composite_task_id).await?;
+
+            println!("▶ Composite task resumed: {}", composite_task.id);
+            println!("  Title: {}", composite_task.title);
+        }
+
+        Commands::Resume {
+            composite_task_id,
+            owner,
+            repo,
+        } => {
+            if db.is_none() {
+                println!("⚠ No database configured: only composite tasks created in this process can be resumed");
+            }
+
+            let repository = composite_repository(&composite_task_id, owner, repo, &db).await?;
+
+            println!("Resuming composite task: {}", composite_task_id);
+
+            let composite_task = autodev_executor::prepare_composite_resume(
+                &composite_task_id,
+                &repository,
+                &engine,
+                &github_client,
+                &db,
+            ).await?;
+
+            let remaining = composite_task
+                .subtasks
+                .iter()
+                .filter(|t| t.status != TaskStatus::Completed)
+                .count();
+            println!("✓ {} of {} subtasks left to run", remaining, composite_task.subtasks.len());
+
+            execute_composite_task(&composite_task, &repository, &engine, &github_client, &ai_agent, &db).await?;
+        }
+
+        Commands::Rollback {
+            composite_task_id,
+            delete_branch,
+            owner,
+            repo,
+        } => {
+            let composite_task = engine
+                .get_composite_task(&composite_task_id)
+                .await
+                .ok_or_else(|| anyhow::anyhow!("Composite task not found: {}", composite_task_id))?;
+            let repository = composite_repository(&composite_task_id, owner, repo, &db).await?;
+
+            println!("Rolling back composite task: {}", composite_task.id);
+
+            let report = autodev_executor::rollback_composite_task(
+                &composite_task,
+                &repository,
+                &engine,
+                &github_client,
+                &db,
+                &autodev_executor::RollbackOptions {
+                    delete_parent_branch: delete_branch,
+                },
+            ).await?;
+
+            println!("✓ Composite task rolled back: {}", composite_task.title);
+            println!("  Closed PRs: {:?}", report.closed_prs);
+            println!("  Merged PRs undone: {:?}", report.reverted_prs);
+            for sha in &report.revert_commits {
+                println!("  Revert commit: {}", sha);
+            }
+            if report.parent_branch_deleted {
+                println!("  Parent branch autodev/{} deleted", composite_task.id);
+            }
+        }
+
+        Commands::Tag { task_id, tags, remove } => {
+            let record = match &db {
+                Some(db) => db.get_task(&task_id).await?,
+                None => None,
+            };
+            let task = engine.get_task(&task_id).await;
+            let current = match (&task, &record) {
+                (Some(task), _) => task.tags.clone(),
+                (None, Some(record)) => record.tags.clone(),
+                (None, None) => anyhow::bail!("Task not found: {}", task_id),
+            };
+
+            let changed = autodev_core::normalize_tags(&tags);
+            let updated: Vec<String> = if remove {
+                current.into_iter().filter(|tag| !changed.contains(tag)).collect()
+            } else {
+                current.into_iter().chain(changed).collect()
+            };
+
+            if task.is_some() {
+                engine.set_task_tags(&task_id, &updated).await?;
+            }
+            if let Some(db) = &db {
+                db.set_task_tags(&task_id, &updated).await?;
+            }
+
+            let updated = autodev_core::normalize_tags(&updated);
+            if output::structured() {
+                return output::print(&updated);
+            }
+            if updated.is_empty() {
+                println!("✓ Task {} has no tags", task_id);
+            } else {
+                println!("✓ Tags of task {}: {}", task_id, updated.join(", "));
+            }
+        }
+
+        Commands::Status { task_id, timeline: true } => {
+            let timeline = composite_timeline(&task_id, &engine, &db).await?;
+            if output::structured() {
+                return output::print(&timeline);
+            }
+            print_timeline(&timeline);
+        }
+
+        Commands::Status { task_id, .. } if output::structured() => {
+            output::print(&task_status(&task_id, &engine, &db).await?)?;
+        }
+
+        Commands::Status { task_id, .. } => {
             match engine.get_task(&task_id).await {
                 Some(task) => {
                     println!("Task: {}", task.id);
                     println!("  Title: {}", task.title);
                     println!("  Status: {:?}", task.status);
+                    println!("  Attempts: {}/{}", task.attempts, task.retry_policy.max_attempts);
                     println!("  Created: {}", task.created_at);
+                    if let Some(created_by) = &task.created_by {
+                        println!("  Created by: {}", created_by);
+                    }
+                    if !task.tags.is_empty() {
+                        println!("  Tags: {}", task.tags.join(", "));
+                    }
 
                     if let Some(started) = task.started_at {
                         println!("  Started: {}", started);
@@ -134,7 +575,14 @@ pub async fn execute(
                     }
 
                     if let Some(pr_url) = &task.pr_url {
-                        println!("  PR: {}", pr_url);
+                        match task.pr_number {
+                            Some(number) => println!("  PR: #{} {}", number, pr_url),
+                            None => println!("  PR: {}", pr_url),
+                        }
+                    }
+
+                    if let Some(run_id) = &task.workflow_run_id {
+                        println!("  Workflow run: {}", run_id);
                     }
 
                     if let Some(error) = &task.error {
@@ -154,11 +602,17 @@ pub async fn execute(
                         // Get metrics
                         if let Some(metrics) = db.get_task_metrics(&task_id).await? {
                             println!("\n  Metrics:");
-                            println!("    Execution time: {}ms", metrics.execution_time_ms);
+                            println!("    Execution time: {:.1}s", metrics.execution_time_ms as f64 / 1000.0);
                             println!("    Files changed: {}", metrics.files_changed);
                             println!("    Lines added: {}", metrics.lines_added);
                             println!("    Lines removed: {}", metrics.lines_removed);
-                            println!("    AI tokens used: {}", metrics.ai_tokens_used);
+                            println!(
+                                "    AI tokens used: {} ({} input / {} output)",
+                                metrics.ai_input_tokens + metrics.ai_output_tokens,
+                                metrics.ai_input_tokens,
+                                metrics.ai_output_tokens
+                            );
+                            println!("    AI cost: ${:.4}", metrics.ai_cost_usd);
                         }
                     }
                 }
@@ -171,24 +625,68 @@ pub async fn execute(
                             println!("\nFound in database:");
                             println!("  Title: {}", record.title);
                             println!("  Status: {}", record.status);
+                            println!("  Attempts: {}", record.attempts);
                             println!("  Repository: {}/{}", record.repository_owner, record.repository_name);
+                            if let Some(pr_url) = &record.pr_url {
+                                println!("  PR: {}", pr_url);
+                            }
+                            if let Some(run_id) = &record.workflow_run_id {
+                                println!("  Workflow run: {}", run_id);
+                            }
                         }
                     }
                 }
             }
         }
 
-        Commands::List { status, limit } => {
-            let tasks = engine.list_active_tasks().await;
+        Commands::Logs { task_id, follow, tail, events, event_type } => {
+            let options = crate::logs::LogsOptions {
+                tail,
+                follow,
+                events,
+                event_type,
+            };
+            crate::logs::logs(db.as_deref(), &task_id, &options).await?;
+        }
 
-            let filtered_tasks: Vec<_> = if let Some(status_filter) = status {
-                tasks.into_iter()
-                    .filter(|t| format!("{:?}", t.status).to_lowercase() == status_filter.to_lowercase())
-                    .take(limit)
-                    .collect()
-            } else {
-                tasks.into_iter().take(limit).collect()
+        Commands::Watch { interval } => {
+            let Some(db) = &db else {
+                anyhow::bail!("The dashboard reads tasks from the database; set DATABASE_URL");
             };
+            crate::watch::watch(db, std::time::Duration::from_secs(interval.max(1))).await?;
+        }
+
+        Commands::List { status, tag, limit } => {
+            let tasks = engine.list_active_tasks().await;
+            let tag = tag.map(|tag| tag.trim().to_lowercase());
+
+            let filtered_tasks: Vec<_> = tasks
+                .into_iter()
+                .filter(|t| {
+                    status
+                        .as_ref()
+                        .is_none_or(|status| format!("{:?}", t.status).to_lowercase() == status.to_lowercase())
+                })
+                .filter(|t| tag.as_ref().is_none_or(|tag| t.tags.contains(tag)))
+                .take(limit)
+                .collect();
+
+            let recent_query = autodev_db::TaskQuery {
+                tag: tag.clone(),
+                limit: limit as i64,
+                ..Default::default()
+            };
+
+            if output::structured() {
+                let recent = match &db {
+                    Some(db) => db.list_tasks(&recent_query).await?.0,
+                    None => Vec::new(),
+                };
+                return output::print(&output::ListOutput {
+                    tasks: filtered_tasks.iter().map(|task| output::TaskOutput::from_task(task, None)).collect(),
+                    recent: recent.iter().map(output::TaskOutput::from_record).collect(),
+                });
+            }
 
             println!("Active Tasks: {}", filtered_tasks.len());
             println!();
@@ -198,6 +696,12 @@ pub async fn execute(
                 println!("  Title: {}", task.title);
                 println!("  Status: {:?}", task.status);
                 println!("  Created: {}", task.created_at);
+                if let Some(created_by) = &task.created_by {
+                    println!("  Created by: {}", created_by);
+                }
+                if !task.tags.is_empty() {
+                    println!("  Tags: {}", task.tags.join(", "));
+                }
                 if let Some(pr_url) = &task.pr_url {
                     println!("  PR: {}", pr_url);
                 }
@@ -206,11 +710,17 @@ pub async fn execute(
 
             // Database tasks
             if let Some(db) = &db {
-                let recent = db.get_recent_tasks(limit as i64).await?;
+                let recent = db.list_tasks(&recent_query).await?.0;
                 if !recent.is_empty() {
                     println!("\nRecent tasks from database: {}", recent.len());
                     for record in recent.iter().take(5) {
-                        println!("  {} - {} ({})", record.id, record.title, record.status);
+                        match &record.created_by {
+                            Some(created_by) => println!(
+                                "  {} - {} ({}, by {})",
+                                record.id, record.title, record.status, created_by
+                            ),
+                            None => println!("  {} - {} ({})", record.id, record.title, record.status),
+                        }
                     }
                 }
             }
@@ -223,12 +733,12 @@ pub async fn execute(
                 println!("Warning: No database configured. Tasks won't be persisted.");
             }
 
-            // Initialize Docker executor if local execution is enabled
-            let use_local_executor = std::env::var("AUTODEV_LOCAL_EXECUTOR")
-                .unwrap_or_else(|_| "false".to_string())
-                .to_lowercase() == "true";
+            // Initialize the local executor (AUTODEV_EXECUTOR: docker or process) if local execution is enabled
+            // or the default execution backend is local
+            let local_kind = autodev_executor::routing::local_executor_kind();
+            let use_local_executor = local_kind.is_some();
 
-            let docker_executor = if use_local_executor {
+            let local_executor = if let Some(kind) = local_kind {
                 let workspace_dir = std::env::var("AUTODEV_WORKSPACE_DIR")
                     .unwrap_or_else(|_| "/tmp/autodev-workspace".to_string());
 
@@ -247,18 +757,23 @@ pub async fn execute(
                 let autodev_server_url = std::env::var("AUTODEV_SERVER_URL")
                     .ok();
 
-                match autodev_local_executor::DockerExecutor::new(
+                let log_sink = autodev_executor::TaskLogSink::new(engine.clone(), db.clone());
+
+                match autodev_local_executor::create_executor(
+                    kind,
                     anthropic_api_key,
                     github_token,
                     autodev_server_url,
                     std::path::PathBuf::from(workspace_dir),
+                    Some(Arc::new(log_sink)),
+                    autodev_executor::secret_source(&db),
                 ).await {
                     Ok(executor) => {
-                        println!("✓ Docker executor initialized for local execution");
-                        Some(Arc::new(executor))
+                        println!("✓ {} executor initialized for local execution", kind);
+                        Some(executor)
                     }
                     Err(e) => {
-                        eprintln!("Failed to initialize Docker executor: {}", e);
+                        eprintln!("Failed to initialize {} executor: {}", kind, e);
                         eprintln!("Falling back to GitHub Actions mode");
                         None
                     }
@@ -269,27 +784,62 @@ pub async fn execute(
             };
 
             // Create API state
+            let orgs = Arc::new(autodev_github::OrgClients::from_env(github_client.clone())?);
+            let vcs = autodev_api::state::vcs_provider_from_env(&orgs, &db)?;
             let api_state = autodev_api::state::ApiState {
                 engine,
                 db,
+                vcs,
                 github_client,
+                orgs,
                 ai_agent,
-                docker_executor,
+                router: Arc::new(autodev_executor::ExecutionRouter::from_env(local_kind.zip(local_executor.clone()))),
+                local_executor,
                 use_local_executor,
+                notifier: Arc::new(autodev_executor::CompletionNotifier::new(
+                    autodev_executor::completion::EVENT_FALLBACK_POLL_INTERVAL,
+                )),
+                background: Default::default(),
+                api_keys: autodev_api::auth::ApiKeys::from_env()?.map(Arc::new),
+                secrets: autodev_db::SecretCipher::from_env()?,
+                artifacts: autodev_api::artifacts::store_from_env()?,
             };
 
-            // Create and run server
-            let app = autodev_api::routes::create_router(api_state);
-
+            // Run server until SIGTERM / Ctrl-C
             let addr = format!("0.0.0.0:{}", port);
             println!("🚀 AutoDev API Server running on http://{}", addr);
 
             let listener = tokio::net::TcpListener::bind(&addr).await?;
-            axum::serve(listener, app).await?;
+            autodev_api::serve(listener, api_state).await?;
         }
 
-        Commands::Stats => {
-            println!("AutoDev Statistics\n");
+        Commands::Stats {
+            owner,
+            repo,
+            tag,
+            days,
+            history,
+            granularity,
+        } => {
+            let since = days
+                .map(|days| {
+                    autodev_db::StatsFilter::since_days(days)
+                        .ok_or_else(|| anyhow::anyhow!("--days is too large: {}", days))
+                })
+                .transpose()?;
+            if history {
+                let Some(db) = &db else {
+                    anyhow::bail!("Statistics history is read from the database; set DATABASE_URL");
+                };
+                let filter = autodev_db::StatsFilter {
+                    repository_owner: owner,
+                    repository_name: repo,
+                    org_id: None,
+                    since,
+                    tag: tag.clone(),
+                };
+                return stats_history(db, &filter, granularity).await;
+            }
 
             let tasks = engine.list_active_tasks().await;
             let total = tasks.len();
@@ -297,6 +847,33 @@ pub async fn execute(
             let failed = tasks.iter().filter(|t| matches!(t.status, TaskStatus::Failed)).count();
             let in_progress = tasks.iter().filter(|t| matches!(t.status, TaskStatus::InProgress)).count();
 
+            let filter = autodev_db::StatsFilter {
+                repository_owner: owner,
+                repository_name: repo,
+                org_id: None,
+                since,
+                tag,
+            };
+
+            if output::structured() {
+                let database = match &db {
+                    Some(db) => Some(output::DatabaseStatsOutput {
+                        totals: db.get_aggregate_stats(&filter).await?,
+                        repositories: db.get_repository_stats(&filter).await?,
+                        tags: db.get_tag_stats(&filter).await?,
+                    }),
+                    None => None,
+                };
+                return output::print(&output::StatsOutput {
+                    total_tasks: total,
+                    completed_tasks: completed,
+                    failed_tasks: failed,
+                    in_progress_tasks: in_progress,
+                    database,
+                });
+            }
+
+            println!("AutoDev Statistics\n");
             println!("In-Memory Stats:");
             println!("  Total tasks: {}", total);
             println!("  Completed: {}", completed);
@@ -305,7 +882,7 @@ pub async fn execute(
 
             if let Some(db) = &db {
                 println!("\nDatabase Stats:");
-                let stats = db.get_aggregate_stats().await?;
+                let stats = db.get_aggregate_stats(&filter).await?;
                 println!("  Total tasks: {}", stats.total_tasks);
                 println!("  Completed: {}", stats.completed_tasks);
                 println!("  Failed: {}", stats.failed_tasks);
@@ -318,12 +895,166 @@ pub async fn execute(
                     println!("  Total files changed: {}", files);
                 }
 
+                if let (Some(added), Some(removed)) = (stats.total_lines_added, stats.total_lines_removed) {
+                    println!("  Total lines changed: +{} -{}", added, removed);
+                }
+
                 if let Some(tokens) = stats.total_tokens_used {
                     println!("  Total AI tokens used: {}", tokens);
                 }
+
+                if let Some(cost) = stats.total_cost_usd {
+                    println!("  Total AI cost: ${:.2}", cost);
+                }
+
+                let repositories = db.get_repository_stats(&filter).await?;
+                if !repositories.is_empty() {
+                    println!("\nBy Repository:");
+                    for repo in repositories {
+                        let finished = repo.completed_tasks + repo.failed_tasks;
+                        let success = if finished > 0 {
+                            format!("{:.0}%", repo.completed_tasks as f64 * 100.0 / finished as f64)
+                        } else {
+                            "-".to_string()
+                        };
+                        let avg_time = repo
+                            .avg_execution_time_ms
+                            .map(|ms| format!("{:.2}s", ms / 1000.0))
+                            .unwrap_or_else(|| "-".to_string());
+
+                        println!(
+                            "  {}/{}: {} tasks, {} success, avg {}, {} files (+{} -{}), {} tokens, ${:.2}",
+                            repo.repository_owner,
+                            repo.repository_name,
+                            repo.total_tasks,
+                            success,
+                            avg_time,
+                            repo.total_files_changed.unwrap_or(0),
+                            repo.total_lines_added.unwrap_or(0),
+                            repo.total_lines_removed.unwrap_or(0),
+                            repo.total_tokens_used.unwrap_or(0),
+                            repo.total_cost_usd.unwrap_or(0.0)
+                        );
+                    }
+                }
+
+                let tags = db.get_tag_stats(&filter).await?;
+                if !tags.is_empty() {
+                    println!("\nBy Tag:");
+                    for tag in tags {
+                        let finished = tag.completed_tasks + tag.failed_tasks;
+                        let success = if finished > 0 {
+                            format!("{:.0}%", tag.completed_tasks as f64 * 100.0 / finished as f64)
+                        } else {
+                            "-".to_string()
+                        };
+                        let avg_time = tag
+                            .avg_execution_time_ms
+                            .map(|ms| format!("{:.2}s", ms / 1000.0))
+                            .unwrap_or_else(|| "-".to_string());
+
+                        println!(
+                            "  {}: {} tasks, {} success, avg {}, {} tokens, ${:.2}",
+                            tag.tag,
+                            tag.total_tasks,
+                            success,
+                            avg_time,
+                            tag.total_tokens_used.unwrap_or(0),
+                            tag.total_cost_usd.unwrap_or(0.0)
+                        );
+                    }
+                }
+            }
+        }
+
+        Commands::SetupRepo {
+            owner,
+            repo,
+            branch,
+            server_url,
+            force,
+        } => {
+            let repository = Repository::new(owner, repo).with_branch(branch);
+            println!("Installing AutoDev workflows into {}...", repository.full_name());
+
+            let installed = github_client
+                .install_workflows(&repository, server_url.as_deref(), force)
+                .await?;
+
+            if installed.is_empty() {
+                println!("✓ Workflows already installed (use --force to overwrite)");
+            } else {
+                for file in &installed {
+                    println!("✓ Committed .github/workflows/{} to {}", file, repository.branch);
+                }
+                println!("\n💡 Add the ANTHROPIC_API_KEY secret to the repository before running tasks");
+            }
+        }
+
+        Commands::Gc { owner, repo, days, dry_run } => {
+            let repository = Repository::new(owner, repo);
+            say!("Looking for AutoDev branches of {} without commits for {} days...", repository.full_name(), days);
+
+            // Branches of unfinished tasks are kept however old they are
+            let mut active: std::collections::HashSet<String> =
+                engine.list_active_tasks().await.into_iter().map(|task| task.id).collect();
+            if let Some(db) = &db {
+                active.extend(db.get_open_tasks().await?.into_iter().map(|task| task.id));
+                active.extend(db.get_open_composite_tasks().await?.into_iter().map(|composite| composite.id));
+            }
+
+            let cutoff = chrono::Utc::now()
+                .checked_sub_signed(chrono::Duration::days(i64::from(days)))
+                .ok_or_else(|| anyhow::anyhow!("--days is too large: {}", days))?;
+            let pruned = autodev_executor::prune_stale_branches(
+                &repository,
+                github_client.as_ref(),
+                cutoff,
+                &active,
+                dry_run,
+            )
+            .await?;
+
+            if output::structured() {
+                return output::print(&pruned);
+            }
+            if pruned.is_empty() {
+                println!("✓ No stale branches");
+            }
+            for branch in &pruned {
+                let age = branch
+                    .committed_at
+                    .map(|committed_at| format!("{} days", (chrono::Utc::now() - committed_at).num_days()))
+                    .unwrap_or_default();
+                println!("{} {} ({})", if dry_run { "would delete" } else { "✓ deleted" }, branch.name, age);
+            }
+            if dry_run && !pruned.is_empty() {
+                println!("\nRun without --dry-run to delete {} branches", pruned.len());
             }
         }
 
+        Commands::BuildImage { context, no_cache } => {
+            println!("🐳 Building {} from {}...", autodev_local_executor::WORKER_IMAGE, context.display());
+            autodev_local_executor::build_worker_image(&context, no_cache, |line| println!("  {}", line)).await?;
+            println!("✓ Worker image built: {}", autodev_local_executor::WORKER_IMAGE);
+        }
+
+        Commands::Schedule { action } => {
+            let Some(db) = &db else {
+                anyhow::bail!("Schedules are stored in the database; set DATABASE_URL");
+            };
+            schedule(action, db).await?;
+        }
+
+        Commands::Secret { action } => {
+            let Some(db) = &db else {
+                anyhow::bail!("Secrets are stored in the database; set DATABASE_URL");
+            };
+            secret(action, db).await?;
+        }
+
+        Commands::Config { .. } => unreachable!("handled before the clients are created"),
+
         Commands::InitDb => {
             match &db {
                 Some(database) => {
@@ -336,11 +1067,341 @@ pub async fn execute(
                 }
             }
         }
+
+        Commands::Migrate { status } => {
+            let Some(database) = &db else {
+                anyhow::bail!("No database URL provided. Set DATABASE_URL environment variable.");
+            };
+
+            if status {
+                let applied = database.applied_migrations().await?;
+                for migration in autodev_db::migrations::MIGRATIONS {
+                    let state = if applied.contains(&migration.version) { "applied" } else { "pending" };
+                    println!("  {:>3} {:<32} {}", migration.version, migration.name, state);
+                }
+            } else {
+                println!("Applying database migrations...");
+                let applied = database.migrate().await?;
+                if applied.is_empty() {
+                    println!("✓ Database schema is up to date (version {})", autodev_db::migrations::latest_version());
+                } else {
+                    println!("✓ Applied migrations: {:?}", applied);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print tasks, success rate, duration and cost per day or week as sparklines
+async fn stats_history(db: &Database, filter: &autodev_db::StatsFilter, granularity: autodev_db::StatsGranularity) -> Result<()> {
+    let points = db.get_timeseries_stats(filter, granularity).await?;
+    if output::structured() {
+        return output::print(&output::StatsHistoryOutput { granularity, points });
+    }
+
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        println!("No statistics");
+        return Ok(());
+    };
+    println!("AutoDev History (per {}, {} to {})\n", granularity, first.date, last.date);
+
+    let total: i64 = points.iter().map(|p| p.total_tasks).sum();
+    let completed: i64 = points.iter().map(|p| p.completed_tasks).sum();
+    let failed: i64 = points.iter().map(|p| p.failed_tasks).sum();
+    let cost = points.iter().filter_map(|p| p.total_cost_usd).fold(0.0, |sum, cost| sum + cost);
+    let tokens: i64 = points.iter().filter_map(|p| p.total_tokens_used).sum();
+    let durations: Vec<f64> = points.iter().filter_map(|p| p.avg_execution_time_ms).collect();
+
+    let success = |completed: i64, failed: i64| {
+        let finished = completed + failed;
+        (finished > 0).then(|| completed as f64 * 100.0 / finished as f64)
+    };
+    let tasks: Vec<_> = points.iter().map(|p| Some(p.total_tasks as f64)).collect();
+    let rates: Vec<_> = points.iter().map(|p| success(p.completed_tasks, p.failed_tasks)).collect();
+    let times: Vec<_> = points.iter().map(|p| p.avg_execution_time_ms).collect();
+    let costs: Vec<_> = points.iter().map(|p| Some(p.total_cost_usd.unwrap_or(0.0))).collect();
+
+    println!("  Tasks         {}  {} total", output::sparkline(&tasks), total);
+    println!(
+        "  Success rate  {}  {}",
+        output::sparkline(&rates),
+        success(completed, failed).map_or("-".to_string(), |rate| format!("{:.0}% overall", rate))
+    );
+    println!(
+        "  Avg duration  {}  {}",
+        output::sparkline(&times),
+        if durations.is_empty() {
+            "-".to_string()
+        } else {
+            format!("{:.1}s mean", durations.iter().sum::<f64>() / durations.len() as f64 / 1000.0)
+        }
+    );
+    println!("  AI cost       {}  ${:.2} total ({} tokens)", output::sparkline(&costs), cost, tokens);
+
+    Ok(())
+}
+
+/// Timeline of a composite task tracked by the engine or stored in the database
+async fn composite_timeline(
+    composite_task_id: &str,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+) -> Result<autodev_core::Timeline> {
+    let composite_task = match engine.get_composite_task(composite_task_id).await {
+        Some(mut composite_task) => {
+            for subtask in &mut composite_task.subtasks {
+                if let Some(task) = engine.get_task(&subtask.id).await {
+                    *subtask = task;
+                }
+            }
+            composite_task
+        }
+        None => {
+            let record = match db {
+                Some(db) => db.get_composite_task(composite_task_id).await?,
+                None => None,
+            };
+            let (Some(db), Some(record)) = (db, record) else {
+                anyhow::bail!("Composite task not found: {}", composite_task_id);
+            };
+            let subtasks = db
+                .get_composite_subtasks(composite_task_id)
+                .await?
+                .iter()
+                .map(|record| record.to_task())
+                .collect::<autodev_core::Result<Vec<_>>>()?;
+            record.to_composite_task(subtasks)?
+        }
+    };
+
+    let events: Vec<autodev_core::LoggedEvent> = match db {
+        Some(db) => db
+            .get_composite_execution_logs(composite_task_id)
+            .await?
+            .into_iter()
+            .map(|log| autodev_core::LoggedEvent {
+                task_id: log.task_id,
+                event_type: log.event_type,
+                timestamp: log.timestamp,
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(autodev_core::Timeline::build(&composite_task, &events))
+}
+
+/// Print a timeline as one bar per subtask, grouped by batch
+fn print_timeline(timeline: &autodev_core::Timeline) {
+    const WIDTH: usize = 50;
+
+    let Some(start) = timeline.started_at else {
+        println!("Composite task {} has not started", timeline.composite_task_id);
+        return;
+    };
+    let end = timeline.finished_at.unwrap_or_else(chrono::Utc::now);
+    let span = (end - start).num_milliseconds().max(1);
+    let column = |time: chrono::DateTime<chrono::Utc>| {
+        (((time - start).num_milliseconds().clamp(0, span) * WIDTH as i64) / span) as usize
+    };
+    let time = |time: Option<chrono::DateTime<chrono::Utc>>| {
+        time.map_or("…".to_string(), |time| time.format("%H:%M:%S").to_string())
+    };
+
+    println!(
+        "Timeline of {} ({} to {} UTC, {})",
+        timeline.composite_task_id,
+        time(Some(start)),
+        time(timeline.finished_at),
+        output::duration_ms(span)
+    );
+    println!("  ░ waiting  █ running  ▒ failed attempt\n");
+
+    for batch in &timeline.batches {
+        println!("Batch {}  {} to {}", batch.batch, time(batch.started_at), time(batch.finished_at));
+        for task in timeline.tasks.iter().filter(|task| task.batch == batch.batch) {
+            let mut bar = vec![' '; WIDTH];
+            if let Some(ready_at) = task.ready_at {
+                let until = task.finished_at.unwrap_or(end);
+                for cell in bar.iter_mut().take(column(until)).skip(column(ready_at)) {
+                    *cell = '░';
+                }
+            }
+            for attempt in &task.attempts {
+                let fill = match attempt.outcome.as_deref() {
+                    None | Some("COMPLETED") => '█',
+                    Some(_) => '▒',
+                };
+                let from = column(attempt.started_at);
+                let to = column(attempt.finished_at.unwrap_or(end)).max(from + 1).min(WIDTH);
+                for cell in bar.iter_mut().take(to).skip(from) {
+                    *cell = fill;
+                }
+            }
+
+            let wait = task.wait_ms.map_or("-".to_string(), output::duration_ms);
+            let run = task.run_ms.map_or("-".to_string(), output::duration_ms);
+            println!(
+                "  {:<24} |{}| {:?}, waited {}, ran {}{}",
+                output::truncate(&task.title, 24),
+                bar.into_iter().collect::<String>(),
+                task.status,
+                wait,
+                run,
+                match task.attempts.len() {
+                    0 | 1 => String::new(),
+                    n => format!(" ({} attempts)", n),
+                }
+            );
+        }
+    }
+}
+
+async fn schedule(action: ScheduleCommands, db: &Database) -> Result<()> {
+    match action {
+        ScheduleCommands::Add {
+            owner,
+            repo,
+            cron,
+            title,
+            description,
+            prompt,
+            auto_approve,
+        } => {
+            let cron: autodev_core::CronSchedule = cron.parse()?;
+            let Some(next_run_at) = cron.next_after(chrono::Utc::now()) else {
+                anyhow::bail!("'{}' never matches a date", cron);
+            };
+
+            let schedule = autodev_db::NewSchedule {
+                cron_expression: cron.to_string(),
+                repository_owner: owner,
+                repository_name: repo,
+                title,
+                description,
+                prompt,
+                auto_approve,
+                enabled: true,
+            };
+            let record = db.create_schedule(&schedule, Some(next_run_at)).await?;
+
+            println!("✓ Schedule created: {}", record.id);
+            println!("  Repository: {}/{}", record.repository_owner, record.repository_name);
+            println!("  Cron: {} (UTC)", record.cron_expression);
+            println!("  Next run: {}", next_run_at.to_rfc3339());
+        }
+
+        ScheduleCommands::List { owner, repo } => {
+            let repository = owner.as_deref().zip(repo.as_deref());
+            let schedules = db.list_schedules(repository).await?;
+
+            if schedules.is_empty() {
+                println!("No schedules");
+            }
+
+            for schedule in schedules {
+                let next_run = match (schedule.enabled, schedule.next_run_at) {
+                    (false, _) => "disabled".to_string(),
+                    (true, Some(next_run_at)) => next_run_at.to_rfc3339(),
+                    (true, None) => "never".to_string(),
+                };
+
+                println!(
+                    "{:>4}  {:<16} {}/{}  {}",
+                    schedule.id,
+                    schedule.cron_expression,
+                    schedule.repository_owner,
+                    schedule.repository_name,
+                    schedule.title
+                );
+                println!("      Next run: {}", next_run);
+                if let (Some(last_run_at), Some(task_id)) = (schedule.last_run_at, schedule.last_task_id) {
+                    println!("      Last run: {} (task {})", last_run_at.to_rfc3339(), task_id);
+                }
+            }
+        }
+
+        ScheduleCommands::Remove { id } => {
+            if !db.delete_schedule(id).await? {
+                anyhow::bail!("Schedule not found: {}", id);
+            }
+            println!("✓ Schedule {} removed", id);
+        }
+    }
+
+    Ok(())
+}
+
+async fn secret(action: SecretCommands, db: &Database) -> Result<()> {
+    match action {
+        SecretCommands::Set { repository, name } => {
+            let (owner, repo) = parse_repository(&repository)?;
+            let Some(cipher) = autodev_db::SecretCipher::from_env()? else {
+                anyhow::bail!("Set {} to encrypt secrets (openssl rand -hex 32)", autodev_db::SECRETS_KEY_ENV);
+            };
+
+            let value = read_secret_value(&name)?;
+            db.set_secret(&cipher, owner, repo, &name, &value).await?;
+            println!("✓ Secret {} of {}/{} set", name, owner, repo);
+        }
+
+        SecretCommands::List { repository } => {
+            let (owner, repo) = parse_repository(&repository)?;
+            let secrets = db.list_secrets(owner, repo).await?;
+
+            if secrets.is_empty() {
+                println!("No secrets");
+            }
+
+            for secret in secrets {
+                println!("  {:<32} updated {}", secret.name, secret.updated_at.to_rfc3339());
+            }
+        }
+
+        SecretCommands::Remove { repository, name } => {
+            let (owner, repo) = parse_repository(&repository)?;
+            if !db.delete_secret(owner, repo, &name).await? {
+                anyhow::bail!("Secret {} of {}/{} not found", name, owner, repo);
+            }
+            println!("✓ Secret {} of {}/{} removed", name, owner, repo);
+        }
     }
 
     Ok(())
 }
 
+fn parse_repository(repository: &str) -> Result<(&str, &str)> {
+    repository
+        .split_once('/')
+        .filter(|(owner, repo)| !owner.is_empty() && !repo.is_empty() && !repo.contains('/'))
+        .ok_or_else(|| anyhow::anyhow!("Expected OWNER/REPO, got '{}'", repository))
+}
+
+/// Secret value from stdin: typed at a prompt, or piped (e.g. `< token.txt`)
+fn read_secret_value(name: &str) -> Result<String> {
+    use std::io::{IsTerminal, Read, Write};
+
+    let mut value = String::new();
+    if std::io::stdin().is_terminal() {
+        eprint!("Value for {}: ", name);
+        std::io::stderr().flush()?;
+        std::io::stdin().read_line(&mut value)?;
+    } else {
+        std::io::stdin().read_to_string(&mut value)?;
+    }
+
+    let value = value.strip_suffix('\n').unwrap_or(&value);
+    let value = value.strip_suffix('\r').unwrap_or(value);
+    if value.is_empty() {
+        anyhow::bail!("Empty value for secret {}", name);
+    }
+    Ok(value.to_string())
+}
+
+/// Run `task` on its execution backend; the workflow run ID when it was
+/// dispatched to GitHub Actions
 async fn execute_task(
     task: &Task,
     repository: &Repository,
@@ -348,41 +1409,41 @@ async fn execute_task(
     github_client: &Arc<GitHubClient>,
     _ai_agent: &Arc<dyn AIAgent>,
     db: &Option<Arc<Database>>,
-    parent_branch: Option<&str>,
-    composite_task_id: Option<&str>,
-) -> Result<u64> {
-    println!("\n{}", "=".repeat(60));
-    println!("Executing: {}", task.title);
-    println!("{}", "=".repeat(60));
+) -> Result<Option<u64>> {
+    say!("\n{}", "=".repeat(60));
+    say!("Executing: {}", task.title);
+    say!("{}", "=".repeat(60));
+
+    let config = github_client.load_repo_config(repository).await?;
+    let backend = autodev_executor::ExecutionRouter::new(autodev_executor::routing::default_backend())
+        .resolve(task.execution_backend, &config);
+    if backend.is_local() {
+        let router = execution_router(backend, engine, db).await;
+        router.execute_task(task, repository, engine, github_client, db).await?;
+        say!("✓ Task completed on {}: {}", backend, task.id);
+        return Ok(None);
+    }
 
     // Use shared executor module
-    let run_id = autodev_executor::execute_simple_task(
-        task,
-        repository,
-        engine,
-        github_client,
-        db,
-        parent_branch,
-        composite_task_id,
-    ).await?;
-
-    println!("✓ Workflow triggered: {}", run_id);
-    println!();
-    println!("🤖 Claude 4.5 Sonnet is now running in GitHub Actions (Docker + API).");
-    println!("   Check progress at: https://github.com/{}/actions", repository.full_name());
-    println!();
-    println!("💡 The workflow will:");
-    println!("   1. Checkout the repository");
-    println!("   2. Run Claude API in Docker container");
-    println!("   3. Automatically commit changes");
-    println!("   4. Create a pull request");
-    println!("   5. Notify AutoDev server on completion");
-    println!();
-    println!("✓ Task dispatched to GitHub Actions");
-    println!("  Task ID: {}", task.id);
-    println!("  Workflow Run: {}", run_id);
-
-    Ok(run_id)
+    let run_id = autodev_executor::execute_simple_task(task, repository, engine, github_client, db, None, None).await?;
+
+    say!("✓ Workflow triggered: {}", run_id);
+    say!();
+    say!("🤖 Claude 4.5 Sonnet is now running in GitHub Actions (Docker + API).");
+    say!("   Check progress at: {}/actions", repository.web_url(github_client.host()));
+    say!();
+    say!("💡 The workflow will:");
+    say!("   1. Checkout the repository");
+    say!("   2. Run Claude API in Docker container");
+    say!("   3. Automatically commit changes");
+    say!("   4. Create a pull request");
+    say!("   5. Notify AutoDev server on completion");
+    say!();
+    say!("✓ Task dispatched to GitHub Actions");
+    say!("  Task ID: {}", task.id);
+    say!("  Workflow Run: {}", run_id);
+
+    Ok(Some(run_id))
 }
 
 async fn execute_composite_task(
@@ -390,79 +1451,218 @@ async fn execute_composite_task(
     repository: &Repository,
     engine: &Arc<AutoDevEngine>,
     github_client: &Arc<GitHubClient>,
-    _ai_agent: &Arc<dyn AIAgent>,
+    ai_agent: &Arc<dyn AIAgent>,
     db: &Option<Arc<Database>>,
 ) -> Result<()> {
-    println!("\n{}", "=".repeat(60));
-    println!("Executing Composite Task: {}", composite_task.title);
-    println!("Subtasks: {}", composite_task.subtasks.len());
-    println!("Auto-approve: {}", composite_task.auto_approve);
-    println!("{}", "=".repeat(60));
-
-    // Check if local executor should be used
-    let use_local_executor = std::env::var("AUTODEV_LOCAL_EXECUTOR")
-        .unwrap_or_else(|_| "false".to_string())
-        .to_lowercase() == "true";
-
-    if use_local_executor {
-        // Initialize Docker executor for local execution
-        println!("🐳 Using Docker local execution mode");
-
-        let workspace_dir = std::env::var("AUTODEV_WORKSPACE_DIR")
-            .unwrap_or_else(|_| "/tmp/autodev-workspace".to_string());
-
-        // API key is optional - will fall back to Claude subscription
-        let anthropic_api_key = std::env::var("ANTHROPIC_API_KEY").ok();
-
-        let github_token = std::env::var("GITHUB_TOKEN")
-            .expect("GITHUB_TOKEN must be set for local execution");
-
-        let autodev_server_url = std::env::var("AUTODEV_SERVER_URL").ok();
-
-        match autodev_local_executor::DockerExecutor::new(
-            anthropic_api_key,
-            github_token,
-            autodev_server_url,
-            std::path::PathBuf::from(workspace_dir),
-        ).await {
-            Ok(executor) => {
-                let executor = Arc::new(executor);
-                autodev_executor::execute_composite_task_docker(
-                    composite_task,
-                    repository,
-                    &executor,
-                    engine,
-                    github_client,
-                    db,
-                ).await?;
-            }
-            Err(e) => {
-                eprintln!("Failed to initialize Docker executor: {}", e);
-                eprintln!("Falling back to GitHub Actions mode");
-
-                autodev_executor::execute_composite_task(
-                    composite_task,
-                    repository,
-                    engine,
-                    github_client,
-                    db,
-                ).await?;
+    say!("\n{}", "=".repeat(60));
+    say!("Executing Composite Task: {}", composite_task.title);
+    say!("Subtasks: {}", composite_task.subtasks.len());
+    say!("Auto-approve: {}", composite_task.auto_approve);
+    say!("{}", "=".repeat(60));
+
+    // No webhooks reach the CLI, so completion is detected by polling
+    let notifier = Arc::new(autodev_executor::CompletionNotifier::default());
+
+    let config = github_client.load_repo_config(repository).await?;
+    let backend = autodev_executor::ExecutionRouter::new(autodev_executor::routing::default_backend())
+        .resolve_composite(composite_task, &config);
+    execution_router(backend, engine, db)
+        .await
+        .execute_composite_task(composite_task, repository, engine, github_client, ai_agent, db, &notifier)
+        .await?;
+
+    say!("\n✓ Composite task completed: {}", composite_task.title);
+
+    Ok(())
+}
+
+/// Task with its logs and metrics, from the engine or the database
+async fn task_status(
+    task_id: &str,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+) -> Result<output::StatusOutput> {
+    let record = match db {
+        Some(db) => db.get_task(task_id).await?,
+        None => None,
+    };
+    let repository = record
+        .as_ref()
+        .and_then(|record| output::repository(&record.repository_owner, &record.repository_name));
+
+    let task = match (engine.get_task(task_id).await, &record) {
+        (Some(task), _) => output::TaskOutput::from_task(&task, repository),
+        (None, Some(record)) => output::TaskOutput::from_record(record),
+        (None, None) => anyhow::bail!("Task not found: {}", task_id),
+    };
+
+    let (execution_logs, metrics) = match db {
+        Some(db) => (db.get_execution_logs(task_id).await?, db.get_task_metrics(task_id).await?),
+        None => (Vec::new(), None),
+    };
+
+    Ok(output::StatusOutput {
+        task,
+        execution_logs,
+        metrics,
+    })
+}
+
+/// Repository of a composite task, falling back to the one stored with it
+async fn composite_repository(
+    composite_task_id: &str,
+    owner: Option<String>,
+    repo: Option<String>,
+    db: &Option<Arc<Database>>,
+) -> Result<Repository> {
+    let record = match db {
+        Some(db) => db.get_composite_task(composite_task_id).await?,
+        None => None,
+    };
+    let owner = owner
+        .or_else(|| record.as_ref().map(|r| r.repository_owner.clone()))
+        .filter(|o| !o.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Repository owner unknown, pass --owner"))?;
+    let repo = repo
+        .or_else(|| record.as_ref().map(|r| r.repository_name.clone()))
+        .filter(|r| !r.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Repository name unknown, pass --repo"))?;
+
+    Ok(Repository::new(owner, repo))
+}
+
+/// Decompose `prompt`, showing how much of the AI response has arrived
+/// Audit action of commands that change tasks, schedules, secrets or repositories;
+/// None for reads (and the schema commands, which run before the audit table exists)
+fn audit_action(command: &Commands) -> Option<&'static str> {
+    let action = match command {
+        Commands::Task { .. } => "cli.task",
+        Commands::Composite { .. } => "cli.composite",
+        Commands::Import { .. } => "cli.import",
+        Commands::Execute { .. } => "cli.execute",
+        Commands::Cancel { composite: true, .. } => "cli.cancel_composite",
+        Commands::Cancel { .. } => "cli.cancel",
+        Commands::ApproveBatch { .. } => "cli.approve_batch",
+        Commands::Pause { .. } => "cli.pause",
+        Commands::Resume { .. } => "cli.resume",
+        Commands::Rollback { .. } => "cli.rollback",
+        Commands::Tag { .. } => "cli.tag",
+        Commands::SetupRepo { .. } => "cli.setup_repo",
+        Commands::Gc { dry_run: false, .. } => "cli.gc",
+        Commands::Schedule { action: ScheduleCommands::Add { .. } } => "cli.schedule.add",
+        Commands::Schedule { action: ScheduleCommands::Remove { .. } } => "cli.schedule.remove",
+        Commands::Secret { action: SecretCommands::Set { .. } } => "cli.secret.set",
+        Commands::Secret { action: SecretCommands::Remove { .. } } => "cli.secret.remove",
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// Who CLI-created tasks are attributed to: AUTODEV_USER, else the login name
+fn local_user() -> Option<String> {
+    ["AUTODEV_USER", "USER", "USERNAME"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+}
+
+async fn plan_with_progress(
+    decomposer: &autodev_ai::TaskDecomposer,
+    prompt: &str,
+) -> Result<autodev_ai::TaskDecompositionResponse> {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let on_text: autodev_ai::StreamSink = {
+        let received = received.clone();
+        Arc::new(move |text: &str| {
+            let chars = text.chars().count();
+            let total = received.fetch_add(chars, Ordering::Relaxed) + chars;
+            // 구조화된 출력일 때 stdout에는 결과만 남김
+            if output::structured() {
+                eprint!("\r  Receiving plan... {} chars", total);
+            } else {
+                print!("\r  Receiving plan... {} chars", total);
+                let _ = std::io::stdout().flush();
             }
-        }
-    } else {
-        // Use GitHub Actions execution (existing behavior)
-        println!("☁️  Using GitHub Actions execution mode");
-
-        autodev_executor::execute_composite_task(
-            composite_task,
-            repository,
-            engine,
-            github_client,
-            db,
-        ).await?;
+        })
+    };
+
+    let plan = autodev_ai::with_stream(on_text, decomposer.plan(prompt)).await;
+    if received.load(Ordering::Relaxed) > 0 {
+        say!();
     }
+    Ok(plan?)
+}
 
-    println!("\n✓ Composite task completed: {}", composite_task.title);
+/// Router running tasks of `backend`, with its local executor started
+///
+/// When the executor fails to start, tasks that do not ask for `backend`
+/// themselves fall back to GitHub Actions.
+async fn execution_router(
+    backend: autodev_core::ExecutionBackend,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+) -> autodev_executor::ExecutionRouter {
+    let Some(kind) = autodev_local_executor::ExecutorKind::for_backend(backend) else {
+        if backend.is_local() {
+            say!("⚠ No {} executor is available here", backend);
+        } else {
+            say!("☁️  Using GitHub Actions execution mode");
+        }
+        return autodev_executor::ExecutionRouter::new(backend);
+    };
+    say!("🖥️  Using local execution mode ({})", kind);
+
+    let workspace_dir = std::env::var("AUTODEV_WORKSPACE_DIR")
+        .unwrap_or_else(|_| "/tmp/autodev-workspace".to_string());
+
+    // API key is optional - will fall back to Claude subscription
+    let anthropic_api_key = std::env::var("ANTHROPIC_API_KEY").ok();
+
+    let github_token = std::env::var("GITHUB_TOKEN")
+        .expect("GITHUB_TOKEN must be set for local execution");
+
+    let log_sink = autodev_executor::TaskLogSink::new(engine.clone(), db.clone());
+
+    match autodev_local_executor::create_executor(
+        kind,
+        anthropic_api_key,
+        github_token,
+        std::env::var("AUTODEV_SERVER_URL").ok(),
+        std::path::PathBuf::from(workspace_dir),
+        Some(Arc::new(log_sink)),
+        autodev_executor::secret_source(db),
+    ).await {
+        Ok(executor) => autodev_executor::ExecutionRouter::new(backend).with_executor(backend, executor),
+        Err(e) => {
+            eprintln!("Failed to initialize {} executor: {}", kind, e);
+            eprintln!("Falling back to GitHub Actions mode");
+            autodev_executor::ExecutionRouter::new(autodev_core::ExecutionBackend::Actions)
+        }
+    }
+}
 
-    Ok(())
-}
\ No newline at end of file
+/// Create the local executor when local execution is enabled
+async fn local_executor() -> Option<Arc<dyn autodev_local_executor::LocalExecutor>> {
+    let kind = autodev_executor::routing::local_executor_kind()?;
+
+    let workspace_dir = std::env::var("AUTODEV_WORKSPACE_DIR")
+        .unwrap_or_else(|_| "/tmp/autodev-workspace".to_string());
+
+    match autodev_local_executor::create_executor(
+        kind,
+        std::env::var("ANTHROPIC_API_KEY").ok(),
+        std::env::var("GITHUB_TOKEN").unwrap_or_default(),
+        std::env::var("AUTODEV_SERVER_URL").ok(),
+        std::path::PathBuf::from(workspace_dir),
+        None,
+        None,
+    ).await {
+        Ok(executor) => Some(executor),
+        Err(e) => {
+            eprintln!("Failed to initialize local executor: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use anyhow::{Context, Result};
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::process::Command;
+
+use autodev_ai::TaskDecompositionResponse;
+
+/// Let the user edit a decomposition plan in $VISUAL / $EDITOR
+///
+/// The plan is written as JSON, re-read after the editor exits and validated
+/// again. Invalid edits can be fixed by re-opening the editor; declining
+/// aborts the command.
+pub fn edit_plan(plan: &TaskDecompositionResponse) -> Result<TaskDecompositionResponse> {
+    let path = std::env::temp_dir().join(format!("autodev-plan-{}.json", std::process::id()));
+    std::fs::write(&path, serde_json::to_string_pretty(plan)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let result = edit_until_valid(&path);
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+fn edit_until_valid(path: &Path) -> Result<TaskDecompositionResponse> {
+    loop {
+        open_editor(path)?;
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        let error = match serde_json::from_str::<TaskDecompositionResponse>(&content) {
+            Ok(plan) => match plan.validate() {
+                Ok(()) if plan.tasks.is_empty() => "Plan has no tasks".to_string(),
+                Ok(()) => return Ok(plan),
+                Err(e) => e,
+            },
+            Err(e) => format!("Invalid JSON: {}", e),
+        };
+
+        println!("✗ {}", error);
+        if !confirm("Re-open the editor to fix it?")? {
+            anyhow::bail!("Plan editing aborted: {}", error);
+        }
+    }
+}
+
+fn open_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    // Editors are often configured with arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{}'", editor))?;
+
+    if !status.success() {
+        anyhow::bail!("Editor '{}' exited with {}", editor, status);
+    }
+
+    Ok(())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [Y/n] ", question);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(!matches!(answer.trim().to_lowercase().as_str(), "n" | "no"))
+}
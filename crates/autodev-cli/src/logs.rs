@@ -0,0 +1,192 @@
+use anyhow::{bail, Result};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use autodev_core::TaskStatus;
+use autodev_db::{Database, ExecutionLog};
+
+/// Lines fetched per query by `autodev logs`
+const LOG_PAGE_SIZE: i64 = 500;
+
+/// How often `autodev logs -f` checks for new lines
+const LOG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What `autodev logs` prints
+#[derive(Debug, Default)]
+pub struct LogsOptions {
+    /// Only the last N lines (events) that exist when the command starts
+    pub tail: Option<usize>,
+    pub follow: bool,
+    /// Execution events instead of output
+    pub events: bool,
+    /// Only events of this type; implies `events`
+    pub event_type: Option<String>,
+}
+
+/// Print the output or the execution events of a task
+///
+/// Output comes from the database when one is configured, otherwise from the
+/// log file the local executor wrote to the workspace directory.
+pub async fn logs(db: Option<&Database>, task_id: &str, options: &LogsOptions) -> Result<()> {
+    let events = options.events || options.event_type.is_some();
+
+    match db {
+        Some(db) if events => print_events(db, task_id, options).await,
+        Some(db) => print_output(db, task_id, options).await,
+        None if events => bail!("Execution events are stored in the database; set DATABASE_URL"),
+        None => print_log_file(task_id, options).await,
+    }
+}
+
+async fn print_events(db: &Database, task_id: &str, options: &LogsOptions) -> Result<()> {
+    let event_type = options.event_type.as_deref();
+    let mut after = 0;
+    let mut tail = options.tail;
+
+    loop {
+        let finished = !options.follow || task_finished(db, task_id).await?;
+
+        let mut logs = db.get_execution_logs_after(task_id, after, event_type).await?;
+        if let Some(last) = logs.last() {
+            after = last.id;
+        }
+        if let Some(tail) = tail.take() {
+            logs.drain(..logs.len().saturating_sub(tail));
+        }
+        for log in &logs {
+            print_event(log);
+        }
+
+        if finished {
+            return Ok(());
+        }
+        tokio::time::sleep(LOG_POLL_INTERVAL).await;
+    }
+}
+
+fn print_event(log: &ExecutionLog) {
+    println!(
+        "{} {:<20} {}",
+        log.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        log.event_type,
+        log.message
+    );
+}
+
+async fn print_output(db: &Database, task_id: &str, options: &LogsOptions) -> Result<()> {
+    let mut after = 0;
+
+    if let Some(tail) = options.tail {
+        let logs = db.get_recent_task_logs(task_id, tail as i64).await?;
+        for log in &logs {
+            println!("{}", log.line);
+        }
+        if let Some(last) = logs.last() {
+            after = last.id;
+        }
+        if !options.follow {
+            return Ok(());
+        }
+    }
+
+    loop {
+        // 출력을 먼저 읽은 뒤 상태를 확인해야 종료 직전의 줄을 놓치지 않음
+        let finished = !options.follow || task_finished(db, task_id).await?;
+
+        loop {
+            let logs = db.get_task_logs(task_id, after, LOG_PAGE_SIZE).await?;
+            for log in &logs {
+                println!("{}", log.line);
+            }
+            match logs.last() {
+                Some(last) if logs.len() as i64 == LOG_PAGE_SIZE => after = last.id,
+                Some(last) => {
+                    after = last.id;
+                    break;
+                }
+                None => break,
+            }
+        }
+
+        if finished {
+            return Ok(());
+        }
+        tokio::time::sleep(LOG_POLL_INTERVAL).await;
+    }
+}
+
+/// Output of a task run by the local executor, read from its log file
+///
+/// Without a database the task status is unknown, so `--follow` keeps
+/// waiting for new output until interrupted.
+async fn print_log_file(task_id: &str, options: &LogsOptions) -> Result<()> {
+    let workspace_dir = std::env::var("AUTODEV_WORKSPACE_DIR")
+        .unwrap_or_else(|_| "/tmp/autodev-workspace".to_string());
+    let path = autodev_local_executor::log_file_path(&PathBuf::from(workspace_dir), task_id);
+
+    let Ok(mut file) = tokio::fs::File::open(&path).await else {
+        bail!(
+            "No log file at {:?}; set DATABASE_URL to read logs from the database",
+            path
+        );
+    };
+
+    let mut content = Vec::new();
+    let mut offset = file.read_to_end(&mut content).await? as u64;
+
+    // 따라갈 때는 마지막 줄바꿈 이후가 아직 쓰는 중일 수 있으므로 완성된 줄만 출력
+    let mut pending = match options.follow {
+        true => content.split_off(line_end(&content).map_or(0, |end| end + 1)),
+        false => Vec::new(),
+    };
+
+    let content = String::from_utf8_lossy(&content);
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(options.tail.unwrap_or(lines.len()));
+    for line in &lines[start..] {
+        println!("{}", line);
+    }
+
+    if !options.follow {
+        return Ok(());
+    }
+
+    loop {
+        tokio::time::sleep(LOG_POLL_INTERVAL).await;
+
+        let mut file = tokio::fs::File::open(&path).await?;
+        if file.metadata().await?.len() < offset {
+            // 같은 ID로 다시 실행되어 파일이 새로 만들어짐
+            offset = 0;
+            pending.clear();
+        }
+        file.seek(std::io::SeekFrom::Start(offset)).await?;
+        offset += file.read_to_end(&mut pending).await? as u64;
+
+        if let Some(end) = line_end(&pending) {
+            for line in String::from_utf8_lossy(&pending[..end]).lines() {
+                println!("{}", line);
+            }
+            pending.drain(..=end);
+        }
+    }
+}
+
+/// Position of the last newline
+fn line_end(bytes: &[u8]) -> Option<usize> {
+    bytes.iter().rposition(|byte| *byte == b'\n')
+}
+
+/// Whether a task has stopped running (unknown tasks count as finished)
+async fn task_finished(db: &Database, task_id: &str) -> Result<bool> {
+    let status = db
+        .get_task(task_id)
+        .await?
+        .and_then(|record| record.status.parse::<TaskStatus>().ok());
+
+    Ok(!matches!(
+        status,
+        Some(TaskStatus::Pending | TaskStatus::WaitingDependencies | TaskStatus::Ready | TaskStatus::InProgress)
+    ))
+}
@@ -5,6 +5,11 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod commands;
 mod cli;
+mod config;
+mod edit;
+mod logs;
+mod output;
+mod watch;
 
 use cli::{Cli, Commands};
 
@@ -16,7 +21,8 @@ async fn main() -> Result<()> {
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "autodev=debug".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        // stdout is reserved for command output (see --output)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .init();
 
     // Load environment variables
@@ -25,13 +31,21 @@ async fn main() -> Result<()> {
     // Parse CLI arguments
     let cli = Cli::parse();
 
+    if let Commands::Config { action } = cli.command {
+        return config::config(action, cli.profile.as_deref());
+    }
+
+    // Config file values only fill unset environment variables; parse again so
+    // that flags falling back to the environment see them
+    autodev_core::config::load_into_env(cli.profile.as_deref())?;
+    let cli = Cli::parse();
+
     // Run CLI
     run(cli).await
 }
 
 async fn run(cli: Cli) -> Result<()> {
-    // Initialize engine
-    let engine = Arc::new(autodev_core::AutoDevEngine::new());
+    output::init(cli.output);
 
     // Initialize database (optional)
     let db = if let Some(ref db_url) = cli.database_url {
@@ -44,9 +58,28 @@ async fn run(cli: Cli) -> Result<()> {
         None
     };
 
+    let manages_schema = matches!(cli.command, Commands::InitDb | Commands::Migrate { .. });
+
+    // Refuse to run against a schema older than this build
+    if let Some(ref database) = db {
+        if !manages_schema {
+            database.check_schema_version().await?;
+        }
+    }
+
+    // Initialize engine (restores tasks from previous runs when a database is configured)
+    let engine = match db {
+        Some(ref database) if !manages_schema => {
+            let engine = autodev_core::AutoDevEngine::with_store(database.clone());
+            engine.hydrate().await?;
+            Arc::new(engine)
+        }
+        _ => Arc::new(autodev_core::AutoDevEngine::new()),
+    };
+
     // Initialize GitHub client
     let github_client = Arc::new(
-        autodev_github::GitHubClient::new(cli.github_token.clone())?
+        autodev_github::GitHubClient::from_env_or_token(cli.github_token.clone()).await?
     );
 
     // Initialize AI agent
@@ -54,43 +87,27 @@ async fn run(cli: Cli) -> Result<()> {
     // For 'serve' command with local Docker executor, AI agent is not used (Docker worker handles it)
     let needs_ai_agent = !matches!(cli.command, Commands::Serve { .. });
 
-    let ai_agent: Arc<dyn autodev_ai::AIAgent> = if needs_ai_agent {
-        // CLI commands need API key
-        match cli.agent_type.as_str() {
-            "claude" | "claude-code" => {
-                let api_key = std::env::var("ANTHROPIC_API_KEY")
-                    .expect("ANTHROPIC_API_KEY must be set for Claude agent in CLI mode");
-                Arc::new(autodev_ai::ClaudeAgent::new(api_key))
-            }
-            "gpt-4" | "openai" => {
-                tracing::warn!("OpenAI agent not implemented, using Claude instead");
-                let api_key = std::env::var("ANTHROPIC_API_KEY")
-                    .expect("ANTHROPIC_API_KEY must be set");
-                Arc::new(autodev_ai::ClaudeAgent::new(api_key))
-            }
-            _ => {
-                tracing::warn!("Unknown AI agent type: {}, using Claude", cli.agent_type);
-                let api_key = std::env::var("ANTHROPIC_API_KEY")
-                    .expect("ANTHROPIC_API_KEY must be set");
-                Arc::new(autodev_ai::ClaudeAgent::new(api_key))
-            }
-        }
-    } else {
-        // For 'serve' command, try OAuth token first, fallback to API key
-        if let Ok(oauth_token) = std::env::var("CLAUDE_CODE_OAUTH_TOKEN") {
-            tracing::info!("Using Docker-based AI executor with Claude subscription OAuth token");
-            Arc::new(
-                autodev_ai::DockerAIExecutor::new(oauth_token)
-                    .expect("Failed to initialize Docker AI executor")
-            )
-        } else if let Ok(api_key) = std::env::var("ANTHROPIC_API_KEY") {
-            tracing::info!("Using HTTP API-based AI agent with API key");
-            Arc::new(autodev_ai::ClaudeAgent::new(api_key))
-        } else {
-            panic!("Either CLAUDE_CODE_OAUTH_TOKEN or ANTHROPIC_API_KEY must be set for serve command");
-        }
+    // 'serve' tries the OAuth token first, other commands need an API key
+    let agent_config = autodev_ai::AgentConfig {
+        agent_type: cli.agent_type.clone(),
+        model: std::env::var("AI_MODEL").ok(),
+        allow_oauth: !needs_ai_agent,
+        fallbacks: autodev_ai::AgentConfig::fallbacks_from_env(),
+        routes: autodev_ai::AgentConfig::routes_from_env(),
+        ..Default::default()
     };
+    let ai_agent = autodev_executor::with_response_cache(autodev_ai::create_agent(&agent_config)?, &db);
+
+    // Notifications of what the command completed are sent before exiting
+    // ('serve' runs its own subscriber)
+    let notifications = (!matches!(cli.command, Commands::Serve { .. }))
+        .then(|| autodev_executor::EventNotifier::spawn(&engine));
 
     // Execute command
-    commands::execute(cli.command, engine, db, github_client, ai_agent).await
+    let result = commands::execute(cli.command, engine, db, github_client, ai_agent).await;
+    if let Some(notifications) = notifications {
+        notifications.shutdown().await;
+    }
+
+    result
 }
\ No newline at end of file
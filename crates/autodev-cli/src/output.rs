@@ -0,0 +1,257 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::OnceLock;
+
+use autodev_core::{CompositeTask, Task, DEFAULT_TASK_MINUTES};
+use autodev_db::{
+    AggregateStats, ExecutionLog, Metrics, RepositoryStats, StatsGranularity, TagStats, TaskRecord, TimeseriesStats,
+};
+
+/// Format of command output (`--output`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Table,
+    Json,
+    Yaml,
+}
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Set the output format for the rest of the process
+pub fn init(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+pub fn format() -> OutputFormat {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Whether results are printed as JSON/YAML instead of text
+pub fn structured() -> bool {
+    format() != OutputFormat::Table
+}
+
+/// Print progress text: to stdout for table output, to stderr otherwise so
+/// that stdout only carries the structured result
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::output::structured() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+pub(crate) use say;
+
+/// Print `value` as JSON or YAML (nothing for table output)
+pub fn print<T: Serialize>(value: &T) -> Result<()> {
+    match format() {
+        OutputFormat::Table => {}
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(value)?),
+    }
+    Ok(())
+}
+
+/// A task as printed by task, status and list
+#[derive(Debug, Serialize)]
+pub struct TaskOutput {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub status: String,
+    /// `owner/name`, when known
+    pub repository: Option<String>,
+    pub dependencies: Vec<String>,
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub pr_url: Option<String>,
+    pub pr_number: Option<u64>,
+    pub workflow_run_id: Option<String>,
+    pub error: Option<String>,
+    /// Failed dependency that blocked or skipped the task
+    pub blocked_by: Option<String>,
+    pub issue_url: Option<String>,
+    pub created_by: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl TaskOutput {
+    pub fn from_task(task: &Task, repository: Option<String>) -> Self {
+        Self {
+            id: task.id.clone(),
+            title: task.title.clone(),
+            description: task.description.clone(),
+            status: format!("{:?}", task.status),
+            repository,
+            dependencies: task.dependencies.clone(),
+            attempts: task.attempts,
+            created_at: task.created_at,
+            started_at: task.started_at,
+            completed_at: task.completed_at,
+            pr_url: task.pr_url.clone(),
+            pr_number: task.pr_number,
+            workflow_run_id: task.workflow_run_id.clone(),
+            error: task.error.clone(),
+            blocked_by: task.blocked_by.clone(),
+            issue_url: task.issue_url.clone(),
+            created_by: task.created_by.clone(),
+            tags: task.tags.clone(),
+        }
+    }
+
+    pub fn from_record(record: &TaskRecord) -> Self {
+        Self {
+            id: record.id.clone(),
+            title: record.title.clone(),
+            description: record.description.clone(),
+            status: record.status.clone(),
+            repository: repository(&record.repository_owner, &record.repository_name),
+            dependencies: record.dependencies.clone(),
+            attempts: record.attempts.max(0) as u32,
+            created_at: record.created_at,
+            started_at: record.started_at,
+            completed_at: record.completed_at,
+            pr_url: record.pr_url.clone(),
+            pr_number: record.pr_number.and_then(|n| u64::try_from(n).ok()),
+            workflow_run_id: record.workflow_run_id.clone(),
+            error: record.error.clone(),
+            blocked_by: record.blocked_by.clone(),
+            issue_url: record.issue_url.clone(),
+            created_by: record.created_by.clone(),
+            tags: record.tags.clone(),
+        }
+    }
+}
+
+/// `owner/name`, None for tasks stored without a repository
+pub fn repository(owner: &str, name: &str) -> Option<String> {
+    (!owner.is_empty() && !name.is_empty()).then(|| format!("{}/{}", owner, name))
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompositeOutput {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub repository: Option<String>,
+    pub auto_approve: bool,
+    pub max_parallel_tasks: Option<usize>,
+    pub merge_method: Option<String>,
+    pub failure_policy: String,
+    pub created_by: Option<String>,
+    pub subtasks: Vec<TaskOutput>,
+    /// Subtask IDs per parallel batch, in execution order
+    pub batches: Vec<Vec<String>>,
+    /// Wall-clock estimate: the slowest subtask of each batch, summed
+    pub estimated_minutes: u64,
+    /// Subtask IDs along the longest chain by estimated duration
+    pub critical_path: Vec<String>,
+}
+
+impl CompositeOutput {
+    pub fn new(composite_task: &CompositeTask, repository: Option<String>) -> Self {
+        Self {
+            id: composite_task.id.clone(),
+            title: composite_task.title.clone(),
+            description: composite_task.description.clone(),
+            repository: repository.clone(),
+            auto_approve: composite_task.auto_approve,
+            max_parallel_tasks: composite_task.max_parallel_tasks,
+            merge_method: composite_task.merge_method.map(|m| m.to_string()),
+            failure_policy: composite_task.failure_policy.to_string(),
+            created_by: composite_task.created_by.clone(),
+            subtasks: composite_task
+                .subtasks
+                .iter()
+                .map(|task| TaskOutput::from_task(task, repository.clone()))
+                .collect(),
+            batches: composite_task
+                .get_parallel_batches()
+                .iter()
+                .map(|batch| batch.iter().map(|task| task.id.clone()).collect())
+                .collect(),
+            estimated_minutes: composite_task.estimate_total_time(u64::from(DEFAULT_TASK_MINUTES) * 60) / 60,
+            critical_path: composite_task.critical_path().0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusOutput {
+    #[serde(flatten)]
+    pub task: TaskOutput,
+    /// Newest first
+    pub execution_logs: Vec<ExecutionLog>,
+    pub metrics: Option<Metrics>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListOutput {
+    /// Tasks known to this process
+    pub tasks: Vec<TaskOutput>,
+    /// Most recently created tasks in the database
+    pub recent: Vec<TaskOutput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsOutput {
+    pub total_tasks: usize,
+    pub completed_tasks: usize,
+    pub failed_tasks: usize,
+    pub in_progress_tasks: usize,
+    pub database: Option<DatabaseStatsOutput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DatabaseStatsOutput {
+    #[serde(flatten)]
+    pub totals: AggregateStats,
+    pub repositories: Vec<RepositoryStats>,
+    pub tags: Vec<TagStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsHistoryOutput {
+    pub granularity: StatsGranularity,
+    pub points: Vec<TimeseriesStats>,
+}
+
+/// One bar per value scaled to the largest; missing values are blank
+pub fn sparkline(values: &[Option<f64>]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let max = values.iter().flatten().fold(0.0_f64, |max, &value| max.max(value));
+    values
+        .iter()
+        .map(|value| match value {
+            None => ' ',
+            Some(_) if max <= 0.0 => BARS[0],
+            Some(value) => BARS[((value / max) * (BARS.len() - 1) as f64).round() as usize],
+        })
+        .collect()
+}
+
+/// `1h 5m`, `3m 20s` or `42s`
+pub fn duration_ms(ms: i64) -> String {
+    let secs = ms.max(0) / 1000;
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+/// `text` cut to `max` characters, ending in `…` when cut
+pub fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    text.chars().take(max.saturating_sub(1)).chain(['…']).collect()
+}
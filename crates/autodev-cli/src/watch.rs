@@ -0,0 +1,268 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::time::{Duration, Instant};
+
+use autodev_core::{CompositeTask, TaskStatus, TaskStore};
+use autodev_db::{Database, TaskQuery, TaskRecord};
+
+/// Tasks listed in the dashboard (newest first)
+const TASK_LIMIT: i64 = 50;
+
+/// Output lines kept for the selected task
+const LOG_LINES: i64 = 200;
+
+/// How long a key press is waited for between redraws
+const INPUT_POLL: Duration = Duration::from_millis(200);
+
+/// Live dashboard of tasks, composite-task batches and output, polling the database
+///
+/// Keys: ↑/↓ (k/j) select a task, r refreshes, q/Esc quits.
+pub async fn watch(db: &Database, interval: Duration) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, db, interval).await;
+    ratatui::restore();
+    result
+}
+
+#[derive(Default)]
+struct Dashboard {
+    tasks: Vec<TaskRecord>,
+    composites: Vec<CompositeTask>,
+    /// Output of the selected task
+    logs: Vec<String>,
+    selected: TableState,
+    updated_at: Option<DateTime<Utc>>,
+    error: Option<String>,
+}
+
+async fn run(terminal: &mut DefaultTerminal, db: &Database, interval: Duration) -> Result<()> {
+    let mut dashboard = Dashboard::default();
+    dashboard.selected.select(Some(0));
+    let mut next_refresh = Instant::now();
+
+    loop {
+        if Instant::now() >= next_refresh {
+            dashboard.refresh(db).await;
+            next_refresh = Instant::now() + interval;
+        }
+
+        terminal.draw(|frame| dashboard.render(frame))?;
+
+        if !event::poll(INPUT_POLL)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('r') => next_refresh = Instant::now(),
+            KeyCode::Down | KeyCode::Char('j') => {
+                dashboard.select_next(1);
+                next_refresh = Instant::now();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                dashboard.select_next(-1);
+                next_refresh = Instant::now();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dashboard {
+    async fn refresh(&mut self, db: &Database) {
+        // 갱신 실패는 대시보드에 표시하고 다음 주기에 다시 시도
+        self.error = self.load(db).await.err().map(|e| e.to_string());
+        self.updated_at = Some(Utc::now());
+    }
+
+    async fn load(&mut self, db: &Database) -> Result<()> {
+        let query = TaskQuery {
+            limit: TASK_LIMIT,
+            ..Default::default()
+        };
+        self.tasks = db.list_tasks(&query).await?.0;
+        self.composites = db.load_snapshot().await?.composite_tasks;
+
+        let selected = self.selected.selected().unwrap_or(0).min(self.tasks.len().saturating_sub(1));
+        self.selected.select(Some(selected));
+
+        self.logs = match self.tasks.get(selected) {
+            Some(task) => db
+                .get_recent_task_logs(&task.id, LOG_LINES)
+                .await?
+                .into_iter()
+                .map(|log| log.line)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Ok(())
+    }
+
+    fn select_next(&mut self, step: isize) {
+        let last = self.tasks.len().saturating_sub(1);
+        let selected = self.selected.selected().unwrap_or(0).saturating_add_signed(step).min(last);
+        self.selected.select(Some(selected));
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let composite_height = (self.composites.len() as u16 * 2).clamp(1, 12) + 2;
+        let [header, tasks, composites, logs] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Percentage(45),
+            Constraint::Length(composite_height),
+            Constraint::Min(5),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(self.header(), header);
+        self.render_tasks(frame, tasks);
+        frame.render_widget(self.composites(), composites);
+        frame.render_widget(self.logs(logs.height.saturating_sub(2) as usize), logs);
+    }
+
+    fn header(&self) -> Paragraph<'static> {
+        let count = |status: TaskStatus| {
+            let status = format!("{:?}", status);
+            self.tasks.iter().filter(|task| task.status == status).count()
+        };
+
+        let mut spans = vec![
+            " AutoDev ".bold().reversed(),
+            Span::raw(format!(
+                "  {} in progress · {} pending · {} failed",
+                count(TaskStatus::InProgress),
+                count(TaskStatus::Pending) + count(TaskStatus::WaitingDependencies),
+                count(TaskStatus::Failed)
+            )),
+        ];
+        if let Some(updated_at) = self.updated_at {
+            spans.push(Span::raw(format!(
+                "  · updated {}",
+                updated_at.with_timezone(&Local).format("%H:%M:%S")
+            )));
+        }
+        if let Some(ref error) = self.error {
+            spans.push(Span::styled(format!("  ✗ {}", error), Style::new().fg(Color::Red)));
+        }
+        spans.push("  (↑↓ select, r refresh, q quit)".dark_gray());
+
+        Paragraph::new(Line::from(spans))
+    }
+
+    fn render_tasks(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let rows = self.tasks.iter().map(|task| {
+            Row::new(vec![
+                Span::styled(task.status.clone(), status_style(&task.status)),
+                Span::raw(task.title.clone()),
+                Span::raw(format!("{}/{}", task.repository_owner, task.repository_name)),
+                Span::raw(task.pr_url.clone().unwrap_or_default()),
+            ])
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(20),
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(["Status", "Title", "Repository", "PR"]).bold())
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .block(Block::bordered().title(format!(" Tasks ({}) ", self.tasks.len())));
+
+        frame.render_stateful_widget(table, area, &mut self.selected);
+    }
+
+    fn composites(&self) -> Paragraph<'static> {
+        let mut lines = Vec::new();
+
+        for composite in &self.composites {
+            let done = composite.subtasks.iter().filter(|t| t.status == TaskStatus::Completed).count();
+            lines.push(Line::from(vec![
+                Span::raw(composite.title.clone()).bold(),
+                Span::raw(format!("  {}/{} subtasks", done, composite.subtasks.len())),
+                if composite.is_pending_review() {
+                    " · awaiting review".yellow()
+                } else if composite.is_fix_required() {
+                    " · tests failed, fix required".red()
+                } else {
+                    Span::raw("")
+                },
+            ]));
+
+            let batches: Vec<Span> = composite
+                .get_parallel_batches()
+                .iter()
+                .enumerate()
+                .map(|(index, batch)| {
+                    let done = batch.iter().filter(|t| t.status == TaskStatus::Completed).count();
+                    let style = if batch.iter().any(|t| t.status == TaskStatus::Failed) {
+                        Style::new().fg(Color::Red)
+                    } else if !composite.is_batch_approved(index + 1) {
+                        Style::new().fg(Color::DarkGray)
+                    } else {
+                        Style::new().fg(Color::Green)
+                    };
+                    Span::styled(
+                        format!("  #{} {} {}/{}", index + 1, progress_bar(done, batch.len()), done, batch.len()),
+                        style,
+                    )
+                })
+                .collect();
+            lines.push(Line::from(batches));
+        }
+
+        if lines.is_empty() {
+            lines.push("No running composite tasks".dark_gray().into());
+        }
+
+        Paragraph::new(lines).block(Block::bordered().title(" Composite tasks (batches) "))
+    }
+
+    fn logs(&self, height: usize) -> Paragraph<'static> {
+        let title = match self.selected.selected().and_then(|index| self.tasks.get(index)) {
+            Some(task) => format!(" Output: {} ", task.title),
+            None => " Output ".to_string(),
+        };
+
+        let lines: Vec<Line> = self
+            .logs
+            .iter()
+            .skip(self.logs.len().saturating_sub(height))
+            .map(|line| Line::raw(line.clone()))
+            .collect();
+
+        Paragraph::new(lines).block(Block::bordered().title(title))
+    }
+}
+
+fn status_style(status: &str) -> Style {
+    match status.parse::<TaskStatus>() {
+        Ok(TaskStatus::Completed) => Style::new().fg(Color::Green),
+        Ok(TaskStatus::InProgress) => Style::new().fg(Color::Yellow),
+        Ok(TaskStatus::Failed) | Ok(TaskStatus::BudgetExceeded) => Style::new().fg(Color::Red),
+        Ok(TaskStatus::Cancelled) | Ok(TaskStatus::Interrupted) => Style::new().fg(Color::DarkGray),
+        _ => Style::new(),
+    }
+}
+
+fn progress_bar(done: usize, total: usize) -> String {
+    const WIDTH: usize = 10;
+    let filled = (done * WIDTH).checked_div(total).unwrap_or(WIDTH);
+    format!("{}{}", "█".repeat(filled), "░".repeat(WIDTH - filled))
+}
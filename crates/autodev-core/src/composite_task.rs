@@ -1,9 +1,206 @@
-use crate::task::Task;
+use crate::task::{ExecutionBackend, Task};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+/// Whether a composite task's decomposition may be executed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReviewState {
+    /// Decomposed only; no branch or workflow is created until approved
+    PendingReview,
+    #[default]
+    Approved,
+    /// The parent branch failed its tests; the next batch waits until a fix is approved
+    FixRequired,
+    /// Paused by a user (e.g. during a release freeze); no subtask starts until resumed
+    Paused,
+}
+
+impl std::str::FromStr for ReviewState {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PendingReview" => Ok(ReviewState::PendingReview),
+            "Approved" => Ok(ReviewState::Approved),
+            "FixRequired" => Ok(ReviewState::FixRequired),
+            "Paused" => Ok(ReviewState::Paused),
+            _ => Err(crate::Error::InvalidTaskState(s.to_string())),
+        }
+    }
+}
+
+/// How subtask PRs are merged into the parent branch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMethod {
+    #[default]
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        }
+    }
+}
+
+impl std::fmt::Display for MergeMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for MergeMethod {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "merge" => Ok(MergeMethod::Merge),
+            "squash" => Ok(MergeMethod::Squash),
+            "rebase" => Ok(MergeMethod::Rebase),
+            _ => Err(crate::Error::InvalidTaskState(format!("Unknown merge method: {}", s))),
+        }
+    }
+}
+
+/// Severity of an AI code review finding, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSeverity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl ReviewSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewSeverity::Info => "info",
+            ReviewSeverity::Low => "low",
+            ReviewSeverity::Medium => "medium",
+            ReviewSeverity::High => "high",
+            ReviewSeverity::Critical => "critical",
+        }
+    }
+}
+
+impl std::fmt::Display for ReviewSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ReviewSeverity {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "info" => Ok(ReviewSeverity::Info),
+            "low" => Ok(ReviewSeverity::Low),
+            "medium" => Ok(ReviewSeverity::Medium),
+            "high" => Ok(ReviewSeverity::High),
+            "critical" => Ok(ReviewSeverity::Critical),
+            _ => Err(crate::Error::InvalidTaskState(format!("Unknown review severity: {}", s))),
+        }
+    }
+}
+
+/// Assumed duration of a subtask the decomposition gave no estimate for
+pub const DEFAULT_TASK_MINUTES: u32 = 30;
+
+/// What happens to the subtasks depending on a subtask that failed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FailurePolicy {
+    /// Dependents are cancelled; independent subtasks still run
+    Skip,
+    /// Dependents are blocked until the failed subtask is retried; execution stops
+    #[default]
+    Block,
+    /// Dependents run as if the dependency had completed
+    RunAnyway,
+}
+
+impl FailurePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailurePolicy::Skip => "skip",
+            FailurePolicy::Block => "block",
+            FailurePolicy::RunAnyway => "run-anyway",
+        }
+    }
+}
+
+impl std::fmt::Display for FailurePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for FailurePolicy {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "skip" => Ok(FailurePolicy::Skip),
+            "block" => Ok(FailurePolicy::Block),
+            "run-anyway" | "runanyway" => Ok(FailurePolicy::RunAnyway),
+            _ => Err(crate::Error::InvalidTaskState(format!("Unknown failure policy: {}", s))),
+        }
+    }
+}
+
+/// Execution state of one parallel batch of a composite task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BatchStatus {
+    Pending,
+    Running,
+    /// Next to run, but held until the batch (or the decomposition) is approved
+    AwaitingApproval,
+    Completed,
+    Failed,
+    /// Every unfinished task waits on (or was skipped because of) a failed dependency
+    Blocked,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProgress {
+    /// 1-based
+    pub batch: usize,
+    pub status: BatchStatus,
+    pub task_ids: Vec<String>,
+}
+
+/// Outcome of a composite task's subtasks
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletionSummary {
+    pub completed: usize,
+    pub failed: Vec<String>,
+    /// Cancelled because a dependency failed
+    pub skipped: Vec<String>,
+    /// Waiting for a failed dependency to be retried
+    pub blocked: Vec<String>,
+    /// Not finished yet (including cancelled by a user)
+    pub remaining: usize,
+}
+
+impl CompletionSummary {
+    /// Nothing is left to run, but not every subtask completed
+    pub fn is_partial(&self) -> bool {
+        self.remaining == 0 && !(self.failed.is_empty() && self.skipped.is_empty() && self.blocked.is_empty())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompositeTask {
     pub id: String,
@@ -13,6 +210,31 @@ pub struct CompositeTask {
     pub auto_approve: bool,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Set when a user cancelled the composite task; no further batch starts
+    #[serde(default)]
+    pub cancelled_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub review_state: ReviewState,
+    /// Batches (1-based) approved to start when `auto_approve` is off
+    #[serde(default)]
+    pub approved_batches: Vec<usize>,
+    /// Cap on subtasks running at once (None: only the process-wide limit applies)
+    #[serde(default)]
+    pub max_parallel_tasks: Option<usize>,
+    /// How subtask PRs are merged (None: the repository's default)
+    #[serde(default)]
+    pub merge_method: Option<MergeMethod>,
+    /// Branch the parent branch is created from (None: the repository's default branch)
+    #[serde(default)]
+    pub base_branch: Option<String>,
+    /// User (API key) that created the composite task
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub failure_policy: FailurePolicy,
+    /// Backend every subtask runs on (None: the repository's or server's default)
+    #[serde(default)]
+    pub execution_backend: Option<ExecutionBackend>,
 }
 
 impl CompositeTask {
@@ -25,6 +247,15 @@ impl CompositeTask {
             auto_approve: false,
             created_at: Utc::now(),
             completed_at: None,
+            cancelled_at: None,
+            review_state: ReviewState::Approved,
+            approved_batches: Vec::new(),
+            max_parallel_tasks: None,
+            merge_method: None,
+            base_branch: None,
+            created_by: None,
+            failure_policy: FailurePolicy::default(),
+            execution_backend: None,
         }
     }
 
@@ -33,6 +264,77 @@ impl CompositeTask {
         self
     }
 
+    pub fn with_max_parallel_tasks(mut self, max_parallel_tasks: Option<usize>) -> Self {
+        self.max_parallel_tasks = max_parallel_tasks.filter(|n| *n > 0);
+        self
+    }
+
+    pub fn with_merge_method(mut self, merge_method: Option<MergeMethod>) -> Self {
+        self.merge_method = merge_method;
+        self
+    }
+
+    pub fn with_base_branch(mut self, base_branch: Option<String>) -> Self {
+        self.base_branch = base_branch;
+        self
+    }
+
+    pub fn with_failure_policy(mut self, failure_policy: FailurePolicy) -> Self {
+        self.failure_policy = failure_policy;
+        self
+    }
+
+    /// Run the composite task and its subtasks on `backend`
+    pub fn with_execution_backend(mut self, backend: Option<ExecutionBackend>) -> Self {
+        for subtask in &mut self.subtasks {
+            subtask.execution_backend = backend;
+        }
+        self.execution_backend = backend;
+        self
+    }
+
+    /// Attribute the composite task and its subtasks to `created_by`
+    pub fn with_created_by(mut self, created_by: Option<String>) -> Self {
+        for subtask in &mut self.subtasks {
+            subtask.created_by = created_by.clone();
+        }
+        self.created_by = created_by;
+        self
+    }
+
+    /// Hold execution until the decomposition is approved
+    pub fn with_review_required(mut self, required: bool) -> Self {
+        if required {
+            self.review_state = ReviewState::PendingReview;
+        }
+        self
+    }
+
+    pub fn is_pending_review(&self) -> bool {
+        self.review_state == ReviewState::PendingReview
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.review_state == ReviewState::Paused
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled_at.is_some()
+    }
+
+    /// Held because the parent branch failed its tests
+    pub fn is_fix_required(&self) -> bool {
+        self.review_state == ReviewState::FixRequired
+    }
+
+    /// Whether batch `batch` (1-based) may start
+    ///
+    /// The first batch starts with execution itself; later batches need an
+    /// explicit approval unless `auto_approve` is set.
+    pub fn is_batch_approved(&self, batch: usize) -> bool {
+        self.auto_approve || batch <= 1 || self.approved_batches.contains(&batch)
+    }
+
     /// Generate dependency graph
     pub fn get_dependency_graph(&self) -> HashMap<String, Vec<String>> {
         self.subtasks
@@ -41,6 +343,16 @@ impl CompositeTask {
             .collect()
     }
 
+    /// First dependency cycle among the subtasks, as the subtask IDs along it
+    pub fn find_dependency_cycle(&self) -> Option<Vec<String>> {
+        let graph: Vec<(&str, &[String])> = self
+            .subtasks
+            .iter()
+            .map(|task| (task.id.as_str(), task.dependencies.as_slice()))
+            .collect();
+        find_cycle(&graph)
+    }
+
     /// Group tasks into parallel execution batches
     pub fn get_parallel_batches(&self) -> Vec<Vec<Task>> {
         let mut completed = HashSet::new();
@@ -73,10 +385,41 @@ impl CompositeTask {
         batches
     }
 
-    /// Calculate total estimated time (assuming parallel execution)
+    /// Estimated wall-clock seconds: the slowest subtask of each parallel batch, summed
+    ///
+    /// Subtasks without an estimate take `avg_task_time_secs`.
     pub fn estimate_total_time(&self, avg_task_time_secs: u64) -> u64 {
-        let batches = self.get_parallel_batches();
-        batches.len() as u64 * avg_task_time_secs
+        self.get_parallel_batches()
+            .iter()
+            .map(|batch| {
+                batch
+                    .iter()
+                    .map(|t| t.estimated_minutes.map_or(avg_task_time_secs, |m| u64::from(m) * 60))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .sum()
+    }
+
+    /// Subtasks along the longest dependency chain by estimated duration, and its minutes
+    pub fn critical_path(&self) -> (Vec<String>, u32) {
+        critical_path(&self.subtasks)
+    }
+
+    /// Estimated wall-clock minutes: the slowest subtask of each parallel batch, summed
+    ///
+    /// None when no subtask carries an estimate.
+    pub fn estimated_minutes(&self) -> Option<u32> {
+        if self.subtasks.iter().all(|t| t.estimated_minutes.is_none()) {
+            return None;
+        }
+
+        Some(
+            self.get_parallel_batches()
+                .iter()
+                .map(|batch| batch.iter().filter_map(|t| t.estimated_minutes).max().unwrap_or(0))
+                .sum(),
+        )
     }
 
     /// Check if all subtasks are completed
@@ -86,6 +429,67 @@ impl CompositeTask {
             .all(|task| matches!(task.status, crate::task::TaskStatus::Completed))
     }
 
+    /// Status of each parallel batch, derived from the subtasks' statuses
+    pub fn batch_progress(&self) -> Vec<BatchProgress> {
+        use crate::task::TaskStatus;
+
+        let mut blocked = false;
+        self.get_parallel_batches()
+            .into_iter()
+            .enumerate()
+            .map(|(i, batch)| {
+                let batch_number = i + 1;
+                let started = batch
+                    .iter()
+                    .any(|t| !matches!(t.status, TaskStatus::Pending | TaskStatus::WaitingDependencies | TaskStatus::Ready));
+
+                let status = if batch.iter().all(|t| t.status == TaskStatus::Completed) {
+                    BatchStatus::Completed
+                } else if batch
+                    .iter()
+                    .any(|t| t.status == TaskStatus::Failed || (t.status == TaskStatus::Cancelled && !t.is_skipped()))
+                {
+                    BatchStatus::Failed
+                } else if batch.iter().all(|t| t.status == TaskStatus::Completed || t.blocked_by.is_some()) {
+                    BatchStatus::Blocked
+                } else if started {
+                    BatchStatus::Running
+                } else if !blocked && (self.is_pending_review() || !self.is_batch_approved(batch_number)) {
+                    BatchStatus::AwaitingApproval
+                } else {
+                    BatchStatus::Pending
+                };
+
+                // 이전 배치가 끝나지 않았으면 이후 배치는 승인 대기가 아님
+                blocked |= status != BatchStatus::Completed;
+
+                BatchProgress {
+                    batch: batch_number,
+                    status,
+                    task_ids: batch.into_iter().map(|t| t.id).collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Completed, failed, skipped and blocked subtasks
+    pub fn completion_summary(&self) -> CompletionSummary {
+        use crate::task::TaskStatus;
+
+        let mut summary = CompletionSummary::default();
+        for task in &self.subtasks {
+            match task.status {
+                TaskStatus::Completed => summary.completed += 1,
+                TaskStatus::Failed => summary.failed.push(task.id.clone()),
+                TaskStatus::Blocked => summary.blocked.push(task.id.clone()),
+                TaskStatus::Cancelled if task.is_skipped() => summary.skipped.push(task.id.clone()),
+                _ => summary.remaining += 1,
+            }
+        }
+
+        summary
+    }
+
     /// Get progress percentage
     pub fn get_progress(&self) -> f32 {
         if self.subtasks.is_empty() {
@@ -102,6 +506,91 @@ impl CompositeTask {
     }
 }
 
+/// Longest dependency chain of `tasks` weighted by estimated minutes
+///
+/// Subtasks without an estimate weigh `DEFAULT_TASK_MINUTES`; dependencies
+/// outside `tasks` are ignored. Returns the task IDs in execution order and
+/// the chain's total minutes.
+pub fn critical_path(tasks: &[Task]) -> (Vec<String>, u32) {
+    // Minutes until each task can finish, and the dependency it waits on longest
+    let mut finish: HashMap<String, (u32, Option<String>)> = HashMap::new();
+
+    // Batches list every task after its dependencies (tasks in a cycle are left out)
+    let batches = CompositeTask::new(String::new(), String::new(), tasks.to_vec()).get_parallel_batches();
+    for task in batches.iter().flatten() {
+        let mut longest: Option<(&String, u32)> = None;
+        for dependency in &task.dependencies {
+            if let Some((minutes, _)) = finish.get(dependency) {
+                if longest.is_none_or(|(_, max)| *minutes > max) {
+                    longest = Some((dependency, *minutes));
+                }
+            }
+        }
+
+        let weight = task.estimated_minutes.unwrap_or(DEFAULT_TASK_MINUTES);
+        let start = longest.map_or(0, |(_, minutes)| minutes);
+        let waits_on = longest.map(|(id, _)| id.clone());
+        finish.insert(task.id.clone(), (start + weight, waits_on));
+    }
+
+    let mut end: Option<(&String, u32)> = None;
+    for task in tasks {
+        if let Some((minutes, _)) = finish.get(&task.id) {
+            if end.is_none_or(|(_, max)| *minutes > max) {
+                end = Some((&task.id, *minutes));
+            }
+        }
+    }
+    let Some((last, minutes)) = end else {
+        return (Vec::new(), 0);
+    };
+
+    let mut path = vec![last.clone()];
+    while let Some((_, Some(previous))) = finish.get(path.last().unwrap()) {
+        path.push(previous.clone());
+    }
+    path.reverse();
+
+    (path, minutes)
+}
+
+/// First cycle in a dependency graph of (node, dependencies)
+///
+/// The cycle lists each node followed by the one it depends on, with the first
+/// node repeated at the end. Dependencies outside the graph are ignored.
+pub(crate) fn find_cycle(graph: &[(&str, &[String])]) -> Option<Vec<String>> {
+    fn visit<'a>(
+        node: &'a str,
+        edges: &HashMap<&'a str, &'a [String]>,
+        done: &mut HashSet<&'a str>,
+        path: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = path.iter().position(|n| *n == node) {
+            return Some(path[start..].iter().chain([&node]).map(|n| n.to_string()).collect());
+        }
+        if done.contains(node) {
+            return None;
+        }
+        let dependencies = edges.get(node)?;
+
+        path.push(node);
+        for dependency in dependencies.iter() {
+            if let Some(cycle) = visit(dependency, edges, done, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.insert(node);
+        None
+    }
+
+    let edges: HashMap<&str, &[String]> = graph.iter().copied().collect();
+    let mut done = HashSet::new();
+    graph
+        .iter()
+        .find_map(|(node, _)| visit(node, &edges, &mut done, &mut Vec::new()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,6 +614,123 @@ mod tests {
         assert!(!composite.auto_approve);
     }
 
+    #[test]
+    fn test_batch_progress() {
+        let task_a = Task::new("A".to_string(), "".to_string(), "".to_string());
+        let task_b = Task::new("B".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![task_a.id.clone()]);
+        let task_c = Task::new("C".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![task_b.id.clone()]);
+
+        let mut composite = CompositeTask::new("Test".to_string(), "".to_string(), vec![task_a, task_b, task_c])
+            .with_review_required(true);
+        let statuses = |c: &CompositeTask| c.batch_progress().iter().map(|b| b.status).collect::<Vec<_>>();
+        assert_eq!(
+            statuses(&composite),
+            vec![BatchStatus::AwaitingApproval, BatchStatus::Pending, BatchStatus::Pending]
+        );
+
+        composite.review_state = ReviewState::Approved;
+        composite.subtasks[0].status = TaskStatus::Completed;
+        assert_eq!(
+            statuses(&composite),
+            vec![BatchStatus::Completed, BatchStatus::AwaitingApproval, BatchStatus::Pending]
+        );
+
+        composite.approved_batches.push(2);
+        composite.subtasks[1].status = TaskStatus::InProgress;
+        assert_eq!(
+            statuses(&composite),
+            vec![BatchStatus::Completed, BatchStatus::Running, BatchStatus::Pending]
+        );
+
+        composite.subtasks[1].status = TaskStatus::Failed;
+        composite.subtasks[2].status = TaskStatus::Blocked;
+        composite.subtasks[2].blocked_by = Some(composite.subtasks[1].id.clone());
+        let progress = composite.batch_progress();
+        assert_eq!(progress[1].status, BatchStatus::Failed);
+        assert_eq!(progress[1].task_ids, vec![composite.subtasks[1].id.clone()]);
+        assert_eq!(progress[2].status, BatchStatus::Blocked);
+    }
+
+    #[test]
+    fn test_completion_summary() {
+        let mut tasks = vec![
+            Task::new("A".to_string(), "".to_string(), "".to_string()),
+            Task::new("B".to_string(), "".to_string(), "".to_string()),
+            Task::new("C".to_string(), "".to_string(), "".to_string()),
+            Task::new("D".to_string(), "".to_string(), "".to_string()),
+        ];
+        tasks[0].status = TaskStatus::Completed;
+        tasks[1].status = TaskStatus::Failed;
+        tasks[2].status = TaskStatus::Cancelled;
+        tasks[2].blocked_by = Some(tasks[1].id.clone());
+
+        let mut composite = CompositeTask::new("Test".to_string(), "".to_string(), tasks)
+            .with_failure_policy(FailurePolicy::Skip);
+        let summary = composite.completion_summary();
+        assert_eq!(summary.completed, 1);
+        assert_eq!(summary.failed, vec![composite.subtasks[1].id.clone()]);
+        assert_eq!(summary.skipped, vec![composite.subtasks[2].id.clone()]);
+        assert_eq!(summary.remaining, 1);
+        assert!(!summary.is_partial());
+
+        composite.subtasks[3].status = TaskStatus::Completed;
+        assert!(composite.completion_summary().is_partial());
+
+        assert_eq!("run_anyway".parse::<FailurePolicy>().unwrap(), FailurePolicy::RunAnyway);
+        assert_eq!(FailurePolicy::default().to_string(), "block");
+    }
+
+    #[test]
+    fn test_estimated_minutes() {
+        let mut task_a = Task::new("A".to_string(), "".to_string(), "".to_string());
+        task_a.estimated_minutes = Some(30);
+        let mut task_b = Task::new("B".to_string(), "".to_string(), "".to_string());
+        task_b.estimated_minutes = Some(10);
+        let mut task_c = Task::new("C".to_string(), "".to_string(), "".to_string());
+        task_c.dependencies = vec![task_a.id.clone()];
+        task_c.estimated_minutes = Some(20);
+
+        let composite = CompositeTask::new("Test".to_string(), "".to_string(), vec![task_a, task_b, task_c]);
+
+        // max(30, 10) + 20
+        assert_eq!(composite.estimated_minutes(), Some(50));
+        assert!(!composite.is_pending_review());
+
+        let unestimated = CompositeTask::new(
+            "Test".to_string(),
+            "".to_string(),
+            vec![Task::new("A".to_string(), "".to_string(), "".to_string())],
+        );
+        assert_eq!(unestimated.estimated_minutes(), None);
+        assert_eq!(unestimated.estimate_total_time(600), 600);
+    }
+
+    #[test]
+    fn test_critical_path() {
+        let mut task_a = Task::new("A".to_string(), "".to_string(), "".to_string());
+        task_a.estimated_minutes = Some(10);
+        let mut task_b = Task::new("B".to_string(), "".to_string(), "".to_string());
+        task_b.estimated_minutes = Some(60);
+        let mut task_c = Task::new("C".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![task_a.id.clone()]);
+        task_c.estimated_minutes = Some(20);
+        // Unestimated: DEFAULT_TASK_MINUTES
+        let task_d = Task::new("D".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![task_b.id.clone(), task_c.id.clone()]);
+        let ids: Vec<String> = [&task_a, &task_b, &task_c, &task_d].iter().map(|t| t.id.clone()).collect();
+
+        let composite = CompositeTask::new("Test".to_string(), "".to_string(), vec![task_a, task_b, task_c, task_d]);
+
+        // B outweighs the longer chain A → C
+        assert_eq!(composite.critical_path(), (vec![ids[1].clone(), ids[3].clone()], 60 + DEFAULT_TASK_MINUTES));
+        // Batches: max(10, 60) + 20 + 30
+        assert_eq!(composite.estimate_total_time(u64::from(DEFAULT_TASK_MINUTES) * 60), 110 * 60);
+
+        assert_eq!(critical_path(&[]), (Vec::new(), 0));
+    }
+
     #[test]
     fn test_parallel_batches() {
         let task_a = Task::new("A".to_string(), "".to_string(), "".to_string());
@@ -145,6 +751,25 @@ mod tests {
         assert_eq!(batches[1].len(), 2); // B and C
     }
 
+    #[test]
+    fn test_find_dependency_cycle() {
+        let task_a = Task::new("A".to_string(), "".to_string(), "".to_string());
+        let task_b = Task::new("B".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![task_a.id.clone(), "outside".to_string()]);
+        let task_c = Task::new("C".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![task_b.id.clone()]);
+        let (a, b, c) = (task_a.id.clone(), task_b.id.clone(), task_c.id.clone());
+
+        let mut composite = CompositeTask::new("Test".to_string(), "".to_string(), vec![task_a, task_b, task_c]);
+        assert_eq!(composite.find_dependency_cycle(), None);
+
+        composite.subtasks[0].dependencies = vec![c.clone()];
+        assert_eq!(composite.find_dependency_cycle(), Some(vec![a.clone(), c, b, a.clone()]));
+
+        composite.subtasks[0].dependencies = vec![a.clone()];
+        assert_eq!(composite.find_dependency_cycle(), Some(vec![a.clone(), a]));
+    }
+
     #[test]
     fn test_progress_calculation() {
         let mut tasks = vec![
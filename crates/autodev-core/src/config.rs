@@ -0,0 +1,354 @@
+//! Configuration file (`~/.config/autodev/config.toml`)
+//!
+//! Settings are layered: the file, then the environment (and `.env`), then
+//! command line flags. The file is applied by exporting its values as the
+//! environment variables every component already reads, for the variables
+//! that are not set yet. Profiles (`[profiles.<name>]`) override the top-level
+//! settings and are selected with `--profile` or `AUTODEV_PROFILE`.
+//!
+//! ```toml
+//! github_token = "github_pat_..."
+//! ai_agent_type = "claude-code"
+//!
+//! [env]
+//! AUTODEV_MAX_PARALLEL_TASKS = "3"
+//!
+//! [profiles.work]
+//! database_url = "postgresql://autodev@db.internal/autodev"
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::{Error, Result};
+
+/// Overrides the path of the configuration file
+pub const CONFIG_PATH_ENV: &str = "AUTODEV_CONFIG";
+
+/// Selects the profile when `--profile` is not given
+pub const PROFILE_ENV: &str = "AUTODEV_PROFILE";
+
+/// Settings keys and the environment variables they are exported as
+pub const KEYS: &[(&str, &str)] = &[
+    ("github_token", "GITHUB_TOKEN"),
+    ("database_url", "DATABASE_URL"),
+    ("ai_agent_type", "AI_AGENT_TYPE"),
+    ("ai_model", "AI_MODEL"),
+    ("anthropic_api_key", "ANTHROPIC_API_KEY"),
+    ("claude_code_oauth_token", "CLAUDE_CODE_OAUTH_TOKEN"),
+    ("openai_api_key", "OPENAI_API_KEY"),
+    ("server_url", "AUTODEV_SERVER_URL"),
+    ("workspace_dir", "AUTODEV_WORKSPACE_DIR"),
+    ("local_executor", "AUTODEV_LOCAL_EXECUTOR"),
+    ("api_port", "API_PORT"),
+];
+
+/// Settings shared by the cli, api and worker
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub github_token: Option<String>,
+    pub database_url: Option<String>,
+    pub ai_agent_type: Option<String>,
+    pub ai_model: Option<String>,
+    pub anthropic_api_key: Option<String>,
+    pub claude_code_oauth_token: Option<String>,
+    pub openai_api_key: Option<String>,
+    pub server_url: Option<String>,
+    pub workspace_dir: Option<String>,
+    pub local_executor: Option<bool>,
+    pub api_port: Option<u16>,
+    /// Any other environment variable, e.g. `AUTODEV_BUDGET_TASK_USD`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Value of a key from [`KEYS`] or `env.<VARIABLE>`
+    pub fn get(&self, key: &str) -> Option<String> {
+        match key {
+            "github_token" => self.github_token.clone(),
+            "database_url" => self.database_url.clone(),
+            "ai_agent_type" => self.ai_agent_type.clone(),
+            "ai_model" => self.ai_model.clone(),
+            "anthropic_api_key" => self.anthropic_api_key.clone(),
+            "claude_code_oauth_token" => self.claude_code_oauth_token.clone(),
+            "openai_api_key" => self.openai_api_key.clone(),
+            "server_url" => self.server_url.clone(),
+            "workspace_dir" => self.workspace_dir.clone(),
+            "local_executor" => self.local_executor.map(|v| v.to_string()),
+            "api_port" => self.api_port.map(|v| v.to_string()),
+            _ => self.env.get(key.strip_prefix("env.")?).cloned(),
+        }
+    }
+
+    /// Set a key from [`KEYS`] or `env.<VARIABLE>`, checking the value's type
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let invalid = |expected: &str| Error::Config(format!("{} must be {}, got '{}'", key, expected, value));
+        let text = Some(value.to_string());
+
+        match key {
+            "github_token" => self.github_token = text,
+            "database_url" => self.database_url = text,
+            "ai_agent_type" => self.ai_agent_type = text,
+            "ai_model" => self.ai_model = text,
+            "anthropic_api_key" => self.anthropic_api_key = text,
+            "claude_code_oauth_token" => self.claude_code_oauth_token = text,
+            "openai_api_key" => self.openai_api_key = text,
+            "server_url" => self.server_url = text,
+            "workspace_dir" => self.workspace_dir = text,
+            "local_executor" => self.local_executor = Some(value.parse().map_err(|_| invalid("true or false"))?),
+            "api_port" => self.api_port = Some(value.parse().map_err(|_| invalid("a port number"))?),
+            _ => match key.strip_prefix("env.") {
+                Some(name) if !name.is_empty() => {
+                    self.env.insert(name.to_string(), value.to_string());
+                }
+                _ => {
+                    let keys: Vec<&str> = KEYS.iter().map(|(key, _)| *key).collect();
+                    return Err(Error::Config(format!(
+                        "Unknown key '{}' (expected one of {}, or env.<VARIABLE>)",
+                        key,
+                        keys.join(", ")
+                    )));
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// `self` with the settings of `other` on top
+    pub fn merged(&self, other: &Config) -> Config {
+        let mut env = self.env.clone();
+        env.extend(other.env.clone());
+
+        Config {
+            github_token: other.github_token.clone().or_else(|| self.github_token.clone()),
+            database_url: other.database_url.clone().or_else(|| self.database_url.clone()),
+            ai_agent_type: other.ai_agent_type.clone().or_else(|| self.ai_agent_type.clone()),
+            ai_model: other.ai_model.clone().or_else(|| self.ai_model.clone()),
+            anthropic_api_key: other.anthropic_api_key.clone().or_else(|| self.anthropic_api_key.clone()),
+            claude_code_oauth_token: other
+                .claude_code_oauth_token
+                .clone()
+                .or_else(|| self.claude_code_oauth_token.clone()),
+            openai_api_key: other.openai_api_key.clone().or_else(|| self.openai_api_key.clone()),
+            server_url: other.server_url.clone().or_else(|| self.server_url.clone()),
+            workspace_dir: other.workspace_dir.clone().or_else(|| self.workspace_dir.clone()),
+            local_executor: other.local_executor.or(self.local_executor),
+            api_port: other.api_port.or(self.api_port),
+            env,
+        }
+    }
+
+    /// Environment variables and values of the settings that are set
+    pub fn vars(&self) -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = KEYS
+            .iter()
+            .filter_map(|(key, name)| Some((name.to_string(), self.get(key)?)))
+            .collect();
+        vars.extend(self.env.iter().map(|(name, value)| (name.clone(), value.clone())));
+        vars
+    }
+
+    /// Settings read from the current environment (for `autodev config init`)
+    pub fn from_env() -> Config {
+        let mut config = Config::default();
+        for (key, name) in KEYS {
+            if let Ok(value) = std::env::var(name) {
+                // 형식이 잘못된 값은 파일에 옮기지 않음
+                let _ = config.set(key, &value);
+            }
+        }
+        config
+    }
+
+    /// Export the settings as environment variables that are not set yet, so
+    /// the environment and the flags reading it take precedence
+    pub fn apply_to_env(&self) {
+        for (name, value) in self.vars() {
+            if std::env::var_os(&name).is_none() {
+                std::env::set_var(name, value);
+            }
+        }
+    }
+}
+
+/// Contents of the configuration file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigFile {
+    #[serde(flatten)]
+    pub config: Config,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Config>,
+}
+
+impl ConfigFile {
+    /// `$AUTODEV_CONFIG`, else `$XDG_CONFIG_HOME/autodev/config.toml`, else
+    /// `~/.config/autodev/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os(CONFIG_PATH_ENV) {
+            return Some(PathBuf::from(path));
+        }
+
+        let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(config_dir.join("autodev").join("config.toml"))
+    }
+
+    /// Read the file; a missing file is empty
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::Config(format!("Failed to read {}: {}", path.display(), e))),
+        };
+
+        Self::parse(&content).map_err(|e| Error::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|e| Error::Config(e.to_string()))
+    }
+
+    /// Write the file, creating its directory; readable by the owner only as it
+    /// holds tokens
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self).map_err(|e| Error::Config(e.to_string()))?;
+        let write = || -> std::io::Result<()> {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, content)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+            }
+            Ok(())
+        };
+
+        write().map_err(|e| Error::Config(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// Settings of `profile` (the top-level settings when None)
+    pub fn resolve(&self, profile: Option<&str>) -> Result<Config> {
+        let Some(name) = profile else {
+            return Ok(self.config.clone());
+        };
+
+        match self.profiles.get(name) {
+            Some(overrides) => Ok(self.config.merged(overrides)),
+            None => {
+                let names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                Err(Error::Config(format!(
+                    "Unknown profile '{}' (available: {})",
+                    name,
+                    if names.is_empty() { "none".to_string() } else { names.join(", ") }
+                )))
+            }
+        }
+    }
+
+    /// Settings of `profile`, creating the profile when missing
+    pub fn section_mut(&mut self, profile: Option<&str>) -> &mut Config {
+        match profile {
+            Some(name) => self.profiles.entry(name.to_string()).or_default(),
+            None => &mut self.config,
+        }
+    }
+}
+
+/// Apply the configuration file to the environment
+///
+/// `profile` falls back to `AUTODEV_PROFILE`. Returns the path of the file
+/// that was read, None when there is none.
+pub fn load_into_env(profile: Option<&str>) -> Result<Option<PathBuf>> {
+    let profile = profile.map(str::to_string).or_else(|| std::env::var(PROFILE_ENV).ok());
+
+    let Some(path) = ConfigFile::default_path() else {
+        return Ok(None);
+    };
+    if !path.exists() {
+        // 없는 프로파일을 고른 경우는 알려야 함
+        if let Some(profile) = profile {
+            return Err(Error::Config(format!(
+                "Profile '{}' selected but {} does not exist",
+                profile,
+                path.display()
+            )));
+        }
+        return Ok(None);
+    }
+
+    ConfigFile::load(&path)?.resolve(profile.as_deref())?.apply_to_env();
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_override_top_level() {
+        let file = ConfigFile::parse(
+            r#"
+            github_token = "base-token"
+            ai_agent_type = "openai"
+            local_executor = true
+
+            [env]
+            AUTODEV_MAX_PARALLEL_TASKS = "3"
+
+            [profiles.work]
+            github_token = "work-token"
+            api_port = 4000
+
+            [profiles.work.env]
+            AUTODEV_ROLLBACK_ON_FAILURE = "true"
+            "#,
+        )
+        .unwrap();
+
+        let base = file.resolve(None).unwrap();
+        assert_eq!(base.github_token.as_deref(), Some("base-token"));
+        assert_eq!(base.api_port, None);
+
+        let work = file.resolve(Some("work")).unwrap();
+        assert_eq!(work.github_token.as_deref(), Some("work-token"));
+        assert_eq!(work.ai_agent_type.as_deref(), Some("openai"));
+        assert_eq!(
+            work.vars(),
+            [
+                ("GITHUB_TOKEN", "work-token"),
+                ("AI_AGENT_TYPE", "openai"),
+                ("AUTODEV_LOCAL_EXECUTOR", "true"),
+                ("API_PORT", "4000"),
+                ("AUTODEV_MAX_PARALLEL_TASKS", "3"),
+                ("AUTODEV_ROLLBACK_ON_FAILURE", "true"),
+            ]
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+        );
+
+        assert!(matches!(file.resolve(Some("home")), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_set_round_trip() {
+        let mut file = ConfigFile::default();
+        file.section_mut(None).set("database_url", "postgresql://localhost/autodev").unwrap();
+        file.section_mut(Some("ci")).set("local_executor", "false").unwrap();
+        file.section_mut(Some("ci")).set("env.AUTODEV_EXECUTOR", "process").unwrap();
+
+        assert!(file.section_mut(None).set("api_port", "http").is_err());
+        assert!(file.section_mut(None).set("github", "x").is_err());
+
+        let saved = toml::to_string_pretty(&file).unwrap();
+        let loaded = ConfigFile::parse(&saved).unwrap();
+        assert_eq!(loaded, file);
+        assert_eq!(loaded.resolve(Some("ci")).unwrap().get("env.AUTODEV_EXECUTOR").as_deref(), Some("process"));
+    }
+}
@@ -0,0 +1,230 @@
+//! Cron expressions for recurring tasks
+//!
+//! Standard five fields (`minute hour day-of-month month day-of-week`) with
+//! `*`, lists (`1,15`), ranges (`1-5`), steps (`*/15`, `0-30/10`), month and
+//! weekday names (`jan`, `mon`) and the `@hourly`, `@daily`, `@weekly`,
+//! `@monthly`, `@yearly` shortcuts. Times are in UTC. As in cron, when both
+//! day fields are restricted a day matching either one is due.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Error, Result};
+
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// How far ahead the next run is searched for (covers every Feb 29)
+const SEARCH_YEARS: i64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    /// Bit n set: value n matches
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the day fields were `*` (decides how the two are combined)
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    /// First run strictly after `after`, None when the expression never matches
+    /// (e.g. `0 0 30 2 *`)
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date_naive();
+        let limit = date + Duration::days(366 * SEARCH_YEARS);
+
+        while date <= limit {
+            if !has(self.months, date.month()) {
+                date = first_of_next_month(date)?;
+                continue;
+            }
+            if !self.day_matches(date) {
+                date = date.succ_opt()?;
+                continue;
+            }
+
+            // The starting day only from the starting time on
+            let from = if date == start.date_naive() { start.time() } else { NaiveTime::MIN };
+            if let Some(time) = self.first_time_from(from) {
+                return Some(date.and_time(time).and_utc());
+            }
+            date = date.succ_opt()?;
+        }
+
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    fn first_time_from(&self, from: NaiveTime) -> Option<NaiveTime> {
+        (from.hour()..24).filter(|hour| has(self.hours, *hour)).find_map(|hour| {
+            let first_minute = if hour == from.hour() { from.minute() } else { 0 };
+            (first_minute..60)
+                .find(|minute| has(self.minutes, *minute))
+                .and_then(|minute| NaiveTime::from_hms_opt(hour, minute, 0))
+        })
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let expression = s.trim();
+        let expanded = match expression.to_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ => expression,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(Error::InvalidCron(format!(
+                "'{}': expected 5 fields (minute hour day month weekday), got {}",
+                expression,
+                fields.len()
+            )));
+        };
+
+        let invalid = |e: String| Error::InvalidCron(format!("'{}': {}", expression, e));
+        // 일요일은 0과 7 둘 다 허용
+        let weekdays = parse_field(weekday, 0, 7, &WEEKDAYS).map_err(invalid)?;
+
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse_field(minute, 0, 59, &[]).map_err(invalid)?,
+            hours: parse_field(hour, 0, 23, &[]).map_err(invalid)?,
+            days: parse_field(day, 1, 31, &[]).map_err(invalid)?,
+            months: parse_field(month, 1, 12, &MONTHS).map_err(invalid)?,
+            weekdays: (weekdays | (weekdays >> 7)) & 0x7f,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDate> {
+    match date.month() {
+        12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+        month => NaiveDate::from_ymd_opt(date.year(), month + 1, 1),
+    }
+}
+
+/// Values of one field as a bit set; `names` start at `min`
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> std::result::Result<u64, String> {
+    let value = |s: &str| -> std::result::Result<u32, String> {
+        let value = match names.iter().position(|name| name.eq_ignore_ascii_case(s)) {
+            Some(index) => index as u32 + min,
+            None => s.parse().map_err(|_| format!("invalid value '{}'", s))?,
+        };
+        if value < min || value > max {
+            return Err(format!("{} is out of range {}-{}", value, min, max));
+        }
+        Ok(value)
+    };
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("invalid step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be positive".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/10`: from 5 to the end of the range
+                None if step > 1 => (value(range)?, max),
+                None => (value(range)?, value(range)?),
+            },
+        };
+        if start > end {
+            return Err(format!("invalid range '{}'", range));
+        }
+
+        for v in (start..=end).step_by(step as usize) {
+            set |= 1 << v;
+        }
+    }
+
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn next(expression: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        expression.parse::<CronSchedule>().unwrap().next_after(after)
+    }
+
+    #[test]
+    fn test_next_after() {
+        // 2026-10-18 is a Sunday
+        let now = at(2026, 10, 18, 14, 30);
+
+        assert_eq!(next("0 3 * * *", now), Some(at(2026, 10, 19, 3, 0)));
+        assert_eq!(next("*/15 * * * *", now), Some(at(2026, 10, 18, 14, 45)));
+        assert_eq!(next("30 14 * * *", now), Some(at(2026, 10, 19, 14, 30)));
+        assert_eq!(next("0 9 * * mon-fri", now), Some(at(2026, 10, 19, 9, 0)));
+        assert_eq!(next("0 0 * * 7", now), Some(at(2026, 10, 25, 0, 0)));
+        assert_eq!(next("@monthly", now), Some(at(2026, 11, 1, 0, 0)));
+        assert_eq!(next("0 12 1 jan *", now), Some(at(2027, 1, 1, 12, 0)));
+        assert_eq!(next("0 0 29 2 *", now), Some(at(2028, 2, 29, 0, 0)));
+        assert_eq!(next("0 0 30 2 *", now), None);
+
+        // Either day field matches when both are restricted
+        assert_eq!(next("0 0 1 * mon", now), Some(at(2026, 10, 19, 0, 0)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for expression in ["", "* * * *", "60 * * * *", "* * 0 * *", "*/0 * * * *", "5-1 * * * *", "* * * foo *"] {
+            assert!(
+                matches!(expression.parse::<CronSchedule>(), Err(Error::InvalidCron(_))),
+                "{:?}",
+                expression
+            );
+        }
+    }
+}
@@ -1,13 +1,19 @@
-use crate::{CompositeTask, Result, Task, TaskStatus};
+use crate::{
+    events::{TaskEvent, EVENT_CHANNEL_CAPACITY},
+    store::TaskStore,
+    CompositeTask, Error, FailureKind, FailurePolicy, Result, ReviewState, Task, TaskStatus,
+};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 #[derive(Clone)]
 pub struct AutoDevEngine {
     pub active_tasks: Arc<RwLock<HashMap<String, Task>>>,
     pub completed_tasks: Arc<RwLock<HashSet<String>>>,
     pub composite_tasks: Arc<RwLock<HashMap<String, CompositeTask>>>,
+    store: Option<Arc<dyn TaskStore>>,
+    events: broadcast::Sender<TaskEvent>,
 }
 
 impl AutoDevEngine {
@@ -16,9 +22,111 @@ impl AutoDevEngine {
             active_tasks: Arc::new(RwLock::new(HashMap::new())),
             completed_tasks: Arc::new(RwLock::new(HashSet::new())),
             composite_tasks: Arc::new(RwLock::new(HashMap::new())),
+            store: None,
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
         }
     }
 
+    /// Create an engine that writes every mutation through to a persistent store
+    ///
+    /// Call `hydrate()` afterwards to restore state from a previous run.
+    pub fn with_store(store: Arc<dyn TaskStore>) -> Self {
+        Self {
+            store: Some(store),
+            ..Self::new()
+        }
+    }
+
+    /// Restore in-memory state from the persistent store
+    pub async fn hydrate(&self) -> Result<()> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+
+        let snapshot = store.load_snapshot().await?;
+
+        let mut tasks = self.active_tasks.write().await;
+        let mut completed = self.completed_tasks.write().await;
+        let mut composites = self.composite_tasks.write().await;
+
+        for task in snapshot.tasks {
+            if task.status == TaskStatus::Completed {
+                completed.insert(task.id.clone());
+            }
+            tasks.insert(task.id.clone(), task);
+        }
+
+        for composite_task in snapshot.composite_tasks {
+            composites.insert(composite_task.id.clone(), composite_task);
+        }
+
+        tracing::info!(
+            "Hydrated engine state: {} tasks ({} completed), {} composite tasks",
+            tasks.len(),
+            completed.len(),
+            composites.len()
+        );
+
+        Ok(())
+    }
+
+    /// Write a task through to the store (errors are logged, not propagated)
+    async fn persist_task(&self, task: &Task) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.persist_task(task).await {
+                tracing::error!("Failed to persist task {}: {}", task.id, e);
+            }
+        }
+    }
+
+    /// Write a composite task through to the store (errors are logged, not propagated)
+    async fn persist_composite_task(&self, composite_task: &CompositeTask) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.persist_composite_task(composite_task).await {
+                tracing::error!("Failed to persist composite task {}: {}", composite_task.id, e);
+            }
+        }
+    }
+
+    /// Subscribe to live task events (status transitions, logs, PR URLs)
+    pub fn subscribe(&self) -> broadcast::Receiver<TaskEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publish an event to current subscribers (dropped when nobody listens)
+    pub fn publish(&self, event: TaskEvent) {
+        let _ = self.events.send(event);
+    }
+
+    fn publish_status(&self, task: &Task) {
+        self.publish(TaskEvent::StatusChanged {
+            task_id: task.id.clone(),
+            status: task.status,
+            error: task.error.clone(),
+            attempts: task.attempts,
+            timestamp: chrono::Utc::now(),
+        });
+    }
+
+    fn publish_created(&self, task: &Task, composite_task_id: Option<&str>) {
+        self.publish(TaskEvent::TaskCreated {
+            task_id: task.id.clone(),
+            title: task.title.clone(),
+            composite_task_id: composite_task_id.map(str::to_string),
+            timestamp: chrono::Utc::now(),
+        });
+    }
+
+    /// Publish an execution log entry for a task
+    pub fn log_event(&self, task_id: &str, event_type: &str, message: &str) {
+        self.publish(TaskEvent::Log {
+            task_id: task_id.to_string(),
+            event_type: event_type.to_string(),
+            message: message.to_string(),
+            timestamp: chrono::Utc::now(),
+        });
+    }
+
     /// Create a simple task
     pub async fn create_simple_task(
         &self,
@@ -26,16 +134,43 @@ impl AutoDevEngine {
         description: String,
         prompt: String,
     ) -> Result<Task> {
-        let task = Task::new(title, description, prompt);
+        self.register_task(Task::new(title, description, prompt)).await
+    }
 
+    /// Track an already built simple task (e.g. one linked to an issue)
+    pub async fn register_task(&self, task: Task) -> Result<Task> {
         let mut tasks = self.active_tasks.write().await;
         tasks.insert(task.id.clone(), task.clone());
+        drop(tasks);
+
+        self.persist_task(&task).await;
+        self.publish_created(&task, None);
 
         tracing::info!("Created simple task: {} ({})", task.title, task.id);
 
         Ok(task)
     }
 
+    /// Oldest unfinished task of `owner/name` with the same prompt (see
+    /// [`crate::normalize_prompt`])
+    ///
+    /// Tasks without a recorded repository never match.
+    pub async fn find_open_duplicate(&self, owner: &str, name: &str, prompt: &str) -> Option<Task> {
+        let prompt = crate::normalize_prompt(prompt);
+        let tasks = self.active_tasks.read().await;
+
+        tasks
+            .values()
+            .filter(|task| {
+                !task.status.is_finished()
+                    && task.repository_owner.as_deref() == Some(owner)
+                    && task.repository_name.as_deref() == Some(name)
+                    && crate::normalize_prompt(&task.prompt) == prompt
+            })
+            .min_by_key(|task| task.created_at)
+            .cloned()
+    }
+
     /// Create a composite task
     pub async fn create_composite_task(
         &self,
@@ -44,18 +179,38 @@ impl AutoDevEngine {
         subtasks: Vec<Task>,
         auto_approve: bool,
     ) -> Result<CompositeTask> {
-        let composite_task = CompositeTask::new(title, description, subtasks.clone())
+        let composite_task = CompositeTask::new(title, description, subtasks)
             .with_auto_approve(auto_approve);
 
+        self.register_composite_task(composite_task).await
+    }
+
+    /// Track an already built composite task (e.g. one held for review)
+    ///
+    /// Subtasks that depend on each other in a cycle could never be scheduled;
+    /// they are rejected with `Error::DependencyCycle`.
+    pub async fn register_composite_task(&self, composite_task: CompositeTask) -> Result<CompositeTask> {
+        if let Some(cycle) = composite_task.find_dependency_cycle() {
+            return Err(Error::DependencyCycle(cycle));
+        }
+
         // Add subtasks to active tasks
         let mut tasks = self.active_tasks.write().await;
-        for task in &subtasks {
+        for task in &composite_task.subtasks {
             tasks.insert(task.id.clone(), task.clone());
         }
 
+        drop(tasks);
+
         // Store composite task
         let mut composites = self.composite_tasks.write().await;
         composites.insert(composite_task.id.clone(), composite_task.clone());
+        drop(composites);
+
+        self.persist_composite_task(&composite_task).await;
+        for task in &composite_task.subtasks {
+            self.publish_created(task, Some(&composite_task.id));
+        }
 
         tracing::info!(
             "Created composite task: {} ({}) with {} subtasks",
@@ -75,6 +230,9 @@ impl AutoDevEngine {
     }
 
     /// Update task status
+    ///
+    /// Illegal transitions (e.g. Completed → InProgress) are rejected with
+    /// `Error::InvalidTransition`; unknown tasks are ignored.
     pub async fn update_task_status(
         &self,
         task_id: &str,
@@ -83,23 +241,785 @@ impl AutoDevEngine {
     ) -> Result<()> {
         let mut tasks = self.active_tasks.write().await;
 
-        if let Some(task) = tasks.get_mut(task_id) {
-            task.status = status;
-            if let Some(err) = error {
-                task.error = Some(err);
-            }
+        let updated = if let Some(task) = tasks.get_mut(task_id) {
+            task.transition_to(status)?;
+            if let Some(err) = error {
+                task.error = Some(err);
+            }
+
+            if status == TaskStatus::Completed {
+                let mut completed = self.completed_tasks.write().await;
+                completed.insert(task_id.to_string());
+
+                tracing::info!("Task completed: {} ({})", task.title, task_id);
+            } else if status == TaskStatus::Failed {
+                tracing::error!("Task failed: {} ({})", task.title, task_id);
+            }
+
+            Some(task.clone())
+        } else {
+            None
+        };
+        drop(tasks);
+
+        if let Some(task) = updated {
+            self.persist_task(&task).await;
+            self.publish_status(&task);
+        }
+
+        Ok(())
+    }
+
+    /// Cancel a task that has not finished yet
+    ///
+    /// Returns the cancelled task so callers can stop any in-flight execution
+    /// (workflow runs, containers) associated with it.
+    pub async fn cancel_task(&self, task_id: &str) -> Result<Task> {
+        let mut tasks = self.active_tasks.write().await;
+
+        let task = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?;
+
+        if task.status.is_finished() {
+            return Err(Error::InvalidTaskState(format!(
+                "Task {} is already {:?}",
+                task_id, task.status
+            )));
+        }
+
+        task.transition_to(TaskStatus::Cancelled)?;
+        let cancelled = task.clone();
+        drop(tasks);
+
+        self.persist_task(&cancelled).await;
+        self.publish_status(&cancelled);
+
+        tracing::info!("Task cancelled: {} ({})", cancelled.title, task_id);
+
+        Ok(cancelled)
+    }
+
+    /// Mark an in-progress task as interrupted by a shutdown
+    ///
+    /// Returns false when the task is not in progress (it finished meanwhile).
+    pub async fn interrupt_task(&self, task_id: &str) -> Result<bool> {
+        let mut tasks = self.active_tasks.write().await;
+
+        let task = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?;
+
+        if task.status != TaskStatus::InProgress {
+            return Ok(false);
+        }
+
+        task.transition_to(TaskStatus::Interrupted)?;
+        task.error = Some("Interrupted by shutdown".to_string());
+        let interrupted = task.clone();
+        drop(tasks);
+
+        self.persist_task(&interrupted).await;
+        self.publish_status(&interrupted);
+
+        tracing::warn!("Task interrupted: {} ({})", interrupted.title, task_id);
+
+        Ok(true)
+    }
+
+    /// Pause a task whose AI budget ran out
+    pub async fn pause_for_budget(&self, task_id: &str, reason: &str) -> Result<Task> {
+        let mut tasks = self.active_tasks.write().await;
+
+        let task = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?;
+
+        task.transition_to(TaskStatus::BudgetExceeded)?;
+        task.error = Some(reason.to_string());
+        let paused = task.clone();
+        drop(tasks);
+
+        self.persist_task(&paused).await;
+        self.publish_status(&paused);
+
+        tracing::warn!("Task paused, {}: {} ({})", reason, paused.title, task_id);
+
+        Ok(paused)
+    }
+
+    /// Move every interrupted task back to Pending so it runs again
+    ///
+    /// Returns the IDs of the requeued tasks.
+    pub async fn requeue_interrupted(&self) -> Vec<String> {
+        let mut tasks = self.active_tasks.write().await;
+
+        let requeued: Vec<Task> = tasks
+            .values_mut()
+            .filter(|task| task.status == TaskStatus::Interrupted)
+            .filter_map(|task| task.transition_to(TaskStatus::Pending).ok().map(|_| task.clone()))
+            .collect();
+        drop(tasks);
+
+        for task in &requeued {
+            self.persist_task(task).await;
+            self.publish_status(task);
+            tracing::info!("Requeued interrupted task: {} ({})", task.title, task.id);
+        }
+
+        requeued.into_iter().map(|task| task.id).collect()
+    }
+
+    /// Backoff before retrying a failed task, if its retry policy allows it
+    ///
+    /// Returns None when the task has exhausted its attempts or `kind` is not retryable.
+    pub async fn retry_backoff(
+        &self,
+        task_id: &str,
+        kind: FailureKind,
+    ) -> Result<Option<std::time::Duration>> {
+        let tasks = self.active_tasks.read().await;
+
+        let task = tasks
+            .get(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?;
+
+        if task.status != TaskStatus::Failed {
+            return Err(Error::InvalidTaskState(format!(
+                "Only failed tasks can be retried, {} is {:?}",
+                task_id, task.status
+            )));
+        }
+
+        if !task.retry_policy.should_retry(task.attempts, kind) {
+            return Ok(None);
+        }
+
+        Ok(Some(task.retry_policy.backoff(task.attempts)))
+    }
+
+    /// Move a failed (or budget-paused) task back to Pending so it can be dispatched again
+    pub async fn reset_for_retry(&self, task_id: &str) -> Result<Task> {
+        let mut tasks = self.active_tasks.write().await;
+
+        let task = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?;
+
+        // The task may have been cancelled while waiting for the backoff
+        if !matches!(task.status, TaskStatus::Failed | TaskStatus::BudgetExceeded) {
+            return Err(Error::InvalidTaskState(format!(
+                "Only failed tasks can be retried, {} is {:?}",
+                task_id, task.status
+            )));
+        }
+
+        task.transition_to(TaskStatus::Pending)?;
+        task.workflow_run_id = None;
+        task.pr_url = None;
+        task.pr_number = None;
+        let retried = task.clone();
+
+        // Tasks blocked by this failure wait for their dependencies again
+        let mut unblocked = Vec::new();
+        for task in tasks.values_mut() {
+            if task.status == TaskStatus::Blocked && task.blocked_by.as_deref() == Some(task_id) {
+                task.transition_to(TaskStatus::WaitingDependencies)?;
+                task.blocked_by = None;
+                task.error = None;
+                unblocked.push(task.clone());
+            }
+        }
+        drop(tasks);
+
+        for task in std::iter::once(&retried).chain(&unblocked) {
+            self.persist_task(task).await;
+            self.publish_status(task);
+        }
+
+        tracing::info!(
+            "Task {} reset for retry (attempt {}/{})",
+            task_id,
+            retried.attempts + 1,
+            retried.retry_policy.max_attempts
+        );
+
+        Ok(retried)
+    }
+
+    /// Apply the failure policy to the tasks depending on a task that failed for good
+    ///
+    /// Under `Skip` the dependents are cancelled, under `Block` they wait in
+    /// Blocked until the failed task is retried; both apply transitively.
+    /// `RunAnyway` leaves them to run. Dependents that already started are not
+    /// touched. Returns the tasks that changed.
+    pub async fn propagate_failure(&self, task_id: &str) -> Result<Vec<Task>> {
+        // Same lock order as get_ready_tasks
+        let mut tasks = self.active_tasks.write().await;
+        let composites = self.composite_tasks.read().await;
+
+        let failed = tasks
+            .get(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?
+            .clone();
+        let policy = composites
+            .values()
+            .find(|c| c.subtasks.iter().any(|t| t.id == task_id))
+            .map_or_else(FailurePolicy::default, |c| c.failure_policy);
+        drop(composites);
+
+        let target = match policy {
+            FailurePolicy::Skip => TaskStatus::Cancelled,
+            FailurePolicy::Block => TaskStatus::Blocked,
+            FailurePolicy::RunAnyway => return Ok(Vec::new()),
+        };
+        let reason = format!("Dependency '{}' ({}) failed", failed.title, failed.id);
+
+        let mut changed = Vec::new();
+        let mut frontier = vec![failed.id.clone()];
+        while let Some(dependency) = frontier.pop() {
+            for task in tasks.values_mut() {
+                let waiting = matches!(
+                    task.status,
+                    TaskStatus::Pending | TaskStatus::WaitingDependencies | TaskStatus::Ready
+                );
+                if !waiting || task.blocked_by.is_some() || !task.dependencies.contains(&dependency) {
+                    continue;
+                }
+
+                task.transition_to(target)?;
+                task.blocked_by = Some(failed.id.clone());
+                task.error = Some(reason.clone());
+                frontier.push(task.id.clone());
+                changed.push(task.clone());
+            }
+        }
+        drop(tasks);
+
+        for task in &changed {
+            self.persist_task(task).await;
+            self.publish_status(task);
+        }
+
+        if !changed.is_empty() {
+            tracing::warn!(
+                "Task {} failed; {} dependent task(s) {} ({})",
+                task_id,
+                changed.len(),
+                if target == TaskStatus::Blocked { "blocked" } else { "skipped" },
+                policy
+            );
+        }
+
+        Ok(changed)
+    }
+
+    /// Replace a subtask that turned out too big with the tasks it was split into
+    ///
+    /// `replacements` only depend on each other; the ones without dependencies
+    /// take over the subtask's dependencies and the subtask's dependents wait
+    /// for the replacements nothing else depends on. The subtask must not be
+    /// running or completed; it is cancelled and leaves the composite task.
+    /// Returns the updated composite task.
+    pub async fn split_subtask(&self, task_id: &str, replacements: Vec<Task>) -> Result<CompositeTask> {
+        // Same lock order as get_ready_tasks
+        let mut tasks = self.active_tasks.write().await;
+        let mut composites = self.composite_tasks.write().await;
+
+        let mut original = tasks
+            .get(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?
+            .clone();
+        if matches!(original.status, TaskStatus::InProgress | TaskStatus::Completed) {
+            return Err(Error::InvalidTaskState(format!(
+                "Task {} is {:?} and cannot be split",
+                task_id, original.status
+            )));
+        }
+        if replacements.is_empty() {
+            return Err(Error::InvalidTaskState(format!("Task {} was split into no tasks", task_id)));
+        }
+        let composite_task = composites
+            .values_mut()
+            .find(|c| c.subtasks.iter().any(|t| t.id == task_id))
+            .ok_or_else(|| Error::InvalidTaskState(format!("Task {} is not a subtask of a composite task", task_id)))?;
+
+        let ids: HashSet<&str> = replacements.iter().map(|t| t.id.as_str()).collect();
+        let depended_on: HashSet<&str> = replacements
+            .iter()
+            .flat_map(|t| t.dependencies.iter().map(String::as_str))
+            .collect();
+        let leaves: Vec<String> = replacements
+            .iter()
+            .filter(|t| !depended_on.contains(t.id.as_str()))
+            .map(|t| t.id.clone())
+            .collect();
+
+        let mut added = Vec::new();
+        for mut task in replacements.iter().cloned() {
+            task.dependencies.retain(|dep| ids.contains(dep.as_str()));
+            if task.dependencies.is_empty() {
+                task.dependencies = original.dependencies.clone();
+            }
+            task.auto_approve = original.auto_approve;
+            task.created_by = original.created_by.clone();
+            added.push(task);
+        }
+
+        // The live state of every subtask, with the original swapped for its replacements
+        let mut subtasks = Vec::new();
+        for subtask in &composite_task.subtasks {
+            if subtask.id == task_id {
+                subtasks.extend(added.iter().cloned());
+                continue;
+            }
+
+            let mut task = tasks.get(&subtask.id).cloned().unwrap_or_else(|| subtask.clone());
+            if task.dependencies.iter().any(|dep| dep == task_id) {
+                task.dependencies.retain(|dep| dep != task_id);
+                task.dependencies.extend(leaves.iter().cloned());
+            }
+            subtasks.push(task);
+        }
+
+        let mut split = composite_task.clone();
+        split.subtasks = subtasks;
+        if let Some(cycle) = split.find_dependency_cycle() {
+            return Err(Error::DependencyCycle(cycle));
+        }
+
+        // Dependents blocked by the original wait for its replacements instead
+        let mut unblocked = Vec::new();
+        for task in &mut split.subtasks {
+            if task.status == TaskStatus::Blocked && task.blocked_by.as_deref() == Some(task_id) {
+                task.transition_to(TaskStatus::WaitingDependencies)?;
+                task.blocked_by = None;
+                task.error = None;
+                unblocked.push(task.clone());
+            }
+        }
+
+        *composite_task = split.clone();
+        tasks.remove(task_id);
+        for task in &split.subtasks {
+            tasks.insert(task.id.clone(), task.clone());
+        }
+        drop(composites);
+        drop(tasks);
+
+        // Replaced rather than moved through the state machine (it may have failed)
+        original.status = TaskStatus::Cancelled;
+        original.completed_at = Some(chrono::Utc::now());
+        original.error = Some(format!("Split into {} subtasks", added.len()));
+        self.persist_task(&original).await;
+        self.publish_status(&original);
+
+        self.persist_composite_task(&split).await;
+        for task in &added {
+            self.publish_created(task, Some(&split.id));
+        }
+        for task in &unblocked {
+            self.publish_status(task);
+        }
+
+        tracing::info!(
+            "Task {} of composite task {} split into {} subtasks",
+            task_id,
+            split.id,
+            added.len()
+        );
+
+        Ok(split)
+    }
+
+    /// Record the GitHub Actions workflow run executing a task
+    pub async fn set_workflow_run(&self, task_id: &str, run_id: u64) -> Result<()> {
+        let mut tasks = self.active_tasks.write().await;
+
+        let task = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?;
+        task.workflow_run_id = Some(run_id.to_string());
+        let updated = task.clone();
+        drop(tasks);
+
+        self.persist_task(&updated).await;
+
+        Ok(())
+    }
+
+    /// Replace the tags of a task (normalized with [`crate::normalize_tags`])
+    pub async fn set_task_tags(&self, task_id: &str, tags: &[String]) -> Result<Task> {
+        let mut tasks = self.active_tasks.write().await;
+
+        let task = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?;
+        task.tags = crate::normalize_tags(tags);
+        let updated = task.clone();
+        drop(tasks);
+
+        self.persist_task(&updated).await;
+
+        Ok(updated)
+    }
+
+    /// Approve a composite task held for review (or for a fix of its parent
+    /// branch) so it can be executed
+    pub async fn approve_composite_task(&self, composite_id: &str) -> Result<CompositeTask> {
+        let mut composites = self.composite_tasks.write().await;
+
+        let composite_task = composites
+            .get_mut(composite_id)
+            .ok_or_else(|| Error::TaskNotFound(composite_id.to_string()))?;
+
+        if matches!(composite_task.review_state, ReviewState::Approved | ReviewState::Paused) {
+            return Err(Error::InvalidTaskState(format!(
+                "Composite task {} is not pending review",
+                composite_id
+            )));
+        }
+
+        composite_task.review_state = ReviewState::Approved;
+        let approved = composite_task.clone();
+        drop(composites);
+
+        self.persist_composite_task(&approved).await;
+
+        tracing::info!("Composite task approved: {} ({})", approved.title, composite_id);
+
+        Ok(approved)
+    }
+
+    /// Hold a composite task whose parent branch failed its tests
+    ///
+    /// No subtask becomes ready until the fix is approved with
+    /// [`approve_composite_task`](Self::approve_composite_task).
+    pub async fn require_fix(&self, composite_id: &str) -> Result<CompositeTask> {
+        let mut composites = self.composite_tasks.write().await;
+
+        let composite_task = composites
+            .get_mut(composite_id)
+            .ok_or_else(|| Error::TaskNotFound(composite_id.to_string()))?;
+
+        composite_task.review_state = ReviewState::FixRequired;
+        let held = composite_task.clone();
+        drop(composites);
+
+        self.persist_composite_task(&held).await;
+
+        tracing::warn!("Composite task {} waits for a fix of its parent branch", composite_id);
+
+        Ok(held)
+    }
+
+    /// Pause an approved composite task: no further subtask starts until it is
+    /// unpaused with [`unpause_composite_task`](Self::unpause_composite_task)
+    ///
+    /// Subtasks already running are left to finish.
+    pub async fn pause_composite_task(&self, composite_id: &str) -> Result<CompositeTask> {
+        let mut composites = self.composite_tasks.write().await;
+
+        let composite_task = composites
+            .get_mut(composite_id)
+            .ok_or_else(|| Error::TaskNotFound(composite_id.to_string()))?;
+
+        if composite_task.completed_at.is_some() || composite_task.is_cancelled() {
+            return Err(Error::InvalidTaskState(format!(
+                "Composite task {} has already finished",
+                composite_id
+            )));
+        }
+        if composite_task.review_state != ReviewState::Approved {
+            return Err(Error::InvalidTaskState(format!(
+                "Composite task {} cannot be paused while {:?}",
+                composite_id, composite_task.review_state
+            )));
+        }
+
+        composite_task.review_state = ReviewState::Paused;
+        let paused = composite_task.clone();
+        drop(composites);
+
+        self.persist_composite_task(&paused).await;
+
+        tracing::info!("Composite task paused: {} ({})", paused.title, composite_id);
+
+        Ok(paused)
+    }
+
+    /// Let a paused composite task start subtasks again
+    pub async fn unpause_composite_task(&self, composite_id: &str) -> Result<CompositeTask> {
+        let mut composites = self.composite_tasks.write().await;
+
+        let composite_task = composites
+            .get_mut(composite_id)
+            .ok_or_else(|| Error::TaskNotFound(composite_id.to_string()))?;
+
+        if !composite_task.is_paused() {
+            return Err(Error::InvalidTaskState(format!(
+                "Composite task {} is not paused",
+                composite_id
+            )));
+        }
+
+        composite_task.review_state = ReviewState::Approved;
+        let unpaused = composite_task.clone();
+        drop(composites);
+
+        self.persist_composite_task(&unpaused).await;
+
+        tracing::info!("Composite task unpaused: {} ({})", unpaused.title, composite_id);
+
+        Ok(unpaused)
+    }
+
+    /// Mark a composite task with nothing left to run as completed
+    ///
+    /// Subtasks skipped or failed under a non-blocking failure policy make it a
+    /// partial completion. Completing twice is a no-op; `CompositeCompleted`
+    /// is published once.
+    pub async fn complete_composite_task(&self, composite_id: &str) -> Result<CompositeTask> {
+        let tasks = self.active_tasks.read().await;
+        let mut composites = self.composite_tasks.write().await;
+
+        let composite_task = composites
+            .get_mut(composite_id)
+            .ok_or_else(|| Error::TaskNotFound(composite_id.to_string()))?;
+
+        if composite_task.completed_at.is_some() || composite_task.is_cancelled() {
+            return Ok(composite_task.clone());
+        }
+
+        for subtask in &mut composite_task.subtasks {
+            if let Some(task) = tasks.get(&subtask.id) {
+                *subtask = task.clone();
+            }
+        }
+        composite_task.completed_at = Some(chrono::Utc::now());
+        let completed = composite_task.clone();
+        drop(composites);
+        drop(tasks);
+
+        let summary = completed.completion_summary();
+        self.persist_composite_task(&completed).await;
+        self.publish(TaskEvent::CompositeCompleted {
+            composite_task_id: completed.id.clone(),
+            title: completed.title.clone(),
+            subtasks: completed.subtasks.len(),
+            failed: summary.failed.len(),
+            skipped: summary.skipped.len(),
+            timestamp: chrono::Utc::now(),
+        });
+
+        tracing::info!("Composite task completed: {} ({})", completed.title, composite_id);
+
+        Ok(completed)
+    }
+
+    /// Mark a composite task as cancelled so no further batch starts
+    ///
+    /// Its subtasks are left as they are; callers cancel the unfinished ones
+    /// and stop whatever executes them. A cancelled composite task is never
+    /// completed, but it can be resumed.
+    pub async fn cancel_composite_task(&self, composite_id: &str) -> Result<CompositeTask> {
+        let mut composites = self.composite_tasks.write().await;
+
+        let composite_task = composites
+            .get_mut(composite_id)
+            .ok_or_else(|| Error::TaskNotFound(composite_id.to_string()))?;
+
+        if composite_task.completed_at.is_some() || composite_task.is_cancelled() {
+            return Err(Error::InvalidTaskState(format!(
+                "Composite task {} is already {}",
+                composite_id,
+                if composite_task.is_cancelled() { "cancelled" } else { "completed" }
+            )));
+        }
+
+        composite_task.cancelled_at = Some(chrono::Utc::now());
+        let cancelled = composite_task.clone();
+        drop(composites);
+
+        self.persist_composite_task(&cancelled).await;
+
+        tracing::info!("Composite task cancelled: {} ({})", cancelled.title, composite_id);
+
+        Ok(cancelled)
+    }
+
+    /// Approve batch `batch` (1-based) of a composite task to start
+    ///
+    /// Approving ahead of time is allowed; approving twice is a no-op.
+    pub async fn approve_batch(&self, composite_id: &str, batch: usize) -> Result<CompositeTask> {
+        let mut composites = self.composite_tasks.write().await;
+
+        let composite_task = composites
+            .get_mut(composite_id)
+            .ok_or_else(|| Error::TaskNotFound(composite_id.to_string()))?;
+
+        if composite_task.is_pending_review() {
+            return Err(Error::InvalidTaskState(format!(
+                "Composite task {} is pending review; approve the plan first",
+                composite_id
+            )));
+        }
+
+        let batch_count = composite_task.get_parallel_batches().len();
+        if batch == 0 || batch > batch_count {
+            return Err(Error::InvalidTaskState(format!(
+                "Composite task {} has no batch {} (batches: {})",
+                composite_id, batch, batch_count
+            )));
+        }
+
+        if !composite_task.approved_batches.contains(&batch) {
+            composite_task.approved_batches.push(batch);
+            composite_task.approved_batches.sort_unstable();
+        }
+        let approved = composite_task.clone();
+        drop(composites);
+
+        self.persist_composite_task(&approved).await;
+        self.publish(TaskEvent::BatchApproved {
+            composite_task_id: composite_id.to_string(),
+            batch,
+            timestamp: chrono::Utc::now(),
+        });
+
+        tracing::info!("Batch {} of composite task {} approved", batch, composite_id);
+
+        Ok(approved)
+    }
+
+    /// Prepare a stopped composite task to run again
+    ///
+    /// Subtasks in `done` (e.g. whose PRs were already merged) are marked
+    /// Completed; every other subtask goes back to Pending so the next
+    /// execution dispatches it again. Execution skips completed subtasks, so
+    /// it continues from the first unfinished batch.
+    pub async fn resume_composite_task(
+        &self,
+        composite_id: &str,
+        done: &HashSet<String>,
+    ) -> Result<CompositeTask> {
+        // Same lock order as get_ready_tasks
+        let mut tasks = self.active_tasks.write().await;
+        let mut completed = self.completed_tasks.write().await;
+        let mut composites = self.composite_tasks.write().await;
+
+        let composite_task = composites
+            .get_mut(composite_id)
+            .ok_or_else(|| Error::TaskNotFound(composite_id.to_string()))?;
+
+        if composite_task.is_pending_review() {
+            return Err(Error::InvalidTaskState(format!(
+                "Composite task {} is pending review; approve it instead",
+                composite_id
+            )));
+        }
+
+        // The composite keeps a copy of each subtask; the task map holds the live state
+        let subtasks: Vec<Task> = composite_task
+            .subtasks
+            .iter()
+            .map(|t| tasks.get(&t.id).cloned().unwrap_or_else(|| t.clone()))
+            .collect();
+
+        if let Some(running) = subtasks.iter().find(|t| t.status == TaskStatus::InProgress) {
+            return Err(Error::InvalidTaskState(format!(
+                "Subtask {} of composite task {} is still running; cancel it first",
+                running.id, composite_id
+            )));
+        }
+
+        if subtasks.iter().all(|t| done.contains(&t.id)) {
+            return Err(Error::InvalidTaskState(format!(
+                "Composite task {} has no unfinished subtasks",
+                composite_id
+            )));
+        }
+
+        // Statuses are reconciled with the merged PRs rather than moved
+        // through the state machine (a merged subtask may have failed later)
+        let mut changed = Vec::new();
+        for mut task in subtasks.iter().cloned() {
+            if done.contains(&task.id) {
+                completed.insert(task.id.clone());
+                if task.status != TaskStatus::Completed {
+                    task.status = TaskStatus::Completed;
+                    task.completed_at = Some(chrono::Utc::now());
+                    changed.push(task.clone());
+                }
+            } else {
+                completed.remove(&task.id);
+                if task.status != TaskStatus::Pending {
+                    task.status = TaskStatus::Pending;
+                    task.completed_at = None;
+                    task.workflow_run_id = None;
+                    task.pr_url = None;
+                    task.pr_number = None;
+                    task.error = None;
+                    task.blocked_by = None;
+                    changed.push(task.clone());
+                }
+            }
+            tasks.insert(task.id.clone(), task);
+        }
+
+        composite_task.subtasks = subtasks
+            .iter()
+            .map(|t| tasks[&t.id].clone())
+            .collect();
+        composite_task.completed_at = None;
+        composite_task.cancelled_at = None;
+        let resumed = composite_task.clone();
+        drop(composites);
+        drop(completed);
+        drop(tasks);
+
+        for task in &changed {
+            self.persist_task(task).await;
+            self.publish_status(task);
+        }
+        self.persist_composite_task(&resumed).await;
+
+        tracing::info!(
+            "Composite task {} resumed: {} of {} subtasks left",
+            composite_id,
+            resumed.subtasks.iter().filter(|t| !done.contains(&t.id)).count(),
+            resumed.subtasks.len()
+        );
+
+        Ok(resumed)
+    }
+
+    /// Whether batch `batch` (1-based) of a composite task may start
+    pub async fn is_batch_approved(&self, composite_id: &str, batch: usize) -> bool {
+        let composites = self.composite_tasks.read().await;
+        composites
+            .get(composite_id)
+            .is_some_and(|c| c.is_batch_approved(batch))
+    }
 
-            if status == TaskStatus::Completed {
-                let mut completed = self.completed_tasks.write().await;
-                completed.insert(task_id.to_string());
-                task.completed_at = Some(chrono::Utc::now());
+    /// Record the pull request opened for a task (the number when the caller knows it)
+    pub async fn set_task_pr(&self, task_id: &str, pr_number: Option<u64>, pr_url: String) -> Result<()> {
+        let mut tasks = self.active_tasks.write().await;
 
-                tracing::info!("Task completed: {} ({})", task.title, task_id);
-            } else if status == TaskStatus::Failed {
-                task.completed_at = Some(chrono::Utc::now());
-                tracing::error!("Task failed: {} ({})", task.title, task_id);
-            }
-        }
+        let task = tasks
+            .get_mut(task_id)
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?;
+        task.pr_number = pr_number.or(task.pr_number);
+        task.pr_url = Some(pr_url.clone());
+        let updated = task.clone();
+        drop(tasks);
+
+        self.persist_task(&updated).await;
+        self.publish(TaskEvent::PrCreated {
+            task_id: task_id.to_string(),
+            pr_url,
+            timestamp: chrono::Utc::now(),
+        });
 
         Ok(())
     }
@@ -122,17 +1042,56 @@ impl AutoDevEngine {
         composites.get(composite_id).cloned()
     }
 
+    /// Composite task `task_id` is a subtask of
+    pub async fn composite_of(&self, task_id: &str) -> Option<CompositeTask> {
+        let composites = self.composite_tasks.read().await;
+        composites
+            .values()
+            .find(|c| c.subtasks.iter().any(|t| t.id == task_id))
+            .cloned()
+    }
+
     /// Get ready tasks (dependencies met)
+    ///
+    /// Subtasks of composite tasks still pending review, waiting for a fix
+    /// of their parent branch, or paused are never ready.
     pub async fn get_ready_tasks(&self) -> Vec<Task> {
         let tasks = self.active_tasks.read().await;
         let completed = self.completed_tasks.read().await;
+        let composites = self.composite_tasks.read().await;
+
+        let held: HashSet<&str> = composites
+            .values()
+            .filter(|c| c.is_pending_review() || c.is_fix_required() || c.is_paused())
+            .flat_map(|c| c.subtasks.iter().map(|t| t.id.as_str()))
+            .collect();
+
+        // Under RunAnyway a failed dependency counts as done
+        let run_anyway: HashSet<&str> = composites
+            .values()
+            .filter(|c| c.failure_policy == FailurePolicy::RunAnyway)
+            .flat_map(|c| c.subtasks.iter().map(|t| t.id.as_str()))
+            .collect();
+        let settled: HashSet<String> = tasks
+            .values()
+            .filter(|t| t.status == TaskStatus::Failed)
+            .map(|t| t.id.clone())
+            .chain(completed.iter().cloned())
+            .collect();
 
         tasks
             .values()
             .filter(|task| {
                 task.status == TaskStatus::Pending || task.status == TaskStatus::WaitingDependencies
             })
-            .filter(|task| task.can_start(&completed))
+            .filter(|task| !held.contains(task.id.as_str()))
+            .filter(|task| {
+                if run_anyway.contains(task.id.as_str()) {
+                    task.can_start(&settled)
+                } else {
+                    task.can_start(&completed)
+                }
+            })
             .cloned()
             .collect()
     }
@@ -170,7 +1129,7 @@ impl Default for AutoDevEngine {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct EngineStatistics {
     pub total_tasks: usize,
     pub completed_tasks: usize,
@@ -209,6 +1168,588 @@ mod tests {
         assert!(retrieved.is_some());
     }
 
+    #[tokio::test]
+    async fn test_find_open_duplicate() {
+        let engine = AutoDevEngine::new();
+        let task = engine
+            .register_task(
+                Task::new("Login".to_string(), "".to_string(), "Add a login page".to_string())
+                    .with_repository("owner".to_string(), "repo".to_string()),
+            )
+            .await
+            .unwrap();
+
+        let duplicate = engine.find_open_duplicate("owner", "repo", "  add a LOGIN\npage").await;
+        assert_eq!(duplicate.map(|t| t.id), Some(task.id.clone()));
+        assert!(engine.find_open_duplicate("owner", "repo", "Add a logout page").await.is_none());
+        assert!(engine.find_open_duplicate("owner", "other", "Add a login page").await.is_none());
+
+        engine.update_task_status(&task.id, TaskStatus::InProgress, None).await.unwrap();
+        engine.update_task_status(&task.id, TaskStatus::Completed, None).await.unwrap();
+        assert!(engine.find_open_duplicate("owner", "repo", "Add a login page").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_register_task_with_issue() {
+        let engine = AutoDevEngine::new();
+
+        let task = Task::new("Fix".to_string(), "".to_string(), "".to_string())
+            .with_issue(42, "https://github.com/o/r/issues/42".to_string());
+        let task = engine.register_task(task).await.unwrap();
+
+        let retrieved = engine.get_task(&task.id).await.unwrap();
+        assert_eq!(retrieved.issue_number, Some(42));
+        assert_eq!(retrieved.issue_url.as_deref(), Some("https://github.com/o/r/issues/42"));
+    }
+
+    #[derive(Default)]
+    struct MemoryStore {
+        tasks: std::sync::Mutex<HashMap<String, Task>>,
+        composites: std::sync::Mutex<HashMap<String, CompositeTask>>,
+    }
+
+    #[async_trait::async_trait]
+    impl TaskStore for MemoryStore {
+        async fn load_snapshot(&self) -> Result<crate::store::EngineSnapshot> {
+            Ok(crate::store::EngineSnapshot {
+                tasks: self.tasks.lock().unwrap().values().cloned().collect(),
+                composite_tasks: self.composites.lock().unwrap().values().cloned().collect(),
+            })
+        }
+
+        async fn persist_task(&self, task: &Task) -> Result<()> {
+            self.tasks.lock().unwrap().insert(task.id.clone(), task.clone());
+            Ok(())
+        }
+
+        async fn persist_composite_task(&self, composite_task: &CompositeTask) -> Result<()> {
+            for task in &composite_task.subtasks {
+                self.persist_task(task).await?;
+            }
+            self.composites
+                .lock()
+                .unwrap()
+                .insert(composite_task.id.clone(), composite_task.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_write_through_and_hydrate() {
+        let store = Arc::new(MemoryStore::default());
+        let engine = AutoDevEngine::with_store(store.clone());
+
+        let task = engine
+            .create_simple_task("Persisted".to_string(), "".to_string(), "".to_string())
+            .await
+            .unwrap();
+        engine.update_task_status(&task.id, TaskStatus::InProgress, None).await.unwrap();
+        engine
+            .update_task_status(&task.id, TaskStatus::Completed, None)
+            .await
+            .unwrap();
+
+        let subtask = Task::new("Sub".to_string(), "".to_string(), "".to_string());
+        let composite = engine
+            .create_composite_task("Composite".to_string(), "".to_string(), vec![subtask], false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.tasks.lock().unwrap().get(&task.id).unwrap().status,
+            TaskStatus::Completed
+        );
+
+        // A fresh engine restores everything from the store
+        let restored = AutoDevEngine::with_store(store);
+        restored.hydrate().await.unwrap();
+
+        assert_eq!(
+            restored.get_task(&task.id).await.unwrap().status,
+            TaskStatus::Completed
+        );
+        assert!(restored.completed_tasks.read().await.contains(&task.id));
+        assert!(restored.get_composite_task(&composite.id).await.is_some());
+        assert_eq!(restored.get_statistics().await.total_tasks, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_task() {
+        let engine = AutoDevEngine::new();
+
+        let task = engine
+            .create_simple_task("Cancel me".to_string(), "".to_string(), "".to_string())
+            .await
+            .unwrap();
+
+        let cancelled = engine.cancel_task(&task.id).await.unwrap();
+        assert_eq!(cancelled.status, TaskStatus::Cancelled);
+        assert!(cancelled.completed_at.is_some());
+
+        // Finished tasks cannot be cancelled again
+        assert!(engine.cancel_task(&task.id).await.is_err());
+        assert!(engine.cancel_task("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_interrupt_and_requeue() {
+        let engine = AutoDevEngine::new();
+
+        let task = engine
+            .create_simple_task("Interrupt me".to_string(), "".to_string(), "".to_string())
+            .await
+            .unwrap();
+
+        // Only in-progress tasks are interrupted
+        assert!(!engine.interrupt_task(&task.id).await.unwrap());
+
+        engine.update_task_status(&task.id, TaskStatus::InProgress, None).await.unwrap();
+        assert!(engine.interrupt_task(&task.id).await.unwrap());
+        assert_eq!(engine.get_task(&task.id).await.unwrap().status, TaskStatus::Interrupted);
+
+        assert_eq!(engine.requeue_interrupted().await, vec![task.id.clone()]);
+        assert_eq!(engine.get_task(&task.id).await.unwrap().status, TaskStatus::Pending);
+        assert!(engine.requeue_interrupted().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pause_for_budget() {
+        let engine = AutoDevEngine::new();
+
+        let task = engine
+            .create_simple_task("Expensive".to_string(), "".to_string(), "".to_string())
+            .await
+            .unwrap();
+        engine.update_task_status(&task.id, TaskStatus::InProgress, None).await.unwrap();
+
+        let paused = engine.pause_for_budget(&task.id, "task budget exhausted").await.unwrap();
+        assert_eq!(paused.status, TaskStatus::BudgetExceeded);
+        assert_eq!(paused.error.as_deref(), Some("task budget exhausted"));
+        assert!(engine.get_ready_tasks().await.is_empty());
+
+        let retried = engine.reset_for_retry(&task.id).await.unwrap();
+        assert_eq!(retried.status, TaskStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_approve_composite_task() {
+        let engine = AutoDevEngine::new();
+
+        let subtask = Task::new("Sub".to_string(), "".to_string(), "".to_string());
+        let composite = engine
+            .register_composite_task(
+                CompositeTask::new("Review me".to_string(), "".to_string(), vec![subtask])
+                    .with_review_required(true),
+            )
+            .await
+            .unwrap();
+        assert!(composite.is_pending_review());
+        assert!(engine.get_ready_tasks().await.is_empty());
+
+        let approved = engine.approve_composite_task(&composite.id).await.unwrap();
+        assert_eq!(approved.review_state, ReviewState::Approved);
+        assert_eq!(engine.get_ready_tasks().await.len(), 1);
+
+        // Approving twice (or an unknown id) is rejected
+        assert!(engine.approve_composite_task(&composite.id).await.is_err());
+        assert!(engine.approve_composite_task("missing").await.is_err());
+
+        // Failed parent branch tests hold it again until the fix is approved
+        let held = engine.require_fix(&composite.id).await.unwrap();
+        assert!(held.is_fix_required());
+        assert!(engine.get_ready_tasks().await.is_empty());
+
+        let approved = engine.approve_composite_task(&composite.id).await.unwrap();
+        assert_eq!(approved.review_state, ReviewState::Approved);
+        assert_eq!(engine.get_ready_tasks().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_register_rejects_dependency_cycle() {
+        let engine = AutoDevEngine::new();
+
+        let mut first = Task::new("First".to_string(), "".to_string(), "".to_string());
+        let second = Task::new("Second".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![first.id.clone()]);
+        first.dependencies = vec![second.id.clone()];
+        let (first_id, second_id) = (first.id.clone(), second.id.clone());
+
+        let result = engine
+            .create_composite_task("Cyclic".to_string(), "".to_string(), vec![first, second], false)
+            .await;
+        match result {
+            Err(Error::DependencyCycle(cycle)) => assert_eq!(cycle, [first_id.clone(), second_id, first_id.clone()]),
+            other => panic!("expected a dependency cycle, got {:?}", other.map(|c| c.id)),
+        }
+
+        // Nothing was tracked
+        assert!(engine.get_task(&first_id).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_approve_batch() {
+        let engine = AutoDevEngine::new();
+
+        let first = Task::new("First".to_string(), "".to_string(), "".to_string());
+        let second = Task::new("Second".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![first.id.clone()]);
+        let composite = engine
+            .create_composite_task("Two batches".to_string(), "".to_string(), vec![first, second], false)
+            .await
+            .unwrap();
+        let mut events = engine.subscribe();
+
+        assert!(engine.is_batch_approved(&composite.id, 1).await);
+        assert!(!engine.is_batch_approved(&composite.id, 2).await);
+
+        let approved = engine.approve_batch(&composite.id, 2).await.unwrap();
+        assert_eq!(approved.approved_batches, vec![2]);
+        assert!(engine.is_batch_approved(&composite.id, 2).await);
+        assert_eq!(events.recv().await.unwrap().name(), "batch_approved");
+
+        // Idempotent, but batches outside the plan are rejected
+        assert_eq!(engine.approve_batch(&composite.id, 2).await.unwrap().approved_batches, vec![2]);
+        assert!(engine.approve_batch(&composite.id, 3).await.is_err());
+        assert!(engine.approve_batch(&composite.id, 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resume_composite_task() {
+        let engine = AutoDevEngine::new();
+
+        let first = Task::new("First".to_string(), "".to_string(), "".to_string());
+        let second = Task::new("Second".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![first.id.clone()]);
+        let third = Task::new("Third".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![second.id.clone()]);
+        let (first_id, second_id, third_id) = (first.id.clone(), second.id.clone(), third.id.clone());
+        let composite = engine
+            .create_composite_task("Resume".to_string(), "".to_string(), vec![first, second, third], true)
+            .await
+            .unwrap();
+
+        // Batch 2 fails after batch 1 was merged
+        engine.update_task_status(&first_id, TaskStatus::InProgress, None).await.unwrap();
+        engine.update_task_status(&first_id, TaskStatus::Completed, None).await.unwrap();
+        engine.update_task_status(&second_id, TaskStatus::InProgress, None).await.unwrap();
+
+        let done: HashSet<String> = [first_id.clone()].into();
+        assert!(engine.resume_composite_task(&composite.id, &done).await.is_err());
+
+        engine
+            .update_task_status(&second_id, TaskStatus::Failed, Some("boom".to_string()))
+            .await
+            .unwrap();
+        let resumed = engine.resume_composite_task(&composite.id, &done).await.unwrap();
+
+        let statuses: Vec<TaskStatus> = resumed.subtasks.iter().map(|t| t.status).collect();
+        assert_eq!(statuses, vec![TaskStatus::Completed, TaskStatus::Pending, TaskStatus::Pending]);
+        let second = engine.get_task(&second_id).await.unwrap();
+        assert_eq!(second.status, TaskStatus::Pending);
+        assert!(second.error.is_none());
+
+        // Only the unfinished subtasks are ready again
+        let ready: Vec<String> = engine.get_ready_tasks().await.into_iter().map(|t| t.id).collect();
+        assert_eq!(ready, vec![second_id.clone()]);
+
+        // Nothing to resume once every subtask is done
+        let all: HashSet<String> = [first_id, second_id, third_id].into();
+        assert!(engine.resume_composite_task(&composite.id, &all).await.is_err());
+        assert!(engine.resume_composite_task("missing", &done).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_task_events() {
+        let engine = AutoDevEngine::new();
+        let mut events = engine.subscribe();
+
+        let task = engine
+            .create_simple_task("Watch me".to_string(), "".to_string(), "".to_string())
+            .await
+            .unwrap();
+
+        engine.update_task_status(&task.id, TaskStatus::InProgress, None).await.unwrap();
+        engine.log_event(&task.id, "WORKFLOW_TRIGGERED", "run 1");
+        engine
+            .set_task_pr(&task.id, Some(1), "https://github.com/o/r/pull/1".to_string())
+            .await
+            .unwrap();
+
+        match events.recv().await.unwrap() {
+            TaskEvent::TaskCreated { task_id, composite_task_id, .. } => {
+                assert_eq!(task_id, task.id);
+                assert!(composite_task_id.is_none());
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        match events.recv().await.unwrap() {
+            TaskEvent::StatusChanged { task_id, status, attempts, .. } => {
+                assert_eq!(task_id, task.id);
+                assert_eq!(status, TaskStatus::InProgress);
+                assert_eq!(attempts, 1);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert_eq!(events.recv().await.unwrap().name(), "log");
+        assert_eq!(events.recv().await.unwrap().name(), "pr_created");
+        let updated = engine.get_task(&task.id).await.unwrap();
+        assert_eq!(updated.pr_url.as_deref(), Some("https://github.com/o/r/pull/1"));
+        assert_eq!(updated.pr_number, Some(1));
+
+        engine.set_workflow_run(&task.id, 42).await.unwrap();
+        assert_eq!(engine.get_task(&task.id).await.unwrap().workflow_run_id.as_deref(), Some("42"));
+    }
+
+    #[tokio::test]
+    async fn test_complete_composite_task() {
+        let engine = AutoDevEngine::new();
+        let mut events = engine.subscribe();
+
+        let subtask = Task::new("Only".to_string(), "".to_string(), "".to_string());
+        let composite = engine
+            .create_composite_task("Done".to_string(), "".to_string(), vec![subtask], true)
+            .await
+            .unwrap();
+        match events.recv().await.unwrap() {
+            TaskEvent::TaskCreated { composite_task_id, .. } => {
+                assert_eq!(composite_task_id.as_deref(), Some(composite.id.as_str()));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let completed = engine.complete_composite_task(&composite.id).await.unwrap();
+        assert!(completed.completed_at.is_some());
+        match events.recv().await.unwrap() {
+            TaskEvent::CompositeCompleted { composite_task_id, subtasks, .. } => {
+                assert_eq!(composite_task_id, composite.id);
+                assert_eq!(subtasks, 1);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        // Completing again neither changes the time nor publishes again
+        let again = engine.complete_composite_task(&composite.id).await.unwrap();
+        assert_eq!(again.completed_at, completed.completed_at);
+        assert!(events.try_recv().is_err());
+        assert!(engine.complete_composite_task("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_composite_task() {
+        let engine = AutoDevEngine::new();
+
+        let subtask = Task::new("Only".to_string(), "".to_string(), "".to_string());
+        let subtask_id = subtask.id.clone();
+        let composite = engine
+            .create_composite_task("Stop".to_string(), "".to_string(), vec![subtask], true)
+            .await
+            .unwrap();
+
+        let cancelled = engine.cancel_composite_task(&composite.id).await.unwrap();
+        assert!(cancelled.is_cancelled());
+        assert!(engine.cancel_composite_task(&composite.id).await.is_err());
+        assert!(engine.cancel_composite_task("missing").await.is_err());
+
+        // A cancelled composite task is not completed
+        let again = engine.complete_composite_task(&composite.id).await.unwrap();
+        assert!(again.completed_at.is_none());
+
+        // Resuming clears the cancellation
+        engine.cancel_task(&subtask_id).await.unwrap();
+        let resumed = engine.resume_composite_task(&composite.id, &HashSet::new()).await.unwrap();
+        assert!(!resumed.is_cancelled());
+        assert_eq!(engine.get_task(&subtask_id).await.unwrap().status, TaskStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_pause_composite_task() {
+        let engine = AutoDevEngine::new();
+
+        let subtask = Task::new("Only".to_string(), "".to_string(), "".to_string());
+        let composite = engine
+            .create_composite_task("Freeze".to_string(), "".to_string(), vec![subtask], true)
+            .await
+            .unwrap();
+        assert_eq!(engine.get_ready_tasks().await.len(), 1);
+
+        // Paused subtasks are never ready
+        assert!(engine.pause_composite_task(&composite.id).await.unwrap().is_paused());
+        assert!(engine.get_ready_tasks().await.is_empty());
+        assert!(engine.pause_composite_task(&composite.id).await.is_err());
+        assert!(engine.approve_composite_task(&composite.id).await.is_err());
+
+        assert_eq!(
+            engine.unpause_composite_task(&composite.id).await.unwrap().review_state,
+            ReviewState::Approved
+        );
+        assert_eq!(engine.get_ready_tasks().await.len(), 1);
+        assert!(engine.unpause_composite_task(&composite.id).await.is_err());
+
+        // Only approved composite tasks can be paused
+        engine.require_fix(&composite.id).await.unwrap();
+        assert!(engine.pause_composite_task(&composite.id).await.is_err());
+        assert!(engine.pause_composite_task("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_task() {
+        let engine = AutoDevEngine::new();
+
+        let task = engine
+            .create_simple_task("Flaky".to_string(), "".to_string(), "".to_string())
+            .await
+            .unwrap();
+
+        // Not failed yet
+        assert!(engine.retry_backoff(&task.id, FailureKind::WorkflowFailure).await.is_err());
+
+        for attempt in 1..=3 {
+            engine.update_task_status(&task.id, TaskStatus::InProgress, None).await.unwrap();
+            assert_eq!(engine.get_task(&task.id).await.unwrap().attempts, attempt);
+
+            engine
+                .update_task_status(&task.id, TaskStatus::Failed, Some("boom".to_string()))
+                .await
+                .unwrap();
+
+            let backoff = engine.retry_backoff(&task.id, FailureKind::WorkflowFailure).await.unwrap();
+            if attempt < 3 {
+                assert!(backoff.is_some());
+                let retried = engine.reset_for_retry(&task.id).await.unwrap();
+                assert_eq!(retried.status, TaskStatus::Pending);
+            } else {
+                // Default policy allows three attempts in total
+                assert!(backoff.is_none());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_propagate_failure() {
+        let task_a = Task::new("A".to_string(), "".to_string(), "".to_string());
+        let task_b = Task::new("B".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![task_a.id.clone()]);
+        let task_c = Task::new("C".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![task_b.id.clone()]);
+        let task_d = Task::new("D".to_string(), "".to_string(), "".to_string());
+        let ids: Vec<String> = [&task_a, &task_b, &task_c, &task_d].iter().map(|t| t.id.clone()).collect();
+
+        let engine = AutoDevEngine::new();
+        engine
+            .register_composite_task(CompositeTask::new(
+                "Blocking".to_string(),
+                "".to_string(),
+                vec![task_a, task_b, task_c, task_d],
+            ))
+            .await
+            .unwrap();
+
+        engine.update_task_status(&ids[0], TaskStatus::InProgress, None).await.unwrap();
+        engine.update_task_status(&ids[0], TaskStatus::Failed, Some("boom".to_string())).await.unwrap();
+
+        // Block (the default) applies transitively; independent tasks are untouched
+        let blocked = engine.propagate_failure(&ids[0]).await.unwrap();
+        assert_eq!(blocked.len(), 2);
+        for id in &ids[1..3] {
+            let task = engine.get_task(id).await.unwrap();
+            assert_eq!(task.status, TaskStatus::Blocked);
+            assert_eq!(task.blocked_by.as_deref(), Some(ids[0].as_str()));
+        }
+        assert_eq!(engine.get_task(&ids[3]).await.unwrap().status, TaskStatus::Pending);
+        assert!(engine.get_ready_tasks().await.iter().all(|t| t.id == ids[3]));
+
+        // Retrying the failed task releases what it blocked
+        engine.reset_for_retry(&ids[0]).await.unwrap();
+        let task_b = engine.get_task(&ids[1]).await.unwrap();
+        assert_eq!(task_b.status, TaskStatus::WaitingDependencies);
+        assert!(task_b.blocked_by.is_none() && task_b.error.is_none());
+
+        // Skip cancels the dependents, RunAnyway lets them start
+        let task_a = Task::new("A".to_string(), "".to_string(), "".to_string());
+        let task_b = Task::new("B".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![task_a.id.clone()]);
+        let (a, b) = (task_a.id.clone(), task_b.id.clone());
+        let skipping = engine
+            .register_composite_task(
+                CompositeTask::new("Skipping".to_string(), "".to_string(), vec![task_a, task_b])
+                    .with_failure_policy(FailurePolicy::Skip),
+            )
+            .await
+            .unwrap();
+        engine.update_task_status(&a, TaskStatus::Failed, None).await.unwrap();
+        engine.propagate_failure(&a).await.unwrap();
+        assert!(engine.get_task(&b).await.unwrap().is_skipped());
+
+        let completed = engine.complete_composite_task(&skipping.id).await.unwrap();
+        let summary = completed.completion_summary();
+        assert!(summary.is_partial());
+        assert_eq!(summary.skipped, vec![b]);
+
+        let task_a = Task::new("A".to_string(), "".to_string(), "".to_string());
+        let task_b = Task::new("B".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![task_a.id.clone()]);
+        let (a, b) = (task_a.id.clone(), task_b.id.clone());
+        engine
+            .register_composite_task(
+                CompositeTask::new("Anyway".to_string(), "".to_string(), vec![task_a, task_b])
+                    .with_failure_policy(FailurePolicy::RunAnyway),
+            )
+            .await
+            .unwrap();
+        engine.update_task_status(&a, TaskStatus::Failed, None).await.unwrap();
+        assert!(engine.propagate_failure(&a).await.unwrap().is_empty());
+        assert!(engine.get_ready_tasks().await.iter().any(|t| t.id == b));
+    }
+
+    #[tokio::test]
+    async fn test_split_subtask() {
+        let first = Task::new("First".to_string(), "".to_string(), "".to_string());
+        let big = Task::new("Big".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![first.id.clone()]);
+        let last = Task::new("Last".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![big.id.clone()]);
+        let (first_id, big_id, last_id) = (first.id.clone(), big.id.clone(), last.id.clone());
+
+        let engine = AutoDevEngine::new();
+        engine
+            .create_composite_task("Split".to_string(), "".to_string(), vec![first, big, last], true)
+            .await
+            .unwrap();
+        engine.update_task_status(&big_id, TaskStatus::Failed, Some("too big".to_string())).await.unwrap();
+        engine.propagate_failure(&big_id).await.unwrap();
+
+        // Big → (part 1 → part 2, part 3)
+        let part_1 = Task::new("Part 1".to_string(), "".to_string(), "".to_string());
+        let part_2 = Task::new("Part 2".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![part_1.id.clone()]);
+        let part_3 = Task::new("Part 3".to_string(), "".to_string(), "".to_string());
+        let parts: Vec<String> = [&part_1, &part_2, &part_3].iter().map(|t| t.id.clone()).collect();
+
+        let split = engine.split_subtask(&big_id, vec![part_1, part_2, part_3]).await.unwrap();
+        let ids: Vec<&str> = split.subtasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, [first_id.as_str(), &parts[0], &parts[1], &parts[2], last_id.as_str()]);
+        assert!(engine.get_task(&big_id).await.is_none());
+
+        // Roots take over the dependencies, the dependents wait for the leaves
+        assert_eq!(split.subtasks[1].dependencies, vec![first_id.clone()]);
+        assert_eq!(split.subtasks[2].dependencies, vec![parts[0].clone()]);
+        assert_eq!(split.subtasks[3].dependencies, vec![first_id.clone()]);
+        let last = engine.get_task(&last_id).await.unwrap();
+        assert_eq!(last.dependencies, vec![parts[1].clone(), parts[2].clone()]);
+        assert_eq!(last.status, TaskStatus::WaitingDependencies);
+        assert!(last.blocked_by.is_none());
+        assert_eq!(split.get_parallel_batches().len(), 4);
+
+        // Only subtasks that are not running or done can be split
+        engine.update_task_status(&first_id, TaskStatus::InProgress, None).await.unwrap();
+        let part = Task::new("Part".to_string(), "".to_string(), "".to_string());
+        assert!(matches!(
+            engine.split_subtask(&first_id, vec![part]).await,
+            Err(Error::InvalidTaskState(_))
+        ));
+        assert!(engine.split_subtask(&last_id, Vec::new()).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_update_task_status() {
         let engine = AutoDevEngine::new();
@@ -229,5 +1770,16 @@ mod tests {
 
         let updated = engine.get_task(&task.id).await.unwrap();
         assert_eq!(updated.status, TaskStatus::InProgress);
+        assert!(updated.started_at.is_some());
+
+        engine.update_task_status(&task.id, TaskStatus::Completed, None).await.unwrap();
+        assert!(engine.get_task(&task.id).await.unwrap().completed_at.is_some());
+
+        // A finished task cannot start again
+        assert!(matches!(
+            engine.update_task_status(&task.id, TaskStatus::InProgress, None).await,
+            Err(Error::InvalidTransition { .. })
+        ));
+        assert_eq!(engine.get_task(&task.id).await.unwrap().status, TaskStatus::Completed);
     }
 }
\ No newline at end of file
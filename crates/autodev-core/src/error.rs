@@ -1,5 +1,8 @@
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::TaskStatus;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Task not found: {0}")]
@@ -8,14 +11,134 @@ pub enum Error {
     #[error("Invalid task state: {0}")]
     InvalidTaskState(String),
 
-    #[error("Dependency cycle detected")]
-    DependencyCycle,
+    #[error("Task {task_id} cannot move from {from:?} to {to:?}")]
+    InvalidTransition {
+        task_id: String,
+        from: TaskStatus,
+        to: TaskStatus,
+    },
+
+    /// Tasks along the cycle, each depending on the next; the first is repeated last
+    #[error("Dependency cycle detected: {}", .0.join(" -> "))]
+    DependencyCycle(Vec<String>),
 
     #[error("Engine error: {0}")]
     EngineError(String),
 
+    #[error("Invalid cron expression: {0}")]
+    InvalidCron(String),
+
+    #[error("Invalid plan: {0}")]
+    InvalidPlan(String),
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[error("Store error: {0}")]
+    StoreError(String),
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
+impl Error {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            Error::TaskNotFound(_) => ErrorClass::NotFound,
+            Error::InvalidTaskState(_) | Error::InvalidTransition { .. } => ErrorClass::Conflict,
+            Error::StoreError(_) => ErrorClass::Transient,
+            Error::DependencyCycle(_)
+            | Error::EngineError(_)
+            | Error::InvalidCron(_)
+            | Error::InvalidPlan(_)
+            | Error::Config(_)
+            | Error::Other(_) => ErrorClass::Permanent,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// What kind of failure an error is, whichever crate it comes from
+///
+/// Decides whether a failed call or task is retried and which HTTP status
+/// the API answers with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The task, repository, PR, branch or model does not exist
+    NotFound,
+    /// Throttled; retry once the delay the service asked for (if any) passed
+    RateLimited { retry_after: Option<Duration> },
+    /// The target's current state refuses the change (e.g. the task already
+    /// finished, the branch exists, the PR is not mergeable)
+    Conflict,
+    /// Credentials are missing, invalid or lack a permission
+    AuthError,
+    /// Server error, timeout or dropped connection: the same call may succeed later
+    Transient,
+    /// Invalid input or anything else that fails again when retried
+    Permanent,
+}
+
+impl ErrorClass {
+    /// Class of an error response with HTTP `status` from an upstream API
+    pub fn from_status(status: u16, retry_after: Option<Duration>) -> Self {
+        match status {
+            401 | 403 => Self::AuthError,
+            404 | 410 => Self::NotFound,
+            409 => Self::Conflict,
+            429 => Self::RateLimited { retry_after },
+            408 | 500..=599 => Self::Transient,
+            _ => Self::Permanent,
+        }
+    }
+
+    /// Whether the same call may succeed when tried again later
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::RateLimited { .. } | Self::Transient)
+    }
+
+    /// Delay asked for before retrying
+    pub fn retry_after(self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after } => retry_after,
+            _ => None,
+        }
+    }
+
+    /// HTTP status the API answers with when an error of this class ends a request
+    ///
+    /// Rejected credentials are AutoDev's own (GitHub's, the AI provider's),
+    /// not the API caller's, so they are reported as a bad gateway.
+    pub fn http_status(self) -> u16 {
+        match self {
+            Self::NotFound => 404,
+            Self::RateLimited { .. } => 429,
+            Self::Conflict => 409,
+            Self::AuthError => 502,
+            Self::Transient => 503,
+            Self::Permanent => 500,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_class() {
+        assert_eq!(Error::TaskNotFound("t1".into()).class(), ErrorClass::NotFound);
+        assert_eq!(Error::InvalidTaskState("done".into()).class(), ErrorClass::Conflict);
+        assert!(!Error::InvalidPlan("empty".into()).class().is_retryable());
+
+        let throttled = ErrorClass::from_status(429, Some(Duration::from_secs(30)));
+        assert!(throttled.is_retryable());
+        assert_eq!(throttled.retry_after(), Some(Duration::from_secs(30)));
+        assert_eq!(throttled.http_status(), 429);
+
+        assert_eq!(ErrorClass::from_status(502, None), ErrorClass::Transient);
+        assert_eq!(ErrorClass::from_status(401, None).http_status(), 502);
+        assert_eq!(ErrorClass::from_status(422, None), ErrorClass::Permanent);
+    }
+}
\ No newline at end of file
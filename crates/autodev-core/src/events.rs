@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::TaskStatus;
+
+/// Number of events buffered per subscriber before slow receivers start lagging
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Live task update published by AutoDevEngine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskEvent {
+    /// Task registered with the engine
+    TaskCreated {
+        task_id: String,
+        title: String,
+        /// Composite task the task is a subtask of
+        composite_task_id: Option<String>,
+        timestamp: DateTime<Utc>,
+    },
+    /// Task moved to a new status
+    StatusChanged {
+        task_id: String,
+        status: TaskStatus,
+        error: Option<String>,
+        attempts: u32,
+        timestamp: DateTime<Utc>,
+    },
+    /// Execution log entry (STARTED, WORKFLOW_TRIGGERED, COMPLETED, ...)
+    Log {
+        task_id: String,
+        event_type: String,
+        message: String,
+        timestamp: DateTime<Utc>,
+    },
+    /// Batch of container output lines
+    Output {
+        task_id: String,
+        lines: Vec<String>,
+        timestamp: DateTime<Utc>,
+    },
+    /// Pull request opened for the task
+    PrCreated {
+        task_id: String,
+        pr_url: String,
+        timestamp: DateTime<Utc>,
+    },
+    /// Batch (1-based) of a composite task approved to start
+    BatchApproved {
+        composite_task_id: String,
+        batch: usize,
+        timestamp: DateTime<Utc>,
+    },
+    /// Nothing is left to run in a composite task; every subtask was merged
+    /// unless some failed or were skipped under its failure policy
+    CompositeCompleted {
+        composite_task_id: String,
+        title: String,
+        subtasks: usize,
+        #[serde(default)]
+        failed: usize,
+        #[serde(default)]
+        skipped: usize,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+impl TaskEvent {
+    /// Task (or composite task) the event belongs to
+    pub fn task_id(&self) -> &str {
+        match self {
+            TaskEvent::TaskCreated { task_id, .. }
+            | TaskEvent::StatusChanged { task_id, .. }
+            | TaskEvent::Log { task_id, .. }
+            | TaskEvent::Output { task_id, .. }
+            | TaskEvent::PrCreated { task_id, .. } => task_id,
+            TaskEvent::BatchApproved { composite_task_id, .. }
+            | TaskEvent::CompositeCompleted { composite_task_id, .. } => composite_task_id,
+        }
+    }
+
+    /// SSE event name
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaskEvent::TaskCreated { .. } => "task_created",
+            TaskEvent::StatusChanged { .. } => "status_changed",
+            TaskEvent::Log { .. } => "log",
+            TaskEvent::Output { .. } => "output",
+            TaskEvent::PrCreated { .. } => "pr_created",
+            TaskEvent::BatchApproved { .. } => "batch_approved",
+            TaskEvent::CompositeCompleted { .. } => "composite_completed",
+        }
+    }
+}
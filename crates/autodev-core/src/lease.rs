@@ -0,0 +1,26 @@
+//! Leases on running tasks, shared by the worker and the heartbeat endpoint
+//!
+//! Whoever runs a task (a worker, a workflow run, a container) holds a lease
+//! on it and extends it by heartbeating. A task whose lease expired lost its
+//! executor and is put back in the queue; only tasks that never held a lease
+//! fall back to the age-based stall timeout.
+
+use std::time::Duration;
+
+/// Seconds a lease stays valid without a heartbeat
+pub const LEASE_ENV: &str = "AUTODEV_WORKER_LEASE";
+
+pub const DEFAULT_LEASE: Duration = Duration::from_secs(300);
+
+/// How long a task nobody heartbeats for may stay InProgress, unless the
+/// worker is configured otherwise
+pub const UNLEASED_STALL_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Lease duration configured by AUTODEV_WORKER_LEASE
+pub fn lease_duration() -> Duration {
+    std::env::var(LEASE_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map_or(DEFAULT_LEASE, Duration::from_secs)
+}
@@ -1,10 +1,29 @@
 pub mod task;
 pub mod composite_task;
+pub mod config;
+pub mod cron;
 pub mod engine;
+pub mod plan;
 pub mod error;
+pub mod store;
+pub mod events;
+pub mod sandbox;
+pub mod timeline;
+pub mod shutdown;
+pub mod lease;
 
 // Re-exports
-pub use task::{Task, TaskStatus, TaskType};
-pub use composite_task::CompositeTask;
+pub use task::{normalize_prompt, normalize_tags, ExecutionBackend, FailureKind, RetryPolicy, Task, TaskStatus, TaskType};
+pub use config::{Config, ConfigFile};
+pub use cron::CronSchedule;
+pub use composite_task::{
+    BatchProgress, BatchStatus, CompletionSummary, CompositeTask, FailurePolicy, MergeMethod, ReviewSeverity, ReviewState,
+    DEFAULT_TASK_MINUTES,
+};
 pub use engine::AutoDevEngine;
-pub use error::{Error, Result};
\ No newline at end of file
+pub use plan::{Plan, PLAN_VERSION};
+pub use error::{Error, ErrorClass, Result};
+pub use store::{EngineSnapshot, TaskStore};
+pub use events::TaskEvent;
+pub use sandbox::ContainerSandbox;
+pub use timeline::{LoggedEvent, Timeline};
\ No newline at end of file
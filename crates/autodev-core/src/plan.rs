@@ -0,0 +1,250 @@
+//! Portable plans (`autodev export` / `autodev import`)
+//!
+//! A plan holds what is needed to create tasks again elsewhere: titles,
+//! prompts, dependencies and composite settings, but no IDs or run state.
+//! Tasks are referred to by keys (`task-1`, ...) so exporting the same plan
+//! twice gives the same document; importing creates new IDs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::{CompositeTask, Error, MergeMethod, Result, RetryPolicy, Task};
+
+/// Version written by this build; newer plans are rejected on import
+pub const PLAN_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    pub version: u32,
+    /// `owner/name` the tasks were created for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    /// Settings of the composite task; None for standalone tasks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub composite: Option<PlannedComposite>,
+    pub tasks: Vec<PlannedTask>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedComposite {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub auto_approve: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_parallel_tasks: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_method: Option<MergeMethod>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedTask {
+    /// Unique within the plan, referenced by `dependencies`
+    pub key: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub prompt: String,
+    /// Keys of the tasks this one waits for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+    #[serde(default)]
+    pub auto_approve: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_minutes: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicy>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Plan {
+    pub fn from_composite(composite_task: &CompositeTask, repository: Option<String>) -> Self {
+        Self {
+            version: PLAN_VERSION,
+            repository,
+            composite: Some(PlannedComposite {
+                title: composite_task.title.clone(),
+                description: composite_task.description.clone(),
+                auto_approve: composite_task.auto_approve,
+                max_parallel_tasks: composite_task.max_parallel_tasks,
+                merge_method: composite_task.merge_method,
+                base_branch: composite_task.base_branch.clone(),
+            }),
+            tasks: planned_tasks(&composite_task.subtasks),
+        }
+    }
+
+    /// A standalone task; dependencies outside the plan are dropped
+    pub fn from_task(task: &Task, repository: Option<String>) -> Self {
+        Self {
+            version: PLAN_VERSION,
+            repository,
+            composite: None,
+            tasks: planned_tasks(std::slice::from_ref(task)),
+        }
+    }
+
+    /// Check the version, keys and dependencies (unknown ones and cycles)
+    pub fn validate(&self) -> Result<()> {
+        if self.version == 0 || self.version > PLAN_VERSION {
+            return Err(Error::InvalidPlan(format!(
+                "unsupported plan version {} (this build reads up to {})",
+                self.version, PLAN_VERSION
+            )));
+        }
+        if self.tasks.is_empty() {
+            return Err(Error::InvalidPlan("plan has no tasks".to_string()));
+        }
+
+        let mut keys = HashSet::new();
+        for task in &self.tasks {
+            if !keys.insert(task.key.as_str()) {
+                return Err(Error::InvalidPlan(format!("duplicate task key '{}'", task.key)));
+            }
+        }
+        for task in &self.tasks {
+            if let Some(missing) = task.dependencies.iter().find(|key| !keys.contains(key.as_str())) {
+                return Err(Error::InvalidPlan(format!(
+                    "task '{}' depends on unknown task '{}'",
+                    task.key, missing
+                )));
+            }
+        }
+
+        let graph: Vec<(&str, &[String])> = self
+            .tasks
+            .iter()
+            .map(|task| (task.key.as_str(), task.dependencies.as_slice()))
+            .collect();
+        if let Some(cycle) = crate::composite_task::find_cycle(&graph) {
+            return Err(Error::DependencyCycle(cycle));
+        }
+
+        Ok(())
+    }
+
+    /// New tasks (fresh IDs, dependencies mapped to them) in plan order
+    pub fn to_tasks(&self) -> Result<Vec<Task>> {
+        self.validate()?;
+
+        let ids: HashMap<&str, String> = self
+            .tasks
+            .iter()
+            .map(|planned| (planned.key.as_str(), uuid::Uuid::new_v4().to_string()))
+            .collect();
+
+        Ok(self
+            .tasks
+            .iter()
+            .map(|planned| {
+                let mut task = Task::new(planned.title.clone(), planned.description.clone(), planned.prompt.clone())
+                    .with_dependencies(planned.dependencies.iter().map(|key| ids[key.as_str()].clone()).collect());
+                task.id = ids[planned.key.as_str()].clone();
+                task.auto_approve = planned.auto_approve;
+                task.estimated_minutes = planned.estimated_minutes;
+                task.tags = crate::normalize_tags(&planned.tags);
+                if let Some(ref retry_policy) = planned.retry_policy {
+                    task.retry_policy = retry_policy.clone();
+                }
+                task
+            })
+            .collect())
+    }
+
+    /// The composite task of the plan, None for standalone tasks
+    pub fn to_composite(&self) -> Result<Option<CompositeTask>> {
+        let Some(ref settings) = self.composite else {
+            return Ok(None);
+        };
+
+        let subtasks = self.to_tasks()?;
+        let mut composite_task = CompositeTask::new(settings.title.clone(), settings.description.clone(), subtasks)
+            .with_auto_approve(settings.auto_approve)
+            .with_max_parallel_tasks(settings.max_parallel_tasks)
+            .with_merge_method(settings.merge_method);
+        composite_task.base_branch = settings.base_branch.clone();
+
+        Ok(Some(composite_task))
+    }
+}
+
+fn planned_tasks(tasks: &[Task]) -> Vec<PlannedTask> {
+    let keys: HashMap<&str, String> = tasks
+        .iter()
+        .enumerate()
+        .map(|(index, task)| (task.id.as_str(), format!("task-{}", index + 1)))
+        .collect();
+
+    tasks
+        .iter()
+        .map(|task| PlannedTask {
+            key: keys[task.id.as_str()].clone(),
+            title: task.title.clone(),
+            description: task.description.clone(),
+            prompt: task.prompt.clone(),
+            dependencies: task
+                .dependencies
+                .iter()
+                .filter_map(|id| keys.get(id.as_str()).cloned())
+                .collect(),
+            auto_approve: task.auto_approve,
+            estimated_minutes: task.estimated_minutes,
+            retry_policy: (task.retry_policy != RetryPolicy::default()).then(|| task.retry_policy.clone()),
+            tags: task.tags.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn composite() -> CompositeTask {
+        let schema = Task::new("Schema".into(), "".into(), "Add the table".into());
+        let api = Task::new("API".into(), "".into(), "Add the endpoint".into())
+            .with_dependencies(vec![schema.id.clone()]);
+        CompositeTask::new("Users".into(), "User accounts".into(), vec![schema, api])
+            .with_merge_method(Some(MergeMethod::Squash))
+    }
+
+    #[test]
+    fn test_round_trip_creates_new_ids() {
+        let original = composite();
+        let plan = Plan::from_composite(&original, Some("acme/app".into()));
+        assert_eq!(plan.tasks[1].dependencies, vec!["task-1"]);
+
+        // Stable: exporting again gives the same document
+        assert_eq!(Plan::from_composite(&original, Some("acme/app".into())), plan);
+
+        let imported = plan.to_composite().unwrap().unwrap();
+        assert_ne!(imported.id, original.id);
+        assert_ne!(imported.subtasks[0].id, original.subtasks[0].id);
+        assert_eq!(imported.subtasks[1].dependencies, vec![imported.subtasks[0].id.clone()]);
+        assert_eq!(imported.merge_method, Some(MergeMethod::Squash));
+        assert_eq!(Plan::from_composite(&imported, Some("acme/app".into())), plan);
+    }
+
+    #[test]
+    fn test_invalid_plans() {
+        let plan = Plan::from_composite(&composite(), None);
+
+        let mut newer = plan.clone();
+        newer.version = PLAN_VERSION + 1;
+        assert!(matches!(newer.to_tasks(), Err(Error::InvalidPlan(_))));
+
+        let mut unknown = plan.clone();
+        unknown.tasks[1].dependencies = vec!["task-9".into()];
+        assert!(matches!(unknown.to_tasks(), Err(Error::InvalidPlan(_))));
+
+        let mut cycle = plan;
+        cycle.tasks[0].dependencies = vec!["task-2".into()];
+        match cycle.to_tasks() {
+            Err(Error::DependencyCycle(keys)) => assert_eq!(keys, ["task-1", "task-2", "task-1"]),
+            other => panic!("expected a dependency cycle, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Resource limits and isolation for the Docker containers agents run in
+
+/// CPUs a container may use, e.g. `2` or `0.5`
+pub const CONTAINER_CPUS_ENV: &str = "AUTODEV_CONTAINER_CPUS";
+/// Memory limit in bytes or with a `k`/`m`/`g` suffix, e.g. `4g`
+pub const CONTAINER_MEMORY_ENV: &str = "AUTODEV_CONTAINER_MEMORY";
+pub const CONTAINER_PIDS_LIMIT_ENV: &str = "AUTODEV_CONTAINER_PIDS_LIMIT";
+pub const CONTAINER_READ_ONLY_ENV: &str = "AUTODEV_CONTAINER_READ_ONLY";
+pub const CONTAINER_NO_NEW_PRIVILEGES_ENV: &str = "AUTODEV_CONTAINER_NO_NEW_PRIVILEGES";
+pub const CONTAINER_DROP_CAPABILITIES_ENV: &str = "AUTODEV_CONTAINER_DROP_CAPABILITIES";
+/// Docker network to attach containers to; `none` disables networking
+pub const CONTAINER_NETWORK_ENV: &str = "AUTODEV_CONTAINER_NETWORK";
+
+/// How agent containers are constrained
+///
+/// Limits are off by default; privilege escalation and Linux capabilities
+/// are dropped unless turned back on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerSandbox {
+    pub cpus: Option<f64>,
+    pub memory_bytes: Option<i64>,
+    pub pids_limit: Option<i64>,
+    /// Mount the root filesystem read-only; the executor's working
+    /// directories are backed by tmpfs instead
+    pub read_only_root: bool,
+    pub no_new_privileges: bool,
+    /// Drop every Linux capability
+    pub drop_capabilities: bool,
+    /// Network mode, e.g. a restricted network name or `none`
+    pub network: Option<String>,
+}
+
+impl Default for ContainerSandbox {
+    fn default() -> Self {
+        Self {
+            cpus: None,
+            memory_bytes: None,
+            pids_limit: None,
+            read_only_root: false,
+            no_new_privileges: true,
+            drop_capabilities: true,
+            network: None,
+        }
+    }
+}
+
+impl ContainerSandbox {
+    /// Read the AUTODEV_CONTAINER_* variables; invalid values are ignored with a warning
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        Self {
+            cpus: env_value(CONTAINER_CPUS_ENV, |v| v.parse::<f64>().ok().filter(|n| *n > 0.0)),
+            memory_bytes: env_value(CONTAINER_MEMORY_ENV, |v| match parse_memory(v) {
+                Ok(bytes) => Some(bytes),
+                Err(e) => {
+                    tracing::warn!("{}: {}", CONTAINER_MEMORY_ENV, e);
+                    None
+                }
+            }),
+            pids_limit: env_value(CONTAINER_PIDS_LIMIT_ENV, |v| v.parse::<i64>().ok().filter(|n| *n > 0)),
+            read_only_root: env_value(CONTAINER_READ_ONLY_ENV, parse_bool).unwrap_or(defaults.read_only_root),
+            no_new_privileges: env_value(CONTAINER_NO_NEW_PRIVILEGES_ENV, parse_bool)
+                .unwrap_or(defaults.no_new_privileges),
+            drop_capabilities: env_value(CONTAINER_DROP_CAPABILITIES_ENV, parse_bool)
+                .unwrap_or(defaults.drop_capabilities),
+            network: env_value(CONTAINER_NETWORK_ENV, |v| Some(v.to_string())),
+        }
+    }
+
+    /// CPU quota in units of 10^-9 CPUs, as Docker's `NanoCpus` expects
+    pub fn nano_cpus(&self) -> Option<i64> {
+        self.cpus.map(|cpus| (cpus * 1e9) as i64)
+    }
+
+    pub fn network_disabled(&self) -> bool {
+        self.network.as_deref() == Some("none")
+    }
+
+    /// Docker `SecurityOpt` entries
+    pub fn security_opts(&self) -> Vec<String> {
+        if self.no_new_privileges {
+            vec!["no-new-privileges:true".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Capabilities to drop
+    pub fn cap_drop(&self) -> Vec<String> {
+        if self.drop_capabilities {
+            vec!["ALL".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// tmpfs mounts keeping `writable_paths` usable under a read-only root filesystem
+    pub fn tmpfs(&self, writable_paths: &[&str]) -> Vec<(String, String)> {
+        if !self.read_only_root {
+            return Vec::new();
+        }
+
+        writable_paths
+            .iter()
+            .map(|path| (path.to_string(), "rw,exec,mode=1777".to_string()))
+            .collect()
+    }
+
+    /// Resource limits and isolation options as a Docker host config
+    ///
+    /// `writable_paths` stay writable under a read-only root filesystem.
+    #[cfg(feature = "docker")]
+    pub fn host_config(&self, writable_paths: &[&str]) -> bollard::models::HostConfig {
+        let non_empty = |v: Vec<String>| if v.is_empty() { None } else { Some(v) };
+        let tmpfs: std::collections::HashMap<String, String> = self.tmpfs(writable_paths).into_iter().collect();
+
+        bollard::models::HostConfig {
+            nano_cpus: self.nano_cpus(),
+            memory: self.memory_bytes,
+            // 스왑으로 메모리 제한을 우회하지 못하도록 같은 값으로 설정
+            memory_swap: self.memory_bytes,
+            pids_limit: self.pids_limit,
+            readonly_rootfs: Some(self.read_only_root),
+            tmpfs: if tmpfs.is_empty() { None } else { Some(tmpfs) },
+            security_opt: non_empty(self.security_opts()),
+            cap_drop: non_empty(self.cap_drop()),
+            network_mode: self.network.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+fn env_value<T>(name: &str, parse: impl FnOnce(&str) -> Option<T>) -> Option<T> {
+    let value = std::env::var(name).ok()?;
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    let parsed = parse(value);
+    if parsed.is_none() {
+        tracing::warn!("Ignoring invalid {}: {}", name, value);
+    }
+    parsed
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse `512m`, `4g`, `1048576` etc. into bytes
+pub fn parse_memory(value: &str) -> Result<i64, String> {
+    let invalid = || format!("invalid size '{}'", value);
+    let lower = value.trim().to_ascii_lowercase();
+    let lower = lower.strip_suffix('b').unwrap_or(&lower);
+
+    let (number, unit) = match lower.char_indices().last().ok_or_else(invalid)? {
+        (i, 'k') => (&lower[..i], 1 << 10),
+        (i, 'm') => (&lower[..i], 1 << 20),
+        (i, 'g') => (&lower[..i], 1 << 30),
+        _ => (lower, 1),
+    };
+
+    let number = number.parse::<i64>().ok().filter(|n| *n > 0).ok_or_else(invalid)?;
+    number
+        .checked_mul(unit)
+        .ok_or_else(|| format!("size '{}' is too large", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory() {
+        assert_eq!(parse_memory("512m"), Ok(512 * 1024 * 1024));
+        assert_eq!(parse_memory("4G"), Ok(4 * 1024 * 1024 * 1024));
+        assert_eq!(parse_memory("2gb"), Ok(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_memory("1048576"), Ok(1048576));
+        assert!(parse_memory("lots").is_err());
+        assert!(parse_memory("0m").is_err());
+        assert!(parse_memory("").is_err());
+        assert_eq!(parse_memory("9000000000g"), Err("size '9000000000g' is too large".to_string()));
+    }
+
+    #[test]
+    fn test_sandbox_options() {
+        let sandbox = ContainerSandbox {
+            cpus: Some(1.5),
+            read_only_root: true,
+            network: Some("none".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(sandbox.nano_cpus(), Some(1_500_000_000));
+        assert!(sandbox.network_disabled());
+        assert_eq!(sandbox.security_opts(), vec!["no-new-privileges:true"]);
+        assert_eq!(sandbox.cap_drop(), vec!["ALL"]);
+        assert_eq!(
+            sandbox.tmpfs(&["/tmp"]),
+            vec![("/tmp".to_string(), "rw,exec,mode=1777".to_string())]
+        );
+        assert!(ContainerSandbox::default().tmpfs(&["/tmp"]).is_empty());
+    }
+
+    #[cfg(feature = "docker")]
+    #[test]
+    fn test_host_config() {
+        let sandbox = ContainerSandbox {
+            memory_bytes: Some(1 << 30),
+            read_only_root: true,
+            ..Default::default()
+        };
+
+        let config = sandbox.host_config(&["/tmp"]);
+        assert_eq!(config.memory, Some(1 << 30));
+        assert_eq!(config.memory_swap, Some(1 << 30));
+        assert_eq!(config.readonly_rootfs, Some(true));
+        assert!(config.tmpfs.unwrap().contains_key("/tmp"));
+        assert_eq!(config.cap_drop, Some(vec!["ALL".to_string()]));
+        assert_eq!(config.network_mode, None);
+    }
+}
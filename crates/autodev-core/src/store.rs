@@ -0,0 +1,25 @@
+use crate::{CompositeTask, Result, Task};
+use async_trait::async_trait;
+
+/// Engine state loaded from a persistent store on startup
+#[derive(Debug, Clone, Default)]
+pub struct EngineSnapshot {
+    pub tasks: Vec<Task>,
+    pub composite_tasks: Vec<CompositeTask>,
+}
+
+/// Persistence backend for AutoDevEngine
+///
+/// The engine keeps its in-memory maps as the source of truth at runtime and
+/// writes every mutation through to the store so state survives restarts.
+#[async_trait]
+pub trait TaskStore: Send + Sync {
+    /// Load tasks and composite tasks the orchestrator still needs to track
+    async fn load_snapshot(&self) -> Result<EngineSnapshot>;
+
+    /// Persist the current state of a task
+    async fn persist_task(&self, task: &Task) -> Result<()>;
+
+    /// Persist a composite task together with its subtasks
+    async fn persist_composite_task(&self, composite_task: &CompositeTask) -> Result<()>;
+}
@@ -12,6 +12,64 @@ pub enum TaskStatus {
     Completed,
     Failed,
     Cancelled,
+    /// Stopped by a shutdown before finishing; the worker puts it back to Pending
+    Interrupted,
+    /// Paused because an AI budget ran out; retried once the budget is raised
+    BudgetExceeded,
+    /// A dependency failed; waits until that dependency is retried
+    Blocked,
+}
+
+impl std::str::FromStr for TaskStatus {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Accepts the stored form (InProgress) as well as in_progress / in-progress
+        match s.to_ascii_lowercase().replace(['_', '-'], "").as_str() {
+            "pending" => Ok(TaskStatus::Pending),
+            "waitingdependencies" => Ok(TaskStatus::WaitingDependencies),
+            "ready" => Ok(TaskStatus::Ready),
+            "inprogress" => Ok(TaskStatus::InProgress),
+            "completed" => Ok(TaskStatus::Completed),
+            "failed" => Ok(TaskStatus::Failed),
+            "cancelled" => Ok(TaskStatus::Cancelled),
+            "interrupted" => Ok(TaskStatus::Interrupted),
+            "budgetexceeded" => Ok(TaskStatus::BudgetExceeded),
+            "blocked" => Ok(TaskStatus::Blocked),
+            _ => Err(crate::Error::InvalidTaskState(s.to_string())),
+        }
+    }
+}
+
+impl TaskStatus {
+    /// Whether a task may move from this status to `next`
+    ///
+    /// Staying in the same status is always allowed (e.g. a repeated callback).
+    pub fn can_transition_to(self, next: TaskStatus) -> bool {
+        use TaskStatus::*;
+
+        self == next
+            || matches!(
+                (self, next),
+                // Not started yet; a budget check may pause the task before dispatch
+                (
+                    Pending | WaitingDependencies | Ready,
+                    Pending | WaitingDependencies | Ready | InProgress | Failed | Cancelled | BudgetExceeded | Blocked
+                ) | (InProgress, Completed | Failed | Cancelled | Interrupted | BudgetExceeded)
+                    // Retried, or a failed workflow run re-run from the Actions UI
+                    | (Failed, Pending | InProgress)
+                    // Resumed with its composite task
+                    | (Cancelled, Pending)
+                    | (Interrupted | BudgetExceeded, Pending | Failed | Cancelled)
+                    // The failed dependency was retried
+                    | (Blocked, Pending | WaitingDependencies | Cancelled)
+            )
+    }
+
+    /// Completed, failed or cancelled
+    pub fn is_finished(self) -> bool {
+        matches!(self, TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -20,6 +78,129 @@ pub enum TaskType {
     Composite,
 }
 
+impl std::str::FromStr for TaskType {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Simple" => Ok(TaskType::Simple),
+            "Composite" => Ok(TaskType::Composite),
+            _ => Err(crate::Error::InvalidTaskState(format!("Unknown task type: {}", s))),
+        }
+    }
+}
+
+/// Why a task attempt failed, used to decide whether it is retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FailureKind {
+    /// GitHub Actions workflow concluded with failure/timed_out
+    WorkflowFailure,
+    /// Local execution failed (container exit non-zero, dispatch error)
+    ExecutionError,
+    /// Task exceeded the stall timeout
+    Timeout,
+}
+
+/// Where a task runs: a provider pipeline or an executor on the server's host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionBackend {
+    /// GitHub Actions (or GitLab CI) workflow
+    Actions,
+    /// Worker container on the server's Docker daemon
+    Docker,
+    /// Job on a Kubernetes cluster
+    K8s,
+    /// Claude CLI subprocess on the server's host
+    Process,
+}
+
+impl ExecutionBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionBackend::Actions => "actions",
+            ExecutionBackend::Docker => "docker",
+            ExecutionBackend::K8s => "k8s",
+            ExecutionBackend::Process => "process",
+        }
+    }
+
+    /// Whether the server runs the task itself instead of dispatching a pipeline
+    pub fn is_local(&self) -> bool {
+        !matches!(self, ExecutionBackend::Actions)
+    }
+}
+
+impl std::fmt::Display for ExecutionBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ExecutionBackend {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "actions" | "github-actions" | "ci" => Ok(ExecutionBackend::Actions),
+            "docker" => Ok(ExecutionBackend::Docker),
+            "k8s" | "kubernetes" => Ok(ExecutionBackend::K8s),
+            "process" | "native" => Ok(ExecutionBackend::Process),
+            _ => Err(crate::Error::InvalidTaskState(format!("Unknown execution backend: {}", s))),
+        }
+    }
+}
+
+/// Automatic retry configuration for a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one (1 = never retry)
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub backoff_secs: u64,
+    /// Multiplier applied to the delay for each subsequent retry
+    pub backoff_multiplier: u32,
+    /// Failure kinds that trigger a retry
+    pub retry_on: Vec<FailureKind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff_secs: 30,
+            backoff_multiplier: 2,
+            retry_on: vec![
+                FailureKind::WorkflowFailure,
+                FailureKind::ExecutionError,
+                FailureKind::Timeout,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Whether another attempt is allowed after `attempts` tries failed with `kind`
+    pub fn should_retry(&self, attempts: u32, kind: FailureKind) -> bool {
+        attempts < self.max_attempts && self.retry_on.contains(&kind)
+    }
+
+    /// Delay before retrying after `attempts` tries (exponential backoff)
+    pub fn backoff(&self, attempts: u32) -> std::time::Duration {
+        let exponent = attempts.saturating_sub(1);
+        let factor = u64::from(self.backoff_multiplier.max(1)).saturating_pow(exponent);
+        std::time::Duration::from_secs(self.backoff_secs.saturating_mul(factor))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: String,
@@ -33,9 +214,62 @@ pub struct Task {
     pub started_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
     pub pr_url: Option<String>,
+    #[serde(default)]
+    pub pr_number: Option<u64>,
     pub workflow_run_id: Option<String>,
     pub error: Option<String>,
     pub auto_approve: bool,
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    /// Number of times the task has been dispatched
+    #[serde(default)]
+    pub attempts: u32,
+    /// Estimated duration from decomposition (minutes)
+    #[serde(default)]
+    pub estimated_minutes: Option<u32>,
+    /// GitHub issue the task was started from
+    #[serde(default)]
+    pub issue_number: Option<u64>,
+    #[serde(default)]
+    pub issue_url: Option<String>,
+    /// User (API key) that created the task
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// Failed dependency that blocked or skipped this task
+    #[serde(default)]
+    pub blocked_by: Option<String>,
+    /// Labels for filtering and grouping (see [`normalize_tags`])
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Backend the task runs on (None: the repository's or server's default)
+    #[serde(default)]
+    pub execution_backend: Option<ExecutionBackend>,
+    /// Repository the task works on, when known to the engine
+    #[serde(default)]
+    pub repository_owner: Option<String>,
+    #[serde(default)]
+    pub repository_name: Option<String>,
+}
+
+/// Trimmed, lowercase tags without empties or duplicates, in first-seen order
+pub fn normalize_tags<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.as_ref().trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Prompt compared for duplicate detection: lower-case, whitespace collapsed
+pub fn normalize_prompt(prompt: &str) -> String {
+    prompt.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
 }
 
 impl Task {
@@ -52,12 +286,62 @@ impl Task {
             started_at: None,
             completed_at: None,
             pr_url: None,
+            pr_number: None,
             workflow_run_id: None,
             error: None,
             auto_approve: false,
+            retry_policy: RetryPolicy::default(),
+            attempts: 0,
+            estimated_minutes: None,
+            issue_number: None,
+            issue_url: None,
+            created_by: None,
+            blocked_by: None,
+            tags: Vec::new(),
+            execution_backend: None,
+            repository_owner: None,
+            repository_name: None,
         }
     }
 
+    /// Record the repository the task works on
+    pub fn with_repository(mut self, owner: String, name: String) -> Self {
+        self.repository_owner = Some(owner);
+        self.repository_name = Some(name);
+        self
+    }
+
+    /// Link the task to the issue it was started from
+    pub fn with_issue(mut self, number: u64, url: String) -> Self {
+        self.issue_number = Some(number);
+        self.issue_url = Some(url);
+        self
+    }
+
+    pub fn with_created_by(mut self, created_by: Option<String>) -> Self {
+        self.created_by = created_by;
+        self
+    }
+
+    pub fn with_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.tags = normalize_tags(tags);
+        self
+    }
+
+    pub fn with_execution_backend(mut self, backend: Option<ExecutionBackend>) -> Self {
+        self.execution_backend = backend;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
     pub fn with_dependencies(mut self, deps: Vec<String>) -> Self {
         let is_empty = deps.is_empty();
         self.dependencies = deps;
@@ -73,6 +357,48 @@ impl Task {
         self.dependencies.iter().all(|dep| completed_tasks.contains(dep))
     }
 
+    /// Cancelled because a dependency failed (the skip failure policy)
+    pub fn is_skipped(&self) -> bool {
+        self.status == TaskStatus::Cancelled && self.blocked_by.is_some()
+    }
+
+    /// Move to `status` if the transition is legal, stamping the times
+    ///
+    /// Entering InProgress is a new attempt: `attempts` is counted and
+    /// `started_at` set. Finishing sets `completed_at`; going back to a
+    /// not-started status clears it.
+    pub fn transition_to(&mut self, status: TaskStatus) -> crate::Result<()> {
+        if !self.status.can_transition_to(status) {
+            return Err(crate::Error::InvalidTransition {
+                task_id: self.id.clone(),
+                from: self.status,
+                to: status,
+            });
+        }
+        if self.status == status {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        match status {
+            TaskStatus::InProgress => {
+                self.attempts += 1;
+                self.started_at = Some(now);
+                self.completed_at = None;
+            }
+            TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled => {
+                self.completed_at = Some(now);
+            }
+            TaskStatus::Pending | TaskStatus::WaitingDependencies | TaskStatus::Ready => {
+                self.completed_at = None;
+            }
+            TaskStatus::Interrupted | TaskStatus::BudgetExceeded | TaskStatus::Blocked => {}
+        }
+        self.status = status;
+
+        Ok(())
+    }
+
     pub fn start(&mut self) {
         self.status = TaskStatus::InProgress;
         self.started_at = Some(Utc::now());
@@ -117,6 +443,116 @@ mod tests {
         assert_eq!(task.dependencies.len(), 1);
     }
 
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            TaskStatus::Pending,
+            TaskStatus::WaitingDependencies,
+            TaskStatus::InProgress,
+            TaskStatus::Completed,
+            TaskStatus::Cancelled,
+            TaskStatus::Blocked,
+        ] {
+            let parsed: TaskStatus = format!("{:?}", status).parse().unwrap();
+            assert_eq!(parsed, status);
+        }
+
+        assert_eq!("in_progress".parse::<TaskStatus>().unwrap(), TaskStatus::InProgress);
+        assert_eq!("failed".parse::<TaskStatus>().unwrap(), TaskStatus::Failed);
+        assert!("Unknown".parse::<TaskStatus>().is_err());
+    }
+
+    #[test]
+    fn test_every_transition() {
+        use TaskStatus::*;
+
+        let all = [
+            Pending,
+            WaitingDependencies,
+            Ready,
+            InProgress,
+            Completed,
+            Failed,
+            Cancelled,
+            Interrupted,
+            BudgetExceeded,
+            Blocked,
+        ];
+        let allowed = [
+            (Pending, vec![WaitingDependencies, Ready, InProgress, Failed, Cancelled, BudgetExceeded, Blocked]),
+            (WaitingDependencies, vec![Pending, Ready, InProgress, Failed, Cancelled, BudgetExceeded, Blocked]),
+            (Ready, vec![Pending, WaitingDependencies, InProgress, Failed, Cancelled, BudgetExceeded, Blocked]),
+            (InProgress, vec![Completed, Failed, Cancelled, Interrupted, BudgetExceeded]),
+            (Completed, vec![]),
+            (Failed, vec![Pending, InProgress]),
+            (Cancelled, vec![Pending]),
+            (Interrupted, vec![Pending, Failed, Cancelled]),
+            (BudgetExceeded, vec![Pending, Failed, Cancelled]),
+            (Blocked, vec![Pending, WaitingDependencies, Cancelled]),
+        ];
+
+        for (from, targets) in &allowed {
+            for to in all {
+                let expected = *from == to || targets.contains(&to);
+                assert_eq!(from.can_transition_to(to), expected, "{:?} -> {:?}", from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transition_stamps_times() {
+        let mut task = Task::new("Test".to_string(), "".to_string(), "".to_string());
+        assert!(task.started_at.is_none());
+
+        task.transition_to(TaskStatus::InProgress).unwrap();
+        assert_eq!(task.attempts, 1);
+        assert!(task.started_at.is_some());
+        assert!(task.completed_at.is_none());
+
+        // Repeating the status is not a new attempt
+        task.transition_to(TaskStatus::InProgress).unwrap();
+        assert_eq!(task.attempts, 1);
+
+        task.transition_to(TaskStatus::Failed).unwrap();
+        assert!(task.completed_at.is_some());
+        task.transition_to(TaskStatus::Pending).unwrap();
+        assert!(task.completed_at.is_none());
+
+        task.transition_to(TaskStatus::InProgress).unwrap();
+        task.transition_to(TaskStatus::Completed).unwrap();
+        assert_eq!(task.attempts, 2);
+        assert!(task.status.is_finished());
+
+        match task.transition_to(TaskStatus::InProgress) {
+            Err(crate::Error::InvalidTransition { from, to, .. }) => {
+                assert_eq!(from, TaskStatus::Completed);
+                assert_eq!(to, TaskStatus::InProgress);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(task.status, TaskStatus::Completed);
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.should_retry(1, FailureKind::WorkflowFailure));
+        assert!(policy.should_retry(2, FailureKind::Timeout));
+        assert!(!policy.should_retry(3, FailureKind::WorkflowFailure));
+        assert!(!RetryPolicy::none().should_retry(1, FailureKind::ExecutionError));
+
+        let workflow_only = RetryPolicy {
+            retry_on: vec![FailureKind::WorkflowFailure],
+            ..RetryPolicy::default()
+        };
+        assert!(!workflow_only.should_retry(1, FailureKind::Timeout));
+
+        assert_eq!(policy.backoff(1).as_secs(), 30);
+        assert_eq!(policy.backoff(2).as_secs(), 60);
+        assert_eq!(policy.backoff(3).as_secs(), 120);
+    }
+
     #[test]
     fn test_can_start() {
         let task = Task::new("".to_string(), "".to_string(), "".to_string())
@@ -128,4 +564,39 @@ mod tests {
         completed.insert("dep1".to_string());
         assert!(task.can_start(&completed));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_tags() {
+        let task = Task::new("".to_string(), "".to_string(), "".to_string())
+            .with_tags([" Auth", "backend", "", "auth", "BACKEND "]);
+        assert_eq!(task.tags, vec!["auth", "backend"]);
+
+        // Tasks stored before tags existed
+        let mut value = serde_json::to_value(&task).unwrap();
+        value.as_object_mut().unwrap().remove("tags");
+        assert!(serde_json::from_value::<Task>(value).unwrap().tags.is_empty());
+    }
+
+    #[test]
+    fn test_parse_execution_backend() {
+        for backend in [
+            ExecutionBackend::Actions,
+            ExecutionBackend::Docker,
+            ExecutionBackend::K8s,
+            ExecutionBackend::Process,
+        ] {
+            assert_eq!(backend.as_str().parse::<ExecutionBackend>().unwrap(), backend);
+        }
+
+        assert_eq!(" Kubernetes ".parse::<ExecutionBackend>().unwrap(), ExecutionBackend::K8s);
+        assert!("lambda".parse::<ExecutionBackend>().is_err());
+        assert!(!ExecutionBackend::Actions.is_local());
+        assert!(ExecutionBackend::Process.is_local());
+    }
+
+    #[test]
+    fn test_normalize_prompt() {
+        assert_eq!(normalize_prompt("  Add a\n\tlogin   PAGE "), "add a login page");
+        assert_ne!(normalize_prompt("Add a login page"), normalize_prompt("Add a logout page"));
+    }
+}
@@ -0,0 +1,280 @@
+//! Gantt-style timeline of a composite task's execution
+//!
+//! Attempts are read from execution log events: STARTED or WORKFLOW_TRIGGERED
+//! opens one; COMPLETED, FAILED, CANCELLED or TIMEOUT closes it. Subtasks
+//! without such events fall back to their own start and completion times.
+//!
+//! A subtask is ready once its last dependency finished (subtasks without
+//! dependencies: once the first subtask started), so the wait before its first
+//! attempt shows time spent on batch approval, parallelism limits and queueing.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{CompositeTask, TaskStatus};
+
+const START_EVENTS: &[&str] = &["STARTED", "WORKFLOW_TRIGGERED"];
+const END_EVENTS: &[&str] = &["COMPLETED", "FAILED", "CANCELLED", "TIMEOUT"];
+
+/// Execution log event of a subtask
+#[derive(Debug, Clone)]
+pub struct LoggedEvent {
+    pub task_id: String,
+    pub event_type: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// One run of a subtask
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub started_at: DateTime<Utc>,
+    /// None while running
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Event (or status) that ended the attempt
+    pub outcome: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskTimeline {
+    pub task_id: String,
+    pub title: String,
+    /// 1-based
+    pub batch: usize,
+    pub status: TaskStatus,
+    /// When every dependency had finished
+    pub ready_at: Option<DateTime<Utc>>,
+    /// Start of the first attempt
+    pub started_at: Option<DateTime<Utc>>,
+    /// End of the last attempt, None while one is running
+    pub finished_at: Option<DateTime<Utc>>,
+    /// From ready to the first attempt
+    pub wait_ms: Option<i64>,
+    /// From the first attempt to the end of the last one
+    pub run_ms: Option<i64>,
+    pub attempts: Vec<Attempt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchTimeline {
+    /// 1-based
+    pub batch: usize,
+    pub task_ids: Vec<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Set once every task of the batch finished
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timeline {
+    pub composite_task_id: String,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Set once every subtask finished
+    pub finished_at: Option<DateTime<Utc>>,
+    pub batches: Vec<BatchTimeline>,
+    /// Subtasks in batch order
+    pub tasks: Vec<TaskTimeline>,
+}
+
+impl Timeline {
+    /// Timeline of `composite_task` from the execution log `events` of its subtasks
+    pub fn build(composite_task: &CompositeTask, events: &[LoggedEvent]) -> Self {
+        let mut events: Vec<&LoggedEvent> = events.iter().collect();
+        events.sort_by_key(|event| event.timestamp);
+
+        let batches = composite_task.get_parallel_batches();
+        let mut tasks: Vec<TaskTimeline> = Vec::new();
+        for (index, batch) in batches.iter().enumerate() {
+            for task in batch {
+                let task_events: Vec<&LoggedEvent> =
+                    events.iter().copied().filter(|event| event.task_id == task.id).collect();
+                let attempts = attempts(task, &task_events);
+                let finished_at = attempts.last().and_then(|attempt| attempt.finished_at);
+
+                tasks.push(TaskTimeline {
+                    task_id: task.id.clone(),
+                    title: task.title.clone(),
+                    batch: index + 1,
+                    status: task.status,
+                    ready_at: None,
+                    started_at: attempts.first().map(|attempt| attempt.started_at),
+                    finished_at,
+                    wait_ms: None,
+                    run_ms: None,
+                    attempts,
+                });
+            }
+        }
+
+        let started_at = tasks.iter().filter_map(|task| task.started_at).min();
+        let finished: HashMap<String, Option<DateTime<Utc>>> = tasks
+            .iter()
+            .map(|task| (task.task_id.clone(), task.finished_at))
+            .collect();
+        for timeline in &mut tasks {
+            let Some(task) = composite_task.subtasks.iter().find(|task| task.id == timeline.task_id) else {
+                continue;
+            };
+            timeline.ready_at = if task.dependencies.is_empty() {
+                started_at
+            } else {
+                task.dependencies
+                    .iter()
+                    .map(|dependency| finished.get(dependency).copied().flatten())
+                    .collect::<Option<Vec<_>>>()
+                    .and_then(|times| times.into_iter().max())
+            };
+            timeline.wait_ms = timeline
+                .ready_at
+                .zip(timeline.started_at)
+                .map(|(ready_at, started_at)| (started_at - ready_at).num_milliseconds().max(0));
+            timeline.run_ms = timeline
+                .started_at
+                .zip(timeline.finished_at)
+                .map(|(started_at, finished_at)| (finished_at - started_at).num_milliseconds());
+        }
+
+        let batches: Vec<BatchTimeline> = batches
+            .iter()
+            .enumerate()
+            .map(|(index, batch)| {
+                let spans: Vec<&TaskTimeline> = tasks.iter().filter(|task| task.batch == index + 1).collect();
+                BatchTimeline {
+                    batch: index + 1,
+                    task_ids: batch.iter().map(|task| task.id.clone()).collect(),
+                    started_at: spans.iter().filter_map(|task| task.started_at).min(),
+                    finished_at: latest(&spans),
+                }
+            })
+            .collect();
+
+        let all: Vec<&TaskTimeline> = tasks.iter().collect();
+        Self {
+            composite_task_id: composite_task.id.clone(),
+            started_at,
+            finished_at: latest(&all),
+            batches,
+            tasks,
+        }
+    }
+}
+
+/// Latest end of `tasks`, None unless every one of them finished
+fn latest(tasks: &[&TaskTimeline]) -> Option<DateTime<Utc>> {
+    tasks
+        .iter()
+        .map(|task| task.finished_at)
+        .collect::<Option<Vec<_>>>()
+        .and_then(|times| times.into_iter().max())
+}
+
+/// Attempts of `task` from its events (oldest first)
+fn attempts(task: &crate::Task, events: &[&LoggedEvent]) -> Vec<Attempt> {
+    let mut attempts = Vec::new();
+    let mut running: Option<Attempt> = None;
+
+    for event in events {
+        let event_type = event.event_type.to_ascii_uppercase();
+        if START_EVENTS.contains(&event_type.as_str()) {
+            // Workers log STARTED and WORKFLOW_TRIGGERED for the same attempt
+            running.get_or_insert(Attempt {
+                started_at: event.timestamp,
+                finished_at: None,
+                outcome: None,
+            });
+        } else if END_EVENTS.contains(&event_type.as_str()) {
+            if let Some(mut attempt) = running.take() {
+                attempt.finished_at = Some(event.timestamp);
+                attempt.outcome = Some(event_type);
+                attempts.push(attempt);
+            }
+        }
+    }
+
+    // Finished without a closing event (e.g. completed by a workflow callback)
+    if let Some(mut attempt) = running {
+        if task.status.is_finished() {
+            attempt.finished_at = task.completed_at.filter(|completed_at| *completed_at >= attempt.started_at);
+            attempt.outcome = attempt.finished_at.map(|_| format!("{:?}", task.status).to_ascii_uppercase());
+        }
+        attempts.push(attempt);
+    }
+
+    if attempts.is_empty() {
+        if let Some(started_at) = task.started_at {
+            attempts.push(Attempt {
+                started_at,
+                finished_at: task.completed_at,
+                outcome: task.completed_at.map(|_| format!("{:?}", task.status).to_ascii_uppercase()),
+            });
+        }
+    }
+
+    attempts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Task;
+    use chrono::Duration;
+
+    #[test]
+    fn test_timeline() {
+        let schema = Task::new("Schema".into(), "".into(), "".into());
+        let api = Task::new("API".into(), "".into(), "".into()).with_dependencies(vec![schema.id.clone()]);
+        let mut composite = CompositeTask::new("Users".into(), "".into(), vec![schema.clone(), api.clone()]);
+        composite.subtasks[0].status = TaskStatus::Completed;
+        composite.subtasks[1].status = TaskStatus::InProgress;
+
+        let t0 = Utc::now() - Duration::hours(1);
+        let at = |minutes: i64| t0 + Duration::minutes(minutes);
+        let event = |task: &Task, event_type: &str, minutes: i64| LoggedEvent {
+            task_id: task.id.clone(),
+            event_type: event_type.to_string(),
+            timestamp: at(minutes),
+        };
+        let events = vec![
+            event(&schema, "FAILED", 5),
+            event(&schema, "STARTED", 0),
+            event(&schema, "WORKFLOW_TRIGGERED", 1),
+            event(&schema, "RETRY_SCHEDULED", 5),
+            event(&schema, "STARTED", 6),
+            event(&schema, "COMPLETED", 10),
+            event(&api, "STARTED", 12),
+        ];
+
+        let timeline = Timeline::build(&composite, &events);
+        assert_eq!(timeline.started_at, Some(at(0)));
+        assert_eq!(timeline.finished_at, None);
+
+        let first = &timeline.tasks[0];
+        assert_eq!(first.attempts.len(), 2);
+        assert_eq!(first.attempts[0].outcome.as_deref(), Some("FAILED"));
+        assert_eq!(first.wait_ms, Some(0));
+        assert_eq!(first.run_ms, Some(10 * 60_000));
+
+        let second = &timeline.tasks[1];
+        assert_eq!(second.batch, 2);
+        assert_eq!(second.ready_at, Some(at(10)));
+        assert_eq!(second.wait_ms, Some(2 * 60_000));
+        assert_eq!(second.finished_at, None);
+
+        assert_eq!(timeline.batches[0].finished_at, Some(at(10)));
+        assert_eq!(timeline.batches[1].started_at, Some(at(12)));
+        assert_eq!(timeline.batches[1].finished_at, None);
+    }
+
+    #[test]
+    fn test_timeline_without_events() {
+        let mut task = Task::new("Only".into(), "".into(), "".into());
+        task.started_at = Some(Utc::now() - Duration::minutes(3));
+        task.completed_at = Some(Utc::now());
+        task.status = TaskStatus::Completed;
+        let composite = CompositeTask::new("One".into(), "".into(), vec![task]);
+
+        let timeline = Timeline::build(&composite, &[]);
+        assert_eq!(timeline.tasks[0].attempts[0].outcome.as_deref(), Some("COMPLETED"));
+        assert!(timeline.finished_at.is_some());
+    }
+}
@@ -1,3 +1,4 @@
+use autodev_core::ErrorClass;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -17,6 +18,12 @@ pub enum Error {
     #[error("Migration error: {0}")]
     Migration(String),
 
+    #[error("Secret error: {0}")]
+    Secret(String),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
     #[error("SQLx error: {0}")]
     Sqlx(#[from] sqlx::Error),
 
@@ -24,4 +31,33 @@ pub enum Error {
     Other(#[from] anyhow::Error),
 }
 
+impl Error {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            Error::Connection(_) => ErrorClass::Transient,
+            Error::TaskNotFound(_) | Error::CompositeTaskNotFound(_) => ErrorClass::NotFound,
+            Error::Sqlx(e) => sqlx_class(e),
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+fn sqlx_class(error: &sqlx::Error) -> ErrorClass {
+    match error {
+        sqlx::Error::RowNotFound => ErrorClass::NotFound,
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::WorkerCrashed => {
+            ErrorClass::Transient
+        }
+        sqlx::Error::Database(e) => match e.code().as_deref() {
+            // unique_violation
+            Some("23505") => ErrorClass::Conflict,
+            // serialization_failure, deadlock_detected, connection exceptions
+            Some("40001" | "40P01") => ErrorClass::Transient,
+            Some(code) if code.starts_with("08") => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        },
+        _ => ErrorClass::Permanent,
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
@@ -1,8 +1,16 @@
+pub mod migrations;
 pub mod models;
 pub mod repository;
+pub mod secrets;
 pub mod error;
+pub mod store;
 
 // Re-exports
-pub use models::{TaskRecord, CompositeTaskRecord, ExecutionLog, Metrics, AggregateStats};
-pub use repository::Database;
+pub use models::{
+    AggregateStats, AuditEvent, AuditQuery, NewAuditEvent, AiUsage, CompositeTaskRecord, DailyStats, ExampleRecord, ExecutionLog, Metrics, NewExample, NewMetrics, RepositoryStats,
+    NewSchedule, ScheduleRecord, SecretRecord, StatsFilter, StatsGranularity, TagStats, TaskArtifact, TaskLog, TaskPayload, TaskQuery, TaskRecord, TaskSort,
+    TimeseriesStats,
+};
+pub use repository::{Database, DEFAULT_TIMESERIES_DAYS};
+pub use secrets::{SecretCipher, SECRETS_KEY_ENV};
 pub use error::{Error, Result};
\ No newline at end of file
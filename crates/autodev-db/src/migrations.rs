@@ -0,0 +1,446 @@
+/// Versioned schema change applied by `Database::migrate`
+///
+/// Migrations run in version order, each exactly once; applied versions are
+/// recorded in `schema_migrations`. Never edit a released migration, add a
+/// new one instead. The statements up to version 6 are idempotent so that
+/// databases created by the old `init_schema` can be brought under version
+/// control without errors.
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub statements: &'static [&'static str],
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS tasks (
+                id VARCHAR(255) PRIMARY KEY,
+                title TEXT NOT NULL,
+                description TEXT NOT NULL,
+                prompt TEXT NOT NULL,
+                task_type VARCHAR(50) NOT NULL,
+                status VARCHAR(50) NOT NULL,
+                dependencies TEXT[] NOT NULL DEFAULT '{}',
+                repository_owner VARCHAR(255) NOT NULL,
+                repository_name VARCHAR(255) NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL,
+                started_at TIMESTAMPTZ,
+                completed_at TIMESTAMPTZ,
+                pr_url TEXT,
+                workflow_run_id VARCHAR(255),
+                error TEXT,
+                auto_approve BOOLEAN NOT NULL DEFAULT FALSE
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS composite_tasks (
+                id VARCHAR(255) PRIMARY KEY,
+                title TEXT NOT NULL,
+                description TEXT NOT NULL,
+                auto_approve BOOLEAN NOT NULL DEFAULT FALSE,
+                repository_owner VARCHAR(255) NOT NULL,
+                repository_name VARCHAR(255) NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL,
+                completed_at TIMESTAMPTZ
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS composite_task_subtasks (
+                composite_task_id VARCHAR(255) NOT NULL,
+                subtask_id VARCHAR(255) NOT NULL,
+                subtask_order INTEGER NOT NULL,
+                PRIMARY KEY (composite_task_id, subtask_id),
+                FOREIGN KEY (composite_task_id) REFERENCES composite_tasks(id),
+                FOREIGN KEY (subtask_id) REFERENCES tasks(id)
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS execution_logs (
+                id SERIAL PRIMARY KEY,
+                task_id VARCHAR(255) NOT NULL,
+                event_type VARCHAR(100) NOT NULL,
+                message TEXT NOT NULL,
+                timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                FOREIGN KEY (task_id) REFERENCES tasks(id)
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS metrics (
+                id SERIAL PRIMARY KEY,
+                task_id VARCHAR(255) NOT NULL,
+                execution_time_ms BIGINT NOT NULL,
+                files_changed INTEGER NOT NULL DEFAULT 0,
+                lines_added INTEGER NOT NULL DEFAULT 0,
+                lines_removed INTEGER NOT NULL DEFAULT 0,
+                ai_tokens_used INTEGER NOT NULL DEFAULT 0,
+                timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                FOREIGN KEY (task_id) REFERENCES tasks(id)
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status)",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_created_at ON tasks(created_at DESC)",
+            "CREATE INDEX IF NOT EXISTS idx_execution_logs_task_id ON execution_logs(task_id)",
+        ],
+    },
+    Migration {
+        version: 2,
+        name: "task_retries",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS attempts INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS retry_policy TEXT",
+        ],
+    },
+    Migration {
+        version: 3,
+        name: "task_estimates",
+        statements: &["ALTER TABLE tasks ADD COLUMN IF NOT EXISTS estimated_minutes INTEGER"],
+    },
+    Migration {
+        version: 4,
+        name: "composite_review_state",
+        statements: &[
+            "ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS review_state VARCHAR(50) NOT NULL DEFAULT 'Approved'",
+        ],
+    },
+    Migration {
+        version: 5,
+        name: "composite_batch_approvals",
+        statements: &[
+            "ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS approved_batches INTEGER[] NOT NULL DEFAULT '{}'",
+        ],
+    },
+    Migration {
+        version: 6,
+        name: "composite_max_parallel_tasks",
+        statements: &["ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS max_parallel_tasks INTEGER"],
+    },
+    Migration {
+        version: 7,
+        name: "task_payloads",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS task_payloads (
+                task_id VARCHAR(255) PRIMARY KEY,
+                composite_task_id VARCHAR(255),
+                title TEXT NOT NULL,
+                prompt TEXT NOT NULL,
+                base_branch VARCHAR(255) NOT NULL,
+                target_branch VARCHAR(255) NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 8,
+        name: "composite_merge_method",
+        statements: &["ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS merge_method VARCHAR(20)"],
+    },
+    Migration {
+        version: 9,
+        name: "task_logs",
+        statements: &[
+            // No foreign key: branch fixes and conflict resolutions log under derived IDs
+            r#"
+            CREATE TABLE IF NOT EXISTS task_logs (
+                id BIGSERIAL PRIMARY KEY,
+                task_id VARCHAR(255) NOT NULL,
+                line TEXT NOT NULL,
+                timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_task_logs_task_id ON task_logs(task_id, id)",
+        ],
+    },
+    Migration {
+        version: 10,
+        name: "task_claims",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS claimed_by VARCHAR(255)",
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMPTZ",
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS heartbeat_at TIMESTAMPTZ",
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS lease_expires_at TIMESTAMPTZ",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_lease_expires_at ON tasks(lease_expires_at) WHERE lease_expires_at IS NOT NULL",
+        ],
+    },
+    Migration {
+        version: 11,
+        name: "composite_base_branch",
+        statements: &["ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS base_branch VARCHAR(255)"],
+    },
+    Migration {
+        version: 12,
+        name: "task_issues",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS issue_number BIGINT",
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS issue_url TEXT",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_issue ON tasks(repository_owner, repository_name, issue_number) WHERE issue_number IS NOT NULL",
+        ],
+    },
+    Migration {
+        version: 13,
+        name: "metrics_ai_cost",
+        statements: &[
+            "ALTER TABLE metrics ADD COLUMN IF NOT EXISTS ai_input_tokens BIGINT NOT NULL DEFAULT 0",
+            "ALTER TABLE metrics ADD COLUMN IF NOT EXISTS ai_output_tokens BIGINT NOT NULL DEFAULT 0",
+            "ALTER TABLE metrics ADD COLUMN IF NOT EXISTS ai_cost_usd DOUBLE PRECISION NOT NULL DEFAULT 0",
+            "CREATE INDEX IF NOT EXISTS idx_metrics_task_id ON metrics(task_id)",
+        ],
+    },
+    Migration {
+        version: 14,
+        name: "few_shot_examples",
+        statements: &[
+            // Examples without a repository are used for every repository
+            r#"
+            CREATE TABLE IF NOT EXISTS few_shot_examples (
+                id BIGSERIAL PRIMARY KEY,
+                repository_owner VARCHAR(255),
+                repository_name VARCHAR(255),
+                domain VARCHAR(50) NOT NULL,
+                user_prompt TEXT NOT NULL,
+                assistant_response TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_few_shot_examples_repository ON few_shot_examples(repository_owner, repository_name)",
+        ],
+    },
+    Migration {
+        version: 15,
+        name: "ai_response_cache",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS ai_response_cache (
+                cache_key CHAR(64) PRIMARY KEY,
+                model VARCHAR(255) NOT NULL,
+                response TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                expires_at TIMESTAMPTZ NOT NULL
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_ai_response_cache_expires_at ON ai_response_cache(expires_at)",
+        ],
+    },
+    Migration {
+        version: 16,
+        name: "task_artifacts",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS task_artifacts (
+                task_id VARCHAR(255) PRIMARY KEY,
+                title TEXT NOT NULL,
+                summary TEXT,
+                files_changed TEXT[] NOT NULL DEFAULT '{}',
+                diff_stat TEXT,
+                pr_url TEXT,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 17,
+        name: "schedules",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS schedules (
+                id BIGSERIAL PRIMARY KEY,
+                cron_expression VARCHAR(255) NOT NULL,
+                repository_owner VARCHAR(255) NOT NULL,
+                repository_name VARCHAR(255) NOT NULL,
+                title TEXT NOT NULL,
+                description TEXT NOT NULL DEFAULT '',
+                prompt TEXT NOT NULL,
+                auto_approve BOOLEAN NOT NULL DEFAULT FALSE,
+                enabled BOOLEAN NOT NULL DEFAULT TRUE,
+                next_run_at TIMESTAMPTZ,
+                last_run_at TIMESTAMPTZ,
+                last_task_id VARCHAR(255),
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_schedules_next_run_at ON schedules(next_run_at) WHERE enabled",
+        ],
+    },
+    Migration {
+        version: 18,
+        name: "created_by",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS created_by VARCHAR(255)",
+            "ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS created_by VARCHAR(255)",
+        ],
+    },
+    Migration {
+        version: 19,
+        name: "org_id",
+        // 모든 저장 경로에서 같은 값이 되도록 저장소 소유자에서 생성
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS org_id VARCHAR(255) GENERATED ALWAYS AS (LOWER(repository_owner)) STORED",
+            "ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS org_id VARCHAR(255) GENERATED ALWAYS AS (LOWER(repository_owner)) STORED",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_org_id ON tasks(org_id, created_at DESC)",
+            "CREATE INDEX IF NOT EXISTS idx_composite_tasks_org_id ON composite_tasks(org_id)",
+        ],
+    },
+    Migration {
+        version: 20,
+        name: "pr_number",
+        statements: &["ALTER TABLE tasks ADD COLUMN IF NOT EXISTS pr_number BIGINT"],
+    },
+    Migration {
+        version: 21,
+        name: "failure_policy",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS blocked_by VARCHAR(255)",
+            "ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS failure_policy VARCHAR(20) NOT NULL DEFAULT 'block'",
+        ],
+    },
+    Migration {
+        version: 22,
+        name: "audit_events",
+        statements: &[
+            // hash = SHA-256 of prev_hash and the event, so edited or deleted rows break the chain
+            r#"
+            CREATE TABLE IF NOT EXISTS audit_events (
+                id BIGSERIAL PRIMARY KEY,
+                created_at TIMESTAMPTZ NOT NULL,
+                actor VARCHAR(255) NOT NULL,
+                action VARCHAR(100) NOT NULL,
+                target VARCHAR(255),
+                repository VARCHAR(255),
+                payload JSONB NOT NULL DEFAULT '{}',
+                prev_hash CHAR(64) NOT NULL,
+                hash CHAR(64) NOT NULL UNIQUE
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_audit_events_created_at ON audit_events(created_at DESC)",
+            "CREATE INDEX IF NOT EXISTS idx_audit_events_actor ON audit_events(actor)",
+            "CREATE INDEX IF NOT EXISTS idx_audit_events_action ON audit_events(action)",
+        ],
+    },
+    Migration {
+        version: 23,
+        name: "repository_secrets",
+        statements: &[
+            // Owner and name are stored lower-case; values are AES-256-GCM sealed
+            r#"
+            CREATE TABLE IF NOT EXISTS repository_secrets (
+                repository_owner VARCHAR(255) NOT NULL,
+                repository_name VARCHAR(255) NOT NULL,
+                name VARCHAR(255) NOT NULL,
+                nonce BYTEA NOT NULL,
+                ciphertext BYTEA NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (repository_owner, repository_name, name)
+            )
+            "#,
+            // One-time tokens a dispatched workflow redeems for its repository's secrets
+            r#"
+            CREATE TABLE IF NOT EXISTS secret_grants (
+                task_id VARCHAR(255) PRIMARY KEY,
+                token_hash CHAR(64) NOT NULL,
+                repository_owner VARCHAR(255) NOT NULL,
+                repository_name VARCHAR(255) NOT NULL,
+                expires_at TIMESTAMPTZ NOT NULL
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 24,
+        name: "task_tags",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}'",
+            "CREATE INDEX IF NOT EXISTS idx_tasks_tags ON tasks USING GIN (tags)",
+        ],
+    },
+    Migration {
+        version: 25,
+        name: "task_prompt_hash",
+        statements: &[
+            // SHA-256 (hex) of the normalized prompt, used to spot duplicate tasks
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS prompt_hash CHAR(64)",
+            // Same normalization as autodev_core::normalize_prompt for existing rows
+            r#"
+            UPDATE tasks
+            SET prompt_hash = encode(sha256(convert_to(lower(btrim(regexp_replace(prompt, '\s+', ' ', 'g'))), 'UTF8')), 'hex')
+            WHERE prompt_hash IS NULL
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_tasks_prompt_hash ON tasks (repository_owner, repository_name, prompt_hash)",
+        ],
+    },
+    Migration {
+        version: 26,
+        name: "composite_cancelled_at",
+        statements: &["ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS cancelled_at TIMESTAMPTZ"],
+    },
+    Migration {
+        version: 27,
+        name: "execution_backend",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS execution_backend VARCHAR(20)",
+            "ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS execution_backend VARCHAR(20)",
+        ],
+    },
+    Migration {
+        version: 28,
+        name: "task_logs_timestamp",
+        statements: &["CREATE INDEX IF NOT EXISTS idx_task_logs_timestamp ON task_logs(timestamp)"],
+    },
+    Migration {
+        version: 29,
+        name: "ci_fix_attempts",
+        statements: &[
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS ci_fix_attempts INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE composite_tasks ADD COLUMN IF NOT EXISTS ci_fix_attempts INTEGER NOT NULL DEFAULT 0",
+        ],
+    },
+    Migration {
+        version: 30,
+        name: "task_payload_tokens",
+        // Payloads saved before have no token and can only be fetched with an API key
+        statements: &["ALTER TABLE task_payloads ADD COLUMN IF NOT EXISTS token_hash VARCHAR(64)"],
+    },
+];
+
+/// Version the database must be at for this build
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Migrations not yet recorded in `applied`, in version order
+pub fn pending(applied: &[i64]) -> Vec<&'static Migration> {
+    MIGRATIONS
+        .iter()
+        .filter(|m| !applied.contains(&m.version))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_strictly_increasing() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+        assert_eq!(MIGRATIONS[0].version, 1);
+        assert!(MIGRATIONS.iter().all(|m| !m.statements.is_empty()));
+    }
+
+    #[test]
+    fn test_pending() {
+        assert_eq!(pending(&[]).len(), MIGRATIONS.len());
+        assert!(pending(&MIGRATIONS.iter().map(|m| m.version).collect::<Vec<_>>()).is_empty());
+
+        let versions: Vec<i64> = pending(&[1, 2, 4]).iter().map(|m| m.version).collect();
+        assert_eq!(versions[..2], [3, 5]);
+    }
+}
@@ -1,4 +1,5 @@
-use chrono::{DateTime, Utc};
+use autodev_core::{CompositeTask, RetryPolicy, Task, TaskStatus, TaskType};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -20,6 +21,145 @@ pub struct TaskRecord {
     pub workflow_run_id: Option<String>,
     pub error: Option<String>,
     pub auto_approve: bool,
+    pub attempts: i32,
+    pub retry_policy: Option<String>,
+    pub estimated_minutes: Option<i32>,
+    /// Worker that claimed the task last (kept after the claim is released)
+    pub claimed_by: Option<String>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub heartbeat_at: Option<DateTime<Utc>>,
+    /// The claim is held until this time unless the worker heartbeats
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    /// GitHub issue the task was started from
+    pub issue_number: Option<i64>,
+    pub issue_url: Option<String>,
+    /// User (API key) that created the task
+    pub created_by: Option<String>,
+    /// Organization (lowercase repository owner) the task belongs to
+    pub org_id: String,
+    /// Number of the task's pull/merge request
+    pub pr_number: Option<i64>,
+    /// Failed dependency that blocked or skipped the task
+    pub blocked_by: Option<String>,
+    pub tags: Vec<String>,
+    /// Backend requested for the task (NULL: the default at execution time)
+    pub execution_backend: Option<String>,
+}
+
+impl TaskRecord {
+    /// Convert the stored row back into a core Task
+    pub fn to_task(&self) -> autodev_core::Result<Task> {
+        Ok(Task {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            description: self.description.clone(),
+            prompt: self.prompt.clone(),
+            task_type: self.task_type.parse::<TaskType>()?,
+            status: self.status.parse::<TaskStatus>()?,
+            dependencies: self.dependencies.clone(),
+            created_at: self.created_at,
+            started_at: self.started_at,
+            completed_at: self.completed_at,
+            pr_url: self.pr_url.clone(),
+            pr_number: self.pr_number.and_then(|n| u64::try_from(n).ok()),
+            workflow_run_id: self.workflow_run_id.clone(),
+            error: self.error.clone(),
+            auto_approve: self.auto_approve,
+            retry_policy: match &self.retry_policy {
+                Some(policy) => serde_json::from_str(policy)
+                    .map_err(|e| autodev_core::Error::StoreError(e.to_string()))?,
+                None => RetryPolicy::default(),
+            },
+            attempts: self.attempts.max(0) as u32,
+            estimated_minutes: self.estimated_minutes.map(|m| m.max(0) as u32),
+            issue_number: self.issue_number.and_then(|n| u64::try_from(n).ok()),
+            issue_url: self.issue_url.clone(),
+            created_by: self.created_by.clone(),
+            blocked_by: self.blocked_by.clone(),
+            tags: self.tags.clone(),
+            execution_backend: self.execution_backend.as_deref().map(str::parse).transpose()?,
+            repository_owner: Some(self.repository_owner.clone()),
+            repository_name: Some(self.repository_name.clone()),
+        })
+    }
+}
+
+/// Sort order for task listings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSort {
+    /// Most recently created first
+    #[default]
+    Newest,
+    Oldest,
+    Title,
+    Status,
+}
+
+impl TaskSort {
+    /// ORDER BY clause (fixed strings, never user input)
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            TaskSort::Newest => "created_at DESC, id",
+            TaskSort::Oldest => "created_at ASC, id",
+            TaskSort::Title => "title ASC, created_at DESC",
+            TaskSort::Status => "status ASC, created_at DESC",
+        }
+    }
+}
+
+/// Filters and paging for `Database::list_tasks`
+#[derive(Debug, Clone)]
+pub struct TaskQuery {
+    pub status: Option<TaskStatus>,
+    /// (owner, name)
+    pub repository: Option<(String, String)>,
+    /// Issue the task was started from
+    pub issue_number: Option<u64>,
+    /// Organization (repository owner, case-insensitive)
+    pub org_id: Option<String>,
+    /// Only tasks with this tag
+    pub tag: Option<String>,
+    pub limit: i64,
+    pub offset: i64,
+    pub sort: TaskSort,
+}
+
+impl Default for TaskQuery {
+    fn default() -> Self {
+        Self {
+            status: None,
+            repository: None,
+            issue_number: None,
+            org_id: None,
+            tag: None,
+            limit: 50,
+            offset: 0,
+            sort: TaskSort::default(),
+        }
+    }
+}
+
+/// Filters for the statistics queries
+#[derive(Debug, Clone, Default)]
+pub struct StatsFilter {
+    pub repository_owner: Option<String>,
+    pub repository_name: Option<String>,
+    /// Organization (repository owner, case-insensitive)
+    pub org_id: Option<String>,
+    /// Only tasks created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only tasks with this tag
+    pub tag: Option<String>,
+}
+
+impl StatsFilter {
+    /// Start of a window covering the last `days` days
+    ///
+    /// None when the window reaches before the earliest representable time.
+    pub fn since_days(days: u32) -> Option<DateTime<Utc>> {
+        Utc::now().checked_sub_signed(chrono::Duration::days(days.into()))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -32,6 +172,139 @@ pub struct CompositeTaskRecord {
     pub repository_name: String,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    pub review_state: String,
+    pub approved_batches: Vec<i32>,
+    pub max_parallel_tasks: Option<i32>,
+    pub merge_method: Option<String>,
+    pub base_branch: Option<String>,
+    pub created_by: Option<String>,
+    pub org_id: String,
+    pub failure_policy: String,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub execution_backend: Option<String>,
+}
+
+impl CompositeTaskRecord {
+    /// Convert the stored row back into a core CompositeTask with `subtasks`
+    pub fn to_composite_task(&self, subtasks: Vec<Task>) -> autodev_core::Result<CompositeTask> {
+        Ok(CompositeTask {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            description: self.description.clone(),
+            subtasks,
+            auto_approve: self.auto_approve,
+            created_at: self.created_at,
+            completed_at: self.completed_at,
+            cancelled_at: self.cancelled_at,
+            review_state: self.review_state.parse()?,
+            approved_batches: self
+                .approved_batches
+                .iter()
+                .filter_map(|batch| usize::try_from(*batch).ok())
+                .collect(),
+            max_parallel_tasks: self.max_parallel_tasks.and_then(|n| usize::try_from(n).ok()),
+            merge_method: self.merge_method.as_deref().map(str::parse).transpose()?,
+            base_branch: self.base_branch.clone(),
+            created_by: self.created_by.clone(),
+            failure_policy: self.failure_policy.parse()?,
+            execution_backend: self.execution_backend.as_deref().map(str::parse).transpose()?,
+        })
+    }
+}
+
+/// Full dispatch payload of a task, fetched by the workflow instead of being
+/// passed as workflow inputs
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskPayload {
+    pub task_id: String,
+    pub composite_task_id: Option<String>,
+    pub title: String,
+    pub prompt: String,
+    pub base_branch: String,
+    pub target_branch: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a finished task changed, passed on to the tasks that depend on it
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct TaskArtifact {
+    pub task_id: String,
+    pub title: String,
+    /// Key decisions / summary of the work (e.g. the agent's final message)
+    pub summary: Option<String>,
+    pub files_changed: Vec<String>,
+    /// `git diff --stat` of the task's changes
+    pub diff_stat: Option<String>,
+    pub pr_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Recurring task created from a template whenever its cron expression is due
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScheduleRecord {
+    pub id: i64,
+    pub cron_expression: String,
+    pub repository_owner: String,
+    pub repository_name: String,
+    pub title: String,
+    pub description: String,
+    pub prompt: String,
+    pub auto_approve: bool,
+    pub enabled: bool,
+    /// None when disabled or the expression never matches again
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Task created by the last run
+    pub last_task_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ScheduleRecord {
+    /// Task to create for a run of this schedule
+    pub fn to_task(&self) -> Task {
+        let mut task = Task::new(self.title.clone(), self.description.clone(), self.prompt.clone());
+        task.auto_approve = self.auto_approve;
+        task
+    }
+}
+
+/// Fields of a schedule to create or replace
+#[derive(Debug, Clone)]
+pub struct NewSchedule {
+    pub cron_expression: String,
+    pub repository_owner: String,
+    pub repository_name: String,
+    pub title: String,
+    pub description: String,
+    pub prompt: String,
+    pub auto_approve: bool,
+    pub enabled: bool,
+}
+
+/// User-provided few-shot example for task decomposition
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExampleRecord {
+    pub id: i64,
+    /// None for examples used with every repository
+    pub repository_owner: Option<String>,
+    pub repository_name: Option<String>,
+    pub domain: String,
+    pub user_prompt: String,
+    /// Decomposition the AI should answer `user_prompt` with (JSON)
+    pub assistant_response: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Fields of an example to create or replace
+#[derive(Debug, Clone)]
+pub struct NewExample {
+    pub repository_owner: Option<String>,
+    pub repository_name: Option<String>,
+    pub domain: String,
+    pub user_prompt: String,
+    pub assistant_response: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -43,6 +316,115 @@ pub struct ExecutionLog {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Entry of the audit trail: who did what, with which parameters
+///
+/// Each row stores the hash of the previous one, so rows edited or deleted
+/// after the fact are found by `Database::verify_audit_chain`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    /// `api:<user>`, `webhook:<delivery>`, `cli:<user>` or `system:<component>`
+    pub actor: String,
+    /// e.g. `task.create`, `branch.create`, `merge_request.merge`
+    pub action: String,
+    /// Task, branch or merge request the action applied to
+    pub target: Option<String>,
+    /// `owner/name`
+    pub repository: Option<String>,
+    #[sqlx(json)]
+    pub payload: serde_json::Value,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+/// Fields of an audit event to record
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub repository: Option<String>,
+    pub payload: serde_json::Value,
+}
+
+impl NewAuditEvent {
+    pub fn new(actor: impl Into<String>, action: impl Into<String>) -> Self {
+        Self {
+            actor: actor.into(),
+            action: action.into(),
+            target: None,
+            repository: None,
+            payload: serde_json::Value::Object(Default::default()),
+        }
+    }
+
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn with_repository(mut self, owner: &str, name: &str) -> Self {
+        self.repository = Some(format!("{}/{}", owner, name));
+        self
+    }
+
+    pub fn with_payload(mut self, payload: serde_json::Value) -> Self {
+        self.payload = payload;
+        self
+    }
+}
+
+/// Filters and paging for `Database::list_audit_events`
+#[derive(Debug, Clone)]
+pub struct AuditQuery {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+    /// `owner/name`
+    pub repository: Option<String>,
+    /// Only events at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only events before this time
+    pub until: Option<DateTime<Utc>>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Default for AuditQuery {
+    fn default() -> Self {
+        Self {
+            actor: None,
+            action: None,
+            target: None,
+            repository: None,
+            since: None,
+            until: None,
+            limit: 50,
+            offset: 0,
+        }
+    }
+}
+
+/// Stored secret of a repository (the value stays encrypted)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SecretRecord {
+    pub repository_owner: String,
+    pub repository_name: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One line of container output
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TaskLog {
+    pub id: i64,
+    pub task_id: String,
+    pub line: String,
+    pub timestamp: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Metrics {
     pub id: i32,
@@ -53,6 +435,30 @@ pub struct Metrics {
     pub lines_removed: i32,
     pub ai_tokens_used: i32,
     pub timestamp: DateTime<Utc>,
+    pub ai_input_tokens: i64,
+    pub ai_output_tokens: i64,
+    /// Estimated AI cost in USD
+    pub ai_cost_usd: f64,
+}
+
+/// AI tokens and cost summed over metrics rows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct AiUsage {
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: f64,
+}
+
+/// Metrics of one run of a task, or of an AI call made for it
+#[derive(Debug, Clone, Default)]
+pub struct NewMetrics {
+    pub execution_time_ms: i64,
+    pub files_changed: i32,
+    pub lines_added: i32,
+    pub lines_removed: i32,
+    pub ai_input_tokens: i64,
+    pub ai_output_tokens: i64,
+    pub ai_cost_usd: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,5 +468,101 @@ pub struct AggregateStats {
     pub failed_tasks: i64,
     pub avg_execution_time_ms: Option<f64>,
     pub total_files_changed: Option<i64>,
+    pub total_lines_added: Option<i64>,
+    pub total_lines_removed: Option<i64>,
+    pub total_tokens_used: Option<i64>,
+    pub total_cost_usd: Option<f64>,
+}
+
+/// Statistics of one repository
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RepositoryStats {
+    pub repository_owner: String,
+    pub repository_name: String,
+    pub total_tasks: i64,
+    pub completed_tasks: i64,
+    pub failed_tasks: i64,
+    pub avg_execution_time_ms: Option<f64>,
+    pub total_files_changed: Option<i64>,
+    pub total_lines_added: Option<i64>,
+    pub total_lines_removed: Option<i64>,
     pub total_tokens_used: Option<i64>,
-}
\ No newline at end of file
+    pub total_cost_usd: Option<f64>,
+}
+
+/// Statistics of the tasks with one tag
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TagStats {
+    pub tag: String,
+    pub total_tasks: i64,
+    pub completed_tasks: i64,
+    pub failed_tasks: i64,
+    pub avg_execution_time_ms: Option<f64>,
+    pub total_tokens_used: Option<i64>,
+    pub total_cost_usd: Option<f64>,
+}
+
+/// Task counts of one day (by creation date, UTC)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DailyStats {
+    pub date: NaiveDate,
+    pub total_tasks: i64,
+    pub completed_tasks: i64,
+    pub failed_tasks: i64,
+    pub total_tokens_used: Option<i64>,
+    pub total_cost_usd: Option<f64>,
+}
+
+/// Bucket size of statistics time series
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsGranularity {
+    #[default]
+    Day,
+    /// Weeks starting on Monday
+    Week,
+}
+
+impl StatsGranularity {
+    /// `date_trunc` field (fixed strings, never user input)
+    pub fn unit(&self) -> &'static str {
+        match self {
+            StatsGranularity::Day => "day",
+            StatsGranularity::Week => "week",
+        }
+    }
+}
+
+impl std::fmt::Display for StatsGranularity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.unit())
+    }
+}
+
+impl std::str::FromStr for StatsGranularity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "day" | "daily" => Ok(StatsGranularity::Day),
+            "week" | "weekly" => Ok(StatsGranularity::Week),
+            _ => Err(format!("Unknown granularity: {} (expected day or week)", s)),
+        }
+    }
+}
+
+/// Tasks finished in one bucket of a time series (UTC)
+///
+/// Unfinished tasks count in the bucket they were created in. Buckets without
+/// tasks are included with zero counts.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TimeseriesStats {
+    /// First day of the bucket
+    pub date: NaiveDate,
+    pub total_tasks: i64,
+    pub completed_tasks: i64,
+    pub failed_tasks: i64,
+    pub avg_execution_time_ms: Option<f64>,
+    pub total_tokens_used: Option<i64>,
+    pub total_cost_usd: Option<f64>,
+}
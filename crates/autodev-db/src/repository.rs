@@ -1,13 +1,36 @@
 use crate::{
-    models::{AggregateStats, CompositeTaskRecord, ExecutionLog, Metrics, TaskRecord},
-    Result,
+    migrations,
+    secrets::{self, SecretCipher},
+    models::{
+        AggregateStats, AiUsage, AuditEvent, AuditQuery, CompositeTaskRecord, DailyStats, ExampleRecord, ExecutionLog, Metrics, NewExample, NewMetrics, NewSchedule, RepositoryStats,
+        NewAuditEvent, ScheduleRecord, SecretRecord, StatsFilter, StatsGranularity, TagStats, TaskArtifact, TaskLog, TaskPayload, TaskQuery, TaskRecord,
+        TimeseriesStats,
+    },
+    Error, Result,
 };
 use autodev_core::{CompositeTask, Task, TaskStatus};
-use sqlx::{postgres::PgPoolOptions, Pool, Postgres, Row};
+use chrono::{DateTime, SecondsFormat, SubsecRound, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{postgres::PgPoolOptions, Pool, Postgres, QueryBuilder, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+
+/// pg_advisory_xact_lock key held while migrating ("autodev" in ASCII)
+const MIGRATION_LOCK_ID: i64 = 0x0061_7574_6f64_6576;
+
+/// pg_advisory_xact_lock key held while appending to the audit chain ("audit" in ASCII)
+const AUDIT_LOCK_ID: i64 = 0x0061_7564_6974;
+
+/// prev_hash of the first audit event
+const AUDIT_GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
 
 #[derive(Clone)]
 pub struct Database {
     pool: Pool<Postgres>,
+    /// Whether the pgvector extension could be enabled (checked on first use)
+    pgvector: Arc<OnceCell<bool>>,
 }
 
 impl Database {
@@ -18,115 +41,123 @@ impl Database {
             .connect(database_url)
             .await?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            pgvector: Arc::new(OnceCell::new()),
+        })
     }
 
-    /// Initialize database schema
-    pub async fn init_schema(&self) -> Result<()> {
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS tasks (
-                id VARCHAR(255) PRIMARY KEY,
-                title TEXT NOT NULL,
-                description TEXT NOT NULL,
-                prompt TEXT NOT NULL,
-                task_type VARCHAR(50) NOT NULL,
-                status VARCHAR(50) NOT NULL,
-                dependencies TEXT[] NOT NULL DEFAULT '{}',
-                repository_owner VARCHAR(255) NOT NULL,
-                repository_name VARCHAR(255) NOT NULL,
-                created_at TIMESTAMPTZ NOT NULL,
-                started_at TIMESTAMPTZ,
-                completed_at TIMESTAMPTZ,
-                pr_url TEXT,
-                workflow_run_id VARCHAR(255),
-                error TEXT,
-                auto_approve BOOLEAN NOT NULL DEFAULT FALSE
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+    /// Check the database answers (`SELECT 1`)
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
 
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS composite_tasks (
-                id VARCHAR(255) PRIMARY KEY,
-                title TEXT NOT NULL,
-                description TEXT NOT NULL,
-                auto_approve BOOLEAN NOT NULL DEFAULT FALSE,
-                repository_owner VARCHAR(255) NOT NULL,
-                repository_name VARCHAR(255) NOT NULL,
-                created_at TIMESTAMPTZ NOT NULL,
-                completed_at TIMESTAMPTZ
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+    /// Close the connection pool, waiting for in-flight queries to finish
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
 
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS composite_task_subtasks (
-                composite_task_id VARCHAR(255) NOT NULL,
-                subtask_id VARCHAR(255) NOT NULL,
-                subtask_order INTEGER NOT NULL,
-                PRIMARY KEY (composite_task_id, subtask_id),
-                FOREIGN KEY (composite_task_id) REFERENCES composite_tasks(id),
-                FOREIGN KEY (subtask_id) REFERENCES tasks(id)
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+    /// Initialize database schema (applies all pending migrations)
+    pub async fn init_schema(&self) -> Result<()> {
+        self.migrate().await?;
+        Ok(())
+    }
 
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS execution_logs (
-                id SERIAL PRIMARY KEY,
-                task_id VARCHAR(255) NOT NULL,
-                event_type VARCHAR(100) NOT NULL,
-                message TEXT NOT NULL,
-                timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                FOREIGN KEY (task_id) REFERENCES tasks(id)
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+    /// Apply pending schema migrations, returning the versions applied
+    ///
+    /// Runs in a single transaction under an advisory lock, so processes
+    /// starting at the same time do not apply a migration twice.
+    pub async fn migrate(&self) -> Result<Vec<i64>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(MIGRATION_LOCK_ID)
+            .execute(&mut *tx)
+            .await?;
 
         sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS metrics (
-                id SERIAL PRIMARY KEY,
-                task_id VARCHAR(255) NOT NULL,
-                execution_time_ms BIGINT NOT NULL,
-                files_changed INTEGER NOT NULL DEFAULT 0,
-                lines_added INTEGER NOT NULL DEFAULT 0,
-                lines_removed INTEGER NOT NULL DEFAULT 0,
-                ai_tokens_used INTEGER NOT NULL DEFAULT 0,
-                timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                FOREIGN KEY (task_id) REFERENCES tasks(id)
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version BIGINT PRIMARY KEY,
+                name TEXT NOT NULL,
+                applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
             )
             "#,
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
-        // Create indexes
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status)")
-            .execute(&self.pool)
+        let applied: Vec<i64> = sqlx::query_scalar("SELECT version FROM schema_migrations")
+            .fetch_all(&mut *tx)
             .await?;
 
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_created_at ON tasks(created_at DESC)")
-            .execute(&self.pool)
+        let mut newly_applied = Vec::new();
+        for migration in migrations::pending(&applied) {
+            tracing::info!("Applying migration {} ({})", migration.version, migration.name);
+
+            for statement in migration.statements {
+                sqlx::query(statement).execute(&mut *tx).await.map_err(|e| {
+                    Error::Migration(format!(
+                        "{} ({}) failed: {}",
+                        migration.version, migration.name, e
+                    ))
+                })?;
+            }
+
+            sqlx::query("INSERT INTO schema_migrations (version, name) VALUES ($1, $2)")
+                .bind(migration.version)
+                .bind(migration.name)
+                .execute(&mut *tx)
+                .await?;
+
+            newly_applied.push(migration.version);
+        }
+
+        tx.commit().await?;
+
+        Ok(newly_applied)
+    }
+
+    /// Versions recorded in `schema_migrations` (empty before the first migration)
+    pub async fn applied_migrations(&self) -> Result<Vec<i64>> {
+        // to_regclass is NULL while the table does not exist
+        let initialized: bool =
+            sqlx::query_scalar("SELECT to_regclass('schema_migrations') IS NOT NULL")
+                .fetch_one(&self.pool)
+                .await?;
+
+        if !initialized {
+            return Ok(Vec::new());
+        }
+
+        let versions = sqlx::query_scalar("SELECT version FROM schema_migrations ORDER BY version")
+            .fetch_all(&self.pool)
             .await?;
 
-        sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_execution_logs_task_id ON execution_logs(task_id)",
-        )
-        .execute(&self.pool)
-        .await?;
+        Ok(versions)
+    }
+
+    /// Fail when the schema is missing migrations this build relies on
+    pub async fn check_schema_version(&self) -> Result<()> {
+        let applied = self.applied_migrations().await?;
+        let pending = migrations::pending(&applied);
+
+        if !pending.is_empty() {
+            let versions: Vec<i64> = pending.iter().map(|m| m.version).collect();
+            return Err(Error::Migration(format!(
+                "Database schema is missing migrations {:?}; run `autodev migrate`",
+                versions
+            )));
+        }
+
+        if let Some(newer) = applied.iter().find(|v| **v > migrations::latest_version()) {
+            tracing::warn!(
+                "Database schema has migration {} which is newer than this build (latest: {})",
+                newer,
+                migrations::latest_version()
+            );
+        }
 
         Ok(())
     }
@@ -143,15 +174,26 @@ impl Database {
                 id, title, description, prompt, task_type, status,
                 dependencies, repository_owner, repository_name,
                 created_at, started_at, completed_at, pr_url,
-                workflow_run_id, error, auto_approve
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+                workflow_run_id, error, auto_approve, attempts, retry_policy,
+                estimated_minutes, issue_number, issue_url, created_by, pr_number, blocked_by, tags,
+                prompt_hash, execution_backend
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27)
             ON CONFLICT (id) DO UPDATE SET
+                created_by = COALESCE(tasks.created_by, $22),
+                repository_owner = $8,
+                repository_name = $9,
+                dependencies = $7,
                 status = $6,
                 started_at = $11,
                 completed_at = $12,
                 pr_url = $13,
+                pr_number = $23,
+                blocked_by = $24,
+                tags = $25,
                 workflow_run_id = $14,
-                error = $15
+                error = $15,
+                attempts = $17,
+                retry_policy = $18
             "#,
         )
         .bind(&task.id)
@@ -170,12 +212,165 @@ impl Database {
         .bind(&task.workflow_run_id)
         .bind(&task.error)
         .bind(task.auto_approve)
+        .bind(task.attempts as i32)
+        .bind(serde_json::to_string(&task.retry_policy)?)
+        .bind(task.estimated_minutes.map(|m| m as i32))
+        .bind(task.issue_number.map(|n| n as i64))
+        .bind(&task.issue_url)
+        .bind(&task.created_by)
+        .bind(task.pr_number.map(|n| n as i64))
+        .bind(&task.blocked_by)
+        .bind(&task.tags)
+        .bind(prompt_hash(&task.prompt))
+        .bind(task.execution_backend.map(|b| b.as_str()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Upsert task state without touching repository information
+    ///
+    /// Used by the engine's write-through store, which does not know the target
+    /// repository. New rows get empty repository fields until `save_task` fills them.
+    pub async fn upsert_task_state(&self, task: &Task) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO tasks (
+                id, title, description, prompt, task_type, status,
+                dependencies, repository_owner, repository_name,
+                created_at, started_at, completed_at, pr_url,
+                workflow_run_id, error, auto_approve, attempts, retry_policy,
+                estimated_minutes, issue_number, issue_url, created_by, pr_number, blocked_by, tags,
+                prompt_hash, execution_backend
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, '', '', $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
+            ON CONFLICT (id) DO UPDATE SET
+                dependencies = $7,
+                status = $6,
+                started_at = $9,
+                completed_at = $10,
+                pr_url = $11,
+                pr_number = $21,
+                blocked_by = $22,
+                tags = $23,
+                workflow_run_id = $12,
+                error = $13,
+                attempts = $15,
+                retry_policy = $16
+            "#,
+        )
+        .bind(&task.id)
+        .bind(&task.title)
+        .bind(&task.description)
+        .bind(&task.prompt)
+        .bind(format!("{:?}", task.task_type))
+        .bind(format!("{:?}", task.status))
+        .bind(&task.dependencies)
+        .bind(task.created_at)
+        .bind(task.started_at)
+        .bind(task.completed_at)
+        .bind(&task.pr_url)
+        .bind(&task.workflow_run_id)
+        .bind(&task.error)
+        .bind(task.auto_approve)
+        .bind(task.attempts as i32)
+        .bind(serde_json::to_string(&task.retry_policy)?)
+        .bind(task.estimated_minutes.map(|m| m as i32))
+        .bind(task.issue_number.map(|n| n as i64))
+        .bind(&task.issue_url)
+        .bind(&task.created_by)
+        .bind(task.pr_number.map(|n| n as i64))
+        .bind(&task.blocked_by)
+        .bind(&task.tags)
+        .bind(prompt_hash(&task.prompt))
+        .bind(task.execution_backend.map(|b| b.as_str()))
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
+    /// Get tasks the orchestrator still tracks: unfinished tasks and
+    /// every subtask of a composite task that has not completed or been cancelled
+    pub async fn get_open_tasks(&self) -> Result<Vec<TaskRecord>> {
+        let records = sqlx::query_as::<_, TaskRecord>(
+            r#"
+            SELECT * FROM tasks
+            WHERE status NOT IN ('Completed', 'Failed', 'Cancelled')
+               OR id IN (
+                   SELECT cts.subtask_id FROM composite_task_subtasks cts
+                   JOIN composite_tasks c ON c.id = cts.composite_task_id
+                   WHERE c.completed_at IS NULL AND c.cancelled_at IS NULL
+               )
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Oldest unfinished task of a repository with the same prompt
+    /// (see [`autodev_core::normalize_prompt`])
+    pub async fn find_open_duplicate(
+        &self,
+        repo_owner: &str,
+        repo_name: &str,
+        prompt: &str,
+    ) -> Result<Option<TaskRecord>> {
+        let record = sqlx::query_as::<_, TaskRecord>(
+            r#"
+            SELECT * FROM tasks
+            WHERE repository_owner = $1
+              AND repository_name = $2
+              AND prompt_hash = $3
+              AND status NOT IN ('Completed', 'Failed', 'Cancelled')
+            ORDER BY created_at
+            LIMIT 1
+            "#,
+        )
+        .bind(repo_owner)
+        .bind(repo_name)
+        .bind(prompt_hash(prompt))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Replace the tags of a stored task; false when it does not exist
+    pub async fn set_task_tags(&self, task_id: &str, tags: &[String]) -> Result<bool> {
+        let result = sqlx::query("UPDATE tasks SET tags = $2 WHERE id = $1")
+            .bind(task_id)
+            .bind(autodev_core::normalize_tags(tags))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count an automatic CI fix on a task or composite task
+    ///
+    /// False once `max_attempts` fixes were made, or when no task has the ID.
+    pub async fn claim_ci_fix_attempt(&self, id: &str, max_attempts: u32) -> Result<bool> {
+        for query in [
+            "UPDATE tasks SET ci_fix_attempts = ci_fix_attempts + 1 WHERE id = $1 AND ci_fix_attempts < $2",
+            "UPDATE composite_tasks SET ci_fix_attempts = ci_fix_attempts + 1 WHERE id = $1 AND ci_fix_attempts < $2",
+        ] {
+            let result = sqlx::query(query)
+                .bind(id)
+                .bind(max_attempts as i32)
+                .execute(&self.pool)
+                .await?;
+            if result.rows_affected() > 0 {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
     /// Get task by ID
     pub async fn get_task(&self, task_id: &str) -> Result<Option<TaskRecord>> {
         let record = sqlx::query_as::<_, TaskRecord>("SELECT * FROM tasks WHERE id = $1")
@@ -198,6 +393,50 @@ impl Database {
         Ok(records)
     }
 
+    /// List tasks matching `query`, returning one page and the total match count
+    pub async fn list_tasks(&self, query: &TaskQuery) -> Result<(Vec<TaskRecord>, i64)> {
+        fn push_filters<'a>(builder: &mut QueryBuilder<'a, Postgres>, query: &'a TaskQuery) {
+            builder.push(" WHERE TRUE");
+            if let Some(status) = query.status {
+                builder.push(" AND status = ").push_bind(format!("{:?}", status));
+            }
+            if let Some((owner, name)) = &query.repository {
+                builder.push(" AND repository_owner = ").push_bind(owner);
+                builder.push(" AND repository_name = ").push_bind(name);
+            }
+            if let Some(issue_number) = query.issue_number {
+                builder.push(" AND issue_number = ").push_bind(issue_number as i64);
+            }
+            if let Some(org_id) = &query.org_id {
+                builder.push(" AND org_id = LOWER(").push_bind(org_id).push(")");
+            }
+            if let Some(tag) = &query.tag {
+                builder.push(" AND tags @> ARRAY[LOWER(").push_bind(tag).push(")]::TEXT[]");
+            }
+        }
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM tasks");
+        push_filters(&mut count, query);
+        let total: i64 = count.build_query_scalar().fetch_one(&self.pool).await?;
+
+        let mut select = QueryBuilder::new("SELECT * FROM tasks");
+        push_filters(&mut select, query);
+        select
+            .push(" ORDER BY ")
+            .push(query.sort.order_by())
+            .push(" LIMIT ")
+            .push_bind(query.limit)
+            .push(" OFFSET ")
+            .push_bind(query.offset);
+
+        let records = select
+            .build_query_as::<TaskRecord>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok((records, total))
+    }
+
     /// Get recent tasks
     pub async fn get_recent_tasks(&self, limit: i64) -> Result<Vec<TaskRecord>> {
         let records = sqlx::query_as::<_, TaskRecord>(
@@ -217,16 +456,181 @@ impl Database {
         status: TaskStatus,
         error: Option<String>,
     ) -> Result<()> {
-        sqlx::query("UPDATE tasks SET status = $1, error = $2 WHERE id = $3")
-            .bind(format!("{:?}", status))
-            .bind(error)
-            .bind(task_id)
-            .execute(&self.pool)
-            .await?;
+        // 시작/종료 시각은 상태에 따라 기록 (AutoDevEngine의 Task::transition_to와 동일)
+        sqlx::query(
+            r#"
+            UPDATE tasks SET
+                status = $1,
+                error = $2,
+                started_at = CASE
+                    WHEN $1 = 'InProgress' AND status <> 'InProgress' THEN NOW()
+                    ELSE started_at
+                END,
+                completed_at = CASE
+                    WHEN status = $1 THEN completed_at
+                    WHEN $1 IN ('Completed', 'Failed', 'Cancelled') THEN NOW()
+                    WHEN $1 IN ('Pending', 'WaitingDependencies', 'Ready', 'InProgress') THEN NULL
+                    ELSE completed_at
+                END
+            WHERE id = $3
+            "#,
+        )
+        .bind(format!("{:?}", status))
+        .bind(error)
+        .bind(task_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Task Queue (multi-worker claiming)
+    // ========================================================================
+
+    /// Claim the oldest claimable task among `candidates` for `worker_id`
+    ///
+    /// A task is claimable while it is Pending or WaitingDependencies and no
+    /// live lease is held on it. Rows locked by a concurrent claim are skipped
+    /// (`FOR UPDATE SKIP LOCKED`), so two workers never claim the same task.
+    pub async fn claim_task(
+        &self,
+        candidates: &[String],
+        worker_id: &str,
+        lease: Duration,
+    ) -> Result<Option<TaskRecord>> {
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        let record = sqlx::query_as::<_, TaskRecord>(
+            r#"
+            UPDATE tasks SET
+                claimed_by = $2,
+                claimed_at = NOW(),
+                heartbeat_at = NOW(),
+                lease_expires_at = NOW() + make_interval(secs => $3)
+            WHERE id = (
+                SELECT id FROM tasks
+                WHERE id = ANY($1)
+                  AND status IN ('Pending', 'WaitingDependencies')
+                  AND (lease_expires_at IS NULL OR lease_expires_at < NOW())
+                ORDER BY created_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(candidates)
+        .bind(worker_id)
+        .bind(lease.as_secs_f64())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Extend the lease `worker_id` holds on a task
+    ///
+    /// Returns false when the worker no longer holds the claim.
+    pub async fn heartbeat_task(&self, task_id: &str, worker_id: &str, lease: Duration) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE tasks SET
+                heartbeat_at = NOW(),
+                lease_expires_at = NOW() + make_interval(secs => $3)
+            WHERE id = $1 AND claimed_by = $2 AND lease_expires_at IS NOT NULL
+            "#,
+        )
+        .bind(task_id)
+        .bind(worker_id)
+        .bind(lease.as_secs_f64())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Release the claim `worker_id` holds on a task
+    pub async fn release_task(&self, task_id: &str, worker_id: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE tasks SET lease_expires_at = NULL WHERE id = $1 AND claimed_by = $2",
+        )
+        .bind(task_id)
+        .bind(worker_id)
+        .execute(&self.pool)
+        .await?;
 
         Ok(())
     }
 
+    /// Extend the lease on a running task for whoever executes it
+    ///
+    /// Used by workflow runs and containers heartbeating through the API; unlike
+    /// [`Database::heartbeat_task`] it does not need a worker's claim. Returns
+    /// the new expiry, or None when the task is not InProgress.
+    pub async fn heartbeat_running_task(&self, task_id: &str, lease: Duration) -> Result<Option<DateTime<Utc>>> {
+        let expires_at = sqlx::query_scalar(
+            r#"
+            UPDATE tasks SET
+                heartbeat_at = NOW(),
+                lease_expires_at = GREATEST(lease_expires_at, NOW() + make_interval(secs => $2))
+            WHERE id = $1 AND status = 'InProgress'
+            RETURNING lease_expires_at
+            "#,
+        )
+        .bind(task_id)
+        .bind(lease.as_secs_f64())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(expires_at)
+    }
+
+    /// IDs of InProgress tasks holding no lease that started more than `timeout` ago
+    ///
+    /// Nobody heartbeats for these, so their age is the only sign of a stall.
+    pub async fn get_unleased_stalled_tasks(&self, timeout: Duration) -> Result<Vec<String>> {
+        let ids = sqlx::query_scalar(
+            r#"
+            SELECT id FROM tasks
+            WHERE status = 'InProgress'
+              AND lease_expires_at IS NULL
+              AND started_at < NOW() - make_interval(secs => $1)
+            ORDER BY started_at
+            "#,
+        )
+        .bind(timeout.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// Put InProgress tasks whose lease expired (their worker, workflow run or
+    /// container died) back to Pending
+    ///
+    /// The update only matches rows whose lease is still expired when it runs,
+    /// so a heartbeat that lands first keeps the task, and a worker that lost its
+    /// lease fails its next heartbeat. Returns the IDs of the requeued tasks.
+    pub async fn requeue_expired_claims(&self) -> Result<Vec<String>> {
+        let ids = sqlx::query_scalar(
+            r#"
+            UPDATE tasks SET
+                status = 'Pending',
+                lease_expires_at = NULL,
+                error = 'Worker lease expired'
+            WHERE status = 'InProgress' AND lease_expires_at < NOW()
+            RETURNING id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
     // ========================================================================
     // Composite Task Operations
     // ========================================================================
@@ -243,8 +647,18 @@ impl Database {
             r#"
             INSERT INTO composite_tasks (
                 id, title, description, auto_approve,
-                repository_owner, repository_name, created_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+                repository_owner, repository_name, created_at, review_state, approved_batches,
+                max_parallel_tasks, merge_method, base_branch, created_by, failure_policy, execution_backend
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            ON CONFLICT (id) DO UPDATE SET
+                created_by = COALESCE(composite_tasks.created_by, $13),
+                repository_owner = $5,
+                repository_name = $6,
+                review_state = $8,
+                approved_batches = ARRAY(
+                    SELECT DISTINCT unnest(composite_tasks.approved_batches || EXCLUDED.approved_batches)
+                    ORDER BY 1
+                )
             "#,
         )
         .bind(&composite_task.id)
@@ -254,6 +668,14 @@ impl Database {
         .bind(repo_owner)
         .bind(repo_name)
         .bind(composite_task.created_at)
+        .bind(format!("{:?}", composite_task.review_state))
+        .bind(approved_batches(composite_task))
+        .bind(composite_task.max_parallel_tasks.map(|n| n as i32))
+        .bind(composite_task.merge_method.map(|m| m.as_str()))
+        .bind(&composite_task.base_branch)
+        .bind(&composite_task.created_by)
+        .bind(composite_task.failure_policy.as_str())
+        .bind(composite_task.execution_backend.map(|b| b.as_str()))
         .execute(&self.pool)
         .await?;
 
@@ -266,6 +688,66 @@ impl Database {
                 INSERT INTO composite_task_subtasks (
                     composite_task_id, subtask_id, subtask_order
                 ) VALUES ($1, $2, $3)
+                ON CONFLICT (composite_task_id, subtask_id) DO UPDATE SET subtask_order = $3
+                "#,
+            )
+            .bind(&composite_task.id)
+            .bind(&subtask.id)
+            .bind(order as i32)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Upsert composite task state without touching repository information
+    pub async fn upsert_composite_task_state(&self, composite_task: &CompositeTask) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO composite_tasks (
+                id, title, description, auto_approve,
+                repository_owner, repository_name, created_at, completed_at, review_state,
+                approved_batches, max_parallel_tasks, merge_method, base_branch, created_by, failure_policy,
+                cancelled_at, execution_backend
+            ) VALUES ($1, $2, $3, $4, '', '', $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            ON CONFLICT (id) DO UPDATE SET
+                completed_at = $6,
+                cancelled_at = $14,
+                review_state = $7,
+                approved_batches = ARRAY(
+                    SELECT DISTINCT unnest(composite_tasks.approved_batches || EXCLUDED.approved_batches)
+                    ORDER BY 1
+                )
+            "#,
+        )
+        .bind(&composite_task.id)
+        .bind(&composite_task.title)
+        .bind(&composite_task.description)
+        .bind(composite_task.auto_approve)
+        .bind(composite_task.created_at)
+        .bind(composite_task.completed_at)
+        .bind(format!("{:?}", composite_task.review_state))
+        .bind(approved_batches(composite_task))
+        .bind(composite_task.max_parallel_tasks.map(|n| n as i32))
+        .bind(composite_task.merge_method.map(|m| m.as_str()))
+        .bind(&composite_task.base_branch)
+        .bind(&composite_task.created_by)
+        .bind(composite_task.failure_policy.as_str())
+        .bind(composite_task.cancelled_at)
+        .bind(composite_task.execution_backend.map(|b| b.as_str()))
+        .execute(&self.pool)
+        .await?;
+
+        for (order, subtask) in composite_task.subtasks.iter().enumerate() {
+            self.upsert_task_state(subtask).await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO composite_task_subtasks (
+                    composite_task_id, subtask_id, subtask_order
+                ) VALUES ($1, $2, $3)
+                ON CONFLICT (composite_task_id, subtask_id) DO UPDATE SET subtask_order = $3
                 "#,
             )
             .bind(&composite_task.id)
@@ -275,9 +757,30 @@ impl Database {
             .await?;
         }
 
+        // Subtasks split into smaller ones are no longer part of the composite task
+        let subtask_ids: Vec<&str> = composite_task.subtasks.iter().map(|t| t.id.as_str()).collect();
+        sqlx::query(
+            "DELETE FROM composite_task_subtasks WHERE composite_task_id = $1 AND subtask_id <> ALL($2)",
+        )
+        .bind(&composite_task.id)
+        .bind(&subtask_ids)
+        .execute(&self.pool)
+        .await?;
+
         Ok(())
     }
 
+    /// Get composite tasks that have neither completed nor been cancelled
+    pub async fn get_open_composite_tasks(&self) -> Result<Vec<CompositeTaskRecord>> {
+        let records = sqlx::query_as::<_, CompositeTaskRecord>(
+            "SELECT * FROM composite_tasks WHERE completed_at IS NULL AND cancelled_at IS NULL ORDER BY created_at",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
     /// Get composite task by ID
     pub async fn get_composite_task(&self, task_id: &str) -> Result<Option<CompositeTaskRecord>> {
         let record = sqlx::query_as::<_, CompositeTaskRecord>(
@@ -308,7 +811,573 @@ impl Database {
     }
 
     // ========================================================================
-    // Logging Operations
+    // Task Payload Operations
+    // ========================================================================
+
+    /// Store (or replace, on re-dispatch) the payload a workflow fetches for a task
+    ///
+    /// Returns the token the workflow fetches it with; only its hash is stored.
+    pub async fn save_task_payload(&self, payload: &TaskPayload) -> Result<String> {
+        let (token, token_hash) = secrets::new_grant_token();
+
+        sqlx::query(
+            r#"
+            INSERT INTO task_payloads (
+                task_id, composite_task_id, title, prompt, base_branch, target_branch, created_at, token_hash
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (task_id) DO UPDATE SET
+                composite_task_id = EXCLUDED.composite_task_id,
+                title = EXCLUDED.title,
+                prompt = EXCLUDED.prompt,
+                base_branch = EXCLUDED.base_branch,
+                target_branch = EXCLUDED.target_branch,
+                created_at = EXCLUDED.created_at,
+                token_hash = EXCLUDED.token_hash
+            "#,
+        )
+        .bind(&payload.task_id)
+        .bind(&payload.composite_task_id)
+        .bind(&payload.title)
+        .bind(&payload.prompt)
+        .bind(&payload.base_branch)
+        .bind(&payload.target_branch)
+        .bind(payload.created_at)
+        .bind(token_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn get_task_payload(&self, task_id: &str) -> Result<Option<TaskPayload>> {
+        let payload = sqlx::query_as::<_, TaskPayload>(
+            r#"
+            SELECT task_id, composite_task_id, title, prompt, base_branch, target_branch, created_at
+            FROM task_payloads WHERE task_id = $1
+            "#,
+        )
+        .bind(task_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(payload)
+    }
+
+    /// Payload of `task_id` if `token` is the one it was saved with
+    ///
+    /// Unlike secret grants the token is not used up: a failed run re-run from
+    /// the Actions UI fetches the payload again.
+    pub async fn get_task_payload_with_token(&self, task_id: &str, token: &str) -> Result<Option<TaskPayload>> {
+        let payload = sqlx::query_as::<_, TaskPayload>(
+            r#"
+            SELECT task_id, composite_task_id, title, prompt, base_branch, target_branch, created_at
+            FROM task_payloads WHERE task_id = $1 AND token_hash = $2
+            "#,
+        )
+        .bind(task_id)
+        .bind(secrets::token_hash(token))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(payload)
+    }
+
+    /// Drop a payload once its workflow has finished
+    pub async fn delete_task_payload(&self, task_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM task_payloads WHERE task_id = $1")
+            .bind(task_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Task Artifact Operations
+    // ========================================================================
+
+    /// Store what a task changed; fields missing from a later report keep
+    /// their earlier values
+    pub async fn save_task_artifact(&self, artifact: &TaskArtifact) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO task_artifacts (task_id, title, summary, files_changed, diff_stat, pr_url, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (task_id) DO UPDATE SET
+                title = EXCLUDED.title,
+                summary = COALESCE(EXCLUDED.summary, task_artifacts.summary),
+                files_changed = CASE
+                    WHEN cardinality(EXCLUDED.files_changed) = 0 THEN task_artifacts.files_changed
+                    ELSE EXCLUDED.files_changed
+                END,
+                diff_stat = COALESCE(EXCLUDED.diff_stat, task_artifacts.diff_stat),
+                pr_url = COALESCE(EXCLUDED.pr_url, task_artifacts.pr_url),
+                created_at = EXCLUDED.created_at
+            "#,
+        )
+        .bind(&artifact.task_id)
+        .bind(&artifact.title)
+        .bind(&artifact.summary)
+        .bind(&artifact.files_changed)
+        .bind(&artifact.diff_stat)
+        .bind(&artifact.pr_url)
+        .bind(artifact.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Artifacts of the given tasks (tasks without one are left out)
+    pub async fn get_task_artifacts(&self, task_ids: &[String]) -> Result<Vec<TaskArtifact>> {
+        let artifacts = sqlx::query_as::<_, TaskArtifact>(
+            "SELECT * FROM task_artifacts WHERE task_id = ANY($1) ORDER BY created_at",
+        )
+        .bind(task_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(artifacts)
+    }
+
+    // ========================================================================
+    // Schedule Operations
+    // ========================================================================
+
+    pub async fn create_schedule(
+        &self,
+        schedule: &NewSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<ScheduleRecord> {
+        let record = sqlx::query_as::<_, ScheduleRecord>(
+            r#"
+            INSERT INTO schedules (
+                cron_expression, repository_owner, repository_name, title,
+                description, prompt, auto_approve, enabled, next_run_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(&schedule.cron_expression)
+        .bind(&schedule.repository_owner)
+        .bind(&schedule.repository_name)
+        .bind(&schedule.title)
+        .bind(&schedule.description)
+        .bind(&schedule.prompt)
+        .bind(schedule.auto_approve)
+        .bind(schedule.enabled)
+        .bind(next_run_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn get_schedule(&self, id: i64) -> Result<Option<ScheduleRecord>> {
+        let record = sqlx::query_as::<_, ScheduleRecord>("SELECT * FROM schedules WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(record)
+    }
+
+    /// Schedules, oldest first, optionally only those of a repository (`owner`, `name`)
+    pub async fn list_schedules(&self, repository: Option<(&str, &str)>) -> Result<Vec<ScheduleRecord>> {
+        let records = match repository {
+            Some((owner, name)) => {
+                sqlx::query_as::<_, ScheduleRecord>(
+                    "SELECT * FROM schedules WHERE repository_owner = $1 AND repository_name = $2 ORDER BY id",
+                )
+                .bind(owner)
+                .bind(name)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as::<_, ScheduleRecord>("SELECT * FROM schedules ORDER BY id")
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+        };
+
+        Ok(records)
+    }
+
+    /// Replace a schedule, returning None when it does not exist
+    pub async fn update_schedule(
+        &self,
+        id: i64,
+        schedule: &NewSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<ScheduleRecord>> {
+        let record = sqlx::query_as::<_, ScheduleRecord>(
+            r#"
+            UPDATE schedules SET
+                cron_expression = $2,
+                repository_owner = $3,
+                repository_name = $4,
+                title = $5,
+                description = $6,
+                prompt = $7,
+                auto_approve = $8,
+                enabled = $9,
+                next_run_at = $10,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(&schedule.cron_expression)
+        .bind(&schedule.repository_owner)
+        .bind(&schedule.repository_name)
+        .bind(&schedule.title)
+        .bind(&schedule.description)
+        .bind(&schedule.prompt)
+        .bind(schedule.auto_approve)
+        .bind(schedule.enabled)
+        .bind(next_run_at)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Delete a schedule, returning false when it does not exist
+    pub async fn delete_schedule(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM schedules WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Enabled schedules whose next run is at or before `now`
+    pub async fn get_due_schedules(&self, now: DateTime<Utc>) -> Result<Vec<ScheduleRecord>> {
+        let records = sqlx::query_as::<_, ScheduleRecord>(
+            "SELECT * FROM schedules WHERE enabled AND next_run_at <= $1 ORDER BY next_run_at",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Record the run of a schedule due at `due_at` as `task_id` and move it to `next_run_at`
+    ///
+    /// Returns false when another worker already ran it (the schedule is no
+    /// longer due at `due_at`), in which case the task must not be created.
+    pub async fn claim_schedule_run(
+        &self,
+        id: i64,
+        due_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+        task_id: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE schedules SET
+                next_run_at = $3,
+                last_run_at = NOW(),
+                last_task_id = $4
+            WHERE id = $1 AND enabled AND next_run_at = $2
+            "#,
+        )
+        .bind(id)
+        .bind(due_at)
+        .bind(next_run_at)
+        .bind(task_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Few-shot Example Operations
+    // ========================================================================
+
+    pub async fn create_example(&self, example: &NewExample) -> Result<ExampleRecord> {
+        let record = sqlx::query_as::<_, ExampleRecord>(
+            r#"
+            INSERT INTO few_shot_examples (
+                repository_owner, repository_name, domain, user_prompt, assistant_response
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(&example.repository_owner)
+        .bind(&example.repository_name)
+        .bind(&example.domain)
+        .bind(&example.user_prompt)
+        .bind(&example.assistant_response)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn get_example(&self, id: i64) -> Result<Option<ExampleRecord>> {
+        let record = sqlx::query_as::<_, ExampleRecord>("SELECT * FROM few_shot_examples WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(record)
+    }
+
+    /// Examples, oldest first
+    ///
+    /// With a repository (`owner`, `name`), only the examples used for it: its
+    /// own and the ones without a repository.
+    pub async fn list_examples(&self, repository: Option<(&str, &str)>) -> Result<Vec<ExampleRecord>> {
+        let records = match repository {
+            Some((owner, name)) => {
+                sqlx::query_as::<_, ExampleRecord>(
+                    r#"
+                    SELECT * FROM few_shot_examples
+                    WHERE (repository_owner = $1 AND repository_name = $2)
+                       OR (repository_owner IS NULL AND repository_name IS NULL)
+                    ORDER BY id
+                    "#,
+                )
+                .bind(owner)
+                .bind(name)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as::<_, ExampleRecord>("SELECT * FROM few_shot_examples ORDER BY id")
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+        };
+
+        Ok(records)
+    }
+
+    /// Replace an example, returning None when it does not exist
+    pub async fn update_example(&self, id: i64, example: &NewExample) -> Result<Option<ExampleRecord>> {
+        let record = sqlx::query_as::<_, ExampleRecord>(
+            r#"
+            UPDATE few_shot_examples SET
+                repository_owner = $2,
+                repository_name = $3,
+                domain = $4,
+                user_prompt = $5,
+                assistant_response = $6,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(&example.repository_owner)
+        .bind(&example.repository_name)
+        .bind(&example.domain)
+        .bind(&example.user_prompt)
+        .bind(&example.assistant_response)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Delete an example, returning false when it does not exist
+    pub async fn delete_example(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM few_shot_examples WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether example embeddings can be stored
+    ///
+    /// Enables the pgvector extension and creates `example_embeddings` on first
+    /// use. This is not a migration because the extension has to be installed
+    /// on the server; without it embeddings are only cached in memory.
+    pub async fn embeddings_available(&self) -> bool {
+        *self
+            .pgvector
+            .get_or_init(|| async {
+                let setup = async {
+                    sqlx::query("CREATE EXTENSION IF NOT EXISTS vector")
+                        .execute(&self.pool)
+                        .await?;
+                    sqlx::query(
+                        r#"
+                        CREATE TABLE IF NOT EXISTS example_embeddings (
+                            model VARCHAR(255) NOT NULL,
+                            content_hash CHAR(32) NOT NULL,
+                            content TEXT NOT NULL,
+                            embedding vector NOT NULL,
+                            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                            PRIMARY KEY (model, content_hash)
+                        )
+                        "#,
+                    )
+                    .execute(&self.pool)
+                    .await?;
+                    Ok::<_, sqlx::Error>(())
+                };
+
+                match setup.await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        tracing::warn!("pgvector is not available, example embeddings stay in memory: {}", e);
+                        false
+                    }
+                }
+            })
+            .await
+    }
+
+    async fn require_embeddings(&self) -> Result<()> {
+        if self.embeddings_available().await {
+            Ok(())
+        } else {
+            Err(Error::Query("pgvector is not available".to_string()))
+        }
+    }
+
+    /// Stored embeddings of `texts` from `model`, as (text, vector)
+    pub async fn get_embeddings(&self, model: &str, texts: &[String]) -> Result<Vec<(String, Vec<f32>)>> {
+        self.require_embeddings().await?;
+
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT content, embedding::text FROM example_embeddings
+            WHERE model = $1 AND content_hash IN (SELECT md5(t) FROM unnest($2::text[]) AS t)
+            "#,
+        )
+        .bind(model)
+        .bind(texts)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(content, embedding)| Ok((content, parse_vector(&embedding)?)))
+            .collect()
+    }
+
+    /// Store embeddings from `model`, replacing existing ones
+    pub async fn save_embeddings(&self, model: &str, vectors: &[(String, Vec<f32>)]) -> Result<()> {
+        self.require_embeddings().await?;
+
+        for (content, vector) in vectors {
+            sqlx::query(
+                r#"
+                INSERT INTO example_embeddings (model, content_hash, content, embedding)
+                VALUES ($1, md5($2), $2, $3::vector)
+                ON CONFLICT (model, content_hash) DO UPDATE SET embedding = EXCLUDED.embedding
+                "#,
+            )
+            .bind(model)
+            .bind(content)
+            .bind(vector_literal(vector))
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Of `texts`, the `limit` whose stored embeddings are closest to `query` (cosine distance)
+    pub async fn nearest_embeddings(
+        &self,
+        model: &str,
+        texts: &[String],
+        query: &[f32],
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        self.require_embeddings().await?;
+
+        let nearest = sqlx::query_scalar(
+            r#"
+            SELECT content FROM example_embeddings
+            WHERE model = $1 AND content_hash IN (SELECT md5(t) FROM unnest($2::text[]) AS t)
+            ORDER BY embedding <=> $3::vector
+            LIMIT $4
+            "#,
+        )
+        .bind(model)
+        .bind(texts)
+        .bind(vector_literal(query))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(nearest)
+    }
+
+    // ========================================================================
+    // AI Response Cache Operations
+    // ========================================================================
+
+    /// Cached AI response stored under `key`, unless it has expired
+    pub async fn get_cached_response(&self, key: &str) -> Result<Option<String>> {
+        let response = sqlx::query_scalar(
+            "SELECT response FROM ai_response_cache WHERE cache_key = $1 AND expires_at > NOW()",
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(response)
+    }
+
+    /// Store an AI response, dropping expired ones
+    pub async fn save_cached_response(
+        &self,
+        key: &str,
+        model: &str,
+        response: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query("DELETE FROM ai_response_cache WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO ai_response_cache (cache_key, model, response, expires_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (cache_key) DO UPDATE SET
+                response = EXCLUDED.response,
+                created_at = NOW(),
+                expires_at = EXCLUDED.expires_at
+            "#,
+        )
+        .bind(key)
+        .bind(model)
+        .bind(response)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drop the AI response stored under `key`
+    pub async fn delete_cached_response(&self, key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM ai_response_cache WHERE cache_key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Logging Operations
     // ========================================================================
 
     /// Add execution log
@@ -345,44 +1414,411 @@ impl Database {
         Ok(logs)
     }
 
+    /// Execution logs of every subtask of a composite task, oldest first
+    pub async fn get_composite_execution_logs(&self, composite_task_id: &str) -> Result<Vec<ExecutionLog>> {
+        let logs = sqlx::query_as::<_, ExecutionLog>(
+            r#"
+            SELECT l.* FROM execution_logs l
+            JOIN composite_task_subtasks cts ON l.task_id = cts.subtask_id
+            WHERE cts.composite_task_id = $1
+            ORDER BY l.timestamp, l.id
+            "#,
+        )
+        .bind(composite_task_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(logs)
+    }
+
+    /// Execution logs of a task after log `after_id`, oldest first, optionally
+    /// only of one event type (case-insensitive)
+    pub async fn get_execution_logs_after(
+        &self,
+        task_id: &str,
+        after_id: i32,
+        event_type: Option<&str>,
+    ) -> Result<Vec<ExecutionLog>> {
+        let logs = sqlx::query_as::<_, ExecutionLog>(
+            r#"
+            SELECT * FROM execution_logs
+            WHERE task_id = $1 AND id > $2 AND ($3::TEXT IS NULL OR UPPER(event_type) = UPPER($3))
+            ORDER BY id
+            "#,
+        )
+        .bind(task_id)
+        .bind(after_id)
+        .bind(event_type)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(logs)
+    }
+
+    /// Append a batch of container output lines
+    pub async fn add_task_logs(&self, task_id: &str, lines: &[String]) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO task_logs (task_id, line, timestamp)
+            SELECT $1, line, NOW() FROM UNNEST($2::TEXT[]) WITH ORDINALITY AS t(line, n)
+            ORDER BY n
+            "#,
+        )
+        .bind(task_id)
+        .bind(lines)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Container output lines after line `after_id`, oldest first
+    pub async fn get_task_logs(&self, task_id: &str, after_id: i64, limit: i64) -> Result<Vec<TaskLog>> {
+        let logs = sqlx::query_as::<_, TaskLog>(
+            "SELECT * FROM task_logs WHERE task_id = $1 AND id > $2 ORDER BY id LIMIT $3",
+        )
+        .bind(task_id)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(logs)
+    }
+
+    /// Delete output lines written before `before`, returning how many were deleted
+    pub async fn delete_task_logs_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM task_logs WHERE timestamp < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Last `limit` output lines of a task, oldest first
+    pub async fn get_recent_task_logs(&self, task_id: &str, limit: i64) -> Result<Vec<TaskLog>> {
+        let logs = sqlx::query_as::<_, TaskLog>(
+            r#"
+            SELECT * FROM (
+                SELECT * FROM task_logs WHERE task_id = $1 ORDER BY id DESC LIMIT $2
+            ) recent
+            ORDER BY id
+            "#,
+        )
+        .bind(task_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(logs)
+    }
+
     // ========================================================================
-    // Metrics Operations
+    // Audit Operations
     // ========================================================================
 
-    /// Save metrics
-    pub async fn save_metrics(
+    /// Append an event to the audit trail
+    ///
+    /// Appends are serialized with an advisory lock so every event chains to
+    /// the one before it, whichever process records it.
+    pub async fn add_audit_event(&self, event: &NewAuditEvent) -> Result<AuditEvent> {
+        // Postgres keeps microseconds; hash what will be read back
+        let created_at = Utc::now().trunc_subsecs(6);
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(AUDIT_LOCK_ID)
+            .execute(&mut *tx)
+            .await?;
+
+        let prev_hash: String = sqlx::query_scalar("SELECT hash FROM audit_events ORDER BY id DESC LIMIT 1")
+            .fetch_optional(&mut *tx)
+            .await?
+            .unwrap_or_else(|| AUDIT_GENESIS_HASH.to_string());
+        let hash = audit_hash(
+            &prev_hash,
+            created_at,
+            &event.actor,
+            &event.action,
+            event.target.as_deref(),
+            event.repository.as_deref(),
+            &event.payload,
+        );
+
+        let record = sqlx::query_as::<_, AuditEvent>(
+            r#"
+            INSERT INTO audit_events (created_at, actor, action, target, repository, payload, prev_hash, hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(created_at)
+        .bind(&event.actor)
+        .bind(&event.action)
+        .bind(&event.target)
+        .bind(&event.repository)
+        .bind(sqlx::types::Json(&event.payload))
+        .bind(&prev_hash)
+        .bind(&hash)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(record)
+    }
+
+    /// List audit events matching `query`, newest first, with the total count
+    pub async fn list_audit_events(&self, query: &AuditQuery) -> Result<(Vec<AuditEvent>, i64)> {
+        fn push_filters<'a>(builder: &mut QueryBuilder<'a, Postgres>, query: &'a AuditQuery) {
+            builder.push(" WHERE TRUE");
+            if let Some(actor) = &query.actor {
+                builder.push(" AND actor = ").push_bind(actor);
+            }
+            if let Some(action) = &query.action {
+                builder.push(" AND action = ").push_bind(action);
+            }
+            if let Some(target) = &query.target {
+                builder.push(" AND target = ").push_bind(target);
+            }
+            if let Some(repository) = &query.repository {
+                builder.push(" AND LOWER(repository) = LOWER(").push_bind(repository).push(")");
+            }
+            if let Some(since) = query.since {
+                builder.push(" AND created_at >= ").push_bind(since);
+            }
+            if let Some(until) = query.until {
+                builder.push(" AND created_at < ").push_bind(until);
+            }
+        }
+
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM audit_events");
+        push_filters(&mut count, query);
+        let total: i64 = count.build_query_scalar().fetch_one(&self.pool).await?;
+
+        let mut select = QueryBuilder::new("SELECT * FROM audit_events");
+        push_filters(&mut select, query);
+        select
+            .push(" ORDER BY id DESC LIMIT ")
+            .push_bind(query.limit)
+            .push(" OFFSET ")
+            .push_bind(query.offset);
+
+        let events = select
+            .build_query_as::<AuditEvent>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok((events, total))
+    }
+
+    /// Recompute the audit chain, returning the ID of the first event that
+    /// was changed, or follows a deleted one (None when the chain is intact)
+    pub async fn verify_audit_chain(&self) -> Result<Option<i64>> {
+        let events = sqlx::query_as::<_, AuditEvent>("SELECT * FROM audit_events ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(first_broken_link(&events))
+    }
+
+    // ========================================================================
+    // Secret Operations
+    // ========================================================================
+
+    /// Store or replace secret `secret` of `owner/name`, encrypted with `cipher`
+    pub async fn set_secret(
         &self,
-        task_id: &str,
-        execution_time_ms: i64,
-        files_changed: i32,
-        lines_added: i32,
-        lines_removed: i32,
-        ai_tokens_used: i32,
+        cipher: &SecretCipher,
+        owner: &str,
+        name: &str,
+        secret: &str,
+        value: &str,
     ) -> Result<()> {
+        secrets::validate_secret_name(secret)?;
+        let (nonce, ciphertext) = cipher.seal(owner, name, secret, value)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO repository_secrets (repository_owner, repository_name, name, nonce, ciphertext)
+            VALUES (LOWER($1), LOWER($2), $3, $4, $5)
+            ON CONFLICT (repository_owner, repository_name, name) DO UPDATE SET
+                nonce = EXCLUDED.nonce,
+                ciphertext = EXCLUDED.ciphertext,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(owner)
+        .bind(name)
+        .bind(secret)
+        .bind(nonce)
+        .bind(ciphertext)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Names of the secrets of `owner/name`, without their values
+    pub async fn list_secrets(&self, owner: &str, name: &str) -> Result<Vec<SecretRecord>> {
+        let records = sqlx::query_as::<_, SecretRecord>(
+            r#"
+            SELECT repository_owner, repository_name, name, created_at, updated_at
+            FROM repository_secrets
+            WHERE repository_owner = LOWER($1) AND repository_name = LOWER($2)
+            ORDER BY name
+            "#,
+        )
+        .bind(owner)
+        .bind(name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Delete a secret, returning whether it existed
+    pub async fn delete_secret(&self, owner: &str, name: &str, secret: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM repository_secrets WHERE repository_owner = LOWER($1) AND repository_name = LOWER($2) AND name = $3",
+        )
+        .bind(owner)
+        .bind(name)
+        .bind(secret)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Decrypted secrets of `owner/name` by name
+    pub async fn get_repository_secrets(
+        &self,
+        cipher: &SecretCipher,
+        owner: &str,
+        name: &str,
+    ) -> Result<HashMap<String, String>> {
+        let rows = sqlx::query(
+            "SELECT name, nonce, ciphertext FROM repository_secrets WHERE repository_owner = LOWER($1) AND repository_name = LOWER($2)",
+        )
+        .bind(owner)
+        .bind(name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let secret: String = row.get("name");
+                let nonce: Vec<u8> = row.get("nonce");
+                let ciphertext: Vec<u8> = row.get("ciphertext");
+                let value = cipher.open(owner, name, &secret, &nonce, &ciphertext)?;
+                Ok((secret, value))
+            })
+            .collect()
+    }
+
+    /// Let the workflow of `task_id` fetch the secrets of `owner/name` once
+    /// within `ttl`, returning the token it must present
+    pub async fn create_secret_grant(&self, task_id: &str, owner: &str, name: &str, ttl: Duration) -> Result<String> {
+        let (token, token_hash) = secrets::new_grant_token();
+        let expires_at = Utc::now() + chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::hours(1));
+
+        sqlx::query(
+            r#"
+            INSERT INTO secret_grants (task_id, token_hash, repository_owner, repository_name, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (task_id) DO UPDATE SET
+                token_hash = EXCLUDED.token_hash,
+                repository_owner = EXCLUDED.repository_owner,
+                repository_name = EXCLUDED.repository_name,
+                expires_at = EXCLUDED.expires_at
+            "#,
+        )
+        .bind(task_id)
+        .bind(token_hash)
+        .bind(owner)
+        .bind(name)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Use up the grant of `task_id`, returning its repository (owner, name)
+    ///
+    /// None when the token does not match or the grant expired or was used.
+    pub async fn redeem_secret_grant(&self, task_id: &str, token: &str) -> Result<Option<(String, String)>> {
+        let row = sqlx::query(
+            r#"
+            DELETE FROM secret_grants
+            WHERE task_id = $1 AND token_hash = $2 AND expires_at > NOW()
+            RETURNING repository_owner, repository_name
+            "#,
+        )
+        .bind(task_id)
+        .bind(secrets::token_hash(token))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| (row.get("repository_owner"), row.get("repository_name"))))
+    }
+
+    // ========================================================================
+    // Metrics Operations
+    // ========================================================================
+
+    /// Save metrics
+    ///
+    /// A task may get several rows (runs, review and CI fixes); readers sum them.
+    pub async fn save_metrics(&self, task_id: &str, metrics: &NewMetrics) -> Result<()> {
+        let ai_tokens_used = (metrics.ai_input_tokens + metrics.ai_output_tokens).min(i32::MAX.into()) as i32;
+
         sqlx::query(
             r#"
             INSERT INTO metrics (
                 task_id, execution_time_ms, files_changed,
-                lines_added, lines_removed, ai_tokens_used, timestamp
-            ) VALUES ($1, $2, $3, $4, $5, $6, NOW())
+                lines_added, lines_removed, ai_tokens_used,
+                ai_input_tokens, ai_output_tokens, ai_cost_usd, timestamp
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
             "#,
         )
         .bind(task_id)
-        .bind(execution_time_ms)
-        .bind(files_changed)
-        .bind(lines_added)
-        .bind(lines_removed)
+        .bind(metrics.execution_time_ms)
+        .bind(metrics.files_changed)
+        .bind(metrics.lines_added)
+        .bind(metrics.lines_removed)
         .bind(ai_tokens_used)
+        .bind(metrics.ai_input_tokens)
+        .bind(metrics.ai_output_tokens)
+        .bind(metrics.ai_cost_usd)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    /// Get task metrics
+    /// Get task metrics, summed over all rows of the task
     pub async fn get_task_metrics(&self, task_id: &str) -> Result<Option<Metrics>> {
         let metrics = sqlx::query_as::<_, Metrics>(
-            "SELECT * FROM metrics WHERE task_id = $1 ORDER BY timestamp DESC LIMIT 1",
+            r#"
+            SELECT
+                MAX(id) as id,
+                task_id,
+                SUM(execution_time_ms)::BIGINT as execution_time_ms,
+                SUM(files_changed)::INTEGER as files_changed,
+                SUM(lines_added)::INTEGER as lines_added,
+                SUM(lines_removed)::INTEGER as lines_removed,
+                SUM(ai_tokens_used)::INTEGER as ai_tokens_used,
+                MAX(timestamp) as timestamp,
+                SUM(ai_input_tokens)::BIGINT as ai_input_tokens,
+                SUM(ai_output_tokens)::BIGINT as ai_output_tokens,
+                SUM(ai_cost_usd)::FLOAT8 as ai_cost_usd
+            FROM metrics
+            WHERE task_id = $1
+            GROUP BY task_id
+            "#,
         )
         .bind(task_id)
         .fetch_optional(&self.pool)
@@ -391,9 +1827,45 @@ impl Database {
         Ok(metrics)
     }
 
+    /// AI usage of every task since `since` (monthly budget)
+    pub async fn get_ai_usage_since(&self, since: DateTime<Utc>) -> Result<AiUsage> {
+        let usage = sqlx::query_as::<_, AiUsage>(&format!("{} FROM metrics WHERE timestamp >= $1", AI_USAGE_SELECT))
+            .bind(since)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(usage)
+    }
+
+    /// AI usage of the subtasks of a composite task
+    pub async fn get_composite_ai_usage(&self, composite_task_id: &str) -> Result<AiUsage> {
+        let usage = sqlx::query_as::<_, AiUsage>(&format!(
+            "{} FROM metrics JOIN composite_task_subtasks cts ON task_id = cts.subtask_id \
+             WHERE cts.composite_task_id = $1",
+            AI_USAGE_SELECT
+        ))
+        .bind(composite_task_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(usage)
+    }
+
+    /// Composite task `task_id` is a subtask of, if any
+    pub async fn get_parent_composite_id(&self, task_id: &str) -> Result<Option<String>> {
+        let composite_task_id = sqlx::query_scalar::<_, String>(
+            "SELECT composite_task_id FROM composite_task_subtasks WHERE subtask_id = $1 LIMIT 1",
+        )
+        .bind(task_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(composite_task_id)
+    }
+
     /// Get aggregate statistics
-    pub async fn get_aggregate_stats(&self) -> Result<AggregateStats> {
-        let row = sqlx::query(
+    pub async fn get_aggregate_stats(&self, filter: &StatsFilter) -> Result<AggregateStats> {
+        let mut query = QueryBuilder::new(
             r#"
             SELECT
                 COUNT(*) as total_tasks,
@@ -402,15 +1874,19 @@ impl Database {
                 AVG(CASE
                     WHEN completed_at IS NOT NULL AND started_at IS NOT NULL
                     THEN EXTRACT(EPOCH FROM (completed_at - started_at)) * 1000
-                END) as avg_execution_time_ms,
-                SUM(m.files_changed) as total_files_changed,
-                SUM(m.ai_tokens_used) as total_tokens_used
+                END)::FLOAT8 as avg_execution_time_ms,
+                SUM(m.files_changed)::BIGINT as total_files_changed,
+                SUM(m.lines_added)::BIGINT as total_lines_added,
+                SUM(m.lines_removed)::BIGINT as total_lines_removed,
+                SUM(m.ai_tokens_used)::BIGINT as total_tokens_used,
+                SUM(m.ai_cost_usd)::FLOAT8 as total_cost_usd
             FROM tasks t
-            LEFT JOIN metrics m ON t.id = m.task_id
             "#,
-        )
-        .fetch_one(&self.pool)
-        .await?;
+        );
+        query.push(TASK_METRICS_JOIN);
+        push_stats_filter(&mut query, filter);
+
+        let row = query.build().fetch_one(&self.pool).await?;
 
         Ok(AggregateStats {
             total_tasks: row.get("total_tasks"),
@@ -418,7 +1894,377 @@ impl Database {
             failed_tasks: row.get("failed_tasks"),
             avg_execution_time_ms: row.get("avg_execution_time_ms"),
             total_files_changed: row.get("total_files_changed"),
+            total_lines_added: row.get("total_lines_added"),
+            total_lines_removed: row.get("total_lines_removed"),
             total_tokens_used: row.get("total_tokens_used"),
+            total_cost_usd: row.get("total_cost_usd"),
+        })
+    }
+
+    /// Statistics per repository, busiest first
+    pub async fn get_repository_stats(&self, filter: &StatsFilter) -> Result<Vec<RepositoryStats>> {
+        let mut query = QueryBuilder::new(
+            r#"
+            SELECT
+                t.repository_owner,
+                t.repository_name,
+                COUNT(*) as total_tasks,
+                COUNT(CASE WHEN status = 'Completed' THEN 1 END) as completed_tasks,
+                COUNT(CASE WHEN status = 'Failed' THEN 1 END) as failed_tasks,
+                AVG(CASE
+                    WHEN completed_at IS NOT NULL AND started_at IS NOT NULL
+                    THEN EXTRACT(EPOCH FROM (completed_at - started_at)) * 1000
+                END)::FLOAT8 as avg_execution_time_ms,
+                SUM(m.files_changed)::BIGINT as total_files_changed,
+                SUM(m.lines_added)::BIGINT as total_lines_added,
+                SUM(m.lines_removed)::BIGINT as total_lines_removed,
+                SUM(m.ai_tokens_used)::BIGINT as total_tokens_used,
+                SUM(m.ai_cost_usd)::FLOAT8 as total_cost_usd
+            FROM tasks t
+            "#,
+        );
+        query.push(TASK_METRICS_JOIN);
+        push_stats_filter(&mut query, filter);
+        query.push(
+            " GROUP BY t.repository_owner, t.repository_name \
+             ORDER BY total_tasks DESC, t.repository_owner, t.repository_name",
+        );
+
+        let stats = query
+            .build_query_as::<RepositoryStats>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(stats)
+    }
+
+    /// Statistics per tag, busiest first (a task counts once for each of its tags)
+    pub async fn get_tag_stats(&self, filter: &StatsFilter) -> Result<Vec<TagStats>> {
+        let mut query = QueryBuilder::new(
+            r#"
+            SELECT
+                tag,
+                COUNT(*) as total_tasks,
+                COUNT(CASE WHEN status = 'Completed' THEN 1 END) as completed_tasks,
+                COUNT(CASE WHEN status = 'Failed' THEN 1 END) as failed_tasks,
+                AVG(CASE
+                    WHEN completed_at IS NOT NULL AND started_at IS NOT NULL
+                    THEN EXTRACT(EPOCH FROM (completed_at - started_at)) * 1000
+                END)::FLOAT8 as avg_execution_time_ms,
+                SUM(m.ai_tokens_used)::BIGINT as total_tokens_used,
+                SUM(m.ai_cost_usd)::FLOAT8 as total_cost_usd
+            FROM tasks t
+            CROSS JOIN LATERAL UNNEST(t.tags) as tag
+            "#,
+        );
+        query.push(TASK_METRICS_JOIN);
+        push_stats_filter(&mut query, filter);
+        query.push(" GROUP BY tag ORDER BY total_tasks DESC, tag");
+
+        let stats = query
+            .build_query_as::<TagStats>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(stats)
+    }
+
+    /// Task counts per day (UTC), oldest first
+    pub async fn get_daily_stats(&self, filter: &StatsFilter) -> Result<Vec<DailyStats>> {
+        let mut query = QueryBuilder::new(
+            r#"
+            SELECT
+                (t.created_at AT TIME ZONE 'UTC')::DATE as date,
+                COUNT(*) as total_tasks,
+                COUNT(CASE WHEN status = 'Completed' THEN 1 END) as completed_tasks,
+                COUNT(CASE WHEN status = 'Failed' THEN 1 END) as failed_tasks,
+                SUM(m.ai_tokens_used)::BIGINT as total_tokens_used,
+                SUM(m.ai_cost_usd)::FLOAT8 as total_cost_usd
+            FROM tasks t
+            "#,
+        );
+        query.push(TASK_METRICS_JOIN);
+        push_stats_filter(&mut query, filter);
+        query.push(" GROUP BY date ORDER BY date");
+
+        let stats = query
+            .build_query_as::<DailyStats>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(stats)
+    }
+
+    /// Tasks finished per day or week since `filter.since` (30 days when
+    /// unset), oldest first and with a row for every bucket
+    ///
+    /// Tasks are bucketed by completion time (creation time while unfinished),
+    /// so `since` selects tasks finished or created after it.
+    pub async fn get_timeseries_stats(
+        &self,
+        filter: &StatsFilter,
+        granularity: StatsGranularity,
+    ) -> Result<Vec<TimeseriesStats>> {
+        let since = filter
+            .since
+            .unwrap_or_else(|| Utc::now() - chrono::Duration::days(DEFAULT_TIMESERIES_DAYS));
+        let scope = StatsFilter {
+            since: None,
+            ..filter.clone()
+        };
+        let unit = granularity.unit();
+
+        let mut query = QueryBuilder::new(format!(
+            r#"
+            WITH buckets AS (
+                SELECT generate_series(
+                    date_trunc('{unit}', "#
+        ));
+        query.push_bind(since);
+        query.push(format!(
+            r#" AT TIME ZONE 'UTC'),
+                    date_trunc('{unit}', NOW() AT TIME ZONE 'UTC'),
+                    INTERVAL '1 {unit}'
+                ) as bucket
+            ),
+            finished AS (
+                SELECT
+                    date_trunc('{unit}', COALESCE(t.completed_at, t.created_at) AT TIME ZONE 'UTC') as bucket,
+                    t.status,
+                    t.started_at,
+                    t.completed_at,
+                    m.ai_tokens_used,
+                    m.ai_cost_usd
+                FROM tasks t
+            "#
+        ));
+        query.push(TASK_METRICS_JOIN);
+        push_stats_filter(&mut query, &scope);
+        query.push(" AND COALESCE(t.completed_at, t.created_at) >= ").push_bind(since);
+        query.push(
+            r#"
+            )
+            SELECT
+                b.bucket::DATE as date,
+                COUNT(f.bucket) as total_tasks,
+                COUNT(CASE WHEN f.status = 'Completed' THEN 1 END) as completed_tasks,
+                COUNT(CASE WHEN f.status = 'Failed' THEN 1 END) as failed_tasks,
+                AVG(CASE
+                    WHEN f.completed_at IS NOT NULL AND f.started_at IS NOT NULL
+                    THEN EXTRACT(EPOCH FROM (f.completed_at - f.started_at)) * 1000
+                END)::FLOAT8 as avg_execution_time_ms,
+                SUM(f.ai_tokens_used)::BIGINT as total_tokens_used,
+                SUM(f.ai_cost_usd)::FLOAT8 as total_cost_usd
+            FROM buckets b
+            LEFT JOIN finished f ON f.bucket = b.bucket
+            GROUP BY b.bucket
+            ORDER BY b.bucket
+            "#,
+        );
+
+        let stats = query
+            .build_query_as::<TimeseriesStats>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(stats)
+    }
+}
+
+/// Window of `get_timeseries_stats` without `StatsFilter::since`
+pub const DEFAULT_TIMESERIES_DAYS: i64 = 30;
+
+const AI_USAGE_SELECT: &str = "SELECT \
+     COALESCE(SUM(ai_input_tokens), 0)::BIGINT as input_tokens, \
+     COALESCE(SUM(ai_output_tokens), 0)::BIGINT as output_tokens, \
+     COALESCE(SUM(ai_cost_usd), 0)::FLOAT8 as cost_usd";
+
+/// Metrics summed per task, so tasks with several metrics rows are counted once
+const TASK_METRICS_JOIN: &str = r#"
+            LEFT JOIN (
+                SELECT
+                    task_id,
+                    SUM(files_changed) as files_changed,
+                    SUM(lines_added) as lines_added,
+                    SUM(lines_removed) as lines_removed,
+                    SUM(ai_tokens_used) as ai_tokens_used,
+                    SUM(ai_cost_usd) as ai_cost_usd
+                FROM metrics
+                GROUP BY task_id
+            ) m ON t.id = m.task_id
+"#;
+
+/// WHERE clause shared by the statistics queries (`tasks` aliased as `t`)
+fn push_stats_filter<'a>(builder: &mut QueryBuilder<'a, Postgres>, filter: &'a StatsFilter) {
+    builder.push(" WHERE TRUE");
+    if let Some(owner) = &filter.repository_owner {
+        builder.push(" AND t.repository_owner = ").push_bind(owner);
+    }
+    if let Some(name) = &filter.repository_name {
+        builder.push(" AND t.repository_name = ").push_bind(name);
+    }
+    if let Some(org_id) = &filter.org_id {
+        builder.push(" AND t.org_id = LOWER(").push_bind(org_id).push(")");
+    }
+    if let Some(since) = filter.since {
+        builder.push(" AND t.created_at >= ").push_bind(since);
+    }
+    if let Some(tag) = &filter.tag {
+        builder.push(" AND t.tags @> ARRAY[LOWER(").push_bind(tag).push(")]::TEXT[]");
+    }
+}
+
+/// Approved batches for the `approved_batches INTEGER[]` column
+///
+/// Upserts merge rather than overwrite this column so an approval stored by
+/// another process (e.g. the API) is not lost.
+fn approved_batches(composite_task: &CompositeTask) -> Vec<i32> {
+    composite_task
+        .approved_batches
+        .iter()
+        .map(|batch| *batch as i32)
+        .collect()
+}
+
+/// SHA-256 (hex) of a normalized prompt
+fn prompt_hash(prompt: &str) -> String {
+    hex::encode(Sha256::digest(autodev_core::normalize_prompt(prompt).as_bytes()))
+}
+
+/// SHA-256 (hex) of an audit event chained to `prev_hash`
+fn audit_hash(
+    prev_hash: &str,
+    created_at: DateTime<Utc>,
+    actor: &str,
+    action: &str,
+    target: Option<&str>,
+    repository: Option<&str>,
+    payload: &serde_json::Value,
+) -> String {
+    let mut hasher = Sha256::new();
+    for field in [
+        prev_hash,
+        &created_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+        actor,
+        action,
+        target.unwrap_or(""),
+        repository.unwrap_or(""),
+        &canonical_json(payload),
+    ] {
+        hasher.update(field.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// JSON with object keys sorted, so a payload hashes the same after a JSONB round trip
+fn canonical_json(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| key.as_str());
+            let fields: Vec<String> = entries
+                .into_iter()
+                .map(|(key, value)| format!("{}:{}", serde_json::Value::from(key.as_str()), canonical_json(value)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        serde_json::Value::Array(values) => {
+            let values: Vec<String> = values.iter().map(canonical_json).collect();
+            format!("[{}]", values.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// ID of the first event whose link or hash does not match
+fn first_broken_link(events: &[AuditEvent]) -> Option<i64> {
+    let mut prev_hash = AUDIT_GENESIS_HASH;
+
+    for event in events {
+        let hash = audit_hash(
+            prev_hash,
+            event.created_at,
+            &event.actor,
+            &event.action,
+            event.target.as_deref(),
+            event.repository.as_deref(),
+            &event.payload,
+        );
+        if event.prev_hash != prev_hash || event.hash != hash {
+            return Some(event.id);
+        }
+        prev_hash = &event.hash;
+    }
+
+    None
+}
+
+/// pgvector text form of a vector (`[1,2.5,3]`)
+fn vector_literal(vector: &[f32]) -> String {
+    let values: Vec<String> = vector.iter().map(|v| v.to_string()).collect();
+    format!("[{}]", values.join(","))
+}
+
+fn parse_vector(literal: &str) -> Result<Vec<f32>> {
+    literal
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| {
+            v.trim()
+                .parse()
+                .map_err(|_| Error::Query(format!("Invalid vector value: {}", v)))
         })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_literal_round_trip() {
+        let vector = vec![0.25, -1.0, 3.5];
+        assert_eq!(vector_literal(&vector), "[0.25,-1,3.5]");
+        assert_eq!(parse_vector(&vector_literal(&vector)).unwrap(), vector);
+        assert!(parse_vector("[]").unwrap().is_empty());
+        assert!(parse_vector("[1,x]").is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_audit_chain() {
+        let created_at = Utc::now().trunc_subsecs(6);
+        let mut events: Vec<AuditEvent> = Vec::new();
+        for (id, action) in [(1, "task.create"), (2, "branch.create"), (3, "merge_request.merge")] {
+            let prev_hash = events.last().map_or(AUDIT_GENESIS_HASH.to_string(), |e| e.hash.clone());
+            let payload = serde_json::json!({ "branch": "autodev/1", "draft": false });
+            events.push(AuditEvent {
+                id,
+                created_at,
+                actor: "api:alice".to_string(),
+                action: action.to_string(),
+                target: Some("1".to_string()),
+                repository: Some("owner/repo".to_string()),
+                hash: audit_hash(&prev_hash, created_at, "api:alice", action, Some("1"), Some("owner/repo"), &payload),
+                payload,
+                prev_hash,
+            });
+        }
+        assert_eq!(first_broken_link(&events), None);
+
+        // Key order does not matter (JSONB reorders keys)
+        let reordered: serde_json::Value = serde_json::from_str(r#"{"draft":false,"branch":"autodev/1"}"#).unwrap();
+        assert_eq!(canonical_json(&reordered), canonical_json(&events[0].payload));
+
+        let mut edited = events.clone();
+        edited[1].payload = serde_json::json!({ "branch": "main" });
+        assert_eq!(first_broken_link(&edited), Some(2));
+
+        let mut deleted = events.clone();
+        deleted.remove(1);
+        assert_eq!(first_broken_link(&deleted), Some(3));
+    }
+}
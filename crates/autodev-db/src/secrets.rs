@@ -0,0 +1,151 @@
+//! Per-repository secrets, encrypted at rest
+//!
+//! Values are sealed with AES-256-GCM under the master key in
+//! `AUTODEV_SECRETS_KEY` (64 hex characters, e.g. `openssl rand -hex 32`).
+//! The repository and secret name are bound as associated data, so a value
+//! copied to another row does not decrypt. Without the key secrets can still
+//! be listed and deleted, but not written or read.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use sha2::{Digest, Sha256};
+
+use crate::{Error, Result};
+
+pub const SECRETS_KEY_ENV: &str = "AUTODEV_SECRETS_KEY";
+
+/// Seals and opens secret values with the master key
+#[derive(Clone)]
+pub struct SecretCipher {
+    cipher: Aes256Gcm,
+}
+
+impl std::fmt::Debug for SecretCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretCipher(..)")
+    }
+}
+
+impl SecretCipher {
+    /// Cipher for a 32-byte key given as 64 hex characters
+    pub fn from_hex(key: &str) -> Result<Self> {
+        let cipher = hex::decode(key.trim())
+            .ok()
+            .and_then(|key| Aes256Gcm::new_from_slice(&key).ok())
+            .ok_or_else(|| Error::Secret(format!("{} must be 64 hex characters (32 bytes)", SECRETS_KEY_ENV)))?;
+
+        Ok(Self { cipher })
+    }
+
+    /// Cipher from AUTODEV_SECRETS_KEY, None when it is unset
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var(SECRETS_KEY_ENV) {
+            Ok(key) if !key.trim().is_empty() => Ok(Some(Self::from_hex(&key)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Encrypt `value`, returning (nonce, ciphertext)
+    pub fn seal(&self, owner: &str, name: &str, secret: &str, value: &str) -> Result<(Vec<u8>, Vec<u8>)> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = associated_data(owner, name, secret);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, Payload { msg: value.as_bytes(), aad: &aad })
+            .map_err(|_| Error::Secret(format!("Failed to encrypt secret {}", secret)))?;
+
+        Ok((nonce.to_vec(), ciphertext))
+    }
+
+    /// Decrypt a value sealed by [`SecretCipher::seal`] for the same repository and name
+    pub fn open(&self, owner: &str, name: &str, secret: &str, nonce: &[u8], ciphertext: &[u8]) -> Result<String> {
+        let nonce: [u8; 12] = nonce
+            .try_into()
+            .map_err(|_| Error::Secret(format!("Secret {} has an invalid nonce", secret)))?;
+
+        let aad = associated_data(owner, name, secret);
+        let value = self
+            .cipher
+            .decrypt(&Nonce::from(nonce), Payload { msg: ciphertext, aad: &aad })
+            .map_err(|_| {
+                Error::Secret(format!(
+                    "Secret {} of {}/{} cannot be decrypted (wrong {}?)",
+                    secret, owner, name, SECRETS_KEY_ENV
+                ))
+            })?;
+
+        String::from_utf8(value).map_err(|_| Error::Secret(format!("Secret {} is not UTF-8", secret)))
+    }
+}
+
+fn associated_data(owner: &str, name: &str, secret: &str) -> Vec<u8> {
+    format!("{}/{}/{}", owner.to_lowercase(), name.to_lowercase(), secret).into_bytes()
+}
+
+/// Secret names become environment variables: `A-Z`, `0-9` and `_`, not starting with a digit
+pub fn validate_secret_name(name: &str) -> Result<()> {
+    let valid = name.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(Error::Secret(format!(
+            "Invalid secret name '{}': use upper-case letters, digits and '_', not starting with a digit",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// New one-time token for fetching secrets, returned with the hash that is stored
+pub fn new_grant_token() -> (String, String) {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token = hex::encode(bytes);
+    let hash = token_hash(&token);
+    (token, hash)
+}
+
+/// SHA-256 (hex) a grant token is stored as
+pub fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.trim().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn test_seal_and_open() {
+        let cipher = SecretCipher::from_hex(KEY).unwrap();
+        let (nonce, ciphertext) = cipher.seal("Acme", "web", "NPM_TOKEN", "npm_secret").unwrap();
+        assert!(!ciphertext.windows(10).any(|w| w == b"npm_secret"));
+
+        // Owner and repository names are case-insensitive
+        assert_eq!(cipher.open("acme", "WEB", "NPM_TOKEN", &nonce, &ciphertext).unwrap(), "npm_secret");
+
+        // Bound to the repository and name, and to the key
+        assert!(cipher.open("acme", "api", "NPM_TOKEN", &nonce, &ciphertext).is_err());
+        assert!(cipher.open("acme", "web", "OTHER", &nonce, &ciphertext).is_err());
+        let other = SecretCipher::from_hex(&KEY.replace("00", "ff")).unwrap();
+        assert!(other.open("acme", "web", "NPM_TOKEN", &nonce, &ciphertext).is_err());
+
+        assert!(SecretCipher::from_hex("abcd").is_err());
+    }
+
+    #[test]
+    fn test_secret_names_and_tokens() {
+        assert!(validate_secret_name("NPM_TOKEN").is_ok());
+        assert!(validate_secret_name("_X1").is_ok());
+        assert!(validate_secret_name("").is_err());
+        assert!(validate_secret_name("1X").is_err());
+        assert!(validate_secret_name("npm_token").is_err());
+        assert!(validate_secret_name("A-B").is_err());
+
+        let (token, hash) = new_grant_token();
+        assert_eq!(token.len(), 64);
+        assert_eq!(token_hash(&token), hash);
+        assert_ne!(new_grant_token().0, token);
+    }
+}
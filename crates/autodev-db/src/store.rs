@@ -0,0 +1,74 @@
+use crate::Database;
+use async_trait::async_trait;
+use autodev_core::{CompositeTask, EngineSnapshot, Task, TaskStore};
+
+fn store_error(e: crate::Error) -> autodev_core::Error {
+    autodev_core::Error::StoreError(e.to_string())
+}
+
+#[async_trait]
+impl TaskStore for Database {
+    async fn load_snapshot(&self) -> autodev_core::Result<EngineSnapshot> {
+        let tasks = self
+            .get_open_tasks()
+            .await
+            .map_err(store_error)?
+            .iter()
+            .map(|record| record.to_task())
+            .collect::<autodev_core::Result<Vec<_>>>()?;
+
+        let mut composite_tasks = Vec::new();
+        for record in self.get_open_composite_tasks().await.map_err(store_error)? {
+            let subtasks = self
+                .get_composite_subtasks(&record.id)
+                .await
+                .map_err(store_error)?
+                .iter()
+                .map(|record| record.to_task())
+                .collect::<autodev_core::Result<Vec<_>>>()?;
+
+            composite_tasks.push(CompositeTask {
+                id: record.id,
+                title: record.title,
+                description: record.description,
+                subtasks,
+                auto_approve: record.auto_approve,
+                created_at: record.created_at,
+                completed_at: record.completed_at,
+                cancelled_at: record.cancelled_at,
+                review_state: record.review_state.parse()?,
+                approved_batches: record
+                    .approved_batches
+                    .iter()
+                    .filter_map(|batch| usize::try_from(*batch).ok())
+                    .collect(),
+                max_parallel_tasks: record
+                    .max_parallel_tasks
+                    .and_then(|n| usize::try_from(n).ok()),
+                merge_method: record.merge_method.as_deref().map(str::parse).transpose()?,
+                base_branch: record.base_branch,
+                created_by: record.created_by,
+                failure_policy: record.failure_policy.parse()?,
+                execution_backend: record.execution_backend.as_deref().map(str::parse).transpose()?,
+            });
+        }
+
+        Ok(EngineSnapshot {
+            tasks,
+            composite_tasks,
+        })
+    }
+
+    async fn persist_task(&self, task: &Task) -> autodev_core::Result<()> {
+        self.upsert_task_state(task).await.map_err(store_error)
+    }
+
+    async fn persist_composite_task(
+        &self,
+        composite_task: &CompositeTask,
+    ) -> autodev_core::Result<()> {
+        self.upsert_composite_task_state(composite_task)
+            .await
+            .map_err(store_error)
+    }
+}
@@ -0,0 +1,125 @@
+//! Results passed between dependent tasks
+//!
+//! When a task finishes, what it changed (files, `git diff --stat`, a summary
+//! of the work) is stored as its artifact. Tasks that depend on it get the
+//! artifacts of their finished dependencies appended to their prompt, so a
+//! subtask builds on its predecessors' changes instead of working blind.
+
+use std::sync::Arc;
+
+use autodev_core::Task;
+use autodev_db::{Database, TaskArtifact};
+use autodev_local_executor::TaskResult;
+
+/// Longest summary of a single dependency put into a prompt
+const MAX_SUMMARY_CHARS: usize = 2000;
+
+/// Changed files listed per dependency
+const MAX_LISTED_FILES: usize = 50;
+
+/// Store the artifact of a finished task (failures are only logged)
+pub async fn save_artifact(db: &Option<Arc<Database>>, artifact: TaskArtifact) {
+    let Some(db) = db else {
+        return;
+    };
+
+    if let Err(e) = db.save_task_artifact(&artifact).await {
+        tracing::warn!("Failed to store artifact of task {}: {}", artifact.task_id, e);
+    }
+}
+
+/// Artifact of a locally executed task
+pub fn artifact_from_result(task: &Task, result: &TaskResult) -> TaskArtifact {
+    TaskArtifact {
+        task_id: task.id.clone(),
+        title: task.title.clone(),
+        summary: None,
+        files_changed: result.files_changed.clone(),
+        diff_stat: None,
+        pr_url: result.pr_url.clone(),
+        created_at: chrono::Utc::now(),
+    }
+}
+
+/// Prompt of `task` with the artifacts of its finished dependencies appended
+pub async fn prompt_with_dependencies(task: &Task, db: &Option<Arc<Database>>) -> String {
+    let (Some(db), false) = (db, task.dependencies.is_empty()) else {
+        return task.prompt.clone();
+    };
+
+    match db.get_task_artifacts(&task.dependencies).await {
+        Ok(artifacts) => append_dependency_context(&task.prompt, &artifacts),
+        Err(e) => {
+            tracing::warn!("Failed to load dependency artifacts of task {}: {}", task.id, e);
+            task.prompt.clone()
+        }
+    }
+}
+
+fn append_dependency_context(prompt: &str, artifacts: &[TaskArtifact]) -> String {
+    if artifacts.is_empty() {
+        return prompt.to_string();
+    }
+
+    let mut context = String::from(
+        "\n\n## 선행 작업 결과\n\
+         이 작업이 의존하는 작업들이 이미 다음과 같이 변경했습니다. 이 변경을 기반으로 작업하고, 되돌리거나 중복 구현하지 마세요.\n",
+    );
+
+    for artifact in artifacts {
+        context.push_str(&format!("\n### {}\n", artifact.title));
+
+        if let Some(summary) = artifact.summary.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            context.extend(summary.chars().take(MAX_SUMMARY_CHARS));
+            if summary.chars().count() > MAX_SUMMARY_CHARS {
+                context.push_str(" …");
+            }
+            context.push('\n');
+        }
+
+        if !artifact.files_changed.is_empty() {
+            let listed = &artifact.files_changed[..artifact.files_changed.len().min(MAX_LISTED_FILES)];
+            context.push_str(&format!("변경된 파일: {}", listed.join(", ")));
+            if artifact.files_changed.len() > listed.len() {
+                context.push_str(&format!(" 외 {}개", artifact.files_changed.len() - listed.len()));
+            }
+            context.push('\n');
+        }
+
+        if let Some(diff_stat) = artifact.diff_stat.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            context.push_str(&format!("```\n{}\n```\n", diff_stat));
+        }
+
+        if let Some(ref pr_url) = artifact.pr_url {
+            context.push_str(&format!("PR: {}\n", pr_url));
+        }
+    }
+
+    format!("{}{}", prompt, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_dependency_context() {
+        assert_eq!(append_dependency_context("Add the login page", &[]), "Add the login page");
+
+        let artifact = TaskArtifact {
+            task_id: "t1".to_string(),
+            title: "Add the auth API".to_string(),
+            summary: Some("Added POST /login returning a JWT".to_string()),
+            files_changed: vec!["src/auth.rs".to_string(), "src/routes.rs".to_string()],
+            diff_stat: Some(" 2 files changed, 80 insertions(+)".to_string()),
+            pr_url: None,
+            created_at: chrono::Utc::now(),
+        };
+
+        let prompt = append_dependency_context("Add the login page", &[artifact]);
+        assert!(prompt.starts_with("Add the login page\n\n## 선행 작업 결과"));
+        assert!(prompt.contains("### Add the auth API\nAdded POST /login returning a JWT\n"));
+        assert!(prompt.contains("변경된 파일: src/auth.rs, src/routes.rs\n"));
+        assert!(prompt.contains("2 files changed"));
+    }
+}
@@ -0,0 +1,310 @@
+//! Audit trail of who triggered what and which VCS operations followed
+//!
+//! Events go to the hash-chained `audit_events` table (see
+//! `Database::add_audit_event`) and are listed with `GET /audit`. Recording
+//! never fails the operation being audited: without a database nothing is
+//! recorded, and write errors are only logged.
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use autodev_db::{Database, NewAuditEvent};
+use autodev_vcs::{
+    Branch, CheckRunReport, DiffStat, Mergeability, MergeOptions, PullRequest, RepoConfig, Repository, Result, VcsProvider,
+    WorkflowStatus,
+};
+
+/// Longest pipeline input value kept in an event (prompts are cut)
+const MAX_INPUT_CHARS: usize = 200;
+
+/// Record `event`, logging instead of failing when it cannot be written
+pub async fn record(db: &Option<Arc<Database>>, event: NewAuditEvent) {
+    let Some(db) = db else {
+        return;
+    };
+
+    if let Err(e) = db.add_audit_event(&event).await {
+        tracing::warn!("Failed to record audit event {} by {}: {}", event.action, event.actor, e);
+    }
+}
+
+/// Audit event about `repo`
+pub fn repository_event(actor: &str, action: &str, repo: &Repository) -> NewAuditEvent {
+    NewAuditEvent::new(actor, action).with_repository(&repo.owner, &repo.name)
+}
+
+/// Parts of input names whose values are credentials (e.g. the one-time `secrets_token`)
+const SECRET_INPUT_PATTERNS: [&str; 4] = ["token", "secret", "password", "key"];
+
+/// Shown instead of a credential input
+const REDACTED: &str = "[redacted]";
+
+/// Pipeline inputs as recorded: credentials redacted and long values cut to
+/// [`MAX_INPUT_CHARS`]
+fn recorded_inputs(inputs: &HashMap<String, String>) -> Value {
+    inputs
+        .iter()
+        .map(|(key, value)| {
+            let name = key.to_lowercase();
+            let value = if SECRET_INPUT_PATTERNS.iter().any(|pattern| name.contains(pattern)) {
+                REDACTED.to_string()
+            } else {
+                match value.char_indices().nth(MAX_INPUT_CHARS) {
+                    Some((end, _)) => format!("{}…", &value[..end]),
+                    None => value.clone(),
+                }
+            };
+            (key.clone(), Value::String(value))
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// Provider that records branch, pipeline and merge request changes made
+/// through it as audit events
+///
+/// Reads are forwarded without being recorded.
+pub struct AuditedVcs<V: ?Sized> {
+    inner: Arc<V>,
+    db: Option<Arc<Database>>,
+    /// Actor of the recorded events, e.g. `system:api`
+    actor: String,
+}
+
+impl<V: VcsProvider + ?Sized> AuditedVcs<V> {
+    pub fn new(inner: Arc<V>, db: Option<Arc<Database>>, actor: impl Into<String>) -> Self {
+        Self {
+            inner,
+            db,
+            actor: actor.into(),
+        }
+    }
+
+    async fn audit<T>(&self, action: &str, repo: &Repository, target: String, mut payload: Value, result: &Result<T>) {
+        if let (Err(e), Some(payload)) = (result, payload.as_object_mut()) {
+            payload.insert("error".to_string(), Value::String(e.to_string()));
+        }
+
+        let event = repository_event(&self.actor, action, repo)
+            .with_target(target)
+            .with_payload(payload);
+        record(&self.db, event).await;
+    }
+}
+
+/// `provider` wrapped so its changes are audited (returned as is without a database)
+pub fn audited(
+    provider: Arc<dyn VcsProvider>,
+    db: &Option<Arc<Database>>,
+    actor: &str,
+) -> Arc<dyn VcsProvider> {
+    match db {
+        Some(_) => Arc::new(AuditedVcs::new(provider, db.clone(), actor)),
+        None => provider,
+    }
+}
+
+#[async_trait]
+impl<V: VcsProvider + ?Sized> VcsProvider for AuditedVcs<V> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn merge_request_url(&self, repo: &Repository, number: u64) -> String {
+        self.inner.merge_request_url(repo, number)
+    }
+
+    async fn trigger_pipeline(
+        &self,
+        repo: &Repository,
+        workflow_file: &str,
+        git_ref: &str,
+        inputs: HashMap<String, String>,
+    ) -> Result<u64> {
+        let mut payload = json!({ "ref": git_ref, "inputs": recorded_inputs(&inputs) });
+        let result = self.inner.trigger_pipeline(repo, workflow_file, git_ref, inputs).await;
+        if let Ok(run_id) = result {
+            payload["run_id"] = run_id.into();
+        }
+        self.audit("pipeline.trigger", repo, workflow_file.to_string(), payload, &result).await;
+        result
+    }
+
+    async fn pipeline_status(&self, repo: &Repository, pipeline_id: u64) -> Result<WorkflowStatus> {
+        self.inner.pipeline_status(repo, pipeline_id).await
+    }
+
+    async fn cancel_pipeline(&self, repo: &Repository, pipeline_id: u64) -> Result<()> {
+        let result = self.inner.cancel_pipeline(repo, pipeline_id).await;
+        self.audit("pipeline.cancel", repo, pipeline_id.to_string(), json!({}), &result).await;
+        result
+    }
+
+    async fn create_branch(&self, repo: &Repository, branch: &str, from_branch: &str) -> Result<()> {
+        let result = self.inner.create_branch(repo, branch, from_branch).await;
+        self.audit("branch.create", repo, branch.to_string(), json!({ "from": from_branch }), &result).await;
+        result
+    }
+
+    async fn delete_branch(&self, repo: &Repository, branch: &str) -> Result<()> {
+        let result = self.inner.delete_branch(repo, branch).await;
+        self.audit("branch.delete", repo, branch.to_string(), json!({}), &result).await;
+        result
+    }
+
+    async fn list_branches(&self, repo: &Repository, prefix: &str) -> Result<Vec<Branch>> {
+        self.inner.list_branches(repo, prefix).await
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &Repository,
+        title: String,
+        body: String,
+        head: String,
+        base: String,
+        draft: bool,
+    ) -> Result<PullRequest> {
+        let mut payload = json!({ "title": title, "head": head, "base": base, "draft": draft });
+        let result = self.inner.create_merge_request(repo, title, body, head.clone(), base, draft).await;
+        let target = match result {
+            Ok(ref pr) => {
+                payload["number"] = pr.number.into();
+                pr.number.to_string()
+            }
+            Err(_) => head,
+        };
+        self.audit("merge_request.create", repo, target, payload, &result).await;
+        result
+    }
+
+    async fn merge_merge_request(&self, repo: &Repository, number: u64, options: &MergeOptions) -> Result<()> {
+        let result = self.inner.merge_merge_request(repo, number, options).await;
+        let payload = json!({ "method": options.method.to_string(), "commit_title": options.commit_title });
+        self.audit("merge_request.merge", repo, number.to_string(), payload, &result).await;
+        result
+    }
+
+    async fn close_merge_request(&self, repo: &Repository, number: u64) -> Result<()> {
+        let result = self.inner.close_merge_request(repo, number).await;
+        self.audit("merge_request.close", repo, number.to_string(), json!({}), &result).await;
+        result
+    }
+
+    async fn merge_request_branch(&self, repo: &Repository, number: u64) -> Result<String> {
+        self.inner.merge_request_branch(repo, number).await
+    }
+
+    async fn merge_request_from_fork(&self, repo: &Repository, number: u64) -> Result<bool> {
+        self.inner.merge_request_from_fork(repo, number).await
+    }
+
+    async fn merge_request_mergeability(&self, repo: &Repository, number: u64) -> Result<Mergeability> {
+        self.inner.merge_request_mergeability(repo, number).await
+    }
+
+    async fn mark_ready_for_review(&self, repo: &Repository, number: u64) -> Result<()> {
+        let result = self.inner.mark_ready_for_review(repo, number).await;
+        self.audit("merge_request.ready", repo, number.to_string(), json!({}), &result).await;
+        result
+    }
+
+    async fn request_reviewers(&self, repo: &Repository, number: u64, reviewers: &[String]) -> Result<()> {
+        let result = self.inner.request_reviewers(repo, number, reviewers).await;
+        let payload = json!({ "reviewers": reviewers });
+        self.audit("merge_request.request_reviewers", repo, number.to_string(), payload, &result).await;
+        result
+    }
+
+    async fn merge_request_diff(&self, repo: &Repository, number: u64, max_chars: usize) -> Result<Vec<String>> {
+        self.inner.merge_request_diff(repo, number, max_chars).await
+    }
+
+    async fn merge_request_diff_stat(&self, repo: &Repository, number: u64) -> Result<DiffStat> {
+        self.inner.merge_request_diff_stat(repo, number).await
+    }
+
+    async fn update_merge_request_body(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        self.inner.update_merge_request_body(repo, number, body).await
+    }
+
+    async fn find_merge_request_by_branch(&self, repo: &Repository, branch: &str) -> Result<Option<u64>> {
+        self.inner.find_merge_request_by_branch(repo, branch).await
+    }
+
+    async fn is_merge_request_merged(&self, repo: &Repository, number: u64) -> Result<bool> {
+        self.inner.is_merge_request_merged(repo, number).await
+    }
+
+    async fn comment(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        self.inner.comment(repo, number, body).await
+    }
+
+    async fn submit_review(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        self.inner.submit_review(repo, number, body).await
+    }
+
+    async fn load_repo_config(&self, repo: &Repository) -> Result<RepoConfig> {
+        self.inner.load_repo_config(repo).await
+    }
+
+    async fn report_check(&self, repo: &Repository, branch: &str, report: &CheckRunReport) -> Result<()> {
+        self.inner.report_check(repo, branch, report).await
+    }
+
+    async fn check_pipeline_setup(&self, repo: &Repository) -> Result<()> {
+        self.inner.check_pipeline_setup(repo).await
+    }
+
+    async fn revert_merge_request(
+        &self,
+        repo: &Repository,
+        branch: &str,
+        number: u64,
+    ) -> Result<Option<String>> {
+        let result = self.inner.revert_merge_request(repo, branch, number).await;
+        let payload = match result {
+            Ok(ref commit) => json!({ "branch": branch, "commit": commit }),
+            Err(_) => json!({ "branch": branch }),
+        };
+        self.audit("merge_request.revert", repo, number.to_string(), payload, &result).await;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_inputs() {
+        let inputs = HashMap::from([
+            ("task_id".to_string(), "abc".to_string()),
+            ("prompt".to_string(), "가".repeat(MAX_INPUT_CHARS + 10)),
+        ]);
+        let payload = recorded_inputs(&inputs);
+
+        assert_eq!(payload["task_id"], "abc");
+        let prompt = payload["prompt"].as_str().unwrap();
+        assert_eq!(prompt.chars().count(), MAX_INPUT_CHARS + 1);
+        assert!(prompt.ends_with('…'));
+    }
+
+    #[test]
+    fn test_secret_inputs_are_redacted() {
+        let token = "a".repeat(64);
+        let inputs = HashMap::from([
+            ("task_id".to_string(), "abc".to_string()),
+            ("secrets_token".to_string(), token.clone()),
+            ("API_KEY".to_string(), "sk-123".to_string()),
+        ]);
+        let payload = recorded_inputs(&inputs);
+
+        assert_eq!(payload["task_id"], "abc");
+        assert_eq!(payload["secrets_token"], REDACTED);
+        assert_eq!(payload["API_KEY"], REDACTED);
+        assert!(!payload.to_string().contains(&token));
+    }
+}
@@ -0,0 +1,119 @@
+//! Cleanup of the `autodev/*` branches tasks work on
+//!
+//! A task branch is deleted as soon as AutoDev merges its merge request, and
+//! the webhook removes branches of merge requests merged by hand as well as
+//! the parent branch of a composite task whose PR is closed. Whatever is left
+//! (e.g. without webhooks) is pruned by `autodev gc` once it went stale.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use autodev_github::webhook::branch_task_id;
+use autodev_github::{Branch, Repository, Result, VcsProvider};
+
+/// Every branch AutoDev creates starts with this
+pub const BRANCH_PREFIX: &str = "autodev/";
+
+/// Delete the source branch of merge request `number` once it is merged
+pub async fn delete_merged_branch<V: VcsProvider + ?Sized>(repository: &Repository, number: u64, vcs: &Arc<V>) {
+    match vcs.merge_request_branch(repository, number).await {
+        Ok(branch) => {
+            delete_task_branch(repository, &branch, vcs.as_ref()).await;
+        }
+        Err(e) => tracing::debug!("Branch of merge request {} not deleted: {}", number, e),
+    }
+}
+
+/// Delete an AutoDev branch; returns whether it was deleted
+///
+/// Other branches are never touched. Failures are only logged since the
+/// branch may be gone already (the provider or a webhook deleted it).
+pub async fn delete_task_branch<V: VcsProvider + ?Sized>(repository: &Repository, branch: &str, vcs: &V) -> bool {
+    if !branch.starts_with(BRANCH_PREFIX) {
+        return false;
+    }
+
+    match vcs.delete_branch(repository, branch).await {
+        Ok(()) => {
+            tracing::info!("Deleted branch {} of {}", branch, repository);
+            true
+        }
+        Err(e) => {
+            tracing::info!("Branch {} of {} not deleted (it may be gone already): {}", branch, repository, e);
+            false
+        }
+    }
+}
+
+/// AutoDev branches last committed to before `cutoff`
+///
+/// Branches with an open merge request, of unknown age, or belonging to one
+/// of the `active` tasks or composite tasks are kept.
+pub fn stale_branches<'a>(branches: &'a [Branch], cutoff: DateTime<Utc>, active: &HashSet<String>) -> Vec<&'a Branch> {
+    branches
+        .iter()
+        .filter(|branch| branch.name.starts_with(BRANCH_PREFIX))
+        .filter(|branch| branch.open_merge_request.is_none())
+        .filter(|branch| branch.committed_at.is_some_and(|committed_at| committed_at < cutoff))
+        .filter(|branch| branch_task_id(&branch.name).is_none_or(|id| !active.contains(id)))
+        .collect()
+}
+
+/// Delete the stale AutoDev branches of `repository` (see [`stale_branches`])
+///
+/// Returns the branches deleted, or those that would be with `dry_run`.
+pub async fn prune_stale_branches<V: VcsProvider + ?Sized>(
+    repository: &Repository,
+    vcs: &V,
+    cutoff: DateTime<Utc>,
+    active: &HashSet<String>,
+    dry_run: bool,
+) -> Result<Vec<Branch>> {
+    let branches = vcs.list_branches(repository, BRANCH_PREFIX).await?;
+    let stale = stale_branches(&branches, cutoff, active);
+
+    let mut pruned = Vec::new();
+    for branch in stale {
+        if dry_run || delete_task_branch(repository, &branch.name, vcs).await {
+            pruned.push(branch.clone());
+        }
+    }
+
+    Ok(pruned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn branch(name: &str, days_old: Option<i64>, open_merge_request: Option<u64>) -> Branch {
+        Branch {
+            name: name.to_string(),
+            committed_at: days_old.map(|days| Utc::now() - Duration::days(days)),
+            open_merge_request,
+        }
+    }
+
+    #[test]
+    fn test_stale_branches() {
+        let branches = vec![
+            branch("autodev/old", Some(40), None),
+            branch("autodev/recent", Some(3), None),
+            branch("autodev/reviewed", Some(40), Some(12)),
+            branch("autodev/unknown", None, None),
+            branch("autodev/running", Some(40), None),
+            branch("autodev/composite/subtask-running", Some(40), None),
+            branch("autodev/composite/subtask-done", Some(40), None),
+            branch("feature/old", Some(40), None),
+        ];
+        let active = HashSet::from(["running".to_string()]);
+
+        let stale: Vec<&str> = stale_branches(&branches, Utc::now() - Duration::days(30), &active)
+            .iter()
+            .map(|branch| branch.name.as_str())
+            .collect();
+        assert_eq!(stale, vec!["autodev/old", "autodev/composite/subtask-done"]);
+    }
+}
@@ -0,0 +1,135 @@
+//! AI budgets of tasks
+//!
+//! Limits come from AUTODEV_BUDGET_* ([`BudgetConfig::from_env`]); what each
+//! scope already spent comes from the `metrics` table. Without a database only
+//! the spending of the current run counts against the task budget.
+
+use chrono::{Datelike, TimeZone, Utc};
+use std::future::Future;
+use std::sync::Arc;
+
+use autodev_ai::{Budget, BudgetConfig, BudgetScope, TokenUsage};
+use autodev_core::{AutoDevEngine, Task};
+use autodev_db::{AiUsage, Database, NewMetrics};
+
+/// Budgets that apply to the AI calls made for `task_id`
+pub async fn task_budgets(task_id: &str, db: &Option<Arc<Database>>) -> Vec<Budget> {
+    let config = BudgetConfig::from_env();
+    if config.is_unlimited() {
+        return Vec::new();
+    }
+
+    let mut budgets = Vec::new();
+
+    if !config.task.is_unlimited() {
+        let spent = match db {
+            Some(db) => match db.get_task_metrics(task_id).await {
+                Ok(metrics) => metrics
+                    .map(|m| spent(AiUsage {
+                        input_tokens: m.ai_input_tokens,
+                        output_tokens: m.ai_output_tokens,
+                        cost_usd: m.ai_cost_usd,
+                    }))
+                    .unwrap_or_default(),
+                Err(e) => {
+                    tracing::warn!("Failed to load AI usage of task {}: {}", task_id, e);
+                    TokenUsage::default()
+                }
+            },
+            None => TokenUsage::default(),
+        };
+        budgets.push(Budget::new(BudgetScope::Task, config.task, spent));
+    }
+
+    let Some(db) = db else {
+        return budgets;
+    };
+
+    if !config.composite_task.is_unlimited() {
+        let usage = async {
+            match db.get_parent_composite_id(task_id).await? {
+                Some(composite_id) => db.get_composite_ai_usage(&composite_id).await.map(Some),
+                None => Ok(None),
+            }
+        };
+        match usage.await {
+            Ok(Some(usage)) => budgets.push(Budget::new(BudgetScope::CompositeTask, config.composite_task, spent(usage))),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to load composite AI usage of task {}: {}", task_id, e),
+        }
+    }
+
+    if !config.monthly.is_unlimited() {
+        let now = Utc::now();
+        let month_start = Utc
+            .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+            .single()
+            .unwrap_or(now);
+        match db.get_ai_usage_since(month_start).await {
+            Ok(usage) => budgets.push(Budget::new(BudgetScope::Monthly, config.monthly, spent(usage))),
+            Err(e) => tracing::warn!("Failed to load monthly AI usage: {}", e),
+        }
+    }
+
+    budgets
+}
+
+fn spent(usage: AiUsage) -> TokenUsage {
+    TokenUsage {
+        input_tokens: usage.input_tokens.max(0) as u64,
+        output_tokens: usage.output_tokens.max(0) as u64,
+        cost_usd: usage.cost_usd,
+    }
+}
+
+/// Run AI work for `task` within its budgets and save the tokens it used
+///
+/// The generated text is streamed into the task's logs while the work runs.
+/// When a budget runs out the task is paused as BudgetExceeded and the error
+/// is returned; callers should not mark the task failed in that case.
+pub async fn run_within_budget<T, F>(
+    task: &Task,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+    work: F,
+) -> autodev_ai::Result<T>
+where
+    F: Future<Output = autodev_ai::Result<T>>,
+{
+    let budgets = task_budgets(&task.id, db).await;
+    let (result, usage) =
+        crate::stream::stream_to_task_logs(&task.id, engine, db, autodev_ai::track_usage_within(budgets, work)).await;
+
+    // Tokens are billed whether or not the run succeeds
+    if let (Some(db), false) = (db, usage.is_empty()) {
+        let metrics = NewMetrics {
+            ai_input_tokens: usage.input_tokens as i64,
+            ai_output_tokens: usage.output_tokens as i64,
+            ai_cost_usd: usage.cost_usd,
+            ..Default::default()
+        };
+        if let Err(e) = db.save_metrics(&task.id, &metrics).await {
+            tracing::warn!("Failed to save AI usage of task {}: {}", task.id, e);
+        }
+    }
+
+    if let Err(autodev_ai::Error::BudgetExceeded(ref reason)) = result {
+        pause_for_budget(task, reason, engine, db).await;
+    }
+
+    result
+}
+
+/// Pause `task`; `EventNotifier` tells someone to raise the budget
+async fn pause_for_budget(task: &Task, reason: &str, engine: &Arc<AutoDevEngine>, db: &Option<Arc<Database>>) {
+    if let Err(e) = engine.pause_for_budget(&task.id, reason).await {
+        tracing::error!("Failed to pause task {}: {}", task.id, e);
+    }
+
+    if let Some(db) = db {
+        if let Err(e) = db.add_execution_log(&task.id, "BUDGET_EXCEEDED", reason).await {
+            tracing::warn!("Failed to log budget pause of task {}: {}", task.id, e);
+        }
+    }
+
+}
@@ -0,0 +1,50 @@
+//! Database tier of the AI response cache
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+use autodev_ai::{AIAgent, CacheStore};
+use autodev_db::Database;
+
+/// Responses kept in the `ai_response_cache` table
+pub struct DbCacheStore {
+    db: Arc<Database>,
+}
+
+impl DbCacheStore {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+}
+
+fn store_error(e: autodev_db::Error) -> autodev_ai::Error {
+    autodev_ai::Error::Other(anyhow::anyhow!("Response cache error: {}", e))
+}
+
+#[async_trait]
+impl CacheStore for DbCacheStore {
+    async fn get(&self, key: &str) -> autodev_ai::Result<Option<String>> {
+        self.db.get_cached_response(key).await.map_err(store_error)
+    }
+
+    async fn put(&self, key: &str, model: &str, response: &str, expires_at: DateTime<Utc>) -> autodev_ai::Result<()> {
+        self.db
+            .save_cached_response(key, model, response, expires_at)
+            .await
+            .map_err(store_error)
+    }
+
+    async fn delete(&self, key: &str) -> autodev_ai::Result<()> {
+        self.db.delete_cached_response(key).await.map_err(store_error)
+    }
+}
+
+/// `agent` with the response cache from AUTODEV_AI_CACHE_TTL, shared through
+/// the database when there is one
+pub fn with_response_cache(agent: Arc<dyn AIAgent>, db: &Option<Arc<Database>>) -> Arc<dyn AIAgent> {
+    let store = db
+        .as_ref()
+        .map(|db| Arc::new(DbCacheStore::new(db.clone())) as Arc<dyn CacheStore>);
+    autodev_ai::with_response_cache(agent, store)
+}
@@ -0,0 +1,257 @@
+//! Cancelling a composite task while its batches run
+//!
+//! Cancellation is recorded on the composite task first, so an execution in
+//! this process (or in another one, through the database) starts no further
+//! batch and gives up on the subtasks it waits for. The unfinished subtasks are
+//! then cancelled one by one, which stops their workflow runs or containers.
+
+use anyhow::Result;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use autodev_core::AutoDevEngine;
+use autodev_db::Database;
+use autodev_github::{Repository, VcsProvider};
+use autodev_local_executor::LocalExecutor;
+
+use crate::{cancel_task, log_execution};
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelOptions {
+    /// Close the subtasks' open PRs with a comment explaining why
+    pub close_prs: bool,
+}
+
+/// What cancelling a composite task stopped
+#[derive(Debug, Clone, Default)]
+pub struct CancelReport {
+    /// Subtasks cancelled (queued, running or blocked ones)
+    pub cancelled_tasks: Vec<String>,
+    /// Subtask PRs closed without merging
+    pub closed_prs: Vec<u64>,
+}
+
+/// Cancel a composite task and everything it still runs
+///
+/// No further batch is scheduled, every unfinished subtask is cancelled (its
+/// workflow run or local container stopped) and, with `close_prs`, the open
+/// PRs of the subtasks are closed. Merged PRs and the parent branch are left
+/// alone; roll the composite task back to undo them. Failures to stop a
+/// subtask or close a PR are logged but do not undo the cancellation.
+pub async fn cancel_composite_task<V: VcsProvider + ?Sized>(
+    composite_id: &str,
+    repository: &Repository,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    db: &Option<Arc<Database>>,
+    local_executor: Option<&Arc<dyn LocalExecutor>>,
+    options: &CancelOptions,
+) -> Result<CancelReport> {
+    let composite_task = engine.cancel_composite_task(composite_id).await?;
+    let mut report = CancelReport::default();
+
+    for subtask in &composite_task.subtasks {
+        let status = engine
+            .get_task(&subtask.id)
+            .await
+            .map_or(subtask.status, |t| t.status);
+
+        if !status.is_finished() {
+            match cancel_task(&subtask.id, repository, engine, vcs, db, local_executor).await {
+                Ok(_) => report.cancelled_tasks.push(subtask.id.clone()),
+                Err(e) => tracing::warn!("Failed to cancel subtask {}: {}", subtask.id, e),
+            }
+        }
+
+        if options.close_prs {
+            match close_subtask_pr(&subtask.id, composite_id, repository, vcs).await {
+                Ok(Some(pr_num)) => {
+                    report.closed_prs.push(pr_num);
+                    log_execution(engine, db, &subtask.id, "CANCELLED", &format!("PR #{} closed", pr_num)).await?;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to close PR of subtask {}: {}", subtask.id, e),
+            }
+        }
+    }
+
+    tracing::info!(
+        "Composite task {} cancelled: {} subtasks cancelled, {} PRs closed",
+        composite_id,
+        report.cancelled_tasks.len(),
+        report.closed_prs.len()
+    );
+
+    Ok(report)
+}
+
+/// Close the unmerged PR of a subtask, explaining the cancellation on it
+async fn close_subtask_pr<V: VcsProvider + ?Sized>(
+    task_id: &str,
+    composite_id: &str,
+    repository: &Repository,
+    vcs: &Arc<V>,
+) -> Result<Option<u64>> {
+    let task_branch = format!("autodev/{}", task_id);
+    let Some(pr_num) = vcs.find_merge_request_by_branch(repository, &task_branch).await? else {
+        return Ok(None);
+    };
+    if vcs.is_merge_request_merged(repository, pr_num).await? {
+        return Ok(None);
+    }
+
+    let body = format!(
+        "🛑 AutoDev 복합 작업 `{}`이(가) 취소되어 이 PR을 닫습니다.\n\n\
+         다시 실행하려면 `POST /composite-tasks/{}/resume`을 호출하세요.",
+        composite_id, composite_id
+    );
+    if let Err(e) = vcs.comment(repository, pr_num, &body).await {
+        tracing::warn!("Failed to comment on PR #{}: {}", pr_num, e);
+    }
+    vcs.close_merge_request(repository, pr_num).await?;
+
+    Ok(Some(pr_num))
+}
+
+/// Whether a composite task was cancelled, here or by another process
+pub async fn is_composite_cancelled(
+    composite_id: &str,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+) -> bool {
+    if engine.get_composite_task(composite_id).await.is_some_and(|c| c.is_cancelled()) {
+        return true;
+    }
+
+    match db {
+        Some(db) => match db.get_composite_task(composite_id).await {
+            Ok(record) => record.is_some_and(|r| r.cancelled_at.is_some()),
+            Err(e) => {
+                tracing::warn!("Error checking cancellation of composite task {}: {}", composite_id, e);
+                false
+            }
+        },
+        None => false,
+    }
+}
+
+/// Run `future` unless the composite task is cancelled first
+///
+/// Cancellations in this process end the wait right away; cancellations by
+/// another process are picked up from the database every poll interval.
+pub(crate) async fn unless_cancelled<T>(
+    composite_id: &str,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+    poll_interval: Duration,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let cancelled = async {
+        let mut events = engine.subscribe();
+        while !is_composite_cancelled(composite_id, engine, db).await {
+            // Any engine event (or the poll interval) triggers a re-check
+            let _ = tokio::time::timeout(poll_interval, events.recv()).await;
+        }
+    };
+
+    tokio::select! {
+        result = future => result,
+        () = cancelled => Err(anyhow::anyhow!("Composite task {} was cancelled", composite_id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autodev_core::{Task, TaskStatus};
+    use autodev_github::mock::MockPullRequestState;
+    use autodev_github::MockGitHubClient;
+
+    #[tokio::test]
+    async fn test_cancel_composite_task() {
+        let engine = Arc::new(AutoDevEngine::new());
+        let github = Arc::new(MockGitHubClient::new());
+        let repository = Repository::new("o".to_string(), "r".to_string());
+        github.add_branch(&repository, "main");
+
+        let running = Task::new("Running".to_string(), "".to_string(), "".to_string());
+        let reviewed = Task::new("Reviewed".to_string(), "".to_string(), "".to_string());
+        let queued = Task::new("Queued".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![running.id.clone()]);
+        let composite = engine
+            .create_composite_task(
+                "Stop me".to_string(),
+                "".to_string(),
+                vec![running.clone(), reviewed.clone(), queued.clone()],
+                true,
+            )
+            .await
+            .unwrap();
+        let parent_branch = format!("autodev/{}", composite.id);
+        github.add_branch(&repository, &parent_branch);
+
+        // One subtask runs its workflow, another waits for its PR to be merged
+        let run_id = crate::execute_simple_task(
+            &running,
+            &repository,
+            &engine,
+            &github,
+            &None,
+            Some(&parent_branch),
+            Some(&composite.id),
+        )
+        .await
+        .unwrap();
+        engine.update_task_status(&reviewed.id, TaskStatus::InProgress, None).await.unwrap();
+        engine.update_task_status(&reviewed.id, TaskStatus::Completed, None).await.unwrap();
+        let reviewed_branch = format!("autodev/{}", reviewed.id);
+        github.add_branch(&repository, &reviewed_branch);
+        let pr = github
+            .create_merge_request(&repository, "Reviewed".to_string(), "".to_string(), reviewed_branch, parent_branch, false)
+            .await
+            .unwrap();
+
+        let waiting = unless_cancelled(
+            &composite.id,
+            &engine,
+            &None,
+            Duration::from_secs(60),
+            std::future::pending::<Result<()>>(),
+        );
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            cancel_composite_task(
+                &composite.id,
+                &repository,
+                &engine,
+                &github,
+                &None,
+                None,
+                &CancelOptions { close_prs: true },
+            )
+            .await
+        };
+        let (waited, report) = tokio::time::timeout(Duration::from_secs(5), async { tokio::join!(waiting, cancel) })
+            .await
+            .expect("cancellation should end the wait");
+        assert!(waited.is_err());
+        let report = report.unwrap();
+
+        assert_eq!(report.cancelled_tasks, vec![running.id.clone(), queued.id.clone()]);
+        assert_eq!(report.closed_prs, vec![pr.number]);
+        for id in [&running.id, &queued.id] {
+            assert_eq!(engine.get_task(id).await.unwrap().status, TaskStatus::Cancelled);
+        }
+        let status = github.pipeline_status(&repository, run_id).await.unwrap();
+        assert_eq!(status.conclusion.as_deref(), Some("cancelled"));
+        let closed = github.pull_request(&repository, pr.number).unwrap();
+        assert_eq!(closed.state, MockPullRequestState::Closed);
+        assert_eq!(closed.comments.len(), 1);
+
+        assert!(is_composite_cancelled(&composite.id, &engine, &None).await);
+        assert!(cancel_composite_task(&composite.id, &repository, &engine, &github, &None, None, &CancelOptions::default())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Composite task progress as the AutoDev check on its parent branch
+//!
+//! The check moves with the parent branch: every merged subtask PR adds a
+//! commit, and the next report creates a new check run on it. Check runs need
+//! a GitHub App token, so failed reports are only logged.
+
+use std::sync::Arc;
+
+use autodev_core::CompositeTask;
+use autodev_github::{CheckRunReport, CheckRunState, Repository, VcsProvider};
+
+pub async fn report_composite_check<V: VcsProvider + ?Sized>(
+    vcs: &Arc<V>,
+    repository: &Repository,
+    composite_task_id: &str,
+    report: &CheckRunReport,
+) {
+    let parent_branch = format!("autodev/{}", composite_task_id);
+
+    if let Err(e) = vcs.report_check(repository, &parent_branch, report).await {
+        tracing::warn!("Failed to report check for composite task {}: {}", composite_task_id, e);
+    }
+}
+
+/// Markdown list of the subtasks by batch
+fn plan_summary(composite_task: &CompositeTask) -> String {
+    composite_task
+        .get_parallel_batches()
+        .iter()
+        .enumerate()
+        .map(|(i, batch)| {
+            let tasks: Vec<String> = batch.iter().map(|t| format!("  - {}", t.title)).collect();
+            format!("- Batch {}\n{}", i + 1, tasks.join("\n"))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn decomposed(composite_task: &CompositeTask) -> CheckRunReport {
+    CheckRunReport::new(
+        CheckRunState::InProgress,
+        format!(
+            "Decomposed into {} subtasks in {} batches",
+            composite_task.subtasks.len(),
+            composite_task.get_parallel_batches().len()
+        ),
+        plan_summary(composite_task),
+    )
+}
+
+pub fn awaiting_approval(composite_task: &CompositeTask, batch: usize) -> CheckRunReport {
+    CheckRunReport::new(
+        CheckRunState::InProgress,
+        format!("Batch {} waiting for approval", batch),
+        format!(
+            "Approve it with `POST /composite-tasks/{}/batches/{}/approve`.\n\n{}",
+            composite_task.id,
+            batch,
+            plan_summary(composite_task)
+        ),
+    )
+}
+
+/// Progress once `merged` of the subtasks are merged into the parent branch
+pub fn subtasks_merged(composite_task: &CompositeTask, merged: usize) -> CheckRunReport {
+    CheckRunReport::new(
+        CheckRunState::InProgress,
+        format!("{}/{} subtasks merged", merged, composite_task.subtasks.len()),
+        plan_summary(composite_task),
+    )
+}
+
+/// Every subtask merged; the parent branch waits for review
+///
+/// Subtasks that failed or were skipped under a non-blocking failure policy
+/// make the check neutral instead.
+pub fn completed(composite_task: &CompositeTask) -> CheckRunReport {
+    let summary = composite_task.completion_summary();
+    if !summary.is_partial() {
+        return CheckRunReport::new(
+            CheckRunState::Success,
+            format!("All {} subtasks merged, review pending", composite_task.subtasks.len()),
+            plan_summary(composite_task),
+        );
+    }
+
+    CheckRunReport::new(
+        CheckRunState::Neutral,
+        format!(
+            "{}/{} subtasks merged, {} failed, {} skipped",
+            summary.completed,
+            composite_task.subtasks.len(),
+            summary.failed.len(),
+            summary.skipped.len()
+        ),
+        plan_summary(composite_task),
+    )
+}
+
+pub fn failed(composite_task: &CompositeTask, error: &str) -> CheckRunReport {
+    CheckRunReport::new(
+        CheckRunState::Failure,
+        format!("Composite task failed: {}", composite_task.title),
+        format!("```\n{}\n```\n\n{}", error, plan_summary(composite_task)),
+    )
+}
+
+/// The composite task is paused before batch `batch`
+pub fn paused(composite_task: &CompositeTask, batch: usize) -> CheckRunReport {
+    CheckRunReport::new(
+        CheckRunState::InProgress,
+        format!("Paused before batch {}", batch),
+        format!(
+            "Resume it with `POST /composite-tasks/{}/resume`.\n\n{}",
+            composite_task.id,
+            plan_summary(composite_task)
+        ),
+    )
+}
+
+/// A user cancelled the composite task; no further batch runs
+pub fn cancelled(composite_task: &CompositeTask) -> CheckRunReport {
+    CheckRunReport::new(
+        CheckRunState::Cancelled,
+        format!("Composite task cancelled: {}", composite_task.title),
+        format!(
+            "Resume it with `POST /composite-tasks/{}/resume`.\n\n{}",
+            composite_task.id,
+            plan_summary(composite_task)
+        ),
+    )
+}
+
+/// The tests failed on the parent branch after batch `batch` was merged
+pub fn fix_required(composite_task: &CompositeTask, batch: usize, reason: &str) -> CheckRunReport {
+    CheckRunReport::new(
+        CheckRunState::Failure,
+        format!("Tests failed after batch {}", batch),
+        format!(
+            "The test workflow concluded `{}`. Push a fix to the parent branch and approve it with \
+             `POST /composite-tasks/{}/approve` to run the tests again.\n\n{}",
+            reason,
+            composite_task.id,
+            plan_summary(composite_task)
+        ),
+    )
+}
+
+/// The security scan of the draft PR found Critical issues
+pub fn security_blocked(composite_task: &CompositeTask, pr_number: u64, critical: usize) -> CheckRunReport {
+    CheckRunReport::new(
+        CheckRunState::Failure,
+        format!("Security scan found {} critical issue(s)", critical),
+        format!(
+            "See the security review on PR #{}; fix the issues before merging.\n\n{}",
+            pr_number,
+            plan_summary(composite_task)
+        ),
+    )
+}
+
+/// The security scan of the draft PR could not run
+pub fn security_scan_failed(composite_task: &CompositeTask, error: &str) -> CheckRunReport {
+    CheckRunReport::new(
+        CheckRunState::Failure,
+        "Security scan could not run",
+        format!("```\n{}\n```\n\n{}", error, plan_summary(composite_task)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autodev_core::Task;
+
+    #[test]
+    fn test_progress_reports() {
+        let first = Task::new("Schema".to_string(), "".to_string(), "".to_string());
+        let second = Task::new("API".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![first.id.clone()]);
+        let composite = CompositeTask::new("Users".to_string(), "".to_string(), vec![first, second]);
+
+        let report = decomposed(&composite);
+        assert_eq!(report.state, CheckRunState::InProgress);
+        assert_eq!(report.title, "Decomposed into 2 subtasks in 2 batches");
+        assert_eq!(report.summary, "- Batch 1\n  - Schema\n- Batch 2\n  - API");
+
+        assert_eq!(subtasks_merged(&composite, 1).title, "1/2 subtasks merged");
+        assert_eq!(completed(&composite).state.conclusion(), Some("success"));
+
+        let mut partial = composite.clone();
+        partial.subtasks[0].status = autodev_core::TaskStatus::Failed;
+        partial.subtasks[1].status = autodev_core::TaskStatus::Cancelled;
+        partial.subtasks[1].blocked_by = Some(partial.subtasks[0].id.clone());
+        let report = completed(&partial);
+        assert_eq!(report.state, CheckRunState::Neutral);
+        assert_eq!(report.title, "0/2 subtasks merged, 1 failed, 1 skipped");
+    }
+}
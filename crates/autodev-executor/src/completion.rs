@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use autodev_github::Repository;
+use tokio::sync::watch;
+
+/// Default interval for polling GitHub when no completion event arrives
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Fallback polling interval for servers that receive webhooks/callbacks
+pub const EVENT_FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(120);
+
+/// What is known about a pending task from webhooks and callbacks
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompletionState {
+    /// Workflow run conclusion (success, failure, cancelled, ...)
+    pub conclusion: Option<String>,
+    /// PR opened for the task branch
+    pub pr_number: Option<u64>,
+    /// Whether the task PR has been merged
+    pub merged: bool,
+}
+
+struct PendingEntry {
+    /// `owner/name` of the repository the task runs in
+    repository: String,
+    run_id: Option<u64>,
+    tx: watch::Sender<CompletionState>,
+}
+
+/// In-process registry of tasks waiting for completion events
+///
+/// The executor registers each dispatched task and awaits the returned receiver.
+/// The callback and webhook handlers signal it, so waits end as soon as GitHub
+/// reports back; `poll_interval` is only the fallback when no event arrives.
+pub struct CompletionNotifier {
+    pending: Mutex<HashMap<String, PendingEntry>>,
+    poll_interval: Duration,
+}
+
+impl Default for CompletionNotifier {
+    fn default() -> Self {
+        Self::new(DEFAULT_POLL_INTERVAL)
+    }
+}
+
+impl CompletionNotifier {
+    pub fn new(poll_interval: Duration) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            poll_interval,
+        }
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    /// Start tracking a task in `repository` (and its workflow run, if known)
+    pub fn register(
+        &self,
+        task_id: &str,
+        repository: &Repository,
+        run_id: Option<u64>,
+    ) -> watch::Receiver<CompletionState> {
+        let mut pending = self.pending.lock().unwrap();
+
+        if let Some(entry) = pending.get_mut(task_id) {
+            // Re-registration (e.g. a retry) keeps the channel but resets the state
+            entry.repository = repository.full_name();
+            entry.run_id = run_id;
+            entry.tx.send_replace(CompletionState::default());
+            return entry.tx.subscribe();
+        }
+
+        let (tx, rx) = watch::channel(CompletionState::default());
+        pending.insert(
+            task_id.to_string(),
+            PendingEntry {
+                repository: repository.full_name(),
+                run_id,
+                tx,
+            },
+        );
+        rx
+    }
+
+    /// Attach the workflow run of a registered task without resetting its state
+    pub fn set_run_id(&self, task_id: &str, run_id: u64) {
+        if let Some(entry) = self.pending.lock().unwrap().get_mut(task_id) {
+            entry.run_id = Some(run_id);
+        }
+    }
+
+    /// Stop tracking a task
+    pub fn unregister(&self, task_id: &str) {
+        self.pending.lock().unwrap().remove(task_id);
+    }
+
+    /// Workflow finished for a task (from the workflow-complete callback)
+    pub fn workflow_completed(&self, task_id: &str, conclusion: &str, pr_number: Option<u64>) -> bool {
+        self.update(task_id, |state| {
+            state.conclusion = Some(conclusion.to_string());
+            if pr_number.is_some() {
+                state.pr_number = pr_number;
+            }
+        })
+    }
+
+    /// Workflow run finished (from the workflow_run webhook)
+    pub fn workflow_run_completed(&self, run_id: u64, conclusion: &str) -> bool {
+        let task_id = {
+            let pending = self.pending.lock().unwrap();
+            pending
+                .iter()
+                .find(|(_, entry)| entry.run_id == Some(run_id))
+                .map(|(task_id, _)| task_id.clone())
+        };
+
+        match task_id {
+            Some(task_id) => self.update(&task_id, |state| {
+                state.conclusion = Some(conclusion.to_string());
+            }),
+            None => false,
+        }
+    }
+
+    /// PR opened in `repository` for a task branch (`autodev/{task_id}`)
+    ///
+    /// PRs of other repositories are ignored, so a branch of the same name
+    /// elsewhere never becomes the task's PR.
+    pub fn pr_opened(&self, repository: &str, branch: &str, pr_number: u64) -> bool {
+        match task_id_from_branch(branch) {
+            Some(task_id) => self.update_in(repository, task_id, |state| state.pr_number = Some(pr_number)),
+            None => false,
+        }
+    }
+
+    /// PR in `repository` for a task branch merged
+    pub fn pr_merged(&self, repository: &str, branch: &str, pr_number: u64) -> bool {
+        match task_id_from_branch(branch) {
+            Some(task_id) => self.update_in(repository, task_id, |state| {
+                state.pr_number = Some(pr_number);
+                state.merged = true;
+            }),
+            None => false,
+        }
+    }
+
+    /// Take the signalled workflow conclusion of a task, clearing it
+    ///
+    /// A conclusion is consumed once so that a retried run of the task does
+    /// not finish on the signal of the previous one. Clearing does not wake
+    /// the task's receiver.
+    pub fn take_conclusion(&self, task_id: &str) -> Option<String> {
+        let pending = self.pending.lock().unwrap();
+        let entry = pending.get(task_id)?;
+
+        let mut conclusion = None;
+        entry.tx.send_if_modified(|state| {
+            conclusion = state.conclusion.take();
+            false
+        });
+        conclusion
+    }
+
+    fn update(&self, task_id: &str, apply: impl FnOnce(&mut CompletionState)) -> bool {
+        let pending = self.pending.lock().unwrap();
+
+        match pending.get(task_id) {
+            Some(entry) => {
+                entry.tx.send_modify(apply);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// [`Self::update`] for a task registered in `repository`
+    fn update_in(&self, repository: &str, task_id: &str, apply: impl FnOnce(&mut CompletionState)) -> bool {
+        let pending = self.pending.lock().unwrap();
+
+        match pending.get(task_id) {
+            Some(entry) if entry.repository.eq_ignore_ascii_case(repository) => {
+                entry.tx.send_modify(apply);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Task branches are named `autodev/{task_id}`
+fn task_id_from_branch(branch: &str) -> Option<&str> {
+    branch
+        .strip_prefix("autodev/")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+/// Wait until a new event arrives or the poll interval elapses
+///
+/// Returns true when woken by an event, false on timeout (time to poll).
+pub async fn wait_for_event(rx: &mut watch::Receiver<CompletionState>, poll_interval: Duration) -> bool {
+    matches!(tokio::time::timeout(poll_interval, rx.changed()).await, Ok(Ok(())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repository() -> Repository {
+        Repository::new("o".to_string(), "r".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_workflow_run_signal() {
+        let notifier = CompletionNotifier::new(Duration::from_secs(60));
+        let mut rx = notifier.register("task-1", &repository(), Some(42));
+
+        assert!(!notifier.workflow_run_completed(7, "success"));
+        assert!(notifier.workflow_run_completed(42, "success"));
+
+        assert!(wait_for_event(&mut rx, Duration::from_millis(10)).await);
+        assert_eq!(notifier.take_conclusion("task-1").as_deref(), Some("success"));
+
+        // Read once; clearing does not count as a new event
+        assert_eq!(notifier.take_conclusion("task-1"), None);
+        assert!(!wait_for_event(&mut rx, Duration::from_millis(10)).await);
+    }
+
+    #[tokio::test]
+    async fn test_pr_signals_by_branch() {
+        let notifier = CompletionNotifier::default();
+        let rx = notifier.register("task-1", &repository(), None);
+
+        // The same branch name in another repository is not the task's PR
+        assert!(!notifier.pr_opened("attacker/r", "autodev/task-1", 9));
+        assert_eq!(rx.borrow().pr_number, None);
+
+        assert!(notifier.pr_opened("o/r", "autodev/task-1", 5));
+        assert!(notifier.pr_merged("o/r", "autodev/task-1", 5));
+        assert!(!notifier.pr_merged("o/r", "feature/other", 6));
+
+        let state = rx.borrow().clone();
+        assert_eq!(state.pr_number, Some(5));
+        assert!(state.merged);
+
+        notifier.unregister("task-1");
+        assert!(!notifier.workflow_completed("task-1", "success", None));
+    }
+
+    #[tokio::test]
+    async fn test_run_id_attached_after_register() {
+        let notifier = CompletionNotifier::default();
+        let rx = notifier.register("task-1", &repository(), None);
+
+        notifier.set_run_id("task-1", 9);
+        assert!(notifier.workflow_run_completed(9, "failure"));
+        assert_eq!(rx.borrow().conclusion.as_deref(), Some("failure"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_times_out_without_event() {
+        let notifier = CompletionNotifier::default();
+        let mut rx = notifier.register("task-1", &repository(), Some(1));
+
+        assert!(!wait_for_event(&mut rx, Duration::from_millis(10)).await);
+    }
+}
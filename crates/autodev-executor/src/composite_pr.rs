@@ -0,0 +1,76 @@
+//! Draft PR of a completed composite task
+//!
+//! Once every subtask is merged into the parent branch, the parent branch is
+//! proposed to the base branch as a draft so a human reviews the combined
+//! change before it lands.
+
+use anyhow::Result;
+use std::sync::Arc;
+
+use autodev_core::CompositeTask;
+use autodev_github::{PullRequest, Repository, VcsProvider};
+
+/// Subtask list and review checklist kept below the PR description
+pub fn footer(composite_task: &CompositeTask) -> String {
+    format!(
+        "## ⚠️ Review Required\n\
+        This is an automatically generated composite task PR. Please review all changes before merging.\n\n\
+        ## Subtasks Completed\n{}\n\n\
+        ## Review Checklist\n\
+        - [ ] All subtask PRs reviewed and verified\n\
+        - [ ] Code quality meets standards\n\
+        - [ ] Tests passing\n\
+        - [ ] No security issues\n\n\
+        ## Next Steps\n\
+        1. Review all changes in this PR\n\
+        2. If satisfied, mark as \"Ready for Review\"\n\
+        3. Merge when approved\n\n\
+        ---\n\
+        🤖 Generated by AutoDev\n\
+        Co-Authored-By: Claude <noreply@anthropic.com>",
+        composite_task
+            .subtasks
+            .iter()
+            .map(|t| format!("- [x] {} ({})", t.title, t.id))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+/// Open the draft PR from the composite task's parent branch to `base_branch`
+///
+/// The body is the task description and [`footer`]; it is replaced by a
+/// description of the combined diff when PR descriptions are enabled.
+pub async fn open_draft_pr<V: VcsProvider + ?Sized>(
+    composite_task: &CompositeTask,
+    repository: &Repository,
+    base_branch: &str,
+    vcs: &Arc<V>,
+) -> Result<PullRequest> {
+    let parent_branch = format!("autodev/{}", composite_task.id);
+    let body = format!(
+        "# {}\n\n## Description\n{}\n\n{}",
+        composite_task.title,
+        composite_task.description,
+        footer(composite_task)
+    );
+
+    let pr = vcs
+        .create_merge_request(
+            repository,
+            format!("[AutoDev] {}", composite_task.title),
+            body,
+            parent_branch,
+            base_branch.to_string(),
+            true,  // draft = true (requires user approval)
+        )
+        .await?;
+
+    tracing::info!(
+        "Created DRAFT PR #{} for composite task {} - awaiting user review",
+        pr.number,
+        composite_task.id
+    );
+
+    Ok(pr)
+}
@@ -0,0 +1,119 @@
+use std::sync::{Arc, OnceLock};
+
+use autodev_core::CompositeTask;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Environment variable capping concurrently running tasks in this process
+pub const MAX_PARALLEL_TASKS_ENV: &str = "AUTODEV_MAX_PARALLEL_TASKS";
+
+/// Process-wide cap when AUTODEV_MAX_PARALLEL_TASKS is not set
+pub const DEFAULT_MAX_PARALLEL_TASKS: usize = 5;
+
+static GLOBAL_LIMIT: OnceLock<Arc<Semaphore>> = OnceLock::new();
+
+/// Process-wide task cap from AUTODEV_MAX_PARALLEL_TASKS
+pub fn max_parallel_tasks() -> usize {
+    std::env::var(MAX_PARALLEL_TASKS_ENV)
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_MAX_PARALLEL_TASKS)
+}
+
+/// Semaphore shared by every composite task running in this process
+pub fn global_task_limit() -> Arc<Semaphore> {
+    GLOBAL_LIMIT
+        .get_or_init(|| {
+            let limit = max_parallel_tasks();
+            tracing::info!("Running at most {} tasks in parallel", limit);
+            Arc::new(Semaphore::new(limit))
+        })
+        .clone()
+}
+
+/// Concurrency limit for the subtasks of one composite task
+///
+/// A slot is taken from both the composite task's own limit
+/// (`max_parallel_tasks`) and the process-wide one.
+#[derive(Clone)]
+pub struct TaskLimiter {
+    local: Option<Arc<Semaphore>>,
+    global: Arc<Semaphore>,
+}
+
+/// Slot held while a task runs; dropping it frees the slot
+pub struct TaskPermit {
+    _local: Option<OwnedSemaphorePermit>,
+    _global: OwnedSemaphorePermit,
+}
+
+impl TaskLimiter {
+    pub fn new(max_parallel_tasks: Option<usize>, global: Arc<Semaphore>) -> Self {
+        Self {
+            local: max_parallel_tasks
+                .filter(|n| *n > 0)
+                .map(|n| Arc::new(Semaphore::new(n))),
+            global,
+        }
+    }
+
+    /// Limiter for a composite task, sharing the process-wide limit
+    pub fn for_composite(composite_task: &CompositeTask) -> Self {
+        Self::new(composite_task.max_parallel_tasks, global_task_limit())
+    }
+
+    /// Wait for a free slot
+    pub async fn acquire(&self) -> TaskPermit {
+        // Take the composite's own slot first so a waiting composite does not
+        // hold a global slot other composites could use
+        let local = match &self.local {
+            Some(local) => Some(local.clone().acquire_owned().await.expect("semaphore closed")),
+            None => None,
+        };
+        let global = self.global.clone().acquire_owned().await.expect("semaphore closed");
+
+        TaskPermit {
+            _local: local,
+            _global: global,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_composite_limit_within_global_limit() {
+        let global = Arc::new(Semaphore::new(3));
+        let limiter = TaskLimiter::new(Some(2), global.clone());
+
+        let first = limiter.acquire().await;
+        let _second = limiter.acquire().await;
+        assert_eq!(global.available_permits(), 1);
+
+        // The composite limit is reached even though a global slot is free
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(20), limiter.acquire())
+            .await
+            .is_err());
+
+        drop(first);
+        let _third = limiter.acquire().await;
+        assert_eq!(global.available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_global_limit_shared_between_composites() {
+        let global = Arc::new(Semaphore::new(1));
+        let a = TaskLimiter::new(None, global.clone());
+        let b = TaskLimiter::new(Some(4), global);
+
+        let permit = a.acquire().await;
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(20), b.acquire())
+            .await
+            .is_err());
+
+        drop(permit);
+        let _permit = b.acquire().await;
+    }
+}
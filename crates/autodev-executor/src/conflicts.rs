@@ -0,0 +1,490 @@
+//! Merge conflicts between subtask PRs and their parent branch
+//!
+//! Subtasks of one batch branch off the same parent commit, so the PRs merged
+//! after the first one may conflict with it. With a local executor the agent
+//! rebases the PR branch onto the parent and resolves the conflicting hunks;
+//! otherwise (or when that fails) the batch is paused until someone resolves
+//! the conflicts by hand.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::OwnedMutexGuard;
+
+use autodev_core::Task;
+use autodev_github::{CheckRunReport, CheckRunState, MergeBlocker, Repository, VcsProvider};
+use autodev_local_executor::LocalExecutor;
+use autodev_notify::NotificationEvent;
+
+use crate::merge::{merge_when_ready, MergeGate, MergeOutcome};
+use crate::subtask_merge_options;
+
+/// How long a batch waits for a conflicting PR to be fixed by hand
+pub const CONFLICT_WAIT_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Locks of the subtask PRs being merged, by `owner/repo#number`
+fn pr_locks() -> &'static Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>> {
+    static LOCKS: OnceLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+    LOCKS.get_or_init(Default::default)
+}
+
+/// Held while a subtask PR is merged; the lock is dropped with its last user
+struct PrLock {
+    key: String,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl PrLock {
+    async fn acquire(repository: &Repository, pr_number: u64) -> Self {
+        let key = format!("{}#{}", repository.full_name(), pr_number);
+        let lock = pr_locks().lock().unwrap().entry(key.clone()).or_default().clone();
+        Self {
+            key,
+            _guard: lock.lock_owned().await,
+        }
+    }
+}
+
+impl Drop for PrLock {
+    fn drop(&mut self) {
+        let mut locks = pr_locks().lock().unwrap();
+        // 맵과 이 guard만 참조하면 기다리는 호출이 없음
+        if locks.get(&self.key).is_some_and(|lock| Arc::strong_count(lock) <= 2) {
+            locks.remove(&self.key);
+        }
+    }
+}
+
+/// Merge a subtask PR into its parent branch, resolving merge conflicts
+///
+/// The PR is reviewed first when the gate has an AI review. Conflicts are
+/// handed to `resolver` when one is given. When it fails or there is none,
+/// the PR is commented on, a `merge_conflict` notification is sent and this
+/// waits for the conflicts to be resolved manually.
+///
+/// Calls for the same PR run one at a time: a second caller waits for the
+/// first and then finds the PR merged instead of resolving it again. A PR
+/// whose head is not the subtask's branch in `repository` is never merged.
+#[allow(clippy::too_many_arguments)]
+pub async fn merge_subtask_pr<V: VcsProvider + ?Sized>(
+    task: &Task,
+    pr_number: u64,
+    repository: &Repository,
+    parent_branch: &str,
+    vcs: &Arc<V>,
+    gate: &MergeGate,
+    resolver: Option<&Arc<dyn LocalExecutor>>,
+) -> Result<MergeOutcome> {
+    let _lock = PrLock::acquire(repository, pr_number).await;
+    check_subtask_pr(task, pr_number, repository, parent_branch, vcs).await?;
+
+    if let Some(review) = &gate.review {
+        if let Some(blocker) = review.check(task, pr_number, repository, vcs).await {
+            tracing::warn!("PR #{} of task {} is held back: {}", pr_number, task.title, blocker);
+            return Ok(MergeOutcome::Blocked(blocker));
+        }
+    }
+
+    let options = subtask_merge_options(task, pr_number, gate.method);
+    let outcome = merge_when_ready(repository, pr_number, vcs, &options, gate).await?;
+
+    if outcome != MergeOutcome::Blocked(MergeBlocker::Conflicts) {
+        return Ok(outcome);
+    }
+    tracing::warn!("PR #{} of task {} conflicts with {}", pr_number, task.title, parent_branch);
+
+    let reason = match resolver {
+        Some(executor) => match resolve_with_agent(task, pr_number, repository, parent_branch, vcs, executor).await {
+            Ok(()) => {
+                let outcome = merge_when_ready(repository, pr_number, vcs, &options, gate).await?;
+                if outcome != MergeOutcome::Blocked(MergeBlocker::Conflicts) {
+                    return Ok(outcome);
+                }
+                "conflicts remain after the agent rebased the branch".to_string()
+            }
+            Err(e) => {
+                tracing::error!("Failed to resolve conflicts of PR #{}: {}", pr_number, e);
+                format!("automatic resolution failed: {}", e)
+            }
+        },
+        None => "automatic resolution needs a local executor".to_string(),
+    };
+
+    request_manual_fix(task, pr_number, repository, parent_branch, vcs, &reason).await;
+    wait_for_conflict_fix(task, pr_number, repository, vcs, gate).await
+}
+
+/// Fail unless PR `pr_number` merges the subtask's own branch of `repository`
+///
+/// Subtask workflows push to `autodev/<id>`, Docker workers to
+/// `<parent_branch>_<id>` and subtasks started from a workflow callback to
+/// `<parent_branch>/subtask-<id>`. A fork's branch of the same name is rejected.
+async fn check_subtask_pr<V: VcsProvider + ?Sized>(
+    task: &Task,
+    pr_number: u64,
+    repository: &Repository,
+    parent_branch: &str,
+    vcs: &Arc<V>,
+) -> Result<()> {
+    let branch = vcs.merge_request_branch(repository, pr_number).await?;
+    let expected = [
+        format!("autodev/{}", task.id),
+        format!("{}_{}", parent_branch, task.id),
+        format!("{}/subtask-{}", parent_branch, task.id),
+    ];
+    if !expected.contains(&branch) {
+        return Err(anyhow::anyhow!(
+            "PR #{} merges {}, not the branch of subtask {}",
+            pr_number,
+            branch,
+            task.title
+        ));
+    }
+
+    if vcs.merge_request_from_fork(repository, pr_number).await? {
+        return Err(anyhow::anyhow!(
+            "PR #{} for subtask {} comes from a fork of {}",
+            pr_number,
+            task.title,
+            repository.full_name()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Rebase the PR branch onto the parent branch with the agent resolving conflicts
+async fn resolve_with_agent<V: VcsProvider + ?Sized>(
+    task: &Task,
+    pr_number: u64,
+    repository: &Repository,
+    parent_branch: &str,
+    vcs: &Arc<V>,
+    executor: &Arc<dyn LocalExecutor>,
+) -> Result<()> {
+    let branch = vcs.merge_request_branch(repository, pr_number).await?;
+    tracing::info!("Resolving conflicts of PR #{} by rebasing {} onto {}", pr_number, branch, parent_branch);
+
+    let result = executor
+        .resolve_conflicts(
+            &format!("resolve-{}", task.id),
+            repository,
+            &branch,
+            parent_branch,
+            &task.title,
+            &resolve_prompt(task, parent_branch),
+        )
+        .await?;
+
+    if !result.success {
+        return Err(anyhow::anyhow!(
+            "{}",
+            result.error.unwrap_or_else(|| "Unknown error".to_string())
+        ));
+    }
+
+    let files: Vec<String> = result.files_changed.iter().map(|f| format!("- `{}`", f)).collect();
+    let body = format!(
+        "🔀 Rebased onto `{}` and resolved conflicts in:\n\n{}",
+        parent_branch,
+        files.join("\n")
+    );
+    if let Err(e) = vcs.comment(repository, pr_number, &body).await {
+        tracing::warn!("Failed to comment on PR #{}: {}", pr_number, e);
+    }
+
+    Ok(())
+}
+
+pub(crate) fn resolve_prompt(task: &Task, parent_branch: &str) -> String {
+    format!(
+        "The branch of this task is being rebased onto `{}` and the files listed below have merge conflicts. \
+         Resolve every conflict so both this task's changes and the changes already on `{}` are kept, \
+         and remove all conflict markers. Do not commit or run git commands.\n\n\
+         ## Task: {}\n\n{}",
+        parent_branch, parent_branch, task.title, task.prompt
+    )
+}
+
+/// Ask for a manual fix on the PR, by notification and on the AutoDev check
+async fn request_manual_fix<V: VcsProvider + ?Sized>(
+    task: &Task,
+    pr_number: u64,
+    repository: &Repository,
+    parent_branch: &str,
+    vcs: &Arc<V>,
+    reason: &str,
+) {
+    let body = format!(
+        "⚠️ This PR conflicts with `{}` ({}). Resolve the conflicts or merge it manually; \
+         the remaining subtasks wait until then.",
+        parent_branch, reason
+    );
+    if let Err(e) = vcs.comment(repository, pr_number, &body).await {
+        tracing::warn!("Failed to comment on PR #{}: {}", pr_number, e);
+    }
+
+    let report = CheckRunReport::new(
+        CheckRunState::Neutral,
+        format!("Paused: PR #{} has merge conflicts", pr_number),
+        body,
+    );
+    if let Err(e) = vcs.report_check(repository, parent_branch, &report).await {
+        tracing::warn!("Failed to report check on {}: {}", parent_branch, e);
+    }
+
+    autodev_notify::notify(NotificationEvent::MergeConflict {
+        task_id: task.id.clone(),
+        title: task.title.clone(),
+        pr_url: vcs.merge_request_url(repository, pr_number),
+        reason: reason.to_string(),
+    });
+}
+
+/// Wait until the PR is merged by hand or no longer conflicts, then merge it
+async fn wait_for_conflict_fix<V: VcsProvider + ?Sized>(
+    task: &Task,
+    pr_number: u64,
+    repository: &Repository,
+    vcs: &Arc<V>,
+    gate: &MergeGate,
+) -> Result<MergeOutcome> {
+    let deadline = tokio::time::Instant::now() + CONFLICT_WAIT_TIMEOUT;
+
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(gate.poll_interval).await;
+
+        match vcs.is_merge_request_merged(repository, pr_number).await {
+            Ok(true) => {
+                tracing::info!("✓ PR #{} manually merged for task: {}", pr_number, task.title);
+                return Ok(MergeOutcome::Merged);
+            }
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!("Error checking PR merge status: {}", e);
+                continue;
+            }
+        }
+
+        match vcs.merge_request_mergeability(repository, pr_number).await {
+            Ok(state) if state.blocker() != Some(MergeBlocker::Conflicts) => {
+                tracing::info!("Conflicts of PR #{} resolved, merging", pr_number);
+                let options = subtask_merge_options(task, pr_number, gate.method);
+                return merge_when_ready(repository, pr_number, vcs, &options, gate).await;
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to check whether PR #{} is mergeable: {}", pr_number, e),
+        }
+    }
+
+    Ok(MergeOutcome::Blocked(MergeBlocker::Conflicts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use autodev_core::MergeMethod;
+    use autodev_github::mock::MockPullRequestState;
+    use autodev_github::{Mergeability, MockGitHubClient, RepoConfig};
+    use autodev_local_executor::TaskResult;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Rebases by clearing the PR's conflicts, or fails when `fixes` is false
+    struct Resolver {
+        github: Arc<MockGitHubClient>,
+        repository: Repository,
+        pr_number: u64,
+        fixes: bool,
+        runs: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl LocalExecutor for Resolver {
+        async fn execute_task(&self, _: &Task, _: &Repository, _: &str, _: &str, _: Option<&str>) -> Result<TaskResult> {
+            Err(anyhow::anyhow!("conflict resolution does not run tasks"))
+        }
+
+        async fn fix_branch(&self, _: &str, _: &Repository, _: &str, _: &str, _: &str) -> Result<TaskResult> {
+            Err(anyhow::anyhow!("conflict resolution does not fix branches"))
+        }
+
+        async fn resolve_conflicts(
+            &self,
+            _id: &str,
+            _repository: &Repository,
+            _branch: &str,
+            _onto: &str,
+            _title: &str,
+            _prompt: &str,
+        ) -> Result<TaskResult> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            if self.fixes {
+                self.github.update_pull_request(&self.repository, self.pr_number, |pr| {
+                    pr.mergeability = Mergeability::ready();
+                });
+            }
+            Ok(TaskResult {
+                has_changes: self.fixes,
+                pr_number: None,
+                pr_url: None,
+                success: self.fixes,
+                error: (!self.fixes).then(|| "rebase failed".to_string()),
+                commit_sha: None,
+                files_changed: vec!["src/lib.rs".to_string()],
+                lines_added: 0,
+                lines_removed: 0,
+            })
+        }
+
+        async fn merge_branch(&self, _: &str, _: &Repository, _: &str, _: &str) -> Result<String> {
+            Err(anyhow::anyhow!("conflict resolution does not merge branches"))
+        }
+
+        async fn stop_task(&self, _: &str) -> Result<bool> {
+            Ok(false)
+        }
+    }
+
+    /// A PR of `task` into `parent` that conflicts with it
+    async fn conflicting_pr(github: &MockGitHubClient, repository: &Repository, parent: &str, task: &Task) -> u64 {
+        let branch = format!("autodev/{}", task.id);
+        github.add_branch(repository, parent);
+        github.add_branch(repository, &branch);
+        let pr = github
+            .create_merge_request(repository, "Sub".to_string(), String::new(), branch, parent.to_string(), false)
+            .await
+            .unwrap();
+        github.update_pull_request(repository, pr.number, |pr| {
+            pr.mergeability = Mergeability {
+                mergeable: Some(false),
+                mergeable_state: "dirty".to_string(),
+                ..Default::default()
+            };
+        });
+        pr.number
+    }
+
+    fn gate() -> MergeGate {
+        MergeGate {
+            poll_interval: Duration::from_secs(10 * 60),
+            ..MergeGate::new(MergeMethod::Merge, &RepoConfig::default())
+        }
+    }
+
+    fn resolver(github: &Arc<MockGitHubClient>, repository: &Repository, pr_number: u64, fixes: bool) -> Arc<Resolver> {
+        Arc::new(Resolver {
+            github: github.clone(),
+            repository: repository.clone(),
+            pr_number,
+            fixes,
+            runs: AtomicUsize::new(0),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_agent_resolves_conflicts_once() {
+        let github = Arc::new(MockGitHubClient::new());
+        let repository = Repository::new("o".to_string(), "resolved".to_string());
+        let task = Task::new("Sub".to_string(), String::new(), String::new());
+        let pr_number = conflicting_pr(&github, &repository, "autodev/parent", &task).await;
+        let resolver = resolver(&github, &repository, pr_number, true);
+        let executor: Arc<dyn LocalExecutor> = resolver.clone();
+        let gate = gate();
+
+        // The second caller waits for the first and finds the PR merged
+        let merge = || merge_subtask_pr(&task, pr_number, &repository, "autodev/parent", &github, &gate, Some(&executor));
+        let (first, second) = tokio::join!(merge(), merge());
+        assert_eq!(first.unwrap(), MergeOutcome::Merged);
+        assert_eq!(second.unwrap(), MergeOutcome::Merged);
+
+        assert_eq!(resolver.runs.load(Ordering::SeqCst), 1);
+        let pr = github.pull_request(&repository, pr_number).unwrap();
+        assert_eq!(pr.state, MockPullRequestState::Merged);
+        assert!(pr.comments[0].contains("resolved conflicts in:\n\n- `src/lib.rs`"));
+        assert!(!pr_locks().lock().unwrap().contains_key(&format!("o/resolved#{}", pr_number)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failed_resolution_waits_for_manual_fix() {
+        let github = Arc::new(MockGitHubClient::new());
+        let repository = Repository::new("o".to_string(), "manual".to_string());
+        let task = Task::new("Sub".to_string(), String::new(), String::new());
+        let pr_number = conflicting_pr(&github, &repository, "autodev/parent", &task).await;
+        let executor: Arc<dyn LocalExecutor> = resolver(&github, &repository, pr_number, false);
+
+        let merge = {
+            let (github, repository) = (github.clone(), repository.clone());
+            tokio::spawn(async move {
+                merge_subtask_pr(&task, pr_number, &repository, "autodev/parent", &github, &gate(), Some(&executor)).await
+            })
+        };
+
+        // Someone resolves the conflicts by hand an hour later
+        tokio::time::sleep(Duration::from_secs(60 * 60)).await;
+        let pr = github.pull_request(&repository, pr_number).unwrap();
+        assert_eq!(pr.state, MockPullRequestState::Open);
+        assert!(pr.comments[0].contains("automatic resolution failed: rebase failed"));
+        let checks = github.checks(&repository);
+        assert_eq!(checks[0].0, "autodev/parent");
+        assert_eq!(checks[0].1.state, CheckRunState::Neutral);
+
+        github.update_pull_request(&repository, pr_number, |pr| pr.mergeability = Mergeability::ready());
+        assert_eq!(merge.await.unwrap().unwrap(), MergeOutcome::Merged);
+        assert_eq!(
+            github.pull_request(&repository, pr_number).unwrap().state,
+            MockPullRequestState::Merged
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_conflict_wait_times_out() {
+        let github = Arc::new(MockGitHubClient::new());
+        let repository = Repository::new("o".to_string(), "timeout".to_string());
+        let task = Task::new("Sub".to_string(), String::new(), String::new());
+        let pr_number = conflicting_pr(&github, &repository, "autodev/parent", &task).await;
+
+        let start = tokio::time::Instant::now();
+        let outcome = merge_subtask_pr(&task, pr_number, &repository, "autodev/parent", &github, &gate(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome, MergeOutcome::Blocked(MergeBlocker::Conflicts));
+        assert!(start.elapsed() >= CONFLICT_WAIT_TIMEOUT);
+        let pr = github.pull_request(&repository, pr_number).unwrap();
+        assert!(pr.comments[0].contains("automatic resolution needs a local executor"));
+        assert_eq!(pr.state, MockPullRequestState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_pr_of_another_branch_or_fork_is_not_merged() {
+        let github = Arc::new(MockGitHubClient::new());
+        let repository = Repository::new("o".to_string(), "foreign".to_string());
+        let task = Task::new("Sub".to_string(), String::new(), String::new());
+        github.add_branch(&repository, "autodev/parent");
+        github.add_branch(&repository, "feature/other");
+        let other = github
+            .create_merge_request(&repository, "Other".to_string(), String::new(), "feature/other".to_string(), "autodev/parent".to_string(), false)
+            .await
+            .unwrap();
+
+        let error = merge_subtask_pr(&task, other.number, &repository, "autodev/parent", &github, &gate(), None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("not the branch of subtask"), "{}", error);
+
+        let fork = conflicting_pr(&github, &repository, "autodev/parent", &task).await;
+        github.update_pull_request(&repository, fork, |pr| {
+            pr.from_fork = true;
+            pr.mergeability = Mergeability::ready();
+        });
+        let error = merge_subtask_pr(&task, fork, &repository, "autodev/parent", &github, &gate(), None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("comes from a fork"), "{}", error);
+
+        let states: Vec<_> = github.pull_requests(&repository).iter().map(|pr| pr.state).collect();
+        assert_eq!(states, vec![MockPullRequestState::Open, MockPullRequestState::Open]);
+    }
+}
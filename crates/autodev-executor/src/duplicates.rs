@@ -0,0 +1,66 @@
+//! Duplicate-task detection on creation
+//!
+//! A new task duplicates an unfinished task of the same repository whose
+//! prompt is equal after [`autodev_core::normalize_prompt`]. Creators return
+//! the existing task instead (API `force`, CLI `--force` skip the check), so
+//! double submissions and webhook redeliveries do not run the same work twice.
+
+use std::sync::Arc;
+
+use autodev_core::{AutoDevEngine, Task};
+use autodev_db::Database;
+use autodev_github::Repository;
+
+/// Unfinished task of `repository` with the same prompt, if any
+///
+/// With a database the lookup is scoped to the repository; when the database
+/// cannot be read (or there is none) the engine's in-memory tasks are used.
+pub async fn find_duplicate_task(
+    engine: &AutoDevEngine,
+    db: &Option<Arc<Database>>,
+    repository: &Repository,
+    prompt: &str,
+) -> Option<Task> {
+    if let Some(db) = db {
+        match db.find_open_duplicate(&repository.owner, &repository.name, prompt).await {
+            Ok(Some(record)) => {
+                if let Some(task) = engine.get_task(&record.id).await {
+                    return Some(task);
+                }
+                match record.to_task() {
+                    Ok(task) => return Some(task),
+                    Err(e) => tracing::warn!("Failed to read duplicate task {}: {}", record.id, e),
+                }
+            }
+            Ok(None) => return None,
+            Err(e) => tracing::warn!("Failed to look up duplicates of a task in {}: {}", repository, e),
+        }
+    }
+
+    engine.find_open_duplicate(&repository.owner, &repository.name, prompt).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_find_duplicate_task_without_database() {
+        let engine = AutoDevEngine::new();
+        let repository = Repository::new("owner".to_string(), "repo".to_string());
+        let task = engine
+            .register_task(
+                Task::new("Login".to_string(), "".to_string(), "Add a login page".to_string())
+                    .with_repository("owner".to_string(), "repo".to_string()),
+            )
+            .await
+            .unwrap();
+
+        let duplicate = find_duplicate_task(&engine, &None, &repository, "add a  login page\n").await;
+        assert_eq!(duplicate.map(|t| t.id), Some(task.id));
+        assert!(find_duplicate_task(&engine, &None, &repository, "Add a signup page").await.is_none());
+
+        let other = Repository::new("owner".to_string(), "other".to_string());
+        assert!(find_duplicate_task(&engine, &None, &other, "Add a login page").await.is_none());
+    }
+}
@@ -0,0 +1,54 @@
+//! pgvector-backed storage for example embeddings
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use autodev_ai::VectorStore;
+use autodev_db::Database;
+
+/// Example vectors kept in the `example_embeddings` table
+pub struct PgVectorStore {
+    db: Arc<Database>,
+}
+
+impl PgVectorStore {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+}
+
+fn store_error(e: autodev_db::Error) -> autodev_ai::Error {
+    autodev_ai::Error::Other(anyhow::anyhow!("Vector store error: {}", e))
+}
+
+#[async_trait]
+impl VectorStore for PgVectorStore {
+    async fn load(&self, model: &str, texts: &[String]) -> autodev_ai::Result<HashMap<String, Vec<f32>>> {
+        let vectors = self.db.get_embeddings(model, texts).await.map_err(store_error)?;
+        Ok(vectors.into_iter().collect())
+    }
+
+    async fn save(&self, model: &str, vectors: &[(String, Vec<f32>)]) -> autodev_ai::Result<()> {
+        self.db.save_embeddings(model, vectors).await.map_err(store_error)
+    }
+
+    async fn nearest(
+        &self,
+        model: &str,
+        candidates: &[String],
+        query: &[f32],
+        limit: usize,
+    ) -> autodev_ai::Result<Vec<String>> {
+        self.db
+            .nearest_embeddings(model, candidates, query, limit)
+            .await
+            .map_err(store_error)
+    }
+}
+
+/// Vector store for example retrieval (None without a database)
+pub fn example_vector_store(db: &Option<Arc<Database>>) -> Option<Arc<dyn VectorStore>> {
+    db.as_ref()
+        .map(|db| Arc::new(PgVectorStore::new(db.clone())) as Arc<dyn VectorStore>)
+}
@@ -0,0 +1,120 @@
+//! Classification of the errors executor functions return
+//!
+//! Executor functions return `anyhow` errors wrapping the typed errors of the
+//! engine, the VCS provider, the AI agent and the database. [`classify`] takes
+//! the class of the first typed error in the chain; plain messages are not
+//! classified and keep being retried as before.
+
+use std::sync::Arc;
+
+use autodev_core::{AutoDevEngine, ErrorClass, FailureKind};
+use autodev_db::Database;
+
+/// Class of the first typed error in `error`'s chain
+pub fn classify(error: &anyhow::Error) -> Option<ErrorClass> {
+    error.chain().find_map(|cause| {
+        if let Some(e) = cause.downcast_ref::<autodev_core::Error>() {
+            return match e {
+                autodev_core::Error::Other(inner) => classify(inner),
+                e => Some(e.class()),
+            };
+        }
+        if let Some(e) = cause.downcast_ref::<autodev_github::Error>() {
+            return match e {
+                autodev_github::Error::Other(inner) => classify(inner),
+                e => Some(e.class()),
+            };
+        }
+        if let Some(e) = cause.downcast_ref::<autodev_vcs::Error>() {
+            return match e {
+                autodev_vcs::Error::Other(inner) => classify(inner),
+                e => Some(e.class()),
+            };
+        }
+        if let Some(e) = cause.downcast_ref::<autodev_ai::Error>() {
+            return match e {
+                autodev_ai::Error::Other(inner) => classify(inner),
+                e => Some(e.class()),
+            };
+        }
+        if let Some(e) = cause.downcast_ref::<autodev_db::Error>() {
+            return match e {
+                autodev_db::Error::Other(inner) => classify(inner),
+                e => Some(e.class()),
+            };
+        }
+        None
+    })
+}
+
+/// Whether what failed with `error` may succeed when tried again
+pub fn is_retryable(error: &anyhow::Error) -> bool {
+    classify(error).is_none_or(ErrorClass::is_retryable)
+}
+
+/// [`crate::schedule_retry`] for a task that failed with `error`
+///
+/// Errors that would fail the same way again (not found, conflict, rejected
+/// credentials, permanent) are not retried; throttled tasks wait at least as
+/// long as the service asked for.
+pub async fn schedule_retry_for_error(
+    task_id: &str,
+    kind: FailureKind,
+    error: &anyhow::Error,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+) -> anyhow::Result<bool> {
+    let class = classify(error);
+    if let Some(class) = class.filter(|class| !class.is_retryable()) {
+        tracing::info!("Task {} will not be retried ({:?} error: {})", task_id, class, error);
+        return Ok(false);
+    }
+
+    crate::schedule_retry_after(task_id, kind, class.and_then(ErrorClass::retry_after), engine, db).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_classify() {
+        let error = anyhow::Error::new(autodev_vcs::Error::PullRequestNotFound("o/r#7".to_string()))
+            .context("Failed to merge");
+        assert_eq!(classify(&error), Some(ErrorClass::NotFound));
+        assert!(!is_retryable(&error));
+
+        // Typed errors wrapped in another crate's Other
+        let throttled = autodev_ai::Error::Unavailable {
+            status: 429,
+            message: "Too many requests".to_string(),
+            retry_after: Some(Duration::from_secs(20)),
+        };
+        let error = anyhow::Error::new(autodev_core::Error::Other(throttled.into()));
+        assert_eq!(
+            classify(&error),
+            Some(ErrorClass::RateLimited { retry_after: Some(Duration::from_secs(20)) })
+        );
+        assert!(is_retryable(&error));
+
+        let error = anyhow::anyhow!("container exited with 137");
+        assert_eq!(classify(&error), None);
+        assert!(is_retryable(&error));
+    }
+
+    #[tokio::test]
+    async fn test_permanent_errors_are_not_retried() {
+        let engine = Arc::new(AutoDevEngine::new());
+        let task = engine
+            .create_simple_task("T".to_string(), "".to_string(), "".to_string())
+            .await
+            .unwrap();
+        let error = anyhow::Error::new(autodev_github::Error::AuthError("Bad credentials".to_string()));
+
+        let retried = schedule_retry_for_error(&task.id, FailureKind::ExecutionError, &error, &engine, &None)
+            .await
+            .unwrap();
+        assert!(!retried);
+    }
+}
@@ -0,0 +1,33 @@
+//! Heartbeats for tasks this process runs itself
+//!
+//! Local containers have no way to reach the API, so the process waiting for
+//! them extends the task's lease instead. Should the process die, the lease
+//! expires and a worker requeues the task. Heartbeat errors are only logged.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use autodev_db::Database;
+
+/// Run `future`, extending the lease on `task_id` every third of the lease
+pub async fn while_heartbeating<T>(db: &Option<Arc<Database>>, task_id: &str, future: impl Future<Output = T>) -> T {
+    let Some(db) = db else {
+        return future.await;
+    };
+
+    let lease = autodev_core::lease::lease_duration();
+    let heartbeat = async {
+        let mut interval = tokio::time::interval(lease / 3);
+        loop {
+            interval.tick().await;
+            if let Err(e) = db.heartbeat_running_task(task_id, lease).await {
+                tracing::warn!("Heartbeat for task {} failed: {}", task_id, e);
+            }
+        }
+    };
+
+    tokio::select! {
+        result = future => result,
+        () = heartbeat => unreachable!("the heartbeat loop never ends"),
+    }
+}
@@ -1,147 +1,732 @@
+pub mod artifacts;
+pub mod audit;
+pub mod branches;
+pub mod budget;
+pub mod cache;
+pub mod cancellation;
+pub mod checks;
+pub mod completion;
+pub mod composite_pr;
+pub mod concurrency;
+pub mod conflicts;
+pub mod duplicates;
+pub mod embeddings;
+pub mod errors;
+pub mod heartbeat;
+pub mod local_orchestrator;
+pub mod logs;
+pub mod merge;
+pub mod metrics;
+pub mod notifications;
+pub mod payload;
+pub mod review_gate;
+pub mod pr_description;
+pub mod rollback;
+pub mod routing;
+pub mod secrets;
+pub mod security_scan;
+pub mod stream;
+pub mod verification;
+
 use anyhow::Result;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 
-use autodev_core::{AutoDevEngine, CompositeTask, Task, TaskStatus};
-use autodev_github::{GitHubClient, Repository};
+use autodev_ai::AIAgent;
+use autodev_core::{AutoDevEngine, CompositeTask, FailureKind, FailurePolicy, MergeMethod, Task, TaskStatus};
+use autodev_github::{MergeOptions, RepoConfig, Repository, VcsProvider};
 use autodev_db::Database;
-use autodev_local_executor::{DockerExecutor, TaskResult};
+use autodev_local_executor::{LocalExecutor, TaskResult};
+use autodev_notify::NotificationEvent;
+use tokio::sync::watch;
+
+pub use artifacts::prompt_with_dependencies;
+pub use audit::{audited, AuditedVcs};
+pub use branches::{delete_merged_branch, prune_stale_branches};
+pub use budget::run_within_budget;
+pub use cache::{with_response_cache, DbCacheStore};
+pub use cancellation::{cancel_composite_task, is_composite_cancelled, CancelOptions, CancelReport};
+pub use completion::{wait_for_event, CompletionNotifier, CompletionState};
+pub use concurrency::{global_task_limit, TaskLimiter};
+pub use conflicts::merge_subtask_pr;
+pub use duplicates::find_duplicate_task;
+pub use embeddings::{example_vector_store, PgVectorStore};
+pub use errors::schedule_retry_for_error;
+pub use logs::TaskLogSink;
+pub use merge::{merge_when_ready, try_merge, MergeGate, MergeOutcome};
+pub use metrics::{save_execution_metrics, save_merge_request_metrics};
+pub use notifications::EventNotifier;
+pub use payload::dispatch_inputs;
+pub use pr_description::{describe_merge_request, pr_descriptions_enabled};
+pub use review_gate::ReviewGate;
+pub use rollback::{rollback_composite_task, RollbackOptions, RollbackReport};
+pub use routing::ExecutionRouter;
+pub use secrets::{secret_source, DbSecretSource, SECRET_GRANT_TTL};
+pub use security_scan::{scan_composite_pr, scan_merge_request, SecurityScan};
+pub use stream::stream_to_task_logs;
+pub use verification::{run_test_workflow, verify_batch, TestOutcome};
+
+/// How long a task's workflow run may take (GitHub Actions stops jobs after 6 hours)
+const WORKFLOW_TIMEOUT: Duration = Duration::from_secs(6 * 60 * 60 + 30 * 60);
+
+/// How long to wait for a PR to be opened or merged
+const PR_WAIT_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// How long a composite task waits for the next batch to be approved
+pub const BATCH_APPROVAL_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Load the repository's `.autodev.yml` and check its pipeline before dispatching
+async fn prepare_repository<V: VcsProvider + ?Sized>(
+    repository: &Repository,
+    vcs: &Arc<V>,
+) -> Result<RepoConfig> {
+    let config = vcs.load_repo_config(repository).await?;
+
+    // 워크플로 파일이 없으면 dispatch가 조용히 실패하므로 먼저 확인
+    // (workflow_file을 직접 지정한 저장소는 사용자가 관리)
+    if config.workflow_file.is_none() {
+        vcs.check_pipeline_setup(repository).await?;
+    }
+
+    Ok(config)
+}
+
+/// `composite_task` with the repository's defaults for settings it leaves open
+///
+/// `max_parallel_tasks` and `merge_method` are taken from the config when the
+/// task leaves them unset. Auto-approve is settled when the task is created
+/// (see [`repo_auto_approve`]), so applying the defaults again is a no-op.
+pub fn with_repo_defaults(composite_task: &CompositeTask, config: &RepoConfig) -> CompositeTask {
+    let mut composite_task = composite_task.clone();
+
+    if composite_task.max_parallel_tasks.is_none() {
+        composite_task.max_parallel_tasks = config.max_parallel_tasks;
+    }
+    if composite_task.merge_method.is_none() {
+        composite_task.merge_method = config.merge_method;
+    }
+
+    composite_task
+}
+
+/// Auto-approve of a new composite task: the requested value, or the
+/// repository's default when the request leaves it open
+pub fn repo_auto_approve(requested: Option<bool>, config: &RepoConfig) -> bool {
+    requested.or(config.auto_approve).unwrap_or(false)
+}
+
+/// How a subtask PR is auto-merged into the parent branch
+///
+/// Squash commits are titled after the task so the parent branch history
+/// reads one commit per subtask.
+pub fn subtask_merge_options(task: &Task, pr_number: u64, method: MergeMethod) -> MergeOptions {
+    let options = MergeOptions::new(method);
+
+    match method {
+        MergeMethod::Squash => options.with_commit(
+            format!("{} (#{})", task.title, pr_number),
+            format!("AutoDev task: {}", task.id),
+        ),
+        MergeMethod::Merge | MergeMethod::Rebase => options,
+    }
+}
 
-/// Wait for a batch of tasks to complete (workflow + PR merge)
-async fn wait_for_batch_completion(
-    workflow_runs: Vec<(Task, u64)>,
+/// Run one subtask of a composite task: dispatch, workflow, PR
+///
+/// The task is registered with the completion notifier so webhook/callback
+/// events end each wait immediately; the provider is only polled when no event
+/// arrives within the notifier's poll interval. A concurrency slot is held
+/// only while the workflow runs, not while its PR waits for review.
+#[allow(clippy::too_many_arguments)]
+async fn run_composite_subtask<V: VcsProvider + ?Sized>(
+    task: &Task,
     repository: &Repository,
-    github_client: &Arc<GitHubClient>,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    db: &Option<Arc<Database>>,
+    notifier: &Arc<CompletionNotifier>,
+    limiter: &TaskLimiter,
+    parent_branch: &str,
+    composite_task_id: &str,
     auto_approve: bool,
+    merge_gate: &MergeGate,
 ) -> Result<()> {
-    for (task, run_id) in workflow_runs {
-        let task_branch = format!("autodev/{}", task.id);
-
-        tracing::info!("Waiting for task {} to complete...", task.title);
-
-        // Step 1: Wait for workflow to complete
-        loop {
-            tokio::time::sleep(Duration::from_secs(30)).await;
-
-            match github_client.get_workflow_run_status(repository, run_id).await {
-                Ok(status) => {
-                    if let Some(conclusion) = &status.conclusion {
-                        match conclusion.as_str() {
-                            "success" => {
-                                tracing::info!("Workflow completed for task: {}", task.title);
-                                break;
-                            }
-                            "failure" | "cancelled" | "timed_out" => {
-                                tracing::error!("Workflow failed for task {}: {}", task.title, conclusion);
-                                return Err(anyhow::anyhow!(
-                                    "Workflow failed with conclusion: {}",
-                                    conclusion
-                                ));
-                            }
-                            _ => {
-                                // Still running or other state
-                            }
-                        }
-                    }
-                }
+    let mut rx = notifier.register(&task.id, repository, None);
+
+    let run = async {
+        wait_while_paused(composite_task_id, engine, db, notifier.poll_interval()).await?;
+        let permit = limiter.acquire().await;
+
+        if is_cancelled(engine, &task.id).await {
+            return Err(anyhow::anyhow!("Task cancelled: {}", task.title));
+        }
+
+        let run_id = execute_simple_task(
+            task,
+            repository,
+            engine,
+            vcs,
+            db,
+            Some(parent_branch),
+            Some(composite_task_id),
+        ).await?;
+        tracing::info!("Workflow triggered successfully for {}: {}", task.title, run_id);
+        notifier.set_run_id(&task.id, run_id);
+
+        wait_for_workflow(task, run_id, &mut rx, notifier, repository, engine, vcs, WORKFLOW_TIMEOUT).await?;
+        drop(permit);
+
+        wait_for_task_pr(
+            task,
+            &mut rx,
+            repository,
+            vcs,
+            notifier.poll_interval(),
+            auto_approve,
+            parent_branch,
+            merge_gate,
+        ).await
+    };
+    let result = cancellation::unless_cancelled(composite_task_id, engine, db, notifier.poll_interval(), run).await;
+
+    notifier.unregister(&task.id);
+    result
+}
+
+/// Wait for the workflow run of a task to finish successfully
+///
+/// Any conclusion ends the wait; every conclusion but `success` is a failure.
+/// A run that does not finish within [`WORKFLOW_TIMEOUT`] is cancelled.
+#[allow(clippy::too_many_arguments)]
+async fn wait_for_workflow<V: VcsProvider + ?Sized>(
+    task: &Task,
+    run_id: u64,
+    rx: &mut watch::Receiver<CompletionState>,
+    notifier: &CompletionNotifier,
+    repository: &Repository,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    timeout: Duration,
+) -> Result<()> {
+    tracing::info!("Waiting for task {} to complete...", task.title);
+
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    let conclusion = loop {
+        let signalled = wait_for_event(rx, notifier.poll_interval()).await;
+
+        if is_cancelled(engine, &task.id).await {
+            tracing::warn!("Task {} was cancelled, stopping batch", task.title);
+            return Err(anyhow::anyhow!("Task cancelled: {}", task.title));
+        }
+
+        let conclusion = match notifier.take_conclusion(&task.id) {
+            Some(conclusion) => Some(conclusion),
+            None if signalled => None,
+            // No event yet: fall back to polling the provider
+            None => match vcs.pipeline_status(repository, run_id).await {
+                Ok(status) => status.conclusion,
                 Err(e) => {
                     tracing::warn!("Error checking workflow status: {}", e);
+                    None
                 }
+            },
+        };
+
+        if let Some(conclusion) = conclusion {
+            break conclusion;
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            tracing::error!("Workflow of task {} did not finish within {:?}", task.title, timeout);
+            if let Err(e) = vcs.cancel_pipeline(repository, run_id).await {
+                tracing::warn!("Failed to cancel workflow run {}: {}", run_id, e);
             }
+            return Err(anyhow::anyhow!(
+                "Workflow did not finish within {:?}",
+                timeout
+            ));
         }
+    };
 
-        // Step 2: Wait for PR to be created
-        tracing::info!("Waiting for PR to be created for task: {}", task.title);
-        let mut pr_number: Option<u64> = None;
+    if conclusion != "success" {
+        tracing::error!("Workflow failed for task {}: {}", task.title, conclusion);
+        return Err(anyhow::anyhow!(
+            "Workflow failed with conclusion: {}",
+            conclusion
+        ));
+    }
 
-        for _ in 0..20 {  // Max 10 minutes (20 * 30s)
-            tokio::time::sleep(Duration::from_secs(30)).await;
+    tracing::info!("Workflow completed for task: {}", task.title);
+    Ok(())
+}
+
+/// Wait for the task PR to be opened, then merge it (auto-approve) or wait for a manual merge
+#[allow(clippy::too_many_arguments)]
+async fn wait_for_task_pr<V: VcsProvider + ?Sized>(
+    task: &Task,
+    rx: &mut watch::Receiver<CompletionState>,
+    repository: &Repository,
+    vcs: &Arc<V>,
+    poll_interval: Duration,
+    auto_approve: bool,
+    parent_branch: &str,
+    merge_gate: &MergeGate,
+) -> Result<()> {
+    let task_branch = format!("autodev/{}", task.id);
+
+    tracing::info!("Waiting for PR to be created for task: {}", task.title);
+    let deadline = tokio::time::Instant::now() + PR_WAIT_TIMEOUT;
+    let mut pr_number = rx.borrow().pr_number;
 
+    while pr_number.is_none() && tokio::time::Instant::now() < deadline {
+        if !wait_for_event(rx, poll_interval).await {
             // Find PR by branch
-            if let Ok(Some(num)) = github_client.find_pr_by_branch(repository, &task_branch).await {
+            if let Ok(Some(num)) = vcs.find_merge_request_by_branch(repository, &task_branch).await {
                 pr_number = Some(num);
-                tracing::info!("Found PR #{} for task: {}", num, task.title);
-                break;
             }
         }
 
-        let pr_num = pr_number.ok_or_else(|| {
-            anyhow::anyhow!("PR not found for task: {}", task.title)
-        })?;
+        pr_number = pr_number.or(rx.borrow().pr_number);
+    }
+
+    let pr_num = pr_number.ok_or_else(|| {
+        anyhow::anyhow!("PR not found for task: {}", task.title)
+    })?;
+    tracing::info!("Found PR #{} for task: {}", pr_num, task.title);
 
-        // Step 3: Auto-merge if enabled, otherwise wait for manual merge
-        if auto_approve {
-            tracing::info!("Auto-approving PR #{} for task: {}", pr_num, task.title);
+    // Auto-merge if enabled, otherwise wait for manual merge
+    if auto_approve {
+        tracing::info!("Auto-approving PR #{} for task: {}", pr_num, task.title);
 
-            // Attempt to merge the PR
-            match github_client.merge_pull_request(repository, pr_num).await {
-                Ok(_) => {
-                    tracing::info!("✓ PR #{} auto-merged successfully for task: {}", pr_num, task.title);
-                }
-                Err(e) => {
-                    tracing::error!("Failed to auto-merge PR #{}: {}", pr_num, e);
-                    return Err(anyhow::anyhow!("Failed to auto-merge PR #{}: {}", pr_num, e));
+        // Merge once branch protection allows it; without a local executor
+        // conflicts wait for a manual fix
+        match merge_subtask_pr(task, pr_num, repository, parent_branch, vcs, merge_gate, None).await {
+            Ok(MergeOutcome::Merged) => {
+                tracing::info!("✓ PR #{} auto-merged successfully for task: {}", pr_num, task.title);
+            }
+            Ok(MergeOutcome::Blocked(blocker)) => {
+                tracing::warn!("PR #{} cannot be auto-merged ({}), waiting for a manual merge", pr_num, blocker);
+                notify_pr_awaiting_review(task, repository, vcs, pr_num).await;
+                wait_for_pr_merge(task, pr_num, rx, repository, vcs, poll_interval).await?;
+            }
+            Err(e) => {
+                tracing::error!("Failed to auto-merge PR #{}: {}", pr_num, e);
+                return Err(anyhow::anyhow!("Failed to auto-merge PR #{}: {}", pr_num, e));
+            }
+        }
+    } else {
+        // Wait for manual merge
+        tracing::info!("Waiting for manual merge of PR #{} for task: {}", pr_num, task.title);
+        notify_pr_awaiting_review(task, repository, vcs, pr_num).await;
+        wait_for_pr_merge(task, pr_num, rx, repository, vcs, poll_interval).await?;
+    }
+
+    Ok(())
+}
+
+/// Tell reviewers a task PR waits for a manual merge
+async fn notify_pr_awaiting_review<V: VcsProvider + ?Sized>(
+    task: &Task,
+    repository: &Repository,
+    vcs: &Arc<V>,
+    pr_num: u64,
+) {
+    autodev_notify::notify(NotificationEvent::PrAwaitingReview {
+        task_id: task.id.clone(),
+        title: task.title.clone(),
+        pr_url: vcs.merge_request_url(repository, pr_num),
+    });
+}
+
+/// Wait until batch `batch` (1-based) of a composite task is approved
+///
+/// Approvals made in this process arrive as engine events. Approvals made by
+/// another process (e.g. the API while the CLI executes) are picked up from
+/// the database every poll interval.
+async fn wait_for_batch_approval(
+    composite_id: &str,
+    batch: usize,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+    poll_interval: Duration,
+) -> Result<()> {
+    let mut events = engine.subscribe();
+    let deadline = tokio::time::Instant::now() + BATCH_APPROVAL_TIMEOUT;
+
+    tracing::info!(
+        "Waiting for approval of batch {} (POST /composite-tasks/{}/batches/{}/approve)",
+        batch,
+        composite_id,
+        batch
+    );
+
+    loop {
+        if engine.is_batch_approved(composite_id, batch).await {
+            tracing::info!("Batch {} of composite task {} approved", batch, composite_id);
+            return Ok(());
+        }
+
+        if let Some(db) = db {
+            match db.get_composite_task(composite_id).await {
+                Ok(Some(record)) if record.approved_batches.contains(&(batch as i32)) => {
+                    engine.approve_batch(composite_id, batch).await?;
+                    continue;
                 }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Error checking batch approval: {}", e),
             }
-        } else {
-            // Wait for manual merge
-            tracing::info!("Waiting for manual merge of PR #{} for task: {}", pr_num, task.title);
+        }
 
-            for _ in 0..20 {  // Max 10 minutes (20 * 30s)
-                tokio::time::sleep(Duration::from_secs(30)).await;
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return Err(anyhow::anyhow!(
+                "Batch {} of composite task {} was not approved within {:?}",
+                batch,
+                composite_id,
+                BATCH_APPROVAL_TIMEOUT
+            ));
+        }
 
-                match github_client.is_pr_merged(repository, pr_num).await {
-                    Ok(true) => {
-                        tracing::info!("✓ PR #{} manually merged for task: {}", pr_num, task.title);
-                        break;
-                    }
-                    Ok(false) => {
-                        // Still waiting for manual merge
-                    }
-                    Err(e) => {
-                        tracing::warn!("Error checking PR merge status: {}", e);
+        // Any engine event (or the poll interval) triggers a re-check
+        let _ = tokio::time::timeout(poll_interval.min(deadline - now), events.recv()).await;
+    }
+}
+
+/// Wait while a composite task is paused
+///
+/// Pauses and resumes made by another process (e.g. the API while a worker
+/// executes) are picked up from the database every poll interval. There is
+/// no deadline: a pause lasts until someone resumes the composite task.
+async fn wait_while_paused(
+    composite_id: &str,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+    poll_interval: Duration,
+) -> Result<()> {
+    let mut events = engine.subscribe();
+    let mut waiting = false;
+
+    loop {
+        if let Some(db) = db {
+            let paused_here = engine.get_composite_task(composite_id).await.is_some_and(|c| c.is_paused());
+            match db.get_composite_task(composite_id).await {
+                Ok(Some(record)) if (record.review_state == "Paused") != paused_here => {
+                    let synced = if paused_here {
+                        engine.unpause_composite_task(composite_id).await
+                    } else {
+                        engine.pause_composite_task(composite_id).await
+                    };
+                    if let Err(e) = synced {
+                        tracing::warn!("Failed to sync pause of composite task {}: {}", composite_id, e);
                     }
                 }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Error checking pause of composite task {}: {}", composite_id, e),
             }
+        }
 
-            // Verify merge completed
-            match github_client.is_pr_merged(repository, pr_num).await {
-                Ok(true) => {
-                    tracing::info!("PR #{} merge confirmed", pr_num);
-                }
-                Ok(false) => {
-                    return Err(anyhow::anyhow!(
-                        "PR #{} was not merged within timeout period for task: {}",
-                        pr_num,
-                        task.title
-                    ));
-                }
-                Err(e) => {
-                    return Err(anyhow::anyhow!(
-                        "Failed to verify PR #{} merge status: {}",
-                        pr_num,
-                        e
-                    ));
-                }
+        if !engine.get_composite_task(composite_id).await.is_some_and(|c| c.is_paused()) {
+            if waiting {
+                tracing::info!("Composite task {} resumed", composite_id);
+            }
+            return Ok(());
+        }
+
+        if !waiting {
+            tracing::info!(
+                "Composite task {} is paused (POST /composite-tasks/{}/resume)",
+                composite_id,
+                composite_id
+            );
+            waiting = true;
+        }
+
+        // Any engine event (or the poll interval) triggers a re-check
+        let _ = tokio::time::timeout(poll_interval, events.recv()).await;
+    }
+}
+
+/// Wait for a task PR to be merged manually, preferring webhook events over polling
+async fn wait_for_pr_merge<V: VcsProvider + ?Sized>(
+    task: &Task,
+    pr_num: u64,
+    rx: &mut watch::Receiver<CompletionState>,
+    repository: &Repository,
+    vcs: &Arc<V>,
+    poll_interval: Duration,
+) -> Result<()> {
+    let deadline = tokio::time::Instant::now() + PR_WAIT_TIMEOUT;
+
+    while tokio::time::Instant::now() < deadline {
+        if rx.borrow().merged {
+            tracing::info!("✓ PR #{} manually merged for task: {}", pr_num, task.title);
+            return Ok(());
+        }
+
+        if wait_for_event(rx, poll_interval).await {
+            continue;
+        }
+
+        match vcs.is_merge_request_merged(repository, pr_num).await {
+            Ok(true) => {
+                tracing::info!("✓ PR #{} manually merged for task: {}", pr_num, task.title);
+                return Ok(());
+            }
+            Ok(false) => {
+                // Still waiting for manual merge
+            }
+            Err(e) => {
+                tracing::warn!("Error checking PR merge status: {}", e);
+            }
+        }
+    }
+
+    // Verify merge completed
+    match vcs.is_merge_request_merged(repository, pr_num).await {
+        Ok(true) => {
+            tracing::info!("PR #{} merge confirmed", pr_num);
+            Ok(())
+        }
+        Ok(false) => Err(anyhow::anyhow!(
+            "PR #{} was not merged within timeout period for task: {}",
+            pr_num,
+            task.title
+        )),
+        Err(e) => Err(anyhow::anyhow!(
+            "Failed to verify PR #{} merge status: {}",
+            pr_num,
+            e
+        )),
+    }
+}
+
+/// Tasks of a batch that still have to run
+///
+/// Completed subtasks are only found in a batch when the composite task was
+/// resumed; they are skipped instead of being dispatched again, as are
+/// subtasks skipped because a dependency failed.
+async fn unfinished_tasks(batch: &[Task], engine: &Arc<AutoDevEngine>) -> Vec<Task> {
+    let mut unfinished = Vec::new();
+
+    for task in batch {
+        let task = engine.get_task(&task.id).await.unwrap_or_else(|| task.clone());
+        if task.status != TaskStatus::Completed && task.blocked_by.is_none() {
+            unfinished.push(task);
+        }
+    }
+
+    unfinished
+}
+
+/// Subtasks of a composite task completed so far
+async fn completed_subtasks(composite_task: &CompositeTask, engine: &Arc<AutoDevEngine>) -> usize {
+    let mut completed = 0;
+    for task in &composite_task.subtasks {
+        if engine.get_task(&task.id).await.is_some_and(|t| t.status == TaskStatus::Completed) {
+            completed += 1;
+        }
+    }
+    completed
+}
+
+/// Apply the composite task's failure policy to the subtasks of a batch that failed
+///
+/// The dependents of failed subtasks are blocked or skipped by the engine.
+/// Returns the first error when execution has to stop: under the block policy,
+/// or when a subtask was cancelled or failed after its workflow (e.g. while
+/// merging its PR). Otherwise execution continues with the other subtasks.
+async fn apply_failure_policy(
+    composite_task: &CompositeTask,
+    failures: Vec<(String, anyhow::Error)>,
+    engine: &Arc<AutoDevEngine>,
+) -> Result<()> {
+    let mut stop = composite_task.failure_policy == FailurePolicy::Block;
+    let mut first_error = None;
+    let count = failures.len();
+
+    for (task_id, error) in failures {
+        let failed = engine
+            .get_task(&task_id)
+            .await
+            .is_some_and(|t| t.status == TaskStatus::Failed);
+        if failed {
+            if let Err(e) = engine.propagate_failure(&task_id).await {
+                tracing::warn!("Failed to apply failure policy to dependents of {}: {}", task_id, e);
             }
+        } else {
+            stop = true;
         }
+        first_error.get_or_insert(error);
+    }
+
+    match first_error {
+        Some(e) if stop => Err(e),
+        Some(_) => {
+            tracing::warn!(
+                "Composite task {} continues after {} failed subtask(s) (failure policy: {})",
+                composite_task.id,
+                count,
+                composite_task.failure_policy
+            );
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
+
+/// Whether a task has been cancelled through the engine
+async fn is_cancelled(engine: &Arc<AutoDevEngine>, task_id: &str) -> bool {
+    engine
+        .get_task(task_id)
+        .await
+        .is_some_and(|t| t.status == TaskStatus::Cancelled)
+}
+
+/// Cancel a task and stop whatever is executing it
+///
+/// Marks the task cancelled in the engine, then cancels its CI pipeline
+/// (if one was recorded) and stops its local run (if a local executor is
+/// given). Failures to stop remote execution are
+/// logged but do not undo the cancellation.
+pub async fn cancel_task<V: VcsProvider + ?Sized>(
+    task_id: &str,
+    repository: &Repository,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    db: &Option<Arc<Database>>,
+    local_executor: Option<&Arc<dyn LocalExecutor>>,
+) -> Result<Task> {
+    let task = engine.cancel_task(task_id).await?;
+
+    if let Some(run_id) = task
+        .workflow_run_id
+        .as_deref()
+        .and_then(|id| id.parse::<u64>().ok())
+    {
+        if let Err(e) = vcs.cancel_pipeline(repository, run_id).await {
+            tracing::warn!("Failed to cancel workflow run {}: {}", run_id, e);
+        }
+    }
+
+    if let Some(local_exec) = local_executor {
+        if let Err(e) = local_exec.stop_task(task_id).await {
+            tracing::warn!("Failed to stop local run of task {}: {}", task_id, e);
+        }
+    }
+
+    log_execution(engine, db, task_id, "CANCELLED", "Task cancelled by user").await?;
+
+    Ok(task)
+}
+
+/// Record an execution log entry and publish it to live subscribers
+async fn log_execution(
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+    task_id: &str,
+    event_type: &str,
+    message: &str,
+) -> Result<()> {
+    engine.log_event(task_id, event_type, message);
+
+    if let Some(db) = db {
+        db.add_execution_log(task_id, event_type, message).await?;
     }
 
     Ok(())
 }
 
-/// Execute a simple task by triggering GitHub Actions workflow
-pub async fn execute_simple_task(
+/// Wait out the retry backoff of a failed task and reset it for another attempt
+///
+/// Returns false when the task's retry policy does not allow another attempt
+/// (or the task was cancelled while waiting). The caller is responsible for
+/// dispatching the task again.
+pub async fn schedule_retry(
+    task_id: &str,
+    kind: FailureKind,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+) -> Result<bool> {
+    schedule_retry_after(task_id, kind, None, engine, db).await
+}
+
+/// [`schedule_retry`] waiting at least `min_delay` (e.g. a rate limit's Retry-After)
+pub async fn schedule_retry_after(
+    task_id: &str,
+    kind: FailureKind,
+    min_delay: Option<Duration>,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+) -> Result<bool> {
+    let backoff = match engine.retry_backoff(task_id, kind).await? {
+        Some(backoff) => backoff.max(min_delay.unwrap_or_default()),
+        None => {
+            tracing::info!("Task {} will not be retried ({:?})", task_id, kind);
+            return Ok(false);
+        }
+    };
+
+    log_execution(
+        engine,
+        db,
+        task_id,
+        "RETRY_SCHEDULED",
+        &format!("Retrying after {:?} ({:?})", backoff, kind),
+    ).await?;
+
+    tokio::time::sleep(backoff).await;
+
+    match engine.reset_for_retry(task_id).await {
+        Ok(_) => Ok(true),
+        Err(autodev_core::Error::InvalidTaskState(reason)) => {
+            tracing::info!("Skipping retry of task {}: {}", task_id, reason);
+            Ok(false)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Retry a failed standalone task by re-triggering its CI pipeline
+///
+/// Returns the new workflow run ID, or None if the task is not retried.
+pub async fn retry_failed_task<V: VcsProvider + ?Sized>(
+    task_id: &str,
+    kind: FailureKind,
+    repository: &Repository,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    db: &Option<Arc<Database>>,
+) -> Result<Option<u64>> {
+    if !schedule_retry(task_id, kind, engine, db).await? {
+        return Ok(None);
+    }
+
+    let task = engine
+        .get_task(task_id)
+        .await
+        .ok_or_else(|| autodev_core::Error::TaskNotFound(task_id.to_string()))?;
+
+    let run_id = execute_simple_task(&task, repository, engine, vcs, db, None, None).await?;
+
+    Ok(Some(run_id))
+}
+
+/// Execute a simple task by triggering a CI pipeline (GitHub Actions workflow)
+pub async fn execute_simple_task<V: VcsProvider + ?Sized>(
     task: &Task,
     repository: &Repository,
     engine: &Arc<AutoDevEngine>,
-    github_client: &Arc<GitHubClient>,
+    vcs: &Arc<V>,
     db: &Option<Arc<Database>>,
     parent_branch: Option<&str>,
     composite_task_id: Option<&str>,
 ) -> Result<u64> {
     tracing::info!("Executing task: {} ({})", task.title, task.id);
 
+    let config = prepare_repository(repository, vcs).await?;
+
     // Update status
     engine.update_task_status(&task.id, TaskStatus::InProgress, None).await?;
 
@@ -150,53 +735,192 @@ pub async fn execute_simple_task(
         // Composite task: branch from parent, PR to parent
         (parent.to_string(), parent.to_string())
     } else {
-        // Standalone task: branch from main, PR to main
-        ("main".to_string(), "main".to_string())
+        // Standalone task: branch from the default branch, PR to it
+        (config.default_branch().to_string(), config.default_branch().to_string())
     };
 
     // Create branch for this task
     let task_branch = format!("autodev/{}", task.id);
-    if let Err(e) = github_client.create_branch(repository, &task_branch, &base_branch).await {
+    if let Err(e) = vcs.create_branch(repository, &task_branch, &base_branch).await {
         tracing::warn!("Failed to create branch (may already exist): {}", e);
     }
 
-    // Trigger GitHub workflow
-    let mut workflow_inputs = std::collections::HashMap::new();
-    workflow_inputs.insert("task_id".to_string(), task.id.clone());
-    workflow_inputs.insert("composite_task_id".to_string(),
-        composite_task_id.unwrap_or("standalone").to_string());
-    workflow_inputs.insert("task_title".to_string(), task.title.clone());
-    workflow_inputs.insert("prompt".to_string(), task.prompt.clone());
-    workflow_inputs.insert("base_branch".to_string(), task_branch.clone());
-    workflow_inputs.insert("target_branch".to_string(), target_branch.clone());
+    // Trigger CI pipeline (with what the task's dependencies changed)
+    let prompt = prompt_with_dependencies(task, db).await;
+    let workflow_inputs = dispatch_inputs(
+        task,
+        repository,
+        composite_task_id,
+        &config.apply_to_prompt(&prompt),
+        &task_branch,
+        &target_branch,
+        db,
+    ).await?;
 
-    tracing::info!("Triggering GitHub Actions workflow for task: {}", task.id);
+    tracing::info!("Triggering {} pipeline for task: {}", vcs.name(), task.id);
 
-    let run_id = github_client
-        .trigger_workflow(repository, "autodev.yml", workflow_inputs)
+    let run_id = vcs
+        .trigger_pipeline(repository, config.workflow_file(), &task_branch, workflow_inputs)
         .await?;
 
     tracing::info!("Workflow triggered: {} (run_id: {})", task.id, run_id);
 
+    // Remember the run so it can be cancelled later
+    engine.set_workflow_run(&task.id, run_id).await?;
+
     // Save execution log
-    if let Some(db) = db {
-        db.add_execution_log(
-            &task.id,
-            "WORKFLOW_TRIGGERED",
-            &format!("GitHub Actions workflow triggered: {}", run_id),
+    log_execution(
+        engine,
+        db,
+        &task.id,
+        "WORKFLOW_TRIGGERED",
+        &format!("{} pipeline triggered: {}", vcs.name(), run_id),
+    ).await?;
+
+    Ok(run_id)
+}
+
+/// Prepare a stopped (e.g. partially failed) composite task to be executed again
+///
+/// Subtasks whose PR is already merged, or that completed without opening a
+/// PR, are kept; all others are reset to Pending. Run the returned composite
+/// task with `execute_composite_task` or `execute_composite_task_docker`:
+/// completed subtasks are skipped, so execution continues from the first
+/// unfinished batch.
+pub async fn prepare_composite_resume<V: VcsProvider + ?Sized>(
+    composite_id: &str,
+    repository: &Repository,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    db: &Option<Arc<Database>>,
+) -> Result<CompositeTask> {
+    let composite_task = engine
+        .get_composite_task(composite_id)
+        .await
+        .ok_or_else(|| autodev_core::Error::TaskNotFound(composite_id.to_string()))?;
+
+    let mut done = HashSet::new();
+    for subtask in &composite_task.subtasks {
+        let status = engine
+            .get_task(&subtask.id)
+            .await
+            .map_or(subtask.status, |t| t.status);
+
+        let task_branch = format!("autodev/{}", subtask.id);
+        let finished = match vcs.find_merge_request_by_branch(repository, &task_branch).await? {
+            Some(pr_num) => vcs.is_merge_request_merged(repository, pr_num).await?,
+            None => status == TaskStatus::Completed,
+        };
+
+        if finished {
+            done.insert(subtask.id.clone());
+        }
+    }
+
+    let resumed = engine.resume_composite_task(composite_id, &done).await?;
+
+    for subtask in resumed.subtasks.iter().filter(|t| !done.contains(&t.id)) {
+        log_execution(
+            engine,
+            db,
+            &subtask.id,
+            "RESUMED",
+            &format!("Composite task {} resumed", composite_id),
         ).await?;
     }
 
-    Ok(run_id)
+    Ok(resumed)
 }
 
 /// Execute a composite task by processing batches sequentially
-pub async fn execute_composite_task(
+///
+/// On failure the composite task is rolled back when AUTODEV_ROLLBACK_ON_FAILURE is set.
+pub async fn execute_composite_task<V: VcsProvider + ?Sized + 'static>(
+    composite_task: &CompositeTask,
+    repository: &Repository,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    ai_agent: &Arc<dyn AIAgent>,
+    db: &Option<Arc<Database>>,
+    notifier: &Arc<CompletionNotifier>,
+) -> Result<()> {
+    let result = execute_composite_batches(composite_task, repository, engine, vcs, ai_agent, db, notifier).await;
+
+    finish_composite_task(&result, composite_task, repository, engine, vcs, db).await;
+
+    result
+}
+
+/// Notify about a completed composite task, or roll back a failed one
+///
+/// A cancelled composite task is neither completed nor rolled back.
+async fn finish_composite_task<V: VcsProvider + ?Sized>(
+    result: &Result<()>,
+    composite_task: &CompositeTask,
+    repository: &Repository,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    db: &Option<Arc<Database>>,
+) {
+    if is_composite_cancelled(&composite_task.id, engine, db).await {
+        checks::report_composite_check(vcs, repository, &composite_task.id, &checks::cancelled(composite_task)).await;
+        return;
+    }
+
+    if let Err(e) = result {
+        checks::report_composite_check(
+            vcs,
+            repository,
+            &composite_task.id,
+            &checks::failed(composite_task, &e.to_string()),
+        ).await;
+        rollback_after_failure(composite_task, repository, engine, vcs, db).await;
+        return;
+    }
+
+    let completed = match engine.complete_composite_task(&composite_task.id).await {
+        Ok(completed) => completed,
+        Err(e) => {
+            tracing::warn!("Failed to mark composite task {} completed: {}", composite_task.id, e);
+            composite_task.clone()
+        }
+    };
+
+    checks::report_composite_check(vcs, repository, &composite_task.id, &checks::completed(&completed)).await;
+}
+
+/// Roll back a failed composite task if automatic rollback is enabled
+async fn rollback_after_failure<V: VcsProvider + ?Sized>(
     composite_task: &CompositeTask,
     repository: &Repository,
     engine: &Arc<AutoDevEngine>,
-    github_client: &Arc<GitHubClient>,
+    vcs: &Arc<V>,
     db: &Option<Arc<Database>>,
+) {
+    if !rollback::rollback_on_failure() {
+        return;
+    }
+
+    if let Err(e) = rollback_composite_task(
+        composite_task,
+        repository,
+        engine,
+        vcs,
+        db,
+        &RollbackOptions::default(),
+    ).await {
+        tracing::error!("Failed to roll back composite task {}: {}", composite_task.id, e);
+    }
+}
+
+async fn execute_composite_batches<V: VcsProvider + ?Sized + 'static>(
+    composite_task: &CompositeTask,
+    repository: &Repository,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    ai_agent: &Arc<dyn AIAgent>,
+    db: &Option<Arc<Database>>,
+    notifier: &Arc<CompletionNotifier>,
 ) -> Result<()> {
     tracing::info!(
         "Executing composite task: {} ({}) with {} subtasks",
@@ -205,17 +929,62 @@ pub async fn execute_composite_task(
         composite_task.subtasks.len()
     );
 
+    let config = prepare_repository(repository, vcs).await?;
+    let composite_task = &with_repo_defaults(composite_task, &config);
+
     // Create parent branch for composite task
     let parent_branch = format!("autodev/{}", composite_task.id);
     tracing::info!("Creating parent branch: {}", parent_branch);
 
-    if let Err(e) = github_client.create_branch(repository, &parent_branch, "main").await {
+    let base_branch = composite_task.base_branch.as_deref().unwrap_or(config.default_branch());
+    if let Err(e) = vcs.create_branch(repository, &parent_branch, base_branch).await {
         tracing::warn!("Failed to create parent branch (may already exist): {}", e);
     }
+    checks::report_composite_check(vcs, repository, &composite_task.id, &checks::decomposed(composite_task)).await;
 
     let batches = composite_task.get_parallel_batches();
+    let limiter = TaskLimiter::for_composite(composite_task);
+    let merge_gate = MergeGate::new(composite_task.merge_method.unwrap_or_default(), &config)
+        .with_review(ReviewGate::from_config(ai_agent.clone(), &config));
 
     for (i, batch) in batches.iter().enumerate() {
+        if is_composite_cancelled(&composite_task.id, engine, db).await {
+            return Err(anyhow::anyhow!("Composite task {} was cancelled", composite_task.id));
+        }
+
+        let batch = unfinished_tasks(batch, engine).await;
+        if batch.is_empty() {
+            tracing::info!("Batch {}/{} already completed or skipped", i + 1, batches.len());
+            continue;
+        }
+
+        if engine.get_composite_task(&composite_task.id).await.is_some_and(|c| c.is_paused()) {
+            checks::report_composite_check(vcs, repository, &composite_task.id, &checks::paused(composite_task, i + 1)).await;
+        }
+        cancellation::unless_cancelled(
+            &composite_task.id,
+            engine,
+            db,
+            notifier.poll_interval(),
+            wait_while_paused(&composite_task.id, engine, db, notifier.poll_interval()),
+        ).await?;
+
+        if !composite_task.auto_approve {
+            checks::report_composite_check(
+                vcs,
+                repository,
+                &composite_task.id,
+                &checks::awaiting_approval(composite_task, i + 1),
+            ).await;
+            cancellation::unless_cancelled(
+                &composite_task.id,
+                engine,
+                db,
+                notifier.poll_interval(),
+                wait_for_batch_approval(&composite_task.id, i + 1, engine, db, notifier.poll_interval()),
+            ).await?;
+        }
+
         tracing::info!(
             "Processing batch {}/{}: {} tasks",
             i + 1,
@@ -223,84 +992,101 @@ pub async fn execute_composite_task(
             batch.len()
         );
 
-        // Trigger all workflows in batch concurrently
+        // Run the batch's tasks concurrently, at most `limiter` at a time
         let mut handles = Vec::new();
 
         for task in batch {
-            let task = task.clone();
+            let task_id = task.id.clone();
             let repository = repository.clone();
             let engine = engine.clone();
-            let github_client = github_client.clone();
+            let vcs = vcs.clone();
             let db = db.clone();
+            let notifier = notifier.clone();
+            let limiter = limiter.clone();
             let parent_branch_clone = parent_branch.clone();
             let composite_id = composite_task.id.clone();
+            let auto_approve = composite_task.auto_approve;
+            let merge_gate = merge_gate.clone();
 
             let handle = tokio::spawn(async move {
-                let run_id = execute_simple_task(
+                run_composite_subtask(
                     &task,
                     &repository,
                     &engine,
-                    &github_client,
+                    &vcs,
                     &db,
-                    Some(&parent_branch_clone),
-                    Some(&composite_id),
-                ).await?;
-                Ok::<(Task, u64), anyhow::Error>((task, run_id))
+                    &notifier,
+                    &limiter,
+                    &parent_branch_clone,
+                    &composite_id,
+                    auto_approve,
+                    &merge_gate,
+                ).await
             });
 
-            handles.push(handle);
+            handles.push((task_id, handle));
         }
 
-        // Collect workflow run IDs
-        let mut workflow_runs = Vec::new();
-        for handle in handles {
-            match handle.await {
-                Ok(Ok((task, run_id))) => {
-                    tracing::info!("Workflow triggered successfully for {}: {}", task.title, run_id);
-                    workflow_runs.push((task, run_id));
-                }
-                Ok(Err(e)) => {
-                    tracing::error!("Failed to trigger workflow: {}", e);
-                    return Err(e);
-                }
-                Err(e) => {
-                    tracing::error!("Task execution failed: {}", e);
-                    return Err(anyhow::anyhow!("Task execution failed: {}", e));
-                }
+        // Wait for every task (workflow + PR merge) before the next batch
+        let mut failures = Vec::new();
+        for (task_id, handle) in handles {
+            let result = match handle.await {
+                Ok(result) => result,
+                Err(e) => Err(anyhow::anyhow!("Task execution failed: {}", e)),
+            };
+
+            if let Err(e) = result {
+                tracing::error!("Batch {}/{} task failed: {}", i + 1, batches.len(), e);
+                failures.push((task_id, e));
             }
         }
 
-        tracing::info!("Batch {}/{} workflows triggered", i + 1, batches.len());
-
-        // Wait for all workflows and PRs in this batch to complete
-        wait_for_batch_completion(workflow_runs, repository, github_client, composite_task.auto_approve).await?;
+        apply_failure_policy(composite_task, failures, engine).await?;
 
         tracing::info!("Batch {}/{} completed and merged", i + 1, batches.len());
+
+        if let Some(workflow_file) = &config.test_workflow {
+            verify_batch(
+                composite_task,
+                i + 1,
+                workflow_file,
+                repository,
+                engine,
+                vcs,
+                db,
+                notifier.poll_interval(),
+            ).await?;
+        }
+
+        let merged = completed_subtasks(composite_task, engine).await;
+        checks::report_composite_check(
+            vcs,
+            repository,
+            &composite_task.id,
+            &checks::subtasks_merged(composite_task, merged),
+        ).await;
     }
 
     tracing::info!("Composite task execution initiated: {}", composite_task.title);
     Ok(())
 }
 
-/// ========================================
-/// Docker-based Local Execution Functions
-/// ========================================
+// ========================================
+// Local Execution Functions (Docker or process)
+// ========================================
 
-/// Execute a simple task using Docker executor
+/// Execute a simple task using the local executor
+///
+/// Failed attempts are retried in place according to the task's retry policy.
 pub async fn execute_simple_task_docker(
     task: &Task,
     repository: &Repository,
-    docker_executor: &Arc<DockerExecutor>,
+    local_executor: &Arc<dyn LocalExecutor>,
     engine: &Arc<AutoDevEngine>,
     db: &Option<Arc<Database>>,
     parent_branch: Option<&str>,
     composite_task_id: Option<&str>,
 ) -> Result<TaskResult> {
-    tracing::info!("Executing task with Docker: {} ({})", task.title, task.id);
-
-    // Update status
-    engine.update_task_status(&task.id, TaskStatus::InProgress, None).await?;
-
     // Determine base branch and target branch
     let (base_branch, target_branch) = if let Some(parent) = parent_branch {
         // Composite task: branch from parent, PR to parent
@@ -310,51 +1096,106 @@ pub async fn execute_simple_task_docker(
         ("main".to_string(), "main".to_string())
     };
 
-    // Execute task in Docker
-    let result = docker_executor.execute_task(
-        task,
-        repository,
-        &base_branch,
-        &target_branch,
-        composite_task_id,
-    ).await?;
+    // Build on what the task's dependencies changed
+    let task = &Task {
+        prompt: prompt_with_dependencies(task, db).await,
+        ..task.clone()
+    };
 
-    // Update task status based on result
-    if result.success {
-        engine.update_task_status(&task.id, TaskStatus::Completed, None).await?;
+    loop {
+        tracing::info!("Executing task locally: {} ({})", task.title, task.id);
+
+        // Update status
+        engine.update_task_status(&task.id, TaskStatus::InProgress, None).await?;
+
+        // Execute task locally
+        let started = std::time::Instant::now();
+        let execution = local_executor.execute_task(
+            task,
+            repository,
+            &base_branch,
+            &target_branch,
+            composite_task_id,
+        );
+        let result = match heartbeat::while_heartbeating(db, &task.id, execution).await {
+            Ok(result) => result,
+            Err(e) => {
+                if is_cancelled(engine, &task.id).await {
+                    return Err(anyhow::anyhow!("Task cancelled: {}", task.title));
+                }
+
+                TaskResult {
+                    has_changes: false,
+                    pr_number: None,
+                    pr_url: None,
+                    success: false,
+                    error: Some(e.to_string()),
+                    commit_sha: None,
+                    files_changed: Vec::new(),
+                    lines_added: 0,
+                    lines_removed: 0,
+                }
+            }
+        };
+        metrics::save_execution_metrics(db, &task.id, started.elapsed(), metrics::result_diff_stat(&result)).await;
+
+        // Update task status based on result
+        if result.success {
+            if let Some(pr_url) = &result.pr_url {
+                engine.set_task_pr(&task.id, result.pr_number, pr_url.clone()).await?;
+            }
+            engine.update_task_status(&task.id, TaskStatus::Completed, None).await?;
+            artifacts::save_artifact(db, artifacts::artifact_from_result(task, &result)).await;
 
-        if let Some(db) = db {
             let pr_info = result.pr_number
                 .map(|n| format!("PR: #{}", n))
                 .unwrap_or_else(|| "No PR created".to_string());
 
-            db.add_execution_log(
+            log_execution(
+                engine,
+                db,
                 &task.id,
                 "COMPLETED",
                 &format!("Task completed successfully. {}", pr_info),
             ).await?;
+
+            return Ok(result);
         }
-    } else {
+
         engine.update_task_status(&task.id, TaskStatus::Failed, result.error.clone()).await?;
 
-        if let Some(db) = db {
-            db.add_execution_log(
-                &task.id,
-                "FAILED",
-                &result.error.clone().unwrap_or_else(|| "Unknown error".to_string())
-            ).await?;
+        log_execution(
+            engine,
+            db,
+            &task.id,
+            "FAILED",
+            &result.error.clone().unwrap_or_else(|| "Unknown error".to_string()),
+        ).await?;
+
+        if !schedule_retry(&task.id, FailureKind::ExecutionError, engine, db).await? {
+            autodev_notify::notify(NotificationEvent::TaskFailed {
+                task_id: task.id.clone(),
+                title: task.title.clone(),
+                error: result.error.clone(),
+            });
+            return Ok(result);
         }
     }
-
-    Ok(result)
 }
 
-/// Wait for a batch of Docker tasks to complete (callback-based)
-async fn wait_for_batch_completion_docker(
+/// Wait for a batch of locally executed tasks to complete (callback-based)
+///
+/// Conflicting PRs are rebased onto the parent branch by `local_executor`.
+#[allow(clippy::too_many_arguments)]
+async fn wait_for_batch_completion_docker<V: VcsProvider + ?Sized>(
     task_results: Vec<(Task, TaskResult)>,
     repository: &Repository,
-    github_client: &Arc<GitHubClient>,
+    vcs: &Arc<V>,
+    local_executor: &Arc<dyn LocalExecutor>,
+    notifier: &Arc<CompletionNotifier>,
     auto_approve: bool,
+    parent_branch: &str,
+    merge_gate: &MergeGate,
 ) -> Result<()> {
     for (task, result) in task_results {
         if !result.success {
@@ -370,90 +1211,154 @@ async fn wait_for_batch_completion_docker(
             if auto_approve {
                 tracing::info!("Auto-approving PR #{} for task: {}", pr_num, task.title);
 
-                match github_client.merge_pull_request(repository, pr_num).await {
-                    Ok(_) => {
+                match merge_subtask_pr(
+                    &task,
+                    pr_num,
+                    repository,
+                    parent_branch,
+                    vcs,
+                    merge_gate,
+                    Some(local_executor),
+                ).await {
+                    Ok(MergeOutcome::Merged) => {
                         tracing::info!("✓ PR #{} auto-merged successfully for task: {}", pr_num, task.title);
+                        continue;
+                    }
+                    Ok(MergeOutcome::Blocked(blocker)) => {
+                        tracing::warn!("PR #{} cannot be auto-merged ({})", pr_num, blocker);
                     }
                     Err(e) => {
                         tracing::error!("Failed to auto-merge PR #{}: {}", pr_num, e);
                         return Err(anyhow::anyhow!("Failed to auto-merge PR #{}: {}", pr_num, e));
                     }
                 }
-            } else {
-                // Wait for manual merge
-                tracing::info!("Waiting for manual merge of PR #{} for task: {}", pr_num, task.title);
-
-                for _ in 0..20 {  // Max 10 minutes
-                    tokio::time::sleep(Duration::from_secs(30)).await;
-
-                    match github_client.is_pr_merged(repository, pr_num).await {
-                        Ok(true) => {
-                            tracing::info!("✓ PR #{} manually merged for task: {}", pr_num, task.title);
-                            break;
-                        }
-                        Ok(false) => {
-                            // Still waiting
-                        }
-                        Err(e) => {
-                            tracing::warn!("Error checking PR merge status: {}", e);
-                        }
-                    }
-                }
-
-                // Verify merge completed
-                match github_client.is_pr_merged(repository, pr_num).await {
-                    Ok(true) => {
-                        tracing::info!("PR #{} merge confirmed", pr_num);
-                    }
-                    Ok(false) => {
-                        return Err(anyhow::anyhow!(
-                            "PR #{} was not merged within timeout period for task: {}",
-                            pr_num,
-                            task.title
-                        ));
-                    }
-                    Err(e) => {
-                        return Err(anyhow::anyhow!(
-                            "Failed to verify PR #{} merge status: {}",
-                            pr_num,
-                            e
-                        ));
-                    }
-                }
             }
+
+            // Wait for manual merge
+            tracing::info!("Waiting for manual merge of PR #{} for task: {}", pr_num, task.title);
+            notify_pr_awaiting_review(&task, repository, vcs, pr_num).await;
+
+            let mut rx = notifier.register(&task.id, repository, None);
+            let merged = wait_for_pr_merge(
+                &task,
+                pr_num,
+                &mut rx,
+                repository,
+                vcs,
+                notifier.poll_interval(),
+            ).await;
+            notifier.unregister(&task.id);
+            merged?;
         }
     }
 
     Ok(())
 }
 
-/// Execute a composite task using Docker executor (batch-based)
-pub async fn execute_composite_task_docker(
+/// Execute a composite task using the local executor (batch-based)
+///
+/// On failure the composite task is rolled back when AUTODEV_ROLLBACK_ON_FAILURE is set.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_composite_task_docker<V: VcsProvider + ?Sized>(
+    composite_task: &CompositeTask,
+    repository: &Repository,
+    local_executor: &Arc<dyn LocalExecutor>,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    ai_agent: &Arc<dyn AIAgent>,
+    db: &Option<Arc<Database>>,
+    notifier: &Arc<CompletionNotifier>,
+) -> Result<()> {
+    let result = execute_composite_batches_docker(
+        composite_task,
+        repository,
+        local_executor,
+        engine,
+        vcs,
+        ai_agent,
+        db,
+        notifier,
+    ).await;
+
+    finish_composite_task(&result, composite_task, repository, engine, vcs, db).await;
+
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn execute_composite_batches_docker<V: VcsProvider + ?Sized>(
     composite_task: &CompositeTask,
     repository: &Repository,
-    docker_executor: &Arc<DockerExecutor>,
+    local_executor: &Arc<dyn LocalExecutor>,
     engine: &Arc<AutoDevEngine>,
-    github_client: &Arc<GitHubClient>,
+    vcs: &Arc<V>,
+    ai_agent: &Arc<dyn AIAgent>,
     db: &Option<Arc<Database>>,
+    notifier: &Arc<CompletionNotifier>,
 ) -> Result<()> {
     tracing::info!(
-        "Executing composite task with Docker: {} ({}) with {} subtasks",
+        "Executing composite task locally: {} ({}) with {} subtasks",
         composite_task.title,
         composite_task.id,
         composite_task.subtasks.len()
     );
 
+    let config = vcs.load_repo_config(repository).await?;
+    let composite_task = &with_repo_defaults(composite_task, &config);
+
     // Create parent branch for composite task
     let parent_branch = format!("autodev/{}", composite_task.id);
     tracing::info!("Creating parent branch: {}", parent_branch);
 
-    if let Err(e) = github_client.create_branch(repository, &parent_branch, "main").await {
+    let base_branch = composite_task.base_branch.as_deref().unwrap_or(config.default_branch());
+    if let Err(e) = vcs.create_branch(repository, &parent_branch, base_branch).await {
         tracing::warn!("Failed to create parent branch (may already exist): {}", e);
     }
+    checks::report_composite_check(vcs, repository, &composite_task.id, &checks::decomposed(composite_task)).await;
 
     let batches = composite_task.get_parallel_batches();
+    let limiter = TaskLimiter::for_composite(composite_task);
+    let merge_gate = MergeGate::new(composite_task.merge_method.unwrap_or_default(), &config)
+        .with_review(ReviewGate::from_config(ai_agent.clone(), &config));
 
     for (i, batch) in batches.iter().enumerate() {
+        if is_composite_cancelled(&composite_task.id, engine, db).await {
+            return Err(anyhow::anyhow!("Composite task {} was cancelled", composite_task.id));
+        }
+
+        let batch = unfinished_tasks(batch, engine).await;
+        if batch.is_empty() {
+            tracing::info!("Batch {}/{} already completed or skipped", i + 1, batches.len());
+            continue;
+        }
+
+        if engine.get_composite_task(&composite_task.id).await.is_some_and(|c| c.is_paused()) {
+            checks::report_composite_check(vcs, repository, &composite_task.id, &checks::paused(composite_task, i + 1)).await;
+        }
+        cancellation::unless_cancelled(
+            &composite_task.id,
+            engine,
+            db,
+            notifier.poll_interval(),
+            wait_while_paused(&composite_task.id, engine, db, notifier.poll_interval()),
+        ).await?;
+
+        if !composite_task.auto_approve {
+            checks::report_composite_check(
+                vcs,
+                repository,
+                &composite_task.id,
+                &checks::awaiting_approval(composite_task, i + 1),
+            ).await;
+            cancellation::unless_cancelled(
+                &composite_task.id,
+                engine,
+                db,
+                notifier.poll_interval(),
+                wait_for_batch_approval(&composite_task.id, i + 1, engine, db, notifier.poll_interval()),
+            ).await?;
+        }
+
         tracing::info!(
             "Processing batch {}/{}: {} tasks",
             i + 1,
@@ -465,57 +1370,356 @@ pub async fn execute_composite_task_docker(
         let mut handles = Vec::new();
 
         for task in batch {
-            let task = task.clone();
+            let task_id = task.id.clone();
             let repository = repository.clone();
-            let docker_executor = docker_executor.clone();
+            let local_executor = local_executor.clone();
             let engine = engine.clone();
             let db = db.clone();
             let parent_branch_clone = parent_branch.clone();
             let composite_id = composite_task.id.clone();
+            let limiter = limiter.clone();
+            let poll_interval = notifier.poll_interval();
 
             let handle = tokio::spawn(async move {
-                let result = execute_simple_task_docker(
-                    &task,
-                    &repository,
-                    &docker_executor,
-                    &engine,
-                    &db,
-                    Some(&parent_branch_clone),
-                    Some(&composite_id),
-                ).await?;
+                let run = async {
+                    wait_while_paused(&composite_id, &engine, &db, poll_interval).await?;
+                    let _permit = limiter.acquire().await;
+                    execute_simple_task_docker(
+                        &task,
+                        &repository,
+                        &local_executor,
+                        &engine,
+                        &db,
+                        Some(&parent_branch_clone),
+                        Some(&composite_id),
+                    ).await
+                };
+                let result = cancellation::unless_cancelled(&composite_id, &engine, &db, poll_interval, run).await?;
                 Ok::<(Task, TaskResult), anyhow::Error>((task, result))
             });
 
-            handles.push(handle);
+            handles.push((task_id, handle));
         }
 
         // Collect results
         let mut task_results = Vec::new();
-        for handle in handles {
+        let mut failures = Vec::new();
+        for (task_id, handle) in handles {
             match handle.await {
+                Ok(Ok((task, result))) if !result.success => {
+                    let error = result.error.unwrap_or_else(|| "Unknown error".to_string());
+                    tracing::error!("Task failed: {} - {}", task.title, error);
+                    failures.push((task.id, anyhow::anyhow!("Task {} failed: {}", task_id, error)));
+                }
                 Ok(Ok((task, result))) => {
                     tracing::info!("Task completed: {} - success: {}", task.title, result.success);
                     task_results.push((task, result));
                 }
                 Ok(Err(e)) => {
                     tracing::error!("Failed to execute task: {}", e);
-                    return Err(e);
+                    failures.push((task_id, e));
                 }
                 Err(e) => {
                     tracing::error!("Task execution panicked: {}", e);
-                    return Err(anyhow::anyhow!("Task execution panicked: {}", e));
+                    failures.push((task_id, anyhow::anyhow!("Task execution panicked: {}", e)));
                 }
             }
         }
 
+        apply_failure_policy(composite_task, failures, engine).await?;
+
         tracing::info!("Batch {}/{} tasks completed", i + 1, batches.len());
 
-        // Wait for all PRs in this batch to be merged
-        wait_for_batch_completion_docker(task_results, repository, github_client, composite_task.auto_approve).await?;
+        // Merge the batch into the parent branch, locally or through its PRs
+        if local_orchestrator::merges_locally(composite_task, &merge_gate) {
+            cancellation::unless_cancelled(
+                &composite_task.id,
+                engine,
+                db,
+                notifier.poll_interval(),
+                local_orchestrator::merge_batch_locally(
+                    &task_results,
+                    repository,
+                    &parent_branch,
+                    vcs,
+                    local_executor,
+                    engine,
+                    db,
+                ),
+            ).await?;
+        } else {
+            cancellation::unless_cancelled(
+                &composite_task.id,
+                engine,
+                db,
+                notifier.poll_interval(),
+                wait_for_batch_completion_docker(
+                    task_results,
+                    repository,
+                    vcs,
+                    local_executor,
+                    notifier,
+                    composite_task.auto_approve,
+                    &parent_branch,
+                    &merge_gate,
+                ),
+            ).await?;
+        }
 
         tracing::info!("Batch {}/{} completed and merged", i + 1, batches.len());
+
+        if let Some(workflow_file) = &config.test_workflow {
+            verify_batch(
+                composite_task,
+                i + 1,
+                workflow_file,
+                repository,
+                engine,
+                vcs,
+                db,
+                notifier.poll_interval(),
+            ).await?;
+        }
+
+        let merged = completed_subtasks(composite_task, engine).await;
+        checks::report_composite_check(
+            vcs,
+            repository,
+            &composite_task.id,
+            &checks::subtasks_merged(composite_task, merged),
+        ).await;
     }
 
     tracing::info!("Composite task execution completed: {}", composite_task.title);
+    local_orchestrator::open_draft_pr(composite_task, repository, base_branch, &config, vcs, ai_agent).await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_repo_defaults() {
+        let composite = CompositeTask::new("C".to_string(), "".to_string(), vec![])
+            .with_max_parallel_tasks(Some(4));
+        let config = RepoConfig {
+            max_parallel_tasks: Some(2),
+            auto_approve: Some(true),
+            merge_method: Some(MergeMethod::Squash),
+            ..Default::default()
+        };
+
+        // The task's own limit wins; its auto-approve is left alone
+        let applied = with_repo_defaults(&composite, &config);
+        assert_eq!(applied.max_parallel_tasks, Some(4));
+        assert!(!applied.auto_approve);
+        assert_eq!(applied.merge_method, Some(MergeMethod::Squash));
+
+        let rebase = composite.clone().with_merge_method(Some(MergeMethod::Rebase));
+        assert_eq!(with_repo_defaults(&rebase, &config).merge_method, Some(MergeMethod::Rebase));
+
+        let applied = with_repo_defaults(&composite.with_max_parallel_tasks(None), &config);
+        assert_eq!(applied.max_parallel_tasks, Some(2));
+    }
+
+    #[test]
+    fn test_repo_auto_approve() {
+        let opted_in = RepoConfig {
+            auto_approve: Some(true),
+            ..Default::default()
+        };
+
+        // An explicit choice wins over the repository's default
+        assert!(!repo_auto_approve(Some(false), &opted_in));
+        assert!(repo_auto_approve(None, &opted_in));
+        assert!(repo_auto_approve(Some(true), &RepoConfig::default()));
+        assert!(!repo_auto_approve(None, &RepoConfig::default()));
+    }
+
+    #[tokio::test]
+    async fn test_resumed_batches_skip_completed_tasks() {
+        let engine = Arc::new(AutoDevEngine::new());
+
+        let first = Task::new("First".to_string(), "".to_string(), "".to_string());
+        let second = Task::new("Second".to_string(), "".to_string(), "".to_string());
+        let composite = engine
+            .create_composite_task("Resume".to_string(), "".to_string(), vec![first, second], true)
+            .await
+            .unwrap();
+        let batch = &composite.get_parallel_batches()[0];
+        assert_eq!(unfinished_tasks(batch, &engine).await.len(), 2);
+
+        let first_id = composite.subtasks[0].id.clone();
+        engine.update_task_status(&first_id, TaskStatus::InProgress, None).await.unwrap();
+        engine.update_task_status(&first_id, TaskStatus::Completed, None).await.unwrap();
+        let resumed = engine
+            .resume_composite_task(&composite.id, &[first_id].into())
+            .await
+            .unwrap();
+
+        // The composite's own copy is stale; the engine's task state decides
+        let unfinished = unfinished_tasks(batch, &engine).await;
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!(unfinished[0].id, resumed.subtasks[1].id);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_batch_approval() {
+        let engine = Arc::new(AutoDevEngine::new());
+
+        let first = Task::new("First".to_string(), "".to_string(), "".to_string());
+        let second = Task::new("Second".to_string(), "".to_string(), "".to_string())
+            .with_dependencies(vec![first.id.clone()]);
+        let composite = engine
+            .create_composite_task("Gated".to_string(), "".to_string(), vec![first, second], false)
+            .await
+            .unwrap();
+
+        // The first batch never waits
+        wait_for_batch_approval(&composite.id, 1, &engine, &None, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let approver = engine.clone();
+        let composite_id = composite.id.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            approver.approve_batch(&composite_id, 2).await.unwrap();
+        });
+
+        // Woken by the approval event well before the poll interval
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            wait_for_batch_approval(&composite.id, 2, &engine, &None, Duration::from_secs(60)),
+        )
+        .await
+        .expect("approval event should end the wait")
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_while_paused() {
+        let engine = Arc::new(AutoDevEngine::new());
+
+        let only = Task::new("Only".to_string(), "".to_string(), "".to_string());
+        let composite = engine
+            .create_composite_task("Freeze".to_string(), "".to_string(), vec![only], true)
+            .await
+            .unwrap();
+
+        // Not paused: no wait
+        wait_while_paused(&composite.id, &engine, &None, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        engine.pause_composite_task(&composite.id).await.unwrap();
+        let resumer = engine.clone();
+        let composite_id = composite.id.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            resumer.unpause_composite_task(&composite_id).await.unwrap();
+        });
+
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            wait_while_paused(&composite.id, &engine, &None, Duration::from_millis(10)),
+        )
+        .await
+        .expect("resuming should end the wait")
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_and_cancel_simple_task() {
+        let engine = Arc::new(AutoDevEngine::new());
+        let github = Arc::new(autodev_github::MockGitHubClient::new());
+        let repository = Repository::new("o".to_string(), "r".to_string());
+        github.add_branch(&repository, "main");
+
+        let task = engine
+            .create_simple_task("Add login".to_string(), "".to_string(), "Add a login page".to_string())
+            .await
+            .unwrap();
+        let run_id = execute_simple_task(&task, &repository, &engine, &github, &None, None, None)
+            .await
+            .unwrap();
+
+        // The workflow runs on the task's own branch, cut from the default branch
+        let branch = format!("autodev/{}", task.id);
+        assert_eq!(github.branch_base(&repository, &branch).as_deref(), Some("main"));
+        let runs = github.workflow_runs(&repository);
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].git_ref, branch);
+        assert_eq!(runs[0].inputs.get("task_id"), Some(&task.id));
+        assert_eq!(engine.get_task(&task.id).await.unwrap().status, TaskStatus::InProgress);
+
+        cancel_task(&task.id, &repository, &engine, &github, &None, None).await.unwrap();
+        let status = github.pipeline_status(&repository, run_id).await.unwrap();
+        assert_eq!(status.conclusion.as_deref(), Some("cancelled"));
+        assert_eq!(engine.get_task(&task.id).await.unwrap().status, TaskStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_execute_simple_task_dispatch_failure() {
+        let engine = Arc::new(AutoDevEngine::new());
+        let github = Arc::new(autodev_github::MockGitHubClient::new());
+        let repository = Repository::new("o".to_string(), "r".to_string());
+        github.add_branch(&repository, "main");
+        github.fail("trigger_pipeline", "Workflow does not have 'workflow_dispatch' trigger");
+
+        let task = engine
+            .create_simple_task("Add login".to_string(), "".to_string(), "Add a login page".to_string())
+            .await
+            .unwrap();
+        let result = execute_simple_task(&task, &repository, &engine, &github, &None, None, None).await;
+
+        assert!(result.is_err());
+        assert!(github.workflow_runs(&repository).is_empty());
+        assert_eq!(engine.get_task(&task.id).await.unwrap().workflow_run_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_workflow_conclusions_and_timeout() {
+        let engine = Arc::new(AutoDevEngine::new());
+        let github = Arc::new(autodev_github::MockGitHubClient::new());
+        let repository = Repository::new("o".to_string(), "r".to_string());
+        github.add_branch(&repository, "main");
+        let notifier = CompletionNotifier::new(Duration::from_millis(10));
+
+        let task = engine
+            .create_simple_task("Add login".to_string(), "".to_string(), "Add a login page".to_string())
+            .await
+            .unwrap();
+        let run_id = execute_simple_task(&task, &repository, &engine, &github, &None, None, None)
+            .await
+            .unwrap();
+        let mut rx = notifier.register(&task.id, &repository, Some(run_id));
+
+        // A run that never finishes is cancelled once the timeout passes
+        let error = wait_for_workflow(
+            &task, run_id, &mut rx, &notifier, &repository, &engine, &github, Duration::from_millis(50),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("did not finish"), "{}", error);
+        assert!(github.calls().contains(&format!("cancel_pipeline:{}", run_id)));
+
+        // Conclusions other than success fail the task
+        notifier.workflow_completed(&task.id, "action_required", None);
+        let error = wait_for_workflow(
+            &task, run_id, &mut rx, &notifier, &repository, &engine, &github, Duration::from_secs(5),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.to_string().contains("action_required"), "{}", error);
+
+        // The signal was consumed: the next wait polls the run instead
+        github.complete_run(&repository, run_id, "success");
+        wait_for_workflow(
+            &task, run_id, &mut rx, &notifier, &repository, &engine, &github, Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+    }
+}
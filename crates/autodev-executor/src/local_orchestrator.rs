@@ -0,0 +1,157 @@
+//! Composite tasks run entirely by the local executor
+//!
+//! With `AUTODEV_LOCAL_EXECUTOR` set, each batch runs in local containers and
+//! its branches are merged into the parent branch in a local checkout instead
+//! of through the provider's merge API; only the final draft PR goes through
+//! the provider. Local merges make merge commits and skip the merge gate, so
+//! they are used only for auto-approved batches merged with `merge` and no AI
+//! review. Other composite tasks merge their subtask PRs as before.
+
+use anyhow::Result;
+use std::sync::Arc;
+
+use autodev_ai::AIAgent;
+use autodev_core::{AutoDevEngine, CompositeTask, MergeMethod, Task};
+use autodev_db::Database;
+use autodev_github::{RepoConfig, Repository, VcsProvider};
+use autodev_local_executor::{LocalExecutor, LocalExecutorError, TaskResult};
+use autodev_notify::NotificationEvent;
+
+use crate::conflicts::resolve_prompt;
+use crate::merge::MergeGate;
+use crate::{composite_pr, describe_merge_request, log_execution, pr_descriptions_enabled, scan_composite_pr};
+
+/// Whether the batches of `composite_task` are merged in a local checkout
+pub fn merges_locally(composite_task: &CompositeTask, gate: &MergeGate) -> bool {
+    composite_task.auto_approve && gate.method == MergeMethod::Merge && gate.review.is_none()
+}
+
+/// Merge the branches of a finished batch into `parent_branch` locally
+///
+/// A branch that conflicts is rebased onto the parent branch by the agent and
+/// merged once more; a conflict after that fails the batch.
+pub async fn merge_batch_locally<V: VcsProvider + ?Sized>(
+    task_results: &[(Task, TaskResult)],
+    repository: &Repository,
+    parent_branch: &str,
+    vcs: &Arc<V>,
+    local_executor: &Arc<dyn LocalExecutor>,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+) -> Result<()> {
+    for (task, result) in task_results {
+        if !result.success {
+            return Err(anyhow::anyhow!(
+                "Task {} failed: {}",
+                task.id,
+                result.error.clone().unwrap_or_else(|| "Unknown error".to_string())
+            ));
+        }
+
+        let Some(pr_num) = result.pr_number else {
+            continue;
+        };
+        let branch = vcs.merge_request_branch(repository, pr_num).await?;
+
+        let head = match local_executor.merge_branch(&task.id, repository, parent_branch, &branch).await {
+            Err(e) if matches!(e.downcast_ref(), Some(LocalExecutorError::MergeConflict { .. })) => {
+                tracing::warn!("{}; rebasing {} onto {}", e, branch, parent_branch);
+
+                let resolved = local_executor
+                    .resolve_conflicts(
+                        &format!("resolve-{}", task.id),
+                        repository,
+                        &branch,
+                        parent_branch,
+                        &task.title,
+                        &resolve_prompt(task, parent_branch),
+                    )
+                    .await?;
+                if !resolved.success {
+                    return Err(anyhow::anyhow!(
+                        "Failed to resolve conflicts of {}: {}",
+                        branch,
+                        resolved.error.unwrap_or_else(|| "Unknown error".to_string())
+                    ));
+                }
+
+                local_executor.merge_branch(&task.id, repository, parent_branch, &branch).await?
+            }
+            merged => merged?,
+        };
+
+        tracing::info!("✓ Merged {} (PR #{}) into {} at {}", branch, pr_num, parent_branch, head);
+        let message = format!("Merged {} into {} locally at {}", branch, parent_branch, head);
+        if let Err(e) = log_execution(engine, db, &task.id, "MERGED", &message).await {
+            tracing::warn!("Failed to log local merge of task {}: {}", task.id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Open the draft PR of a completed composite task, then describe and scan it
+///
+/// Failures are logged; the subtasks are already merged into the parent branch.
+pub async fn open_draft_pr<V: VcsProvider + ?Sized>(
+    composite_task: &CompositeTask,
+    repository: &Repository,
+    base_branch: &str,
+    config: &RepoConfig,
+    vcs: &Arc<V>,
+    ai_agent: &Arc<dyn AIAgent>,
+) {
+    let pr = match composite_pr::open_draft_pr(composite_task, repository, base_branch, vcs).await {
+        Ok(pr) => pr,
+        Err(e) => {
+            tracing::error!("Failed to create DRAFT PR for composite task {}: {}", composite_task.id, e);
+            return;
+        }
+    };
+
+    if pr_descriptions_enabled() {
+        if let Err(e) = describe_merge_request(
+            ai_agent.clone(),
+            vcs,
+            repository,
+            pr.number,
+            &composite_task.title,
+            &composite_task.description,
+            &composite_pr::footer(composite_task),
+        ).await {
+            tracing::warn!("Keeping the generated body of PR #{}: {}", pr.number, e);
+        }
+    }
+
+    if config.security_scan {
+        if let Err(e) = scan_composite_pr(ai_agent, vcs, repository, composite_task, pr.number).await {
+            tracing::error!("Security scan of PR #{} failed: {}", pr.number, e);
+        }
+    }
+
+    if let Some(pr_url) = pr.url {
+        autodev_notify::notify(NotificationEvent::PrAwaitingReview {
+            task_id: composite_task.id.clone(),
+            title: composite_task.title.clone(),
+            pr_url,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merges_locally_only_auto_approved_merge_commits() {
+        let gate = |method| MergeGate::new(method, &RepoConfig::default());
+        let mut composite = CompositeTask::new("C".to_string(), "".to_string(), vec![]);
+
+        assert!(!merges_locally(&composite, &gate(MergeMethod::Merge)));
+
+        composite.auto_approve = true;
+        assert!(merges_locally(&composite, &gate(MergeMethod::Merge)));
+        assert!(!merges_locally(&composite, &gate(MergeMethod::Squash)));
+        assert!(!merges_locally(&composite, &gate(MergeMethod::Rebase)));
+    }
+}
@@ -0,0 +1,37 @@
+//! Live container output of locally executed tasks
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use autodev_core::{AutoDevEngine, TaskEvent};
+use autodev_db::Database;
+use autodev_local_executor::LogSink;
+
+/// Stores container output in `task_logs` and publishes it as `output` events
+pub struct TaskLogSink {
+    engine: Arc<AutoDevEngine>,
+    db: Option<Arc<Database>>,
+}
+
+impl TaskLogSink {
+    pub fn new(engine: Arc<AutoDevEngine>, db: Option<Arc<Database>>) -> Self {
+        Self { engine, db }
+    }
+}
+
+#[async_trait]
+impl LogSink for TaskLogSink {
+    async fn write_lines(&self, id: &str, lines: Vec<String>) {
+        if let Some(db) = &self.db {
+            if let Err(e) = db.add_task_logs(id, &lines).await {
+                tracing::warn!("Failed to store {} log lines of {}: {}", lines.len(), id, e);
+            }
+        }
+
+        self.engine.publish(TaskEvent::Output {
+            task_id: id.to_string(),
+            lines,
+            timestamp: chrono::Utc::now(),
+        });
+    }
+}
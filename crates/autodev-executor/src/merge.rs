@@ -0,0 +1,313 @@
+//! Auto-merge of subtask PRs behind branch protection
+//!
+//! A PR whose required checks are still running or that waits for a review is
+//! polled until it becomes mergeable or the repository's merge timeout passes.
+//! Drafts are marked ready and the configured reviewers requested as soon as a
+//! review is what blocks the merge. The branch of a merged PR is deleted.
+
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+
+use autodev_core::MergeMethod;
+use autodev_github::{MergeBlocker, MergeOptions, RepoConfig, Repository, VcsProvider};
+
+use crate::review_gate::ReviewGate;
+
+/// How often a blocked PR is checked again
+pub const MERGEABILITY_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How subtask PRs of a repository are auto-merged
+#[derive(Debug, Clone)]
+pub struct MergeGate {
+    pub method: MergeMethod,
+    /// How long to wait for checks and reviews before giving up on the auto-merge
+    pub timeout: Duration,
+    pub poll_interval: Duration,
+    /// Requested when a review blocks the merge
+    pub reviewers: Vec<String>,
+    /// AI review run on a subtask PR before it is merged
+    pub review: Option<ReviewGate>,
+}
+
+impl MergeGate {
+    pub fn new(method: MergeMethod, config: &RepoConfig) -> Self {
+        Self {
+            method,
+            timeout: config.merge_timeout(),
+            poll_interval: MERGEABILITY_POLL_INTERVAL,
+            reviewers: config.reviewers.clone(),
+            review: None,
+        }
+    }
+
+    pub fn with_review(mut self, review: Option<ReviewGate>) -> Self {
+        self.review = review;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    Merged,
+    /// Left open; it needs a manual merge once the blocker is resolved
+    Blocked(MergeBlocker),
+}
+
+/// Merge the PR if it is mergeable now; otherwise hand it to reviewers when a
+/// review blocks it and report what does
+pub async fn try_merge<V: VcsProvider + ?Sized>(
+    repository: &Repository,
+    pr_number: u64,
+    vcs: &Arc<V>,
+    options: &MergeOptions,
+    gate: &MergeGate,
+) -> Result<MergeOutcome> {
+    attempt_merge(repository, pr_number, vcs, options, gate, &mut false).await
+}
+
+/// Merge the PR once branch protection allows it
+///
+/// Returns `Blocked` right away for blockers waiting can't resolve (conflicts,
+/// failing checks, requested changes) and after `gate.timeout` otherwise.
+pub async fn merge_when_ready<V: VcsProvider + ?Sized>(
+    repository: &Repository,
+    pr_number: u64,
+    vcs: &Arc<V>,
+    options: &MergeOptions,
+    gate: &MergeGate,
+) -> Result<MergeOutcome> {
+    let deadline = tokio::time::Instant::now() + gate.timeout;
+    let mut review_requested = false;
+
+    loop {
+        let outcome = attempt_merge(repository, pr_number, vcs, options, gate, &mut review_requested).await?;
+
+        let MergeOutcome::Blocked(blocker) = &outcome else {
+            return Ok(outcome);
+        };
+        if blocker.is_terminal() || tokio::time::Instant::now() + gate.poll_interval > deadline {
+            return Ok(outcome);
+        }
+
+        tracing::info!(
+            "PR #{} cannot be merged yet ({}), checking again in {:?}",
+            pr_number,
+            blocker,
+            gate.poll_interval
+        );
+        tokio::time::sleep(gate.poll_interval).await;
+    }
+}
+
+async fn attempt_merge<V: VcsProvider + ?Sized>(
+    repository: &Repository,
+    pr_number: u64,
+    vcs: &Arc<V>,
+    options: &MergeOptions,
+    gate: &MergeGate,
+    review_requested: &mut bool,
+) -> Result<MergeOutcome> {
+    let blocker = match vcs.merge_request_mergeability(repository, pr_number).await {
+        Ok(state) if state.merged => {
+            tracing::info!("PR #{} is already merged", pr_number);
+            return Ok(MergeOutcome::Merged);
+        }
+        Ok(state) => state.blocker(),
+        Err(e) => {
+            tracing::warn!("Failed to check whether PR #{} is mergeable: {}", pr_number, e);
+            Some(MergeBlocker::Unknown(e.to_string()))
+        }
+    };
+
+    let Some(blocker) = blocker else {
+        vcs.merge_merge_request(repository, pr_number, options).await?;
+        crate::branches::delete_merged_branch(repository, pr_number, vcs).await;
+        return Ok(MergeOutcome::Merged);
+    };
+
+    if blocker.needs_review() && !*review_requested {
+        request_review(repository, pr_number, vcs, gate, &blocker).await;
+        *review_requested = true;
+    }
+
+    Ok(MergeOutcome::Blocked(blocker))
+}
+
+/// Make a PR blocked on review visible to reviewers
+async fn request_review<V: VcsProvider + ?Sized>(
+    repository: &Repository,
+    pr_number: u64,
+    vcs: &Arc<V>,
+    gate: &MergeGate,
+    blocker: &MergeBlocker,
+) {
+    if *blocker == MergeBlocker::Draft {
+        if let Err(e) = vcs.mark_ready_for_review(repository, pr_number).await {
+            tracing::warn!("Failed to mark PR #{} ready for review: {}", pr_number, e);
+        }
+    }
+
+    if !gate.reviewers.is_empty() {
+        if let Err(e) = vcs.request_reviewers(repository, pr_number, &gate.reviewers).await {
+            tracing::warn!("Failed to request reviewers for PR #{}: {}", pr_number, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autodev_github::{Mergeability, MockGitHubClient};
+
+    /// A pull request of `autodev/task-1` into main
+    async fn pull_request(github: &MockGitHubClient, repo: &Repository) -> u64 {
+        github.add_branch(repo, "main");
+        github.add_branch(repo, "autodev/task-1");
+        github
+            .create_merge_request(repo, "Task".to_string(), String::new(), "autodev/task-1".to_string(), "main".to_string(), false)
+            .await
+            .unwrap()
+            .number
+    }
+
+    /// Calls that change the pull request or its branch
+    fn actions(github: &MockGitHubClient) -> Vec<String> {
+        github
+            .calls()
+            .into_iter()
+            .filter(|call| {
+                ["mark_ready_for_review", "request_reviewers", "merge_merge_request", "delete_branch", "submit_review"]
+                    .iter()
+                    .any(|op| call.starts_with(&format!("{}:", op)))
+            })
+            .collect()
+    }
+
+    fn state(mergeable_state: &str, draft: bool) -> Mergeability {
+        Mergeability {
+            mergeable: Some(true),
+            mergeable_state: mergeable_state.to_string(),
+            draft,
+            ..Default::default()
+        }
+    }
+
+    fn gate() -> MergeGate {
+        MergeGate {
+            timeout: Duration::from_secs(1),
+            poll_interval: Duration::from_millis(10),
+            reviewers: vec!["alice".to_string()],
+            ..MergeGate::new(MergeMethod::Squash, &RepoConfig::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_draft_is_handed_to_reviewers_then_merged() {
+        let github = Arc::new(MockGitHubClient::new());
+        let repo = Repository::new("o".to_string(), "r".to_string());
+        let pr = pull_request(&github, &repo).await;
+        github.queue_mergeability(&repo, pr, vec![state("draft", true), state("blocked", false), state("clean", false)]);
+
+        let outcome = merge_when_ready(&repo, pr, &github, &MergeOptions::default(), &gate()).await.unwrap();
+
+        assert_eq!(outcome, MergeOutcome::Merged);
+        assert_eq!(
+            actions(&github),
+            vec![
+                format!("mark_ready_for_review:{}", pr),
+                format!("request_reviewers:{}", pr),
+                format!("merge_merge_request:{}", pr),
+                "delete_branch:autodev/task-1".to_string(),
+            ]
+        );
+        assert_eq!(github.pull_request(&repo, pr).unwrap().reviewers, vec!["alice"]);
+    }
+
+    #[tokio::test]
+    async fn test_blocked_merge_times_out_or_stops() {
+        let github = Arc::new(MockGitHubClient::new());
+        let repo = Repository::new("o".to_string(), "r".to_string());
+        let pr = pull_request(&github, &repo).await;
+        github.queue_mergeability(
+            &repo,
+            pr,
+            vec![Mergeability {
+                required_approvals: 1,
+                ..state("blocked", false)
+            }],
+        );
+
+        let outcome = merge_when_ready(&repo, pr, &github, &MergeOptions::default(), &gate()).await.unwrap();
+        assert_eq!(
+            outcome,
+            MergeOutcome::Blocked(MergeBlocker::ReviewRequired { approvals: 0, required: 1 })
+        );
+        // 리뷰어는 한 번만 요청
+        assert_eq!(actions(&github), vec![format!("request_reviewers:{}", pr)]);
+
+        let github = Arc::new(MockGitHubClient::new());
+        let pr = pull_request(&github, &repo).await;
+        github.queue_mergeability(&repo, pr, vec![state("dirty", false)]);
+        let outcome = try_merge(&repo, pr, &github, &MergeOptions::default(), &gate()).await.unwrap();
+        assert_eq!(outcome, MergeOutcome::Blocked(MergeBlocker::Conflicts));
+        assert!(actions(&github).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_review_gate_holds_merge() {
+        let reply = serde_json::json!({
+            "message": {
+                "content": serde_json::json!({
+                    "summary": "unsafe 블록이 추가되었습니다",
+                    "findings": [{ "severity": "high", "file": "src/auth.rs", "line": 1, "message": "불필요한 unsafe" }]
+                })
+                .to_string()
+            }
+        });
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/chat")
+            .with_status(200)
+            .with_body(reply.to_string())
+            .create_async()
+            .await;
+
+        let agent: Arc<dyn autodev_ai::AIAgent> = Arc::new(autodev_ai::OllamaAgent::new(server.url(), "llama3.1"));
+        let repo = Repository::new("o".to_string(), "r".to_string());
+        let mut task = autodev_core::Task::new("Auth".to_string(), String::new(), String::new());
+        task.id = "task-1".to_string();
+        let review_gate = |threshold| {
+            gate().with_review(Some(ReviewGate { agent: agent.clone(), threshold }))
+        };
+        let github_with_diff = || async {
+            let github = Arc::new(MockGitHubClient::new());
+            let pr = pull_request(&github, &repo).await;
+            github.update_pull_request(&repo, pr, |pr| {
+                pr.diff = "diff --git a/src/auth.rs b/src/auth.rs\n+unsafe {}\n".to_string();
+            });
+            (github, pr)
+        };
+
+        let (github, pr) = github_with_diff().await;
+        let gate = review_gate(autodev_core::ReviewSeverity::High);
+        let outcome = crate::merge_subtask_pr(&task, pr, &repo, "autodev/c", &github, &gate, None).await.unwrap();
+        assert!(matches!(outcome, MergeOutcome::Blocked(MergeBlocker::ReviewFindings(_))));
+        assert_eq!(actions(&github), vec![format!("submit_review:{}", pr)]);
+        assert!(github.pull_request(&repo, pr).unwrap().reviews[0].contains("on hold"));
+
+        let (github, pr) = github_with_diff().await;
+        let gate = review_gate(autodev_core::ReviewSeverity::Critical);
+        let outcome = crate::merge_subtask_pr(&task, pr, &repo, "autodev/c", &github, &gate, None).await.unwrap();
+        assert_eq!(outcome, MergeOutcome::Merged);
+        assert_eq!(
+            actions(&github),
+            vec![
+                format!("submit_review:{}", pr),
+                format!("merge_merge_request:{}", pr),
+                "delete_branch:autodev/task-1".to_string(),
+            ]
+        );
+        assert!(!github.pull_request(&repo, pr).unwrap().reviews[0].contains("on hold"));
+    }
+}
@@ -0,0 +1,116 @@
+//! Execution metrics of finished runs
+//!
+//! Every run of a task adds a `metrics` row with how long it took and how big
+//! its change was; AI tokens are added separately by
+//! [`crate::run_within_budget`]. Readers sum the rows of a task. Saving never
+//! fails the run: errors are only logged.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use autodev_core::Task;
+use autodev_db::{Database, NewMetrics};
+use autodev_github::{DiffStat, Repository, VcsProvider};
+use autodev_local_executor::TaskResult;
+
+/// Metrics row for a run that took `elapsed` and changed `stat`
+pub fn execution_metrics(elapsed: Duration, stat: DiffStat) -> NewMetrics {
+    let count = |n: u64| n.min(i32::MAX as u64) as i32;
+
+    NewMetrics {
+        execution_time_ms: elapsed.as_millis().min(i64::MAX as u128) as i64,
+        files_changed: count(stat.files_changed),
+        lines_added: count(stat.additions),
+        lines_removed: count(stat.deletions),
+        ..Default::default()
+    }
+}
+
+/// Size of the change a local run pushed
+pub fn result_diff_stat(result: &TaskResult) -> DiffStat {
+    DiffStat {
+        files_changed: result.files_changed.len() as u64,
+        additions: result.lines_added,
+        deletions: result.lines_removed,
+    }
+}
+
+/// How long `task` ran: from its start to its completion (or now)
+pub fn run_time(task: &Task) -> Duration {
+    let Some(started_at) = task.started_at else {
+        return Duration::ZERO;
+    };
+    let finished_at = task.completed_at.unwrap_or_else(chrono::Utc::now);
+
+    (finished_at - started_at).to_std().unwrap_or_default()
+}
+
+/// Save the time and change size of a run of `task_id`
+pub async fn save_execution_metrics(db: &Option<Arc<Database>>, task_id: &str, elapsed: Duration, stat: DiffStat) {
+    let Some(db) = db else {
+        return;
+    };
+
+    if let Err(e) = db.save_metrics(task_id, &execution_metrics(elapsed, stat)).await {
+        tracing::warn!("Failed to save execution metrics of task {}: {}", task_id, e);
+    }
+}
+
+/// Save a run whose change is merge request `number`, sized by the provider
+///
+/// Without a merge request (or when its size cannot be fetched) only the
+/// time is saved.
+pub async fn save_merge_request_metrics<V: VcsProvider + ?Sized>(
+    db: &Option<Arc<Database>>,
+    vcs: &V,
+    repository: &Repository,
+    task_id: &str,
+    number: Option<u64>,
+    elapsed: Duration,
+) {
+    if db.is_none() {
+        return;
+    }
+
+    let stat = match number {
+        Some(number) => vcs.merge_request_diff_stat(repository, number).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to fetch the size of merge request {} of task {}: {}", number, task_id, e);
+            DiffStat::default()
+        }),
+        None => DiffStat::default(),
+    };
+
+    save_execution_metrics(db, task_id, elapsed, stat).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_metrics() {
+        let stat = DiffStat {
+            files_changed: 3,
+            additions: 120,
+            deletions: u64::MAX,
+        };
+        let metrics = execution_metrics(Duration::from_millis(1500), stat);
+
+        assert_eq!(metrics.execution_time_ms, 1500);
+        assert_eq!(metrics.files_changed, 3);
+        assert_eq!(metrics.lines_added, 120);
+        assert_eq!(metrics.lines_removed, i32::MAX);
+        assert_eq!(metrics.ai_input_tokens, 0);
+    }
+
+    #[test]
+    fn test_run_time() {
+        let mut task = Task::new("Title".to_string(), "".to_string(), "Do it".to_string());
+        assert_eq!(run_time(&task), Duration::ZERO);
+
+        let now = chrono::Utc::now();
+        task.started_at = Some(now - chrono::Duration::seconds(90));
+        task.completed_at = Some(now);
+        assert_eq!(run_time(&task), Duration::from_secs(90));
+    }
+}
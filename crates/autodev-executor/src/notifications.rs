@@ -0,0 +1,123 @@
+//! Notifications driven by engine events
+//!
+//! Completed composite tasks and budget pauses are reported from the engine's
+//! event bus, so every place that completes or pauses work gets the same
+//! notification without calling the notifier itself.
+
+use std::sync::Arc;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+
+use autodev_core::{AutoDevEngine, TaskEvent, TaskStatus};
+use autodev_notify::NotificationEvent;
+
+/// Background subscriber sending notifications for engine events
+pub struct EventNotifier {
+    stop: watch::Sender<bool>,
+    handle: JoinHandle<()>,
+}
+
+impl EventNotifier {
+    /// Follow `engine`'s events until `shutdown`
+    pub fn spawn(engine: &Arc<AutoDevEngine>) -> Self {
+        let engine = engine.clone();
+        let mut events = engine.subscribe();
+        let (stop, mut stopped) = watch::channel(false);
+
+        let handle = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = events.recv() => match event {
+                        Ok(event) => on_event(&engine, &event).await,
+                        Err(RecvError::Lagged(skipped)) => {
+                            tracing::warn!("Notifications missed {} task events", skipped);
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                    _ = stopped.changed() => {
+                        drain(&engine, &mut events).await;
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self { stop, handle }
+    }
+
+    /// Send the notifications for events published so far, then stop
+    pub async fn shutdown(self) {
+        let _ = self.stop.send(true);
+        if let Err(e) = self.handle.await {
+            tracing::warn!("Notification subscriber stopped abnormally: {}", e);
+        }
+    }
+}
+
+async fn drain(engine: &Arc<AutoDevEngine>, events: &mut broadcast::Receiver<TaskEvent>) {
+    loop {
+        match events.try_recv() {
+            Ok(event) => on_event(engine, &event).await,
+            Err(TryRecvError::Lagged(_)) => continue,
+            Err(_) => break,
+        }
+    }
+}
+
+async fn on_event(engine: &Arc<AutoDevEngine>, event: &TaskEvent) {
+    if let Some(notification) = notification_for(engine, event).await {
+        autodev_notify::notify(notification);
+    }
+}
+
+async fn notification_for(engine: &Arc<AutoDevEngine>, event: &TaskEvent) -> Option<NotificationEvent> {
+    match event {
+        TaskEvent::CompositeCompleted { composite_task_id, title, subtasks, failed, skipped, .. } => {
+            Some(NotificationEvent::CompositeCompleted {
+                composite_task_id: composite_task_id.clone(),
+                title: title.clone(),
+                subtasks: *subtasks,
+                failed: *failed,
+                skipped: *skipped,
+            })
+        }
+        TaskEvent::StatusChanged { task_id, status: TaskStatus::BudgetExceeded, error, .. } => {
+            let title = engine.get_task(task_id).await.map_or_else(|| task_id.clone(), |t| t.title);
+            Some(NotificationEvent::BudgetExceeded {
+                task_id: task_id.clone(),
+                title,
+                reason: error.clone().unwrap_or_default(),
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_notification_for() {
+        let engine = Arc::new(AutoDevEngine::new());
+        let mut events = engine.subscribe();
+
+        let task = engine
+            .create_simple_task("Costly".to_string(), "".to_string(), "".to_string())
+            .await
+            .unwrap();
+        engine.pause_for_budget(&task.id, "Daily budget of $5 exceeded").await.unwrap();
+
+        let created = events.recv().await.unwrap();
+        assert!(notification_for(&engine, &created).await.is_none());
+
+        match notification_for(&engine, &events.recv().await.unwrap()).await {
+            Some(NotificationEvent::BudgetExceeded { title, reason, .. }) => {
+                assert_eq!(title, "Costly");
+                assert_eq!(reason, "Daily budget of $5 exceeded");
+            }
+            other => panic!("unexpected notification: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Workflow dispatch inputs for a task
+//!
+//! workflow_dispatch inputs are capped (10 inputs, 65,535 characters in total)
+//! and show up in the run's metadata. With a database configured the prompt is
+//! stored as a payload the workflow fetches from `GET /tasks/:id/payload` with
+//! the dispatched `payload_token`, and only IDs, branch names and that token
+//! are dispatched. Repositories with secrets get a one-time `secrets_token` input
+//! instead of the values (see [`crate::secrets`]).
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use autodev_core::Task;
+use autodev_db::{Database, TaskPayload};
+use autodev_github::Repository;
+
+use crate::secrets::SECRET_GRANT_TTL;
+
+/// GitHub's limit on the combined size of workflow_dispatch inputs
+pub const MAX_INLINE_INPUT_CHARS: usize = 65_535;
+
+/// Inputs to dispatch `task` with
+///
+/// Without a database the prompt is passed inline, as long as it fits.
+pub async fn dispatch_inputs(
+    task: &Task,
+    repository: &Repository,
+    composite_task_id: Option<&str>,
+    prompt: &str,
+    base_branch: &str,
+    target_branch: &str,
+    db: &Option<Arc<Database>>,
+) -> Result<HashMap<String, String>> {
+    let mut inputs = HashMap::new();
+    inputs.insert("task_id".to_string(), task.id.clone());
+    inputs.insert(
+        "composite_task_id".to_string(),
+        composite_task_id.unwrap_or("standalone").to_string(),
+    );
+    inputs.insert("task_title".to_string(), task.title.clone());
+    inputs.insert("base_branch".to_string(), base_branch.to_string());
+    inputs.insert("target_branch".to_string(), target_branch.to_string());
+
+    if let Some(db) = db {
+        let payload_token = db.save_task_payload(&TaskPayload {
+            task_id: task.id.clone(),
+            composite_task_id: composite_task_id.map(str::to_string),
+            title: task.title.clone(),
+            prompt: prompt.to_string(),
+            base_branch: base_branch.to_string(),
+            target_branch: target_branch.to_string(),
+            created_at: chrono::Utc::now(),
+        })
+        .await?;
+        inputs.insert("payload_token".to_string(), payload_token);
+
+        if !db.list_secrets(&repository.owner, &repository.name).await?.is_empty() {
+            let token = db
+                .create_secret_grant(&task.id, &repository.owner, &repository.name, SECRET_GRANT_TTL)
+                .await?;
+            inputs.insert("secrets_token".to_string(), token);
+        }
+
+        return Ok(inputs);
+    }
+
+    inputs.insert("prompt".to_string(), prompt.to_string());
+
+    let size: usize = inputs.iter().map(|(k, v)| k.len() + v.len()).sum();
+    if size > MAX_INLINE_INPUT_CHARS {
+        anyhow::bail!(
+            "Task {} is too large to pass as workflow inputs ({} characters); set DATABASE_URL so the workflow can fetch it from the server",
+            task.id,
+            size
+        );
+    }
+
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_inline_inputs_without_database() {
+        let task = Task::new("Title".to_string(), "".to_string(), "Do it".to_string());
+        let repository = Repository::new("acme".to_string(), "web".to_string());
+
+        let inputs = dispatch_inputs(&task, &repository, None, "Do it", "autodev/1", "main", &None).await.unwrap();
+        assert_eq!(inputs["prompt"], "Do it");
+        assert_eq!(inputs["composite_task_id"], "standalone");
+        assert_eq!(inputs.len(), 6);
+
+        let huge = "x".repeat(MAX_INLINE_INPUT_CHARS);
+        assert!(dispatch_inputs(&task, &repository, None, &huge, "autodev/1", "main", &None).await.is_err());
+    }
+}
@@ -0,0 +1,47 @@
+//! PR bodies written from the actual diff
+//!
+//! PRs are opened with a body built from the task alone (the worker image's
+//! or the composite draft's). Once the PR exists, its diff is fetched and an
+//! AI description (summary, highlights, testing notes, risks) replaces the
+//! body; the original body stays when the diff or the AI is unavailable.
+
+use anyhow::Result;
+use std::sync::Arc;
+
+use autodev_ai::{AIAgent, PrDescriptionGenerator};
+use autodev_github::{Repository, VcsProvider, DEFAULT_CHUNK_CHARS};
+
+/// Environment variable turning AI-written PR descriptions off ("false")
+pub const PR_DESCRIPTIONS_ENV: &str = "AUTODEV_PR_DESCRIPTIONS";
+
+/// Whether PR bodies are rewritten from their diff (on by default)
+pub fn pr_descriptions_enabled() -> bool {
+    std::env::var(PR_DESCRIPTIONS_ENV)
+        .map(|v| v.to_lowercase() != "false")
+        .unwrap_or(true)
+}
+
+/// Replace the body of merge request `number` with a description of its diff
+///
+/// `footer` (task metadata, review checklist) is kept below the description.
+pub async fn describe_merge_request<V: VcsProvider + ?Sized>(
+    agent: Arc<dyn AIAgent>,
+    vcs: &Arc<V>,
+    repo: &Repository,
+    number: u64,
+    title: &str,
+    description: &str,
+    footer: &str,
+) -> Result<()> {
+    let chunks = vcs.merge_request_diff(repo, number, DEFAULT_CHUNK_CHARS).await?;
+    let generated = PrDescriptionGenerator::new(agent)
+        .describe(title, description, &chunks)
+        .await?;
+
+    let body = format!("# {}\n\n{}\n{}", title, generated.to_markdown(), footer);
+    vcs.update_merge_request_body(repo, number, &body).await?;
+
+    tracing::info!("Described merge request #{} from its diff ({} parts)", number, chunks.len());
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! AI review of subtask PRs before they are auto-merged
+//!
+//! Repositories opt in with `review_gate: <severity>` in `.autodev.yml`. The
+//! PR diff is reviewed by the agent and the findings are posted as a PR
+//! review; findings at or above the severity keep the PR from being
+//! auto-merged until someone approves it (`POST /tasks/:id/approve-merge`)
+//! or merges it by hand. A review that cannot run blocks the merge too.
+
+use std::fmt;
+use std::sync::Arc;
+
+use autodev_ai::{AIAgent, MergeReviewer};
+use autodev_core::{ReviewSeverity, Task};
+use autodev_github::{MergeBlocker, RepoConfig, Repository, VcsProvider, DEFAULT_CHUNK_CHARS};
+
+/// Reviews subtask PRs and decides whether they may be auto-merged
+#[derive(Clone)]
+pub struct ReviewGate {
+    pub agent: Arc<dyn AIAgent>,
+    /// Findings of this severity or worse block the merge
+    pub threshold: ReviewSeverity,
+}
+
+impl fmt::Debug for ReviewGate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReviewGate")
+            .field("model", &self.agent.model())
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+impl ReviewGate {
+    /// Gate configured by the repository's `review_gate`, None when it is off
+    pub fn from_config(agent: Arc<dyn AIAgent>, config: &RepoConfig) -> Option<Self> {
+        config.review_gate.map(|threshold| Self { agent, threshold })
+    }
+
+    /// Review the PR and post the findings
+    ///
+    /// Returns what blocks the merge, or None when it may go ahead.
+    pub async fn check<V: VcsProvider + ?Sized>(
+        &self,
+        task: &Task,
+        pr_number: u64,
+        repository: &Repository,
+        vcs: &Arc<V>,
+    ) -> Option<MergeBlocker> {
+        tracing::info!("Reviewing PR #{} of task {} before merging", pr_number, task.title);
+
+        let chunks = match vcs.merge_request_diff(repository, pr_number, DEFAULT_CHUNK_CHARS).await {
+            Ok(chunks) => chunks,
+            Err(e) => {
+                tracing::error!("Failed to fetch the diff of PR #{} for review: {}", pr_number, e);
+                return Some(MergeBlocker::ReviewFindings(format!("review could not run: {}", e)));
+            }
+        };
+
+        let review = match MergeReviewer::new(self.agent.clone())
+            .review(&task.title, &task.description, &chunks)
+            .await
+        {
+            Ok(review) => review,
+            Err(e) => {
+                tracing::error!("AI review of PR #{} failed: {}", pr_number, e);
+                return Some(MergeBlocker::ReviewFindings(format!("review could not run: {}", e)));
+            }
+        };
+
+        let blocking = review.blocking(self.threshold).len();
+        let verdict = if blocking == 0 {
+            format!("✅ No findings at or above **{}**; auto-merging.", self.threshold)
+        } else {
+            format!(
+                "⛔ Auto-merge is on hold: {} finding(s) at or above **{}**.\n\
+                 Fix them, merge by hand, or approve with `POST /tasks/{}/approve-merge`.",
+                blocking, self.threshold, task.id
+            )
+        };
+        let body = format!("{}\n---\n{}", review.to_markdown(), verdict);
+        if let Err(e) = vcs.submit_review(repository, pr_number, &body).await {
+            tracing::warn!("Failed to post the review of PR #{}: {}", pr_number, e);
+        }
+
+        if blocking == 0 {
+            return None;
+        }
+        Some(MergeBlocker::ReviewFindings(format!(
+            "{} finding(s) at or above {}",
+            blocking, self.threshold
+        )))
+    }
+}
@@ -0,0 +1,245 @@
+use anyhow::Result;
+use std::sync::Arc;
+
+use autodev_core::{AutoDevEngine, CompositeTask, TaskStatus};
+use autodev_db::Database;
+use autodev_github::{Repository, VcsProvider};
+
+use crate::{cancel_task, log_execution};
+
+/// Environment variable enabling automatic rollback when a composite task fails
+pub const ROLLBACK_ON_FAILURE_ENV: &str = "AUTODEV_ROLLBACK_ON_FAILURE";
+
+/// Whether failed composite tasks are rolled back automatically
+pub fn rollback_on_failure() -> bool {
+    std::env::var(ROLLBACK_ON_FAILURE_ENV)
+        .map(|v| v.to_lowercase() == "true")
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RollbackOptions {
+    /// Delete the parent branch instead of reverting the merged subtask PRs on it
+    pub delete_parent_branch: bool,
+}
+
+/// What a rollback changed on the code host
+#[derive(Debug, Clone, Default)]
+pub struct RollbackReport {
+    /// Subtask PRs closed without merging
+    pub closed_prs: Vec<u64>,
+    /// Subtask PRs already merged into the parent branch
+    pub reverted_prs: Vec<u64>,
+    /// Commits undoing the merged subtask PRs on the parent branch
+    pub revert_commits: Vec<String>,
+    pub parent_branch_deleted: bool,
+}
+
+/// Undo the work of a failed composite task
+///
+/// Unfinished subtasks are cancelled and their open PRs closed. Merged
+/// subtask PRs are reverted on the parent branch, latest first, leaving the
+/// rest of its history alone, or the parent branch is deleted altogether. Each affected
+/// subtask gets a ROLLBACK execution log entry.
+pub async fn rollback_composite_task<V: VcsProvider + ?Sized>(
+    composite_task: &CompositeTask,
+    repository: &Repository,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    db: &Option<Arc<Database>>,
+    options: &RollbackOptions,
+) -> Result<RollbackReport> {
+    tracing::info!(
+        "Rolling back composite task: {} ({})",
+        composite_task.title,
+        composite_task.id
+    );
+
+    let parent_branch = format!("autodev/{}", composite_task.id);
+    let mut report = RollbackReport::default();
+
+    for subtask in &composite_task.subtasks {
+        let status = engine
+            .get_task(&subtask.id)
+            .await
+            .map_or(subtask.status, |t| t.status);
+
+        // Stop anything still queued or running first
+        if matches!(
+            status,
+            TaskStatus::Pending | TaskStatus::WaitingDependencies | TaskStatus::InProgress
+        ) {
+            if let Err(e) = cancel_task(&subtask.id, repository, engine, vcs, db, None).await {
+                tracing::warn!("Failed to cancel subtask {}: {}", subtask.id, e);
+            }
+        }
+
+        let task_branch = format!("autodev/{}", subtask.id);
+        let Some(pr_num) = vcs.find_merge_request_by_branch(repository, &task_branch).await? else {
+            continue;
+        };
+
+        let message = if vcs.is_merge_request_merged(repository, pr_num).await? {
+            report.reverted_prs.push(pr_num);
+            if options.delete_parent_branch {
+                format!("PR #{} was merged into {}, which is deleted", pr_num, parent_branch)
+            } else {
+                format!("PR #{} reverted on {}", pr_num, parent_branch)
+            }
+        } else {
+            vcs.close_merge_request(repository, pr_num).await?;
+            report.closed_prs.push(pr_num);
+            format!("PR #{} closed", pr_num)
+        };
+
+        log_execution(engine, db, &subtask.id, "ROLLBACK", &message).await?;
+    }
+
+    if options.delete_parent_branch {
+        vcs.delete_branch(repository, &parent_branch).await?;
+        report.parent_branch_deleted = true;
+    } else {
+        for &pr_num in report.reverted_prs.iter().rev() {
+            if let Some(commit) = vcs.revert_merge_request(repository, &parent_branch, pr_num).await? {
+                report.revert_commits.push(commit);
+            }
+        }
+    }
+
+    tracing::info!(
+        "Composite task {} rolled back: {} PRs closed, {} merged PRs reverted{}",
+        composite_task.id,
+        report.closed_prs.len(),
+        report.reverted_prs.len(),
+        if report.parent_branch_deleted { ", parent branch deleted" } else { "" }
+    );
+
+    Ok(report)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autodev_core::{Task, TaskEvent};
+    use autodev_github::mock::MockPullRequestState;
+    use autodev_github::MockGitHubClient;
+
+    /// A composite task whose first two subtasks were merged into the parent
+    /// branch and whose third has an open PR
+    async fn partly_merged(
+        engine: &Arc<AutoDevEngine>,
+        github: &MockGitHubClient,
+        repository: &Repository,
+    ) -> (CompositeTask, Vec<u64>) {
+        let subtasks: Vec<Task> = ["First", "Second", "Open"]
+            .iter()
+            .map(|title| Task::new(title.to_string(), "".to_string(), "".to_string()))
+            .collect();
+        let composite = engine
+            .create_composite_task("Roll me back".to_string(), "".to_string(), subtasks, true)
+            .await
+            .unwrap();
+        let parent_branch = format!("autodev/{}", composite.id);
+        github.add_branch(repository, "main");
+        github.add_branch(repository, &parent_branch);
+
+        let mut prs = Vec::new();
+        for (i, subtask) in composite.subtasks.iter().enumerate() {
+            engine.update_task_status(&subtask.id, TaskStatus::InProgress, None).await.unwrap();
+            engine.update_task_status(&subtask.id, TaskStatus::Completed, None).await.unwrap();
+            let branch = format!("autodev/{}", subtask.id);
+            github.add_branch(repository, &branch);
+            let pr = github
+                .create_merge_request(repository, subtask.title.clone(), "".to_string(), branch, parent_branch.clone(), false)
+                .await
+                .unwrap();
+            if i < 2 {
+                github.update_pull_request(repository, pr.number, |pr| pr.state = MockPullRequestState::Merged);
+            }
+            prs.push(pr.number);
+        }
+
+        (composite, prs)
+    }
+
+    fn rollback_logs(events: &mut tokio::sync::broadcast::Receiver<TaskEvent>) -> Vec<String> {
+        let mut logs = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let TaskEvent::Log { event_type, message, .. } = event {
+                if event_type == "ROLLBACK" {
+                    logs.push(message);
+                }
+            }
+        }
+        logs
+    }
+
+    #[tokio::test]
+    async fn test_rollback_reverts_merged_prs_latest_first() {
+        let engine = Arc::new(AutoDevEngine::new());
+        let github = Arc::new(MockGitHubClient::new());
+        let repository = Repository::new("o".to_string(), "r".to_string());
+        let (composite, prs) = partly_merged(&engine, &github, &repository).await;
+        let parent_branch = format!("autodev/{}", composite.id);
+        let mut events = engine.subscribe();
+
+        let report = rollback_composite_task(&composite, &repository, &engine, &github, &None, &RollbackOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(report.closed_prs, vec![prs[2]]);
+        assert_eq!(report.reverted_prs, vec![prs[0], prs[1]]);
+        assert_eq!(report.revert_commits.len(), 2);
+        assert!(!report.parent_branch_deleted);
+
+        // Only the merges are undone; the parent branch itself is left in place
+        let reverts: Vec<String> = github
+            .calls()
+            .into_iter()
+            .filter(|call| call.starts_with("revert_merge_request:"))
+            .collect();
+        assert_eq!(
+            reverts,
+            vec![
+                format!("revert_merge_request:{}#{}", parent_branch, prs[1]),
+                format!("revert_merge_request:{}#{}", parent_branch, prs[0]),
+            ]
+        );
+        assert!(github.branches(&repository).contains(&parent_branch));
+        assert!(github.pull_request(&repository, prs[0]).unwrap().reverted);
+        assert_eq!(
+            github.pull_request(&repository, prs[2]).unwrap().state,
+            MockPullRequestState::Closed
+        );
+
+        assert_eq!(
+            rollback_logs(&mut events),
+            vec![
+                format!("PR #{} reverted on {}", prs[0], parent_branch),
+                format!("PR #{} reverted on {}", prs[1], parent_branch),
+                format!("PR #{} closed", prs[2]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollback_deletes_parent_branch() {
+        let engine = Arc::new(AutoDevEngine::new());
+        let github = Arc::new(MockGitHubClient::new());
+        let repository = Repository::new("o".to_string(), "r".to_string());
+        let (composite, prs) = partly_merged(&engine, &github, &repository).await;
+        let parent_branch = format!("autodev/{}", composite.id);
+
+        let options = RollbackOptions { delete_parent_branch: true };
+        let report = rollback_composite_task(&composite, &repository, &engine, &github, &None, &options)
+            .await
+            .unwrap();
+
+        assert_eq!(report.reverted_prs, vec![prs[0], prs[1]]);
+        assert!(report.revert_commits.is_empty());
+        assert!(report.parent_branch_deleted);
+        assert!(!github.branches(&repository).contains(&parent_branch));
+        assert!(!github.calls().iter().any(|call| call.starts_with("revert_merge_request:")));
+    }
+}
@@ -0,0 +1,241 @@
+//! Per-task choice between provider pipelines and local executors
+//!
+//! A task runs on the first backend named by, in order: the task itself (for
+//! subtasks, their composite task), the repository's `execution_backend` in
+//! `.autodev.yml`, and the server default. The server default is
+//! AUTODEV_EXECUTION_BACKEND, else the local executor (AUTODEV_EXECUTOR) when
+//! AUTODEV_LOCAL_EXECUTOR is true, else GitHub Actions. Local backends need
+//! their executor to be running on the server; there is no k8s executor yet,
+//! so k8s tasks fail instead of silently running elsewhere.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use autodev_ai::AIAgent;
+use autodev_core::{AutoDevEngine, CompositeTask, ExecutionBackend, Task};
+use autodev_db::Database;
+use autodev_github::{RepoConfig, Repository, VcsProvider};
+use autodev_local_executor::{ExecutorKind, LocalExecutor};
+
+use crate::CompletionNotifier;
+
+/// Server-wide default backend: actions, docker, k8s or process
+pub const EXECUTION_BACKEND_ENV: &str = "AUTODEV_EXECUTION_BACKEND";
+
+/// Whether AUTODEV_LOCAL_EXECUTOR enables the local executor
+pub fn local_executor_enabled() -> bool {
+    std::env::var("AUTODEV_LOCAL_EXECUTOR")
+        .is_ok_and(|value| value.eq_ignore_ascii_case("true"))
+}
+
+/// Backend of tasks that name none and whose repository names none
+///
+/// An unknown AUTODEV_EXECUTION_BACKEND is logged and ignored.
+pub fn default_backend() -> ExecutionBackend {
+    if let Ok(value) = std::env::var(EXECUTION_BACKEND_ENV) {
+        match value.parse() {
+            Ok(backend) => return backend,
+            Err(e) => tracing::warn!("Ignoring {}: {}", EXECUTION_BACKEND_ENV, e),
+        }
+    }
+
+    if local_executor_enabled() {
+        ExecutorKind::from_env().into()
+    } else {
+        ExecutionBackend::Actions
+    }
+}
+
+/// Local executor the server should start, if any
+///
+/// The default backend's executor when it is local, otherwise AUTODEV_EXECUTOR's
+/// when AUTODEV_LOCAL_EXECUTOR is true.
+pub fn local_executor_kind() -> Option<ExecutorKind> {
+    ExecutorKind::for_backend(default_backend())
+        .or_else(|| local_executor_enabled().then(ExecutorKind::from_env))
+}
+
+/// Dispatches tasks to the backend they resolve to
+#[derive(Clone)]
+pub struct ExecutionRouter {
+    default: ExecutionBackend,
+    executors: HashMap<ExecutionBackend, Arc<dyn LocalExecutor>>,
+}
+
+impl ExecutionRouter {
+    pub fn new(default: ExecutionBackend) -> Self {
+        Self {
+            default,
+            executors: HashMap::new(),
+        }
+    }
+
+    /// Router with [`default_backend`] and `local_executor` serving `kind`
+    ///
+    /// A local default whose executor failed to start falls back to GitHub Actions.
+    pub fn from_env(local_executor: Option<(ExecutorKind, Arc<dyn LocalExecutor>)>) -> Self {
+        let mut default = default_backend();
+        if ExecutorKind::for_backend(default).is_some() && local_executor.is_none() {
+            tracing::warn!("No {} executor is running; tasks default to GitHub Actions", default);
+            default = ExecutionBackend::Actions;
+        }
+
+        let router = Self::new(default);
+        match local_executor {
+            Some((kind, executor)) => router.with_executor(kind.into(), executor),
+            None => router,
+        }
+    }
+
+    /// Run tasks resolving to `backend` on `executor`
+    pub fn with_executor(mut self, backend: ExecutionBackend, executor: Arc<dyn LocalExecutor>) -> Self {
+        self.executors.insert(backend, executor);
+        self
+    }
+
+    pub fn default_backend(&self) -> ExecutionBackend {
+        self.default
+    }
+
+    /// Backend of a task that asked for `requested` in a repository with `config`
+    pub fn resolve(&self, requested: Option<ExecutionBackend>, config: &RepoConfig) -> ExecutionBackend {
+        requested.or(config.execution_backend).unwrap_or(self.default)
+    }
+
+    /// Backend a composite task and its subtasks run on
+    pub fn resolve_composite(&self, composite_task: &CompositeTask, config: &RepoConfig) -> ExecutionBackend {
+        self.resolve(composite_task.execution_backend, config)
+    }
+
+    /// Executor of a local `backend`; None for GitHub Actions
+    ///
+    /// Fails when the backend is local but this server does not run its executor.
+    pub fn executor(&self, backend: ExecutionBackend) -> Result<Option<&Arc<dyn LocalExecutor>>> {
+        if !backend.is_local() {
+            return Ok(None);
+        }
+
+        self.executors.get(&backend).map(Some).ok_or_else(|| {
+            let available: Vec<&str> = self.executors.keys().map(|b| b.as_str()).collect();
+            anyhow::anyhow!(
+                "The {} execution backend is not available on this server (local executors: {})",
+                backend,
+                if available.is_empty() { "none".to_string() } else { available.join(", ") }
+            )
+        })
+    }
+
+    /// Any local executor, for work that is not tied to a backend (e.g. resolving conflicts)
+    pub fn local_executor(&self) -> Option<&Arc<dyn LocalExecutor>> {
+        self.executors.get(&self.default).or_else(|| self.executors.values().next())
+    }
+
+    /// Start `task` on its backend and return the backend
+    ///
+    /// GitHub Actions runs are dispatched and left to their callback; local
+    /// runs are awaited.
+    pub async fn execute_task<V: VcsProvider + ?Sized>(
+        &self,
+        task: &Task,
+        repository: &Repository,
+        engine: &Arc<AutoDevEngine>,
+        vcs: &Arc<V>,
+        db: &Option<Arc<Database>>,
+    ) -> Result<ExecutionBackend> {
+        let config = vcs.load_repo_config(repository).await?;
+        let backend = self.resolve(task.execution_backend, &config);
+        tracing::info!("Task {} runs on {}", task.id, backend);
+
+        match self.executor(backend)? {
+            Some(executor) => {
+                let result = crate::execute_simple_task_docker(task, repository, executor, engine, db, None, None).await?;
+                if !result.success {
+                    return Err(anyhow::anyhow!(
+                        "Task {} failed: {}",
+                        task.id,
+                        result.error.unwrap_or_else(|| "Unknown error".to_string())
+                    ));
+                }
+            }
+            None => {
+                crate::execute_simple_task(task, repository, engine, vcs, db, None, None).await?;
+            }
+        }
+
+        Ok(backend)
+    }
+
+    /// Run `composite_task` on its backend
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute_composite_task<V: VcsProvider + ?Sized + 'static>(
+        &self,
+        composite_task: &CompositeTask,
+        repository: &Repository,
+        engine: &Arc<AutoDevEngine>,
+        vcs: &Arc<V>,
+        ai_agent: &Arc<dyn AIAgent>,
+        db: &Option<Arc<Database>>,
+        notifier: &Arc<CompletionNotifier>,
+    ) -> Result<()> {
+        let config = vcs.load_repo_config(repository).await?;
+        let backend = self.resolve_composite(composite_task, &config);
+        tracing::info!("Composite task {} runs on {}", composite_task.id, backend);
+
+        match self.executor(backend)? {
+            Some(executor) => {
+                crate::execute_composite_task_docker(
+                    composite_task,
+                    repository,
+                    executor,
+                    engine,
+                    vcs,
+                    ai_agent,
+                    db,
+                    notifier,
+                ).await
+            }
+            None => crate::execute_composite_task(composite_task, repository, engine, vcs, ai_agent, db, notifier).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_backend() {
+        let router = ExecutionRouter::new(ExecutionBackend::Actions);
+        let config = RepoConfig {
+            execution_backend: Some(ExecutionBackend::Docker),
+            ..Default::default()
+        };
+
+        // The task wins, then the repository, then the server default
+        assert_eq!(router.resolve(Some(ExecutionBackend::Process), &config), ExecutionBackend::Process);
+        assert_eq!(router.resolve(None, &config), ExecutionBackend::Docker);
+        assert_eq!(router.resolve(None, &RepoConfig::default()), ExecutionBackend::Actions);
+
+        let composite = CompositeTask::new("C".to_string(), "".to_string(), vec![Task::new(
+            "A".to_string(),
+            "".to_string(),
+            "".to_string(),
+        )])
+        .with_execution_backend(Some(ExecutionBackend::Process));
+        assert_eq!(composite.subtasks[0].execution_backend, Some(ExecutionBackend::Process));
+        assert_eq!(router.resolve_composite(&composite, &config), ExecutionBackend::Process);
+    }
+
+    #[test]
+    fn test_missing_executor() {
+        let router = ExecutionRouter::new(ExecutionBackend::Actions);
+
+        assert!(router.executor(ExecutionBackend::Actions).unwrap().is_none());
+        let error = router.executor(ExecutionBackend::K8s).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "The k8s execution backend is not available on this server (local executors: none)"
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! Repository secrets for local execution and workflow runs
+//!
+//! Containers get the decrypted secrets from [`DbSecretSource`] when they
+//! start. Workflow runs only get a one-time token as a dispatch input (see
+//! [`crate::dispatch_inputs`]) and exchange it for the secrets with
+//! `GET /tasks/:task_id/secrets`, so values never appear in inputs or tasks.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use autodev_db::{Database, SecretCipher};
+use autodev_github::Repository;
+use autodev_local_executor::SecretSource;
+
+/// How long a workflow run has to fetch its secrets after dispatch
+pub const SECRET_GRANT_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Decrypts repository secrets stored in the database
+pub struct DbSecretSource {
+    db: Arc<Database>,
+    cipher: SecretCipher,
+}
+
+impl DbSecretSource {
+    pub fn new(db: Arc<Database>, cipher: SecretCipher) -> Self {
+        Self { db, cipher }
+    }
+}
+
+#[async_trait]
+impl SecretSource for DbSecretSource {
+    async fn repository_secrets(&self, repository: &Repository) -> anyhow::Result<HashMap<String, String>> {
+        Ok(self
+            .db
+            .get_repository_secrets(&self.cipher, &repository.owner, &repository.name)
+            .await?)
+    }
+}
+
+/// Secret source for the local executor, None without a database or
+/// AUTODEV_SECRETS_KEY
+pub fn secret_source(db: &Option<Arc<Database>>) -> Option<Arc<dyn SecretSource>> {
+    let db = db.as_ref()?;
+    match SecretCipher::from_env() {
+        Ok(Some(cipher)) => Some(Arc::new(DbSecretSource::new(db.clone(), cipher))),
+        Ok(None) => None,
+        Err(e) => {
+            tracing::error!("Repository secrets are disabled: {}", e);
+            None
+        }
+    }
+}
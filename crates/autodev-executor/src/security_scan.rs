@@ -0,0 +1,174 @@
+//! Security scan of a composite task's combined changes
+//!
+//! Repositories opt in with `security_scan: true` in `.autodev.yml`. Once every
+//! subtask is merged into the parent branch and the draft PR is open, its diff
+//! is split by language and analyzed with `analyze_security`, and the issues
+//! are posted to the draft PR as a review. Critical issues, or a scan that
+//! cannot run, fail the AutoDev check on the parent branch so the PR is not
+//! merged while that check is required.
+
+use anyhow::Result;
+use std::sync::Arc;
+
+use autodev_ai::{AIAgent, SecurityIssue, SecuritySeverity};
+use autodev_core::CompositeTask;
+use autodev_github::{chunk_diff_by_language, Repository, VcsProvider, DEFAULT_CHUNK_CHARS};
+
+use crate::checks;
+
+/// An issue found in the code of one language
+#[derive(Debug, Clone)]
+pub struct SecurityFinding {
+    pub language: String,
+    pub issue: SecurityIssue,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SecurityScan {
+    pub findings: Vec<SecurityFinding>,
+}
+
+impl SecurityScan {
+    /// Number of Critical issues, which block the PR
+    pub fn critical(&self) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.issue.severity == SecuritySeverity::Critical)
+            .count()
+    }
+
+    /// PR review body, most severe issues first
+    pub fn to_markdown(&self) -> String {
+        let mut body = String::from("## AutoDev Security Scan\n\n");
+
+        if self.findings.is_empty() {
+            body.push_str("No security issues found.\n");
+            return body;
+        }
+
+        let mut findings: Vec<&SecurityFinding> = self.findings.iter().collect();
+        // 선언 순서가 Critical부터이므로 판별값이 작을수록 심각
+        findings.sort_by_key(|f| f.issue.severity as u8);
+
+        body.push_str(&format!("{} issue(s) found.\n\n### Issues\n", findings.len()));
+        for finding in findings {
+            let issue = &finding.issue;
+            let location = match (&issue.file, issue.line) {
+                (Some(file), Some(line)) => format!(" `{}:{}`", file, line),
+                (Some(file), None) => format!(" `{}`", file),
+                _ => String::new(),
+            };
+            body.push_str(&format!(
+                "- **{}**{} {} ({})\n  {}\n  Fix: {}\n",
+                issue.severity,
+                location,
+                issue.title,
+                finding.language,
+                issue.description.trim(),
+                issue.recommendation.trim()
+            ));
+        }
+
+        body
+    }
+}
+
+/// Analyze the diff of merge request `number`, one request per language chunk
+pub async fn scan_merge_request<V: VcsProvider + ?Sized>(
+    agent: &Arc<dyn AIAgent>,
+    vcs: &Arc<V>,
+    repository: &Repository,
+    number: u64,
+) -> Result<SecurityScan> {
+    let diff = vcs.merge_request_diff(repository, number, DEFAULT_CHUNK_CHARS).await?.concat();
+    let chunks = chunk_diff_by_language(&diff, DEFAULT_CHUNK_CHARS);
+    let mut scan = SecurityScan::default();
+
+    for (i, (language, chunk)) in chunks.iter().enumerate() {
+        tracing::info!("Security scan of PR #{}: {} part {}/{}", number, language, i + 1, chunks.len());
+
+        let issues = agent.analyze_security(chunk, language).await?;
+        scan.findings.extend(issues.into_iter().map(|issue| SecurityFinding {
+            language: language.to_string(),
+            issue,
+        }));
+    }
+
+    Ok(scan)
+}
+
+/// Scan the draft PR of a completed composite task and post the issues
+///
+/// Critical issues and scan errors fail the AutoDev check on the parent branch.
+pub async fn scan_composite_pr<V: VcsProvider + ?Sized>(
+    agent: &Arc<dyn AIAgent>,
+    vcs: &Arc<V>,
+    repository: &Repository,
+    composite_task: &CompositeTask,
+    pr_number: u64,
+) -> Result<SecurityScan> {
+    let scan = match scan_merge_request(agent, vcs, repository, pr_number).await {
+        Ok(scan) => scan,
+        Err(e) => {
+            let report = checks::security_scan_failed(composite_task, &e.to_string());
+            checks::report_composite_check(vcs, repository, &composite_task.id, &report).await;
+            return Err(e);
+        }
+    };
+
+    let critical = scan.critical();
+    let verdict = if critical == 0 {
+        "✅ No critical issues.".to_string()
+    } else {
+        format!("⛔ {} critical issue(s): do not merge until they are fixed.", critical)
+    };
+    let body = format!("{}\n---\n{}", scan.to_markdown(), verdict);
+    if let Err(e) = vcs.submit_review(repository, pr_number, &body).await {
+        tracing::warn!("Failed to post the security scan of PR #{}: {}", pr_number, e);
+    }
+
+    if critical > 0 {
+        tracing::warn!("Security scan of PR #{} found {} critical issues", pr_number, critical);
+        let report = checks::security_blocked(composite_task, pr_number, critical);
+        checks::report_composite_check(vcs, repository, &composite_task.id, &report).await;
+    }
+
+    Ok(scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(severity: SecuritySeverity, title: &str) -> SecurityFinding {
+        SecurityFinding {
+            language: "rust".to_string(),
+            issue: SecurityIssue {
+                severity,
+                title: title.to_string(),
+                description: "설명".to_string(),
+                file: Some("src/db.rs".to_string()),
+                line: Some(12),
+                recommendation: "수정".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_scan_report() {
+        assert_eq!(SecurityScan::default().critical(), 0);
+        assert!(SecurityScan::default().to_markdown().contains("No security issues found."));
+
+        let scan = SecurityScan {
+            findings: vec![
+                finding(SecuritySeverity::Low, "로그에 토큰"),
+                finding(SecuritySeverity::Critical, "SQL 인젝션"),
+            ],
+        };
+        assert_eq!(scan.critical(), 1);
+
+        let body = scan.to_markdown();
+        assert!(body.contains("2 issue(s) found."));
+        assert!(body.contains("### Issues\n- **Critical** `src/db.rs:12` SQL 인젝션 (rust)\n  설명\n  Fix: 수정\n- **Low**"));
+    }
+}
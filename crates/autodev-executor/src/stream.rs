@@ -0,0 +1,118 @@
+//! Streaming AI output of tasks
+//!
+//! While AI works on a task the generated text is split into lines and handed
+//! to [`TaskLogSink`] in batches, so it shows up in `task_logs` and as
+//! `output` events on the task's SSE stream before the response completes.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use autodev_core::AutoDevEngine;
+use autodev_db::Database;
+use autodev_local_executor::{LogSink, LOG_BATCH_LINES, LOG_FLUSH_INTERVAL};
+use tokio::sync::mpsc;
+
+use crate::TaskLogSink;
+
+/// Run `work`, streaming the AI output it generates into the logs of `task_id`
+pub async fn stream_to_task_logs<F: Future>(
+    task_id: &str,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+    work: F,
+) -> F::Output {
+    let sink: Arc<dyn LogSink> = Arc::new(TaskLogSink::new(engine.clone(), db.clone()));
+    stream_to_sink(task_id, sink, work).await
+}
+
+async fn stream_to_sink<F: Future>(id: &str, sink: Arc<dyn LogSink>, work: F) -> F::Output {
+    // 스트림 콜백은 동기 함수이므로 채널로 넘기고 별도 태스크에서 기록
+    let (tx, rx) = mpsc::unbounded_channel::<String>();
+    let writer = tokio::spawn(write_lines(id.to_string(), sink, rx));
+
+    let on_text: autodev_ai::StreamSink = Arc::new(move |text: &str| {
+        let _ = tx.send(text.to_string());
+    });
+    let output = autodev_ai::with_stream(on_text, work).await;
+
+    // with_stream이 끝나면 송신자가 drop되어 writer가 남은 줄을 기록하고 종료
+    if let Err(e) = writer.await {
+        tracing::warn!("AI output writer of {} failed: {}", id, e);
+    }
+    output
+}
+
+/// Split the received text into lines and write them in batches
+async fn write_lines(id: String, sink: Arc<dyn LogSink>, mut rx: mpsc::UnboundedReceiver<String>) {
+    let mut partial = String::new();
+    let mut lines: Vec<String> = Vec::new();
+    let mut ticker = tokio::time::interval(LOG_FLUSH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            text = rx.recv() => {
+                let Some(text) = text else { break };
+                partial.push_str(&text);
+
+                while let Some(end) = partial.find('\n') {
+                    let line: String = partial.drain(..=end).collect();
+                    let line = line.trim_end();
+                    if !line.is_empty() {
+                        lines.push(line.to_string());
+                    }
+                }
+
+                if lines.len() >= LOG_BATCH_LINES {
+                    sink.write_lines(&id, std::mem::take(&mut lines)).await;
+                }
+            }
+            _ = ticker.tick() => {
+                if !lines.is_empty() {
+                    sink.write_lines(&id, std::mem::take(&mut lines)).await;
+                }
+            }
+        }
+    }
+
+    let rest = partial.trim_end();
+    if !rest.is_empty() {
+        lines.push(rest.to_string());
+    }
+    if !lines.is_empty() {
+        sink.write_lines(&id, lines).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl LogSink for Recorder {
+        async fn write_lines(&self, id: &str, lines: Vec<String>) {
+            assert_eq!(id, "t1");
+            self.0.lock().unwrap().extend(lines);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_to_sink() {
+        let recorder = Arc::new(Recorder::default());
+
+        let output = stream_to_sink("t1", recorder.clone(), async {
+            autodev_ai::stream::emit("Planning the ");
+            autodev_ai::stream::emit("change\n\nEditing src/");
+            autodev_ai::stream::emit("lib.rs");
+            42
+        })
+        .await;
+
+        assert_eq!(output, 42);
+        assert_eq!(*recorder.0.lock().unwrap(), vec!["Planning the change", "Editing src/lib.rs"]);
+    }
+}
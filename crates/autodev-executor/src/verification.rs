@@ -0,0 +1,241 @@
+//! Test verification of a composite task's parent branch
+//!
+//! Repositories opt in with `test_workflow: <file>` in `.autodev.yml`. After
+//! each batch is merged, the workflow is dispatched on the parent branch and
+//! awaited. When it fails, the composite task is held as FixRequired: the
+//! AutoDev check fails, a `tests_failed` notification is sent, and the next
+//! batch waits until someone pushes a fix and approves the composite task
+//! (`POST /composite-tasks/:id/approve`), after which the tests run again.
+//!
+//! Like the AutoDev workflow, the test workflow needs a `task_id` input and a
+//! `run-name` ending in `[<task_id>]` so the dispatched run can be found.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use autodev_core::{AutoDevEngine, CompositeTask};
+use autodev_db::Database;
+use autodev_github::{Repository, VcsProvider, CORRELATION_INPUT};
+use autodev_notify::NotificationEvent;
+
+use crate::{checks, BATCH_APPROVAL_TIMEOUT};
+
+/// How long a test workflow run is waited for
+pub const TEST_WORKFLOW_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestOutcome {
+    Passed,
+    /// Conclusion of the run ("failure", "cancelled", ...)
+    Failed(String),
+}
+
+/// Dispatch `workflow_file` on `branch` and wait for its conclusion
+pub async fn run_test_workflow<V: VcsProvider + ?Sized>(
+    vcs: &Arc<V>,
+    repository: &Repository,
+    branch: &str,
+    workflow_file: &str,
+    correlation_id: &str,
+    poll_interval: Duration,
+) -> Result<TestOutcome> {
+    let inputs = HashMap::from([(CORRELATION_INPUT.to_string(), correlation_id.to_string())]);
+
+    let run_id = vcs.trigger_pipeline(repository, workflow_file, branch, inputs).await?;
+    tracing::info!("Test workflow {} started on {} (run {})", workflow_file, branch, run_id);
+
+    let deadline = tokio::time::Instant::now() + TEST_WORKFLOW_TIMEOUT;
+
+    loop {
+        tokio::time::sleep(poll_interval).await;
+
+        match vcs.pipeline_status(repository, run_id).await {
+            Ok(status) => match status.conclusion.as_deref() {
+                Some("success") => return Ok(TestOutcome::Passed),
+                Some(conclusion) => return Ok(TestOutcome::Failed(conclusion.to_string())),
+                None => {}
+            },
+            Err(e) => tracing::warn!("Error checking test workflow run {}: {}", run_id, e),
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            if let Err(e) = vcs.cancel_pipeline(repository, run_id).await {
+                tracing::warn!("Failed to cancel test workflow run {}: {}", run_id, e);
+            }
+            return Ok(TestOutcome::Failed(format!("not finished within {:?}", TEST_WORKFLOW_TIMEOUT)));
+        }
+    }
+}
+
+/// Run the test workflow after batch `batch` (1-based) was merged, holding the
+/// composite task until the parent branch passes
+#[allow(clippy::too_many_arguments)]
+pub async fn verify_batch<V: VcsProvider + ?Sized>(
+    composite_task: &CompositeTask,
+    batch: usize,
+    workflow_file: &str,
+    repository: &Repository,
+    engine: &Arc<AutoDevEngine>,
+    vcs: &Arc<V>,
+    db: &Option<Arc<Database>>,
+    poll_interval: Duration,
+) -> Result<()> {
+    let parent_branch = format!("autodev/{}", composite_task.id);
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let correlation_id = format!("{}-batch-{}-{}", composite_task.id, batch, attempt);
+
+        let reason = match run_test_workflow(vcs, repository, &parent_branch, workflow_file, &correlation_id, poll_interval).await? {
+            TestOutcome::Passed => {
+                tracing::info!("Tests passed on {} after batch {}", parent_branch, batch);
+                return Ok(());
+            }
+            TestOutcome::Failed(reason) => reason,
+        };
+
+        tracing::warn!("Tests failed on {} after batch {}: {}", parent_branch, batch, reason);
+
+        engine.require_fix(&composite_task.id).await?;
+        checks::report_composite_check(
+            vcs,
+            repository,
+            &composite_task.id,
+            &checks::fix_required(composite_task, batch, &reason),
+        ).await;
+        autodev_notify::notify(NotificationEvent::TestsFailed {
+            composite_task_id: composite_task.id.clone(),
+            title: composite_task.title.clone(),
+            batch,
+            reason,
+        });
+
+        wait_for_fix(&composite_task.id, engine, db, poll_interval).await?;
+    }
+}
+
+/// Wait until a composite task held for a fix is approved again
+///
+/// Like batch approvals, approvals made by another process are picked up from
+/// the database every poll interval.
+async fn wait_for_fix(
+    composite_id: &str,
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+    poll_interval: Duration,
+) -> Result<()> {
+    let mut events = engine.subscribe();
+    let deadline = tokio::time::Instant::now() + BATCH_APPROVAL_TIMEOUT;
+
+    tracing::info!(
+        "Waiting for a fix of autodev/{} (POST /composite-tasks/{}/approve)",
+        composite_id,
+        composite_id
+    );
+
+    loop {
+        if engine
+            .get_composite_task(composite_id)
+            .await
+            .is_some_and(|c| !c.is_fix_required())
+        {
+            tracing::info!("Fix of composite task {} approved, running the tests again", composite_id);
+            return Ok(());
+        }
+
+        if let Some(db) = db {
+            match db.get_composite_task(composite_id).await {
+                Ok(Some(record)) if record.review_state == "Approved" => {
+                    engine.approve_composite_task(composite_id).await?;
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Error checking fix approval: {}", e),
+            }
+        }
+
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return Err(anyhow::anyhow!(
+                "No fix of composite task {} was approved within {:?}",
+                composite_id,
+                BATCH_APPROVAL_TIMEOUT
+            ));
+        }
+
+        let _ = tokio::time::timeout(poll_interval.min(deadline - now), events.recv()).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autodev_core::Task;
+    use autodev_github::MockGitHubClient;
+
+    #[tokio::test]
+    async fn test_failed_tests_hold_until_fix_approved() {
+        let engine = Arc::new(AutoDevEngine::new());
+        let subtask = Task::new("Sub".to_string(), "".to_string(), "".to_string());
+        let composite = engine
+            .register_composite_task(CompositeTask::new("Users".to_string(), "".to_string(), vec![subtask]))
+            .await
+            .unwrap();
+
+        let vcs = Arc::new(MockGitHubClient::new());
+        let repository = Repository::new("owner".to_string(), "repo".to_string());
+        let branch = format!("autodev/{}", composite.id);
+        vcs.add_branch(&repository, &branch);
+
+        // CI fails the first test run and passes the second
+        let ci = {
+            let (vcs, repository) = (vcs.clone(), repository.clone());
+            tokio::spawn(async move {
+                let mut conclusions = vec!["failure", "success"].into_iter();
+                let mut concluded = 0;
+                while concluded < 2 {
+                    if let Some(run) = vcs.workflow_runs(&repository).get(concluded) {
+                        vcs.complete_run(&repository, run.id, conclusions.next().unwrap());
+                        concluded += 1;
+                    }
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            })
+        };
+
+        // Approve the fix as soon as the composite task is held
+        let approver = {
+            let engine = engine.clone();
+            let id = composite.id.clone();
+            tokio::spawn(async move {
+                while !engine.get_composite_task(&id).await.unwrap().is_fix_required() {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+                engine.approve_composite_task(&id).await.unwrap();
+            })
+        };
+
+        verify_batch(&composite, 1, "ci.yml", &repository, &engine, &vcs, &None, Duration::from_millis(10))
+            .await
+            .unwrap();
+        approver.await.unwrap();
+        ci.await.unwrap();
+
+        let dispatched: Vec<(String, String)> = vcs
+            .workflow_runs(&repository)
+            .into_iter()
+            .map(|run| (run.git_ref, run.inputs[CORRELATION_INPUT].clone()))
+            .collect();
+        assert_eq!(
+            dispatched,
+            vec![
+                (branch.clone(), format!("{}-batch-1-1", composite.id)),
+                (branch, format!("{}-batch-1-2", composite.id)),
+            ]
+        );
+        assert!(!engine.get_composite_task(&composite.id).await.unwrap().is_fix_required());
+    }
+}
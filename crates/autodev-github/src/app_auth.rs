@@ -1,4 +1,4 @@
-use crate::Result;
+use crate::{GitHubHost, Result};
 use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -10,10 +10,11 @@ pub struct GitHubAppAuth {
     app_id: String,
     private_key: EncodingKey,
     client: Client,
+    host: GitHubHost,
 }
 
 impl GitHubAppAuth {
-    /// GitHub App 인증 생성
+    /// GitHub App 인증 생성 (API 주소는 GITHUB_API_URL, 기본값 github.com)
     ///
     /// # Arguments
     /// * `app_id` - GitHub App ID
@@ -31,9 +32,46 @@ impl GitHubAppAuth {
             app_id,
             private_key,
             client: Client::new(),
+            host: GitHubHost::from_env(),
         })
     }
 
+    /// 환경 변수(GITHUB_APP_ID, GITHUB_APP_PRIVATE_KEY_PATH)로 인증 생성
+    ///
+    /// 두 변수가 모두 없으면 None (개인 토큰 사용)
+    pub fn from_env() -> Result<Option<Self>> {
+        let app_id = std::env::var("GITHUB_APP_ID").ok().filter(|v| !v.trim().is_empty());
+        let key_path = std::env::var("GITHUB_APP_PRIVATE_KEY_PATH")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+
+        match (app_id, key_path) {
+            (Some(app_id), Some(key_path)) => Ok(Some(Self::new(app_id, &key_path)?)),
+            (None, None) => Ok(None),
+            _ => Err(crate::Error::AuthError(
+                "GITHUB_APP_ID and GITHUB_APP_PRIVATE_KEY_PATH must be set together".to_string(),
+            )),
+        }
+    }
+
+    pub fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
+    pub(crate) fn private_key(&self) -> &EncodingKey {
+        &self.private_key
+    }
+
+    pub(crate) fn host(&self) -> &GitHubHost {
+        &self.host
+    }
+
+    /// 다른 GitHub 인스턴스(GitHub Enterprise Server 등) 사용
+    pub fn with_host(mut self, host: GitHubHost) -> Self {
+        self.host = host;
+        self
+    }
+
     /// JWT 토큰 생성 (GitHub App 인증용)
     ///
     /// GitHub App으로 API를 호출하기 위한 JWT 생성
@@ -66,7 +104,8 @@ impl GitHubAppAuth {
         let jwt = self.generate_jwt()?;
 
         let url = format!(
-            "https://api.github.com/app/installations/{}/access_tokens",
+            "{}/app/installations/{}/access_tokens",
+            self.host.api_url(),
             installation_id
         );
 
@@ -97,6 +136,53 @@ impl GitHubAppAuth {
         Ok(token_response.token)
     }
 
+    /// App이 설치된 Installation ID 목록 조회
+    pub async fn list_installation_ids(&self) -> Result<Vec<u64>> {
+        let jwt = self.generate_jwt()?;
+
+        let url = format!("{}/app/installations", self.host.api_url());
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", jwt))
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "AutoDev-Rust")
+            .send()
+            .await
+            .map_err(|e| crate::Error::ApiError(format!("Failed to list installations: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(crate::Error::AuthError(format!(
+                "Failed to list installations ({}): {}",
+                status, error_text
+            )));
+        }
+
+        let installations: Vec<InstallationResponse> = response
+            .json()
+            .await
+            .map_err(|e| crate::Error::ApiError(format!("Failed to parse installations: {}", e)))?;
+
+        Ok(installations.into_iter().map(|i| i.id).collect())
+    }
+
+    /// 사용할 Installation ID 결정
+    ///
+    /// GITHUB_APP_INSTALLATION_ID가 있으면 그 값을, 없으면 App이 설치된
+    /// 유일한 Installation을 사용
+    pub async fn resolve_installation_id(&self) -> Result<u64> {
+        if let Ok(id) = std::env::var("GITHUB_APP_INSTALLATION_ID") {
+            return id.trim().parse().map_err(|_| {
+                crate::Error::AuthError(format!("Invalid GITHUB_APP_INSTALLATION_ID: {}", id))
+            });
+        }
+
+        single_installation(&self.list_installation_ids().await?)
+    }
+
     /// Installation ID 조회 (Repository 기반)
     ///
     /// 특정 Repository에 설치된 GitHub App의 Installation ID 조회
@@ -111,7 +197,7 @@ impl GitHubAppAuth {
     ) -> Result<u64> {
         let jwt = self.generate_jwt()?;
 
-        let url = format!("https://api.github.com/repos/{}/{}/installation", owner, repo);
+        let url = format!("{}/repos/{}/{}/installation", self.host.api_url(), owner, repo);
 
         let response = self
             .client
@@ -160,10 +246,30 @@ struct InstallationResponse {
     id: u64,
 }
 
+fn single_installation(ids: &[u64]) -> Result<u64> {
+    match ids {
+        [id] => Ok(*id),
+        [] => Err(crate::Error::AuthError(
+            "GitHub App is not installed anywhere".to_string(),
+        )),
+        _ => Err(crate::Error::AuthError(format!(
+            "GitHub App has {} installations; set GITHUB_APP_INSTALLATION_ID",
+            ids.len()
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_single_installation() {
+        assert_eq!(single_installation(&[42]).unwrap(), 42);
+        assert!(single_installation(&[]).is_err());
+        assert!(single_installation(&[1, 2]).is_err());
+    }
+
     #[test]
     fn test_jwt_generation() {
         // 테스트용 키 생성은 실제 private key가 필요하므로 스킵
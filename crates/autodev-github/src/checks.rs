@@ -0,0 +1,136 @@
+use serde_json::json;
+
+use crate::{CheckRunReport, Error, GitHubClient, Repository, Result};
+
+/// Request body for a check run showing `report`
+fn check_run_body(report: &CheckRunReport) -> serde_json::Value {
+    let mut body = json!({
+        "status": report.state.status(),
+        "output": { "title": report.title, "summary": report.summary },
+    });
+    if let Some(conclusion) = report.state.conclusion() {
+        body["conclusion"] = json!(conclusion);
+    }
+    body
+}
+
+impl GitHubClient {
+    /// Create a check run on `head_sha`, returning its ID
+    ///
+    /// The Checks API is only available to GitHub Apps; personal tokens get a 403.
+    pub async fn create_check_run(
+        &self,
+        repo: &Repository,
+        head_sha: &str,
+        name: &str,
+        report: &CheckRunReport,
+    ) -> Result<u64> {
+        let mut body = check_run_body(report);
+        body["name"] = json!(name);
+        body["head_sha"] = json!(head_sha);
+
+        let url = format!("/repos/{}/{}/check-runs", repo.owner, repo.name);
+        let run: serde_json::Value = self
+            .creating(
+                || self.client.post(&url, Some(&body)),
+                || async {
+                    let run = self.find_check_run(repo, head_sha, name).await?;
+                    Ok(run.map(|id| json!({ "id": id })))
+                },
+            )
+            .await?;
+
+        run["id"]
+            .as_u64()
+            .ok_or_else(|| Error::ApiError(format!("No ID in created check run {}", name)))
+    }
+
+    pub async fn update_check_run(&self, repo: &Repository, check_run_id: u64, report: &CheckRunReport) -> Result<()> {
+        let body = check_run_body(report);
+        let url = format!("/repos/{}/{}/check-runs/{}", repo.owner, repo.name, check_run_id);
+        let _: serde_json::Value = self.retrying(|| self.client.patch(&url, Some(&body))).await?;
+
+        Ok(())
+    }
+
+    /// ID of the latest check run called `name` on `head_sha`
+    pub async fn find_check_run(&self, repo: &Repository, head_sha: &str, name: &str) -> Result<Option<u64>> {
+        let url = format!("/repos/{}/{}/commits/{}/check-runs", repo.owner, repo.name, head_sha);
+        let params = [("check_name", name), ("filter", "latest")];
+        let runs: serde_json::Value = self.retrying(|| self.client.get(&url, Some(&params))).await?;
+
+        Ok(runs["check_runs"]
+            .as_array()
+            .and_then(|runs| runs.first())
+            .and_then(|run| run["id"].as_u64()))
+    }
+
+    /// Report `report` on the head commit of `branch`
+    ///
+    /// The check run already on that commit is updated; a new one is created
+    /// once the branch has moved on (e.g. after a subtask PR was merged).
+    pub async fn report_check_run(
+        &self,
+        repo: &Repository,
+        branch: &str,
+        name: &str,
+        report: &CheckRunReport,
+    ) -> Result<u64> {
+        let head_sha = self.get_branch_sha(repo, branch).await?;
+
+        match self.find_check_run(repo, &head_sha, name).await? {
+            Some(id) => {
+                self.update_check_run(repo, id, report).await?;
+                Ok(id)
+            }
+            None => self.create_check_run(repo, &head_sha, name, report).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CheckRunState, GitHubHost, CHECK_RUN_NAME};
+
+    #[tokio::test]
+    async fn test_report_check_run_on_branch_head() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/o/r/git/ref/heads/autodev/c1")
+            .with_status(200)
+            .with_body(r#"{"ref": "refs/heads/autodev/c1", "node_id": "n", "url": "https://api.github.com/repos/o/r/git/refs/heads/autodev/c1",
+                "object": {"type": "commit", "sha": "abc", "url": "https://api.github.com/repos/o/r/git/commits/abc"}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/commits/abc/check-runs")
+            .match_query(mockito::Matcher::UrlEncoded("check_name".into(), CHECK_RUN_NAME.into()))
+            .with_status(200)
+            .with_body(r#"{"total_count": 0, "check_runs": []}"#)
+            .create_async()
+            .await;
+        let create = server
+            .mock("POST", "/repos/o/r/check-runs")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "name": CHECK_RUN_NAME,
+                "head_sha": "abc",
+                "status": "completed",
+                "conclusion": "success",
+            })))
+            .with_status(201)
+            .with_body(r#"{"id": 42}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let report = CheckRunReport::new(CheckRunState::Success, "All subtasks merged", "");
+        let id = client
+            .report_check_run(&Repository::new("o".to_string(), "r".to_string()), "autodev/c1", CHECK_RUN_NAME, &report)
+            .await
+            .unwrap();
+
+        assert_eq!(id, 42);
+        create.assert_async().await;
+    }
+}
@@ -1,77 +1,385 @@
-use crate::{Repository, Result};
+use crate::rate_limit::{self, RateLimitStatus, RetryPolicy, Throttle};
+use crate::workflow::{WorkflowRun, CORRELATION_INPUT};
+use crate::{Branch, DiffStat, Error, GitHubAppAuth, GitHubHost, MergeOptions, PullRequest, Repository, Result, WorkflowStatus};
+use autodev_core::MergeMethod;
+use octocrab::models::{AppId, InstallationId};
 use octocrab::params::repos::Reference;
-use octocrab::Octocrab;
+use octocrab::service::middleware::retry::RetryConfig;
+use octocrab::{DefaultOctocrabBuilderConfig, NoAuth, NoSvc, NotLayerReady, Octocrab, OctocrabBuilder};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// How long a dispatched run may take to show up in the runs list
+const RUN_LOOKUP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+const RUN_LOOKUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Octocrab builder without its built-in retries
+///
+/// Octocrab repeats any request answered with a 5xx, creates included;
+/// [`GitHubClient::retrying`] and [`GitHubClient::creating`] retry instead.
+fn octocrab_builder() -> OctocrabBuilder<NoSvc, DefaultOctocrabBuilderConfig, NoAuth, NotLayerReady> {
+    let mut builder = Octocrab::builder();
+    builder.add_retry_config(RetryConfig::None);
+    builder
+}
+
+/// Runtime that hosts octocrab's request buffer when no Tokio runtime is running
+static FALLBACK_RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
+
+/// Run `build` inside a Tokio runtime
+///
+/// Octocrab spawns a worker task while building, so creating a client from
+/// synchronous code would otherwise panic.
+fn within_runtime<T>(build: impl FnOnce() -> T) -> T {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return build();
+    }
+
+    let runtime = FALLBACK_RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("failed to start GitHub client runtime")
+    });
+    let _guard = runtime.enter();
+    build()
+}
 
 #[derive(Clone)]
 pub struct GitHubClient {
-    client: Octocrab,
+    pub(crate) client: Octocrab,
+    host: GitHubHost,
+    retry: RetryPolicy,
+    /// Last known quota, shared by clones so every task waits for the same reset
+    rate_limit: Arc<Mutex<Option<RateLimitStatus>>>,
 }
 
 impl GitHubClient {
+    /// Client for the API selected by GITHUB_API_URL (github.com by default)
     pub fn new(token: String) -> Result<Self> {
-        let client = Octocrab::builder()
-            .personal_token(token)
-            .build()?;
+        Self::with_host(token, GitHubHost::from_env())
+    }
+
+    /// Client for a specific GitHub instance (e.g. GitHub Enterprise Server)
+    pub fn with_host(token: String, host: GitHubHost) -> Result<Self> {
+        let client = within_runtime(|| {
+            octocrab_builder()
+                .personal_token(token)
+                .base_uri(host.api_url())?
+                .build()
+                .map_err(Error::from)
+        })?;
+
+        Ok(Self::from_octocrab(client, host))
+    }
+
+    fn from_octocrab(client: Octocrab, host: GitHubHost) -> Self {
+        Self {
+            client,
+            host,
+            retry: RetryPolicy::default(),
+            rate_limit: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Client authenticated as a GitHub App installation
+    ///
+    /// Installation tokens expire after an hour; a new one is requested
+    /// automatically shortly before the cached token expires.
+    pub fn from_app(app_auth: &GitHubAppAuth, installation_id: u64) -> Result<Self> {
+        let app_id: u64 = app_auth.app_id().parse().map_err(|_| {
+            Error::AuthError(format!("Invalid GitHub App ID: {}", app_auth.app_id()))
+        })?;
+        let host = app_auth.host().clone();
+
+        let app_client = within_runtime(|| {
+            octocrab_builder()
+                .app(AppId(app_id), app_auth.private_key().clone())
+                .base_uri(host.api_url())?
+                .build()
+                .map_err(Error::from)
+        })?;
+
+        tracing::info!("Authenticating as GitHub App {} (installation {})", app_id, installation_id);
+
+        Ok(Self::from_octocrab(
+            app_client.installation(InstallationId(installation_id)),
+            host,
+        ))
+    }
+
+    /// Client from the environment
+    ///
+    /// Uses the GitHub App when GITHUB_APP_ID and GITHUB_APP_PRIVATE_KEY_PATH
+    /// are set, otherwise the personal `token` (usually GITHUB_TOKEN).
+    pub async fn from_env_or_token(token: Option<String>) -> Result<Self> {
+        if let Some(app_auth) = GitHubAppAuth::from_env()? {
+            let installation_id = app_auth.resolve_installation_id().await?;
+            return Self::from_app(&app_auth, installation_id);
+        }
+
+        match token.filter(|t| !t.trim().is_empty()) {
+            Some(token) => Self::new(token),
+            None => Err(Error::AuthError(
+                "Set GITHUB_TOKEN or GITHUB_APP_ID and GITHUB_APP_PRIVATE_KEY_PATH".to_string(),
+            )),
+        }
+    }
+
+    /// [`from_env_or_token`](Self::from_env_or_token) with GITHUB_TOKEN
+    pub async fn from_env() -> Result<Self> {
+        Self::from_env_or_token(std::env::var("GITHUB_TOKEN").ok()).await
+    }
+
+    pub fn host(&self) -> &GitHubHost {
+        &self.host
+    }
+
+    /// Override how throttled and failed requests are retried
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Current core API quota
+    ///
+    /// `/rate_limit` itself does not count against the quota.
+    pub async fn rate_limit_status(&self) -> Result<RateLimitStatus> {
+        let response = self.client._get("/rate_limit").await?;
+        let response = octocrab::map_github_error(response).await?;
+
+        let status = RateLimitStatus::from_headers(response.headers())
+            .ok_or_else(|| Error::ApiError("Rate limit headers missing".to_string()))?;
+
+        *self.rate_limit.lock().unwrap() = Some(status.clone());
+
+        Ok(status)
+    }
+
+    /// Run an idempotent request (GET, PUT, DELETE, or a PATCH setting
+    /// absolute values), waiting out rate limits and retrying server errors
+    ///
+    /// `request` is called again for every attempt. Requests that create
+    /// something go through [`creating`](Self::creating) instead.
+    pub(crate) async fn retrying<T, E, F, Fut>(&self, request: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+        E: Into<Error>,
+    {
+        self.creating(request, || async { Ok(None) }).await
+    }
+
+    /// Run a request that creates a resource, retrying like [`retrying`](Self::retrying)
+    ///
+    /// A server error does not tell whether the resource was created, so
+    /// `lookup` is asked for it before each retry; when it finds the resource
+    /// that is returned instead of creating a duplicate.
+    pub(crate) async fn creating<T, E, F, Fut, L, LFut>(&self, request: F, lookup: L) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+        E: Into<Error>,
+        L: Fn() -> LFut,
+        LFut: Future<Output = Result<Option<T>>>,
+    {
+        let mut attempt = 0;
 
-        Ok(Self { client })
+        loop {
+            self.wait_for_quota().await;
+
+            let error = match request().await {
+                Ok(value) => return Ok(value),
+                Err(e) => e.into(),
+            };
+
+            let Some(throttle) = rate_limit::classify(&error) else {
+                return Err(error);
+            };
+
+            if attempt >= self.retry.max_retries {
+                tracing::error!("GitHub request failed after {} retries: {}", attempt, error);
+                return Err(error);
+            }
+
+            let delay = match throttle {
+                // 한도 초과: 최신 리셋 시각을 받아 두면 다음 루프에서 wait_for_quota가 대기
+                Throttle::RateLimited => match self.rate_limit_status().await {
+                    Ok(status) if status.is_exhausted() => std::time::Duration::ZERO,
+                    _ => self.retry.backoff(attempt),
+                },
+                Throttle::SecondaryRateLimited | Throttle::ServerError => self.retry.backoff(attempt),
+            };
+
+            attempt += 1;
+            tracing::warn!(
+                "GitHub request throttled ({:?}: {}), retry {}/{} in {:?}",
+                throttle,
+                error,
+                attempt,
+                self.retry.max_retries,
+                delay
+            );
+            tokio::time::sleep(delay).await;
+
+            // 레이트 리밋 응답은 요청이 처리되지 않았다는 뜻이지만, 5xx는 처리됐을 수도 있음
+            if throttle == Throttle::ServerError {
+                if let Some(value) = lookup().await? {
+                    tracing::info!("GitHub request succeeded despite the error, not repeating it");
+                    return Ok(value);
+                }
+            }
+        }
     }
 
-    /// Trigger a GitHub Actions workflow
+    /// Sleep until the quota resets when the last known quota is used up
+    async fn wait_for_quota(&self) {
+        let wait = {
+            let rate_limit = self.rate_limit.lock().unwrap();
+            rate_limit
+                .as_ref()
+                .filter(|status| status.is_exhausted())
+                .map(|status| status.time_until_reset().min(self.retry.max_wait))
+        };
+
+        if let Some(wait) = wait {
+            tracing::warn!("GitHub rate limit exhausted, waiting {:?} for reset", wait);
+            tokio::time::sleep(wait).await;
+            self.rate_limit.lock().unwrap().take();
+        }
+    }
+
+    /// Trigger a GitHub Actions workflow on `git_ref`
+    ///
+    /// The workflow file revision on `git_ref` is the one that runs, so it must
+    /// exist there; otherwise `Error::WorkflowNotFound` is returned without dispatching.
     pub async fn trigger_workflow(
         &self,
         repo: &Repository,
         workflow_file: &str,
+        git_ref: &str,
         inputs: HashMap<String, String>,
     ) -> Result<u64> {
         tracing::info!(
-            "Triggering workflow {} for {}/{}",
+            "Triggering workflow {} for {}/{} on {}",
             workflow_file,
             repo.owner,
-            repo.name
+            repo.name,
+            git_ref
         );
 
         // Using octocrab for workflow dispatch (octocrab 0.32 API)
         // Convert HashMap to serde_json::Value
         let inputs_json = json!(inputs);
 
-        // Trigger the workflow
-        self.client
-            .actions()
-            .create_workflow_dispatch(&repo.owner, &repo.name, workflow_file, &repo.branch)
-            .inputs(inputs_json)
-            .send()
-            .await?;
+        // The run is found by the correlation ID in its run-name
+        let correlation_id = inputs.get(CORRELATION_INPUT).cloned().ok_or_else(|| {
+            Error::ApiError(format!("Workflow inputs need {} to identify the run", CORRELATION_INPUT))
+        })?;
 
-        // Wait briefly for the workflow to appear in the API
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+        if !self.has_workflow(repo, workflow_file, git_ref).await? {
+            return Err(Error::WorkflowNotFound(format!(
+                "{} does not exist on {} of {} (install it with `autodev setup-repo` or merge it into that branch)",
+                crate::setup::workflow_path(workflow_file),
+                git_ref,
+                repo.full_name()
+            )));
+        }
+
+        // Runs created before the dispatch cannot match; leave room for clock skew
+        let created_after = (chrono::Utc::now() - chrono::Duration::seconds(60))
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+
+        // Trigger the workflow; a run that already showed up means the dispatch went through
+        self.creating(
+            || async {
+                self.client
+                    .actions()
+                    .create_workflow_dispatch(&repo.owner, &repo.name, workflow_file, git_ref)
+                    .inputs(inputs_json.clone())
+                    .send()
+                    .await
+            },
+            || async {
+                let run = self
+                    .dispatched_run(repo, workflow_file, git_ref, &correlation_id, &created_after)
+                    .await?;
+                Ok(run.map(|_| ()))
+            },
+        )
+        .await?;
+
+        self.find_dispatched_run(repo, workflow_file, git_ref, &correlation_id, &created_after)
+            .await
+    }
 
-        // Get the latest workflow run using REST API directly
+    /// ID of the dispatched run titled with `correlation_id`, if it is listed yet
+    async fn dispatched_run(
+        &self,
+        repo: &Repository,
+        workflow_file: &str,
+        git_ref: &str,
+        correlation_id: &str,
+        created_after: &str,
+    ) -> Result<Option<u64>> {
         let workflow_runs_url = format!(
             "/repos/{}/{}/actions/workflows/{}/runs",
             repo.owner, repo.name, workflow_file
         );
+        let created = format!(">={}", created_after);
+        let params = [
+            ("event", "workflow_dispatch"),
+            ("branch", git_ref),
+            ("created", created.as_str()),
+            ("per_page", "100"),
+        ];
 
-        let response: serde_json::Value = self
-            .client
-            .get(&workflow_runs_url, None::<&()>)
+        let runs: WorkflowRuns = self
+            .retrying(|| self.client.get(&workflow_runs_url, Some(&params)))
             .await?;
 
-        if let Some(runs) = response["workflow_runs"].as_array() {
-            if let Some(run) = runs.first() {
-                if let Some(run_id) = run["id"].as_u64() {
-                    tracing::info!("Workflow triggered with run ID: {}", run_id);
-                    return Ok(run_id);
-                }
+        Ok(runs
+            .workflow_runs
+            .iter()
+            .find(|run| run.correlation_id() == Some(correlation_id))
+            .map(|run| run.id))
+    }
+
+    /// Poll the runs of a dispatch until the one titled with `correlation_id` appears
+    async fn find_dispatched_run(
+        &self,
+        repo: &Repository,
+        workflow_file: &str,
+        git_ref: &str,
+        correlation_id: &str,
+        created_after: &str,
+    ) -> Result<u64> {
+        let deadline = tokio::time::Instant::now() + RUN_LOOKUP_TIMEOUT;
+
+        loop {
+            if let Some(run_id) = self
+                .dispatched_run(repo, workflow_file, git_ref, correlation_id, created_after)
+                .await?
+            {
+                tracing::info!("Workflow triggered with run ID: {} ({})", run_id, correlation_id);
+                return Ok(run_id);
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Error::WorkflowNotFound(format!(
+                    "no {} run for {} within {:?} (its run-name must end with \"[<{}>]\")",
+                    workflow_file, correlation_id, RUN_LOOKUP_TIMEOUT, CORRELATION_INPUT
+                )));
             }
-        }
 
-        // Fallback: return a timestamp-based ID if we can't get the actual run
-        let fallback_id = chrono::Utc::now().timestamp() as u64;
-        tracing::warn!("Could not get workflow run ID, using fallback: {}", fallback_id);
-        Ok(fallback_id)
+            tokio::time::sleep(RUN_LOOKUP_INTERVAL).await;
+        }
     }
 
     /// Get workflow run status by ID
@@ -85,8 +393,7 @@ impl GitHubClient {
         let run_url = format!("/repos/{}/{}/actions/runs/{}", repo.owner, repo.name, run_id);
 
         let run: serde_json::Value = self
-            .client
-            .get(&run_url, None::<&()>)
+            .retrying(|| self.client.get(&run_url, None::<&()>))
             .await?;
 
         let status = run["status"].as_str().unwrap_or("unknown").to_string();
@@ -98,6 +405,67 @@ impl GitHubClient {
         })
     }
 
+    /// Cancel an in-progress workflow run
+    pub async fn cancel_workflow_run(&self, repo: &Repository, run_id: u64) -> Result<()> {
+        tracing::info!("Cancelling workflow run {} in {}/{}", run_id, repo.owner, repo.name);
+
+        let cancel_url = format!(
+            "/repos/{}/{}/actions/runs/{}/cancel",
+            repo.owner, repo.name, run_id
+        );
+
+        // The endpoint answers 202 with an empty body, so skip deserialization
+        self.creating(
+            || async {
+                let response = self.client._post(&cancel_url, None::<&()>).await?;
+                octocrab::map_github_error(response).await.map(|_| ())
+            },
+            || async {
+                let status = self.get_workflow_run_status(repo, run_id).await?;
+                Ok(matches!(status.status.as_str(), "completed" | "cancelling").then_some(()))
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Failure output of a workflow run, at most `max_chars` long
+    ///
+    /// Downloads the run's logs archive and keeps the logs of the failed steps
+    /// (see [`logs::extract_failure_logs`](crate::logs::extract_failure_logs)).
+    pub async fn get_workflow_run_logs(
+        &self,
+        repo: &Repository,
+        run_id: u64,
+        max_chars: usize,
+    ) -> Result<String> {
+        tracing::info!("Fetching logs of workflow run {} in {}/{}", run_id, repo.owner, repo.name);
+
+        let jobs_url = format!(
+            "/repos/{}/{}/actions/runs/{}/jobs?per_page=100",
+            repo.owner, repo.name, run_id
+        );
+        let jobs: serde_json::Value = self
+            .retrying(|| self.client.get(&jobs_url, None::<&()>))
+            .await?;
+        let failed = crate::logs::failed_steps(&jobs);
+
+        // The endpoint redirects to a short-lived archive URL
+        let logs_url = format!("/repos/{}/{}/actions/runs/{}/logs", repo.owner, repo.name, run_id);
+        let archive = self
+            .retrying(|| async {
+                let response = self.client._get(logs_url.as_str()).await?;
+                let response = octocrab::map_github_error(response).await?;
+                hyper::body::to_bytes(response.into_body())
+                    .await
+                    .map_err(|e| Error::ApiError(format!("Failed to download logs: {}", e)))
+            })
+            .await?;
+
+        crate::logs::extract_failure_logs(&archive, &failed, max_chars)
+    }
+
     /// Check workflow status (legacy method, kept for compatibility)
     pub async fn check_workflow_status(
         &self,
@@ -128,20 +496,70 @@ impl GitHubClient {
     ) -> Result<PullRequest> {
         tracing::info!("Creating PR: {} ({} -> {}) [draft: {}]", title, head, base, draft);
 
-        let pr = self
-            .client
-            .pulls(&repo.owner, &repo.name)
-            .create(title, head, base)
-            .body(body)
-            .draft(draft)
-            .send()
-            .await?;
+        self.creating(
+            || async {
+                let pr = self
+                    .client
+                    .pulls(&repo.owner, &repo.name)
+                    .create(&title, &head, &base)
+                    .body(&body)
+                    .draft(draft)
+                    .send()
+                    .await?;
 
-        Ok(PullRequest {
-            number: pr.number,
-            url: pr.html_url.map(|u| u.to_string()),
-            title: pr.title.unwrap_or_default(),
-        })
+                Ok::<_, Error>(PullRequest {
+                    number: pr.number,
+                    url: pr.html_url.map(|u| u.to_string()),
+                    title: pr.title.unwrap_or_default(),
+                })
+            },
+            || async {
+                let pr = self.find_pr_by_branch(repo, &head).await?;
+                Ok(pr.filter(|pr| pr.state == "open" && pr.base_branch == base).map(|pr| PullRequest {
+                    number: pr.number,
+                    url: pr.url,
+                    title: pr.title,
+                }))
+            },
+        )
+        .await
+    }
+
+    /// Whether `body` was commented on issue or PR `number` since `since`
+    async fn has_comment(
+        &self,
+        repo: &Repository,
+        number: u64,
+        body: &str,
+        since: &str,
+    ) -> Result<Option<()>> {
+        let url = format!("/repos/{}/{}/issues/{}/comments", repo.owner, repo.name, number);
+        let params = [("since", since), ("per_page", "100")];
+        let comments: Vec<serde_json::Value> = self.retrying(|| self.client.get(&url, Some(&params))).await?;
+
+        Ok(comments
+            .iter()
+            .any(|comment| comment["body"].as_str() == Some(body))
+            .then_some(()))
+    }
+
+    /// Comment on an issue or PR, without repeating the comment when a retry follows a server error
+    async fn create_comment(&self, repo: &Repository, number: u64, comment: &str) -> Result<()> {
+        let since = (chrono::Utc::now() - chrono::Duration::seconds(60))
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+
+        self.creating(
+            || async {
+                self.client
+                    .issues(&repo.owner, &repo.name)
+                    .create_comment(number, comment)
+                    .await
+                    .map(|_| ())
+            },
+            || self.has_comment(repo, number, comment, &since),
+        )
+        .await
     }
 
     /// Add comment to PR
@@ -153,12 +571,7 @@ impl GitHubClient {
     ) -> Result<()> {
         tracing::info!("Adding comment to PR #{}", pr_number);
 
-        self.client
-            .issues(&repo.owner, &repo.name)
-            .create_comment(pr_number as u64, comment)
-            .await?;
-
-        Ok(())
+        self.create_comment(repo, pr_number as u64, comment).await
     }
 
     /// Add comment to Issue
@@ -173,10 +586,22 @@ impl GitHubClient {
     ) -> Result<()> {
         tracing::info!("Adding comment to Issue #{}", issue_number);
 
-        self.client
-            .issues(&repo.owner, &repo.name)
-            .create_comment(issue_number as u64, comment)
-            .await?;
+        self.create_comment(repo, issue_number as u64, comment).await
+    }
+
+    /// Close an issue (as completed)
+    pub async fn close_issue(&self, repo: &Repository, issue_number: u32) -> Result<()> {
+        tracing::info!("Closing Issue #{}", issue_number);
+
+        self.retrying(|| async {
+            self.client
+                .issues(&repo.owner, &repo.name)
+                .update(issue_number as u64)
+                .state(octocrab::models::IssueState::Closed)
+                .send()
+                .await
+        })
+        .await?;
 
         Ok(())
     }
@@ -188,9 +613,12 @@ impl GitHubClient {
         pr_number: u32,
     ) -> Result<PullRequest> {
         let pr = self
-            .client
-            .pulls(&repo.owner, &repo.name)
-            .get(pr_number as u64)
+            .retrying(|| async {
+                self.client
+                    .pulls(&repo.owner, &repo.name)
+                    .get(pr_number as u64)
+                    .await
+            })
             .await?;
 
         Ok(PullRequest {
@@ -200,25 +628,198 @@ impl GitHubClient {
         })
     }
 
+    /// Name of the branch a pull request merges from
+    pub async fn get_pr_head_branch(&self, repo: &Repository, pr_number: u64) -> Result<String> {
+        let pr = self
+            .retrying(|| async {
+                self.client
+                    .pulls(&repo.owner, &repo.name)
+                    .get(pr_number)
+                    .await
+            })
+            .await?;
+
+        Ok(pr.head.ref_field)
+    }
+
+    /// Whether a pull request's head branch is in another repository (a fork)
+    ///
+    /// A head repository that was deleted counts as a fork.
+    pub async fn is_pr_from_fork(&self, repo: &Repository, pr_number: u64) -> Result<bool> {
+        let pr = self
+            .retrying(|| async {
+                self.client
+                    .pulls(&repo.owner, &repo.name)
+                    .get(pr_number)
+                    .await
+            })
+            .await?;
+
+        let head_repo = pr.head.repo.and_then(|head| head.full_name);
+        Ok(!head_repo.is_some_and(|name| name.eq_ignore_ascii_case(&repo.full_name())))
+    }
+
+    /// Files changed and lines added and removed by a pull request
+    pub async fn get_pr_diff_stat(&self, repo: &Repository, pr_number: u64) -> Result<DiffStat> {
+        let pr = self
+            .retrying(|| async {
+                self.client
+                    .pulls(&repo.owner, &repo.name)
+                    .get(pr_number)
+                    .await
+            })
+            .await?;
+
+        Ok(DiffStat {
+            files_changed: pr.changed_files.unwrap_or_default(),
+            additions: pr.additions.unwrap_or_default(),
+            deletions: pr.deletions.unwrap_or_default(),
+        })
+    }
+
+    /// Unified diff of a pull request (`application/vnd.github.diff`)
+    pub async fn get_pr_diff(&self, repo: &Repository, pr_number: u64) -> Result<String> {
+        let pr_url = format!("/repos/{}/{}/pulls/{}", repo.owner, repo.name, pr_number);
+
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            http::header::ACCEPT,
+            http::HeaderValue::from_static("application/vnd.github.diff"),
+        );
+
+        self.retrying(|| async {
+            let response = self
+                .client
+                ._get_with_headers(pr_url.as_str(), Some(headers.clone()))
+                .await?;
+            let response = octocrab::map_github_error(response).await?;
+            self.client.body_to_string(response).await
+        })
+        .await
+    }
+
+    /// Files changed by a pull request, with per-file patches
+    ///
+    /// GitHub lists at most 3000 files and omits patches of binary or very large files.
+    pub async fn get_pr_files(&self, repo: &Repository, pr_number: u64) -> Result<Vec<PullRequestFile>> {
+        const PER_PAGE: usize = 100;
+
+        let mut files = Vec::new();
+
+        for page in 1..=30 {
+            let files_url = format!(
+                "/repos/{}/{}/pulls/{}/files?per_page={}&page={}",
+                repo.owner, repo.name, pr_number, PER_PAGE, page
+            );
+            let batch: Vec<PullRequestFile> = self
+                .retrying(|| self.client.get(&files_url, None::<&()>))
+                .await?;
+
+            let done = batch.len() < PER_PAGE;
+            files.extend(batch);
+            if done {
+                break;
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Pull request diff split into chunks of at most `max_chars` for AI review
+    ///
+    /// Falls back to the files listing when GitHub refuses the full diff.
+    pub async fn get_pr_diff_chunks(
+        &self,
+        repo: &Repository,
+        pr_number: u64,
+        max_chars: usize,
+    ) -> Result<Vec<String>> {
+        let diff = match self.get_pr_diff(repo, pr_number).await {
+            Ok(diff) => diff,
+            Err(e) => {
+                tracing::warn!("Could not fetch diff of PR #{} ({}), using file patches", pr_number, e);
+                crate::diff::diff_from_files(&self.get_pr_files(repo, pr_number).await?)
+            }
+        };
+
+        Ok(crate::chunk_diff(&diff, max_chars))
+    }
+
     /// Merge a pull request
     pub async fn merge_pull_request(
         &self,
         repo: &Repository,
         pr_number: u64,
+        options: &MergeOptions,
     ) -> Result<()> {
-        tracing::info!("Merging PR #{} in {}/{}", pr_number, repo.owner, repo.name);
+        tracing::info!(
+            "Merging PR #{} in {}/{} ({})",
+            pr_number,
+            repo.owner,
+            repo.name,
+            options.method
+        );
 
-        self.client
-            .pulls(&repo.owner, &repo.name)
-            .merge(pr_number)
-            .send()
-            .await?;
+        let method = match options.method {
+            MergeMethod::Merge => octocrab::params::pulls::MergeMethod::Merge,
+            MergeMethod::Squash => octocrab::params::pulls::MergeMethod::Squash,
+            MergeMethod::Rebase => octocrab::params::pulls::MergeMethod::Rebase,
+        };
+
+        self.retrying(|| async {
+            let pulls = self.client.pulls(&repo.owner, &repo.name);
+            let mut request = pulls.merge(pr_number).method(method);
+            if let Some(title) = &options.commit_title {
+                request = request.title(title);
+            }
+            if let Some(message) = &options.commit_message {
+                request = request.message(message);
+            }
+            request.send().await
+        })
+        .await?;
 
         tracing::info!("✓ PR #{} merged successfully", pr_number);
 
         Ok(())
     }
 
+    /// Close a pull request without merging it
+    pub async fn close_pull_request(
+        &self,
+        repo: &Repository,
+        pr_number: u64,
+    ) -> Result<()> {
+        tracing::info!("Closing PR #{} in {}/{}", pr_number, repo.owner, repo.name);
+
+        self.retrying(|| async {
+            self.client
+                .pulls(&repo.owner, &repo.name)
+                .update(pr_number)
+                .state(octocrab::params::pulls::State::Closed)
+                .send()
+                .await
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replace the body of a pull request
+    pub async fn update_pr_body(&self, repo: &Repository, pr_number: u64, body: &str) -> Result<()> {
+        self.retrying(|| async {
+            self.client
+                .pulls(&repo.owner, &repo.name)
+                .update(pr_number)
+                .body(body)
+                .send()
+                .await
+        })
+        .await?;
+
+        Ok(())
+    }
+
     /// Check if a pull request is merged
     pub async fn is_pr_merged(
         &self,
@@ -226,40 +827,80 @@ impl GitHubClient {
         pr_number: u64,
     ) -> Result<bool> {
         let pr = self
-            .client
-            .pulls(&repo.owner, &repo.name)
-            .get(pr_number)
+            .retrying(|| async {
+                self.client
+                    .pulls(&repo.owner, &repo.name)
+                    .get(pr_number)
+                    .await
+            })
             .await?;
 
         Ok(pr.merged_at.is_some())
     }
 
-    /// Find PR by head branch
+    /// Find the PR whose head is `branch` of this repository's owner
     pub async fn find_pr_by_branch(
         &self,
         repo: &Repository,
         branch: &str,
-    ) -> Result<Option<u64>> {
-        let prs = self
-            .client
-            .pulls(&repo.owner, &repo.name)
-            .list()
-            .state(octocrab::params::State::All)
-            .head(format!("{}:{}", repo.owner, branch))
-            .per_page(1)
-            .send()
-            .await?;
+    ) -> Result<Option<PullRequestInfo>> {
+        self.find_pr_by_head(repo, None, branch).await
+    }
 
-        Ok(prs.items.first().map(|pr| pr.number))
+    /// Find the PR whose head is `branch` pushed by `head_owner`
+    ///
+    /// Open PRs are searched first, then closed and merged ones. Without a
+    /// `head_owner` the repository's owner is tried first, then the most
+    /// recently updated PRs from any owner (forks, app installations).
+    pub async fn find_pr_by_head(
+        &self,
+        repo: &Repository,
+        head_owner: Option<&str>,
+        branch: &str,
+    ) -> Result<Option<PullRequestInfo>> {
+        let pulls_url = format!("/repos/{}/{}/pulls", repo.owner, repo.name);
+        let head = format!("{}:{}", head_owner.unwrap_or(&repo.owner), branch);
+
+        for state in ["open", "all"] {
+            let params = [("state", state), ("head", head.as_str()), ("per_page", "1")];
+            let pulls: Vec<RawPullRequest> = self
+                .retrying(|| self.client.get(&pulls_url, Some(&params)))
+                .await?;
+            if let Some(pull) = pulls.into_iter().next() {
+                return Ok(Some(pull.into()));
+            }
+
+            if head_owner.is_some() {
+                continue;
+            }
+
+            let params = [
+                ("state", state),
+                ("sort", "updated"),
+                ("direction", "desc"),
+                ("per_page", "100"),
+            ];
+            let pulls: Vec<RawPullRequest> = self
+                .retrying(|| self.client.get(&pulls_url, Some(&params)))
+                .await?;
+            if let Some(pull) = pulls.into_iter().find(|pull| pull.head.ref_ == branch) {
+                return Ok(Some(pull.into()));
+            }
+        }
+
+        Ok(None)
     }
 
     /// List repository workflows
     pub async fn list_workflows(&self, repo: &Repository) -> Result<Vec<String>> {
         let workflows = self
-            .client
-            .workflows(&repo.owner, &repo.name)
-            .list()
-            .send()
+            .retrying(|| async {
+                self.client
+                    .workflows(&repo.owner, &repo.name)
+                    .list()
+                    .send()
+                    .await
+            })
             .await?;
 
         Ok(workflows
@@ -269,6 +910,27 @@ impl GitHubClient {
             .collect())
     }
 
+    /// SHA of the commit `branch` points to
+    pub async fn get_branch_sha(&self, repo: &Repository, branch: &str) -> Result<String> {
+        // Get the ref of the branch (octocrab 0.32 uses Reference enum)
+        let branch_ref = self
+            .retrying(|| async {
+                self.client
+                    .repos(&repo.owner, &repo.name)
+                    .get_ref(&Reference::Branch(branch.to_string()))
+                    .await
+            })
+            .await?;
+
+        // Extract SHA from the Object enum using pattern matching (octocrab 0.32)
+        // Object is marked as non-exhaustive, so we need a wildcard pattern
+        use octocrab::models::repos::Object;
+        match branch_ref.object {
+            Object::Commit { sha, .. } | Object::Tag { sha, .. } => Ok(sha),
+            _ => Err(anyhow::anyhow!("Unexpected object type in ref").into()),
+        }
+    }
+
     /// Create a branch
     pub async fn create_branch(
         &self,
@@ -284,45 +946,299 @@ impl GitHubClient {
             repo.name
         );
 
-        // Get the ref of the source branch (octocrab 0.32 uses Reference enum)
-        let source_ref = self
-            .client
-            .repos(&repo.owner, &repo.name)
-            .get_ref(&Reference::Branch(from_branch.to_string()))
+        let sha = self.get_branch_sha(repo, from_branch).await?;
+
+        // Create new branch; it exists already when a failed attempt went through
+        self.creating(
+            || async {
+                self.client
+                    .repos(&repo.owner, &repo.name)
+                    .create_ref(&Reference::Branch(branch_name.to_string()), &sha)
+                    .await
+                    .map(|_| ())
+            },
+            || async {
+                Ok(match self.get_branch_sha(repo, branch_name).await {
+                    Ok(branch_sha) if branch_sha == sha => Some(()),
+                    _ => None,
+                })
+            },
+        )
+        .await
+    }
+
+    /// Delete a branch
+    pub async fn delete_branch(&self, repo: &Repository, branch_name: &str) -> Result<()> {
+        tracing::info!("Deleting branch {} in {}/{}", branch_name, repo.owner, repo.name);
+
+        let ref_url = format!(
+            "/repos/{}/{}/git/refs/heads/{}",
+            repo.owner, repo.name, branch_name
+        );
+
+        // The endpoint answers 204 with an empty body, so skip deserialization
+        self.retrying(|| async {
+            let response = self.client._delete(&ref_url, None::<&()>).await?;
+            octocrab::map_github_error(response).await
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Branches whose name starts with `prefix`, with their head commit time
+    /// and the open pull request from each
+    pub async fn list_branches(&self, repo: &Repository, prefix: &str) -> Result<Vec<Branch>> {
+        let refs_url = format!("/repos/{}/{}/git/matching-refs/heads/{}", repo.owner, repo.name, prefix);
+        let refs: Vec<serde_json::Value> = self
+            .retrying(|| self.client.get(&refs_url, None::<&()>))
             .await?;
+        let open_pulls = self.open_pull_request_heads(repo).await?;
 
-        // Extract SHA from the Object enum using pattern matching (octocrab 0.32)
-        // Object is marked as non-exhaustive, so we need a wildcard pattern
-        use octocrab::models::repos::Object;
-        let sha = match &source_ref.object {
-            Object::Commit { sha, .. } | Object::Tag { sha, .. } => sha.clone(),
-            _ => return Err(anyhow::anyhow!("Unexpected object type in ref").into()),
-        };
+        let mut branches = Vec::new();
+        for git_ref in refs {
+            let (Some(name), Some(sha)) = (
+                git_ref["ref"].as_str().and_then(|r| r.strip_prefix("refs/heads/")),
+                git_ref["object"]["sha"].as_str(),
+            ) else {
+                continue;
+            };
 
-        // Create new branch
-        self.client
-            .repos(&repo.owner, &repo.name)
-            .create_ref(
-                &Reference::Branch(branch_name.to_string()),
-                sha,
-            )
+            let commit_url = format!("/repos/{}/{}/commits/{}", repo.owner, repo.name, sha);
+            let commit: serde_json::Value = self
+                .retrying(|| self.client.get(&commit_url, None::<&()>))
+                .await?;
+            let committed_at = commit["commit"]["committer"]["date"]
+                .as_str()
+                .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok())
+                .map(|date| date.with_timezone(&chrono::Utc));
+
+            branches.push(Branch {
+                name: name.to_string(),
+                committed_at,
+                open_merge_request: open_pulls.get(name).copied(),
+            });
+        }
+
+        Ok(branches)
+    }
+
+    /// Head branch (in `repo`, not a fork) -> number of every open pull request
+    async fn open_pull_request_heads(&self, repo: &Repository) -> Result<HashMap<String, u64>> {
+        let pulls_url = format!("/repos/{}/{}/pulls", repo.owner, repo.name);
+        let full_name = repo.full_name();
+        let mut heads = HashMap::new();
+
+        for page in 1u32.. {
+            let page = page.to_string();
+            let params = [("state", "open"), ("per_page", "100"), ("page", page.as_str())];
+            let pulls: Vec<serde_json::Value> = self
+                .retrying(|| self.client.get(&pulls_url, Some(&params)))
+                .await?;
+
+            for pull in &pulls {
+                let same_repo = pull["head"]["repo"]["full_name"]
+                    .as_str()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(&full_name));
+                if let (true, Some(head), Some(number)) =
+                    (same_repo, pull["head"]["ref"].as_str(), pull["number"].as_u64())
+                {
+                    heads.insert(head.to_string(), number);
+                }
+            }
+
+            if pulls.len() < 100 {
+                break;
+            }
+        }
+
+        Ok(heads)
+    }
+
+    /// Undo the merge of pull request `pr_number` on `branch`, like `git revert -m1`
+    ///
+    /// A commit restoring the tree of the merge commit's first parent is
+    /// created on top of the merge commit and merged into `branch`, so later
+    /// changes on the branch are kept. Returns the SHA of the resulting
+    /// commit, or None when `branch` no longer contains the change.
+    pub async fn revert_pull_request(
+        &self,
+        repo: &Repository,
+        branch: &str,
+        pr_number: u64,
+    ) -> Result<Option<String>> {
+        let pr_url = format!("/repos/{}/{}/pulls/{}", repo.owner, repo.name, pr_number);
+        let pr: serde_json::Value = self
+            .retrying(|| self.client.get(&pr_url, None::<&()>))
             .await?;
 
-        Ok(())
+        if !pr["merged"].as_bool().unwrap_or(false) {
+            return Err(Error::ApiError(format!("PR #{} is not merged", pr_number)));
+        }
+        let merge_sha = pr["merge_commit_sha"]
+            .as_str()
+            .ok_or_else(|| Error::ApiError(format!("PR #{} has no merge commit", pr_number)))?
+            .to_string();
+
+        let merge_commit = self.git_commit(repo, &merge_sha).await?;
+        let mainline = merge_commit["parents"][0]["sha"]
+            .as_str()
+            .ok_or_else(|| Error::ApiError(format!("Merge commit {} has no parent", merge_sha)))?
+            .to_string();
+        let mainline_tree = self.git_commit(repo, &mainline).await?["tree"]["sha"]
+            .as_str()
+            .ok_or_else(|| Error::ApiError(format!("Commit {} has no tree", mainline)))?
+            .to_string();
+
+        let message = format!(
+            "Revert \"{}\" (#{})\n\nThis reverts commit {}.",
+            pr["title"].as_str().unwrap_or_default(),
+            pr_number,
+            merge_sha
+        );
+        let commit_body = json!({
+            "message": message,
+            "tree": mainline_tree,
+            "parents": [merge_sha],
+        });
+        // Not retried: a commit created by a failed attempt cannot be looked up
+        self.wait_for_quota().await;
+        let commit: serde_json::Value = self
+            .client
+            .post(format!("/repos/{}/{}/git/commits", repo.owner, repo.name), Some(&commit_body))
+            .await?;
+        let revert_sha = commit["sha"]
+            .as_str()
+            .ok_or_else(|| Error::ApiError("Revert commit has no SHA".to_string()))?
+            .to_string();
+
+        // Merging the revert applies it on top of whatever followed the merge;
+        // 204 means the branch doesn't contain the merge (anymore)
+        let merges_url = format!("/repos/{}/{}/merges", repo.owner, repo.name);
+        let merge_body = json!({
+            "base": branch,
+            "head": revert_sha,
+            "commit_message": message,
+        });
+        self.wait_for_quota().await;
+        let response = self.client._post(&merges_url, Some(&merge_body)).await?;
+        let response = octocrab::map_github_error(response).await?;
+        if response.status() == http::StatusCode::NO_CONTENT {
+            tracing::info!("Branch {} does not contain PR #{}, nothing to revert", branch, pr_number);
+            return Ok(None);
+        }
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(|e| Error::ApiError(format!("Failed to read merge response: {}", e)))?;
+        let merged: serde_json::Value = serde_json::from_slice(&body)?;
+        let commit_sha = merged["sha"]
+            .as_str()
+            .ok_or_else(|| Error::ApiError("Revert merge has no SHA".to_string()))?
+            .to_string();
+
+        tracing::info!("Reverted PR #{} on {} with commit {}", pr_number, branch, commit_sha);
+
+        Ok(Some(commit_sha))
+    }
+
+    /// Git commit object `sha`
+    async fn git_commit(&self, repo: &Repository, sha: &str) -> Result<serde_json::Value> {
+        let commit_url = format!("/repos/{}/{}/git/commits/{}", repo.owner, repo.name, sha);
+        self.retrying(|| self.client.get(&commit_url, None::<&()>)).await
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WorkflowStatus {
-    pub status: String,
-    pub conclusion: Option<String>,
+#[derive(Debug, Deserialize)]
+struct WorkflowRuns {
+    workflow_runs: Vec<WorkflowRun>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PullRequest {
+/// A pull request found by its head branch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PullRequestInfo {
     pub number: u64,
-    pub url: Option<String>,
     pub title: String,
+    pub url: Option<String>,
+    /// `open` or `closed`
+    pub state: String,
+    pub draft: bool,
+    pub merged: bool,
+    pub head_branch: String,
+    pub head_sha: String,
+    /// Owner of the head repository (None when it was deleted)
+    pub head_owner: Option<String>,
+    pub base_branch: String,
+}
+
+impl PullRequestInfo {
+    pub fn is_open(&self) -> bool {
+        self.state == "open"
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPullRequest {
+    number: u64,
+    title: String,
+    html_url: Option<String>,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    merged_at: Option<String>,
+    head: RawPullRequestRef,
+    base: RawPullRequestRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPullRequestRef {
+    #[serde(rename = "ref")]
+    ref_: String,
+    #[serde(default)]
+    sha: String,
+    repo: Option<RawPullRequestRepo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPullRequestRepo {
+    owner: RawPullRequestOwner,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPullRequestOwner {
+    login: String,
+}
+
+impl From<RawPullRequest> for PullRequestInfo {
+    fn from(pull: RawPullRequest) -> Self {
+        Self {
+            number: pull.number,
+            title: pull.title,
+            url: pull.html_url,
+            state: pull.state,
+            draft: pull.draft,
+            merged: pull.merged_at.is_some(),
+            head_branch: pull.head.ref_,
+            head_sha: pull.head.sha,
+            head_owner: pull.head.repo.map(|repo| repo.owner.login),
+            base_branch: pull.base.ref_,
+        }
+    }
+}
+
+/// A file changed by a pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestFile {
+    pub filename: String,
+    /// Old path of a renamed file
+    #[serde(default)]
+    pub previous_filename: Option<String>,
+    /// added, removed, modified, renamed, ...
+    pub status: String,
+    pub additions: u64,
+    pub deletions: u64,
+    /// Unified diff hunks; missing for binary or very large files
+    #[serde(default)]
+    pub patch: Option<String>,
 }
 
 #[cfg(test)]
@@ -334,4 +1250,444 @@ mod tests {
         let result = GitHubClient::new("test_token".to_string());
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_enterprise_base_url() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v3/repos/o/r/actions/runs/7")
+            .with_status(200)
+            .with_body(r#"{"status": "completed", "conclusion": "success"}"#)
+            .create_async()
+            .await;
+
+        let host = GitHubHost::new(format!("{}/api/v3", server.url()));
+        let client = GitHubClient::with_host("test_token".to_string(), host).unwrap();
+        let status = client
+            .get_workflow_run_status(&Repository::new("o".to_string(), "r".to_string()), 7)
+            .await
+            .unwrap();
+
+        assert_eq!(status.conclusion.as_deref(), Some("success"));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_pr_diff_chunks_fall_back_to_files() {
+        let mut server = mockito::Server::new_async().await;
+        let diff = server
+            .mock("GET", "/repos/o/r/pulls/5")
+            .match_header("accept", "application/vnd.github.diff")
+            .with_status(406)
+            .with_body(r#"{"message": "Sorry, the diff exceeded the maximum number of files (300)."}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/pulls/5/files")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"[{"filename": "src/main.rs", "status": "modified", "additions": 1, "deletions": 1, "patch": "@@ -1 +1 @@\n-a\n+b"}]"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let chunks = client
+            .get_pr_diff_chunks(&Repository::new("o".to_string(), "r".to_string()), 5, 1000)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            chunks,
+            vec!["diff --git a/src/main.rs b/src/main.rs\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1 +1 @@\n-a\n+b\n"]
+        );
+        diff.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_revert_pull_request_reverts_merge_commit() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/o/r/pulls/7")
+            .with_status(200)
+            .with_body(r#"{"number": 7, "title": "Sub", "merged": true, "merge_commit_sha": "m1"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/git/commits/m1")
+            .with_status(200)
+            .with_body(r#"{"sha": "m1", "tree": {"sha": "t1"}, "parents": [{"sha": "p1"}, {"sha": "s1"}]}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/git/commits/p1")
+            .with_status(200)
+            .with_body(r#"{"sha": "p1", "tree": {"sha": "t0"}, "parents": []}"#)
+            .create_async()
+            .await;
+        // The revert commit restores the first parent's tree on top of the merge
+        let commit = server
+            .mock("POST", "/repos/o/r/git/commits")
+            .match_body(mockito::Matcher::PartialJson(json!({ "tree": "t0", "parents": ["m1"] })))
+            .with_status(201)
+            .with_body(r#"{"sha": "r1"}"#)
+            .create_async()
+            .await;
+        let merge = server
+            .mock("POST", "/repos/o/r/merges")
+            .match_body(mockito::Matcher::PartialJson(json!({ "base": "autodev/parent", "head": "r1" })))
+            .with_status(201)
+            .with_body(r#"{"sha": "c1"}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let repo = Repository::new("o".to_string(), "r".to_string());
+
+        assert_eq!(
+            client.revert_pull_request(&repo, "autodev/parent", 7).await.unwrap(),
+            Some("c1".to_string())
+        );
+        commit.assert_async().await;
+        merge.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_trigger_workflow_finds_correlated_run() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/o/r/contents/.github/workflows/autodev.yml")
+            .match_query(mockito::Matcher::UrlEncoded("ref".into(), "autodev/task_a".into()))
+            .with_status(200)
+            .with_body(r#"{"type": "file", "sha": "abc"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/contents/.github/workflows/autodev.yml")
+            .match_query(mockito::Matcher::UrlEncoded("ref".into(), "old-branch".into()))
+            .with_status(404)
+            .with_body(r#"{"message": "Not Found", "documentation_url": "https://docs.github.com"}"#)
+            .create_async()
+            .await;
+        let dispatch = server
+            .mock("POST", "/repos/o/r/actions/workflows/autodev.yml/dispatches")
+            .match_body(mockito::Matcher::PartialJson(json!({ "ref": "autodev/task_a" })))
+            .with_status(204)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/actions/workflows/autodev.yml/runs")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("event".into(), "workflow_dispatch".into()),
+                mockito::Matcher::UrlEncoded("branch".into(), "autodev/task_a".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"{"total_count": 2, "workflow_runs": [
+                    {"id": 2, "name": "AutoDev", "display_title": "AutoDev: B [task_b]", "status": "queued", "conclusion": null,
+                     "html_url": "https://github.com/o/r/actions/runs/2", "created_at": "", "updated_at": ""},
+                    {"id": 1, "name": "AutoDev", "display_title": "AutoDev: A [task_a]", "status": "queued", "conclusion": null,
+                     "html_url": "https://github.com/o/r/actions/runs/1", "created_at": "", "updated_at": ""}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let repo = Repository::new("o".to_string(), "r".to_string());
+
+        let inputs = HashMap::from([("task_id".to_string(), "task_a".to_string())]);
+        assert_eq!(
+            client.trigger_workflow(&repo, "autodev.yml", "autodev/task_a", inputs.clone()).await.unwrap(),
+            1
+        );
+
+        // 상관 ID가 없으면 실행을 특정할 수 없음
+        assert!(client.trigger_workflow(&repo, "autodev.yml", "autodev/task_a", HashMap::new()).await.is_err());
+
+        // 워크플로 파일이 없는 브랜치에는 디스패치하지 않음
+        let error = client.trigger_workflow(&repo, "autodev.yml", "old-branch", inputs).await.unwrap_err();
+        assert!(matches!(error, Error::WorkflowNotFound(_)), "{}", error);
+        dispatch.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_branches() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/o/r/git/matching-refs/heads/autodev/")
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"ref": "refs/heads/autodev/a", "object": {"sha": "aaa", "type": "commit"}},
+                    {"ref": "refs/heads/autodev/b", "object": {"sha": "bbb", "type": "commit"}}
+                ]"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/pulls")
+            .match_query(mockito::Matcher::UrlEncoded("state".into(), "open".into()))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"number": 5, "head": {"ref": "autodev/b", "repo": {"full_name": "o/r"}}},
+                    {"number": 6, "head": {"ref": "autodev/a", "repo": {"full_name": "fork/r"}}}
+                ]"#,
+            )
+            .create_async()
+            .await;
+        for (sha, date) in [("aaa", "2026-01-01T00:00:00Z"), ("bbb", "2026-02-01T00:00:00Z")] {
+            server
+                .mock("GET", format!("/repos/o/r/commits/{}", sha).as_str())
+                .with_status(200)
+                .with_body(format!(r#"{{"sha": "{}", "commit": {{"committer": {{"date": "{}"}}}}}}"#, sha, date))
+                .create_async()
+                .await;
+        }
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let repo = Repository::new("o".to_string(), "r".to_string());
+        let branches = client.list_branches(&repo, "autodev/").await.unwrap();
+
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].name, "autodev/a");
+        assert_eq!(branches[0].committed_at.unwrap().to_rfc3339(), "2026-01-01T00:00:00+00:00");
+        // PRs from forks do not keep a branch of this repository alive
+        assert_eq!(branches[0].open_merge_request, None);
+        assert_eq!(branches[1].open_merge_request, Some(5));
+    }
+
+    fn pulls_query(state: &str, head: Option<&str>) -> mockito::Matcher {
+        let mut matchers = vec![mockito::Matcher::UrlEncoded("state".into(), state.into())];
+        match head {
+            Some(head) => matchers.push(mockito::Matcher::UrlEncoded("head".into(), head.into())),
+            None => matchers.push(mockito::Matcher::UrlEncoded("sort".into(), "updated".into())),
+        }
+        mockito::Matcher::AllOf(matchers)
+    }
+
+    #[tokio::test]
+    async fn test_find_pr_by_branch_prefers_open() {
+        let mut server = mockito::Server::new_async().await;
+        let open = server
+            .mock("GET", "/repos/o/r/pulls")
+            .match_query(pulls_query("open", Some("o:autodev/t1")))
+            .with_status(200)
+            .with_body(include_str!("../tests/fixtures/pulls_open.json"))
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let repo = Repository::new("o".to_string(), "r".to_string());
+        let pr = client.find_pr_by_branch(&repo, "autodev/t1").await.unwrap().unwrap();
+
+        assert_eq!(pr.number, 42);
+        assert!(pr.is_open());
+        assert!(!pr.merged);
+        assert_eq!(pr.url.as_deref(), Some("https://github.com/o/r/pull/42"));
+        assert_eq!(pr.head_owner.as_deref(), Some("o"));
+        assert_eq!(pr.head_sha, "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678");
+        assert_eq!(pr.base_branch, "main");
+        open.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_find_pr_by_head_falls_back_to_closed() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/o/r/pulls")
+            .match_query(pulls_query("open", Some("o:autodev/t1")))
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        let all = server
+            .mock("GET", "/repos/o/r/pulls")
+            .match_query(pulls_query("all", Some("o:autodev/t1")))
+            .with_status(200)
+            .with_body(include_str!("../tests/fixtures/pulls_merged.json"))
+            .create_async()
+            .await;
+        // An explicit head owner is never searched for among other owners' PRs
+        let scan = server
+            .mock("GET", "/repos/o/r/pulls")
+            .match_query(mockito::Matcher::UrlEncoded("sort".into(), "updated".into()))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let repo = Repository::new("o".to_string(), "r".to_string());
+        let pr = client.find_pr_by_head(&repo, Some("o"), "autodev/t1").await.unwrap().unwrap();
+
+        assert_eq!(pr.number, 41);
+        assert!(!pr.is_open());
+        assert!(pr.merged);
+        assert_eq!(pr.base_branch, "develop");
+        all.assert_async().await;
+        scan.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_find_pr_by_branch_from_fork() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/o/r/pulls")
+            .match_query(pulls_query("open", Some("o:autodev/t2")))
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/pulls")
+            .match_query(pulls_query("open", None))
+            .with_status(200)
+            .with_body(include_str!("../tests/fixtures/pulls_recent.json"))
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let repo = Repository::new("o".to_string(), "r".to_string());
+        let pr = client.find_pr_by_branch(&repo, "autodev/t2").await.unwrap().unwrap();
+
+        assert_eq!(pr.number, 43);
+        assert!(pr.draft);
+        assert_eq!(pr.head_owner.as_deref(), Some("autodev-fork"));
+    }
+
+    #[tokio::test]
+    async fn test_find_pr_by_branch_not_found() {
+        let mut server = mockito::Server::new_async().await;
+        for state in ["open", "all"] {
+            server
+                .mock("GET", "/repos/o/r/pulls")
+                .match_query(pulls_query(state, Some("o:autodev/none")))
+                .with_status(200)
+                .with_body("[]")
+                .create_async()
+                .await;
+            server
+                .mock("GET", "/repos/o/r/pulls")
+                .match_query(pulls_query(state, None))
+                .with_status(200)
+                .with_body(include_str!("../tests/fixtures/pulls_recent.json"))
+                .create_async()
+                .await;
+        }
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let repo = Repository::new("o".to_string(), "r".to_string());
+        assert_eq!(client.find_pr_by_branch(&repo, "autodev/none").await.unwrap(), None);
+    }
+
+    fn fast_retry() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            base_delay: std::time::Duration::from_millis(10),
+            max_wait: std::time::Duration::from_secs(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let failure = server
+            .mock("GET", "/repos/o/r/actions/runs/7")
+            .with_status(502)
+            .with_body(r#"{"message": "Server Error"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let success = server
+            .mock("GET", "/repos/o/r/actions/runs/7")
+            .with_status(200)
+            .with_body(r#"{"status": "in_progress", "conclusion": null}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url()))
+            .unwrap()
+            .with_retry_policy(fast_retry());
+        let status = client
+            .get_workflow_run_status(&Repository::new("o".to_string(), "r".to_string()), 7)
+            .await
+            .unwrap();
+
+        assert_eq!(status.status, "in_progress");
+        failure.assert_async().await;
+        success.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_is_not_repeated_after_server_error() {
+        let mut server = mockito::Server::new_async().await;
+        // 502가 났지만 댓글은 실제로 생성됨
+        let create = server
+            .mock("POST", "/repos/o/r/issues/5/comments")
+            .with_status(502)
+            .with_body(r#"{"message": "Server Error"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let lookup = server
+            .mock("GET", "/repos/o/r/issues/5/comments")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"[{"id": 1, "body": "older"}, {"id": 2, "body": "done"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url()))
+            .unwrap()
+            .with_retry_policy(fast_retry());
+        client
+            .create_issue_comment(&Repository::new("o".to_string(), "r".to_string()), 5, "done")
+            .await
+            .unwrap();
+
+        create.assert_async().await;
+        lookup.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_rate_limit_reset() {
+        let mut server = mockito::Server::new_async().await;
+        let reset = (chrono::Utc::now().timestamp() + 1).to_string();
+        server
+            .mock("GET", "/rate_limit")
+            .with_status(200)
+            .with_header("x-ratelimit-limit", "5000")
+            .with_header("x-ratelimit-remaining", "0")
+            .with_header("x-ratelimit-reset", &reset)
+            .with_body("{}")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/pulls/3")
+            .with_status(403)
+            .with_body(r#"{"message": "API rate limit exceeded for installation ID 1."}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let not_found = server
+            .mock("GET", "/repos/o/r/pulls/3")
+            .with_status(404)
+            .with_body(r#"{"message": "Not Found"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url()))
+            .unwrap()
+            .with_retry_policy(fast_retry());
+        let result = client
+            .is_pr_merged(&Repository::new("o".to_string(), "r".to_string()), 3)
+            .await;
+
+        // 리셋 후 한 번 더 요청하고, 재시도 대상이 아닌 404는 그대로 반환
+        assert!(result.is_err());
+        assert_eq!(client.rate_limit_status().await.unwrap().remaining, 0);
+        not_found.assert_async().await;
+    }
 }
\ No newline at end of file
@@ -0,0 +1,235 @@
+//! `autodev` commands in issue comments
+//!
+//! ```text
+//! autodev: <prompt> [--branch <branch>]
+//! autodev decompose: <prompt> [--branch <branch>] [--auto-approve]
+//! autodev status <task_id>
+//! autodev cancel <task_id>
+//! autodev retry [<task_id>]
+//! ```
+
+use crate::{Error, Result};
+
+const PREFIX: &str = "autodev";
+
+/// Flags given after a prompt
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOptions {
+    /// Branch to start from and open PRs against (None: the repository's default branch)
+    pub branch: Option<String>,
+    /// Merge subtask PRs without waiting for review (decompose only)
+    pub auto_approve: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueCommand {
+    /// Run the prompt as a single task
+    Run { prompt: String, options: CommandOptions },
+    /// Decompose the prompt into subtasks and run them
+    Decompose { prompt: String, options: CommandOptions },
+    Status { task_id: String },
+    Cancel { task_id: String },
+    /// Retry a failed task (None: the latest failed task started from the issue)
+    Retry { task_id: Option<String> },
+}
+
+impl IssueCommand {
+    /// Parse an issue comment
+    ///
+    /// Returns None when the comment is not an AutoDev command, and an error
+    /// when it is one but cannot be run as written.
+    pub fn parse(body: &str) -> Result<Option<Self>> {
+        let body = body.trim();
+        let rest = match body.get(..PREFIX.len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(PREFIX) => &body[PREFIX.len()..],
+            _ => return Ok(None),
+        };
+
+        if let Some(prompt) = rest.strip_prefix(':') {
+            let (prompt, options) = parse_prompt(prompt)?;
+            if options.auto_approve {
+                return Err(invalid("--auto-approve only applies to `autodev decompose`"));
+            }
+            return Ok(Some(Self::Run { prompt, options }));
+        }
+
+        // "autodevelopment ..." is not addressed to us
+        if !rest.starts_with(char::is_whitespace) {
+            return Ok(None);
+        }
+
+        let rest = rest.trim_start();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == ':')
+            .unwrap_or(rest.len());
+        let (verb, args) = rest.split_at(end);
+        let args = args.trim_start();
+        let args = args.strip_prefix(':').unwrap_or(args);
+
+        let command = match verb.to_ascii_lowercase().as_str() {
+            "decompose" => {
+                let (prompt, options) = parse_prompt(args)?;
+                Self::Decompose { prompt, options }
+            }
+            "status" => Self::Status {
+                task_id: single_arg(verb, args)?.ok_or_else(|| invalid("`autodev status` needs a task ID"))?,
+            },
+            "cancel" => Self::Cancel {
+                task_id: single_arg(verb, args)?.ok_or_else(|| invalid("`autodev cancel` needs a task ID"))?,
+            },
+            "retry" => Self::Retry {
+                task_id: single_arg(verb, args)?,
+            },
+            // Prose that merely starts with the word "autodev"
+            _ => return Ok(None),
+        };
+
+        Ok(Some(command))
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidCommand(message.to_string())
+}
+
+/// The only argument of `verb`, if any
+fn single_arg(verb: &str, args: &str) -> Result<Option<String>> {
+    let mut words = args.split_whitespace();
+    match (words.next(), words.next()) {
+        (first, None) => Ok(first.map(str::to_string)),
+        _ => Err(invalid(&format!("`autodev {}` takes a single task ID", verb))),
+    }
+}
+
+fn is_option(word: &str) -> bool {
+    word == "--auto-approve" || word == "--branch" || word.starts_with("--branch=")
+}
+
+/// Split a prompt into its text and flags
+///
+/// Lines without flags are kept as written; other `--` words stay part of the prompt.
+fn parse_prompt(text: &str) -> Result<(String, CommandOptions)> {
+    let mut options = CommandOptions::default();
+    let mut lines = Vec::new();
+
+    for line in text.lines() {
+        if !line.split_whitespace().any(is_option) {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let mut kept = Vec::new();
+        let mut words = line.split_whitespace();
+        while let Some(word) = words.next() {
+            let branch = match word {
+                "--auto-approve" => {
+                    options.auto_approve = true;
+                    continue;
+                }
+                "--branch" => words.next().filter(|w| !is_option(w)),
+                _ => match word.strip_prefix("--branch=") {
+                    Some(branch) => Some(branch).filter(|b| !b.is_empty()),
+                    None => {
+                        kept.push(word);
+                        continue;
+                    }
+                },
+            };
+            options.branch = Some(branch.ok_or_else(|| invalid("--branch needs a branch name"))?.to_string());
+        }
+        lines.push(kept.join(" "));
+    }
+
+    let prompt = lines.join("\n").trim().to_string();
+    if prompt.is_empty() {
+        return Err(invalid("The prompt is empty"));
+    }
+
+    Ok((prompt, options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(body: &str) -> Option<IssueCommand> {
+        IssueCommand::parse(body).unwrap()
+    }
+
+    #[test]
+    fn test_parse_prompt() {
+        assert_eq!(
+            parse("  autodev: Add Google OAuth\n\n- keep the session code\n"),
+            Some(IssueCommand::Run {
+                prompt: "Add Google OAuth\n\n- keep the session code".to_string(),
+                options: CommandOptions::default(),
+            })
+        );
+
+        // Flags are taken out of the prompt; unknown `--` words are prompt text
+        assert_eq!(
+            parse("autodev: Add a --verbose flag --branch staging"),
+            Some(IssueCommand::Run {
+                prompt: "Add a --verbose flag".to_string(),
+                options: CommandOptions {
+                    branch: Some("staging".to_string()),
+                    auto_approve: false,
+                },
+            })
+        );
+
+        assert_eq!(
+            parse("AutoDev decompose: Build a billing module\n--branch=release/2.0 --auto-approve"),
+            Some(IssueCommand::Decompose {
+                prompt: "Build a billing module".to_string(),
+                options: CommandOptions {
+                    branch: Some("release/2.0".to_string()),
+                    auto_approve: true,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_task_commands() {
+        assert_eq!(
+            parse("autodev status 1234"),
+            Some(IssueCommand::Status { task_id: "1234".to_string() })
+        );
+        assert_eq!(
+            parse("autodev cancel: 1234"),
+            Some(IssueCommand::Cancel { task_id: "1234".to_string() })
+        );
+        assert_eq!(parse("autodev retry"), Some(IssueCommand::Retry { task_id: None }));
+        assert_eq!(
+            parse("autodev retry 1234"),
+            Some(IssueCommand::Retry { task_id: Some("1234".to_string()) })
+        );
+    }
+
+    #[test]
+    fn test_parse_not_a_command() {
+        assert_eq!(parse("Looks good to me"), None);
+        assert_eq!(parse("autodevelopment is fun"), None);
+        assert_eq!(parse("autodev is great"), None);
+        assert_eq!(parse("autodev"), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for body in [
+            "autodev:",
+            "autodev: --branch staging",
+            "autodev: Fix it --branch",
+            "autodev: Fix it --auto-approve",
+            "autodev decompose:",
+            "autodev status",
+            "autodev cancel 1 2",
+        ] {
+            assert!(
+                matches!(IssueCommand::parse(body), Err(Error::InvalidCommand(_))),
+                "{body}"
+            );
+        }
+    }
+}
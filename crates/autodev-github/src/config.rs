@@ -0,0 +1,11 @@
+use crate::{GitHubClient, RepoConfig, Repository, Result, CONFIG_FILE};
+
+impl GitHubClient {
+    /// `.autodev.yml` from the repository's default branch (defaults when absent)
+    pub async fn load_repo_config(&self, repo: &Repository) -> Result<RepoConfig> {
+        match self.get_file(repo, CONFIG_FILE, None).await? {
+            Some(yaml) => Ok(RepoConfig::parse(&yaml)?),
+            None => Ok(RepoConfig::default()),
+        }
+    }
+}
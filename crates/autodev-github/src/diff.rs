@@ -0,0 +1,60 @@
+use crate::client::PullRequestFile;
+
+/// Rebuild a unified diff from the per-file patches of the files listing
+///
+/// Used when the diff media type is unavailable (GitHub refuses very large diffs).
+pub fn diff_from_files(files: &[PullRequestFile]) -> String {
+    let mut diff = String::new();
+
+    for file in files {
+        let old_path = file.previous_filename.as_deref().unwrap_or(&file.filename);
+        let old = if file.status == "added" { "/dev/null".to_string() } else { format!("a/{}", old_path) };
+        let new = if file.status == "removed" { "/dev/null".to_string() } else { format!("b/{}", file.filename) };
+
+        diff.push_str(&format!("diff --git a/{} b/{}\n--- {}\n+++ {}\n", old_path, file.filename, old, new));
+
+        match &file.patch {
+            Some(patch) => {
+                diff.push_str(patch);
+                if !patch.ends_with('\n') {
+                    diff.push('\n');
+                }
+            }
+            // 바이너리 파일이나 너무 큰 파일은 patch가 없음
+            None => diff.push_str("Binary or oversized file, patch not available\n"),
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_from_files() {
+        let files = vec![
+            PullRequestFile {
+                filename: "new.rs".to_string(),
+                previous_filename: None,
+                status: "added".to_string(),
+                additions: 1,
+                deletions: 0,
+                patch: Some("@@ -0,0 +1 @@\n+fn main() {}".to_string()),
+            },
+            PullRequestFile {
+                filename: "logo.png".to_string(),
+                previous_filename: None,
+                status: "modified".to_string(),
+                additions: 0,
+                deletions: 0,
+                patch: None,
+            },
+        ];
+
+        let diff = diff_from_files(&files);
+        assert!(diff.starts_with("diff --git a/new.rs b/new.rs\n--- /dev/null\n+++ b/new.rs\n@@"));
+        assert!(diff.contains("+++ b/logo.png\nBinary"));
+    }
+}
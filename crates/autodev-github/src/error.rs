@@ -1,5 +1,8 @@
+use autodev_core::ErrorClass;
 use thiserror::Error;
 
+use crate::rate_limit::{self, Throttle};
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("GitHub API error: {0}")]
@@ -17,6 +20,9 @@ pub enum Error {
     #[error("Pull request not found: {0}")]
     PullRequestNotFound(String),
 
+    #[error("Invalid command: {0}")]
+    InvalidCommand(String),
+
     #[error("Unsupported webhook event: {0}")]
     UnsupportedEvent(String),
 
@@ -26,8 +32,71 @@ pub enum Error {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// Error shared with other providers (e.g. an invalid `.autodev.yml`)
+    #[error(transparent)]
+    Vcs(#[from] autodev_vcs::Error),
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
+impl Error {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            Error::AuthError(_) => ErrorClass::AuthError,
+            Error::RepoNotFound(_) | Error::WorkflowNotFound(_) | Error::PullRequestNotFound(_) => ErrorClass::NotFound,
+            Error::Vcs(e) => e.class(),
+            Error::Octocrab(e) => octocrab_class(self, e),
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// Errors of the GitHub `VcsProvider` implementation; GitHub-only failures keep their class
+impl From<Error> for autodev_vcs::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::ApiError(message) => Self::ApiError(message),
+            Error::AuthError(message) => Self::AuthError(message),
+            Error::RepoNotFound(message) => Self::RepoNotFound(message),
+            Error::WorkflowNotFound(message) => Self::WorkflowNotFound(message),
+            Error::PullRequestNotFound(message) => Self::PullRequestNotFound(message),
+            Error::Json(e) => Self::Json(e),
+            Error::Vcs(e) => e,
+            Error::Other(e) => Self::Other(e),
+            Error::InvalidCommand(_) | Error::UnsupportedEvent(_) | Error::Octocrab(_) => Self::Provider {
+                class: error.class(),
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+/// Class of an octocrab error, by GitHub's message (octocrab drops the status code)
+fn octocrab_class(error: &Error, octocrab_error: &octocrab::Error) -> ErrorClass {
+    match rate_limit::classify(error) {
+        Some(Throttle::RateLimited | Throttle::SecondaryRateLimited) => {
+            return ErrorClass::RateLimited { retry_after: None };
+        }
+        Some(Throttle::ServerError) => return ErrorClass::Transient,
+        None => {}
+    }
+
+    let octocrab::Error::GitHub { source, .. } = octocrab_error else {
+        return ErrorClass::Permanent;
+    };
+    let message = source.message.to_lowercase();
+    let any = |patterns: &[&str]| patterns.iter().any(|pattern| message.contains(pattern));
+
+    if any(&["not found"]) {
+        ErrorClass::NotFound
+    } else if any(&["bad credentials", "requires authentication", "resource not accessible", "must have admin rights"]) {
+        ErrorClass::AuthError
+    } else if any(&["already exists", "not mergeable", "was modified", "merge conflict", "not a fast forward"]) {
+        ErrorClass::Conflict
+    } else {
+        ErrorClass::Permanent
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
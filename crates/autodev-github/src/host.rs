@@ -0,0 +1,106 @@
+/// Environment variable selecting the GitHub API (e.g. a GitHub Enterprise Server)
+pub const GITHUB_API_URL_ENV: &str = "GITHUB_API_URL";
+
+/// API of github.com
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// GitHub instance the crate talks to: github.com or a GitHub Enterprise Server
+///
+/// GHES serves its REST API under `https://<host>/api/v3`, so web URLs
+/// (PRs, clones) are derived from the API URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubHost {
+    api_url: String,
+}
+
+impl GitHubHost {
+    pub fn new(api_url: impl Into<String>) -> Self {
+        let api_url = api_url.into();
+
+        Self {
+            api_url: api_url.trim().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Read GITHUB_API_URL, defaulting to github.com
+    pub fn from_env() -> Self {
+        match std::env::var(GITHUB_API_URL_ENV) {
+            Ok(url) if !url.trim().is_empty() => Self::new(url),
+            _ => Self::default(),
+        }
+    }
+
+    /// REST API base URL without a trailing slash
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    /// GraphQL endpoint (`/api/graphql` on GHES)
+    pub fn graphql_url(&self) -> String {
+        match self.api_url.strip_suffix("/api/v3") {
+            Some(base) => format!("{}/api/graphql", base),
+            None => format!("{}/graphql", self.api_url),
+        }
+    }
+
+    pub fn is_enterprise(&self) -> bool {
+        self.api_url != DEFAULT_API_URL
+    }
+
+    /// Web UI base URL (https://github.com or https://<ghes-host>)
+    pub fn web_url(&self) -> String {
+        if !self.is_enterprise() {
+            return "https://github.com".to_string();
+        }
+
+        self.api_url
+            .strip_suffix("/api/v3")
+            .unwrap_or(&self.api_url)
+            .to_string()
+    }
+
+    /// Host name of the web UI, as sent in the X-GitHub-Enterprise-Host header
+    pub fn hostname(&self) -> String {
+        let web_url = self.web_url();
+        let without_scheme = web_url
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(&web_url);
+
+        without_scheme
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+impl Default for GitHubHost {
+    fn default() -> Self {
+        Self::new(DEFAULT_API_URL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_com() {
+        let host = GitHubHost::default();
+        assert!(!host.is_enterprise());
+        assert_eq!(host.web_url(), "https://github.com");
+        assert_eq!(host.hostname(), "github.com");
+        assert_eq!(host.graphql_url(), "https://api.github.com/graphql");
+    }
+
+    #[test]
+    fn test_enterprise_server() {
+        let host = GitHubHost::new("https://ghe.example.com/api/v3/");
+        assert!(host.is_enterprise());
+        assert_eq!(host.api_url(), "https://ghe.example.com/api/v3");
+        assert_eq!(host.web_url(), "https://ghe.example.com");
+        assert_eq!(host.hostname(), "ghe.example.com");
+        assert_eq!(host.graphql_url(), "https://ghe.example.com/api/graphql");
+    }
+}
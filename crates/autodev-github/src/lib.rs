@@ -4,11 +4,39 @@ pub mod workflow;
 pub mod webhook;
 pub mod error;
 pub mod app_auth;
+pub mod host;
+pub mod vcs;
+pub mod rate_limit;
+pub mod diff;
+pub mod logs;
+pub mod setup;
+pub mod config;
+pub mod mergeability;
+pub mod checks;
+pub mod review_comments;
+pub mod command;
+pub mod orgs;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 
 // Re-exports
-pub use client::GitHubClient;
-pub use repository::Repository;
-pub use workflow::{WorkflowDispatch, WorkflowRun};
+pub use client::{GitHubClient, PullRequestFile, PullRequestInfo};
+pub use repository::GitHubUrls;
+pub use workflow::{WorkflowDispatch, WorkflowRun, CORRELATION_INPUT};
 pub use webhook::{WebhookEvent, WebhookHandler};
 pub use error::{Error, Result};
-pub use app_auth::GitHubAppAuth;
\ No newline at end of file
+pub use app_auth::GitHubAppAuth;
+pub use host::{GitHubHost, DEFAULT_API_URL, GITHUB_API_URL_ENV};
+pub use rate_limit::{RateLimitStatus, RetryPolicy};
+pub use logs::DEFAULT_MAX_LOG_CHARS;
+pub use review_comments::{ReviewComment, ReviewThread};
+pub use command::{CommandOptions, IssueCommand};
+pub use orgs::{org_id, OrgClients, ORGS_ENV};
+#[cfg(any(test, feature = "mock"))]
+pub use mock::MockGitHubClient;
+// Provider-neutral types, re-exported so GitHub callers need only this crate
+pub use autodev_vcs::{
+    chunk_diff, chunk_diff_by_language, diff_language, Branch, CheckRunReport, CheckRunState, CheckState, DiffStat,
+    MergeBlocker, MergeOptions, Mergeability, PullRequest, RepoConfig, Repository, VcsProvider, WorkflowStatus,
+    CHECK_RUN_NAME, CONFIG_FILE, DEFAULT_CHUNK_CHARS, SUBTASK_WORKFLOW_FILE, WORKFLOW_FILE,
+};
@@ -0,0 +1,226 @@
+use crate::{Error, Result};
+use std::io::{Cursor, Read};
+
+/// Largest amount of CI log output handed to the AI (characters)
+pub const DEFAULT_MAX_LOG_CHARS: usize = 50_000;
+
+/// Decompressed size limit per archive entry, against zip bombs
+const MAX_ENTRY_BYTES: usize = 64 * 1024 * 1024;
+
+/// A failed step of a workflow job, as reported by the jobs API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedStep {
+    pub job: String,
+    pub number: u64,
+    pub name: String,
+}
+
+/// Failed steps from a `/actions/runs/{id}/jobs` response
+///
+/// A failed job without a failed step (e.g. it failed while starting) is
+/// reported with step number 0.
+pub fn failed_steps(jobs: &serde_json::Value) -> Vec<FailedStep> {
+    let mut failed = Vec::new();
+
+    for job in jobs["jobs"].as_array().into_iter().flatten() {
+        if job["conclusion"].as_str() != Some("failure") {
+            continue;
+        }
+        let job_name = job["name"].as_str().unwrap_or_default().to_string();
+
+        let steps: Vec<FailedStep> = job["steps"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|step| step["conclusion"].as_str() == Some("failure"))
+            .map(|step| FailedStep {
+                job: job_name.clone(),
+                number: step["number"].as_u64().unwrap_or_default(),
+                name: step["name"].as_str().unwrap_or_default().to_string(),
+            })
+            .collect();
+
+        if steps.is_empty() {
+            failed.push(FailedStep { job: job_name, number: 0, name: String::new() });
+        } else {
+            failed.extend(steps);
+        }
+    }
+
+    failed
+}
+
+/// Failure output from a run's logs archive, at most `max_chars` long
+///
+/// The archive holds `<job>/<number>_<step>.txt` per step and `<n>_<job>.txt`
+/// per job. Logs of the failed steps are used, falling back to the whole
+/// job log and finally to every log in the archive. Each section keeps its
+/// tail, where the errors usually are.
+pub fn extract_failure_logs(archive: &[u8], failed: &[FailedStep], max_chars: usize) -> Result<String> {
+    let entries = unzip_text(archive)?;
+
+    let mut sections: Vec<(String, &str)> = Vec::new();
+    for step in failed {
+        let job = normalize(&step.job);
+
+        let step_logs = entries.iter().filter(|(path, _)| match path.split_once('/') {
+            Some((dir, file)) => {
+                step.number > 0 && normalize(dir) == job && file.starts_with(&format!("{}_", step.number))
+            }
+            None => false,
+        });
+        let job_logs = entries.iter().filter(|(path, _)| {
+            !path.contains('/')
+                && path
+                    .split_once('_')
+                    .is_some_and(|(_, name)| normalize(name.trim_end_matches(".txt")) == job)
+        });
+
+        let mut found: Vec<_> = step_logs.collect();
+        if found.is_empty() {
+            found = job_logs.collect();
+        }
+
+        for (path, log) in found {
+            if !sections.iter().any(|(title, _)| title == path) {
+                sections.push((path.clone(), log));
+            }
+        }
+    }
+
+    if sections.is_empty() {
+        sections = entries.iter().map(|(path, log)| (path.clone(), log.as_str())).collect();
+    }
+
+    if sections.is_empty() {
+        return Ok(String::new());
+    }
+
+    let budget = max_chars / sections.len();
+    Ok(sections
+        .into_iter()
+        .map(|(title, log)| format!("## {}\n{}\n", title.trim_end_matches(".txt"), truncate_log(&strip_timestamps(log), budget)))
+        .collect())
+}
+
+/// Keep the last `max_chars` of a log, marking what was cut
+pub fn truncate_log(log: &str, max_chars: usize) -> String {
+    if log.len() <= max_chars {
+        return log.to_string();
+    }
+
+    let mut start = log.len() - max_chars;
+    while !log.is_char_boundary(start) {
+        start += 1;
+    }
+    // 줄 중간에서 시작하지 않도록 다음 줄부터
+    if let Some(newline) = log[start..].find('\n') {
+        start += newline + 1;
+    }
+
+    format!("... ({} characters truncated) ...\n{}", start, &log[start..])
+}
+
+/// Drop the `2024-01-01T00:00:00.0000000Z ` prefix GitHub puts on every line
+fn strip_timestamps(log: &str) -> String {
+    log.lines()
+        .map(|line| {
+            let bytes = line.as_bytes();
+            if bytes.len() > 20 && bytes[4] == b'-' && bytes[10] == b'T' {
+                line.split_once("Z ").map(|(_, rest)| rest).unwrap_or(line)
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compare job names loosely: the archive sanitizes characters like `/` and `:`
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Text files of a zip archive as (path, content), in archive order
+fn unzip_text(archive: &[u8]) -> Result<Vec<(String, String)>> {
+    let invalid = |reason: String| Error::ApiError(format!("Invalid logs archive: {}", reason));
+
+    let mut zip = zip::ZipArchive::new(Cursor::new(archive)).map_err(|e| invalid(e.to_string()))?;
+    let mut entries = Vec::with_capacity(zip.len());
+
+    for i in 0..zip.len() {
+        let file = zip.by_index(i).map_err(|e| invalid(e.to_string()))?;
+        if file.is_dir() {
+            continue;
+        }
+
+        let name = file.name().to_string();
+        let mut content = Vec::new();
+        file.take(MAX_ENTRY_BYTES as u64 + 1)
+            .read_to_end(&mut content)
+            .map_err(|e| invalid(format!("{} in {}", e, name)))?;
+        if content.len() > MAX_ENTRY_BYTES {
+            return Err(invalid(format!("{} is larger than {} bytes", name, MAX_ENTRY_BYTES)));
+        }
+
+        entries.push((name, String::from_utf8_lossy(&content).into_owned()));
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_failed_step() {
+        let archive = zip(&[
+            ("0_build.txt", "full job log"),
+            ("build/1_Set up job.txt", "setup ok"),
+            ("build/3_Run cargo test.txt", "2024-01-01T00:00:00.1234567Z error[E0425]: cannot find value"),
+        ]);
+        let jobs = serde_json::json!({
+            "jobs": [{
+                "name": "build",
+                "conclusion": "failure",
+                "steps": [
+                    { "number": 1, "name": "Set up job", "conclusion": "success" },
+                    { "number": 3, "name": "Run cargo test", "conclusion": "failure" }
+                ]
+            }]
+        });
+
+        let logs = extract_failure_logs(&archive, &failed_steps(&jobs), 1000).unwrap();
+        assert_eq!(logs, "## build/3_Run cargo test\nerror[E0425]: cannot find value\n");
+    }
+
+    #[test]
+    fn test_falls_back_to_job_log() {
+        let archive = zip(&[("0_lint.txt", "job failed to start"), ("1_build.txt", "ok")]);
+        let failed = vec![FailedStep { job: "lint".to_string(), number: 0, name: String::new() }];
+
+        let logs = extract_failure_logs(&archive, &failed, 1000).unwrap();
+        assert_eq!(logs, "## 0_lint\njob failed to start\n");
+    }
+
+    #[test]
+    fn test_truncate_log_keeps_tail() {
+        let log = "first line\nsecond line\nerror: boom\n";
+        let truncated = truncate_log(log, 15);
+
+        assert!(truncated.ends_with("error: boom\n"));
+        assert!(!truncated.contains("first line"));
+        assert_eq!(truncate_log(log, 100), log);
+    }
+}
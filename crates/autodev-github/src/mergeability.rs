@@ -0,0 +1,299 @@
+use serde_json::json;
+use std::collections::HashMap;
+
+use crate::{CheckState, Error, GitHubClient, Mergeability, Repository, Result};
+
+/// Branch protection of the base branch that matters for merging
+struct Protection {
+    required_checks: Vec<String>,
+    required_approvals: u32,
+}
+
+impl GitHubClient {
+    async fn get_json(&self, url: &str) -> Result<serde_json::Value> {
+        self.retrying(|| self.client.get::<serde_json::Value, _, ()>(url, None)).await
+    }
+
+    /// Mergeable state, required checks and review state of a pull request
+    pub async fn get_pr_mergeability(&self, repo: &Repository, pr_number: u64) -> Result<Mergeability> {
+        let base_url = format!("/repos/{}/{}", repo.owner, repo.name);
+
+        let pr = self.get_json(&format!("{}/pulls/{}", base_url, pr_number)).await?;
+        let head_sha = pr["head"]["sha"]
+            .as_str()
+            .ok_or_else(|| Error::PullRequestNotFound(format!("PR #{} has no head commit", pr_number)))?;
+        let base_ref = pr["base"]["ref"].as_str().unwrap_or_default();
+
+        let protection = self.get_branch_protection(repo, base_ref).await;
+        let checks = self.get_commit_checks(repo, head_sha).await?;
+        let (approvals, changes_requested) = self.get_review_state(repo, pr_number).await?;
+
+        let required_checks = match &protection {
+            Some(protection) => protection
+                .required_checks
+                .iter()
+                .map(|name| {
+                    // 아직 보고되지 않은 필수 체크는 대기 중으로 취급
+                    checks.iter().find(|c| &c.name == name).cloned().unwrap_or(CheckState {
+                        name: name.clone(),
+                        conclusion: None,
+                    })
+                })
+                .collect(),
+            None => checks,
+        };
+
+        let merged = pr["merged"].as_bool().unwrap_or(false);
+        Ok(Mergeability {
+            merged,
+            closed: !merged && pr["state"].as_str() == Some("closed"),
+            mergeable: pr["mergeable"].as_bool(),
+            mergeable_state: pr["mergeable_state"].as_str().unwrap_or("unknown").to_string(),
+            draft: pr["draft"].as_bool().unwrap_or(false),
+            required_checks,
+            approvals,
+            required_approvals: protection.map(|p| p.required_approvals).unwrap_or(0),
+            changes_requested,
+        })
+    }
+
+    /// Protection rules of `branch`, or None when it has none or the token may not read them
+    async fn get_branch_protection(&self, repo: &Repository, branch: &str) -> Option<Protection> {
+        let url = format!("/repos/{}/{}/branches/{}/protection", repo.owner, repo.name, branch);
+
+        // 보호 규칙 조회는 관리자 권한이 필요해 실패하면 PR 상태만으로 판단
+        let protection = match self.get_json(&url).await {
+            Ok(protection) => protection,
+            Err(e) => {
+                tracing::debug!("Branch protection of {} is not available: {}", branch, e);
+                return None;
+            }
+        };
+
+        let required_checks = protection["required_status_checks"]["contexts"]
+            .as_array()
+            .map(|contexts| {
+                contexts
+                    .iter()
+                    .filter_map(|c| c.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let required_approvals = protection["required_pull_request_reviews"]
+            ["required_approving_review_count"]
+            .as_u64()
+            .unwrap_or(0) as u32;
+
+        Some(Protection {
+            required_checks,
+            required_approvals,
+        })
+    }
+
+    /// Check runs and commit statuses reported for `sha`
+    async fn get_commit_checks(&self, repo: &Repository, sha: &str) -> Result<Vec<CheckState>> {
+        let base_url = format!("/repos/{}/{}/commits/{}", repo.owner, repo.name, sha);
+        let mut checks = Vec::new();
+
+        let runs = self.get_json(&format!("{}/check-runs?per_page=100", base_url)).await?;
+        for run in runs["check_runs"].as_array().into_iter().flatten() {
+            let conclusion = match run["status"].as_str() {
+                Some("completed") => run["conclusion"].as_str().map(str::to_string),
+                _ => None,
+            };
+            checks.push(CheckState {
+                name: run["name"].as_str().unwrap_or_default().to_string(),
+                conclusion,
+            });
+        }
+
+        let status = self.get_json(&format!("{}/status", base_url)).await?;
+        for status in status["statuses"].as_array().into_iter().flatten() {
+            let conclusion = match status["state"].as_str() {
+                Some("success") => Some("success".to_string()),
+                Some("failure") | Some("error") => Some("failure".to_string()),
+                _ => None,
+            };
+            checks.push(CheckState {
+                name: status["context"].as_str().unwrap_or_default().to_string(),
+                conclusion,
+            });
+        }
+
+        Ok(checks)
+    }
+
+    /// Approvals and whether changes are requested, counting each reviewer's latest review
+    async fn get_review_state(&self, repo: &Repository, pr_number: u64) -> Result<(u32, bool)> {
+        let url = format!("/repos/{}/{}/pulls/{}/reviews?per_page=100", repo.owner, repo.name, pr_number);
+        let reviews = self.get_json(&url).await?;
+
+        let mut latest: HashMap<&str, &str> = HashMap::new();
+        for review in reviews.as_array().into_iter().flatten() {
+            let (Some(login), Some(state)) = (review["user"]["login"].as_str(), review["state"].as_str()) else {
+                continue;
+            };
+            // 코멘트만 남긴 리뷰는 이전 승인/변경 요청을 바꾸지 않음
+            if matches!(state, "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED") {
+                latest.insert(login, state);
+            }
+        }
+
+        let approvals = latest.values().filter(|s| **s == "APPROVED").count() as u32;
+        let changes_requested = latest.values().any(|s| *s == "CHANGES_REQUESTED");
+
+        Ok((approvals, changes_requested))
+    }
+
+    /// Convert a draft pull request to ready for review
+    ///
+    /// The REST API cannot do this, so it goes through GraphQL.
+    pub async fn mark_pr_ready_for_review(&self, repo: &Repository, pr_number: u64) -> Result<()> {
+        let pr = self
+            .get_json(&format!("/repos/{}/{}/pulls/{}", repo.owner, repo.name, pr_number))
+            .await?;
+        let node_id = pr["node_id"]
+            .as_str()
+            .ok_or_else(|| Error::PullRequestNotFound(format!("PR #{} has no node ID", pr_number)))?;
+
+        let query = json!({
+            "query": "mutation($id: ID!) { markPullRequestReadyForReview(input: {pullRequestId: $id}) { pullRequest { isDraft } } }",
+            "variables": { "id": node_id },
+        });
+        let url = self.host().graphql_url();
+        let pr_url = format!("/repos/{}/{}/pulls/{}", repo.owner, repo.name, pr_number);
+        let response: serde_json::Value = self
+            .creating(
+                || self.client.post(&url, Some(&query)),
+                || async {
+                    let pr = self.get_json(&pr_url).await?;
+                    Ok((pr["draft"].as_bool() == Some(false)).then(|| json!({})))
+                },
+            )
+            .await?;
+
+        if let Some(errors) = response["errors"].as_array().filter(|e| !e.is_empty()) {
+            return Err(Error::ApiError(format!(
+                "Failed to mark PR #{} ready for review: {}",
+                pr_number, errors[0]["message"]
+            )));
+        }
+
+        tracing::info!("PR #{} marked ready for review", pr_number);
+        Ok(())
+    }
+
+    pub async fn request_pr_reviewers(
+        &self,
+        repo: &Repository,
+        pr_number: u64,
+        reviewers: &[String],
+    ) -> Result<()> {
+        let url = format!("/repos/{}/{}/pulls/{}/requested_reviewers", repo.owner, repo.name, pr_number);
+        let body = json!({ "reviewers": reviewers });
+
+        let _: serde_json::Value = self
+            .creating(
+                || self.client.post(&url, Some(&body)),
+                || async {
+                    let requested = self.get_json(&url).await?;
+                    let logins: Vec<&str> = requested["users"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|user| user["login"].as_str())
+                        .collect();
+                    Ok(reviewers
+                        .iter()
+                        .all(|reviewer| logins.iter().any(|login| login.eq_ignore_ascii_case(reviewer)))
+                        .then(|| json!({})))
+                },
+            )
+            .await?;
+
+        tracing::info!("Requested review of PR #{} from {}", pr_number, reviewers.join(", "));
+        Ok(())
+    }
+
+    /// Post a review that only comments (approving or requesting changes on
+    /// PRs the same token opened is refused)
+    pub async fn submit_pr_review(&self, repo: &Repository, pr_number: u64, body: &str) -> Result<()> {
+        let url = format!("/repos/{}/{}/pulls/{}/reviews", repo.owner, repo.name, pr_number);
+        let review = json!({ "event": "COMMENT", "body": body });
+
+        let _: serde_json::Value = self
+            .creating(
+                || self.client.post(&url, Some(&review)),
+                || async {
+                    let reviews = self.get_json(&format!("{}?per_page=100", url)).await?;
+                    Ok(reviews
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .find(|review| review["body"].as_str() == Some(body))
+                        .cloned())
+                },
+            )
+            .await?;
+
+        tracing::info!("Posted review on PR #{}", pr_number);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GitHubHost, MergeBlocker};
+
+    #[tokio::test]
+    async fn test_get_pr_mergeability() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/o/r/pulls/3")
+            .with_status(200)
+            .with_body(r#"{"state": "open", "merged": false, "mergeable": true, "mergeable_state": "blocked", "draft": false,
+                "head": {"sha": "abc"}, "base": {"ref": "main"}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/branches/main/protection")
+            .with_status(200)
+            .with_body(r#"{"required_status_checks": {"contexts": ["ci"]},
+                "required_pull_request_reviews": {"required_approving_review_count": 1}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/commits/abc/check-runs")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"check_runs": [{"name": "ci", "status": "completed", "conclusion": "success"}]}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/commits/abc/status")
+            .with_status(200)
+            .with_body(r#"{"statuses": []}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/pulls/3/reviews")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"[{"user": {"login": "a"}, "state": "CHANGES_REQUESTED"},
+                {"user": {"login": "a"}, "state": "COMMENTED"}]"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let state = client
+            .get_pr_mergeability(&Repository::new("o".to_string(), "r".to_string()), 3)
+            .await
+            .unwrap();
+
+        assert_eq!(state.required_approvals, 1);
+        assert!(!state.merged && !state.closed);
+        assert!(state.changes_requested);
+        assert_eq!(state.blocker(), Some(MergeBlocker::ChangesRequested));
+    }
+}
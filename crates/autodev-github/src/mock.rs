@@ -0,0 +1,612 @@
+//! In-memory GitHub for tests (the `mock` feature)
+//!
+//! [`MockGitHubClient`] implements [`VcsProvider`], the interface the executor
+//! drives GitHub through (`ApiState::vcs` in the API), without any network:
+//! branches, pull requests, workflow runs, comments and checks live in memory
+//! and can be seeded and inspected by tests. Every call is recorded, and any
+//! operation can be made to fail with [`MockGitHubClient::fail`].
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{
+    Branch, CheckRunReport, DiffStat, GitHubHost, GitHubUrls, MergeOptions, Mergeability, PullRequest, RepoConfig,
+    Repository, VcsProvider, WorkflowStatus,
+};
+use autodev_vcs::{Error, Result};
+
+/// State of a mock pull request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockPullRequestState {
+    Open,
+    Closed,
+    Merged,
+}
+
+#[derive(Debug, Clone)]
+pub struct MockPullRequest {
+    pub number: u64,
+    pub title: String,
+    pub body: String,
+    pub head: String,
+    pub base: String,
+    /// Opened from a branch of another repository
+    pub from_fork: bool,
+    pub draft: bool,
+    pub state: MockPullRequestState,
+    pub reviewers: Vec<String>,
+    pub comments: Vec<String>,
+    pub reviews: Vec<String>,
+    /// Returned as its only diff chunk
+    pub diff: String,
+    pub diff_stat: DiffStat,
+    pub mergeability: Mergeability,
+    /// Become `mergeability` one per mergeability check, in order
+    pub queued_mergeability: Vec<Mergeability>,
+    pub merge_options: Option<MergeOptions>,
+    /// Set once the merge was reverted
+    pub reverted: bool,
+}
+
+/// A dispatched workflow run
+#[derive(Debug, Clone)]
+pub struct MockWorkflowRun {
+    pub id: u64,
+    pub workflow_file: String,
+    pub git_ref: String,
+    pub inputs: HashMap<String, String>,
+    pub status: WorkflowStatus,
+}
+
+#[derive(Debug, Clone)]
+struct MockBranch {
+    name: String,
+    base: String,
+    committed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct MockRepository {
+    branches: Vec<MockBranch>,
+    pull_requests: Vec<MockPullRequest>,
+    runs: Vec<MockWorkflowRun>,
+    checks: Vec<(String, CheckRunReport)>,
+    config: RepoConfig,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    repositories: HashMap<String, MockRepository>,
+    /// Numbers are shared by pull requests and workflow runs, like IDs on GitHub
+    next_id: u64,
+    failures: HashMap<String, String>,
+    calls: Vec<String>,
+}
+
+impl MockState {
+    fn repository(&mut self, repo: &Repository) -> &mut MockRepository {
+        self.repositories.entry(repo.full_name()).or_default()
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+/// GitHub held in memory
+#[derive(Debug, Default)]
+pub struct MockGitHubClient {
+    host: GitHubHost,
+    state: Mutex<MockState>,
+}
+
+impl MockGitHubClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a branch (e.g. the default branch tasks start from)
+    pub fn add_branch(&self, repo: &Repository, branch: &str) {
+        let mut state = self.state.lock().unwrap();
+        let repository = state.repository(repo);
+        repository.branches.retain(|b| b.name != branch);
+        repository.branches.push(MockBranch {
+            name: branch.to_string(),
+            base: String::new(),
+            committed_at: Some(chrono::Utc::now()),
+        });
+    }
+
+    /// Set the time of a branch's head commit
+    pub fn set_committed_at(&self, repo: &Repository, branch: &str, committed_at: chrono::DateTime<chrono::Utc>) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(b) = state.repository(repo).branches.iter_mut().find(|b| b.name == branch) {
+            b.committed_at = Some(committed_at);
+        }
+    }
+
+    /// Settings returned as the repository's `.autodev.yml`
+    pub fn set_config(&self, repo: &Repository, config: RepoConfig) {
+        self.state.lock().unwrap().repository(repo).config = config;
+    }
+
+    /// Make every call of `operation` (a [`VcsProvider`] method name) fail with `message`
+    pub fn fail(&self, operation: &str, message: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .failures
+            .insert(operation.to_string(), message.to_string());
+    }
+
+    /// Let `operation` succeed again
+    pub fn recover(&self, operation: &str) {
+        self.state.lock().unwrap().failures.remove(operation);
+    }
+
+    /// Calls made so far, as `operation:detail`
+    pub fn calls(&self) -> Vec<String> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// Branch names of `repo`
+    pub fn branches(&self, repo: &Repository) -> Vec<String> {
+        let mut state = self.state.lock().unwrap();
+        state.repository(repo).branches.iter().map(|b| b.name.clone()).collect()
+    }
+
+    /// Branch `branch` was created from
+    pub fn branch_base(&self, repo: &Repository, branch: &str) -> Option<String> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .repository(repo)
+            .branches
+            .iter()
+            .find(|b| b.name == branch)
+            .map(|b| b.base.clone())
+    }
+
+    pub fn pull_requests(&self, repo: &Repository) -> Vec<MockPullRequest> {
+        self.state.lock().unwrap().repository(repo).pull_requests.clone()
+    }
+
+    pub fn pull_request(&self, repo: &Repository, number: u64) -> Option<MockPullRequest> {
+        self.pull_requests(repo).into_iter().find(|pr| pr.number == number)
+    }
+
+    /// Change a pull request, e.g. its mergeability or diff
+    pub fn update_pull_request(&self, repo: &Repository, number: u64, update: impl FnOnce(&mut MockPullRequest)) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(pr) = state.repository(repo).pull_requests.iter_mut().find(|pr| pr.number == number) {
+            update(pr);
+        }
+    }
+
+    /// Report `states` from the next mergeability checks of a pull request,
+    /// one per check; the last one stays (e.g. checks pending, then passing)
+    pub fn queue_mergeability(&self, repo: &Repository, number: u64, states: Vec<Mergeability>) {
+        self.update_pull_request(repo, number, |pr| pr.queued_mergeability.extend(states));
+    }
+
+    pub fn workflow_runs(&self, repo: &Repository) -> Vec<MockWorkflowRun> {
+        self.state.lock().unwrap().repository(repo).runs.clone()
+    }
+
+    /// Finish a workflow run with `conclusion` ("success", "failure", ...)
+    pub fn complete_run(&self, repo: &Repository, run_id: u64, conclusion: &str) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(run) = state.repository(repo).runs.iter_mut().find(|run| run.id == run_id) {
+            run.status = WorkflowStatus {
+                status: "completed".to_string(),
+                conclusion: Some(conclusion.to_string()),
+            };
+        }
+    }
+
+    /// Checks reported so far, with the branch they were reported on
+    pub fn checks(&self, repo: &Repository) -> Vec<(String, CheckRunReport)> {
+        self.state.lock().unwrap().repository(repo).checks.clone()
+    }
+
+    /// Record a call and apply the failure set for its operation
+    fn call(&self, operation: &str, detail: impl std::fmt::Display) -> Result<std::sync::MutexGuard<'_, MockState>> {
+        let mut state = self.state.lock().unwrap();
+        state.calls.push(format!("{}:{}", operation, detail));
+        match state.failures.get(operation) {
+            Some(message) => Err(Error::ApiError(message.clone())),
+            None => Ok(state),
+        }
+    }
+}
+
+fn not_found(repo: &Repository, number: u64) -> Error {
+    Error::PullRequestNotFound(format!("{}#{}", repo.full_name(), number))
+}
+
+fn pull_request_mut<'a>(
+    state: &'a mut MockState,
+    repo: &Repository,
+    number: u64,
+) -> Result<&'a mut MockPullRequest> {
+    state
+        .repository(repo)
+        .pull_requests
+        .iter_mut()
+        .find(|pr| pr.number == number)
+        .ok_or_else(|| not_found(repo, number))
+}
+
+#[async_trait]
+impl VcsProvider for MockGitHubClient {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    fn merge_request_url(&self, repo: &Repository, number: u64) -> String {
+        format!("{}/pull/{}", repo.web_url(&self.host), number)
+    }
+
+    async fn trigger_pipeline(
+        &self,
+        repo: &Repository,
+        workflow_file: &str,
+        git_ref: &str,
+        inputs: HashMap<String, String>,
+    ) -> Result<u64> {
+        let mut state = self.call("trigger_pipeline", git_ref)?;
+        if !state.repository(repo).branches.iter().any(|b| b.name == git_ref) {
+            return Err(Error::WorkflowNotFound(format!(
+                "{} on {} of {}",
+                workflow_file,
+                git_ref,
+                repo.full_name()
+            )));
+        }
+
+        let id = state.next_id();
+        state.repository(repo).runs.push(MockWorkflowRun {
+            id,
+            workflow_file: workflow_file.to_string(),
+            git_ref: git_ref.to_string(),
+            inputs,
+            status: WorkflowStatus {
+                status: "queued".to_string(),
+                conclusion: None,
+            },
+        });
+        Ok(id)
+    }
+
+    async fn pipeline_status(&self, repo: &Repository, pipeline_id: u64) -> Result<WorkflowStatus> {
+        let mut state = self.call("pipeline_status", pipeline_id)?;
+        state
+            .repository(repo)
+            .runs
+            .iter()
+            .find(|run| run.id == pipeline_id)
+            .map(|run| run.status.clone())
+            .ok_or_else(|| Error::ApiError(format!("Workflow run {} not found", pipeline_id)))
+    }
+
+    async fn cancel_pipeline(&self, repo: &Repository, pipeline_id: u64) -> Result<()> {
+        let mut state = self.call("cancel_pipeline", pipeline_id)?;
+        let run = state
+            .repository(repo)
+            .runs
+            .iter_mut()
+            .find(|run| run.id == pipeline_id)
+            .ok_or_else(|| Error::ApiError(format!("Workflow run {} not found", pipeline_id)))?;
+        if run.status.status != "completed" {
+            run.status = WorkflowStatus {
+                status: "completed".to_string(),
+                conclusion: Some("cancelled".to_string()),
+            };
+        }
+        Ok(())
+    }
+
+    async fn create_branch(&self, repo: &Repository, branch: &str, from_branch: &str) -> Result<()> {
+        let mut state = self.call("create_branch", branch)?;
+        let repository = state.repository(repo);
+        if repository.branches.iter().any(|b| b.name == branch) {
+            return Err(Error::ApiError(format!("Reference already exists: {}", branch)));
+        }
+        if !repository.branches.iter().any(|b| b.name == from_branch) {
+            return Err(Error::ApiError(format!("Branch not found: {}", from_branch)));
+        }
+
+        repository.branches.push(MockBranch {
+            name: branch.to_string(),
+            base: from_branch.to_string(),
+            committed_at: Some(chrono::Utc::now()),
+        });
+        Ok(())
+    }
+
+    async fn delete_branch(&self, repo: &Repository, branch: &str) -> Result<()> {
+        let mut state = self.call("delete_branch", branch)?;
+        let repository = state.repository(repo);
+        let before = repository.branches.len();
+        repository.branches.retain(|b| b.name != branch);
+        if repository.branches.len() == before {
+            return Err(Error::ApiError(format!("Reference does not exist: {}", branch)));
+        }
+        Ok(())
+    }
+
+    async fn list_branches(&self, repo: &Repository, prefix: &str) -> Result<Vec<Branch>> {
+        let mut state = self.call("list_branches", prefix)?;
+        let repository = state.repository(repo);
+        Ok(repository
+            .branches
+            .iter()
+            .filter(|b| b.name.starts_with(prefix))
+            .map(|b| Branch {
+                name: b.name.clone(),
+                committed_at: b.committed_at,
+                open_merge_request: repository
+                    .pull_requests
+                    .iter()
+                    .find(|pr| pr.head == b.name && pr.state == MockPullRequestState::Open)
+                    .map(|pr| pr.number),
+            })
+            .collect())
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &Repository,
+        title: String,
+        body: String,
+        head: String,
+        base: String,
+        draft: bool,
+    ) -> Result<PullRequest> {
+        let mut state = self.call("create_merge_request", &head)?;
+        let repository = state.repository(repo);
+        for branch in [&head, &base] {
+            if !repository.branches.iter().any(|b| &b.name == branch) {
+                return Err(Error::ApiError(format!("Branch not found: {}", branch)));
+            }
+        }
+        if repository
+            .pull_requests
+            .iter()
+            .any(|pr| pr.head == head && pr.state == MockPullRequestState::Open)
+        {
+            return Err(Error::ApiError(format!("A pull request already exists for {}", head)));
+        }
+
+        let number = state.next_id();
+        state.repository(repo).pull_requests.push(MockPullRequest {
+            number,
+            title: title.clone(),
+            body,
+            head,
+            base,
+            from_fork: false,
+            draft,
+            state: MockPullRequestState::Open,
+            reviewers: Vec::new(),
+            comments: Vec::new(),
+            reviews: Vec::new(),
+            diff: String::new(),
+            diff_stat: DiffStat::default(),
+            mergeability: Mergeability {
+                draft,
+                ..Mergeability::ready()
+            },
+            queued_mergeability: Vec::new(),
+            merge_options: None,
+            reverted: false,
+        });
+
+        Ok(PullRequest {
+            number,
+            url: Some(self.merge_request_url(repo, number)),
+            title,
+        })
+    }
+
+    async fn merge_merge_request(&self, repo: &Repository, number: u64, options: &MergeOptions) -> Result<()> {
+        let mut state = self.call("merge_merge_request", number)?;
+        let pr = pull_request_mut(&mut state, repo, number)?;
+        if pr.state != MockPullRequestState::Open {
+            return Err(Error::ApiError(format!("Pull request {} is not open", number)));
+        }
+        if pr.draft || pr.mergeability.mergeable == Some(false) {
+            return Err(Error::ApiError(format!("Pull request {} is not mergeable", number)));
+        }
+
+        pr.state = MockPullRequestState::Merged;
+        pr.merge_options = Some(options.clone());
+        Ok(())
+    }
+
+    async fn close_merge_request(&self, repo: &Repository, number: u64) -> Result<()> {
+        let mut state = self.call("close_merge_request", number)?;
+        let pr = pull_request_mut(&mut state, repo, number)?;
+        if pr.state == MockPullRequestState::Open {
+            pr.state = MockPullRequestState::Closed;
+        }
+        Ok(())
+    }
+
+    async fn merge_request_branch(&self, repo: &Repository, number: u64) -> Result<String> {
+        let mut state = self.call("merge_request_branch", number)?;
+        Ok(pull_request_mut(&mut state, repo, number)?.head.clone())
+    }
+
+    async fn merge_request_from_fork(&self, repo: &Repository, number: u64) -> Result<bool> {
+        let mut state = self.call("merge_request_from_fork", number)?;
+        Ok(pull_request_mut(&mut state, repo, number)?.from_fork)
+    }
+
+    async fn merge_request_mergeability(&self, repo: &Repository, number: u64) -> Result<Mergeability> {
+        let mut state = self.call("merge_request_mergeability", number)?;
+        let pr = pull_request_mut(&mut state, repo, number)?;
+        if !pr.queued_mergeability.is_empty() {
+            pr.mergeability = pr.queued_mergeability.remove(0);
+        }
+        Ok(Mergeability {
+            merged: pr.state == MockPullRequestState::Merged,
+            closed: pr.state == MockPullRequestState::Closed,
+            ..pr.mergeability.clone()
+        })
+    }
+
+    async fn mark_ready_for_review(&self, repo: &Repository, number: u64) -> Result<()> {
+        let mut state = self.call("mark_ready_for_review", number)?;
+        let pr = pull_request_mut(&mut state, repo, number)?;
+        pr.draft = false;
+        pr.mergeability.draft = false;
+        Ok(())
+    }
+
+    async fn request_reviewers(&self, repo: &Repository, number: u64, reviewers: &[String]) -> Result<()> {
+        let mut state = self.call("request_reviewers", number)?;
+        pull_request_mut(&mut state, repo, number)?
+            .reviewers
+            .extend(reviewers.iter().cloned());
+        Ok(())
+    }
+
+    async fn merge_request_diff(&self, repo: &Repository, number: u64, _max_chars: usize) -> Result<Vec<String>> {
+        let mut state = self.call("merge_request_diff", number)?;
+        let diff = &pull_request_mut(&mut state, repo, number)?.diff;
+        Ok(if diff.is_empty() { Vec::new() } else { vec![diff.clone()] })
+    }
+
+    async fn merge_request_diff_stat(&self, repo: &Repository, number: u64) -> Result<DiffStat> {
+        let mut state = self.call("merge_request_diff_stat", number)?;
+        Ok(pull_request_mut(&mut state, repo, number)?.diff_stat)
+    }
+
+    async fn update_merge_request_body(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        let mut state = self.call("update_merge_request_body", number)?;
+        pull_request_mut(&mut state, repo, number)?.body = body.to_string();
+        Ok(())
+    }
+
+    async fn find_merge_request_by_branch(&self, repo: &Repository, branch: &str) -> Result<Option<u64>> {
+        let mut state = self.call("find_merge_request_by_branch", branch)?;
+        Ok(state
+            .repository(repo)
+            .pull_requests
+            .iter()
+            .rev()
+            .find(|pr| pr.head == branch)
+            .map(|pr| pr.number))
+    }
+
+    async fn is_merge_request_merged(&self, repo: &Repository, number: u64) -> Result<bool> {
+        let mut state = self.call("is_merge_request_merged", number)?;
+        Ok(pull_request_mut(&mut state, repo, number)?.state == MockPullRequestState::Merged)
+    }
+
+    async fn comment(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        let mut state = self.call("comment", number)?;
+        pull_request_mut(&mut state, repo, number)?.comments.push(body.to_string());
+        Ok(())
+    }
+
+    async fn submit_review(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        let mut state = self.call("submit_review", number)?;
+        pull_request_mut(&mut state, repo, number)?.reviews.push(body.to_string());
+        Ok(())
+    }
+
+    async fn load_repo_config(&self, repo: &Repository) -> Result<RepoConfig> {
+        let mut state = self.call("load_repo_config", repo)?;
+        Ok(state.repository(repo).config.clone())
+    }
+
+    async fn report_check(&self, repo: &Repository, branch: &str, report: &CheckRunReport) -> Result<()> {
+        let mut state = self.call("report_check", branch)?;
+        state.repository(repo).checks.push((branch.to_string(), report.clone()));
+        Ok(())
+    }
+
+    async fn check_pipeline_setup(&self, repo: &Repository) -> Result<()> {
+        self.call("check_pipeline_setup", repo).map(drop)
+    }
+
+    async fn revert_merge_request(&self, repo: &Repository, branch: &str, number: u64) -> Result<Option<String>> {
+        let mut state = self.call("revert_merge_request", format!("{}#{}", branch, number))?;
+        if !state.repository(repo).branches.iter().any(|b| b.name == branch) {
+            return Err(Error::ApiError(format!("Branch not found: {}", branch)));
+        }
+        let pr = pull_request_mut(&mut state, repo, number)?;
+        if pr.state != MockPullRequestState::Merged {
+            return Err(Error::ApiError(format!("PR #{} is not merged", number)));
+        }
+        if pr.reverted {
+            return Ok(None);
+        }
+        pr.reverted = true;
+        let sha = format!("{:040x}", state.next_id());
+        Ok(Some(sha))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autodev_core::MergeMethod;
+
+    #[tokio::test]
+    async fn test_pull_request_lifecycle() {
+        let github = MockGitHubClient::new();
+        let repo = Repository::new("o".to_string(), "r".to_string());
+        github.add_branch(&repo, "main");
+
+        github.create_branch(&repo, "autodev/t1", "main").await.unwrap();
+        assert!(github.create_branch(&repo, "autodev/t1", "main").await.is_err());
+
+        let pr = github
+            .create_merge_request(&repo, "T1".into(), "".into(), "autodev/t1".into(), "main".into(), true)
+            .await
+            .unwrap();
+        assert_eq!(pr.url.as_deref(), Some(format!("https://github.com/o/r/pull/{}", pr.number).as_str()));
+        assert_eq!(github.find_merge_request_by_branch(&repo, "autodev/t1").await.unwrap(), Some(pr.number));
+
+        let options = MergeOptions::new(MergeMethod::Squash);
+        // Drafts cannot be merged
+        assert!(github.merge_merge_request(&repo, pr.number, &options).await.is_err());
+        github.mark_ready_for_review(&repo, pr.number).await.unwrap();
+
+        github.fail("merge_merge_request", "Base branch was modified");
+        assert!(github.merge_merge_request(&repo, pr.number, &options).await.is_err());
+        github.recover("merge_merge_request");
+        github.merge_merge_request(&repo, pr.number, &options).await.unwrap();
+
+        assert!(github.is_merge_request_merged(&repo, pr.number).await.unwrap());
+        assert_eq!(github.calls().last().map(String::as_str), Some(format!("is_merge_request_merged:{}", pr.number).as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_workflow_runs() {
+        let github = MockGitHubClient::new();
+        let repo = Repository::new("o".to_string(), "r".to_string());
+        github.add_branch(&repo, "main");
+
+        // Workflows are dispatched on an existing branch
+        assert!(github.trigger_pipeline(&repo, "autodev.yml", "autodev/t1", HashMap::new()).await.is_err());
+
+        let run_id = github.trigger_pipeline(&repo, "autodev.yml", "main", HashMap::new()).await.unwrap();
+        assert_eq!(github.pipeline_status(&repo, run_id).await.unwrap().status, "queued");
+
+        github.cancel_pipeline(&repo, run_id).await.unwrap();
+        let status = github.pipeline_status(&repo, run_id).await.unwrap();
+        assert_eq!(status.conclusion.as_deref(), Some("cancelled"));
+
+        // Finished runs keep their conclusion
+        let run_id = github.trigger_pipeline(&repo, "autodev.yml", "main", HashMap::new()).await.unwrap();
+        github.complete_run(&repo, run_id, "success");
+        github.cancel_pipeline(&repo, run_id).await.unwrap();
+        let status = github.pipeline_status(&repo, run_id).await.unwrap();
+        assert_eq!(status.conclusion.as_deref(), Some("success"));
+    }
+}
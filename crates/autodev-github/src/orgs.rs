@@ -0,0 +1,274 @@
+//! Organizations (tenants) sharing one AutoDev instance
+//!
+//! An organization is the owner of a repository (GitHub organization or user),
+//! compared case-insensitively. Organizations listed in `AUTODEV_ORGS` get
+//! their own credentials; every other repository uses the default client.
+//!
+//! ```text
+//! AUTODEV_ORGS=acme,globex
+//! AUTODEV_ORG_ACME_GITHUB_TOKEN=ghp_...        # personal access token
+//! AUTODEV_ORG_GLOBEX_INSTALLATION_ID=12345678  # installation of the GitHub App
+//! ```
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{
+    Branch, CheckRunReport, DiffStat, Error, GitHubAppAuth, GitHubClient, MergeOptions, Mergeability, PullRequest,
+    RepoConfig, Repository, Result, VcsProvider, WorkflowStatus,
+};
+
+pub const ORGS_ENV: &str = "AUTODEV_ORGS";
+
+/// Normalized organization ID of a repository owner
+pub fn org_id(owner: &str) -> String {
+    owner.trim().to_lowercase()
+}
+
+/// Environment variable holding a setting of one organization
+/// (`my-org`, `GITHUB_TOKEN` → `AUTODEV_ORG_MY_ORG_GITHUB_TOKEN`)
+pub fn org_env_var(org: &str, setting: &str) -> String {
+    let org: String = org
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("AUTODEV_ORG_{}_{}", org, setting)
+}
+
+/// GitHub clients per organization
+pub struct OrgClients {
+    default: Arc<GitHubClient>,
+    orgs: HashMap<String, Arc<GitHubClient>>,
+}
+
+impl OrgClients {
+    /// Every repository uses `default`
+    pub fn new(default: Arc<GitHubClient>) -> Self {
+        Self {
+            default,
+            orgs: HashMap::new(),
+        }
+    }
+
+    pub fn with_org(mut self, org: &str, client: GitHubClient) -> Self {
+        self.orgs.insert(org_id(org), Arc::new(client));
+        self
+    }
+
+    /// Organizations from AUTODEV_ORGS with a token or GitHub App installation each
+    pub fn from_env(default: Arc<GitHubClient>) -> Result<Self> {
+        let mut clients = Self::new(default);
+        let Ok(orgs) = std::env::var(ORGS_ENV) else {
+            return Ok(clients);
+        };
+
+        let app_auth = GitHubAppAuth::from_env()?;
+        for org in orgs.split(',').map(str::trim).filter(|org| !org.is_empty()) {
+            let token_var = org_env_var(org, "GITHUB_TOKEN");
+            let installation_var = org_env_var(org, "INSTALLATION_ID");
+
+            let client = match (std::env::var(&token_var), std::env::var(&installation_var)) {
+                (Ok(token), _) if !token.trim().is_empty() => GitHubClient::new(token)?,
+                (_, Ok(id)) => {
+                    let id: u64 = id
+                        .trim()
+                        .parse()
+                        .map_err(|_| Error::AuthError(format!("Invalid {}: {}", installation_var, id)))?;
+                    let Some(ref app_auth) = app_auth else {
+                        return Err(Error::AuthError(format!(
+                            "{} needs GITHUB_APP_ID and GITHUB_APP_PRIVATE_KEY_PATH",
+                            installation_var
+                        )));
+                    };
+                    GitHubClient::from_app(app_auth, id)?
+                }
+                _ => {
+                    return Err(Error::AuthError(format!(
+                        "Organization '{}' in {} needs {} or {}",
+                        org, ORGS_ENV, token_var, installation_var
+                    )))
+                }
+            };
+
+            tracing::info!("Using separate GitHub credentials for organization {}", org);
+            clients = clients.with_org(org, client);
+        }
+
+        Ok(clients)
+    }
+
+    /// Client used for repositories outside the configured organizations
+    pub fn default_client(&self) -> &Arc<GitHubClient> {
+        &self.default
+    }
+
+    /// Organizations with their own credentials and their clients, by ID
+    pub fn orgs(&self) -> Vec<(&str, &Arc<GitHubClient>)> {
+        let mut orgs: Vec<(&str, &Arc<GitHubClient>)> =
+            self.orgs.iter().map(|(id, client)| (id.as_str(), client)).collect();
+        orgs.sort_unstable_by_key(|(id, _)| *id);
+        orgs
+    }
+
+    /// Client holding the credentials of the repository's organization
+    pub fn client(&self, repo: &Repository) -> &Arc<GitHubClient> {
+        self.orgs.get(&org_id(&repo.owner)).unwrap_or(&self.default)
+    }
+}
+
+#[async_trait]
+impl VcsProvider for OrgClients {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    fn merge_request_url(&self, repo: &Repository, number: u64) -> String {
+        self.client(repo).merge_request_url(repo, number)
+    }
+
+    async fn trigger_pipeline(
+        &self,
+        repo: &Repository,
+        workflow_file: &str,
+        git_ref: &str,
+        inputs: HashMap<String, String>,
+    ) -> autodev_vcs::Result<u64> {
+        self.client(repo).trigger_pipeline(repo, workflow_file, git_ref, inputs).await
+    }
+
+    async fn pipeline_status(&self, repo: &Repository, pipeline_id: u64) -> autodev_vcs::Result<WorkflowStatus> {
+        self.client(repo).pipeline_status(repo, pipeline_id).await
+    }
+
+    async fn cancel_pipeline(&self, repo: &Repository, pipeline_id: u64) -> autodev_vcs::Result<()> {
+        self.client(repo).cancel_pipeline(repo, pipeline_id).await
+    }
+
+    async fn create_branch(&self, repo: &Repository, branch: &str, from_branch: &str) -> autodev_vcs::Result<()> {
+        VcsProvider::create_branch(self.client(repo).as_ref(), repo, branch, from_branch).await
+    }
+
+    async fn delete_branch(&self, repo: &Repository, branch: &str) -> autodev_vcs::Result<()> {
+        VcsProvider::delete_branch(self.client(repo).as_ref(), repo, branch).await
+    }
+
+    async fn list_branches(&self, repo: &Repository, prefix: &str) -> autodev_vcs::Result<Vec<Branch>> {
+        VcsProvider::list_branches(self.client(repo).as_ref(), repo, prefix).await
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &Repository,
+        title: String,
+        body: String,
+        head: String,
+        base: String,
+        draft: bool,
+    ) -> autodev_vcs::Result<PullRequest> {
+        self.client(repo)
+            .create_merge_request(repo, title, body, head, base, draft)
+            .await
+    }
+
+    async fn merge_merge_request(&self, repo: &Repository, number: u64, options: &MergeOptions) -> autodev_vcs::Result<()> {
+        self.client(repo).merge_merge_request(repo, number, options).await
+    }
+
+    async fn close_merge_request(&self, repo: &Repository, number: u64) -> autodev_vcs::Result<()> {
+        self.client(repo).close_merge_request(repo, number).await
+    }
+
+    async fn merge_request_branch(&self, repo: &Repository, number: u64) -> autodev_vcs::Result<String> {
+        self.client(repo).merge_request_branch(repo, number).await
+    }
+
+    async fn merge_request_from_fork(&self, repo: &Repository, number: u64) -> autodev_vcs::Result<bool> {
+        self.client(repo).merge_request_from_fork(repo, number).await
+    }
+
+    async fn merge_request_mergeability(&self, repo: &Repository, number: u64) -> autodev_vcs::Result<Mergeability> {
+        self.client(repo).merge_request_mergeability(repo, number).await
+    }
+
+    async fn mark_ready_for_review(&self, repo: &Repository, number: u64) -> autodev_vcs::Result<()> {
+        self.client(repo).mark_ready_for_review(repo, number).await
+    }
+
+    async fn request_reviewers(&self, repo: &Repository, number: u64, reviewers: &[String]) -> autodev_vcs::Result<()> {
+        self.client(repo).request_reviewers(repo, number, reviewers).await
+    }
+
+    async fn merge_request_diff(&self, repo: &Repository, number: u64, max_chars: usize) -> autodev_vcs::Result<Vec<String>> {
+        self.client(repo).merge_request_diff(repo, number, max_chars).await
+    }
+
+    async fn merge_request_diff_stat(&self, repo: &Repository, number: u64) -> autodev_vcs::Result<DiffStat> {
+        self.client(repo).merge_request_diff_stat(repo, number).await
+    }
+
+    async fn update_merge_request_body(&self, repo: &Repository, number: u64, body: &str) -> autodev_vcs::Result<()> {
+        self.client(repo).update_merge_request_body(repo, number, body).await
+    }
+
+    async fn find_merge_request_by_branch(&self, repo: &Repository, branch: &str) -> autodev_vcs::Result<Option<u64>> {
+        self.client(repo).find_merge_request_by_branch(repo, branch).await
+    }
+
+    async fn is_merge_request_merged(&self, repo: &Repository, number: u64) -> autodev_vcs::Result<bool> {
+        self.client(repo).is_merge_request_merged(repo, number).await
+    }
+
+    async fn comment(&self, repo: &Repository, number: u64, body: &str) -> autodev_vcs::Result<()> {
+        self.client(repo).comment(repo, number, body).await
+    }
+
+    async fn submit_review(&self, repo: &Repository, number: u64, body: &str) -> autodev_vcs::Result<()> {
+        self.client(repo).submit_review(repo, number, body).await
+    }
+
+    async fn load_repo_config(&self, repo: &Repository) -> autodev_vcs::Result<RepoConfig> {
+        VcsProvider::load_repo_config(self.client(repo).as_ref(), repo).await
+    }
+
+    async fn report_check(&self, repo: &Repository, branch: &str, report: &CheckRunReport) -> autodev_vcs::Result<()> {
+        self.client(repo).report_check(repo, branch, report).await
+    }
+
+    async fn check_pipeline_setup(&self, repo: &Repository) -> autodev_vcs::Result<()> {
+        self.client(repo).check_pipeline_setup(repo).await
+    }
+
+    async fn revert_merge_request(
+        &self,
+        repo: &Repository,
+        branch: &str,
+        number: u64,
+    ) -> autodev_vcs::Result<Option<String>> {
+        VcsProvider::revert_merge_request(self.client(repo).as_ref(), repo, branch, number).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_org_env_var() {
+        assert_eq!(org_env_var("acme", "GITHUB_TOKEN"), "AUTODEV_ORG_ACME_GITHUB_TOKEN");
+        assert_eq!(org_env_var("My-Org.io", "INSTALLATION_ID"), "AUTODEV_ORG_MY_ORG_IO_INSTALLATION_ID");
+    }
+
+    #[tokio::test]
+    async fn test_client_by_owner() {
+        let default = Arc::new(GitHubClient::new("default".into()).unwrap());
+        let clients = OrgClients::new(default.clone()).with_org("Acme", GitHubClient::new("acme".into()).unwrap());
+
+        let acme = Repository::new("ACME".into(), "app".into());
+        let other = Repository::new("globex".into(), "app".into());
+        assert!(!Arc::ptr_eq(clients.client(&acme), &default));
+        assert!(Arc::ptr_eq(clients.client(&other), &default));
+        assert_eq!(clients.orgs().iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec!["acme"]);
+    }
+}
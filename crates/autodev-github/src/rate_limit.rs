@@ -0,0 +1,147 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::Error;
+
+/// Core REST API quota, read from the X-RateLimit-* headers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitStatus {
+    pub limit: u64,
+    pub remaining: u64,
+    pub used: u64,
+    pub reset_at: DateTime<Utc>,
+}
+
+impl RateLimitStatus {
+    pub fn from_headers(headers: &http::HeaderMap) -> Option<Self> {
+        let header = |name: &str| -> Option<u64> {
+            headers.get(name)?.to_str().ok()?.trim().parse().ok()
+        };
+
+        let limit = header("x-ratelimit-limit")?;
+        let remaining = header("x-ratelimit-remaining")?;
+        let reset = header("x-ratelimit-reset")?;
+
+        Some(Self {
+            limit,
+            remaining,
+            used: header("x-ratelimit-used").unwrap_or(limit.saturating_sub(remaining)),
+            reset_at: Utc.timestamp_opt(reset as i64, 0).single()?,
+        })
+    }
+
+    /// No requests left until the window resets
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0 && self.reset_at > Utc::now()
+    }
+
+    /// Time until the quota resets
+    pub fn time_until_reset(&self) -> Duration {
+        (self.reset_at - Utc::now()).to_std().unwrap_or_default()
+    }
+}
+
+/// How GitHubClient retries throttled and failed requests
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after server errors and secondary rate limits
+    pub max_retries: u32,
+    /// First backoff, doubled on every retry
+    pub base_delay: Duration,
+    /// Longest single wait (including waiting for a rate limit reset)
+    pub max_wait: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(2),
+            max_wait: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Exponential backoff before retry `attempt` (0-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_wait)
+    }
+}
+
+/// Why a failed request may succeed later
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Throttle {
+    /// Primary quota used up: wait for the reset
+    RateLimited,
+    /// Secondary (abuse) limit: back off
+    SecondaryRateLimited,
+    /// 5xx or a garbled response
+    ServerError,
+}
+
+/// Classify an error by GitHub's message (octocrab drops the status code)
+pub(crate) fn classify(error: &Error) -> Option<Throttle> {
+    let Error::Octocrab(error) = error else {
+        return None;
+    };
+
+    match error {
+        octocrab::Error::GitHub { source, .. } => {
+            let message = source.message.to_lowercase();
+
+            if message.contains("secondary rate limit") || message.contains("abuse") {
+                Some(Throttle::SecondaryRateLimited)
+            } else if message.contains("rate limit") {
+                Some(Throttle::RateLimited)
+            } else if ["server error", "bad gateway", "service unavailable", "gateway timeout"]
+                .iter()
+                .any(|m| message.contains(m))
+            {
+                Some(Throttle::ServerError)
+            } else {
+                None
+            }
+        }
+        // 5xx pages from proxies are HTML, which fails to parse as a GitHub error
+        octocrab::Error::Serde { .. } | octocrab::Error::Hyper { .. } | octocrab::Error::Service { .. } => {
+            Some(Throttle::ServerError)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("x-ratelimit-limit", "5000".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "4102444800".parse().unwrap());
+
+        let status = RateLimitStatus::from_headers(&headers).unwrap();
+        assert_eq!(status.used, 5000);
+        assert!(status.is_exhausted());
+
+        assert!(RateLimitStatus::from_headers(&http::HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_secs(1),
+            max_wait: Duration::from_secs(5),
+        };
+
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(4));
+        assert_eq!(policy.backoff(3), Duration::from_secs(5));
+    }
+}
@@ -1,50 +1,40 @@
-use serde::{Deserialize, Serialize};
+use crate::{GitHubHost, Repository};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Repository {
-    pub owner: String,
-    pub name: String,
-    pub branch: String,
-}
+/// GitHub web URLs of a repository
+///
+/// They follow GITHUB_API_URL, so they point at the GHES instance when one is configured.
+pub trait GitHubUrls {
+    fn clone_url(&self) -> String;
 
-impl Repository {
-    pub fn new(owner: String, name: String) -> Self {
-        Self {
-            owner,
-            name,
-            branch: "main".to_string(),
-        }
-    }
+    fn ssh_url(&self) -> String;
 
-    pub fn with_branch(mut self, branch: String) -> Self {
-        self.branch = branch;
-        self
-    }
+    fn https_url(&self) -> String;
 
-    pub fn full_name(&self) -> String {
-        format!("{}/{}", self.owner, self.name)
-    }
+    fn actions_url(&self) -> String;
 
-    pub fn clone_url(&self) -> String {
-        format!("https://github.com/{}/{}.git", self.owner, self.name)
+    /// Repository page on a specific GitHub instance
+    fn web_url(&self, host: &GitHubHost) -> String;
+}
+
+impl GitHubUrls for Repository {
+    fn clone_url(&self) -> String {
+        format!("{}.git", self.https_url())
     }
 
-    pub fn ssh_url(&self) -> String {
-        format!("git@github.com:{}/{}.git", self.owner, self.name)
+    fn ssh_url(&self) -> String {
+        format!("git@{}:{}/{}.git", GitHubHost::from_env().hostname(), self.owner, self.name)
     }
 
-    pub fn https_url(&self) -> String {
-        format!("https://github.com/{}/{}", self.owner, self.name)
+    fn https_url(&self) -> String {
+        self.web_url(&GitHubHost::from_env())
     }
 
-    pub fn actions_url(&self) -> String {
-        format!("https://github.com/{}/{}/actions", self.owner, self.name)
+    fn actions_url(&self) -> String {
+        format!("{}/actions", self.https_url())
     }
-}
 
-impl std::fmt::Display for Repository {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.full_name())
+    fn web_url(&self, host: &GitHubHost) -> String {
+        format!("{}/{}/{}", host.web_url(), self.owner, self.name)
     }
 }
 
@@ -52,14 +42,6 @@ impl std::fmt::Display for Repository {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_repository_creation() {
-        let repo = Repository::new("owner".to_string(), "name".to_string());
-        assert_eq!(repo.owner, "owner");
-        assert_eq!(repo.name, "name");
-        assert_eq!(repo.branch, "main");
-    }
-
     #[test]
     fn test_repository_urls() {
         let repo = Repository::new("myorg".to_string(), "myrepo".to_string());
@@ -71,9 +53,10 @@ mod tests {
     }
 
     #[test]
-    fn test_with_branch() {
-        let repo = Repository::new("owner".to_string(), "name".to_string())
-            .with_branch("develop".to_string());
-        assert_eq!(repo.branch, "develop");
+    fn test_enterprise_web_url() {
+        let repo = Repository::new("myorg".to_string(), "myrepo".to_string());
+        let host = GitHubHost::new("https://ghe.example.com/api/v3");
+
+        assert_eq!(repo.web_url(&host), "https://ghe.example.com/myorg/myrepo");
     }
-}
\ No newline at end of file
+}
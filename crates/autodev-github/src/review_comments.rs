@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+
+use crate::{GitHubClient, Repository, Result};
+
+/// Inline review comment on a pull request diff
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub id: u64,
+    /// Review the comment was submitted with
+    #[serde(default)]
+    pub pull_request_review_id: Option<u64>,
+    /// Root comment of the thread, for replies
+    #[serde(default)]
+    pub in_reply_to_id: Option<u64>,
+    pub path: String,
+    /// Line in the new version of the file; None once the line is outdated
+    #[serde(default)]
+    pub line: Option<u64>,
+    #[serde(default)]
+    pub original_line: Option<u64>,
+    pub body: String,
+    /// Hunk the comment was made on
+    #[serde(default)]
+    pub diff_hunk: String,
+    #[serde(default)]
+    pub user: Option<ReviewCommentUser>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewCommentUser {
+    pub login: String,
+}
+
+/// A review comment and the replies under it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewThread {
+    pub root: ReviewComment,
+    pub replies: Vec<ReviewComment>,
+}
+
+impl ReviewThread {
+    /// Line the thread refers to, falling back to the original line of outdated comments
+    pub fn line(&self) -> Option<u64> {
+        self.root.line.or(self.root.original_line)
+    }
+
+    /// The thread as text for an AI prompt: location, hunk and every comment
+    pub fn to_prompt(&self) -> String {
+        let location = match self.line() {
+            Some(line) => format!("{}:{}", self.root.path, line),
+            None => self.root.path.clone(),
+        };
+
+        let comments: Vec<String> = std::iter::once(&self.root)
+            .chain(&self.replies)
+            .map(|c| {
+                let author = c.user.as_ref().map_or("reviewer", |u| u.login.as_str());
+                format!("{}: {}", author, c.body.trim())
+            })
+            .collect();
+
+        format!("{}\n```diff\n{}\n```\n{}", location, self.root.diff_hunk.trim_end(), comments.join("\n"))
+    }
+}
+
+/// Group comments into threads, keeping the order threads were started in
+pub fn review_threads(comments: &[ReviewComment]) -> Vec<ReviewThread> {
+    let mut threads: Vec<ReviewThread> = comments
+        .iter()
+        .filter(|c| c.in_reply_to_id.is_none())
+        .map(|c| ReviewThread {
+            root: c.clone(),
+            replies: Vec::new(),
+        })
+        .collect();
+
+    for reply in comments.iter().filter(|c| c.in_reply_to_id.is_some()) {
+        if let Some(thread) = threads.iter_mut().find(|t| Some(t.root.id) == reply.in_reply_to_id) {
+            thread.replies.push(reply.clone());
+        }
+    }
+
+    threads
+}
+
+/// Threads by the file they comment on
+pub fn threads_by_file(threads: Vec<ReviewThread>) -> BTreeMap<String, Vec<ReviewThread>> {
+    let mut files: BTreeMap<String, Vec<ReviewThread>> = BTreeMap::new();
+    for thread in threads {
+        files.entry(thread.root.path.clone()).or_default().push(thread);
+    }
+    files
+}
+
+impl GitHubClient {
+    /// Inline review comments of a pull request, replies included
+    pub async fn get_pr_review_comments(&self, repo: &Repository, pr_number: u64) -> Result<Vec<ReviewComment>> {
+        const PER_PAGE: usize = 100;
+
+        let mut comments = Vec::new();
+
+        for page in 1..=30 {
+            let url = format!(
+                "/repos/{}/{}/pulls/{}/comments?per_page={}&page={}",
+                repo.owner, repo.name, pr_number, PER_PAGE, page
+            );
+            let batch: Vec<ReviewComment> = self.retrying(|| self.client.get(&url, None::<&()>)).await?;
+
+            let done = batch.len() < PER_PAGE;
+            comments.extend(batch);
+            if done {
+                break;
+            }
+        }
+
+        Ok(comments)
+    }
+
+    /// Reply in the thread of the review comment `comment_id`
+    pub async fn reply_to_review_comment(
+        &self,
+        repo: &Repository,
+        pr_number: u64,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<()> {
+        let url = format!(
+            "/repos/{}/{}/pulls/{}/comments/{}/replies",
+            repo.owner, repo.name, pr_number, comment_id
+        );
+        let reply = json!({ "body": body });
+        let comments_url = format!("/repos/{}/{}/pulls/{}/comments", repo.owner, repo.name, pr_number);
+        let since = (chrono::Utc::now() - chrono::Duration::seconds(60))
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+        let params = [("since", since.as_str()), ("per_page", "100")];
+
+        let _: serde_json::Value = self
+            .creating(
+                || self.client.post(&url, Some(&reply)),
+                || async {
+                    let comments: Vec<serde_json::Value> =
+                        self.retrying(|| self.client.get(&comments_url, Some(&params))).await?;
+                    Ok(comments
+                        .into_iter()
+                        .find(|c| c["in_reply_to_id"].as_u64() == Some(comment_id) && c["body"].as_str() == Some(body)))
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: u64, reply_to: Option<u64>, path: &str, body: &str) -> ReviewComment {
+        ReviewComment {
+            id,
+            pull_request_review_id: Some(1),
+            in_reply_to_id: reply_to,
+            path: path.to_string(),
+            line: Some(10),
+            original_line: Some(10),
+            body: body.to_string(),
+            diff_hunk: "@@ -1,3 +1,3 @@".to_string(),
+            user: None,
+        }
+    }
+
+    #[test]
+    fn test_threads_by_file() {
+        let comments = vec![
+            comment(1, None, "src/b.rs", "Rename this"),
+            comment(2, None, "src/a.rs", "Handle the error"),
+            comment(3, Some(1), "src/b.rs", "Also the caller"),
+            // Reply to a comment that is not listed is dropped
+            comment(4, Some(99), "src/a.rs", "orphan"),
+        ];
+
+        let threads = review_threads(&comments);
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].replies.len(), 1);
+        assert!(threads[0].to_prompt().contains("src/b.rs:10"));
+        assert!(threads[0].to_prompt().contains("reviewer: Also the caller"));
+
+        let files = threads_by_file(threads);
+        assert_eq!(files.keys().collect::<Vec<_>>(), ["src/a.rs", "src/b.rs"]);
+    }
+}
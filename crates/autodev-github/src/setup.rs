@@ -0,0 +1,263 @@
+use base64::Engine;
+use serde_json::json;
+
+use crate::{Error, GitHubClient, Repository, Result, SUBTASK_WORKFLOW_FILE, WORKFLOW_FILE};
+
+/// Install missing workflows before running a task instead of failing
+pub const AUTO_PROVISION_ENV: &str = "AUTODEV_AUTO_PROVISION_WORKFLOWS";
+
+const TEMPLATE: &str = include_str!("../../../templates/autodev.yml");
+
+/// Callback URL baked into the template as the `autodev_server_url` default
+const TEMPLATE_SERVER_URL: &str = "http://localhost:3000";
+
+pub fn workflow_path(file: &str) -> String {
+    format!(".github/workflows/{}", file)
+}
+
+/// Workflow files AutoDev dispatches, rendered for `server_url`
+pub fn workflow_files(server_url: Option<&str>) -> Vec<(&'static str, String)> {
+    let template = match server_url {
+        Some(url) => TEMPLATE.replace(
+            &format!("default: \"{}\"", TEMPLATE_SERVER_URL),
+            &format!("default: \"{}\"", url.trim_end_matches('/')),
+        ),
+        None => TEMPLATE.to_string(),
+    };
+
+    // 두 워크플로는 입력이 같고 Actions 화면에서 구분되도록 이름만 다름
+    let subtask = template.replacen("name: 'AutoDev'", "name: 'AutoDev Subtask'", 1);
+
+    vec![(WORKFLOW_FILE, template), (SUBTASK_WORKFLOW_FILE, subtask)]
+}
+
+pub fn auto_provision_enabled() -> bool {
+    std::env::var(AUTO_PROVISION_ENV)
+        .map(|v| v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn is_not_found(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Octocrab(octocrab::Error::GitHub { source, .. }) if source.message == "Not Found"
+    )
+}
+
+impl GitHubClient {
+    /// Contents API entry for `path` at `git_ref` (the default branch when None)
+    async fn get_contents(
+        &self,
+        repo: &Repository,
+        path: &str,
+        git_ref: Option<&str>,
+    ) -> Result<Option<serde_json::Value>> {
+        let url = format!("/repos/{}/{}/contents/{}", repo.owner, repo.name, path);
+        let params: Vec<(&str, &str)> = git_ref.map(|r| ("ref", r)).into_iter().collect();
+
+        match self
+            .retrying(|| self.client.get::<serde_json::Value, _, _>(&url, Some(&params)))
+            .await
+        {
+            Ok(file) => Ok(Some(file)),
+            Err(e) if is_not_found(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Blob SHA of a file on `repo.branch`, or None when it does not exist
+    pub async fn get_file_sha(&self, repo: &Repository, path: &str) -> Result<Option<String>> {
+        let file = self.get_contents(repo, path, Some(&repo.branch)).await?;
+        Ok(file.and_then(|f| f["sha"].as_str().map(str::to_string)))
+    }
+
+    /// Text of a file at `git_ref` (the default branch when None), or None when it does not exist
+    pub async fn get_file(
+        &self,
+        repo: &Repository,
+        path: &str,
+        git_ref: Option<&str>,
+    ) -> Result<Option<String>> {
+        let Some(file) = self.get_contents(repo, path, git_ref).await? else {
+            return Ok(None);
+        };
+
+        // Contents API는 60자마다 줄바꿈을 넣은 base64로 돌려줌
+        let encoded: String = file["content"]
+            .as_str()
+            .ok_or_else(|| Error::ApiError(format!("{} is not a file", path)))?
+            .split_whitespace()
+            .collect();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| Error::ApiError(format!("Invalid contents of {}: {}", path, e)))?;
+
+        String::from_utf8(bytes)
+            .map(Some)
+            .map_err(|e| Error::ApiError(format!("{} is not UTF-8: {}", path, e)))
+    }
+
+    /// Create or replace a file on `repo.branch` in one commit
+    ///
+    /// Returns the commit SHA. Writing under `.github/workflows` needs a token
+    /// with the `workflow` scope (or a GitHub App with workflows: write).
+    pub async fn put_file(
+        &self,
+        repo: &Repository,
+        path: &str,
+        content: &str,
+        message: &str,
+    ) -> Result<String> {
+        tracing::info!("Writing {} to {} on {}", path, repo.full_name(), repo.branch);
+
+        let mut body = json!({
+            "message": message,
+            "content": base64::engine::general_purpose::STANDARD.encode(content),
+            "branch": repo.branch,
+        });
+        if let Some(sha) = self.get_file_sha(repo, path).await? {
+            body["sha"] = json!(sha);
+        }
+
+        let url = format!("/repos/{}/{}/contents/{}", repo.owner, repo.name, path);
+        let response: serde_json::Value = self
+            .retrying(|| self.client.put(&url, Some(&body)))
+            .await?;
+
+        response["commit"]["sha"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| Error::ApiError(format!("No commit SHA after writing {}", path)))
+    }
+
+    /// Whether `workflow_file` exists on `git_ref`
+    pub async fn has_workflow(&self, repo: &Repository, workflow_file: &str, git_ref: &str) -> Result<bool> {
+        Ok(self
+            .get_contents(repo, &workflow_path(workflow_file), Some(git_ref))
+            .await?
+            .is_some())
+    }
+
+    /// AutoDev workflows missing from `repo.branch`
+    pub async fn missing_workflows(&self, repo: &Repository) -> Result<Vec<&'static str>> {
+        let mut missing = Vec::new();
+
+        for file in [WORKFLOW_FILE, SUBTASK_WORKFLOW_FILE] {
+            if self.get_file_sha(repo, &workflow_path(file)).await?.is_none() {
+                missing.push(file);
+            }
+        }
+
+        Ok(missing)
+    }
+
+    /// Commit the AutoDev workflows to `repo.branch`
+    ///
+    /// Existing files are kept unless `overwrite` is set. Returns the files written.
+    pub async fn install_workflows(
+        &self,
+        repo: &Repository,
+        server_url: Option<&str>,
+        overwrite: bool,
+    ) -> Result<Vec<&'static str>> {
+        let missing = self.missing_workflows(repo).await?;
+        let mut installed = Vec::new();
+
+        for (file, content) in workflow_files(server_url) {
+            if !overwrite && !missing.contains(&file) {
+                continue;
+            }
+
+            self.put_file(
+                repo,
+                &workflow_path(file),
+                &content,
+                &format!("Add AutoDev workflow {}", file),
+            )
+            .await?;
+            installed.push(file);
+        }
+
+        Ok(installed)
+    }
+
+    /// Pre-flight check before dispatching AutoDev workflows
+    ///
+    /// Missing workflows are installed when AUTODEV_AUTO_PROVISION_WORKFLOWS
+    /// is true; otherwise the error explains how to install them.
+    pub async fn ensure_workflows(&self, repo: &Repository) -> Result<()> {
+        let missing = self.missing_workflows(repo).await?;
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        if auto_provision_enabled() {
+            tracing::info!("Installing missing workflows {:?} into {}", missing, repo.full_name());
+            let server_url = std::env::var("AUTODEV_SERVER_URL").ok();
+            self.install_workflows(repo, server_url.as_deref(), false).await?;
+            return Ok(());
+        }
+
+        Err(Error::WorkflowNotFound(format!(
+            "{} has no {} on {}; run `autodev setup-repo --owner {} --repo {}` or set {}=true",
+            repo.full_name(),
+            missing.join(", "),
+            repo.branch,
+            repo.owner,
+            repo.name,
+            AUTO_PROVISION_ENV
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GitHubHost;
+
+    #[test]
+    fn test_workflow_files() {
+        let files = workflow_files(Some("https://autodev.example.com/"));
+
+        assert_eq!(files[0].0, WORKFLOW_FILE);
+        assert!(files[0].1.contains("default: \"https://autodev.example.com\""));
+        assert!(files[1].1.starts_with("# AutoDev Workflow"));
+        assert!(files[1].1.contains("name: 'AutoDev Subtask'"));
+    }
+
+    #[tokio::test]
+    async fn test_install_missing_workflow() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/o/r/contents/.github/workflows/autodev.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"sha": "abc"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/o/r/contents/.github/workflows/autodev-subtask.yml")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .with_body(r#"{"message": "Not Found"}"#)
+            .create_async()
+            .await;
+        let put = server
+            .mock("PUT", "/repos/o/r/contents/.github/workflows/autodev-subtask.yml")
+            .match_body(mockito::Matcher::PartialJson(json!({ "branch": "main" })))
+            .with_status(201)
+            .with_body(r#"{"commit": {"sha": "def"}}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url())).unwrap();
+        let repo = Repository::new("o".to_string(), "r".to_string());
+
+        assert_eq!(client.missing_workflows(&repo).await.unwrap(), vec![SUBTASK_WORKFLOW_FILE]);
+        assert_eq!(
+            client.install_workflows(&repo, None, false).await.unwrap(),
+            vec![SUBTASK_WORKFLOW_FILE]
+        );
+        put.assert_async().await;
+    }
+}
@@ -0,0 +1,139 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::{
+    Branch, CheckRunReport, DiffStat, GitHubClient, GitHubUrls, Mergeability, MergeOptions, PullRequest, RepoConfig,
+    Repository, VcsProvider, WorkflowStatus, CHECK_RUN_NAME,
+};
+use autodev_vcs::Result;
+
+#[async_trait]
+impl VcsProvider for GitHubClient {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    fn merge_request_url(&self, repo: &Repository, number: u64) -> String {
+        format!("{}/pull/{}", repo.web_url(self.host()), number)
+    }
+
+    async fn trigger_pipeline(
+        &self,
+        repo: &Repository,
+        workflow_file: &str,
+        git_ref: &str,
+        inputs: HashMap<String, String>,
+    ) -> Result<u64> {
+        Ok(self.trigger_workflow(repo, workflow_file, git_ref, inputs).await?)
+    }
+
+    async fn pipeline_status(&self, repo: &Repository, pipeline_id: u64) -> Result<WorkflowStatus> {
+        Ok(self.get_workflow_run_status(repo, pipeline_id).await?)
+    }
+
+    async fn cancel_pipeline(&self, repo: &Repository, pipeline_id: u64) -> Result<()> {
+        Ok(self.cancel_workflow_run(repo, pipeline_id).await?)
+    }
+
+    async fn create_branch(&self, repo: &Repository, branch: &str, from_branch: &str) -> Result<()> {
+        Ok(GitHubClient::create_branch(self, repo, branch, from_branch).await?)
+    }
+
+    async fn delete_branch(&self, repo: &Repository, branch: &str) -> Result<()> {
+        Ok(GitHubClient::delete_branch(self, repo, branch).await?)
+    }
+
+    async fn list_branches(&self, repo: &Repository, prefix: &str) -> Result<Vec<Branch>> {
+        Ok(GitHubClient::list_branches(self, repo, prefix).await?)
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &Repository,
+        title: String,
+        body: String,
+        head: String,
+        base: String,
+        draft: bool,
+    ) -> Result<PullRequest> {
+        Ok(self.create_pull_request(repo, title, body, head, base, draft).await?)
+    }
+
+    async fn merge_merge_request(&self, repo: &Repository, number: u64, options: &MergeOptions) -> Result<()> {
+        Ok(self.merge_pull_request(repo, number, options).await?)
+    }
+
+    async fn close_merge_request(&self, repo: &Repository, number: u64) -> Result<()> {
+        Ok(self.close_pull_request(repo, number).await?)
+    }
+
+    async fn merge_request_branch(&self, repo: &Repository, number: u64) -> Result<String> {
+        Ok(self.get_pr_head_branch(repo, number).await?)
+    }
+
+    async fn merge_request_from_fork(&self, repo: &Repository, number: u64) -> Result<bool> {
+        Ok(self.is_pr_from_fork(repo, number).await?)
+    }
+
+    async fn merge_request_mergeability(&self, repo: &Repository, number: u64) -> Result<Mergeability> {
+        Ok(self.get_pr_mergeability(repo, number).await?)
+    }
+
+    async fn mark_ready_for_review(&self, repo: &Repository, number: u64) -> Result<()> {
+        Ok(self.mark_pr_ready_for_review(repo, number).await?)
+    }
+
+    async fn request_reviewers(&self, repo: &Repository, number: u64, reviewers: &[String]) -> Result<()> {
+        Ok(self.request_pr_reviewers(repo, number, reviewers).await?)
+    }
+
+    async fn merge_request_diff(&self, repo: &Repository, number: u64, max_chars: usize) -> Result<Vec<String>> {
+        Ok(self.get_pr_diff_chunks(repo, number, max_chars).await?)
+    }
+
+    async fn merge_request_diff_stat(&self, repo: &Repository, number: u64) -> Result<DiffStat> {
+        Ok(self.get_pr_diff_stat(repo, number).await?)
+    }
+
+    async fn update_merge_request_body(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        Ok(self.update_pr_body(repo, number, body).await?)
+    }
+
+    async fn find_merge_request_by_branch(&self, repo: &Repository, branch: &str) -> Result<Option<u64>> {
+        Ok(self.find_pr_by_branch(repo, branch).await?.map(|pr| pr.number))
+    }
+
+    async fn is_merge_request_merged(&self, repo: &Repository, number: u64) -> Result<bool> {
+        Ok(self.is_pr_merged(repo, number).await?)
+    }
+
+    async fn comment(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        Ok(self.create_pr_comment(repo, number as u32, body).await?)
+    }
+
+    async fn submit_review(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        Ok(self.submit_pr_review(repo, number, body).await?)
+    }
+
+    async fn load_repo_config(&self, repo: &Repository) -> Result<RepoConfig> {
+        Ok(GitHubClient::load_repo_config(self, repo).await?)
+    }
+
+    async fn report_check(&self, repo: &Repository, branch: &str, report: &CheckRunReport) -> Result<()> {
+        self.report_check_run(repo, branch, CHECK_RUN_NAME, report).await?;
+        Ok(())
+    }
+
+    async fn check_pipeline_setup(&self, repo: &Repository) -> Result<()> {
+        Ok(self.ensure_workflows(repo).await?)
+    }
+
+    async fn revert_merge_request(
+        &self,
+        repo: &Repository,
+        branch: &str,
+        number: u64,
+    ) -> Result<Option<String>> {
+        Ok(self.revert_pull_request(repo, branch, number).await?)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::Result;
+use crate::{GitHubHost, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -36,6 +36,18 @@ pub enum WebhookEvent {
         workflow_run: WorkflowRunPayload,
         repository: RepositoryPayload,
     },
+    Push {
+        push: PushPayload,
+        repository: RepositoryPayload,
+    },
+    CheckSuite {
+        check_suite: CheckSuitePayload,
+        repository: RepositoryPayload,
+    },
+    CheckRun {
+        check_run: CheckRunPayload,
+        repository: RepositoryPayload,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +60,19 @@ pub struct PullRequestPayload {
     pub html_url: String,
     pub head: BranchInfo,
     pub base: BranchInfo,
+    #[serde(default)]
+    pub merged: bool,
+}
+
+impl PullRequestPayload {
+    /// Whether the head branch is in `full_name` (false for a fork or a
+    /// deleted head repository)
+    pub fn head_in_repository(&self, full_name: &str) -> bool {
+        self.head
+            .repo
+            .as_ref()
+            .is_some_and(|repo| repo.full_name.eq_ignore_ascii_case(full_name))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +80,14 @@ pub struct BranchInfo {
     #[serde(rename = "ref")]
     pub ref_: String,
     pub sha: String,
+    /// Repository the branch is in (None when it was deleted)
+    #[serde(default)]
+    pub repo: Option<BranchRepoPayload>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchRepoPayload {
+    pub full_name: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +117,29 @@ pub struct CommentPayload {
     pub id: u64,
     pub body: String,
     pub created_at: String,
+    /// The commenter's relation to the repository: OWNER, MEMBER,
+    /// COLLABORATOR, CONTRIBUTOR, FIRST_TIME_CONTRIBUTOR, NONE, ...
+    #[serde(default)]
+    pub author_association: String,
+    #[serde(default)]
+    pub user: Option<CommentAuthor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentAuthor {
+    pub login: String,
+}
+
+impl CommentPayload {
+    /// Whether the commenter owns the repository, belongs to its
+    /// organization or collaborates on it
+    pub fn is_from_maintainer(&self) -> bool {
+        matches!(self.author_association.as_str(), "OWNER" | "MEMBER" | "COLLABORATOR")
+    }
+
+    pub fn author(&self) -> &str {
+        self.user.as_ref().map_or("unknown", |user| user.login.as_str())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,6 +148,8 @@ pub struct IssuePayload {
     pub number: u32,
     pub title: String,
     pub state: String,
+    #[serde(default)]
+    pub html_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -101,6 +159,156 @@ pub struct WorkflowRunPayload {
     pub status: String,
     pub conclusion: Option<String>,
     pub workflow_id: u64,
+    /// Workflow file, e.g. `.github/workflows/autodev.yml`
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Rendered `run-name`, carrying the task ID of AutoDev runs
+    #[serde(default)]
+    pub display_title: Option<String>,
+    #[serde(default)]
+    pub head_branch: Option<String>,
+    /// PRs whose head is the run's branch (empty for runs from forks)
+    #[serde(default)]
+    pub pull_requests: Vec<WorkflowRunPullRequest>,
+    /// Repository the run's branch lives in (a fork for runs from forks)
+    #[serde(default)]
+    pub head_repository: Option<RepositoryPayload>,
+}
+
+impl WorkflowRunPayload {
+    /// Whether the run is one of the workflows AutoDev dispatches itself
+    pub fn is_autodev_workflow(&self) -> bool {
+        self.path
+            .as_deref()
+            .and_then(|path| path.rsplit('/').next())
+            .is_some_and(|file| file == crate::WORKFLOW_FILE || file == crate::SUBTASK_WORKFLOW_FILE)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRunPullRequest {
+    pub number: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushPayload {
+    /// Full ref, e.g. `refs/heads/autodev/<task_id>`
+    #[serde(rename = "ref")]
+    pub ref_: String,
+    pub before: String,
+    pub after: String,
+    #[serde(default)]
+    pub created: bool,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub forced: bool,
+    pub pusher: PusherPayload,
+    #[serde(default)]
+    pub commits: Vec<PushCommitPayload>,
+}
+
+impl PushPayload {
+    /// Branch name when the push updated a branch (not a tag)
+    pub fn branch(&self) -> Option<&str> {
+        self.ref_.strip_prefix("refs/heads/")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PusherPayload {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushCommitPayload {
+    pub id: String,
+    pub message: String,
+}
+
+/// App that reported a check suite or check run (`github-actions` for workflows)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckAppPayload {
+    #[serde(default)]
+    pub slug: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckSuitePayload {
+    pub id: u64,
+    #[serde(default)]
+    pub head_branch: Option<String>,
+    pub head_sha: String,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub conclusion: Option<String>,
+    #[serde(default)]
+    pub app: Option<CheckAppPayload>,
+    #[serde(default)]
+    pub pull_requests: Vec<WorkflowRunPullRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckRunPayload {
+    pub id: u64,
+    pub name: String,
+    pub head_sha: String,
+    pub status: String,
+    #[serde(default)]
+    pub conclusion: Option<String>,
+    #[serde(default)]
+    pub html_url: Option<String>,
+    #[serde(default)]
+    pub output: Option<CheckRunOutputPayload>,
+    #[serde(default)]
+    pub check_suite: Option<CheckRunSuitePayload>,
+    #[serde(default)]
+    pub app: Option<CheckAppPayload>,
+    #[serde(default)]
+    pub pull_requests: Vec<WorkflowRunPullRequest>,
+}
+
+impl CheckRunPayload {
+    pub fn head_branch(&self) -> Option<&str> {
+        self.check_suite.as_ref()?.head_branch.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckRunOutputPayload {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckRunSuitePayload {
+    pub id: u64,
+    #[serde(default)]
+    pub head_branch: Option<String>,
+}
+
+/// Whether a check conclusion means the check failed
+pub fn is_failing_conclusion(conclusion: &str) -> bool {
+    matches!(conclusion, "failure" | "timed_out" | "action_required" | "startup_failure")
+}
+
+/// ID of the task an AutoDev branch belongs to
+///
+/// `autodev/<id>` names a task or composite task, and
+/// `autodev/<composite_id>/subtask-<task_id>` a subtask of a composite task.
+pub fn branch_task_id(branch: &str) -> Option<&str> {
+    let rest = branch.strip_prefix("autodev/")?;
+
+    match rest.split_once('/') {
+        Some((_, subtask)) => subtask.strip_prefix("subtask-").filter(|id| !id.is_empty()),
+        None => Some(rest).filter(|id| !id.is_empty()),
+    }
 }
 
 pub struct WebhookHandler;
@@ -120,7 +328,24 @@ impl WebhookHandler {
             }
             "pull_request_review" => Ok(serde_json::from_value(payload)?),
             "issue_comment" => Ok(serde_json::from_value(payload)?),
-            "workflow_run" => Ok(serde_json::from_value(payload)?),
+            // workflow_run actions (requested/in_progress/completed) share one shape
+            "workflow_run" => Ok(WebhookEvent::WorkflowRun {
+                workflow_run: serde_json::from_value(payload["workflow_run"].clone())?,
+                repository: serde_json::from_value(payload["repository"].clone())?,
+            }),
+            "push" => Ok(WebhookEvent::Push {
+                repository: serde_json::from_value(payload["repository"].clone())?,
+                push: serde_json::from_value(payload)?,
+            }),
+            // Every action is parsed; handlers act on `status == "completed"`
+            "check_suite" => Ok(WebhookEvent::CheckSuite {
+                check_suite: serde_json::from_value(payload["check_suite"].clone())?,
+                repository: serde_json::from_value(payload["repository"].clone())?,
+            }),
+            "check_run" => Ok(WebhookEvent::CheckRun {
+                check_run: serde_json::from_value(payload["check_run"].clone())?,
+                repository: serde_json::from_value(payload["repository"].clone())?,
+            }),
             _ => Err(crate::Error::UnsupportedEvent(event_type.to_string())),
         }
     }
@@ -138,6 +363,17 @@ impl WebhookHandler {
         let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
         expected == signature
     }
+
+    /// Check that a webhook comes from the configured GitHub instance
+    ///
+    /// GitHub Enterprise Server names itself in the X-GitHub-Enterprise-Host
+    /// header; github.com does not send it.
+    pub fn verify_host(host: &GitHubHost, enterprise_host: Option<&str>) -> bool {
+        match enterprise_host {
+            Some(sender) => host.is_enterprise() && sender.eq_ignore_ascii_case(&host.hostname()),
+            None => !host.is_enterprise(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -162,4 +398,140 @@ mod tests {
         assert!(WebhookHandler::verify_signature(payload, &signature, secret));
         assert!(!WebhookHandler::verify_signature(payload, "wrong_sig", secret));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_verify_host() {
+        let ghes = GitHubHost::new("https://ghe.example.com/api/v3");
+        assert!(WebhookHandler::verify_host(&ghes, Some("GHE.example.com")));
+        assert!(!WebhookHandler::verify_host(&ghes, Some("other.example.com")));
+        assert!(!WebhookHandler::verify_host(&ghes, None));
+
+        assert!(WebhookHandler::verify_host(&GitHubHost::default(), None));
+        assert!(!WebhookHandler::verify_host(&GitHubHost::default(), Some("ghe.example.com")));
+    }
+
+    #[test]
+    fn test_parse_workflow_run_completed() {
+        let payload = serde_json::json!({
+            "action": "completed",
+            "workflow_run": {
+                "id": 42,
+                "name": "AutoDev",
+                "status": "completed",
+                "conclusion": "success",
+                "workflow_id": 7,
+                "path": ".github/workflows/autodev.yml"
+            },
+            "repository": {
+                "id": 1,
+                "name": "repo",
+                "full_name": "owner/repo",
+                "owner": { "login": "owner", "id": 2 }
+            }
+        });
+
+        match WebhookHandler::parse_event("workflow_run", payload).unwrap() {
+            WebhookEvent::WorkflowRun { workflow_run, .. } => {
+                assert_eq!(workflow_run.id, 42);
+                assert_eq!(workflow_run.conclusion.as_deref(), Some("success"));
+                assert!(workflow_run.is_autodev_workflow());
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_pull_request_head_repository() {
+        let pull_request = |head_repo: &str| {
+            serde_json::json!({
+                "action": "opened",
+                "pull_request": {
+                    "id": 1,
+                    "number": 5,
+                    "title": "T",
+                    "body": null,
+                    "state": "open",
+                    "html_url": "https://github.com/owner/repo/pull/5",
+                    "head": { "ref": "autodev/t1", "sha": "abc", "repo": { "full_name": head_repo } },
+                    "base": { "ref": "main", "sha": "def", "repo": { "full_name": "owner/repo" } }
+                },
+                "repository": {
+                    "id": 1,
+                    "name": "repo",
+                    "full_name": "owner/repo",
+                    "owner": { "login": "owner", "id": 2 }
+                }
+            })
+        };
+
+        let WebhookEvent::PullRequestOpened { pull_request: pr, .. } =
+            WebhookHandler::parse_event("pull_request", pull_request("owner/repo")).unwrap()
+        else {
+            panic!("expected an opened pull request");
+        };
+        assert!(pr.head_in_repository("owner/repo"));
+
+        let WebhookEvent::PullRequestOpened { pull_request: fork, .. } =
+            WebhookHandler::parse_event("pull_request", pull_request("attacker/repo")).unwrap()
+        else {
+            panic!("expected an opened pull request");
+        };
+        assert!(!fork.head_in_repository("owner/repo"));
+    }
+
+    #[test]
+    fn test_parse_push_and_check_run() {
+        let repository = serde_json::json!({
+            "id": 1,
+            "name": "repo",
+            "full_name": "owner/repo",
+            "owner": { "login": "owner", "id": 2 }
+        });
+
+        let push = serde_json::json!({
+            "ref": "refs/heads/autodev/task-1",
+            "before": "aaa",
+            "after": "bbb",
+            "forced": true,
+            "pusher": { "name": "someone" },
+            "repository": repository
+        });
+        match WebhookHandler::parse_event("push", push).unwrap() {
+            WebhookEvent::Push { push, .. } => {
+                assert_eq!(push.branch(), Some("autodev/task-1"));
+                assert!(push.forced && !push.deleted);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let check_run = serde_json::json!({
+            "action": "completed",
+            "check_run": {
+                "id": 9,
+                "name": "ci/circleci: test",
+                "head_sha": "bbb",
+                "status": "completed",
+                "conclusion": "failure",
+                "check_suite": { "id": 3, "head_branch": "autodev/task-1" },
+                "app": { "slug": "circleci-checks", "name": "CircleCI Checks" }
+            },
+            "repository": repository
+        });
+        match WebhookHandler::parse_event("check_run", check_run).unwrap() {
+            WebhookEvent::CheckRun { check_run, .. } => {
+                assert_eq!(check_run.head_branch(), Some("autodev/task-1"));
+                assert!(is_failing_conclusion(check_run.conclusion.as_deref().unwrap()));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_branch_task_id() {
+        assert_eq!(branch_task_id("autodev/abc"), Some("abc"));
+        assert_eq!(branch_task_id("autodev/comp/subtask-sub"), Some("sub"));
+        assert_eq!(branch_task_id("autodev/"), None);
+        assert_eq!(branch_task_id("autodev/comp/other"), None);
+        assert_eq!(branch_task_id("feature/abc"), None);
+    }
+}
@@ -1,6 +1,22 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Workflow input carrying the ID that ties a dispatch to its run
+pub const CORRELATION_INPUT: &str = "task_id";
+
+/// Correlation ID of a run titled `... [<id>]`
+///
+/// The AutoDev workflow sets `run-name: 'AutoDev: <title> [<task_id>]'`,
+/// which the runs API reports as `display_title`.
+pub fn correlation_id(title: &str) -> Option<&str> {
+    let title = title.trim_end();
+    let start = title.rfind('[')?;
+
+    title[start + 1..]
+        .strip_suffix(']')
+        .filter(|id| !id.is_empty())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowDispatch {
     #[serde(rename = "ref")]
@@ -17,6 +33,9 @@ pub struct WorkflowRun {
     pub html_url: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Rendered `run-name`
+    #[serde(default)]
+    pub display_title: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,10 +54,27 @@ impl WorkflowRun {
     }
 
     pub fn is_successful(&self) -> bool {
-        self.conclusion.as_ref().map_or(false, |c| c == "success")
+        self.conclusion.as_ref().is_some_and(|c| c == "success")
     }
 
     pub fn is_failed(&self) -> bool {
-        self.conclusion.as_ref().map_or(false, |c| c == "failure" || c == "cancelled")
+        self.conclusion.as_ref().is_some_and(|c| c == "failure" || c == "cancelled")
+    }
+
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.display_title.as_deref().and_then(correlation_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correlation_id() {
+        assert_eq!(correlation_id("AutoDev: Fix login [task_123]"), Some("task_123"));
+        assert_eq!(correlation_id("AutoDev: [WIP] Fix login [task_123] "), Some("task_123"));
+        assert_eq!(correlation_id("AutoDev: Fix login"), None);
+        assert_eq!(correlation_id("AutoDev: Fix login []"), None);
     }
 }
\ No newline at end of file
@@ -0,0 +1,394 @@
+//! HTTP contract tests for GitHubClient
+//!
+//! A mock GitHub answers with recorded responses, and every test asserts the
+//! requests GitHubClient sends (method, path, query and body) and how it maps
+//! the answers, errors included.
+
+use autodev_core::{ErrorClass, MergeMethod};
+use autodev_github::{Error, GitHubClient, GitHubHost, MergeOptions, Repository, RetryPolicy};
+use mockito::Matcher;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const NOT_FOUND: &str = r#"{"message": "Not Found", "documentation_url": "https://docs.github.com/rest"}"#;
+
+fn client(server: &mockito::Server) -> GitHubClient {
+    GitHubClient::with_host("test_token".to_string(), GitHubHost::new(server.url()))
+        .unwrap()
+        .with_retry_policy(RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(10),
+            max_wait: Duration::from_secs(5),
+        })
+}
+
+fn repo() -> Repository {
+    Repository::new("o".to_string(), "r".to_string())
+}
+
+fn run(id: u64, display_title: &str) -> serde_json::Value {
+    json!({
+        "id": id,
+        "name": "AutoDev",
+        "display_title": display_title,
+        "status": "queued",
+        "conclusion": null,
+        "html_url": format!("https://github.com/o/r/actions/runs/{}", id),
+        "created_at": "2026-09-01T10:00:00Z",
+        "updated_at": "2026-09-01T10:00:00Z"
+    })
+}
+
+#[tokio::test]
+async fn test_workflow_dispatch_request() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/repos/o/r/contents/.github/workflows/autodev.yml")
+        .match_query(Matcher::UrlEncoded("ref".into(), "autodev/t1".into()))
+        .with_status(200)
+        .with_body(r#"{"type": "file", "sha": "abc"}"#)
+        .create_async()
+        .await;
+    let dispatch = server
+        .mock("POST", "/repos/o/r/actions/workflows/autodev.yml/dispatches")
+        .match_header("authorization", Matcher::Regex("test_token".into()))
+        .match_body(Matcher::Json(json!({
+            "ref": "autodev/t1",
+            "inputs": { "task_id": "t1", "prompt": "Add a login page" }
+        })))
+        .with_status(204)
+        .expect(1)
+        .create_async()
+        .await;
+    let runs = server
+        .mock("GET", "/repos/o/r/actions/workflows/autodev.yml/runs")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("event".into(), "workflow_dispatch".into()),
+            Matcher::UrlEncoded("branch".into(), "autodev/t1".into()),
+            Matcher::UrlEncoded("per_page".into(), "100".into()),
+            Matcher::Regex("created=%3E%3D".into()),
+        ]))
+        .with_status(200)
+        .with_body(json!({ "total_count": 1, "workflow_runs": [run(31, "AutoDev: Add login [t1]")] }).to_string())
+        .create_async()
+        .await;
+
+    let inputs = HashMap::from([
+        ("task_id".to_string(), "t1".to_string()),
+        ("prompt".to_string(), "Add a login page".to_string()),
+    ]);
+    let run_id = client(&server)
+        .trigger_workflow(&repo(), "autodev.yml", "autodev/t1", inputs)
+        .await
+        .unwrap();
+
+    assert_eq!(run_id, 31);
+    dispatch.assert_async().await;
+    runs.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_dispatched_run_correlation() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/repos/o/r/contents/.github/workflows/autodev.yml")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(r#"{"type": "file", "sha": "abc"}"#)
+        .create_async()
+        .await;
+    server
+        .mock("POST", "/repos/o/r/actions/workflows/autodev.yml/dispatches")
+        .with_status(204)
+        .create_async()
+        .await;
+    // The run is not listed yet right after the dispatch; runs of other tasks
+    // (and a task whose ID merely starts with ours) must not be taken for it
+    let pending = server
+        .mock("GET", "/repos/o/r/actions/workflows/autodev.yml/runs")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(
+            json!({ "total_count": 2, "workflow_runs": [
+                run(40, "AutoDev: Other [t10]"),
+                run(39, "AutoDev: Older [t2]"),
+            ] })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let listed = server
+        .mock("GET", "/repos/o/r/actions/workflows/autodev.yml/runs")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(
+            json!({ "total_count": 3, "workflow_runs": [
+                run(41, "AutoDev: Mine [t1]"),
+                run(40, "AutoDev: Other [t10]"),
+                run(39, "AutoDev: Older [t2]"),
+            ] })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let inputs = HashMap::from([("task_id".to_string(), "t1".to_string())]);
+    let run_id = client(&server)
+        .trigger_workflow(&repo(), "autodev.yml", "autodev/t1", inputs)
+        .await
+        .unwrap();
+
+    assert_eq!(run_id, 41);
+    pending.assert_async().await;
+    listed.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_dispatch_without_workflow_on_ref() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/repos/o/r/contents/.github/workflows/autodev.yml")
+        .match_query(Matcher::Any)
+        .with_status(404)
+        .with_body(NOT_FOUND)
+        .create_async()
+        .await;
+    let dispatch = server
+        .mock("POST", "/repos/o/r/actions/workflows/autodev.yml/dispatches")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let inputs = HashMap::from([("task_id".to_string(), "t1".to_string())]);
+    let error = client(&server)
+        .trigger_workflow(&repo(), "autodev.yml", "autodev/t1", inputs)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::WorkflowNotFound(_)), "{}", error);
+    dispatch.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_create_branch_request() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/repos/o/r/git/ref/heads/main")
+        .with_status(200)
+        .with_body(
+            json!({
+                "ref": "refs/heads/main",
+                "node_id": "REF_main",
+                "url": "https://api.github.com/repos/o/r/git/refs/heads/main",
+                "object": {
+                    "type": "commit",
+                    "sha": "aa218f56b14c9653891f9e74264a383fa43fefbd",
+                    "url": "https://api.github.com/repos/o/r/git/commits/aa218f56b14c9653891f9e74264a383fa43fefbd"
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let create = server
+        .mock("POST", "/repos/o/r/git/refs")
+        .match_body(Matcher::Json(json!({
+            "ref": "refs/heads/autodev/t1",
+            "sha": "aa218f56b14c9653891f9e74264a383fa43fefbd"
+        })))
+        .with_status(201)
+        .with_body(
+            json!({
+                "ref": "refs/heads/autodev/t1",
+                "node_id": "REF_t1",
+                "url": "https://api.github.com/repos/o/r/git/refs/heads/autodev/t1",
+                "object": {
+                    "type": "commit",
+                    "sha": "aa218f56b14c9653891f9e74264a383fa43fefbd",
+                    "url": "https://api.github.com/repos/o/r/git/commits/aa218f56b14c9653891f9e74264a383fa43fefbd"
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    client(&server).create_branch(&repo(), "autodev/t1", "main").await.unwrap();
+    create.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_create_branch_errors() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/repos/o/r/git/ref/heads/gone")
+        .with_status(404)
+        .with_body(NOT_FOUND)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/repos/o/r/git/ref/heads/main")
+        .with_status(200)
+        .with_body(r#"{"ref": "refs/heads/main", "node_id": "REF_main", "url": "https://api.github.com/repos/o/r/git/refs/heads/main",
+               "object": {"type": "commit", "sha": "abc", "url": "https://api.github.com/repos/o/r/git/commits/abc"}}"#)
+        .create_async()
+        .await;
+    // Client errors are reported as they are, without retries
+    let create = server
+        .mock("POST", "/repos/o/r/git/refs")
+        .with_status(422)
+        .with_body(r#"{"message": "Reference already exists", "documentation_url": "https://docs.github.com/rest"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    let error = client.create_branch(&repo(), "autodev/t1", "gone").await.unwrap_err();
+    assert!(matches!(error, Error::Octocrab(_)), "{}", error);
+    assert_eq!(error.class(), ErrorClass::NotFound);
+
+    let error = client.create_branch(&repo(), "autodev/t1", "main").await.unwrap_err();
+    assert!(format!("{:?}", error).contains("Reference already exists"), "{:?}", error);
+    assert_eq!(error.class(), ErrorClass::Conflict);
+    create.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_create_pull_request_request() {
+    let mut server = mockito::Server::new_async().await;
+    let create = server
+        .mock("POST", "/repos/o/r/pulls")
+        .match_body(Matcher::PartialJson(json!({
+            "title": "Add login",
+            "head": "autodev/t1",
+            "base": "main",
+            "body": "Closes #3",
+            "draft": true
+        })))
+        .with_status(201)
+        .with_body(include_str!("fixtures/pull_created.json"))
+        .create_async()
+        .await;
+
+    let pr = client(&server)
+        .create_pull_request(
+            &repo(),
+            "Add login".to_string(),
+            "Closes #3".to_string(),
+            "autodev/t1".to_string(),
+            "main".to_string(),
+            true,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(pr.number, 42);
+    assert_eq!(pr.title, "Add login");
+    assert_eq!(pr.url.as_deref(), Some("https://github.com/o/r/pull/42"));
+    create.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_merge_pull_request_request() {
+    let mut server = mockito::Server::new_async().await;
+    let merge = server
+        .mock("PUT", "/repos/o/r/pulls/42/merge")
+        .match_body(Matcher::Json(json!({
+            "merge_method": "squash",
+            "commit_title": "Add login (#42)",
+            "commit_message": "Task t1"
+        })))
+        .with_status(200)
+        .with_body(r#"{"sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e", "merged": true, "message": "Pull Request successfully merged"}"#)
+        .create_async()
+        .await;
+    let conflict = server
+        .mock("PUT", "/repos/o/r/pulls/43/merge")
+        .with_status(405)
+        .with_body(r#"{"message": "Pull Request is not mergeable", "documentation_url": "https://docs.github.com/rest"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    let options = MergeOptions::new(MergeMethod::Squash).with_commit("Add login (#42)".to_string(), "Task t1".to_string());
+    client.merge_pull_request(&repo(), 42, &options).await.unwrap();
+
+    let error = client
+        .merge_pull_request(&repo(), 43, &MergeOptions::new(MergeMethod::Merge))
+        .await
+        .unwrap_err();
+    assert!(format!("{:?}", error).contains("not mergeable"), "{:?}", error);
+    assert_eq!(error.class(), ErrorClass::Conflict);
+    merge.assert_async().await;
+    conflict.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_open_pull_requests_pagination() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/repos/o/r/git/matching-refs/heads/autodev/")
+        .with_status(200)
+        .with_body(r#"[{"ref": "refs/heads/autodev/t150", "object": {"sha": "fff", "type": "commit"}}]"#)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/repos/o/r/commits/fff")
+        .with_status(200)
+        .with_body(r#"{"sha": "fff", "commit": {"committer": {"date": "2026-09-01T10:00:00Z"}}}"#)
+        .create_async()
+        .await;
+    let pull = |number: u64| json!({ "number": number, "head": { "ref": format!("autodev/t{}", number), "repo": { "full_name": "o/r" } } });
+    let first = server
+        .mock("GET", "/repos/o/r/pulls")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("state".into(), "open".into()),
+            Matcher::UrlEncoded("page".into(), "1".into()),
+        ]))
+        .with_status(200)
+        .with_body(json!((1..=100).map(pull).collect::<Vec<_>>()).to_string())
+        .create_async()
+        .await;
+    let second = server
+        .mock("GET", "/repos/o/r/pulls")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("state".into(), "open".into()),
+            Matcher::UrlEncoded("page".into(), "2".into()),
+        ]))
+        .with_status(200)
+        .with_body(json!((101..=150).map(pull).collect::<Vec<_>>()).to_string())
+        .create_async()
+        .await;
+
+    let branches = client(&server).list_branches(&repo(), "autodev/").await.unwrap();
+
+    // The PR from the branch is only on the second page
+    assert_eq!(branches[0].open_merge_request, Some(150));
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_cancel_workflow_run() {
+    let mut server = mockito::Server::new_async().await;
+    let cancel = server
+        .mock("POST", "/repos/o/r/actions/runs/31/cancel")
+        .with_status(202)
+        .create_async()
+        .await;
+    server
+        .mock("POST", "/repos/o/r/actions/runs/32/cancel")
+        .with_status(409)
+        .with_body(r#"{"message": "Cannot cancel a workflow run that is completed.", "documentation_url": "https://docs.github.com/rest"}"#)
+        .create_async()
+        .await;
+
+    let client = client(&server);
+    client.cancel_workflow_run(&repo(), 31).await.unwrap();
+    let error = client.cancel_workflow_run(&repo(), 32).await.unwrap_err();
+    assert_eq!(error.class(), ErrorClass::Permanent);
+    cancel.assert_async().await;
+}
@@ -0,0 +1,665 @@
+use async_trait::async_trait;
+use reqwest::Method;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use autodev_core::MergeMethod;
+use autodev_vcs::{Branch, DiffStat, Error, MergeOptions, PullRequest, Repository, Result, VcsProvider, WorkflowStatus};
+
+/// Environment variable selecting the GitLab instance (self-managed or gitlab.com)
+pub const GITLAB_URL_ENV: &str = "GITLAB_URL";
+
+pub const DEFAULT_GITLAB_URL: &str = "https://gitlab.com";
+
+/// Pipeline variable carrying the workflow file a GitHub Actions dispatch would select
+pub const WORKFLOW_VARIABLE: &str = "AUTODEV_WORKFLOW";
+
+/// GitLab backend: CI pipelines and merge requests through the REST API (v4)
+///
+/// Repositories map to projects by path (`owner/name`); merge request
+/// numbers are project-level IIDs.
+#[derive(Clone)]
+pub struct GitLabClient {
+    client: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl GitLabClient {
+    /// Client for the instance selected by GITLAB_URL (gitlab.com by default)
+    pub fn new(token: String) -> Result<Self> {
+        let base_url = std::env::var(GITLAB_URL_ENV)
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_GITLAB_URL.to_string());
+
+        Self::with_base_url(token, base_url)
+    }
+
+    pub fn with_base_url(token: String, base_url: String) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .user_agent("AutoDev-Rust")
+            .build()
+            .map_err(|e| Error::ApiError(format!("Failed to build GitLab client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim().trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    /// API path prefix of a project
+    fn project(repo: &Repository) -> String {
+        format!("/projects/{}", urlencoding::encode(&repo.full_name()))
+    }
+
+    async fn request(&self, method: Method, path: &str, body: Option<&Value>) -> Result<Value> {
+        let url = format!("{}/api/v4{}", self.base_url, path);
+        let mut request = self
+            .client
+            .request(method, &url)
+            .header("PRIVATE-TOKEN", &self.token);
+
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| Error::Transport(format!("GitLab request failed: {}", e)))?;
+
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(std::time::Duration::from_secs);
+        let text = response.text().await.unwrap_or_default();
+
+        if !status.is_success() {
+            return Err(Error::Http {
+                status: status.as_u16(),
+                message: format!("GitLab {}: {}", path, text),
+                retry_after,
+            });
+        }
+
+        // e.g. 204 No Content
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    async fn get_merge_request(&self, repo: &Repository, iid: u64) -> Result<Value> {
+        let path = format!("{}/merge_requests/{}", Self::project(repo), iid);
+        self.request(Method::GET, &path, None).await
+    }
+
+    /// Every item of a list endpoint (`query` without paging), 100 per request
+    async fn get_all(&self, path: &str, query: &str) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+
+        for page in 1u32.. {
+            let url = format!("{}?{}&per_page=100&page={}", path, query, page);
+            let batch = match self.request(Method::GET, &url, None).await? {
+                Value::Array(batch) => batch,
+                _ => Vec::new(),
+            };
+            let last = batch.len() < 100;
+            items.extend(batch);
+            if last {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+}
+
+/// Map a GitLab pipeline status onto GitHub Actions status/conclusion
+fn workflow_status(status: &str) -> WorkflowStatus {
+    let conclusion = match status {
+        "success" => Some("success"),
+        "failed" => Some("failure"),
+        "canceled" | "skipped" => Some("cancelled"),
+        // Stopped until someone plays a manual job or the schedule fires
+        "manual" | "scheduled" => Some("action_required"),
+        _ => None,
+    };
+
+    WorkflowStatus {
+        status: if conclusion.is_some() { "completed" } else { "in_progress" }.to_string(),
+        conclusion: conclusion.map(str::to_string),
+    }
+}
+
+fn id_field(value: &Value, field: &str) -> Result<u64> {
+    value[field]
+        .as_u64()
+        .ok_or_else(|| Error::ApiError(format!("GitLab response has no {}", field)))
+}
+
+#[async_trait]
+impl VcsProvider for GitLabClient {
+    fn name(&self) -> &str {
+        "gitlab"
+    }
+
+    fn merge_request_url(&self, repo: &Repository, number: u64) -> String {
+        format!("{}/{}/-/merge_requests/{}", self.base_url, repo.full_name(), number)
+    }
+
+    /// Inputs become pipeline variables of the same name; the workflow file is
+    /// passed as AUTODEV_WORKFLOW for `.gitlab-ci.yml` rules to select jobs
+    async fn trigger_pipeline(
+        &self,
+        repo: &Repository,
+        workflow_file: &str,
+        git_ref: &str,
+        inputs: HashMap<String, String>,
+    ) -> Result<u64> {
+        tracing::info!(
+            "Triggering pipeline {} for {} on {}",
+            workflow_file,
+            repo.full_name(),
+            git_ref
+        );
+
+        let mut variables = vec![json!({ "key": WORKFLOW_VARIABLE, "value": workflow_file })];
+        variables.extend(
+            inputs
+                .into_iter()
+                .map(|(key, value)| json!({ "key": key, "value": value })),
+        );
+
+        let path = format!("{}/pipeline", Self::project(repo));
+        let pipeline = self
+            .request(
+                Method::POST,
+                &path,
+                Some(&json!({ "ref": git_ref, "variables": variables })),
+            )
+            .await?;
+
+        let pipeline_id = id_field(&pipeline, "id")?;
+        tracing::info!("Pipeline triggered with ID: {}", pipeline_id);
+
+        Ok(pipeline_id)
+    }
+
+    async fn pipeline_status(&self, repo: &Repository, pipeline_id: u64) -> Result<WorkflowStatus> {
+        let path = format!("{}/pipelines/{}", Self::project(repo), pipeline_id);
+        let pipeline = self.request(Method::GET, &path, None).await?;
+
+        Ok(workflow_status(pipeline["status"].as_str().unwrap_or("unknown")))
+    }
+
+    async fn cancel_pipeline(&self, repo: &Repository, pipeline_id: u64) -> Result<()> {
+        tracing::info!("Cancelling pipeline {} in {}", pipeline_id, repo.full_name());
+
+        let path = format!("{}/pipelines/{}/cancel", Self::project(repo), pipeline_id);
+        self.request(Method::POST, &path, None).await?;
+
+        Ok(())
+    }
+
+    async fn create_branch(&self, repo: &Repository, branch: &str, from_branch: &str) -> Result<()> {
+        tracing::info!("Creating branch {} from {} in {}", branch, from_branch, repo.full_name());
+
+        let path = format!("{}/repository/branches", Self::project(repo));
+        self.request(
+            Method::POST,
+            &path,
+            Some(&json!({ "branch": branch, "ref": from_branch })),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_branch(&self, repo: &Repository, branch: &str) -> Result<()> {
+        tracing::info!("Deleting branch {} in {}", branch, repo.full_name());
+
+        let path = format!(
+            "{}/repository/branches/{}",
+            Self::project(repo),
+            urlencoding::encode(branch)
+        );
+        self.request(Method::DELETE, &path, None).await?;
+
+        Ok(())
+    }
+
+    async fn list_branches(&self, repo: &Repository, prefix: &str) -> Result<Vec<Branch>> {
+        let project = Self::project(repo);
+        let search = format!("search={}", urlencoding::encode(&format!("^{}", prefix)));
+        let branches = self.get_all(&format!("{}/repository/branches", project), &search).await?;
+
+        // Merge requests from forks have another source project
+        let open: HashMap<String, u64> = self
+            .get_all(&format!("{}/merge_requests", project), "state=opened")
+            .await?
+            .iter()
+            .filter(|mr| mr["source_project_id"] == mr["target_project_id"])
+            .filter_map(|mr| Some((mr["source_branch"].as_str()?.to_string(), mr["iid"].as_u64()?)))
+            .collect();
+
+        Ok(branches
+            .iter()
+            .filter_map(|branch| {
+                let name = branch["name"].as_str().filter(|name| name.starts_with(prefix))?;
+                Some(Branch {
+                    name: name.to_string(),
+                    committed_at: branch["commit"]["committed_date"]
+                        .as_str()
+                        .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok())
+                        .map(|date| date.with_timezone(&chrono::Utc)),
+                    open_merge_request: open.get(name).copied(),
+                })
+            })
+            .collect())
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &Repository,
+        title: String,
+        body: String,
+        head: String,
+        base: String,
+        draft: bool,
+    ) -> Result<PullRequest> {
+        tracing::info!("Creating MR: {} ({} -> {}) [draft: {}]", title, head, base, draft);
+
+        // GitLab marks drafts by title prefix
+        let title = if draft { format!("Draft: {}", title) } else { title };
+
+        let path = format!("{}/merge_requests", Self::project(repo));
+        let mr = self
+            .request(
+                Method::POST,
+                &path,
+                Some(&json!({
+                    "source_branch": head,
+                    "target_branch": base,
+                    "title": title,
+                    "description": body,
+                })),
+            )
+            .await?;
+
+        Ok(PullRequest {
+            number: id_field(&mr, "iid")?,
+            url: mr["web_url"].as_str().map(str::to_string),
+            title: mr["title"].as_str().unwrap_or_default().to_string(),
+        })
+    }
+
+    async fn merge_merge_request(&self, repo: &Repository, number: u64, options: &MergeOptions) -> Result<()> {
+        tracing::info!("Merging MR !{} in {} ({})", number, repo.full_name(), options.method);
+
+        // GitLab은 커밋 제목/본문을 하나의 메시지로 받음
+        let message = match (&options.commit_title, &options.commit_message) {
+            (Some(title), Some(body)) => Some(format!("{}\n\n{}", title, body)),
+            (Some(title), None) => Some(title.clone()),
+            (None, body) => body.clone(),
+        };
+
+        let mut body = json!({});
+        match options.method {
+            MergeMethod::Merge => {
+                if let Some(message) = message {
+                    body["merge_commit_message"] = json!(message);
+                }
+            }
+            MergeMethod::Squash => {
+                body["squash"] = json!(true);
+                if let Some(message) = message {
+                    body["squash_commit_message"] = json!(message);
+                }
+            }
+            MergeMethod::Rebase => {
+                return Err(Error::ApiError(format!(
+                    "GitLab cannot rebase MR !{} on merge; set the project's merge method to fast-forward and use merge",
+                    number
+                )));
+            }
+        }
+
+        let path = format!("{}/merge_requests/{}/merge", Self::project(repo), number);
+        self.request(Method::PUT, &path, Some(&body)).await?;
+
+        tracing::info!("✓ MR !{} merged successfully", number);
+
+        Ok(())
+    }
+
+    async fn close_merge_request(&self, repo: &Repository, number: u64) -> Result<()> {
+        tracing::info!("Closing MR !{} in {}", number, repo.full_name());
+
+        let path = format!("{}/merge_requests/{}", Self::project(repo), number);
+        self.request(Method::PUT, &path, Some(&json!({ "state_event": "close" })))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_merge_request_by_branch(&self, repo: &Repository, branch: &str) -> Result<Option<u64>> {
+        let path = format!(
+            "{}/merge_requests?source_branch={}&state=all&per_page=1",
+            Self::project(repo),
+            urlencoding::encode(branch)
+        );
+        let mrs = self.request(Method::GET, &path, None).await?;
+
+        Ok(mrs
+            .as_array()
+            .and_then(|mrs| mrs.first())
+            .and_then(|mr| mr["iid"].as_u64()))
+    }
+
+    async fn merge_request_branch(&self, repo: &Repository, number: u64) -> Result<String> {
+        let mr = self.get_merge_request(repo, number).await?;
+
+        mr["source_branch"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| Error::ApiError(format!("MR !{} has no source branch", number)))
+    }
+
+    async fn merge_request_from_fork(&self, repo: &Repository, number: u64) -> Result<bool> {
+        let mr = self.get_merge_request(repo, number).await?;
+
+        Ok(mr["source_project_id"] != mr["target_project_id"])
+    }
+
+    /// Rebuilt from the per-file diffs of the changes listing
+    async fn merge_request_diff(&self, repo: &Repository, number: u64, max_chars: usize) -> Result<Vec<String>> {
+        let path = format!("{}/merge_requests/{}/changes", Self::project(repo), number);
+        let mr = self.request(Method::GET, &path, None).await?;
+
+        let mut diff = String::new();
+        for change in mr["changes"].as_array().into_iter().flatten() {
+            let old_path = change["old_path"].as_str().unwrap_or_default();
+            let new_path = change["new_path"].as_str().unwrap_or_default();
+            let old = match change["new_file"].as_bool() {
+                Some(true) => "/dev/null".to_string(),
+                _ => format!("a/{}", old_path),
+            };
+            let new = match change["deleted_file"].as_bool() {
+                Some(true) => "/dev/null".to_string(),
+                _ => format!("b/{}", new_path),
+            };
+
+            diff.push_str(&format!("diff --git a/{} b/{}\n--- {}\n+++ {}\n", old_path, new_path, old, new));
+            diff.push_str(change["diff"].as_str().unwrap_or_default());
+            if !diff.ends_with('\n') {
+                diff.push('\n');
+            }
+        }
+
+        Ok(autodev_vcs::chunk_diff(&diff, max_chars))
+    }
+
+    /// Counted from the per-file diffs of the changes listing
+    async fn merge_request_diff_stat(&self, repo: &Repository, number: u64) -> Result<DiffStat> {
+        let path = format!("{}/merge_requests/{}/changes", Self::project(repo), number);
+        let mr = self.request(Method::GET, &path, None).await?;
+
+        let mut stat = DiffStat::default();
+        for change in mr["changes"].as_array().into_iter().flatten() {
+            stat.files_changed += 1;
+            for line in change["diff"].as_str().unwrap_or_default().lines() {
+                match line.as_bytes().first() {
+                    Some(b'+') => stat.additions += 1,
+                    Some(b'-') => stat.deletions += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(stat)
+    }
+
+    async fn update_merge_request_body(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        let path = format!("{}/merge_requests/{}", Self::project(repo), number);
+        self.request(Method::PUT, &path, Some(&json!({ "description": body })))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn is_merge_request_merged(&self, repo: &Repository, number: u64) -> Result<bool> {
+        let mr = self.get_merge_request(repo, number).await?;
+
+        Ok(mr["state"].as_str() == Some("merged"))
+    }
+
+    async fn comment(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        tracing::info!("Adding comment to MR !{}", number);
+
+        let path = format!("{}/merge_requests/{}/notes", Self::project(repo), number);
+        self.request(Method::POST, &path, Some(&json!({ "body": body })))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn repo() -> Repository {
+        Repository::new("group".to_string(), "project".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_trigger_pipeline() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v4/projects/group%2Fproject/pipeline")
+            .match_header("PRIVATE-TOKEN", "secret")
+            .match_body(Matcher::PartialJson(json!({
+                "ref": "autodev/t1",
+                "variables": [
+                    { "key": "AUTODEV_WORKFLOW", "value": "autodev.yml" },
+                    { "key": "task_id", "value": "t1" }
+                ]
+            })))
+            .with_status(201)
+            .with_body(r#"{"id": 99, "status": "created"}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::with_base_url("secret".to_string(), server.url()).unwrap();
+        let inputs = HashMap::from([("task_id".to_string(), "t1".to_string())]);
+        let pipeline_id = client.trigger_pipeline(&repo(), "autodev.yml", "autodev/t1", inputs).await.unwrap();
+
+        assert_eq!(pipeline_id, 99);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_error_class() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/pipelines/1")
+            .with_status(429)
+            .with_header("Retry-After", "30")
+            .with_body(r#"{"message": "Retry later"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/pipelines/2")
+            .with_status(404)
+            .with_body(r#"{"message": "404 Not found"}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::with_base_url("secret".to_string(), server.url()).unwrap();
+        let error = client.pipeline_status(&repo(), 1).await.unwrap_err();
+        assert_eq!(
+            error.class(),
+            autodev_core::ErrorClass::RateLimited { retry_after: Some(std::time::Duration::from_secs(30)) }
+        );
+        let error = client.pipeline_status(&repo(), 2).await.unwrap_err();
+        assert_eq!(error.class(), autodev_core::ErrorClass::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_list_branches() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/repository/branches")
+            .match_query(Matcher::UrlEncoded("search".into(), "^autodev/".into()))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"name": "autodev/a", "commit": {"committed_date": "2026-01-02T03:04:05.000+09:00"}},
+                    {"name": "autodev/b", "commit": {}}
+                ]"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/merge_requests")
+            .match_query(Matcher::UrlEncoded("state".into(), "opened".into()))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"iid": 7, "source_branch": "autodev/b", "source_project_id": 1, "target_project_id": 1},
+                    {"iid": 8, "source_branch": "autodev/a", "source_project_id": 2, "target_project_id": 1}
+                ]"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::with_base_url("secret".to_string(), server.url()).unwrap();
+        let branches = client.list_branches(&repo(), "autodev/").await.unwrap();
+
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].committed_at.unwrap().to_rfc3339(), "2026-01-01T18:04:05+00:00");
+        assert_eq!(branches[0].open_merge_request, None);
+        assert_eq!(branches[1].committed_at, None);
+        assert_eq!(branches[1].open_merge_request, Some(7));
+    }
+
+    #[tokio::test]
+    async fn test_find_merge_request_by_branch() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/merge_requests")
+            .match_query(Matcher::UrlEncoded("source_branch".into(), "autodev/t1".into()))
+            .with_status(200)
+            .with_body(r#"[{"iid": 7, "state": "opened"}]"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::with_base_url("secret".to_string(), server.url()).unwrap();
+        let mr = client.find_merge_request_by_branch(&repo(), "autodev/t1").await.unwrap();
+
+        assert_eq!(mr, Some(7));
+        assert_eq!(
+            client.merge_request_url(&repo(), 7),
+            format!("{}/group/project/-/merge_requests/7", server.url())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_squash_merge_request() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/api/v4/projects/group%2Fproject/merge_requests/7/merge")
+            .match_body(Matcher::PartialJson(json!({
+                "squash": true,
+                "squash_commit_message": "Add login (#7)\n\nAutoDev task: t1"
+            })))
+            .with_status(200)
+            .with_body(r#"{"iid": 7, "state": "merged"}"#)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::with_base_url("secret".to_string(), server.url()).unwrap();
+        let options = MergeOptions::new(MergeMethod::Squash)
+            .with_commit("Add login (#7)".to_string(), "AutoDev task: t1".to_string());
+        client.merge_merge_request(&repo(), 7, &options).await.unwrap();
+        mock.assert_async().await;
+
+        let rebase = MergeOptions::new(MergeMethod::Rebase);
+        assert!(client.merge_merge_request(&repo(), 7, &rebase).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_merge_request_diff() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/merge_requests/7/changes")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "iid": 7,
+                    "changes": [
+                        { "old_path": "a.rs", "new_path": "a.rs", "new_file": false, "deleted_file": false, "diff": "@@ -1 +1 @@\n-a\n+b\n" },
+                        { "old_path": "b.rs", "new_path": "b.rs", "new_file": true, "deleted_file": false, "diff": "@@ -0,0 +1 @@\n+c" }
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::with_base_url("secret".to_string(), server.url()).unwrap();
+        let chunks = client.merge_request_diff(&repo(), 7, 60_000).await.unwrap();
+
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].starts_with("diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@"));
+        assert!(chunks[0].contains("--- /dev/null\n+++ b/b.rs\n@@ -0,0 +1 @@\n+c\n"));
+    }
+
+    #[tokio::test]
+    async fn test_merge_request_diff_stat() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Fproject/merge_requests/7/changes")
+            .with_status(200)
+            .with_body(
+                json!({
+                    "iid": 7,
+                    "changes": [
+                        { "old_path": "a.rs", "new_path": "a.rs", "diff": "@@ -1,2 +1,2 @@\n-a\n+b\n+c\n d\n" },
+                        { "old_path": "b.rs", "new_path": "b.rs", "diff": "@@ -1 +0,0 @@\n-e" }
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = GitLabClient::with_base_url("secret".to_string(), server.url()).unwrap();
+        let stat = client.merge_request_diff_stat(&repo(), 7).await.unwrap();
+
+        assert_eq!(stat, DiffStat { files_changed: 2, additions: 2, deletions: 2 });
+    }
+
+    #[test]
+    fn test_pipeline_status_mapping() {
+        assert_eq!(workflow_status("failed").conclusion.as_deref(), Some("failure"));
+        assert_eq!(workflow_status("canceled").conclusion.as_deref(), Some("cancelled"));
+
+        let manual = workflow_status("manual");
+        assert_eq!(manual.status, "completed");
+        assert_eq!(manual.conclusion.as_deref(), Some("action_required"));
+        assert_eq!(workflow_status("scheduled").conclusion.as_deref(), Some("action_required"));
+
+        let running = workflow_status("running");
+        assert_eq!(running.status, "in_progress");
+        assert!(running.conclusion.is_none());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod client;
+
+// Re-exports
+pub use client::{GitLabClient, DEFAULT_GITLAB_URL, GITLAB_URL_ENV, WORKFLOW_VARIABLE};
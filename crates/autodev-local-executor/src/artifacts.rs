@@ -0,0 +1,80 @@
+//! Uploading the output of task runs to the AutoDev server
+//!
+//! Logs stay in the workspace directory of the executor's host as well; the
+//! upload makes them reachable through `GET /tasks/:id/artifacts` when the
+//! API runs in a container or on another host. Uploads are best effort: a
+//! failure is logged and does not fail the task.
+
+use std::path::Path;
+
+/// Set to `false` to keep run output on the executor's host only
+pub const UPLOAD_ARTIFACTS_ENV: &str = "AUTODEV_UPLOAD_ARTIFACTS";
+/// API key (operator role) sent with uploads when the server requires keys
+pub const API_KEY_ENV: &str = "AUTODEV_API_KEY";
+
+/// Posts run output to `POST /tasks/:id/artifacts`
+pub(crate) struct ArtifactUploader {
+    client: reqwest::Client,
+    server_url: String,
+    api_key: Option<String>,
+}
+
+impl ArtifactUploader {
+    /// Uploader for `server_url` unless AUTODEV_UPLOAD_ARTIFACTS is false
+    pub(crate) fn from_env(server_url: Option<&str>) -> Option<Self> {
+        let enabled = std::env::var(UPLOAD_ARTIFACTS_ENV)
+            .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "false" | "0" | "no" | "off"))
+            .unwrap_or(true);
+
+        Some(Self {
+            client: reqwest::Client::new(),
+            server_url: server_url.filter(|_| enabled)?.trim_end_matches('/').to_string(),
+            api_key: std::env::var(API_KEY_ENV).ok().filter(|key| !key.trim().is_empty()),
+        })
+    }
+
+    /// Upload `artifacts` (name, content) of task `task_id`
+    pub(crate) async fn upload(&self, task_id: &str, artifacts: Vec<(String, Vec<u8>)>) {
+        if artifacts.is_empty() {
+            return;
+        }
+
+        let names: Vec<String> = artifacts.iter().map(|(name, _)| name.clone()).collect();
+        let form = artifacts.into_iter().fold(reqwest::multipart::Form::new(), |form, (name, content)| {
+            form.part(name.clone(), reqwest::multipart::Part::bytes(content).file_name(name))
+        });
+
+        let mut request = self
+            .client
+            .post(format!("{}/tasks/{}/artifacts", self.server_url, task_id))
+            .multipart(form);
+        if let Some(ref key) = self.api_key {
+            request = request.header("x-api-key", key);
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                tracing::info!("Uploaded {} of task {}", names.join(", "), task_id);
+            }
+            Ok(response) => {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                tracing::warn!("Failed to upload the artifacts of task {}: {} {}", task_id, status, body.trim());
+            }
+            Err(e) => tracing::warn!("Failed to upload the artifacts of task {}: {}", task_id, e),
+        }
+    }
+}
+
+/// Read `files` (artifact name, path) that exist
+pub(crate) async fn read_artifacts(files: &[(&str, &Path)]) -> Vec<(String, Vec<u8>)> {
+    let mut artifacts = Vec::new();
+    for (name, path) in files {
+        match tokio::fs::read(path).await {
+            Ok(content) => artifacts.push((name.to_string(), content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("Failed to read {:?} for upload: {}", path, e),
+        }
+    }
+    artifacts
+}
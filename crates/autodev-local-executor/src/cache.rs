@@ -0,0 +1,319 @@
+//! Shared clone cache for worker containers
+//!
+//! Every repository gets one bare mirror on the host, refreshed with a fetch
+//! before each task. Workers clone with `--reference-if-able` against the
+//! mirror mounted read-only, so only objects newer than the last fetch come
+//! over the network. Once the cache outgrows its size limit the least
+//! recently used mirrors are evicted; mirrors of running containers are kept.
+
+use autodev_github::GitHubUrls;
+use git2::{Cred, FetchOptions, FetchPrune, RemoteCallbacks};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use autodev_github::Repository;
+
+use crate::error::{LocalExecutorError, Result};
+
+/// Set to `false` to clone every task from scratch
+pub const CLONE_CACHE_ENV: &str = "AUTODEV_CLONE_CACHE";
+/// Directory holding the mirrors (default: `<workspace>/repo-cache`)
+pub const CLONE_CACHE_DIR_ENV: &str = "AUTODEV_CLONE_CACHE_DIR";
+/// Size limit of the cache, e.g. `20g`
+pub const CLONE_CACHE_MAX_SIZE_ENV: &str = "AUTODEV_CLONE_CACHE_MAX_SIZE";
+
+pub const DEFAULT_CLONE_CACHE_MAX_BYTES: u64 = 10 << 30;
+
+/// Where the mirror of the task's repository is mounted in worker containers
+pub const CACHE_MOUNT_PATH: &str = "/cache/repo.git";
+
+/// Touched whenever a mirror is used; its mtime orders eviction
+const LAST_USED_FILE: &str = "autodev-last-used";
+
+const MIRROR_REFSPEC: &str = "+refs/heads/*:refs/heads/*";
+
+#[derive(Default)]
+struct MirrorState {
+    /// Serializes fetches into the mirror
+    lock: Arc<tokio::sync::Mutex<()>>,
+    /// Containers currently referencing the mirror
+    leases: usize,
+}
+
+pub struct CloneCache {
+    root: PathBuf,
+    max_bytes: u64,
+    github_token: String,
+    mirrors: Mutex<HashMap<PathBuf, MirrorState>>,
+}
+
+/// Keeps a mirror from being evicted while a container references it
+pub struct CacheLease {
+    cache: Arc<CloneCache>,
+    path: PathBuf,
+}
+
+impl CacheLease {
+    /// Host path of the mirror
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for CacheLease {
+    fn drop(&mut self) {
+        let mut mirrors = self.cache.mirrors.lock().unwrap();
+        if let Some(state) = mirrors.get_mut(&self.path) {
+            state.leases = state.leases.saturating_sub(1);
+        }
+    }
+}
+
+impl CloneCache {
+    pub fn new(root: PathBuf, max_bytes: u64, github_token: String) -> Self {
+        Self {
+            root,
+            max_bytes,
+            github_token,
+            mirrors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cache configured by the AUTODEV_CLONE_CACHE* variables, `None` when disabled
+    pub fn from_env(workspace_dir: &Path, github_token: String) -> Option<Self> {
+        let enabled = std::env::var(CLONE_CACHE_ENV)
+            .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "false" | "0" | "off" | "no"))
+            .unwrap_or(true);
+        if !enabled {
+            return None;
+        }
+
+        let root = std::env::var(CLONE_CACHE_DIR_ENV)
+            .ok()
+            .filter(|dir| !dir.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| workspace_dir.join("repo-cache"));
+        let max_bytes = std::env::var(CLONE_CACHE_MAX_SIZE_ENV)
+            .ok()
+            .and_then(|v| match autodev_core::sandbox::parse_memory(&v) {
+                Ok(bytes) => Some(bytes as u64),
+                Err(e) => {
+                    tracing::warn!("{}: {}", CLONE_CACHE_MAX_SIZE_ENV, e);
+                    None
+                }
+            })
+            .unwrap_or(DEFAULT_CLONE_CACHE_MAX_BYTES);
+
+        Some(Self::new(root, max_bytes, github_token))
+    }
+
+    /// `<root>/<owner>/<name>.git`, refusing names that could leave the cache directory
+    fn mirror_path(&self, repository: &Repository) -> Result<PathBuf> {
+        let valid = |part: &str| {
+            !part.is_empty()
+                && !part.contains("..")
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+        };
+        if !valid(&repository.owner) || !valid(&repository.name) {
+            return Err(LocalExecutorError::ExecutionFailed(format!(
+                "Invalid repository name for the clone cache: {}",
+                repository
+            )));
+        }
+
+        Ok(self
+            .root
+            .join(&repository.owner)
+            .join(format!("{}.git", repository.name)))
+    }
+
+    /// Bring the repository's mirror up to date and lease it for one container
+    pub async fn prepare(self: &Arc<Self>, repository: &Repository) -> Result<CacheLease> {
+        let path = self.mirror_path(repository)?;
+
+        // Lease before fetching so a concurrent eviction skips the mirror
+        let lock = {
+            let mut mirrors = self.mirrors.lock().unwrap();
+            let state = mirrors.entry(path.clone()).or_default();
+            state.leases += 1;
+            state.lock.clone()
+        };
+        let lease = CacheLease {
+            cache: self.clone(),
+            path: path.clone(),
+        };
+
+        {
+            let _guard = lock.lock().await;
+            let url = repository.clone_url();
+            let token = self.github_token.clone();
+            let mirror = path.clone();
+
+            tokio::task::spawn_blocking(move || update_mirror(&mirror, &url, &token))
+                .await
+                .map_err(|e| LocalExecutorError::ExecutionFailed(e.to_string()))??;
+        }
+
+        self.evict().await;
+
+        Ok(lease)
+    }
+
+    /// Remove the least recently used mirrors until the cache fits its limit
+    async fn evict(&self) {
+        let in_use: Vec<PathBuf> = self
+            .mirrors
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, state)| state.leases > 0)
+            .map(|(path, _)| path.clone())
+            .collect();
+        let root = self.root.clone();
+        let max_bytes = self.max_bytes;
+
+        let evicted = tokio::task::spawn_blocking(move || evict_mirrors(&root, max_bytes, &in_use)).await;
+        match evicted {
+            Ok(Ok(evicted)) => {
+                for path in evicted {
+                    tracing::info!("Evicted cached mirror {:?}", path);
+                }
+            }
+            Ok(Err(e)) => tracing::warn!("Failed to evict cached mirrors: {}", e),
+            Err(e) => tracing::warn!("Failed to evict cached mirrors: {}", e),
+        }
+    }
+}
+
+/// Create the bare mirror at `path` if needed and fetch every branch into it
+fn update_mirror(path: &Path, url: &str, token: &str) -> Result<()> {
+    let repo = if path.join("HEAD").exists() {
+        git2::Repository::open_bare(path)?
+    } else {
+        tracing::info!("Creating mirror of {} at {:?}", url, path);
+        std::fs::create_dir_all(path)?;
+        git2::Repository::init_bare(path)?
+    };
+
+    let mut remote = match repo.find_remote("origin") {
+        Ok(remote) => remote,
+        Err(_) => repo.remote_with_fetch("origin", url, MIRROR_REFSPEC)?,
+    };
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |_url, _username_from_url, _allowed_types| {
+        Cred::userpass_plaintext("x-access-token", token)
+    });
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+    fetch_options.prune(FetchPrune::On);
+
+    remote.fetch(&[MIRROR_REFSPEC], Some(&mut fetch_options), None)?;
+    tracing::debug!("Mirror {:?} fetched from {}", path, url);
+
+    std::fs::File::create(path.join(LAST_USED_FILE))?;
+    Ok(())
+}
+
+/// Evict mirrors under `root` (laid out as `<owner>/<name>.git`), oldest use first
+fn evict_mirrors(root: &Path, max_bytes: u64, in_use: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut mirrors = Vec::new();
+    for owner in read_dirs(root)? {
+        for mirror in read_dirs(&owner)? {
+            let last_used = std::fs::metadata(mirror.join(LAST_USED_FILE))
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            mirrors.push((last_used, dir_size(&mirror)?, mirror));
+        }
+    }
+
+    let mut total: u64 = mirrors.iter().map(|(_, size, _)| size).sum();
+    mirrors.sort_by_key(|(last_used, _, _)| *last_used);
+
+    let mut evicted = Vec::new();
+    for (_, size, mirror) in mirrors {
+        if total <= max_bytes {
+            break;
+        }
+        if in_use.contains(&mirror) {
+            continue;
+        }
+
+        std::fs::remove_dir_all(&mirror)?;
+        total -= size;
+        evicted.push(mirror);
+    }
+
+    Ok(evicted)
+}
+
+fn read_dirs(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut dirs = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            dirs.push(entry.path());
+        }
+    }
+    Ok(dirs)
+}
+
+fn dir_size(path: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() { dir_size(&entry.path())? } else { metadata.len() };
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mirror(root: &Path, name: &str, bytes: usize) -> PathBuf {
+        let path = root.join("o").join(format!("{}.git", name));
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("pack"), vec![0u8; bytes]).unwrap();
+        std::fs::File::create(path.join(LAST_USED_FILE)).unwrap();
+        // mtime 해상도가 낮은 파일시스템에서도 사용 순서가 구분되도록
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        path
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let root = tempfile::tempdir().unwrap();
+        let oldest = mirror(root.path(), "oldest", 100);
+        let in_use = mirror(root.path(), "in-use", 100);
+        let older = mirror(root.path(), "older", 100);
+        let newest = mirror(root.path(), "newest", 100);
+
+        let evicted = evict_mirrors(root.path(), 250, std::slice::from_ref(&in_use)).unwrap();
+
+        assert_eq!(evicted, vec![oldest.clone(), older.clone()]);
+        assert!(!oldest.exists() && !older.exists());
+        assert!(in_use.exists() && newest.exists());
+    }
+
+    #[test]
+    fn test_mirror_path() {
+        let cache = CloneCache::new(PathBuf::from("/cache"), 0, String::new());
+        let path = |owner: &str, name: &str| cache.mirror_path(&Repository::new(owner.to_string(), name.to_string()));
+
+        assert_eq!(path("load28", "a-dev.rs").unwrap(), PathBuf::from("/cache/load28/a-dev.rs.git"));
+        assert!(path("..", "x").is_err());
+        assert!(path("o", "..").is_err());
+        assert!(path("o", "a/../../etc").is_err());
+        assert!(path("o", "").is_err());
+        assert!(path("/etc", "x").is_err());
+    }
+}
@@ -1,17 +1,28 @@
 use anyhow::{anyhow, Result};
 use bollard::Docker;
-use bollard::container::{Config, CreateContainerOptions, LogsOptions, StartContainerOptions, WaitContainerOptions};
+use bollard::container::{Config, CreateContainerOptions, LogsOptions, StartContainerOptions, StopContainerOptions, WaitContainerOptions};
 use bollard::models::{HostConfig, Mount, MountTypeEnum};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use futures_util::StreamExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use autodev_core::Task;
+use autodev_core::{ContainerSandbox, Task};
 use autodev_github::Repository;
 
-const WORKER_IMAGE: &str = "autodev-worker:latest";
+use crate::artifacts::{read_artifacts, ArtifactUploader};
+use crate::cache::{CloneCache, CACHE_MOUNT_PATH};
+use crate::executor::LocalExecutor;
+use crate::git::{self, GitManager};
+use crate::image::{self, WORKER_IMAGE};
+use crate::logs::{log_file_path, LineBatcher, LogSink, LOG_FLUSH_INTERVAL};
+use crate::secrets::{secret_env, SecretSource};
+
+/// Paths the worker writes to, kept writable by tmpfs under a read-only root
+const WORKER_WRITABLE_PATHS: [&str; 3] = ["/tmp", "/workspace", "/home/node"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskResult {
@@ -20,19 +31,34 @@ pub struct TaskResult {
     pub pr_url: Option<String>,
     pub success: bool,
     pub error: Option<String>,
+    /// Commit pushed by a branch fix or conflict resolution
+    #[serde(default)]
+    pub commit_sha: Option<String>,
+    #[serde(default)]
+    pub files_changed: Vec<String>,
+    /// Lines added and removed by the pushed commit
+    #[serde(default)]
+    pub lines_added: u64,
+    #[serde(default)]
+    pub lines_removed: u64,
 }
 
 pub struct DockerExecutor {
     docker: Docker,
-    anthropic_api_key: Option<String>,
     github_token: String,
     autodev_server_url: Option<String>,
     workspace_dir: PathBuf,
+    sandbox: ContainerSandbox,
+    log_sink: Option<Arc<dyn LogSink>>,
+    clone_cache: Option<Arc<CloneCache>>,
+    secrets: Option<Arc<dyn SecretSource>>,
+    artifacts: Option<ArtifactUploader>,
 }
 
 impl DockerExecutor {
+    /// `_anthropic_api_key` is unused: containers authenticate with the Claude subscription
     pub async fn new(
-        anthropic_api_key: Option<String>,
+        _anthropic_api_key: Option<String>,
         github_token: String,
         autodev_server_url: Option<String>,
         workspace_dir: PathBuf,
@@ -42,51 +68,96 @@ impl DockerExecutor {
         // Verify docker connection
         docker.ping().await?;
 
+        // Build the worker image when it is missing and AUTODEV_WORKER_AUTO_BUILD is set
+        if image::image_exists(&docker, WORKER_IMAGE).await? {
+            image::check_result_schema(&docker, WORKER_IMAGE).await?;
+        } else {
+            if image::auto_build_enabled() {
+                image::build_image(&docker, &image::worker_context_dir(), WORKER_IMAGE, false, image::log_output).await?;
+            } else {
+                tracing::warn!(
+                    "Worker image {} not found. Build it with 'autodev build-image' or set {}=true",
+                    WORKER_IMAGE,
+                    image::WORKER_AUTO_BUILD_ENV
+                );
+            }
+        }
+
         // Create workspace directory if it doesn't exist
         fs::create_dir_all(&workspace_dir).await?;
 
+        let clone_cache = CloneCache::from_env(&workspace_dir, github_token.clone()).map(Arc::new);
+        let artifacts = ArtifactUploader::from_env(autodev_server_url.as_deref());
+
         Ok(Self {
             docker,
-            anthropic_api_key,
             github_token,
             autodev_server_url,
             workspace_dir,
+            sandbox: ContainerSandbox::from_env(),
+            log_sink: None,
+            clone_cache,
+            secrets: None,
+            artifacts,
         })
     }
 
-    pub async fn execute_task(
-        &self,
-        task: &Task,
-        repository: &Repository,
-        base_branch: &str,
-        target_branch: &str,
-        composite_task_id: Option<&str>,
-    ) -> Result<TaskResult> {
-        tracing::info!(
-            "Executing task {} in Docker container for {}/{}",
-            task.id,
-            repository.owner,
-            repository.name
-        );
+    /// Override the container limits read from AUTODEV_CONTAINER_*
+    pub fn with_sandbox(mut self, sandbox: ContainerSandbox) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
 
+    /// Stream container output to `sink` while containers run (it is always
+    /// written to the log file as well)
+    pub fn with_log_sink(mut self, sink: Arc<dyn LogSink>) -> Self {
+        self.log_sink = Some(sink);
+        self
+    }
+
+    /// Pass the repository's secrets from `source` to the containers as
+    /// environment variables
+    pub fn with_secret_source(mut self, source: Arc<dyn SecretSource>) -> Self {
+        self.secrets = Some(source);
+        self
+    }
+
+    /// Run the worker container with `env_strings` and read its result.json
+    ///
+    /// With `upload`, the log file and result.json are uploaded to the server
+    /// as artifacts of task `id`.
+    async fn run_worker(&self, id: &str, repository: &Repository, env_strings: Vec<String>, upload: bool) -> Result<TaskResult> {
         // Create output directory on HOST filesystem
-        let output_dir = self.workspace_dir.join(format!("output-{}", task.id));
+        let output_dir = self.workspace_dir.join(format!("output-{}", id));
         fs::create_dir_all(&output_dir).await?;
 
         tracing::debug!("Created output directory: {:?}", output_dir);
 
-        // Build environment variables
-        let mut env_strings = vec![
-            format!("GITHUB_TOKEN={}", self.github_token),
-            format!("TASK_ID={}", task.id),
-            format!("TASK_TITLE={}", task.title),
-            format!("TASK_PROMPT={}", task.prompt),
-            format!("REPO_OWNER={}", repository.owner),
-            format!("REPO_NAME={}", repository.name),
-            format!("BASE_BRANCH={}", base_branch),
-            format!("TARGET_BRANCH={}", target_branch),
-            format!("COMPOSITE_TASK_ID={}", composite_task_id.unwrap_or("standalone")),
-        ];
+        let result = self.run_container(id, repository, env_strings, &output_dir).await;
+
+        if let Some(artifacts) = self.artifacts.as_ref().filter(|_| upload) {
+            let log_file = log_file_path(&self.workspace_dir, id);
+            let result_file = output_dir.join("result.json");
+            let files = read_artifacts(&[("logs.txt", &log_file), ("result.json", &result_file)]).await;
+            artifacts.upload(id, files).await;
+        }
+
+        // The output of failed runs is kept for debugging
+        if result.is_ok() {
+            fs::remove_dir_all(&output_dir).await.ok();
+        }
+
+        result
+    }
+
+    async fn run_container(
+        &self,
+        id: &str,
+        repository: &Repository,
+        mut env_strings: Vec<String>,
+        output_dir: &Path,
+    ) -> Result<TaskResult> {
+        env_strings.push(format!("GITHUB_TOKEN={}", self.github_token));
 
         // Use Claude subscription OAuth token for Docker executor
         if let Ok(oauth_token) = std::env::var("CLAUDE_CODE_OAUTH_TOKEN") {
@@ -102,6 +173,24 @@ impl DockerExecutor {
             env_strings.push(format!("AUTODEV_SERVER_URL={}", url));
         }
 
+        // The container fetches its task payload with the executor's key
+        if let Ok(api_key) = std::env::var(crate::artifacts::API_KEY_ENV) {
+            env_strings.push(format!("{}={}", crate::artifacts::API_KEY_ENV, api_key));
+        }
+
+        // Repository secrets are resolved now rather than stored with the task
+        if let Some(ref source) = self.secrets {
+            let secrets = source
+                .repository_secrets(repository)
+                .await
+                .map_err(|e| anyhow!("Failed to load the secrets of {}/{}: {}", repository.owner, repository.name, e))?;
+            if !secrets.is_empty() {
+                tracing::info!("Passing {} repository secrets to the container", secrets.len());
+            }
+            let entries = secret_env(&env_strings, secrets);
+            env_strings.extend(entries);
+        }
+
         let env: Vec<&str> = env_strings.iter().map(|s| s.as_str()).collect();
 
         // Create container configuration with HOST path bind mount
@@ -139,21 +228,43 @@ impl DockerExecutor {
             return Err(anyhow!("HOME environment variable not set"));
         }
 
+        // Mirror the worker clones against; without it the worker clones from scratch
+        let cache_lease = match &self.clone_cache {
+            Some(cache) => match cache.prepare(repository).await {
+                Ok(lease) => Some(lease),
+                Err(e) => {
+                    tracing::warn!("Clone cache unavailable for {}: {}", repository.full_name(), e);
+                    None
+                }
+            },
+            None => None,
+        };
+        if let Some(path) = cache_lease.as_ref().and_then(|lease| lease.path().to_str()) {
+            mounts.push(Mount {
+                target: Some(CACHE_MOUNT_PATH.to_string()),
+                source: Some(path.to_string()),
+                typ: Some(MountTypeEnum::BIND),
+                read_only: Some(true),
+                ..Default::default()
+            });
+        }
+
         let host_config = HostConfig {
             mounts: Some(mounts),
             auto_remove: Some(true),
-            ..Default::default()
+            ..self.sandbox.host_config(&WORKER_WRITABLE_PATHS)
         };
 
         let config = Config {
             image: Some(WORKER_IMAGE),
             env: Some(env),
             host_config: Some(host_config),
+            network_disabled: Some(self.sandbox.network_disabled()),
             ..Default::default()
         };
 
         // Create container
-        let container_name = format!("autodev-task-{}", task.id);
+        let container_name = format!("autodev-task-{}", id);
         let create_options = CreateContainerOptions {
             name: container_name.clone(),
             platform: None,
@@ -176,18 +287,19 @@ impl DockerExecutor {
         tracing::info!("Started container: {}", container.id);
 
         // Create log file path
-        let log_file_path = self.workspace_dir.join(format!("logs-{}.txt", task.id));
+        let log_file_path = log_file_path(&self.workspace_dir, id);
         // Create log file to ensure it exists
         let _ = fs::File::create(&log_file_path).await?;
 
         tracing::info!("Collecting container logs to: {:?}", log_file_path);
 
-        // Collect container logs in the background
+        // Collect container logs in the background, batching them to the log sink
         let docker_clone = self.docker.clone();
         let container_id_clone = container.id.clone();
         let log_file_path_clone = log_file_path.clone();
+        let mut batcher = LineBatcher::new(id, self.log_sink.clone());
 
-        tokio::spawn(async move {
+        let log_handle = tokio::spawn(async move {
             let log_options = LogsOptions::<String> {
                 follow: true,
                 stdout: true,
@@ -198,20 +310,34 @@ impl DockerExecutor {
 
             let mut log_stream = docker_clone.logs(&container_id_clone, Some(log_options));
 
-            if let Ok(mut file) = fs::OpenOptions::new()
+            let mut file = fs::OpenOptions::new()
                 .create(true)
                 .append(true)
                 .open(&log_file_path_clone)
                 .await
-            {
-                while let Some(log_result) = log_stream.next().await {
-                    if let Ok(log_output) = log_result {
-                        let log_str = log_output.to_string();
-                        let _ = file.write_all(log_str.as_bytes()).await;
-                        let _ = file.flush().await;
-                    }
+                .ok();
+
+            let mut flush = tokio::time::interval(LOG_FLUSH_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    log_result = log_stream.next() => match log_result {
+                        Some(Ok(log_output)) => {
+                            let log_str = log_output.to_string();
+                            if let Some(file) = file.as_mut() {
+                                let _ = file.write_all(log_str.as_bytes()).await;
+                                let _ = file.flush().await;
+                            }
+                            batcher.push(&log_str).await;
+                        }
+                        Some(Err(_)) => {}
+                        None => break,
+                    },
+                    _ = flush.tick() => batcher.flush().await,
                 }
             }
+
+            batcher.flush().await;
         });
 
         // Wait for container to finish
@@ -235,6 +361,9 @@ impl DockerExecutor {
 
         tracing::info!("Container exited with code: {}", exit_code);
 
+        // The log stream ends with the container; wait for the last batch
+        let _ = log_handle.await;
+
         // If container failed, include log tail in error
         if exit_code != 0 {
             let log_tail = Self::read_log_tail(&log_file_path, 50).await;
@@ -257,10 +386,9 @@ impl DockerExecutor {
             )
         })?;
 
-        let result: TaskResult = serde_json::from_str(&result_content)?;
-
-        // Cleanup output directory
-        fs::remove_dir_all(&output_dir).await.ok();
+        let result = crate::result::parse_result(&result_content).map_err(|e| {
+            anyhow!("{}\nCheck log file at: {:?}", e, log_file_path)
+        })?;
 
         // Container is auto-removed due to auto_remove flag
         tracing::info!("Task execution completed: {:?}", result);
@@ -275,29 +403,12 @@ impl DockerExecutor {
     }
 
     pub async fn check_worker_image_exists(&self) -> Result<bool> {
-        let images = self.docker.list_images::<String>(None).await?;
-
-        for image in images {
-            let repo_tags = &image.repo_tags;
-            if repo_tags.contains(&WORKER_IMAGE.to_string()) {
-                return Ok(true);
-            }
-        }
-
-        Ok(false)
+        image::image_exists(&self.docker, WORKER_IMAGE).await
     }
 
-    pub async fn build_worker_image(&self, dockerfile_path: &str) -> Result<()> {
-        tracing::info!("Building worker image from: {}", dockerfile_path);
-
-        // This is a simplified version - in production, you'd want to use
-        // bollard's build_image method with proper tar stream
-
-        Err(anyhow!(
-            "Worker image build not implemented. Please build manually with: \
-            cd docker/worker && docker build -t {} .",
-            WORKER_IMAGE
-        ))
+    /// Build the worker image from `context_dir` (usually `docker/worker`)
+    pub async fn build_worker_image(&self, context_dir: &Path, no_cache: bool) -> Result<()> {
+        image::build_image(&self.docker, context_dir, WORKER_IMAGE, no_cache, image::log_output).await
     }
 
     /// Read last N lines from log file
@@ -312,3 +423,140 @@ impl DockerExecutor {
         }
     }
 }
+
+#[async_trait]
+impl LocalExecutor for DockerExecutor {
+    async fn execute_task(
+        &self,
+        task: &Task,
+        repository: &Repository,
+        base_branch: &str,
+        target_branch: &str,
+        composite_task_id: Option<&str>,
+    ) -> Result<TaskResult> {
+        tracing::info!(
+            "Executing task {} in Docker container for {}/{}",
+            task.id,
+            repository.owner,
+            repository.name
+        );
+
+        // Build environment variables
+        let env_strings = vec![
+            format!("TASK_ID={}", task.id),
+            format!("TASK_TITLE={}", task.title),
+            format!("TASK_PROMPT={}", task.prompt),
+            format!("REPO_OWNER={}", repository.owner),
+            format!("REPO_NAME={}", repository.name),
+            format!("BASE_BRANCH={}", base_branch),
+            format!("TARGET_BRANCH={}", target_branch),
+            format!("COMPOSITE_TASK_ID={}", composite_task_id.unwrap_or("standalone")),
+        ];
+
+        self.run_worker(&task.id, repository, env_strings, true).await
+    }
+
+    async fn fix_branch(
+        &self,
+        id: &str,
+        repository: &Repository,
+        branch: &str,
+        title: &str,
+        prompt: &str,
+    ) -> Result<TaskResult> {
+        tracing::info!(
+            "Fixing branch {} of {}/{} in Docker container",
+            branch,
+            repository.owner,
+            repository.name
+        );
+
+        let env_strings = vec![
+            "WORK_MODE=fix".to_string(),
+            format!("TASK_ID={}", id),
+            format!("TASK_TITLE={}", title),
+            format!("TASK_PROMPT={}", prompt),
+            format!("REPO_OWNER={}", repository.owner),
+            format!("REPO_NAME={}", repository.name),
+            format!("BASE_BRANCH={}", branch),
+            format!("TARGET_BRANCH={}", branch),
+            "COMPOSITE_TASK_ID=standalone".to_string(),
+        ];
+
+        self.run_worker(id, repository, env_strings, false).await
+    }
+
+    async fn resolve_conflicts(
+        &self,
+        id: &str,
+        repository: &Repository,
+        branch: &str,
+        onto: &str,
+        title: &str,
+        prompt: &str,
+    ) -> Result<TaskResult> {
+        tracing::info!(
+            "Rebasing {} onto {} of {}/{} in Docker container",
+            branch,
+            onto,
+            repository.owner,
+            repository.name
+        );
+
+        let env_strings = vec![
+            "WORK_MODE=resolve".to_string(),
+            format!("TASK_ID={}", id),
+            format!("TASK_TITLE={}", title),
+            format!("TASK_PROMPT={}", prompt),
+            format!("REPO_OWNER={}", repository.owner),
+            format!("REPO_NAME={}", repository.name),
+            format!("BASE_BRANCH={}", onto),
+            format!("TARGET_BRANCH={}", branch),
+            "COMPOSITE_TASK_ID=standalone".to_string(),
+        ];
+
+        self.run_worker(id, repository, env_strings, false).await
+    }
+
+    /// Merges run on the host with git, not in a container
+    async fn merge_branch(
+        &self,
+        id: &str,
+        repository: &Repository,
+        target: &str,
+        branch: &str,
+    ) -> Result<String> {
+        tracing::info!("Merging {} into {} of {}/{}", branch, target, repository.owner, repository.name);
+
+        let git = Arc::new(GitManager::new(self.github_token.clone()));
+        let checkout = self.workspace_dir.join(format!("merge-{}", id));
+        Ok(git::merge_and_push(git, checkout, repository, target, branch).await?)
+    }
+
+    /// Containers are created with auto_remove, so stopping also cleans them up
+    async fn stop_task(&self, task_id: &str) -> Result<bool> {
+        let container_name = format!("autodev-task-{}", task_id);
+
+        match self
+            .docker
+            .stop_container(&container_name, Some(StopContainerOptions { t: 10 }))
+            .await
+        {
+            Ok(()) => {
+                tracing::info!("Stopped container {} for task {}", container_name, task_id);
+                Ok(true)
+            }
+            Err(bollard::errors::Error::DockerResponseServerError { status_code: 404, .. })
+            | Err(bollard::errors::Error::DockerResponseServerError { status_code: 304, .. }) => {
+                tracing::debug!("No running container for task {}", task_id);
+                Ok(false)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.docker.ping().await?;
+        Ok(())
+    }
+}
@@ -20,6 +20,12 @@ pub enum LocalExecutorError {
     #[error("Task execution failed: {0}")]
     ExecutionFailed(String),
 
+    #[error("Invalid result.json: {0}")]
+    InvalidResult(String),
+
+    #[error("Merging {branch} conflicts in {}", files.join(", "))]
+    MergeConflict { branch: String, files: Vec<String> },
+
     #[error("GitHub error: {0}")]
     GitHub(#[from] autodev_github::Error),
 
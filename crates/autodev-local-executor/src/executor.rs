@@ -0,0 +1,199 @@
+//! Common interface of the local execution backends
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use autodev_core::{ExecutionBackend, Task};
+use autodev_github::Repository;
+
+use crate::docker_executor::{DockerExecutor, TaskResult};
+use crate::logs::LogSink;
+use crate::secrets::SecretSource;
+use crate::process_executor::ProcessExecutor;
+
+/// Selects the local execution backend: `docker` (default) or `process`
+pub const EXECUTOR_ENV: &str = "AUTODEV_EXECUTOR";
+
+/// Runs the agent for a task on this machine and reports a [`TaskResult`]
+#[async_trait]
+pub trait LocalExecutor: Send + Sync {
+    /// Branch from `base_branch`, run the task and open a PR against `target_branch`
+    async fn execute_task(
+        &self,
+        task: &Task,
+        repository: &Repository,
+        base_branch: &str,
+        target_branch: &str,
+        composite_task_id: Option<&str>,
+    ) -> Result<TaskResult>;
+
+    /// Let the agent fix an existing branch (e.g. a PR failing CI)
+    ///
+    /// The agent runs on a checkout of `branch` with `prompt`, and the changes
+    /// are committed and pushed to the same branch without opening a PR.
+    /// `id` names the run and its logs.
+    async fn fix_branch(
+        &self,
+        id: &str,
+        repository: &Repository,
+        branch: &str,
+        title: &str,
+        prompt: &str,
+    ) -> Result<TaskResult>;
+
+    /// Rebase `branch` onto `onto`, letting the agent resolve conflicting hunks
+    ///
+    /// The rebase stops at every conflicting commit, runs the agent with
+    /// `prompt` plus the conflicting files, and force-pushes the rebased
+    /// branch. `files_changed` lists the files that had conflicts.
+    async fn resolve_conflicts(
+        &self,
+        id: &str,
+        repository: &Repository,
+        branch: &str,
+        onto: &str,
+        title: &str,
+        prompt: &str,
+    ) -> Result<TaskResult>;
+
+    /// Merge `branch` into `target` with a merge commit in a local checkout
+    /// and push `target`
+    ///
+    /// A conflicting merge pushes nothing and fails with
+    /// [`crate::LocalExecutorError::MergeConflict`]. `id` names the checkout.
+    /// Returns the commit `target` was pushed at.
+    async fn merge_branch(
+        &self,
+        id: &str,
+        repository: &Repository,
+        target: &str,
+        branch: &str,
+    ) -> Result<String>;
+
+    /// Stop the run of a task, if any
+    ///
+    /// Returns false when nothing was running for the task.
+    async fn stop_task(&self, task_id: &str) -> Result<bool>;
+
+    /// Check the backend can run tasks now (e.g. the Docker daemon answers)
+    async fn ping(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutorKind {
+    /// Worker containers (requires a Docker daemon)
+    #[default]
+    Docker,
+    /// Claude CLI and git subprocesses in a workspace directory
+    Process,
+}
+
+impl ExecutorKind {
+    /// Backend selected by AUTODEV_EXECUTOR; unknown values fall back to Docker
+    pub fn from_env() -> Self {
+        match std::env::var(EXECUTOR_ENV) {
+            Ok(value) => value.parse().unwrap_or_else(|_| {
+                tracing::warn!("Unknown {} '{}', using docker", EXECUTOR_ENV, value);
+                Self::Docker
+            }),
+            Err(_) => Self::Docker,
+        }
+    }
+}
+
+impl ExecutorKind {
+    /// Local executor running tasks of `backend`; None for the non-local ones
+    /// and backends without a local executor (k8s)
+    pub fn for_backend(backend: ExecutionBackend) -> Option<Self> {
+        match backend {
+            ExecutionBackend::Docker => Some(Self::Docker),
+            ExecutionBackend::Process => Some(Self::Process),
+            ExecutionBackend::Actions | ExecutionBackend::K8s => None,
+        }
+    }
+}
+
+impl From<ExecutorKind> for ExecutionBackend {
+    fn from(kind: ExecutorKind) -> Self {
+        match kind {
+            ExecutorKind::Docker => ExecutionBackend::Docker,
+            ExecutorKind::Process => ExecutionBackend::Process,
+        }
+    }
+}
+
+impl std::str::FromStr for ExecutorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "docker" => Ok(Self::Docker),
+            "process" | "native" => Ok(Self::Process),
+            other => Err(format!("unknown executor: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for ExecutorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Docker => write!(f, "docker"),
+            Self::Process => write!(f, "process"),
+        }
+    }
+}
+
+/// Create the executor of `kind` (usually [`ExecutorKind::from_env`])
+///
+/// Output is streamed to `log_sink` while tasks run. Worker containers get
+/// the repository's secrets from `secrets`; the process executor runs on this
+/// host's environment and does not use them.
+pub async fn create_executor(
+    kind: ExecutorKind,
+    anthropic_api_key: Option<String>,
+    github_token: String,
+    autodev_server_url: Option<String>,
+    workspace_dir: PathBuf,
+    log_sink: Option<Arc<dyn LogSink>>,
+    secrets: Option<Arc<dyn SecretSource>>,
+) -> Result<Arc<dyn LocalExecutor>> {
+    match kind {
+        ExecutorKind::Docker => {
+            let mut executor =
+                DockerExecutor::new(anthropic_api_key, github_token, autodev_server_url, workspace_dir).await?;
+            if let Some(sink) = log_sink {
+                executor = executor.with_log_sink(sink);
+            }
+            if let Some(source) = secrets {
+                executor = executor.with_secret_source(source);
+            }
+            Ok(Arc::new(executor))
+        }
+        ExecutorKind::Process => {
+            let mut executor =
+                ProcessExecutor::new(anthropic_api_key, github_token, autodev_server_url, workspace_dir).await?;
+            if let Some(sink) = log_sink {
+                executor = executor.with_log_sink(sink);
+            }
+            Ok(Arc::new(executor))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_executor_kind() {
+        assert_eq!("docker".parse::<ExecutorKind>(), Ok(ExecutorKind::Docker));
+        assert_eq!(" Process ".parse::<ExecutorKind>(), Ok(ExecutorKind::Process));
+        assert_eq!("native".parse::<ExecutorKind>(), Ok(ExecutorKind::Process));
+        assert!("podman".parse::<ExecutorKind>().is_err());
+        assert_eq!(ExecutorKind::Process.to_string(), "process");
+    }
+}
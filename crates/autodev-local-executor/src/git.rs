@@ -1,6 +1,8 @@
-use crate::error::Result;
-use git2::{Repository, Signature, RemoteCallbacks, Cred, PushOptions};
+use crate::error::{LocalExecutorError, Result};
+use autodev_github::GitHubUrls;
+use git2::{Repository, Signature, RemoteCallbacks, Cred, PushOptions, StatusOptions};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{info, debug};
 
 pub struct GitManager {
@@ -20,7 +22,7 @@ impl GitManager {
         branch: &str,
         target_dir: &Path,
     ) -> Result<Repository> {
-        let repo_url = format!("https://github.com/{}/{}.git", owner, name);
+        let repo_url = autodev_github::Repository::new(owner.to_string(), name.to_string()).clone_url();
 
         info!("Cloning repository {} to {:?}", repo_url, target_dir);
 
@@ -76,8 +78,9 @@ impl GitManager {
 
         let mut index = repo.index()?;
 
-        // Add all changes
+        // Add all changes, including deletions
         index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+        index.update_all(["*"].iter(), None)?;
         index.write()?;
 
         let tree_id = index.write_tree()?;
@@ -106,6 +109,15 @@ impl GitManager {
 
     /// Push branch to remote
     pub fn push_branch(&self, repo: &Repository, branch_name: &str) -> Result<()> {
+        self.push(repo, branch_name, false)
+    }
+
+    /// Push branch to remote, overwriting its history (e.g. after a rebase)
+    pub fn force_push_branch(&self, repo: &Repository, branch_name: &str) -> Result<()> {
+        self.push(repo, branch_name, true)
+    }
+
+    fn push(&self, repo: &Repository, branch_name: &str, force: bool) -> Result<()> {
         info!("Pushing branch: {}", branch_name);
 
         let mut remote = repo.find_remote("origin")?;
@@ -122,7 +134,12 @@ impl GitManager {
         push_options.remote_callbacks(callbacks);
 
         // Push the branch
-        let refspec = format!("refs/heads/{}:refs/heads/{}", branch_name, branch_name);
+        let refspec = format!(
+            "{}refs/heads/{}:refs/heads/{}",
+            if force { "+" } else { "" },
+            branch_name,
+            branch_name
+        );
         remote.push(&[&refspec], Some(&mut push_options))?;
 
         info!("Branch pushed successfully: {}", branch_name);
@@ -130,11 +147,141 @@ impl GitManager {
         Ok(())
     }
 
+    /// Fetch `branch` from origin into `refs/remotes/origin/<branch>`
+    pub fn fetch_branch(&self, repo: &Repository, branch: &str) -> Result<git2::Oid> {
+        debug!("Fetching branch: {}", branch);
+
+        let mut remote = repo.find_remote("origin")?;
+
+        let mut callbacks = RemoteCallbacks::new();
+        let token = self.github_token.clone();
+
+        callbacks.credentials(move |_url, _username_from_url, _allowed_types| {
+            Cred::userpass_plaintext("x-access-token", &token)
+        });
+
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+
+        let refspec = format!("+refs/heads/{}:refs/remotes/origin/{}", branch, branch);
+        remote.fetch(&[&refspec], Some(&mut fetch_options), None)?;
+
+        Ok(repo.refname_to_id(&format!("refs/remotes/origin/{}", branch))?)
+    }
+
+    /// Merge `branch` from origin into HEAD with a merge commit
+    ///
+    /// A branch HEAD already contains is a no-op. When the merge conflicts
+    /// nothing is committed and the conflicting paths are returned in
+    /// [`LocalExecutorError::MergeConflict`].
+    pub fn merge_branch(&self, repo: &Repository, branch: &str, message: &str) -> Result<git2::Oid> {
+        let theirs_id = self.fetch_branch(repo, branch)?;
+        let head = repo.head()?.peel_to_commit()?;
+
+        if head.id() == theirs_id || repo.graph_descendant_of(head.id(), theirs_id)? {
+            debug!("{} is already merged", branch);
+            return Ok(head.id());
+        }
+
+        let theirs = repo.find_commit(theirs_id)?;
+        let mut index = repo.merge_commits(&head, &theirs, None)?;
+
+        if index.has_conflicts() {
+            let mut files: Vec<String> = index
+                .conflicts()?
+                .filter_map(|conflict| conflict.ok())
+                .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+                .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+                .collect();
+            files.dedup();
+            return Err(LocalExecutorError::MergeConflict {
+                branch: branch.to_string(),
+                files,
+            });
+        }
+
+        let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+        let sig = Signature::now("AutoDev Bot", "autodev@github-actions.bot")?;
+        let commit_id = repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[&head, &theirs])?;
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+
+        info!("Merged {} into HEAD: {}", branch, commit_id);
+
+        Ok(commit_id)
+    }
+
     /// Check if there are any changes in the working directory
     pub fn has_changes(&self, repo: &Repository) -> Result<bool> {
-        let statuses = repo.statuses(None)?;
+        // 빌드 산출물 같은 ignored 파일은 변경으로 보지 않음
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true).include_ignored(false);
+
+        let statuses = repo.statuses(Some(&mut options))?;
         Ok(!statuses.is_empty())
     }
+
+    /// Paths touched by `commit` relative to its first parent
+    pub fn files_changed(&self, repo: &Repository, commit: git2::Oid) -> Result<Vec<String>> {
+        let diff = commit_diff(repo, commit)?;
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect())
+    }
+
+    /// Lines (added, removed) by `commit` relative to its first parent
+    pub fn line_counts(&self, repo: &Repository, commit: git2::Oid) -> Result<(u64, u64)> {
+        let stats = commit_diff(repo, commit)?.stats()?;
+        Ok((stats.insertions() as u64, stats.deletions() as u64))
+    }
+}
+
+/// Clone `target` into `checkout`, merge `branch` into it and push it
+///
+/// Runs on a blocking thread; nothing is pushed when the merge conflicts. The
+/// checkout is removed afterwards. Returns the commit `target` was pushed at.
+pub(crate) async fn merge_and_push(
+    git: Arc<GitManager>,
+    checkout: PathBuf,
+    repository: &autodev_github::Repository,
+    target: &str,
+    branch: &str,
+) -> Result<String> {
+    let (owner, name, target, branch) = (
+        repository.owner.clone(),
+        repository.name.clone(),
+        target.to_string(),
+        branch.to_string(),
+    );
+
+    tokio::task::spawn_blocking(move || {
+        if checkout.exists() {
+            std::fs::remove_dir_all(&checkout)?;
+        }
+
+        let merged = (|| {
+            let repo = git.clone_repository(&owner, &name, &target, &checkout)?;
+            let head = git.merge_branch(&repo, &branch, &format!("Merge branch '{}' into {}", branch, target))?;
+            git.push_branch(&repo, &target)?;
+            Ok(head.to_string())
+        })();
+
+        std::fs::remove_dir_all(&checkout).ok();
+        merged
+    })
+    .await
+    .map_err(anyhow::Error::from)?
+}
+
+fn commit_diff(repo: &Repository, commit: git2::Oid) -> Result<git2::Diff<'_>> {
+    let commit = repo.find_commit(commit)?;
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+
+    Ok(repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?)
 }
 
 #[cfg(test)]
@@ -146,4 +293,62 @@ mod tests {
         let manager = GitManager::new("test_token".to_string());
         assert!(!manager.github_token.is_empty());
     }
+
+    /// Commit `content` to `file` on `branch` of `repo`, starting from `from`
+    fn commit_file(repo: &Repository, branch: &str, from: Option<git2::Oid>, file: &str, content: &str) -> git2::Oid {
+        let workdir = repo.workdir().unwrap().to_path_buf();
+        if let Some(from) = from {
+            repo.checkout_tree(&repo.find_object(from, None).unwrap(), Some(git2::build::CheckoutBuilder::new().force()))
+                .unwrap();
+        }
+        std::fs::write(workdir.join(file), content).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test", "test@example.com").unwrap();
+        let parents: Vec<git2::Commit> = from.map(|id| repo.find_commit(id).unwrap()).into_iter().collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some(&format!("refs/heads/{}", branch)), &sig, &sig, file, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_merge_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let origin = Repository::init(dir.path().join("origin")).unwrap();
+        let base = commit_file(&origin, "parent", None, "a.txt", "a\n");
+        commit_file(&origin, "one", Some(base), "one.txt", "one\n");
+        commit_file(&origin, "two", Some(base), "two.txt", "two\n");
+        commit_file(&origin, "clash", Some(base), "a.txt", "changed\n");
+        commit_file(&origin, "clash-too", Some(base), "a.txt", "different\n");
+
+        let local = git2::build::RepoBuilder::new()
+            .branch("parent")
+            .clone(dir.path().join("origin").to_str().unwrap(), &dir.path().join("local"))
+            .unwrap();
+        let git = GitManager::new(String::new());
+
+        let first = git.merge_branch(&local, "one", "Merge one").unwrap();
+        let second = git.merge_branch(&local, "two", "Merge two").unwrap();
+        let merged = local.find_commit(second).unwrap();
+        assert_eq!(merged.parent_count(), 2);
+        assert_eq!(merged.parent_id(0).unwrap(), first);
+        assert!(local.workdir().unwrap().join("one.txt").exists());
+        assert!(local.workdir().unwrap().join("two.txt").exists());
+
+        // Merging again changes nothing
+        assert_eq!(git.merge_branch(&local, "two", "Merge two").unwrap(), second);
+
+        git.merge_branch(&local, "clash", "Merge clash").unwrap();
+        match git.merge_branch(&local, "clash-too", "Merge clash-too") {
+            Err(LocalExecutorError::MergeConflict { branch, files }) => {
+                assert_eq!(branch, "clash-too");
+                assert_eq!(files, vec!["a.txt".to_string()]);
+            }
+            other => panic!("expected a merge conflict, got {:?}", other.map(|oid| oid.to_string())),
+        }
+        assert_eq!(local.head().unwrap().peel_to_commit().unwrap().parent_count(), 2);
+    }
 }
@@ -0,0 +1,166 @@
+//! Building the worker image the Docker executor runs tasks in
+
+use anyhow::{anyhow, Result};
+use bollard::image::BuildImageOptions;
+use bollard::Docker;
+use futures_util::StreamExt;
+use std::path::{Path, PathBuf};
+
+use crate::result::{check_schema_version, RESULT_SCHEMA_LABEL};
+
+pub const WORKER_IMAGE: &str = "autodev-worker:latest";
+
+/// Build context of the worker image (default: `docker/worker`)
+pub const WORKER_CONTEXT_ENV: &str = "AUTODEV_WORKER_CONTEXT";
+/// Set to `true` to build the worker image at startup when it is missing
+pub const WORKER_AUTO_BUILD_ENV: &str = "AUTODEV_WORKER_AUTO_BUILD";
+
+const DEFAULT_WORKER_CONTEXT: &str = "docker/worker";
+
+/// Build context configured by AUTODEV_WORKER_CONTEXT
+pub fn worker_context_dir() -> PathBuf {
+    std::env::var(WORKER_CONTEXT_ENV)
+        .ok()
+        .filter(|dir| !dir.trim().is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_WORKER_CONTEXT))
+}
+
+pub(crate) fn auto_build_enabled() -> bool {
+    std::env::var(WORKER_AUTO_BUILD_ENV)
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "true" | "1" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// Build the worker image from `context_dir` with the local Docker daemon
+///
+/// Every line of build output is passed to `on_output`.
+pub async fn build_worker_image(context_dir: &Path, no_cache: bool, on_output: impl FnMut(&str)) -> Result<()> {
+    let docker = Docker::connect_with_local_defaults()?;
+    docker.ping().await?;
+
+    build_image(&docker, context_dir, WORKER_IMAGE, no_cache, on_output).await
+}
+
+/// Build output handler writing to the tracing log
+pub(crate) fn log_output(line: &str) {
+    tracing::info!("[build {}] {}", WORKER_IMAGE, line);
+}
+
+pub(crate) async fn image_exists(docker: &Docker, image: &str) -> Result<bool> {
+    match docker.inspect_image(image).await {
+        Ok(_) => Ok(true),
+        Err(bollard::errors::Error::DockerResponseServerError { status_code: 404, .. }) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Fail when `image` writes a result.json schema this server cannot read
+///
+/// Images without the schema label predate versioning and write version 1.
+pub(crate) async fn check_result_schema(docker: &Docker, image: &str) -> Result<()> {
+    let inspect = docker.inspect_image(image).await?;
+    let label = inspect
+        .config
+        .and_then(|config| config.labels)
+        .and_then(|labels| labels.get(RESULT_SCHEMA_LABEL).cloned());
+
+    let version = match label {
+        Some(label) => label.trim().parse::<u32>().map_err(|_| {
+            anyhow!("Worker image {} has an invalid {} label: {:?}", image, RESULT_SCHEMA_LABEL, label)
+        })?,
+        None => {
+            tracing::warn!(
+                "Worker image {} has no {} label and writes result schema version 1; rebuild it with 'autodev build-image'",
+                image,
+                RESULT_SCHEMA_LABEL
+            );
+            1
+        }
+    };
+
+    check_schema_version(version).map_err(|e| anyhow!("Worker image {} is incompatible: {}", image, e))?;
+    tracing::info!("Worker image {} writes result schema version {}", image, version);
+    Ok(())
+}
+
+/// Build `tag` from the Dockerfile in `context_dir`, passing the build output to `on_output`
+pub(crate) async fn build_image(
+    docker: &Docker,
+    context_dir: &Path,
+    tag: &str,
+    no_cache: bool,
+    mut on_output: impl FnMut(&str),
+) -> Result<()> {
+    if !context_dir.join("Dockerfile").is_file() {
+        return Err(anyhow!("No Dockerfile in build context {:?}", context_dir));
+    }
+
+    tracing::info!("Building image {} from {:?}", tag, context_dir);
+
+    let dir = context_dir.to_path_buf();
+    let context = tokio::task::spawn_blocking(move || tar_context(&dir)).await??;
+    tracing::debug!("Build context is {} bytes", context.len());
+
+    let options = BuildImageOptions {
+        dockerfile: "Dockerfile",
+        t: tag,
+        rm: true,
+        forcerm: true,
+        nocache: no_cache,
+        ..Default::default()
+    };
+
+    let mut stream = docker.build_image(options, None, Some(context.into()));
+
+    while let Some(info) = stream.next().await {
+        let info = info?;
+
+        if let Some(error) = info.error {
+            return Err(anyhow!("Building {} failed: {}", tag, error.trim()));
+        }
+        if let Some(output) = info.stream {
+            for line in output.lines().map(str::trim_end).filter(|line| !line.is_empty()) {
+                on_output(line);
+            }
+        }
+        if let Some(status) = info.status {
+            tracing::debug!("[build {}] {} {}", tag, status, info.progress.unwrap_or_default());
+        }
+    }
+
+    tracing::info!("✓ Built image {}", tag);
+    Ok(())
+}
+
+/// Tar archive of `dir`, the form Docker expects a build context in
+fn tar_context(dir: &Path) -> std::io::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(Vec::new());
+    builder.append_dir_all(".", dir)?;
+    builder.into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tar_context() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Dockerfile"), "FROM node:20\n").unwrap();
+        std::fs::create_dir(dir.path().join("scripts")).unwrap();
+        std::fs::write(dir.path().join("scripts/entrypoint.sh"), "#!/bin/bash\n").unwrap();
+
+        let context = tar_context(dir.path()).unwrap();
+
+        let mut archive = tar::Archive::new(context.as_slice());
+        let paths: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().to_string_lossy().to_string())
+            .collect();
+
+        assert!(paths.iter().any(|p| p.trim_start_matches("./") == "Dockerfile"));
+        assert!(paths.iter().any(|p| p.trim_start_matches("./") == "scripts/entrypoint.sh"));
+    }
+}
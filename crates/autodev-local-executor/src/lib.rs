@@ -1,8 +1,25 @@
+mod artifacts;
+mod cache;
 mod error;
 mod docker_executor;
+mod executor;
+mod git;
+mod image;
+mod logs;
+mod process_executor;
+mod result;
+mod secrets;
 
+pub use artifacts::{API_KEY_ENV, UPLOAD_ARTIFACTS_ENV};
+pub use cache::{CacheLease, CloneCache};
 pub use error::{LocalExecutorError, Result};
 pub use docker_executor::{DockerExecutor, TaskResult};
+pub use executor::{create_executor, ExecutorKind, LocalExecutor, EXECUTOR_ENV};
+pub use image::{build_worker_image, worker_context_dir, WORKER_AUTO_BUILD_ENV, WORKER_CONTEXT_ENV, WORKER_IMAGE};
+pub use process_executor::{ProcessExecutor, CLAUDE_BIN_ENV};
+pub use result::{parse_result, MIN_RESULT_SCHEMA_VERSION, RESULT_SCHEMA_LABEL, RESULT_SCHEMA_VERSION};
+pub use logs::{log_file_path, LogSink, LOG_BATCH_LINES, LOG_FLUSH_INTERVAL};
+pub use secrets::SecretSource;
 
 use serde::{Deserialize, Serialize};
 
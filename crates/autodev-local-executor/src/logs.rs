@@ -0,0 +1,109 @@
+//! Live output of worker containers
+
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Lines buffered before a batch is handed to the sink early
+pub const LOG_BATCH_LINES: usize = 50;
+
+/// How often buffered lines are flushed while a container runs
+pub const LOG_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// File the whole output of run `id` is written to (read by `autodev logs`
+/// when no database is configured)
+pub fn log_file_path(workspace_dir: &Path, id: &str) -> PathBuf {
+    workspace_dir.join(format!("logs-{}.txt", id))
+}
+
+/// Receives container output while the container runs
+///
+/// `id` is the ID the container was started for (a task ID, or e.g.
+/// `fix-<task>` for a branch fix). Batches arrive in output order.
+#[async_trait]
+pub trait LogSink: Send + Sync {
+    async fn write_lines(&self, id: &str, lines: Vec<String>);
+}
+
+/// Collects output lines into batches for a [`LogSink`]
+pub(crate) struct LineBatcher {
+    id: String,
+    sink: Option<Arc<dyn LogSink>>,
+    lines: Vec<String>,
+}
+
+impl LineBatcher {
+    pub(crate) fn new(id: &str, sink: Option<Arc<dyn LogSink>>) -> Self {
+        Self {
+            id: id.to_string(),
+            sink,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Buffer the lines of `output`, flushing once a batch is full
+    pub(crate) async fn push(&mut self, output: &str) {
+        if self.sink.is_none() {
+            return;
+        }
+
+        self.lines.extend(
+            output
+                .lines()
+                .map(|line| line.trim_end().to_string())
+                .filter(|line| !line.is_empty()),
+        );
+
+        if self.lines.len() >= LOG_BATCH_LINES {
+            self.flush().await;
+        }
+    }
+
+    pub(crate) async fn flush(&mut self) {
+        if self.lines.is_empty() {
+            return;
+        }
+
+        let lines = std::mem::take(&mut self.lines);
+        if let Some(sink) = &self.sink {
+            sink.write_lines(&self.id, lines).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, Vec<String>)>>);
+
+    #[async_trait]
+    impl LogSink for Recorder {
+        async fn write_lines(&self, id: &str, lines: Vec<String>) {
+            self.0.lock().unwrap().push((id.to_string(), lines));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batches_lines() {
+        let recorder = Arc::new(Recorder::default());
+        let mut batcher = LineBatcher::new("t1", Some(recorder.clone()));
+
+        batcher.push("cloning\r\n\nbuilding\n").await;
+        assert!(recorder.0.lock().unwrap().is_empty());
+
+        batcher.push(&"x\n".repeat(LOG_BATCH_LINES)).await;
+        batcher.push("done").await;
+        batcher.flush().await;
+
+        let batches = recorder.0.lock().unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].0, "t1");
+        assert_eq!(batches[0].1[..2], ["cloning", "building"]);
+        assert_eq!(batches[0].1.len(), LOG_BATCH_LINES + 2);
+        assert_eq!(batches[1].1, vec!["done"]);
+    }
+}
@@ -0,0 +1,745 @@
+//! Local execution without Docker
+//!
+//! Runs the steps of the worker container (docker/worker/entrypoint.sh)
+//! directly on the host: the repository is cloned into a directory of its own
+//! under the workspace, the Claude CLI runs there as a subprocess, and the
+//! changes are committed, pushed and opened as a PR. Apart from the separate
+//! directory there is no isolation, so only use it on trusted machines.
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tokio::fs;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::watch;
+
+use autodev_core::Task;
+use autodev_github::{GitHubClient, Repository};
+
+use crate::docker_executor::TaskResult;
+use crate::executor::LocalExecutor;
+use crate::git::{self, GitManager};
+use crate::artifacts::{read_artifacts, ArtifactUploader};
+use crate::logs::{log_file_path, LineBatcher, LogSink, LOG_FLUSH_INTERVAL};
+
+/// Path or name of the Claude CLI (default: `claude` on PATH)
+pub const CLAUDE_BIN_ENV: &str = "AUTODEV_CLAUDE_BIN";
+
+/// Times the rebase may stop on a commit before conflict resolution gives up
+const MAX_RESOLVE_STEPS: usize = 20;
+
+const COMMITTER_NAME: &str = "AutoDev Bot";
+const COMMITTER_EMAIL: &str = "autodev@github-actions.bot";
+
+pub struct ProcessExecutor {
+    anthropic_api_key: Option<String>,
+    github_token: String,
+    autodev_server_url: Option<String>,
+    workspace_dir: PathBuf,
+    claude_bin: String,
+    git: Arc<GitManager>,
+    github_client: GitHubClient,
+    log_sink: Option<Arc<dyn LogSink>>,
+    /// Stop signals of the runs in progress, by ID
+    running: Mutex<HashMap<String, watch::Sender<bool>>>,
+    artifacts: Option<ArtifactUploader>,
+}
+
+/// Commit pushed by a run
+struct Pushed {
+    sha: String,
+    files: Vec<String>,
+    lines_added: u64,
+    lines_removed: u64,
+}
+
+/// One task, fix or rebase run in its own checkout
+struct Run {
+    id: String,
+    checkout: PathBuf,
+    log_file_path: PathBuf,
+    log_file: Option<fs::File>,
+    batcher: LineBatcher,
+    stopped: watch::Receiver<bool>,
+}
+
+impl Run {
+    async fn log(&mut self, line: &str) {
+        if let Some(file) = self.log_file.as_mut() {
+            let _ = file.write_all(format!("{}\n", line).as_bytes()).await;
+        }
+        self.batcher.push(line).await;
+    }
+
+    fn check_stopped(&self) -> Result<()> {
+        if *self.stopped.borrow() {
+            bail!("Run {} was stopped", self.id);
+        }
+        Ok(())
+    }
+
+    /// Last `lines` lines of the log file
+    async fn log_tail(&mut self, lines: usize) -> String {
+        if let Some(file) = self.log_file.as_mut() {
+            let _ = file.flush().await;
+        }
+        match fs::read_to_string(&self.log_file_path).await {
+            Ok(content) => {
+                let all_lines: Vec<&str> = content.lines().collect();
+                all_lines[all_lines.len().saturating_sub(lines)..].join("\n")
+            }
+            Err(e) => format!("Failed to read log file: {}", e),
+        }
+    }
+}
+
+impl ProcessExecutor {
+    pub async fn new(
+        anthropic_api_key: Option<String>,
+        github_token: String,
+        autodev_server_url: Option<String>,
+        workspace_dir: PathBuf,
+    ) -> Result<Self> {
+        let claude_bin = std::env::var(CLAUDE_BIN_ENV)
+            .ok()
+            .filter(|bin| !bin.trim().is_empty())
+            .unwrap_or_else(|| "claude".to_string());
+
+        // Verify the tools are installed
+        for bin in [claude_bin.as_str(), "git"] {
+            let status = Command::new(bin)
+                .arg("--version")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .await
+                .map_err(|e| anyhow!("{} is not available: {}", bin, e))?;
+            if !status.success() {
+                bail!("{} --version failed with {}", bin, status);
+            }
+        }
+
+        fs::create_dir_all(&workspace_dir).await?;
+        let artifacts = ArtifactUploader::from_env(autodev_server_url.as_deref());
+
+        Ok(Self {
+            anthropic_api_key,
+            github_client: GitHubClient::new(github_token.clone())?,
+            git: Arc::new(GitManager::new(github_token.clone())),
+            github_token,
+            autodev_server_url,
+            workspace_dir,
+            claude_bin,
+            log_sink: None,
+            running: Mutex::new(HashMap::new()),
+            artifacts,
+        })
+    }
+
+    /// Stream output to `sink` while tasks run (it is always written to the
+    /// log file as well)
+    pub fn with_log_sink(mut self, sink: Arc<dyn LogSink>) -> Self {
+        self.log_sink = Some(sink);
+        self
+    }
+
+    /// Register a run and clone `branch` into its checkout
+    async fn start(&self, id: &str, repository: &Repository, branch: &str) -> Result<Run> {
+        let checkout = self.workspace_dir.join(format!("run-{}", id));
+        if fs::metadata(&checkout).await.is_ok() {
+            fs::remove_dir_all(&checkout).await?;
+        }
+
+        let (stop, stopped) = watch::channel(false);
+        self.running.lock().unwrap().insert(id.to_string(), stop);
+
+        let log_file_path = log_file_path(&self.workspace_dir, id);
+        let log_file = fs::File::create(&log_file_path).await.ok();
+        tracing::info!("Collecting output of {} to: {:?}", id, log_file_path);
+
+        let mut run = Run {
+            id: id.to_string(),
+            checkout,
+            log_file_path,
+            log_file,
+            batcher: LineBatcher::new(id, self.log_sink.clone()),
+            stopped,
+        };
+
+        run.log(&format!("Cloning {} ({}) into {:?}", repository.full_name(), branch, run.checkout))
+            .await;
+
+        let git = self.git.clone();
+        let (owner, name, branch, checkout) = (
+            repository.owner.clone(),
+            repository.name.clone(),
+            branch.to_string(),
+            run.checkout.clone(),
+        );
+        let cloned = tokio::task::spawn_blocking(move || git.clone_repository(&owner, &name, &branch, &checkout).map(|_| ()))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|cloned| cloned.map_err(anyhow::Error::from));
+
+        if let Err(e) = cloned {
+            run.log(&format!("ERROR: {}", e)).await;
+            run.batcher.flush().await;
+            self.running.lock().unwrap().remove(id);
+            return Err(e);
+        }
+
+        Ok(run)
+    }
+
+    /// Unregister a run and remove its checkout (the log file is kept)
+    async fn finish(&self, mut run: Run, result: &Result<TaskResult>) {
+        if let Err(e) = result {
+            run.log(&format!("ERROR: {}", e)).await;
+            tracing::error!("Run {} failed. Check logs at: {:?}", run.id, run.log_file_path);
+        }
+
+        self.running.lock().unwrap().remove(&run.id);
+        run.batcher.flush().await;
+        fs::remove_dir_all(&run.checkout).await.ok();
+        tracing::info!("Output of {} saved to: {:?}", run.id, run.log_file_path);
+    }
+
+    /// Run the Claude CLI in the checkout, streaming its output to the log
+    async fn run_claude(&self, run: &mut Run, prompt: &str) -> Result<()> {
+        run.check_stopped()?;
+
+        let mut command = Command::new(&self.claude_bin);
+        command
+            .args([
+                "--dangerously-skip-permissions",
+                "--allowedTools",
+                "Bash,Read,Write,Edit,Glob,Grep",
+                "--model",
+                "sonnet",
+                "--output-format",
+                "text",
+                "--append-system-prompt",
+                "Make autonomous decisions and modify files directly without asking questions. Complete the task in minimal steps.",
+                prompt,
+            ])
+            .current_dir(&run.checkout)
+            .env("GITHUB_TOKEN", &self.github_token)
+            .env("GH_TOKEN", &self.github_token)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        // Without an API key the CLI uses the host's Claude subscription login
+        if let Some(ref api_key) = self.anthropic_api_key {
+            command.env("ANTHROPIC_API_KEY", api_key);
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| anyhow!("Failed to start {}: {}", self.claude_bin, e))?;
+
+        let mut stdout = BufReader::new(child.stdout.take().ok_or_else(|| anyhow!("No stdout"))?).lines();
+        let mut stderr = BufReader::new(child.stderr.take().ok_or_else(|| anyhow!("No stderr"))?).lines();
+        let (mut stdout_done, mut stderr_done) = (false, false);
+        let mut stopped = run.stopped.clone();
+        let mut flush = tokio::time::interval(LOG_FLUSH_INTERVAL);
+
+        while !(stdout_done && stderr_done) {
+            tokio::select! {
+                line = stdout.next_line(), if !stdout_done => match line? {
+                    Some(line) => run.log(&line).await,
+                    None => stdout_done = true,
+                },
+                line = stderr.next_line(), if !stderr_done => match line? {
+                    Some(line) => run.log(&line).await,
+                    None => stderr_done = true,
+                },
+                Ok(()) = stopped.changed() => {
+                    child.kill().await?;
+                    bail!("Run {} was stopped", run.id);
+                }
+                _ = flush.tick() => run.batcher.flush().await,
+            }
+        }
+
+        let status = child.wait().await?;
+        tracing::info!("Claude Code for {} exited with {}", run.id, status);
+
+        if !status.success() {
+            let log_tail = run.log_tail(50).await;
+            bail!(
+                "Claude Code execution failed with {}.\nLog file: {:?}\n\nLast 50 lines:\n{}",
+                status,
+                run.log_file_path,
+                log_tail
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Run a git command in the checkout; returns whether it succeeded and its stdout
+    async fn git_command(&self, run: &mut Run, args: &[&str]) -> Result<(bool, String)> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&run.checkout)
+            .env("GIT_EDITOR", "true")
+            .env("GIT_AUTHOR_NAME", COMMITTER_NAME)
+            .env("GIT_AUTHOR_EMAIL", COMMITTER_EMAIL)
+            .env("GIT_COMMITTER_NAME", COMMITTER_NAME)
+            .env("GIT_COMMITTER_EMAIL", COMMITTER_EMAIL)
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        run.log(&format!("+ git {}", args.join(" "))).await;
+        for line in stdout.lines().chain(String::from_utf8_lossy(&output.stderr).lines()) {
+            run.log(line).await;
+        }
+
+        Ok((output.status.success(), stdout))
+    }
+
+    /// Commit everything the agent changed and push `branch`
+    ///
+    /// Returns the new commit and the files it touched, or `None` without changes.
+    async fn commit_and_push(&self, run: &mut Run, branch: &str, message: String) -> Result<Option<Pushed>> {
+        run.check_stopped()?;
+
+        let git = self.git.clone();
+        let checkout = run.checkout.clone();
+        let branch_name = branch.to_string();
+
+        let pushed = tokio::task::spawn_blocking(move || -> crate::error::Result<_> {
+            let repo = git2::Repository::open(&checkout)?;
+            if !git.has_changes(&repo)? {
+                return Ok(None);
+            }
+
+            let commit = git.commit_changes(&repo, &message)?;
+            let files = git.files_changed(&repo, commit)?;
+            let (lines_added, lines_removed) = git.line_counts(&repo, commit)?;
+            git.push_branch(&repo, &branch_name)?;
+            Ok(Some(Pushed {
+                sha: commit.to_string(),
+                files,
+                lines_added,
+                lines_removed,
+            }))
+        })
+        .await??;
+
+        match &pushed {
+            Some(pushed) => {
+                run.log(&format!(
+                    "Pushed {} to {} ({} files, +{} -{})",
+                    pushed.sha,
+                    branch,
+                    pushed.files.len(),
+                    pushed.lines_added,
+                    pushed.lines_removed
+                ))
+                .await
+            }
+            None => run.log("No changes to commit").await,
+        }
+
+        Ok(pushed)
+    }
+
+    async fn run_task(
+        &self,
+        run: &mut Run,
+        task: &Task,
+        repository: &Repository,
+        base_branch: &str,
+    ) -> Result<TaskResult> {
+        // 워커와 같은 규칙으로 태스크 브랜치 생성
+        let task_branch = format!("{}_{}", base_branch, task.id);
+        {
+            let git = self.git.clone();
+            let checkout = run.checkout.clone();
+            let task_branch = task_branch.clone();
+            tokio::task::spawn_blocking(move || {
+                let repo = git2::Repository::open(&checkout)?;
+                git.create_branch(&repo, &task_branch)
+            })
+            .await??;
+        }
+
+        self.run_claude(run, &task.prompt).await?;
+
+        let message = format!(
+            "AutoDev: {}\n\nTask ID: {}\n\n{}\n\nGenerated with AutoDev\nCo-Authored-By: Claude <noreply@anthropic.com>",
+            task.title, task.id, task.prompt
+        );
+        let Some(pushed) = self.commit_and_push(run, &task_branch, message).await? else {
+            return Ok(TaskResult {
+                has_changes: false,
+                pr_number: None,
+                pr_url: None,
+                success: true,
+                error: None,
+                commit_sha: None,
+                files_changed: Vec::new(),
+                lines_added: 0,
+                lines_removed: 0,
+            });
+        };
+
+        let body = format!(
+            "Task: {title}\n\n**Task ID:** `{id}`\n**Task Branch:** `{branch}`\n**Base Branch:** `{base}`\n\n\
+             Description:\n{prompt}\n\nChanges:\nThis PR contains the automated changes for this task.\n\n\
+             ---\n🤖 Generated with AutoDev",
+            title = task.title,
+            id = task.id,
+            branch = task_branch,
+            base = base_branch,
+            prompt = task.prompt,
+        );
+        let pr = self
+            .github_client
+            .create_pull_request(
+                repository,
+                format!("AutoDev: {}", task.title),
+                body,
+                task_branch.clone(),
+                base_branch.to_string(),
+                false,
+            )
+            .await?;
+
+        run.log(&format!("PR created: #{} {}", pr.number, pr.url.as_deref().unwrap_or(""))).await;
+
+        Ok(TaskResult {
+            has_changes: true,
+            pr_number: Some(pr.number),
+            pr_url: pr.url,
+            success: true,
+            error: None,
+            commit_sha: None,
+            files_changed: pushed.files,
+            lines_added: pushed.lines_added,
+            lines_removed: pushed.lines_removed,
+        })
+    }
+
+    async fn run_fix(&self, run: &mut Run, branch: &str, title: &str, prompt: &str) -> Result<TaskResult> {
+        self.run_claude(run, prompt).await?;
+
+        let message = format!("AutoDev: {}\n\n{}\n\nGenerated with AutoDev", title, prompt);
+        let pushed = self.commit_and_push(run, branch, message).await?;
+
+        Ok(match pushed {
+            Some(pushed) => TaskResult {
+                has_changes: true,
+                pr_number: None,
+                pr_url: None,
+                success: true,
+                error: None,
+                commit_sha: Some(pushed.sha),
+                files_changed: pushed.files,
+                lines_added: pushed.lines_added,
+                lines_removed: pushed.lines_removed,
+            },
+            None => TaskResult {
+                has_changes: false,
+                pr_number: None,
+                pr_url: None,
+                success: true,
+                error: None,
+                commit_sha: None,
+                files_changed: Vec::new(),
+                lines_added: 0,
+                lines_removed: 0,
+            },
+        })
+    }
+
+    async fn run_resolve(&self, run: &mut Run, branch: &str, onto: &str, prompt: &str) -> Result<TaskResult> {
+        let upstream = format!("origin/{}", onto);
+        let mut resolved = BTreeSet::new();
+
+        let (rebased, _) = self.git_command(run, &["rebase", &upstream]).await?;
+        if !rebased {
+            if let Err(e) = self.continue_rebase(run, prompt, &mut resolved).await {
+                self.git_command(run, &["rebase", "--abort"]).await.ok();
+                return Err(e);
+            }
+        }
+
+        run.check_stopped()?;
+
+        let git = self.git.clone();
+        let checkout = run.checkout.clone();
+        let branch_name = branch.to_string();
+        let commit_sha = tokio::task::spawn_blocking(move || -> crate::error::Result<String> {
+            let repo = git2::Repository::open(&checkout)?;
+            git.force_push_branch(&repo, &branch_name)?;
+            let head = repo.head()?.peel_to_commit()?.id();
+            Ok(head.to_string())
+        })
+        .await??;
+
+        run.log(&format!("Pushed rebased {}: {}", branch, commit_sha)).await;
+
+        Ok(TaskResult {
+            has_changes: true,
+            pr_number: None,
+            pr_url: None,
+            success: true,
+            error: None,
+            commit_sha: Some(commit_sha),
+            files_changed: resolved.into_iter().collect(),
+            lines_added: 0,
+            lines_removed: 0,
+        })
+    }
+
+    /// Resolve the conflicts of a stopped rebase with the agent until it completes
+    async fn continue_rebase(&self, run: &mut Run, prompt: &str, resolved: &mut BTreeSet<String>) -> Result<()> {
+        for _ in 0..MAX_RESOLVE_STEPS {
+            let conflicts = self.conflicting_files(run).await?;
+
+            if !conflicts.is_empty() {
+                run.log(&format!("Resolving conflicts in: {}", conflicts.join(" "))).await;
+
+                let file_list: String = conflicts.iter().map(|file| format!("- {}\n", file)).collect();
+                self.run_claude(run, &format!("{}\n\n## Conflicting files\n{}", prompt, file_list))
+                    .await?;
+
+                // 충돌 마커가 남아 있으면 해결되지 않은 것으로 봄
+                let unresolved: Vec<&String> = conflicts
+                    .iter()
+                    .filter(|file| has_conflict_markers(&run.checkout.join(file)))
+                    .collect();
+                if !unresolved.is_empty() {
+                    bail!("Conflict markers left in {:?}", unresolved);
+                }
+
+                let mut add = vec!["add", "-A", "--"];
+                add.extend(conflicts.iter().map(String::as_str));
+                self.git_command(run, &add).await?;
+                resolved.extend(conflicts);
+            }
+
+            if self.git_command(run, &["rebase", "--continue"]).await?.0 {
+                return Ok(());
+            }
+
+            // 해결 후 비어 버린 커밋은 건너뜀
+            if self.conflicting_files(run).await?.is_empty()
+                && self.git_command(run, &["diff", "--cached", "--quiet"]).await?.0
+                && self.git_command(run, &["rebase", "--skip"]).await?.0
+            {
+                return Ok(());
+            }
+        }
+
+        bail!("Gave up after {} rebase steps", MAX_RESOLVE_STEPS)
+    }
+
+    async fn conflicting_files(&self, run: &mut Run) -> Result<Vec<String>> {
+        let (_, stdout) = self
+            .git_command(run, &["diff", "--name-only", "--diff-filter=U"])
+            .await?;
+        Ok(stdout.lines().filter(|line| !line.is_empty()).map(String::from).collect())
+    }
+
+    /// Report a task's outcome to the AutoDev server like the worker does
+    async fn notify_server(
+        &self,
+        task: &Task,
+        repository: &Repository,
+        composite_task_id: Option<&str>,
+        result: &TaskResult,
+    ) {
+        let Some(ref url) = self.autodev_server_url else {
+            return;
+        };
+
+        let payload = serde_json::json!({
+            "task_id": task.id,
+            "composite_task_id": composite_task_id.unwrap_or("standalone"),
+            "repository_owner": repository.owner,
+            "repository_name": repository.name,
+            "pr_number": result.pr_number,
+            "pr_url": result.pr_url,
+            "success": result.success,
+            "error": result.error,
+            "files_changed": result.files_changed,
+        });
+
+        if let Err(e) = reqwest::Client::new()
+            .post(format!("{}/callbacks/workflow-complete", url))
+            .json(&payload)
+            .send()
+            .await
+        {
+            tracing::warn!("Failed to notify server of task {} (non-fatal): {}", task.id, e);
+        }
+    }
+}
+
+#[async_trait]
+impl LocalExecutor for ProcessExecutor {
+    async fn execute_task(
+        &self,
+        task: &Task,
+        repository: &Repository,
+        base_branch: &str,
+        _target_branch: &str,
+        composite_task_id: Option<&str>,
+    ) -> Result<TaskResult> {
+        tracing::info!(
+            "Executing task {} as a local process for {}/{}",
+            task.id,
+            repository.owner,
+            repository.name
+        );
+
+        let result = async {
+            let mut run = self.start(&task.id, repository, base_branch).await?;
+            let result = self.run_task(&mut run, task, repository, base_branch).await;
+            self.finish(run, &result).await;
+            result
+        }
+        .await;
+
+        let reported = match &result {
+            Ok(result) => result.clone(),
+            Err(e) => TaskResult {
+                has_changes: false,
+                pr_number: None,
+                pr_url: None,
+                success: false,
+                error: Some(e.to_string()),
+                commit_sha: None,
+                files_changed: Vec::new(),
+                lines_added: 0,
+                lines_removed: 0,
+            },
+        };
+        self.notify_server(task, repository, composite_task_id, &reported).await;
+
+        if let Some(ref artifacts) = self.artifacts {
+            let mut files = read_artifacts(&[("logs.txt", &log_file_path(&self.workspace_dir, &task.id))]).await;
+            match serde_json::to_vec_pretty(&reported) {
+                Ok(content) => files.push(("result.json".to_string(), content)),
+                Err(e) => tracing::warn!("Failed to serialize the result of task {}: {}", task.id, e),
+            }
+            artifacts.upload(&task.id, files).await;
+        }
+
+        result
+    }
+
+    async fn fix_branch(
+        &self,
+        id: &str,
+        repository: &Repository,
+        branch: &str,
+        title: &str,
+        prompt: &str,
+    ) -> Result<TaskResult> {
+        tracing::info!(
+            "Fixing branch {} of {}/{} as a local process",
+            branch,
+            repository.owner,
+            repository.name
+        );
+
+        let mut run = self.start(id, repository, branch).await?;
+        let result = self.run_fix(&mut run, branch, title, prompt).await;
+        self.finish(run, &result).await;
+        result
+    }
+
+    async fn resolve_conflicts(
+        &self,
+        id: &str,
+        repository: &Repository,
+        branch: &str,
+        onto: &str,
+        _title: &str,
+        prompt: &str,
+    ) -> Result<TaskResult> {
+        tracing::info!(
+            "Rebasing {} onto {} of {}/{} as a local process",
+            branch,
+            onto,
+            repository.owner,
+            repository.name
+        );
+
+        let mut run = self.start(id, repository, branch).await?;
+        let result = self.run_resolve(&mut run, branch, onto, prompt).await;
+        self.finish(run, &result).await;
+        result
+    }
+
+    async fn merge_branch(
+        &self,
+        id: &str,
+        repository: &Repository,
+        target: &str,
+        branch: &str,
+    ) -> Result<String> {
+        tracing::info!("Merging {} into {} of {}/{}", branch, target, repository.owner, repository.name);
+
+        let checkout = self.workspace_dir.join(format!("merge-{}", id));
+        Ok(git::merge_and_push(self.git.clone(), checkout, repository, target, branch).await?)
+    }
+
+    async fn stop_task(&self, task_id: &str) -> Result<bool> {
+        match self.running.lock().unwrap().get(task_id) {
+            Some(stop) => {
+                tracing::info!("Stopping local process run of task {}", task_id);
+                let _ = stop.send(true);
+                Ok(true)
+            }
+            None => {
+                tracing::debug!("No running process for task {}", task_id);
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// Whether `path` still contains conflict markers
+fn has_conflict_markers(path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .map(|content| {
+            content.lines().any(|line| {
+                ["<<<<<<<", ">>>>>>>"].iter().any(|marker| {
+                    line.strip_prefix(marker)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+                })
+            })
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_conflict_markers() {
+        let dir = tempfile::tempdir().unwrap();
+        let conflicted = dir.path().join("conflicted.rs");
+        std::fs::write(&conflicted, "<<<<<<< HEAD\na\n=======\nb\n>>>>>>> theirs\n").unwrap();
+        let clean = dir.path().join("clean.md");
+        std::fs::write(&clean, "<<<<<<<<< not a marker\n").unwrap();
+
+        assert!(has_conflict_markers(&conflicted));
+        assert!(!has_conflict_markers(&clean));
+        assert!(!has_conflict_markers(&dir.path().join("deleted.rs")));
+    }
+}
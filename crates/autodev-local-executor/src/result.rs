@@ -0,0 +1,207 @@
+//! Versioned contract of the result.json the worker image writes
+//!
+//! The worker declares the schema it writes in `schema_version` and in the
+//! [`RESULT_SCHEMA_LABEL`] label of its image. Results without a version are
+//! version 1, written by images built before the field existed. The current
+//! version is parsed strictly so that fields drifting between the image and
+//! the server fail loudly instead of being dropped.
+
+use serde::Deserialize;
+
+use crate::docker_executor::TaskResult;
+use crate::error::{LocalExecutorError, Result};
+
+/// Schema version of result.json this server writes and expects
+pub const RESULT_SCHEMA_VERSION: u32 = 2;
+
+/// Oldest schema version still accepted
+pub const MIN_RESULT_SCHEMA_VERSION: u32 = 1;
+
+/// Image label declaring the result schema version the worker writes
+pub const RESULT_SCHEMA_LABEL: &str = "io.autodev.result-schema";
+
+/// Hint appended to errors caused by a worker image of another version
+const REBUILD_HINT: &str = "rebuild the worker image with 'autodev build-image'";
+
+/// Version 2: version 1 with `schema_version`, no unknown fields
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ResultV2 {
+    #[allow(dead_code)]
+    schema_version: u32,
+    has_changes: bool,
+    pr_number: Option<u64>,
+    pr_url: Option<String>,
+    success: bool,
+    error: Option<String>,
+    #[serde(default)]
+    commit_sha: Option<String>,
+    #[serde(default)]
+    files_changed: Vec<String>,
+    #[serde(default)]
+    lines_added: u64,
+    #[serde(default)]
+    lines_removed: u64,
+}
+
+/// Version 1: unversioned results of older worker images
+#[derive(Debug, Deserialize)]
+struct ResultV1 {
+    has_changes: bool,
+    pr_number: Option<u64>,
+    pr_url: Option<String>,
+    success: bool,
+    error: Option<String>,
+    #[serde(default)]
+    commit_sha: Option<String>,
+    #[serde(default)]
+    files_changed: Vec<String>,
+    #[serde(default)]
+    lines_added: u64,
+    #[serde(default)]
+    lines_removed: u64,
+}
+
+impl From<ResultV2> for TaskResult {
+    fn from(r: ResultV2) -> Self {
+        Self {
+            has_changes: r.has_changes,
+            pr_number: r.pr_number,
+            pr_url: r.pr_url,
+            success: r.success,
+            error: r.error,
+            commit_sha: r.commit_sha,
+            files_changed: r.files_changed,
+            lines_added: r.lines_added,
+            lines_removed: r.lines_removed,
+        }
+    }
+}
+
+impl From<ResultV1> for TaskResult {
+    fn from(r: ResultV1) -> Self {
+        Self {
+            has_changes: r.has_changes,
+            pr_number: r.pr_number,
+            pr_url: r.pr_url,
+            success: r.success,
+            error: r.error,
+            commit_sha: r.commit_sha,
+            files_changed: r.files_changed,
+            lines_added: r.lines_added,
+            lines_removed: r.lines_removed,
+        }
+    }
+}
+
+fn invalid(message: String) -> LocalExecutorError {
+    LocalExecutorError::InvalidResult(message)
+}
+
+/// Fail unless results of schema `version` can be read
+pub fn check_schema_version(version: u32) -> Result<()> {
+    if version > RESULT_SCHEMA_VERSION {
+        return Err(invalid(format!(
+            "schema version {} is newer than this server supports ({}); upgrade the server or {}",
+            version, RESULT_SCHEMA_VERSION, REBUILD_HINT
+        )));
+    }
+    if version < MIN_RESULT_SCHEMA_VERSION {
+        return Err(invalid(format!(
+            "schema version {} is no longer supported (oldest: {}); {}",
+            version, MIN_RESULT_SCHEMA_VERSION, REBUILD_HINT
+        )));
+    }
+
+    Ok(())
+}
+
+/// Parse and validate the content of a result.json
+pub fn parse_result(content: &str) -> Result<TaskResult> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| invalid(format!("not valid JSON: {}", e)))?;
+
+    let version = match value.get("schema_version") {
+        None => 1,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| invalid(format!("schema_version must be a positive integer, got {}", v)))?,
+    };
+    check_schema_version(version)?;
+
+    let result: TaskResult = match version {
+        1 => serde_json::from_value::<ResultV1>(value).map(Into::into),
+        _ => serde_json::from_value::<ResultV2>(value).map(Into::into),
+    }
+    .map_err(|e| invalid(format!("does not match schema version {}: {}; {}", version, e, REBUILD_HINT)))?;
+
+    validate(&result)?;
+    Ok(result)
+}
+
+/// Check the fields of a result against each other
+fn validate(result: &TaskResult) -> Result<()> {
+    if !result.success && result.error.as_deref().is_none_or(|e| e.trim().is_empty()) {
+        return Err(invalid("failed result has no error message".to_string()));
+    }
+    if result.pr_number.is_some() && result.pr_url.is_none() {
+        return Err(invalid("pr_number is set but pr_url is missing".to_string()));
+    }
+    if result.success && !result.has_changes && (result.pr_number.is_some() || result.commit_sha.is_some()) {
+        return Err(invalid("result without changes has a PR or commit".to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_current_and_legacy_results() {
+        let current = parse_result(
+            r#"{"schema_version": 2, "has_changes": true, "pr_number": 7, "pr_url": "https://x/pull/7",
+                "success": true, "error": null, "files_changed": ["a.rs"], "lines_added": 3, "lines_removed": 1}"#,
+        )
+        .unwrap();
+        assert_eq!(current.pr_number, Some(7));
+        assert_eq!(current.lines_added, 3);
+
+        // Images built before schema_version write version 1, which may carry extra fields
+        let legacy = parse_result(
+            r#"{"has_changes": false, "pr_number": null, "pr_url": null, "success": true, "error": null, "extra": 1}"#,
+        )
+        .unwrap();
+        assert!(!legacy.has_changes);
+    }
+
+    #[test]
+    fn test_reject_invalid_results() {
+        let error = |content: &str| parse_result(content).unwrap_err().to_string();
+
+        assert!(error(r#"{"schema_version": 3}"#).contains("newer than this server supports (2)"));
+        assert!(error(r#"{"schema_version": 0}"#).contains("no longer supported"));
+        assert!(error(r#"{"schema_version": "2"}"#).contains("must be a positive integer"));
+        assert!(error(
+            r#"{"schema_version": 2, "has_changes": false, "pr_number": null, "pr_url": null,
+                "success": true, "error": null, "extra": 1}"#
+        )
+        .contains("unknown field `extra`"));
+        assert!(error(
+            r#"{"schema_version": 2, "pr_number": null, "pr_url": null, "success": true, "error": null}"#
+        )
+        .contains("missing field `has_changes`"));
+        assert!(error(
+            r#"{"schema_version": 2, "has_changes": false, "pr_number": null, "pr_url": null,
+                "success": false, "error": ""}"#
+        )
+        .contains("no error message"));
+        assert!(error(
+            r#"{"schema_version": 2, "has_changes": true, "pr_number": 7, "pr_url": null,
+                "success": true, "error": null}"#
+        )
+        .contains("pr_url is missing"));
+    }
+}
@@ -0,0 +1,50 @@
+//! Repository secrets for worker containers
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use autodev_github::Repository;
+
+/// Looks up the secrets of a repository when its container starts, so values
+/// are never stored with a task
+#[async_trait]
+pub trait SecretSource: Send + Sync {
+    /// Secrets of `repository` by environment variable name
+    async fn repository_secrets(&self, repository: &Repository) -> anyhow::Result<HashMap<String, String>>;
+}
+
+/// `NAME=value` entries for `secrets`, leaving out names already in `env`
+/// (AutoDev's own variables win over repository secrets)
+pub(crate) fn secret_env(env: &[String], secrets: HashMap<String, String>) -> Vec<String> {
+    let taken: Vec<&str> = env.iter().filter_map(|entry| entry.split_once('=').map(|(name, _)| name)).collect();
+
+    let mut entries: Vec<String> = secrets
+        .into_iter()
+        .filter(|(name, _)| {
+            let reserved = taken.contains(&name.as_str());
+            if reserved {
+                tracing::warn!("Ignoring repository secret {}: AutoDev sets this variable itself", name);
+            }
+            !reserved
+        })
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect();
+    entries.sort();
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_env() {
+        let env = vec!["TASK_ID=1".to_string(), "GITHUB_TOKEN=ghp".to_string()];
+        let secrets = HashMap::from([
+            ("NPM_TOKEN".to_string(), "npm=1".to_string()),
+            ("GITHUB_TOKEN".to_string(), "other".to_string()),
+        ]);
+
+        assert_eq!(secret_env(&env, secrets), vec!["NPM_TOKEN=npm=1".to_string()]);
+    }
+}
@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::{Error, NotificationEvent, Result};
+
+/// Destination notifications are delivered to
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    /// Channel name for logs
+    fn name(&self) -> &str;
+
+    async fn send(&self, event: &NotificationEvent) -> Result<()>;
+}
+
+/// Upper bound for one webhook request
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn webhook_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// Slack incoming webhook
+pub struct SlackChannel {
+    webhook_url: String,
+    client: reqwest::Client,
+}
+
+impl SlackChannel {
+    pub fn new(webhook_url: String) -> Self {
+        Self {
+            webhook_url,
+            client: webhook_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for SlackChannel {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn send(&self, event: &NotificationEvent) -> Result<()> {
+        let text = format!("*{}*\n{}", event.subject(), event.body());
+        post_webhook(&self.client, &self.webhook_url, &json!({ "text": text })).await
+    }
+}
+
+/// Discord webhook
+pub struct DiscordChannel {
+    webhook_url: String,
+    client: reqwest::Client,
+}
+
+impl DiscordChannel {
+    pub fn new(webhook_url: String) -> Self {
+        Self {
+            webhook_url,
+            client: webhook_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for DiscordChannel {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    async fn send(&self, event: &NotificationEvent) -> Result<()> {
+        let content = format!("**{}**\n{}", event.subject(), event.body());
+        post_webhook(&self.client, &self.webhook_url, &json!({ "content": content })).await
+    }
+}
+
+async fn post_webhook(
+    client: &reqwest::Client,
+    url: &str,
+    payload: &serde_json::Value,
+) -> Result<()> {
+    let response = client.post(url).json(payload).send().await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(Error::Webhook(format!("{}: {}", status, body)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_slack_webhook_payload() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "text": "*❌ Task failed: Add login*\nTask t1 failed: boom"
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let channel = SlackChannel::new(format!("{}/hook", server.url()));
+        channel
+            .send(&NotificationEvent::TaskFailed {
+                task_id: "t1".to_string(),
+                title: "Add login".to_string(),
+                error: Some("boom".to_string()),
+            })
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_webhook_error_status() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/hook")
+            .with_status(404)
+            .with_body("no_team")
+            .create_async()
+            .await;
+
+        let channel = DiscordChannel::new(format!("{}/hook", server.url()));
+        let result = channel
+            .send(&NotificationEvent::PrAwaitingReview {
+                task_id: "t1".to_string(),
+                title: "Add login".to_string(),
+                pr_url: "https://github.com/o/r/pull/1".to_string(),
+            })
+            .await;
+
+        assert!(matches!(result, Err(Error::Webhook(_))));
+    }
+}
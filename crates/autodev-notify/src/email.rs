@@ -0,0 +1,230 @@
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use crate::{Error, NotificationChannel, NotificationEvent, Result};
+
+/// Upper bound for one SMTP conversation
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Email through an SMTP relay
+///
+/// Speaks plain SMTP without TLS or authentication, so point it at a relay
+/// on a trusted network (a local postfix, a cluster mail relay, ...).
+pub struct EmailChannel {
+    host: String,
+    port: u16,
+    from: String,
+    to: Vec<String>,
+}
+
+impl EmailChannel {
+    pub fn new(host: String, port: u16, from: String, to: Vec<String>) -> Self {
+        Self { host, port, from, to }
+    }
+
+    async fn deliver(&self, subject: &str, body: &str) -> Result<()> {
+        let stream = TcpStream::connect((self.host.as_str(), self.port)).await?;
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+
+        expect_reply(&mut reader, 220).await?;
+        command(&mut writer, &mut reader, "EHLO autodev", 250).await?;
+        command(&mut writer, &mut reader, &format!("MAIL FROM:<{}>", self.from), 250).await?;
+        for to in &self.to {
+            command(&mut writer, &mut reader, &format!("RCPT TO:<{}>", to), 250).await?;
+        }
+        command(&mut writer, &mut reader, "DATA", 354).await?;
+
+        let message = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\n\
+             Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n{}\r\n.",
+            self.from,
+            self.to.join(", "),
+            encode_header(subject),
+            chrono::Utc::now().to_rfc2822(),
+            dot_stuff(body)
+        );
+        command(&mut writer, &mut reader, &message, 250).await?;
+
+        // The message is accepted; a failed QUIT does not matter
+        let _ = command(&mut writer, &mut reader, "QUIT", 221).await;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    async fn send(&self, event: &NotificationEvent) -> Result<()> {
+        tokio::time::timeout(SMTP_TIMEOUT, self.deliver(&event.subject(), &event.body()))
+            .await
+            .map_err(|_| Error::Smtp(format!("{}:{} timed out", self.host, self.port)))?
+    }
+}
+
+async fn command<W, R>(writer: &mut W, reader: &mut R, line: &str, expected: u16) -> Result<()>
+where
+    W: AsyncWriteExt + Unpin,
+    R: AsyncBufReadExt + Unpin,
+{
+    writer.write_all(line.as_bytes()).await?;
+    writer.write_all(b"\r\n").await?;
+    writer.flush().await?;
+
+    expect_reply(reader, expected).await
+}
+
+/// Read a (possibly multi-line) reply and check its code
+async fn expect_reply<R: AsyncBufReadExt + Unpin>(reader: &mut R, expected: u16) -> Result<()> {
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(Error::Smtp("Connection closed by server".to_string()));
+        }
+
+        let code: u16 = line
+            .get(..3)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| Error::Smtp(format!("Malformed reply: {}", line.trim_end())))?;
+
+        if code != expected {
+            return Err(Error::Smtp(format!(
+                "Expected {}, got: {}",
+                expected,
+                line.trim_end()
+            )));
+        }
+
+        // "250-..." continues, "250 ..." ends the reply
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(());
+        }
+    }
+}
+
+/// RFC 2047 encoded-word for non-ASCII header values
+///
+/// Line breaks and other control characters become spaces, so a value such
+/// as a task title cannot end the header or add headers of its own.
+fn encode_header(value: &str) -> String {
+    let value: String = value.chars().map(|c| if c.is_control() { ' ' } else { c }).collect();
+    if value.is_ascii() {
+        return value;
+    }
+
+    let encoded: String = value
+        .bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => (b as char).to_string(),
+            b' ' => "_".to_string(),
+            _ => format!("={:02X}", b),
+        })
+        .collect();
+
+    format!("=?UTF-8?Q?{}?=", encoded)
+}
+
+/// Normalize line endings and escape lines starting with '.'
+fn dot_stuff(body: &str) -> String {
+    body.lines()
+        .map(|line| match line.strip_prefix('.') {
+            Some(_) => format!(".{}", line),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_send_through_relay() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // Minimal relay recording the conversation
+        let relay = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut reader = BufReader::new(reader);
+            let mut transcript = String::new();
+
+            writer.write_all(b"220 relay ready\r\n").await.unwrap();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                transcript.push_str(&line);
+
+                let reply: &[u8] = if in_data {
+                    if line != ".\r\n" {
+                        continue;
+                    }
+                    in_data = false;
+                    b"250 queued\r\n"
+                } else if line.starts_with("EHLO") {
+                    b"250-relay\r\n250 8BITMIME\r\n"
+                } else if line.starts_with("DATA") {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line.starts_with("QUIT") {
+                    writer.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                writer.write_all(reply).await.unwrap();
+            }
+
+            transcript
+        });
+
+        let channel = EmailChannel::new(
+            "127.0.0.1".to_string(),
+            port,
+            "autodev@example.com".to_string(),
+            vec!["dev@example.com".to_string()],
+        );
+        channel
+            .send(&NotificationEvent::CompositeCompleted {
+                composite_task_id: "c1".to_string(),
+                title: "보안 점검".to_string(),
+                subtasks: 3,
+                failed: 0,
+                skipped: 0,
+            })
+            .await
+            .unwrap();
+
+        let transcript = relay.await.unwrap();
+        assert!(transcript.contains("RCPT TO:<dev@example.com>\r\n"));
+        assert!(transcript.contains("Subject: =?UTF-8?Q?"));
+        assert!(transcript.contains("All 3 subtasks of composite task c1"));
+    }
+
+    #[test]
+    fn test_dot_stuffing() {
+        assert_eq!(dot_stuff("a\n.b\nc"), "a\r\n..b\r\nc");
+    }
+
+    #[test]
+    fn test_header_control_characters() {
+        assert_eq!(
+            encode_header("Task failed\r\nBcc: victim@example.com"),
+            "Task failed  Bcc: victim@example.com"
+        );
+        assert!(!encode_header("실패\r\nBcc: x").contains(['\r', '\n']));
+        assert_eq!(encode_header("a\tb\u{7f}"), "a b ");
+    }
+}
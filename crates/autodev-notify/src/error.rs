@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Webhook rejected the notification: {0}")]
+    Webhook(String),
+
+    #[error("SMTP error: {0}")]
+    Smtp(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid notification config: {0}")]
+    Config(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// Something a user may want to hear about without watching logs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// A task failed (after its last attempt, where the caller knows)
+    TaskFailed {
+        task_id: String,
+        title: String,
+        error: Option<String>,
+    },
+    /// Every subtask of a composite task finished and was merged, or the
+    /// rest was skipped after a failure (partial completion)
+    CompositeCompleted {
+        composite_task_id: String,
+        title: String,
+        subtasks: usize,
+        #[serde(default)]
+        failed: usize,
+        #[serde(default)]
+        skipped: usize,
+    },
+    /// A pull request waits for a human review or merge
+    PrAwaitingReview {
+        task_id: String,
+        title: String,
+        pr_url: String,
+    },
+    /// A subtask PR conflicts with its parent branch and its batch is paused
+    /// until someone resolves the conflicts
+    MergeConflict {
+        task_id: String,
+        title: String,
+        pr_url: String,
+        /// Why automatic resolution didn't work
+        reason: String,
+    },
+    /// An AI budget ran out and the task is paused until it is retried
+    BudgetExceeded {
+        task_id: String,
+        title: String,
+        /// Which budget ran out and by how much
+        reason: String,
+    },
+    /// The tests failed on a composite task's parent branch after a batch was
+    /// merged; the composite task waits for a fix
+    TestsFailed {
+        composite_task_id: String,
+        title: String,
+        batch: usize,
+        /// Conclusion of the test workflow run
+        reason: String,
+    },
+}
+
+/// Event kinds notifications can be enabled for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotifyTrigger {
+    TaskFailed,
+    CompositeCompleted,
+    PrAwaitingReview,
+    MergeConflict,
+    BudgetExceeded,
+    TestsFailed,
+}
+
+impl NotifyTrigger {
+    pub const ALL: [NotifyTrigger; 6] = [
+        NotifyTrigger::TaskFailed,
+        NotifyTrigger::CompositeCompleted,
+        NotifyTrigger::PrAwaitingReview,
+        NotifyTrigger::MergeConflict,
+        NotifyTrigger::BudgetExceeded,
+        NotifyTrigger::TestsFailed,
+    ];
+}
+
+impl std::str::FromStr for NotifyTrigger {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "task_failed" => Ok(NotifyTrigger::TaskFailed),
+            "composite_completed" => Ok(NotifyTrigger::CompositeCompleted),
+            "pr_awaiting_review" => Ok(NotifyTrigger::PrAwaitingReview),
+            "merge_conflict" => Ok(NotifyTrigger::MergeConflict),
+            "budget_exceeded" => Ok(NotifyTrigger::BudgetExceeded),
+            "tests_failed" => Ok(NotifyTrigger::TestsFailed),
+            _ => Err(Error::Config(format!("Unknown notification event: {}", s))),
+        }
+    }
+}
+
+impl NotificationEvent {
+    pub fn trigger(&self) -> NotifyTrigger {
+        match self {
+            NotificationEvent::TaskFailed { .. } => NotifyTrigger::TaskFailed,
+            NotificationEvent::CompositeCompleted { .. } => NotifyTrigger::CompositeCompleted,
+            NotificationEvent::PrAwaitingReview { .. } => NotifyTrigger::PrAwaitingReview,
+            NotificationEvent::MergeConflict { .. } => NotifyTrigger::MergeConflict,
+            NotificationEvent::BudgetExceeded { .. } => NotifyTrigger::BudgetExceeded,
+            NotificationEvent::TestsFailed { .. } => NotifyTrigger::TestsFailed,
+        }
+    }
+
+    /// Identifies repeats of the same event reported from several places
+    pub fn key(&self) -> String {
+        match self {
+            NotificationEvent::TaskFailed { task_id, .. } => format!("task_failed:{}", task_id),
+            NotificationEvent::CompositeCompleted { composite_task_id, .. } => {
+                format!("composite_completed:{}", composite_task_id)
+            }
+            NotificationEvent::PrAwaitingReview { pr_url, .. } => {
+                format!("pr_awaiting_review:{}", pr_url)
+            }
+            NotificationEvent::MergeConflict { pr_url, .. } => format!("merge_conflict:{}", pr_url),
+            NotificationEvent::BudgetExceeded { task_id, .. } => format!("budget_exceeded:{}", task_id),
+            NotificationEvent::TestsFailed { composite_task_id, batch, .. } => {
+                format!("tests_failed:{}:{}", composite_task_id, batch)
+            }
+        }
+    }
+
+    /// One-line summary (chat message, email subject)
+    pub fn subject(&self) -> String {
+        match self {
+            NotificationEvent::TaskFailed { title, .. } => format!("❌ Task failed: {}", title),
+            NotificationEvent::CompositeCompleted { title, failed: 0, skipped: 0, .. } => {
+                format!("✅ Composite task completed: {}", title)
+            }
+            NotificationEvent::CompositeCompleted { title, .. } => {
+                format!("⚠️ Composite task partially completed: {}", title)
+            }
+            NotificationEvent::PrAwaitingReview { title, .. } => {
+                format!("👀 PR awaiting review: {}", title)
+            }
+            NotificationEvent::MergeConflict { title, .. } => {
+                format!("🔀 Merge conflict needs a manual fix: {}", title)
+            }
+            NotificationEvent::BudgetExceeded { title, .. } => {
+                format!("💸 AI budget exceeded: {}", title)
+            }
+            NotificationEvent::TestsFailed { title, .. } => {
+                format!("🧪 Tests failed on the parent branch: {}", title)
+            }
+        }
+    }
+
+    /// Details below the subject
+    pub fn body(&self) -> String {
+        match self {
+            NotificationEvent::TaskFailed { task_id, error, .. } => format!(
+                "Task {} failed: {}",
+                task_id,
+                error.as_deref().unwrap_or("unknown error")
+            ),
+            NotificationEvent::CompositeCompleted {
+                composite_task_id,
+                subtasks,
+                failed: 0,
+                skipped: 0,
+                ..
+            } => format!(
+                "All {} subtasks of composite task {} are merged into autodev/{}",
+                subtasks, composite_task_id, composite_task_id
+            ),
+            NotificationEvent::CompositeCompleted {
+                composite_task_id,
+                subtasks,
+                failed,
+                skipped,
+                ..
+            } => format!(
+                "{} of {} subtasks of composite task {} are merged into autodev/{}; {} failed, {} skipped",
+                subtasks.saturating_sub(failed + skipped),
+                subtasks,
+                composite_task_id,
+                composite_task_id,
+                failed,
+                skipped
+            ),
+            NotificationEvent::PrAwaitingReview { task_id, pr_url, .. } => {
+                format!("Task {} opened {} and waits for a merge", task_id, pr_url)
+            }
+            NotificationEvent::MergeConflict { task_id, pr_url, reason, .. } => format!(
+                "{} of task {} conflicts with its parent branch ({}). Its batch resumes once the PR is merged or the conflicts are resolved.",
+                pr_url, task_id, reason
+            ),
+            NotificationEvent::BudgetExceeded { task_id, reason, .. } => format!(
+                "Task {} is paused: {}. Raise the budget and retry the task to continue.",
+                task_id, reason
+            ),
+            NotificationEvent::TestsFailed { composite_task_id, batch, reason, .. } => format!(
+                "Tests on autodev/{} failed after batch {} was merged ({}). Push a fix to the branch and approve with POST /composite-tasks/{}/approve to continue.",
+                composite_task_id, batch, reason, composite_task_id
+            ),
+        }
+    }
+}
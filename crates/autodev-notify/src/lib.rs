@@ -0,0 +1,35 @@
+pub mod channel;
+pub mod email;
+pub mod error;
+pub mod event;
+pub mod notifier;
+
+use std::sync::OnceLock;
+
+// Re-exports
+pub use channel::{DiscordChannel, NotificationChannel, SlackChannel};
+pub use email::EmailChannel;
+pub use error::{Error, Result};
+pub use event::{NotificationEvent, NotifyTrigger};
+pub use notifier::Notifier;
+
+static GLOBAL: OnceLock<Notifier> = OnceLock::new();
+
+/// Process-wide notifier configured from AUTODEV_NOTIFY_* variables
+pub fn global() -> &'static Notifier {
+    GLOBAL.get_or_init(|| match Notifier::from_env() {
+        Ok(notifier) => notifier,
+        Err(e) => {
+            tracing::error!("Notifications disabled: {}", e);
+            Notifier::new([])
+        }
+    })
+}
+
+/// Send an event through the process-wide notifier
+///
+/// Delivery runs in the background so a slow webhook or SMTP server never
+/// holds up the caller.
+pub fn notify(event: NotificationEvent) {
+    tokio::spawn(async move { global().notify(&event).await });
+}
@@ -0,0 +1,176 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{
+    DiscordChannel, EmailChannel, Error, NotificationChannel, NotificationEvent, NotifyTrigger,
+    Result, SlackChannel,
+};
+
+/// Repeats of an event within this window (e.g. a failure seen by both the
+/// callback handler and the executor) are sent once
+pub const DEDUP_WINDOW: Duration = Duration::from_secs(300);
+
+/// Delivers events to every configured channel
+pub struct Notifier {
+    channels: Vec<Box<dyn NotificationChannel>>,
+    triggers: HashSet<NotifyTrigger>,
+    recent: Mutex<HashMap<String, Instant>>,
+}
+
+impl Notifier {
+    /// Notifier without channels (notifications are dropped)
+    pub fn new(triggers: impl IntoIterator<Item = NotifyTrigger>) -> Self {
+        Self {
+            channels: Vec::new(),
+            triggers: triggers.into_iter().collect(),
+            recent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_channel(mut self, channel: impl NotificationChannel + 'static) -> Self {
+        self.channels.push(Box::new(channel));
+        self
+    }
+
+    /// Configure channels and triggers from AUTODEV_NOTIFY_* variables
+    ///
+    /// - AUTODEV_NOTIFY_SLACK_WEBHOOK_URL / AUTODEV_NOTIFY_DISCORD_WEBHOOK_URL
+    /// - AUTODEV_NOTIFY_SMTP_HOST, AUTODEV_NOTIFY_SMTP_PORT (25),
+    ///   AUTODEV_NOTIFY_EMAIL_FROM, AUTODEV_NOTIFY_EMAIL_TO (comma-separated)
+    /// - AUTODEV_NOTIFY_EVENTS: task_failed, composite_completed,
+    ///   pr_awaiting_review, merge_conflict, budget_exceeded, tests_failed (comma-separated, default: all)
+    pub fn from_env() -> Result<Self> {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+
+        let triggers = match env("AUTODEV_NOTIFY_EVENTS") {
+            Some(events) => events
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<NotifyTrigger>>>()?,
+            None => NotifyTrigger::ALL.to_vec(),
+        };
+
+        let mut notifier = Self::new(triggers);
+
+        if let Some(url) = env("AUTODEV_NOTIFY_SLACK_WEBHOOK_URL") {
+            notifier = notifier.with_channel(SlackChannel::new(url));
+        }
+
+        if let Some(url) = env("AUTODEV_NOTIFY_DISCORD_WEBHOOK_URL") {
+            notifier = notifier.with_channel(DiscordChannel::new(url));
+        }
+
+        if let Some(host) = env("AUTODEV_NOTIFY_SMTP_HOST") {
+            let port = match env("AUTODEV_NOTIFY_SMTP_PORT") {
+                Some(port) => port
+                    .parse()
+                    .map_err(|_| Error::Config(format!("Invalid SMTP port: {}", port)))?,
+                None => 25,
+            };
+            let to: Vec<String> = env("AUTODEV_NOTIFY_EMAIL_TO")
+                .ok_or_else(|| Error::Config("AUTODEV_NOTIFY_EMAIL_TO must be set".to_string()))?
+                .split(',')
+                .map(|to| to.trim().to_string())
+                .filter(|to| !to.is_empty())
+                .collect();
+            let from = env("AUTODEV_NOTIFY_EMAIL_FROM").unwrap_or_else(|| "autodev@localhost".to_string());
+
+            notifier = notifier.with_channel(EmailChannel::new(host, port, from, to));
+        }
+
+        Ok(notifier)
+    }
+
+    /// Whether any channel is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.channels.is_empty()
+    }
+
+    /// Send an event to every channel
+    ///
+    /// Delivery failures are logged, never returned: a broken webhook must
+    /// not fail the task that triggered the notification.
+    pub async fn notify(&self, event: &NotificationEvent) {
+        if !self.is_enabled() || !self.triggers.contains(&event.trigger()) || self.is_repeat(event) {
+            return;
+        }
+
+        for channel in &self.channels {
+            match channel.send(event).await {
+                Ok(()) => tracing::debug!("Sent {} notification via {}", event.key(), channel.name()),
+                Err(e) => tracing::warn!(
+                    "Failed to send {} notification via {}: {}",
+                    event.key(),
+                    channel.name(),
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Record the event, returning true if it was already sent recently
+    fn is_repeat(&self, event: &NotificationEvent) -> bool {
+        let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        recent.retain(|_, sent| now.duration_since(*sent) < DEDUP_WINDOW);
+
+        recent.insert(event.key(), now).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Arc;
+
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl NotificationChannel for Recorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        async fn send(&self, event: &NotificationEvent) -> Result<()> {
+            self.0.lock().unwrap().push(event.key());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_triggers_and_dedup() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let notifier = Notifier::new([NotifyTrigger::TaskFailed]).with_channel(Recorder(sent.clone()));
+
+        let failed = NotificationEvent::TaskFailed {
+            task_id: "t1".to_string(),
+            title: "Add login".to_string(),
+            error: None,
+        };
+        notifier.notify(&failed).await;
+        // Reported again by another component
+        notifier.notify(&failed).await;
+        // Not enabled
+        notifier
+            .notify(&NotificationEvent::CompositeCompleted {
+                composite_task_id: "c1".to_string(),
+                title: "Audit".to_string(),
+                subtasks: 2,
+                failed: 0,
+                skipped: 0,
+            })
+            .await;
+
+        assert_eq!(*sent.lock().unwrap(), vec!["task_failed:t1".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_triggers() {
+        assert_eq!("PR-awaiting-review".parse::<NotifyTrigger>().unwrap(), NotifyTrigger::PrAwaitingReview);
+        assert_eq!("merge_conflict".parse::<NotifyTrigger>().unwrap(), NotifyTrigger::MergeConflict);
+        assert_eq!("budget-exceeded".parse::<NotifyTrigger>().unwrap(), NotifyTrigger::BudgetExceeded);
+        assert!("task_done".parse::<NotifyTrigger>().is_err());
+    }
+}
@@ -0,0 +1,51 @@
+
+/// Name of the check run composite task progress is reported under
+pub const CHECK_RUN_NAME: &str = "AutoDev";
+
+/// State shown for a check run in the PR checks list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckRunState {
+    InProgress,
+    Success,
+    Failure,
+    /// Completed without a verdict, e.g. waiting on a human
+    Neutral,
+    Cancelled,
+}
+
+impl CheckRunState {
+    pub fn status(self) -> &'static str {
+        match self {
+            Self::InProgress => "in_progress",
+            _ => "completed",
+        }
+    }
+
+    pub fn conclusion(self) -> Option<&'static str> {
+        match self {
+            Self::InProgress => None,
+            Self::Success => Some("success"),
+            Self::Failure => Some("failure"),
+            Self::Neutral => Some("neutral"),
+            Self::Cancelled => Some("cancelled"),
+        }
+    }
+}
+
+/// What a check run shows: its state plus the output title and Markdown summary
+#[derive(Debug, Clone)]
+pub struct CheckRunReport {
+    pub state: CheckRunState,
+    pub title: String,
+    pub summary: String,
+}
+
+impl CheckRunReport {
+    pub fn new(state: CheckRunState, title: impl Into<String>, summary: impl Into<String>) -> Self {
+        Self {
+            state,
+            title: title.into(),
+            summary: summary.into(),
+        }
+    }
+}
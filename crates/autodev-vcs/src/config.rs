@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use autodev_core::{ExecutionBackend, MergeMethod, ReviewSeverity};
+
+use crate::{Error, Result};
+
+/// Per-repository settings file read from the target repository's default branch
+pub const CONFIG_FILE: &str = ".autodev.yml";
+
+/// Workflow dispatched for standalone tasks and composite subtasks
+pub const WORKFLOW_FILE: &str = "autodev.yml";
+
+/// Workflow dispatched for dependent subtasks once their dependencies finish
+pub const SUBTASK_WORKFLOW_FILE: &str = "autodev-subtask.yml";
+
+const DEFAULT_MERGE_TIMEOUT_MINUTES: u64 = 30;
+
+/// Settings from `.autodev.yml`; every field is optional
+///
+/// ```yaml
+/// default_branch: develop
+/// workflow_file: autodev.yml
+/// test_workflow: ci.yml
+/// max_parallel_tasks: 2
+/// auto_approve: false
+/// merge_method: squash
+/// merge_timeout_minutes: 60
+/// reviewers: [alice, bob]
+/// review_gate: high
+/// security_scan: true
+/// execution_backend: docker
+/// protected_paths:
+///   - migrations/
+///   - "*.lock"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RepoConfig {
+    /// Branch tasks start from and open PRs against (main when unset)
+    pub default_branch: Option<String>,
+
+    /// Workflow dispatched for tasks (autodev.yml when unset)
+    pub workflow_file: Option<String>,
+
+    /// Workflow run on a composite task's parent branch after each batch is
+    /// merged; a failure holds the composite task until fixed (off when unset)
+    pub test_workflow: Option<String>,
+
+    /// Default cap on subtasks of a composite task running at once
+    pub max_parallel_tasks: Option<usize>,
+
+    /// Default for composite tasks' auto-approve
+    pub auto_approve: Option<bool>,
+
+    /// How subtask PRs are merged (merge when unset)
+    pub merge_method: Option<MergeMethod>,
+
+    /// How long an auto-merge waits for required checks and reviews (30 when unset)
+    pub merge_timeout_minutes: Option<u64>,
+
+    /// Requested as reviewers when branch protection blocks an auto-merge
+    pub reviewers: Vec<String>,
+
+    /// AI-review subtask PRs before auto-merging them; findings of this
+    /// severity or worse block the merge until approved (off when unset)
+    pub review_gate: Option<ReviewSeverity>,
+
+    /// Security-scan the draft PR of a completed composite task; Critical
+    /// issues fail its AutoDev check (off when unset)
+    pub security_scan: bool,
+
+    /// Backend tasks run on unless the task names one (the server's default when unset)
+    pub execution_backend: Option<ExecutionBackend>,
+
+    /// Paths AutoDev must not modify: directories end with `/`, `*` matches any characters
+    pub protected_paths: Vec<String>,
+}
+
+impl RepoConfig {
+    pub fn parse(yaml: &str) -> Result<Self> {
+        // 빈 파일은 기본값으로 취급
+        if yaml.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        serde_yaml::from_str(yaml).map_err(|e| Error::InvalidConfig(format!("{}: {}", CONFIG_FILE, e)))
+    }
+
+    pub fn default_branch(&self) -> &str {
+        self.default_branch.as_deref().unwrap_or("main")
+    }
+
+    pub fn workflow_file(&self) -> &str {
+        self.workflow_file.as_deref().unwrap_or(WORKFLOW_FILE)
+    }
+
+    pub fn merge_timeout(&self) -> Duration {
+        Duration::from_secs(self.merge_timeout_minutes.unwrap_or(DEFAULT_MERGE_TIMEOUT_MINUTES) * 60)
+    }
+
+    pub fn is_protected(&self, path: &str) -> bool {
+        self.protected_paths.iter().any(|pattern| path_matches(pattern, path))
+    }
+
+    /// Protected paths among `paths`
+    pub fn protected_changes<'a>(&self, paths: &'a [String]) -> Vec<&'a str> {
+        paths
+            .iter()
+            .filter(|p| self.is_protected(p))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// `prompt` with the protected-path rule appended for the AI agent
+    pub fn apply_to_prompt(&self, prompt: &str) -> String {
+        if self.protected_paths.is_empty() {
+            return prompt.to_string();
+        }
+
+        format!(
+            "{}\n\nDo not create, modify or delete files matching these paths: {}",
+            prompt,
+            self.protected_paths.join(", ")
+        )
+    }
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./");
+    let path = path.trim_start_matches("./");
+
+    if let Some(dir) = pattern.strip_suffix('/') {
+        return path == dir || path.starts_with(pattern);
+    }
+
+    if !pattern.contains('*') {
+        return path == pattern;
+    }
+
+    // 확장자 패턴("*.lock")은 하위 디렉터리 파일에도 적용
+    let target = if pattern.contains('/') {
+        path
+    } else {
+        path.rsplit('/').next().unwrap_or(path)
+    };
+    wildcard_match(pattern.as_bytes(), target.as_bytes())
+}
+
+/// `*` matches any run of bytes; linear in practice, never exponential
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it is matched up to
+    let mut star = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // 마지막 '*'가 한 글자 더 삼키도록 되돌아감
+            star = Some((star_p, star_t + 1));
+            p = star_p + 1;
+            t = star_t + 1;
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repo_config() {
+        let config = RepoConfig::parse(
+            "default_branch: develop\nmax_parallel_tasks: 2\nprotected_paths:\n  - migrations/\n  - \"*.lock\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.default_branch(), "develop");
+        assert_eq!(config.workflow_file(), WORKFLOW_FILE);
+        assert_eq!(config.max_parallel_tasks, Some(2));
+        assert_eq!(config.auto_approve, None);
+        assert_eq!(config.merge_timeout(), Duration::from_secs(30 * 60));
+        assert_eq!(RepoConfig::parse("merge_method: squash").unwrap().merge_method, Some(MergeMethod::Squash));
+        assert_eq!(
+            RepoConfig::parse("execution_backend: k8s").unwrap().execution_backend,
+            Some(ExecutionBackend::K8s)
+        );
+        assert_eq!(RepoConfig::parse("review_gate: high").unwrap().review_gate, Some(ReviewSeverity::High));
+        assert!(RepoConfig::parse("security_scan: true").unwrap().security_scan);
+        assert_eq!(RepoConfig::parse("test_workflow: ci.yml").unwrap().test_workflow.as_deref(), Some("ci.yml"));
+
+        assert_eq!(RepoConfig::parse("").unwrap(), RepoConfig::default());
+        assert!(RepoConfig::parse("max_parallel: 2").is_err());
+    }
+
+    #[test]
+    fn test_protected_paths() {
+        let config = RepoConfig {
+            protected_paths: vec!["migrations/".into(), "*.lock".into(), ".github/workflows/*.yml".into()],
+            ..Default::default()
+        };
+
+        assert!(config.is_protected("migrations/001_init.sql"));
+        assert!(config.is_protected("Cargo.lock"));
+        assert!(config.is_protected("web/yarn.lock"));
+        assert!(config.is_protected(".github/workflows/autodev.yml"));
+        assert!(!config.is_protected("migrations_old.sql"));
+        assert!(!config.is_protected("src/lock.rs"));
+
+        assert_eq!(
+            config.protected_changes(&["src/main.rs".into(), "Cargo.lock".into()]),
+            vec!["Cargo.lock"]
+        );
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match(b"a*b*c", b"axxbyyc"));
+        assert!(wildcard_match(b"*", b""));
+        assert!(wildcard_match(b"**.lock", b"Cargo.lock"));
+        assert!(!wildcard_match(b"a*b", b"acb_"));
+        assert!(!wildcard_match(b"*.lock", b"lock"));
+
+        // Many stars against a long near-miss finish right away
+        let pattern = "*a".repeat(30);
+        let text = "a".repeat(5000) + "b";
+        assert!(!wildcard_match(pattern.as_bytes(), text.as_bytes()));
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+
+/// Largest diff chunk sent to the AI in one review request (characters)
+pub const DEFAULT_CHUNK_CHARS: usize = 60_000;
+
+/// Split a unified diff into chunks of at most `max_chars`
+///
+/// Chunks break at file boundaries; a file larger than one chunk is split at
+/// hunk (and, if needed, line) boundaries with its `diff --git` line repeated.
+/// A single line longer than `max_chars` is kept whole.
+pub fn chunk_diff(diff: &str, max_chars: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+
+    for file in split_before(diff, "diff --git ") {
+        if file.len() <= max_chars {
+            pieces.push(file.to_string());
+        } else {
+            pieces.extend(split_file(file, max_chars));
+        }
+    }
+
+    pack(pieces.iter().map(String::as_str), max_chars)
+}
+
+/// Split a unified diff into chunks of at most `max_chars` grouped by language
+///
+/// Each file goes to the language of its path ([`diff_language`]), so one
+/// chunk only holds code of one language; languages are sorted by name.
+pub fn chunk_diff_by_language(diff: &str, max_chars: usize) -> Vec<(&'static str, String)> {
+    let mut files: BTreeMap<&'static str, String> = BTreeMap::new();
+
+    for file in split_before(diff, "diff --git ") {
+        let header = file.lines().next().unwrap_or_default();
+        let path = header.rsplit_once(" b/").map(|(_, path)| path).unwrap_or(header);
+        files.entry(diff_language(path)).or_default().push_str(file);
+    }
+
+    files
+        .into_iter()
+        .flat_map(|(language, diff)| {
+            chunk_diff(&diff, max_chars).into_iter().map(move |chunk| (language, chunk))
+        })
+        .collect()
+}
+
+/// Language of a file by its name, "text" when unknown
+pub fn diff_language(path: &str) -> &'static str {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    if name == "Dockerfile" || name.starts_with("Dockerfile.") {
+        return "dockerfile";
+    }
+
+    match name.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).as_deref() {
+        Some("rs") => "rust",
+        Some("py") => "python",
+        Some("js" | "jsx" | "mjs" | "cjs") => "javascript",
+        Some("ts" | "tsx") => "typescript",
+        Some("go") => "go",
+        Some("java") => "java",
+        Some("kt" | "kts") => "kotlin",
+        Some("rb") => "ruby",
+        Some("php") => "php",
+        Some("c" | "h") => "c",
+        Some("cc" | "cpp" | "cxx" | "hpp") => "cpp",
+        Some("cs") => "csharp",
+        Some("swift") => "swift",
+        Some("sh" | "bash") => "shell",
+        Some("sql") => "sql",
+        Some("yml" | "yaml") => "yaml",
+        Some("toml") => "toml",
+        Some("json") => "json",
+        Some("html" | "htm") => "html",
+        _ => "text",
+    }
+}
+
+fn split_file(file: &str, max_chars: usize) -> Vec<String> {
+    let header = file.lines().next().unwrap_or_default();
+    let budget = max_chars.saturating_sub(header.len() + 1).max(1);
+
+    let segments = split_before(file, "@@").into_iter().flat_map(|hunk| {
+        if hunk.len() <= budget {
+            vec![hunk]
+        } else {
+            hunk.split_inclusive('\n').collect()
+        }
+    });
+
+    pack(segments, budget)
+        .into_iter()
+        .enumerate()
+        .map(|(i, piece)| if i == 0 { piece } else { format!("{}\n{}", header, piece) })
+        .collect()
+}
+
+/// Greedily join segments while they fit in `max_chars`
+fn pack<'a>(segments: impl IntoIterator<Item = &'a str>, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for segment in segments {
+        if !current.is_empty() && current.len() + segment.len() > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push_str(segment);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// Split `text` before every line starting with `marker`
+fn split_before<'a>(text: &'a str, marker: &str) -> Vec<&'a str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        if offset > start && line.starts_with(marker) {
+            segments.push(&text[start..offset]);
+            start = offset;
+        }
+        offset += line.len();
+    }
+
+    if start < text.len() {
+        segments.push(&text[start..]);
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_diff(name: &str, hunks: usize) -> String {
+        let mut diff = format!("diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n", name);
+        for i in 0..hunks {
+            diff.push_str(&format!("@@ -{0},1 +{0},1 @@\n-old line {0}\n+new line {0}\n", i + 1));
+        }
+        diff
+    }
+
+    #[test]
+    fn test_chunk_by_file() {
+        let diff = format!("{}{}{}", file_diff("a.rs", 1), file_diff("b.rs", 1), file_diff("c.rs", 1));
+        let file_len = file_diff("a.rs", 1).len();
+
+        assert_eq!(chunk_diff(&diff, diff.len()), vec![diff.clone()]);
+
+        let chunks = chunk_diff(&diff, file_len * 2);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks.concat(), diff);
+        assert!(chunks[1].starts_with("diff --git a/c.rs"));
+    }
+
+    #[test]
+    fn test_chunk_large_file_by_hunk() {
+        let diff = file_diff("big.rs", 10);
+        let chunks = chunk_diff(&diff, 150);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 150);
+            assert!(chunk.starts_with("diff --git a/big.rs b/big.rs\n"));
+        }
+    }
+
+    #[test]
+    fn test_chunk_by_language() {
+        let diff = format!(
+            "{}{}{}{}",
+            file_diff("src/a.rs", 1),
+            file_diff("web/app.ts", 1),
+            file_diff("src/b.rs", 1),
+            file_diff("docker/Dockerfile", 1)
+        );
+
+        let chunks = chunk_diff_by_language(&diff, diff.len());
+        let languages: Vec<&str> = chunks.iter().map(|(language, _)| *language).collect();
+        assert_eq!(languages, vec!["dockerfile", "rust", "typescript"]);
+        assert_eq!(chunks[1].1, format!("{}{}", file_diff("src/a.rs", 1), file_diff("src/b.rs", 1)));
+
+        assert_eq!(diff_language("README"), "text");
+        assert_eq!(diff_language("ui/Button.TSX"), "typescript");
+    }
+}
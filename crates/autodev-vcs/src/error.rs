@@ -0,0 +1,61 @@
+use autodev_core::ErrorClass;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("API error: {0}")]
+    ApiError(String),
+
+    #[error("Authentication error: {0}")]
+    AuthError(String),
+
+    #[error("Repository not found: {0}")]
+    RepoNotFound(String),
+
+    #[error("Workflow not found: {0}")]
+    WorkflowNotFound(String),
+
+    #[error("Pull request not found: {0}")]
+    PullRequestNotFound(String),
+
+    #[error("Invalid repository config: {0}")]
+    InvalidConfig(String),
+
+    /// Error response of a provider API
+    #[error("HTTP {status}: {message}")]
+    Http {
+        status: u16,
+        message: String,
+        /// Delay asked for in Retry-After
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// The request never got a response (connection refused, timeout)
+    #[error("Request failed: {0}")]
+    Transport(String),
+
+    /// Error of a provider's own client, already classified by the provider
+    #[error("{message}")]
+    Provider { class: ErrorClass, message: String },
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl Error {
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            Error::AuthError(_) => ErrorClass::AuthError,
+            Error::RepoNotFound(_) | Error::WorkflowNotFound(_) | Error::PullRequestNotFound(_) => ErrorClass::NotFound,
+            Error::Http { status, retry_after, .. } => ErrorClass::from_status(*status, *retry_after),
+            Error::Transport(_) => ErrorClass::Transient,
+            Error::Provider { class, .. } => *class,
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,18 @@
+pub mod error;
+pub mod repository;
+pub mod types;
+pub mod provider;
+pub mod mergeability;
+pub mod checks;
+pub mod config;
+pub mod diff;
+
+// Re-exports
+pub use error::{Error, Result};
+pub use repository::Repository;
+pub use types::{Branch, DiffStat, MergeOptions, PullRequest, WorkflowStatus};
+pub use provider::VcsProvider;
+pub use mergeability::{CheckState, MergeBlocker, Mergeability};
+pub use checks::{CheckRunReport, CheckRunState, CHECK_RUN_NAME};
+pub use config::{RepoConfig, CONFIG_FILE, SUBTASK_WORKFLOW_FILE, WORKFLOW_FILE};
+pub use diff::{chunk_diff, chunk_diff_by_language, diff_language, DEFAULT_CHUNK_CHARS};
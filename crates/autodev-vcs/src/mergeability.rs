@@ -0,0 +1,221 @@
+use std::fmt;
+
+/// State of one status check on the head commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckState {
+    pub name: String,
+    /// None while the check is queued, running or not reported yet
+    pub conclusion: Option<String>,
+}
+
+impl CheckState {
+    fn is_failing(&self) -> bool {
+        matches!(
+            self.conclusion.as_deref(),
+            Some(c) if !matches!(c, "success" | "neutral" | "skipped")
+        )
+    }
+}
+
+/// Why a pull request cannot be merged yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeBlocker {
+    /// Closed without being merged
+    Closed,
+    Draft,
+    Conflicts,
+    /// The base branch requires the head branch to be up to date
+    Behind,
+    PendingChecks(Vec<String>),
+    FailingChecks(Vec<String>),
+    ReviewRequired { approvals: u32, required: u32 },
+    ChangesRequested,
+    /// The AI review gate found issues at or above the repository's threshold
+    ReviewFindings(String),
+    /// Mergeability is still being computed, or the reason is not visible to the token
+    Unknown(String),
+}
+
+impl MergeBlocker {
+    /// Waiting won't resolve it; a human has to fix the PR
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Closed
+                | Self::Conflicts
+                | Self::Behind
+                | Self::FailingChecks(_)
+                | Self::ChangesRequested
+                | Self::ReviewFindings(_)
+        )
+    }
+
+    /// Resolved by a reviewer rather than by CI
+    pub fn needs_review(&self) -> bool {
+        matches!(self, Self::Draft | Self::ReviewRequired { .. })
+    }
+}
+
+impl fmt::Display for MergeBlocker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => write!(f, "pull request is closed"),
+            Self::Draft => write!(f, "pull request is a draft"),
+            Self::Conflicts => write!(f, "merge conflicts with the base branch"),
+            Self::Behind => write!(f, "head branch is behind the base branch"),
+            Self::PendingChecks(checks) => write!(f, "required checks pending: {}", checks.join(", ")),
+            Self::FailingChecks(checks) => write!(f, "required checks failing: {}", checks.join(", ")),
+            Self::ReviewRequired { approvals, required } => {
+                write!(f, "review required ({}/{} approvals)", approvals, required)
+            }
+            Self::ChangesRequested => write!(f, "changes requested by a reviewer"),
+            Self::ReviewFindings(summary) => write!(f, "blocked by AI review ({})", summary),
+            Self::Unknown(state) => write!(f, "not mergeable yet ({})", state),
+        }
+    }
+}
+
+/// Whether a pull request can be merged, as reported by GitHub
+#[derive(Debug, Clone, Default)]
+pub struct Mergeability {
+    /// Already merged, by AutoDev or by someone else
+    pub merged: bool,
+    /// Closed without being merged
+    pub closed: bool,
+    /// None while GitHub is still computing it
+    pub mergeable: Option<bool>,
+    /// clean, unstable, has_hooks, blocked, behind, dirty, draft or unknown
+    pub mergeable_state: String,
+    pub draft: bool,
+    /// Checks required by branch protection, or every check on the head
+    /// commit when the protection rules are not readable with this token
+    pub required_checks: Vec<CheckState>,
+    pub approvals: u32,
+    pub required_approvals: u32,
+    pub changes_requested: bool,
+}
+
+impl Mergeability {
+    /// A merge request with nothing known to block it
+    ///
+    /// Reported by providers that don't expose branch protection; the merge
+    /// itself fails if the provider refuses it.
+    pub fn ready() -> Self {
+        Self {
+            mergeable: Some(true),
+            mergeable_state: "clean".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// What keeps the PR from being merged now, or None when it can be
+    ///
+    /// A merged PR has nothing blocking it; callers check [`Self::merged`]
+    /// before merging it again.
+    pub fn blocker(&self) -> Option<MergeBlocker> {
+        if self.merged {
+            return None;
+        }
+        if self.closed {
+            return Some(MergeBlocker::Closed);
+        }
+        if self.draft {
+            return Some(MergeBlocker::Draft);
+        }
+        if self.mergeable == Some(false) || self.mergeable_state == "dirty" {
+            return Some(MergeBlocker::Conflicts);
+        }
+
+        match self.mergeable_state.as_str() {
+            // unstable: 필수가 아닌 체크만 실패한 상태
+            "clean" | "unstable" | "has_hooks" => return None,
+            "behind" => return Some(MergeBlocker::Behind),
+            "blocked" => {}
+            other => return Some(MergeBlocker::Unknown(other.to_string())),
+        }
+
+        // blocked: 브랜치 보호 규칙 중 무엇이 막는지 찾음
+        let names = |failing: bool| -> Vec<String> {
+            self.required_checks
+                .iter()
+                .filter(|c| if failing { c.is_failing() } else { c.conclusion.is_none() })
+                .map(|c| c.name.clone())
+                .collect()
+        };
+
+        let failing = names(true);
+        if !failing.is_empty() {
+            return Some(MergeBlocker::FailingChecks(failing));
+        }
+        let pending = names(false);
+        if !pending.is_empty() {
+            return Some(MergeBlocker::PendingChecks(pending));
+        }
+        if self.changes_requested {
+            return Some(MergeBlocker::ChangesRequested);
+        }
+
+        if self.approvals < self.required_approvals {
+            return Some(MergeBlocker::ReviewRequired {
+                approvals: self.approvals,
+                required: self.required_approvals,
+            });
+        }
+
+        Some(MergeBlocker::Unknown("blocked".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocked() -> Mergeability {
+        Mergeability {
+            mergeable: Some(true),
+            mergeable_state: "blocked".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_blocker() {
+        assert_eq!(Mergeability::ready().blocker(), None);
+
+        let mut state = blocked();
+        state.required_checks = vec![
+            CheckState { name: "build".into(), conclusion: Some("success".into()) },
+            CheckState { name: "test".into(), conclusion: None },
+        ];
+        assert_eq!(state.blocker(), Some(MergeBlocker::PendingChecks(vec!["test".into()])));
+
+        state.required_checks[1].conclusion = Some("failure".into());
+        let blocker = state.blocker().unwrap();
+        assert_eq!(blocker, MergeBlocker::FailingChecks(vec!["test".into()]));
+        assert!(blocker.is_terminal());
+
+        state.required_checks.clear();
+        state.required_approvals = 2;
+        state.approvals = 1;
+        let blocker = state.blocker().unwrap();
+        assert_eq!(blocker, MergeBlocker::ReviewRequired { approvals: 1, required: 2 });
+        assert!(blocker.needs_review() && !blocker.is_terminal());
+
+        // Branch protection without required reviews: the reason is not visible
+        state.required_approvals = 0;
+        state.approvals = 0;
+        assert_eq!(state.blocker(), Some(MergeBlocker::Unknown("blocked".into())));
+
+        state.draft = true;
+        assert_eq!(state.blocker(), Some(MergeBlocker::Draft));
+
+        state.closed = true;
+        let blocker = state.blocker().unwrap();
+        assert_eq!(blocker, MergeBlocker::Closed);
+        assert!(blocker.is_terminal());
+
+        state.closed = false;
+        state.merged = true;
+        assert_eq!(state.blocker(), None);
+    }
+}
@@ -0,0 +1,187 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::{
+    Branch, CheckRunReport, DiffStat, Error, Mergeability, MergeOptions, PullRequest, RepoConfig, Repository, Result,
+    WorkflowStatus,
+};
+
+/// Code hosting backend the executor drives: CI pipelines, branches and
+/// merge requests (pull requests on GitHub)
+///
+/// Pipeline statuses are reported in GitHub Actions terms ("completed" with a
+/// "success" / "failure" / "cancelled" conclusion) so callers handle every
+/// provider the same way. Pipelines stopped on a manual or scheduled job
+/// are "completed" with an "action_required" conclusion.
+#[async_trait]
+pub trait VcsProvider: Send + Sync {
+    /// Provider name for logs ("github", "gitlab")
+    fn name(&self) -> &str;
+
+    /// Web URL of a merge request
+    fn merge_request_url(&self, repo: &Repository, number: u64) -> String;
+
+    /// Start a CI pipeline on `git_ref` (the branch a task works on), returning its ID
+    async fn trigger_pipeline(
+        &self,
+        repo: &Repository,
+        workflow_file: &str,
+        git_ref: &str,
+        inputs: HashMap<String, String>,
+    ) -> Result<u64>;
+
+    async fn pipeline_status(&self, repo: &Repository, pipeline_id: u64) -> Result<WorkflowStatus>;
+
+    async fn cancel_pipeline(&self, repo: &Repository, pipeline_id: u64) -> Result<()>;
+
+    async fn create_branch(&self, repo: &Repository, branch: &str, from_branch: &str) -> Result<()>;
+
+    async fn delete_branch(&self, repo: &Repository, branch: &str) -> Result<()>;
+
+    /// Branches whose name starts with `prefix`
+    async fn list_branches(&self, repo: &Repository, prefix: &str) -> Result<Vec<Branch>> {
+        Err(Error::ApiError(format!(
+            "{} cannot list the {}* branches of {}",
+            self.name(),
+            prefix,
+            repo.full_name()
+        )))
+    }
+
+    async fn create_merge_request(
+        &self,
+        repo: &Repository,
+        title: String,
+        body: String,
+        head: String,
+        base: String,
+        draft: bool,
+    ) -> Result<PullRequest>;
+
+    async fn merge_merge_request(&self, repo: &Repository, number: u64, options: &MergeOptions) -> Result<()>;
+
+    /// Close a merge request without merging it
+    async fn close_merge_request(&self, repo: &Repository, number: u64) -> Result<()>;
+
+    /// Source branch of a merge request
+    async fn merge_request_branch(&self, _repo: &Repository, number: u64) -> Result<String> {
+        Err(Error::ApiError(format!(
+            "{} cannot look up the branch of merge request {}",
+            self.name(),
+            number
+        )))
+    }
+
+    /// Whether the source branch of a merge request is in another repository (a fork)
+    async fn merge_request_from_fork(&self, _repo: &Repository, number: u64) -> Result<bool> {
+        Err(Error::ApiError(format!(
+            "{} cannot look up the source repository of merge request {}",
+            self.name(),
+            number
+        )))
+    }
+
+    /// Whether a merge request can be merged now, and what blocks it otherwise
+    ///
+    /// Providers without branch protection details report it as ready and
+    /// let the merge itself fail.
+    async fn merge_request_mergeability(&self, _repo: &Repository, _number: u64) -> Result<Mergeability> {
+        Ok(Mergeability::ready())
+    }
+
+    /// Take a merge request out of draft
+    async fn mark_ready_for_review(&self, _repo: &Repository, number: u64) -> Result<()> {
+        Err(Error::ApiError(format!(
+            "{} cannot mark merge request {} ready for review",
+            self.name(),
+            number
+        )))
+    }
+
+    async fn request_reviewers(&self, _repo: &Repository, number: u64, _reviewers: &[String]) -> Result<()> {
+        Err(Error::ApiError(format!(
+            "{} cannot request reviewers for merge request {}",
+            self.name(),
+            number
+        )))
+    }
+
+    /// Unified diff of a merge request, split into chunks of at most `max_chars`
+    async fn merge_request_diff(&self, _repo: &Repository, number: u64, _max_chars: usize) -> Result<Vec<String>> {
+        Err(Error::ApiError(format!(
+            "{} cannot fetch the diff of merge request {}",
+            self.name(),
+            number
+        )))
+    }
+
+    /// Files changed and lines added and removed by a merge request
+    async fn merge_request_diff_stat(&self, _repo: &Repository, number: u64) -> Result<DiffStat> {
+        Err(Error::ApiError(format!(
+            "{} cannot fetch the size of merge request {}",
+            self.name(),
+            number
+        )))
+    }
+
+    /// Replace the description of a merge request
+    async fn update_merge_request_body(&self, _repo: &Repository, number: u64, _body: &str) -> Result<()> {
+        Err(Error::ApiError(format!(
+            "{} cannot update merge request {}",
+            self.name(),
+            number
+        )))
+    }
+
+    /// Most recent merge request opened from `branch`, in any state
+    async fn find_merge_request_by_branch(&self, repo: &Repository, branch: &str) -> Result<Option<u64>>;
+
+    async fn is_merge_request_merged(&self, repo: &Repository, number: u64) -> Result<bool>;
+
+    async fn comment(&self, repo: &Repository, number: u64, body: &str) -> Result<()>;
+
+    /// Post a review on a merge request
+    ///
+    /// Providers without reviews post it as a comment.
+    async fn submit_review(&self, repo: &Repository, number: u64, body: &str) -> Result<()> {
+        self.comment(repo, number, body).await
+    }
+
+    /// Settings from the repository's `.autodev.yml`
+    async fn load_repo_config(&self, _repo: &Repository) -> Result<RepoConfig> {
+        Ok(RepoConfig::default())
+    }
+
+    /// Show AutoDev's status as a check on the head commit of `branch`
+    ///
+    /// Providers without a checks API ignore it.
+    async fn report_check(&self, _repo: &Repository, _branch: &str, _report: &CheckRunReport) -> Result<()> {
+        Ok(())
+    }
+
+    /// Pre-flight check that `repo` can run AutoDev pipelines
+    ///
+    /// Providers that need pipeline files in the repository verify (or
+    /// install) them here so a dispatch doesn't fail silently.
+    async fn check_pipeline_setup(&self, _repo: &Repository) -> Result<()> {
+        Ok(())
+    }
+
+    /// Add a commit on `branch` undoing the merge of merge request `number`
+    /// (like `git revert -m1` of its merge commit)
+    ///
+    /// Returns the commit SHA, or None when there is nothing to revert.
+    async fn revert_merge_request(
+        &self,
+        _repo: &Repository,
+        branch: &str,
+        number: u64,
+    ) -> Result<Option<String>> {
+        Err(Error::ApiError(format!(
+            "{} cannot revert #{} on {}",
+            self.name(),
+            number,
+            branch
+        )))
+    }
+}
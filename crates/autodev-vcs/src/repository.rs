@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Repository {
+    pub owner: String,
+    pub name: String,
+    pub branch: String,
+}
+
+impl Repository {
+    pub fn new(owner: String, name: String) -> Self {
+        Self {
+            owner,
+            name,
+            branch: "main".to_string(),
+        }
+    }
+
+    pub fn with_branch(mut self, branch: String) -> Self {
+        self.branch = branch;
+        self
+    }
+
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+}
+
+impl std::fmt::Display for Repository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.full_name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository_creation() {
+        let repo = Repository::new("owner".to_string(), "name".to_string());
+        assert_eq!(repo.owner, "owner");
+        assert_eq!(repo.name, "name");
+        assert_eq!(repo.branch, "main");
+        assert_eq!(repo.full_name(), "owner/name");
+    }
+
+    #[test]
+    fn test_with_branch() {
+        let repo = Repository::new("owner".to_string(), "name".to_string())
+            .with_branch("develop".to_string());
+        assert_eq!(repo.branch, "develop");
+    }
+}
@@ -0,0 +1,58 @@
+use autodev_core::MergeMethod;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStatus {
+    pub status: String,
+    pub conclusion: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub url: Option<String>,
+    pub title: String,
+}
+
+/// How to merge a pull request
+#[derive(Debug, Clone, Default)]
+pub struct MergeOptions {
+    pub method: MergeMethod,
+    /// Title of the merge or squash commit (the provider's default when None)
+    pub commit_title: Option<String>,
+    /// Body of the merge or squash commit
+    pub commit_message: Option<String>,
+}
+
+impl MergeOptions {
+    pub fn new(method: MergeMethod) -> Self {
+        Self {
+            method,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_commit(mut self, title: String, message: String) -> Self {
+        self.commit_title = Some(title);
+        self.commit_message = Some(message);
+        self
+    }
+}
+
+/// A branch with the time of its head commit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Branch {
+    pub name: String,
+    /// Committer date of the head commit (None when unknown)
+    pub committed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Open merge request from the branch
+    pub open_merge_request: Option<u64>,
+}
+
+/// Size of a change: files touched and lines added and removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStat {
+    pub files_changed: u64,
+    pub additions: u64,
+    pub deletions: u64,
+}
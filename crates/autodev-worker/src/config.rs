@@ -0,0 +1,102 @@
+//! Worker settings from the environment (or the `[env]` table of the config file)
+//!
+//! Every setting has a default; a value that is set but does not parse, or is
+//! out of range, stops the worker at startup instead of being ignored.
+
+use anyhow::{bail, Context, Result};
+use std::time::Duration;
+
+/// Seconds between two passes over the queue
+pub const POLL_INTERVAL_ENV: &str = "AUTODEV_WORKER_POLL_INTERVAL";
+/// Seconds a task nobody heartbeats for may stay InProgress
+pub const STALL_TIMEOUT_ENV: &str = "AUTODEV_WORKER_STALL_TIMEOUT";
+/// Tasks this worker runs at the same time
+pub const MAX_CONCURRENT_ENV: &str = "AUTODEV_WORKER_MAX_CONCURRENT";
+/// Days completed tasks are kept before they are archived
+pub const CLEANUP_RETENTION_ENV: &str = "AUTODEV_WORKER_CLEANUP_RETENTION_DAYS";
+/// Attempts after which the worker stops retrying, whatever the task's policy allows
+pub const MAX_ATTEMPTS_ENV: &str = "AUTODEV_WORKER_MAX_ATTEMPTS";
+
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+pub const DEFAULT_MAX_CONCURRENT: usize = 1;
+pub const DEFAULT_CLEANUP_RETENTION_DAYS: u32 = 7;
+
+#[derive(Debug, Clone)]
+pub struct WorkerConfig {
+    pub poll_interval: Duration,
+    pub stall_timeout: Duration,
+    pub max_concurrent: usize,
+    pub cleanup_retention_days: u32,
+    /// None leaves retries to each task's retry policy
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            stall_timeout: autodev_core::lease::UNLEASED_STALL_TIMEOUT,
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+            cleanup_retention_days: DEFAULT_CLEANUP_RETENTION_DAYS,
+            max_attempts: None,
+        }
+    }
+}
+
+impl WorkerConfig {
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        let config = Self {
+            poll_interval: env_number(POLL_INTERVAL_ENV)?.map_or(defaults.poll_interval, Duration::from_secs),
+            stall_timeout: env_number(STALL_TIMEOUT_ENV)?.map_or(defaults.stall_timeout, Duration::from_secs),
+            max_concurrent: env_number(MAX_CONCURRENT_ENV)?.unwrap_or(defaults.max_concurrent),
+            cleanup_retention_days: env_number(CLEANUP_RETENTION_ENV)?.unwrap_or(defaults.cleanup_retention_days),
+            max_attempts: env_number(MAX_ATTEMPTS_ENV)?,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.poll_interval.is_zero() {
+            bail!("{} must be at least 1 second", POLL_INTERVAL_ENV);
+        }
+        if self.stall_timeout <= self.poll_interval {
+            bail!(
+                "{} ({}s) must be longer than {} ({}s)",
+                STALL_TIMEOUT_ENV,
+                self.stall_timeout.as_secs(),
+                POLL_INTERVAL_ENV,
+                self.poll_interval.as_secs()
+            );
+        }
+        if self.max_concurrent == 0 {
+            bail!("{} must be at least 1", MAX_CONCURRENT_ENV);
+        }
+        if self.cleanup_retention_days == 0 {
+            bail!("{} must be at least 1 day", CLEANUP_RETENTION_ENV);
+        }
+        if self.max_attempts == Some(0) {
+            bail!("{} must be at least 1 (1 = never retry)", MAX_ATTEMPTS_ENV);
+        }
+        Ok(())
+    }
+
+    /// Whether a task that failed after `attempts` tries may be retried at all
+    pub fn allows_retry(&self, attempts: u32) -> bool {
+        self.max_attempts.is_none_or(|max| attempts < max)
+    }
+}
+
+/// Number in `name`, None when unset or empty
+fn env_number<T: std::str::FromStr>(name: &str) -> Result<Option<T>> {
+    match std::env::var(name) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse()
+            .map(Some)
+            .ok()
+            .with_context(|| format!("{} must be a whole number, got '{}'", name, value)),
+        _ => Ok(None),
+    }
+}
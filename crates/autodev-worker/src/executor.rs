@@ -1,59 +1,51 @@
 use anyhow::Result;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::path::PathBuf;
 
-use autodev_core::{AutoDevEngine, Task, TaskStatus};
-use autodev_github::{GitHubClient, Repository};
+use autodev_core::{AutoDevEngine, ExecutionBackend, Task, TaskStatus};
+use autodev_github::{GitHubClient, OrgClients, Repository};
 use autodev_ai::AIAgent;
 use autodev_db::Database;
-use autodev_local_executor::DockerExecutor;
+use autodev_executor::{audit, ExecutionRouter};
+
+/// Actor of the audit events recorded by the worker
+const AUDIT_ACTOR: &str = "system:worker";
 
 pub struct TaskExecutor {
     engine: Arc<AutoDevEngine>,
-    github_client: Arc<GitHubClient>,
+    /// GitHub clients per organization
+    orgs: Arc<OrgClients>,
     ai_agent: Arc<dyn AIAgent>,
     db: Option<Arc<Database>>,
-    local_executor: Option<Arc<DockerExecutor>>,
-    use_local_executor: bool,
-    autodev_server_url: String,
+    /// Resolves task backends; the worker runs no local executors
+    router: ExecutionRouter,
 }
 
 impl TaskExecutor {
     pub fn new(
         engine: Arc<AutoDevEngine>,
-        github_client: Arc<GitHubClient>,
+        orgs: Arc<OrgClients>,
         ai_agent: Arc<dyn AIAgent>,
         db: Option<Arc<Database>>,
     ) -> Self {
-        // Check if local executor should be used
-        let use_local_executor = std::env::var("AUTODEV_LOCAL_EXECUTOR")
-            .unwrap_or_else(|_| "false".to_string())
-            .to_lowercase() == "true";
-
-        let autodev_server_url = std::env::var("AUTODEV_SERVER_URL")
-            .unwrap_or_else(|_| "http://localhost:3000".to_string());
-
-        let local_executor = if use_local_executor {
-            // DockerExecutor::new is async, so we can't call it here in sync context
-            // This will be None for now, worker will use GitHub Actions mode
+        if autodev_executor::routing::local_executor_enabled() {
             tracing::warn!("Local executor not supported in worker mode, use API server instead");
-            None
-        } else {
-            None
-        };
+        }
 
         Self {
             engine,
-            github_client,
+            orgs,
             ai_agent,
             db,
-            local_executor,
-            use_local_executor,
-            autodev_server_url,
+            router: ExecutionRouter::new(ExecutionBackend::Actions),
         }
     }
 
+    /// Client with the credentials of the repository's organization
+    fn github(&self, repo: &Repository) -> &GitHubClient {
+        self.orgs.client(repo)
+    }
+
     pub async fn execute_task(&self, task: &Task) -> Result<()> {
         tracing::info!("Executing task: {} ({})", task.title, task.id);
 
@@ -73,25 +65,19 @@ impl TaskExecutor {
         let (owner, name) = self.get_repository_info(&task.id).await?;
         let repository = Repository::new(owner, name);
 
-        // Choose execution mode: Local Docker or GitHub Actions
-        if self.use_local_executor && self.local_executor.is_some() {
-            tracing::info!("Using LOCAL EXECUTOR mode");
-            self.execute_task_local(task, &repository, start_time).await
-        } else {
-            tracing::info!("Using GITHUB ACTIONS mode");
-            self.execute_task_github_actions(task, &repository, start_time).await
+        // Local backends run on the API server, which awaits them itself
+        let config = self.github(&repository).load_repo_config(&repository).await?;
+        let backend = self.router.resolve(task.execution_backend, &config);
+        if backend.is_local() {
+            anyhow::bail!(
+                "Task {} runs on the {} execution backend, which the worker cannot run. Use API server for local execution.",
+                task.id,
+                backend
+            );
         }
-    }
 
-    /// Execute task using Local Docker Executor
-    /// NOTE: Worker doesn't use local executor anymore, use API server instead
-    async fn execute_task_local(
-        &self,
-        _task: &Task,
-        _repository: &Repository,
-        _start_time: std::time::Instant,
-    ) -> Result<()> {
-        anyhow::bail!("Local executor not supported in worker mode. Use API server for Docker execution.")
+        tracing::info!("Using GITHUB ACTIONS mode");
+        self.execute_task_github_actions(task, &repository, start_time).await
     }
 
     /// Execute task using GitHub Actions (existing behavior)
@@ -101,10 +87,22 @@ impl TaskExecutor {
         repository: &Repository,
         start_time: std::time::Instant,
     ) -> Result<()> {
-        // Execute task with AI agent
-        let result = self.ai_agent
-            .execute_task(task, &format!("/workspace/{}", repository.full_name()))
-            .await?;
+        // Docker AI executor 등 코드를 수정할 수 없는 에이전트는 호출 전에 거절
+        if !self.ai_agent.supports(autodev_ai::Capability::ExecuteTask) {
+            anyhow::bail!(
+                "AI agent {} cannot execute tasks; route the execute operation to another agent (AI_ROUTES)",
+                self.ai_agent.model()
+            );
+        }
+
+        // Execute task with AI agent within its budgets, recording the tokens it uses
+        let result = autodev_executor::run_within_budget(
+            task,
+            &self.engine,
+            &self.db,
+            self.ai_agent.execute_task(task, &format!("/workspace/{}", repository.full_name())),
+        )
+        .await?;
 
         if !result.success {
             anyhow::bail!("AI agent execution failed");
@@ -114,37 +112,49 @@ impl TaskExecutor {
         tracing::debug!("Files changed: {:?}", result.files_changed);
 
         // Create branch if it doesn't exist
-        self.github_client
-            .create_branch(&repository, &result.pr_branch, &repository.branch)
+        self.github(repository)
+            .create_branch(repository, &result.pr_branch, &repository.branch)
             .await
             .ok(); // Ignore if branch already exists
 
         // Trigger GitHub Actions workflow
+        let config = self.github(repository).load_repo_config(repository).await?;
         let mut workflow_inputs = HashMap::new();
         workflow_inputs.insert("task_id".to_string(), task.id.clone());
         workflow_inputs.insert("branch".to_string(), result.pr_branch.clone());
         workflow_inputs.insert("commit_message".to_string(), result.commit_message.clone());
-        workflow_inputs.insert("prompt".to_string(), task.prompt.clone());
+        workflow_inputs.insert("prompt".to_string(), config.apply_to_prompt(&task.prompt));
 
-        let workflow_run_id = self.github_client
-            .trigger_workflow(&repository, "autodev.yml", workflow_inputs)
+        let workflow_run_id = self.github(repository)
+            .trigger_workflow(repository, config.workflow_file(), &result.pr_branch, workflow_inputs)
             .await?;
 
         tracing::info!("Triggered workflow: {} for task: {}", workflow_run_id, task.id);
+        audit::record(
+            &self.db,
+            audit::repository_event(AUDIT_ACTOR, "pipeline.trigger", repository)
+                .with_target(config.workflow_file())
+                .with_payload(serde_json::json!({
+                    "run_id": workflow_run_id,
+                    "task_id": task.id,
+                    "ref": result.pr_branch,
+                })),
+        )
+        .await;
 
         // Wait for workflow completion (simplified - in production, poll status)
         tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
 
         // Check workflow status
-        let status = self.github_client
-            .get_workflow_run_status(&repository, workflow_run_id)
+        let status = self.github(repository)
+            .get_workflow_run_status(repository, workflow_run_id)
             .await?;
 
         if status.status == "completed" && status.conclusion == Some("success".to_string()) {
             // Create pull request
-            let pr = self.github_client
+            let pr = self.github(repository)
                 .create_pull_request(
-                    &repository,
+                    repository,
                     format!("[AutoDev] {}", task.title),
                     format!(
                         "## AutoDev Task\n\n\
@@ -165,24 +175,52 @@ impl TaskExecutor {
                 .await?;
 
             tracing::info!("Created PR #{} for task: {}", pr.number, task.id);
+            audit::record(
+                &self.db,
+                audit::repository_event(AUDIT_ACTOR, "merge_request.create", repository)
+                    .with_target(pr.number.to_string())
+                    .with_payload(serde_json::json!({
+                        "task_id": task.id,
+                        "title": pr.title,
+                        "base": repository.branch,
+                    })),
+            )
+            .await;
+
+            if autodev_executor::pr_descriptions_enabled() {
+                let footer = format!("---\n**Task ID:** {}\n\n*Generated by AutoDev*", task.id);
+                if let Err(e) = autodev_executor::describe_merge_request(
+                    self.ai_agent.clone(),
+                    self.orgs.client(repository),
+                    repository,
+                    pr.number,
+                    &task.title,
+                    &task.description,
+                    &footer,
+                )
+                .await
+                {
+                    tracing::warn!("Keeping the generated body of PR #{}: {}", pr.number, e);
+                }
+            }
 
             // Update task status to Completed
             self.engine
                 .update_task_status(&task.id, TaskStatus::Completed, None)
                 .await?;
 
-            // Save metrics
-            let elapsed = start_time.elapsed();
-            if let Some(ref db) = self.db {
-                db.save_metrics(
-                    &task.id,
-                    elapsed.as_millis() as i64,
-                    result.files_changed.len() as i32,
-                    100, // Placeholder
-                    20,  // Placeholder
-                    500, // Placeholder
-                ).await?;
+            // Save metrics, sized by the PR
+            autodev_executor::save_merge_request_metrics(
+                &self.db,
+                self.github(repository),
+                repository,
+                &task.id,
+                Some(pr.number),
+                start_time.elapsed(),
+            )
+            .await;
 
+            if let Some(ref db) = self.db {
                 db.add_execution_log(
                     &task.id,
                     "COMPLETED",
@@ -232,13 +270,13 @@ impl TaskExecutor {
         let (owner, name) = self.get_repository_info(task_id).await?;
         let repository = Repository::new(owner, name);
 
-        // Get PR diff
-        let _pr = self.github_client.get_pull_request(&repository, pr_number).await?;
-        let pr_diff = ""; // Would fetch actual diff from GitHub
+        // Get PR diff, chunked for large PRs
+        let chunks = self.github(&repository)
+            .get_pr_diff_chunks(&repository, pr_number as u64, autodev_github::DEFAULT_CHUNK_CHARS)
+            .await?;
 
         // Use AI to address review comments
-        let result = self.ai_agent
-            .review_code_changes(pr_diff, &review_comments)
+        let result = autodev_ai::review_diff_chunks(self.ai_agent.as_ref(), &chunks, &review_comments)
             .await?;
 
         if result.success {
@@ -254,7 +292,7 @@ impl TaskExecutor {
                     .join("\n")
             );
 
-            self.github_client
+            self.github(&repository)
                 .create_pr_comment(&repository, pr_number, &comment)
                 .await?;
 
@@ -299,7 +337,7 @@ impl TaskExecutor {
                     .join("\n")
             );
 
-            self.github_client
+            self.github(&repository)
                 .create_pr_comment(&repository, pr_number, &comment)
                 .await?;
 
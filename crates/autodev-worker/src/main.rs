@@ -1,16 +1,26 @@
 use anyhow::Result;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
 use tokio::time::interval;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+mod config;
 mod executor;
+mod queue;
 mod scheduler;
+mod schedules;
 
-use autodev_core::{AutoDevEngine, TaskStatus};
-use autodev_github::GitHubClient;
-use autodev_ai::AIAgent;
+use autodev_core::{AutoDevEngine, FailureKind, Task, TaskStatus};
+use autodev_github::{GitHubClient, OrgClients};
+use autodev_ai::{AIAgent, AgentConfig};
 use autodev_db::Database;
+use autodev_notify::NotificationEvent;
+
+use config::WorkerConfig;
+use queue::TaskQueue;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -23,130 +33,315 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Load environment variables
+    // Load environment variables (.env, then the config file for unset ones)
     dotenv::dotenv().ok();
+    if let Some(path) = autodev_core::config::load_into_env(None)? {
+        tracing::info!("Loaded configuration from {}", path.display());
+    }
 
     tracing::info!("Starting AutoDev Worker");
 
-    // Initialize components
-    let engine = Arc::new(AutoDevEngine::new());
-
-    let github_token = std::env::var("GITHUB_TOKEN")
-        .expect("GITHUB_TOKEN must be set");
-    let github_client = Arc::new(GitHubClient::new(github_token)?);
+    // Invalid settings stop the worker here rather than being ignored
+    let config = Arc::new(WorkerConfig::from_env()?);
+    tracing::info!(
+        "Polling every {:?}, running up to {} tasks at once, stall timeout {:?}",
+        config.poll_interval,
+        config.max_concurrent,
+        config.stall_timeout
+    );
 
-    let ai_agent_type = std::env::var("AI_AGENT_TYPE")
-        .unwrap_or_else(|_| "claude-code".to_string());
-
-    let ai_agent: Arc<dyn AIAgent> = match ai_agent_type.as_str() {
-        "claude" | "claude-code" => {
-            let api_key = std::env::var("ANTHROPIC_API_KEY")
-                .expect("ANTHROPIC_API_KEY must be set");
-            Arc::new(autodev_ai::ClaudeAgent::new(api_key))
-        }
-        "gpt-4" | "openai" => {
-            tracing::warn!("OpenAI agent not implemented, using Claude instead");
-            let api_key = std::env::var("ANTHROPIC_API_KEY")
-                .expect("ANTHROPIC_API_KEY must be set");
-            Arc::new(autodev_ai::ClaudeAgent::new(api_key))
-        }
-        _ => {
-            let api_key = std::env::var("ANTHROPIC_API_KEY")
-                .expect("ANTHROPIC_API_KEY must be set");
-            Arc::new(autodev_ai::ClaudeAgent::new(api_key))
-        }
-    };
+    // Initialize components
+    // GitHub App when configured, otherwise GITHUB_TOKEN
+    // Organizations in AUTODEV_ORGS use their own token or App installation
+    let github_client = Arc::new(OrgClients::from_env(Arc::new(GitHubClient::from_env().await?))?);
 
     let db = if let Ok(db_url) = std::env::var("DATABASE_URL") {
         let database = Database::new(&db_url).await?;
+        database.check_schema_version().await?;
         Some(Arc::new(database))
     } else {
         None
     };
 
+    let ai_agent: Arc<dyn AIAgent> =
+        autodev_executor::with_response_cache(autodev_ai::create_agent(&AgentConfig::from_env())?, &db);
+
+    let engine = match db {
+        Some(ref database) => {
+            let engine = AutoDevEngine::with_store(database.clone());
+            engine.hydrate().await?;
+            Arc::new(engine)
+        }
+        None => Arc::new(AutoDevEngine::new()),
+    };
+
+    // With a database, tasks are claimed there so several workers can run side by side
+    let queue = db.as_ref().map(|database| Arc::new(TaskQueue::from_env(database.clone())));
+    if let Some(ref queue) = queue {
+        tracing::info!("Claiming tasks from the database as worker {}", queue.worker_id());
+    }
+
+    // SIGTERM / Ctrl-C stops claiming new tasks; the running one gets the grace period
+    let (stop_tx, mut stop_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        autodev_core::shutdown::signal().await;
+        let _ = stop_tx.send(true);
+    });
+    let grace = autodev_core::shutdown::grace_period();
+
+    let notifications = autodev_executor::EventNotifier::spawn(&engine);
+
+    let runner = Runner {
+        engine: engine.clone(),
+        github_client,
+        ai_agent,
+        db: db.clone(),
+        config: config.clone(),
+        stop: stop_rx.clone(),
+        grace,
+    };
+
     // Start worker loop
-    let mut ticker = interval(Duration::from_secs(10));
+    let mut ticker = interval(config.poll_interval);
 
     loop {
-        ticker.tick().await;
-
-        // Get ready tasks
-        let ready_tasks = engine.get_ready_tasks().await;
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = stop_rx.wait_for(|stop| *stop) => break,
+        }
 
-        if !ready_tasks.is_empty() {
-            tracing::info!("Found {} ready tasks", ready_tasks.len());
+        // Tasks a previous shutdown interrupted run again
+        engine.requeue_interrupted().await;
 
-            for task in ready_tasks {
-                tracing::info!("Processing task: {} - {}", task.id, task.title);
+        // Recurring tasks that are due join the queue
+        if let Some(ref database) = db {
+            match schedules::run_due_schedules(&engine, database).await {
+                Ok(0) => {}
+                Ok(created) => tracing::info!("Created {} scheduled tasks", created),
+                Err(e) => tracing::error!("Failed to run due schedules: {}", e),
+            }
+        }
 
-                // Execute task
-                let executor = executor::TaskExecutor::new(
-                    engine.clone(),
-                    github_client.clone(),
-                    ai_agent.clone(),
-                    db.clone(),
-                );
+        match queue {
+            Some(ref queue) => {
+                if let Err(e) = queue.requeue_expired().await {
+                    tracing::error!("Failed to requeue expired claims: {}", e);
+                }
+                match queue.unleased_stalled(config.stall_timeout).await {
+                    Ok(task_ids) => {
+                        // Another process may have started them; pick up their current state
+                        if !task_ids.is_empty() {
+                            if let Err(e) = engine.hydrate().await {
+                                tracing::error!("Failed to refresh tasks: {}", e);
+                            }
+                        }
+                        for task_id in task_ids {
+                            if let Some(task) = engine.get_task(&task_id).await {
+                                fail_stalled_task(&engine, &db, &config, &task).await;
+                            }
+                        }
+                    }
+                    Err(e) => tracing::error!("Failed to check for stalled tasks: {}", e),
+                }
 
-                match executor.execute_task(&task).await {
-                    Ok(_) => {
-                        tracing::info!("Task {} completed successfully", task.id);
+                while !*stop_rx.borrow() {
+                    // Claim up to max_concurrent tasks and run them side by side
+                    let mut claimed = Vec::new();
+                    while claimed.len() < config.max_concurrent {
+                        match queue.claim(&engine).await {
+                            Ok(Some(task)) => claimed.push(task),
+                            Ok(None) => break,
+                            Err(e) => {
+                                tracing::error!("Failed to claim a task: {}", e);
+                                break;
+                            }
+                        }
                     }
-                    Err(e) => {
-                        tracing::error!("Task {} failed: {}", task.id, e);
-
-                        // Update task status
-                        let _ = engine
-                            .update_task_status(&task.id, TaskStatus::Failed, Some(e.to_string()))
-                            .await;
-
-                        // Log to database
-                        if let Some(ref db) = db {
-                            let _ = db
-                                .add_execution_log(&task.id, "FAILED", &e.to_string())
-                                .await;
+                    if claimed.is_empty() {
+                        break;
+                    }
+                    runner.run_all(claimed, Some(queue)).await;
+                }
+            }
+            None => {
+                // Get ready tasks
+                let ready_tasks = engine.get_ready_tasks().await;
+
+                if !ready_tasks.is_empty() {
+                    tracing::info!("Found {} ready tasks", ready_tasks.len());
+
+                    for batch in ready_tasks.chunks(config.max_concurrent) {
+                        if *stop_rx.borrow() {
+                            break;
                         }
+                        runner.run_all(batch.to_vec(), None).await;
                     }
                 }
+
+                // Without a database there are no leases, so a task's age is all there is
+                check_stalled_tasks(&engine, &db, &config).await?;
             }
         }
 
-        // Check for stalled tasks
-        check_stalled_tasks(&engine, &db).await?;
-
         // Clean up completed tasks periodically
-        cleanup_completed_tasks(&engine, &db).await?;
+        cleanup_completed_tasks(&engine, &db, &config).await?;
+    }
+
+    notifications.shutdown().await;
+    if let Some(ref db) = db {
+        db.close().await;
+    }
+    tracing::info!("AutoDev Worker stopped");
+
+    Ok(())
+}
+
+/// What running a task needs, cloned into each task run side by side
+#[derive(Clone)]
+struct Runner {
+    engine: Arc<AutoDevEngine>,
+    github_client: Arc<OrgClients>,
+    ai_agent: Arc<dyn AIAgent>,
+    db: Option<Arc<Database>>,
+    config: Arc<WorkerConfig>,
+    stop: watch::Receiver<bool>,
+    grace: Duration,
+}
+
+impl Runner {
+    /// Run `tasks` concurrently and wait for all of them
+    ///
+    /// With a queue, the tasks were claimed from it: each one heartbeats its
+    /// lease while it runs and releases the claim afterwards.
+    async fn run_all(&self, tasks: Vec<Task>, queue: Option<&Arc<TaskQueue>>) {
+        let mut running = JoinSet::new();
+        for task in tasks {
+            let runner = self.clone();
+            let queue = queue.cloned();
+            running.spawn(async move {
+                match queue {
+                    Some(queue) => runner.run_claimed(&task, &queue).await,
+                    None => runner.run(&task).await,
+                }
+            });
+        }
+
+        while let Some(result) = running.join_next().await {
+            if let Err(e) = result {
+                tracing::error!("Task run panicked: {}", e);
+            }
+        }
+    }
+
+    async fn run(&self, task: &Task) {
+        let work = process_task(task, &self.engine, &self.github_client, &self.ai_agent, &self.db, &self.config);
+        if !run_until_shutdown(work, &self.stop, self.grace).await {
+            interrupt(&self.engine, task).await;
+        }
+    }
+
+    async fn run_claimed(&self, task: &Task, queue: &TaskQueue) {
+        let mut heartbeat = queue.start_heartbeat(&task.id);
+        tokio::select! {
+            () = self.run(task) => {
+                heartbeat.abort();
+                queue.release(&task.id).await;
+            }
+            // The lease expired and the task went back to the queue;
+            // whoever reclaims it runs it, so stop here without writing results
+            _ = &mut heartbeat => {
+                tracing::warn!("Lost the lease on task {}, abandoning it", task.id);
+            }
+        }
+    }
+}
+
+/// Drive `work` to completion unless a shutdown's grace period runs out first
+///
+/// Returns false when the work was abandoned.
+async fn run_until_shutdown(work: impl Future<Output = ()>, stop: &watch::Receiver<bool>, grace: Duration) -> bool {
+    let mut stop = stop.clone();
+    let deadline = async move {
+        let _ = stop.wait_for(|stop| *stop).await;
+        tracing::info!("Shutting down, waiting up to {:?} for the running task", grace);
+        tokio::time::sleep(grace).await;
+    };
+
+    tokio::select! {
+        _ = work => true,
+        _ = deadline => false,
+    }
+}
+
+/// Record that a shutdown abandoned `task` so the next start runs it again
+async fn interrupt(engine: &Arc<AutoDevEngine>, task: &Task) {
+    if let Err(e) = engine.interrupt_task(&task.id).await {
+        tracing::error!("Failed to mark task {} interrupted: {}", task.id, e);
+    }
+}
+
+/// Execute a task, marking it failed and scheduling a retry on error
+async fn process_task(
+    task: &Task,
+    engine: &Arc<AutoDevEngine>,
+    github_client: &Arc<OrgClients>,
+    ai_agent: &Arc<dyn AIAgent>,
+    db: &Option<Arc<Database>>,
+    config: &WorkerConfig,
+) {
+    tracing::info!("Processing task: {} - {}", task.id, task.title);
+
+    // Execute task
+    let executor = executor::TaskExecutor::new(
+        engine.clone(),
+        github_client.clone(),
+        ai_agent.clone(),
+        db.clone(),
+    );
+
+    match executor.execute_task(task).await {
+        Ok(_) => {
+            tracing::info!("Task {} completed successfully", task.id);
+        }
+        Err(e) if e.downcast_ref::<autodev_ai::Error>().is_some_and(|e| e.is_budget_exceeded()) => {
+            // Already paused; it runs again once retried after the budget is raised
+            tracing::warn!("Task {} paused: {}", task.id, e);
+        }
+        Err(e) => {
+            tracing::error!("Task {} failed: {}", task.id, e);
+
+            // Update task status
+            let _ = engine
+                .update_task_status(&task.id, TaskStatus::Failed, Some(e.to_string()))
+                .await;
+
+            // Log to database
+            if let Some(ref db) = db {
+                let _ = db
+                    .add_execution_log(&task.id, "FAILED", &e.to_string())
+                    .await;
+            }
+
+            // Back to Pending after the backoff unless the error would recur
+            spawn_retry(engine, db, config, task, FailureKind::ExecutionError, e);
+        }
     }
 }
 
 async fn check_stalled_tasks(
     engine: &Arc<AutoDevEngine>,
     db: &Option<Arc<Database>>,
+    config: &WorkerConfig,
 ) -> Result<()> {
     let tasks = engine.list_active_tasks().await;
     let now = chrono::Utc::now();
+    let timeout = chrono::Duration::from_std(config.stall_timeout)?;
 
     for task in tasks {
         if task.status == TaskStatus::InProgress {
             if let Some(started_at) = task.started_at {
-                let duration = now.signed_duration_since(started_at);
-
-                // If task has been running for more than 1 hour, mark as failed
-                if duration.num_hours() > 1 {
-                    tracing::warn!("Task {} appears to be stalled, marking as failed", task.id);
-
-                    let _ = engine
-                        .update_task_status(
-                            &task.id,
-                            TaskStatus::Failed,
-                            Some("Task timed out after 1 hour".to_string()),
-                        )
-                        .await;
-
-                    if let Some(ref db) = db {
-                        let _ = db
-                            .add_execution_log(&task.id, "TIMEOUT", "Task timed out after 1 hour")
-                            .await;
-                    }
+                if now.signed_duration_since(started_at) > timeout {
+                    fail_stalled_task(engine, db, config, &task).await;
                 }
             }
         }
@@ -155,12 +350,71 @@ async fn check_stalled_tasks(
     Ok(())
 }
 
+/// Fail a task nobody heartbeats for that ran past the stall timeout and retry it
+async fn fail_stalled_task(engine: &Arc<AutoDevEngine>, db: &Option<Arc<Database>>, config: &WorkerConfig, task: &Task) {
+    tracing::warn!("Task {} appears to be stalled, marking as failed", task.id);
+    let message = format!("Task timed out after {}s without a heartbeat", config.stall_timeout.as_secs());
+
+    let _ = engine
+        .update_task_status(&task.id, TaskStatus::Failed, Some(message.clone()))
+        .await;
+
+    if let Some(ref db) = db {
+        let _ = db.add_execution_log(&task.id, "TIMEOUT", &message).await;
+    }
+
+    spawn_retry(engine, db, config, task, FailureKind::Timeout, anyhow::anyhow!(message));
+}
+
+/// Retry a failed task in the background, notifying once no attempt is left
+///
+/// AUTODEV_WORKER_MAX_ATTEMPTS caps the attempts on top of the task's policy.
+fn spawn_retry(
+    engine: &Arc<AutoDevEngine>,
+    db: &Option<Arc<Database>>,
+    config: &WorkerConfig,
+    task: &Task,
+    kind: FailureKind,
+    error: anyhow::Error,
+) {
+    let engine = engine.clone();
+    let db = db.clone();
+    let task_id = task.id.clone();
+    let title = task.title.clone();
+    let config = config.clone();
+
+    tokio::spawn(async move {
+        let attempts = engine.get_task(&task_id).await.map_or(0, |task| task.attempts);
+        let retry = if config.allows_retry(attempts) {
+            autodev_executor::schedule_retry_for_error(&task_id, kind, &error, &engine, &db).await
+        } else {
+            tracing::info!("Task {} used up the worker's {} attempts", task_id, attempts);
+            Ok(false)
+        };
+
+        match retry {
+            Ok(true) => {}
+            Ok(false) => {
+                autodev_notify::notify(NotificationEvent::TaskFailed {
+                    task_id,
+                    title,
+                    error: Some(error.to_string()),
+                });
+            }
+            Err(e) => {
+                tracing::error!("Failed to schedule retry for task {}: {}", task_id, e);
+            }
+        }
+    });
+}
+
 async fn cleanup_completed_tasks(
     _engine: &Arc<AutoDevEngine>,
     db: &Option<Arc<Database>>,
+    config: &WorkerConfig,
 ) -> Result<()> {
     if let Some(ref db) = db {
-        // Get tasks completed more than 7 days ago
+        // Get tasks completed longer ago than the retention window
         let old_tasks = db.get_tasks_by_status(TaskStatus::Completed).await?;
         let now = chrono::Utc::now();
 
@@ -168,12 +422,21 @@ async fn cleanup_completed_tasks(
             if let Some(completed_at) = task.completed_at {
                 let duration = now.signed_duration_since(completed_at);
 
-                if duration.num_days() > 7 {
+                if duration.num_days() > i64::from(config.cleanup_retention_days) {
                     tracing::debug!("Archiving old task: {}", task.id);
                     // In a real implementation, move to archive table
                 }
             }
         }
+
+        // Container output is kept as long as the tasks themselves
+        let retention = chrono::Duration::days(i64::from(config.cleanup_retention_days));
+        if let Some(cutoff) = now.checked_sub_signed(retention) {
+            let deleted = db.delete_task_logs_before(cutoff).await?;
+            if deleted > 0 {
+                tracing::info!("Deleted {} task log lines older than {} days", deleted, config.cleanup_retention_days);
+            }
+        }
     }
 
     Ok(())
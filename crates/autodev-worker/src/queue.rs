@@ -0,0 +1,121 @@
+//! Database-backed task claiming so several workers can share one database
+//!
+//! Each worker still resolves dependencies and review holds with its engine,
+//! but only runs a task after claiming it in the `tasks` table. A claim is a
+//! lease the worker extends by heartbeating while the task runs; when a worker
+//! dies, its lease expires and the task goes back to the queue.
+
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use autodev_core::{AutoDevEngine, Task};
+use autodev_db::Database;
+
+/// Name this worker claims tasks under (default: `$HOSTNAME-<pid>`)
+pub const WORKER_ID_ENV: &str = "AUTODEV_WORKER_ID";
+
+pub struct TaskQueue {
+    db: Arc<Database>,
+    worker_id: String,
+    lease: Duration,
+}
+
+impl TaskQueue {
+    pub fn from_env(db: Arc<Database>) -> Self {
+        let worker_id = std::env::var(WORKER_ID_ENV)
+            .ok()
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| {
+                let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "autodev-worker".to_string());
+                format!("{}-{}", host, std::process::id())
+            });
+        let lease = autodev_core::lease::lease_duration();
+
+        Self { db, worker_id, lease }
+    }
+
+    pub fn worker_id(&self) -> &str {
+        &self.worker_id
+    }
+
+    /// Refresh the engine from the database and claim one ready task
+    ///
+    /// Returns None when no ready task is left unclaimed.
+    pub async fn claim(&self, engine: &AutoDevEngine) -> Result<Option<Task>> {
+        // Pick up tasks created elsewhere and status changes made by other workers
+        engine.hydrate().await?;
+
+        let candidates: Vec<String> = engine
+            .get_ready_tasks()
+            .await
+            .into_iter()
+            .map(|task| task.id)
+            .collect();
+
+        let Some(record) = self.db.claim_task(&candidates, &self.worker_id, self.lease).await? else {
+            return Ok(None);
+        };
+
+        tracing::debug!("Worker {} claimed task {}", self.worker_id, record.id);
+
+        match engine.get_task(&record.id).await {
+            Some(task) => Ok(Some(task)),
+            None => Ok(Some(record.to_task()?)),
+        }
+    }
+
+    /// Keep extending the lease on `task_id` until the returned handle is aborted
+    ///
+    /// The handle finishes on its own once the claim is lost (the lease expired
+    /// and the task was requeued), so the caller can abandon the task instead
+    /// of racing the worker that reclaims it.
+    pub fn start_heartbeat(&self, task_id: &str) -> JoinHandle<()> {
+        let db = self.db.clone();
+        let worker_id = self.worker_id.clone();
+        let task_id = task_id.to_string();
+        let lease = self.lease;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(lease / 3);
+            // The claim itself counts as the first heartbeat
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                match db.heartbeat_task(&task_id, &worker_id, lease).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        tracing::warn!("Worker {} no longer holds the claim on task {}", worker_id, task_id);
+                        break;
+                    }
+                    Err(e) => tracing::warn!("Heartbeat for task {} failed: {}", task_id, e),
+                }
+            }
+        })
+    }
+
+    /// Release the claim after the task ran (errors are logged, the lease expires anyway)
+    pub async fn release(&self, task_id: &str) {
+        if let Err(e) = self.db.release_task(task_id, &self.worker_id).await {
+            tracing::warn!("Failed to release claim on task {}: {}", task_id, e);
+        }
+    }
+
+    /// Requeue tasks left InProgress by workers whose lease expired
+    pub async fn requeue_expired(&self) -> Result<()> {
+        for task_id in self.db.requeue_expired_claims().await? {
+            tracing::warn!("Lease on task {} expired, requeued", task_id);
+            if let Err(e) = self.db.add_execution_log(&task_id, "LEASE_EXPIRED", "Lease expired without a heartbeat, requeued").await {
+                tracing::warn!("Failed to log requeue of task {}: {}", task_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// InProgress tasks holding no lease that started longer ago than `timeout`
+    pub async fn unleased_stalled(&self, timeout: Duration) -> Result<Vec<String>> {
+        Ok(self.db.get_unleased_stalled_tasks(timeout).await?)
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use std::sync::Arc;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
 use autodev_core::{AutoDevEngine, Task};
 
@@ -81,15 +81,10 @@ impl TaskScheduler {
         let mut rec_stack = HashSet::new();
 
         for task in tasks {
-            if !visited.contains(&task.id) {
-                if self.has_cycle(
-                    &task.id,
-                    tasks,
-                    &mut visited,
-                    &mut rec_stack,
-                )? {
-                    anyhow::bail!("Circular dependency detected involving task: {}", task.id);
-                }
+            if !visited.contains(&task.id)
+                && self.has_cycle(&task.id, tasks, &mut visited, &mut rec_stack)?
+            {
+                anyhow::bail!("Circular dependency detected involving task: {}", task.id);
             }
         }
 
@@ -128,95 +123,8 @@ impl TaskScheduler {
         Ok(false)
     }
 
-    /// Calculate critical path (longest dependency chain)
+    /// Calculate critical path (longest dependency chain, weighted by estimated duration)
     pub fn calculate_critical_path(&self, tasks: &[Task]) -> Vec<String> {
-        let mut path_lengths: HashMap<String, usize> = HashMap::new();
-        let mut paths: HashMap<String, Vec<String>> = HashMap::new();
-
-        // Topological sort
-        let sorted_tasks = self.topological_sort(tasks);
-
-        for task in &sorted_tasks {
-            if task.dependencies.is_empty() {
-                path_lengths.insert(task.id.clone(), 1);
-                paths.insert(task.id.clone(), vec![task.id.clone()]);
-            } else {
-                let max_dep_length = task.dependencies
-                    .iter()
-                    .map(|dep| path_lengths.get(dep).unwrap_or(&0))
-                    .max()
-                    .unwrap_or(&0);
-
-                path_lengths.insert(task.id.clone(), max_dep_length + 1);
-
-                // Find the dependency with the longest path
-                if let Some(longest_dep) = task.dependencies
-                    .iter()
-                    .max_by_key(|dep| path_lengths.get(*dep).unwrap_or(&0))
-                {
-                    let mut path = paths.get(longest_dep).cloned().unwrap_or_default();
-                    path.push(task.id.clone());
-                    paths.insert(task.id.clone(), path);
-                }
-            }
-        }
-
-        // Find the longest path overall
-        paths.values()
-            .max_by_key(|path| path.len())
-            .cloned()
-            .unwrap_or_default()
-    }
-
-    fn topological_sort(&self, tasks: &[Task]) -> Vec<Task> {
-        let mut sorted = Vec::new();
-        let mut visited = HashSet::new();
-        let mut temp_visited = HashSet::new();
-
-        for task in tasks {
-            if !visited.contains(&task.id) {
-                self.dfs_visit(
-                    task,
-                    tasks,
-                    &mut visited,
-                    &mut temp_visited,
-                    &mut sorted,
-                );
-            }
-        }
-
-        sorted.reverse();
-        sorted
-    }
-
-    fn dfs_visit(
-        &self,
-        task: &Task,
-        tasks: &[Task],
-        visited: &mut HashSet<String>,
-        temp_visited: &mut HashSet<String>,
-        sorted: &mut Vec<Task>,
-    ) {
-        if temp_visited.contains(&task.id) {
-            // Cycle detected, but we'll ignore for sorting
-            return;
-        }
-
-        if visited.contains(&task.id) {
-            return;
-        }
-
-        temp_visited.insert(task.id.clone());
-
-        // Visit dependencies first
-        for dep_id in &task.dependencies {
-            if let Some(dep_task) = tasks.iter().find(|t| t.id == *dep_id) {
-                self.dfs_visit(dep_task, tasks, visited, temp_visited, sorted);
-            }
-        }
-
-        temp_visited.remove(&task.id);
-        visited.insert(task.id.clone());
-        sorted.push(task.clone());
+        autodev_core::composite_task::critical_path(tasks).0
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,71 @@
+//! Recurring tasks
+//!
+//! On every tick the worker creates a task from each schedule that is due and
+//! moves the schedule to its next run. Workers running side by side race for
+//! the run in the database; only the winner creates the task. Runs missed
+//! while no worker was up are not caught up: the schedule runs once and
+//! continues from the current time.
+
+use anyhow::Result;
+use std::sync::Arc;
+
+use autodev_core::{AutoDevEngine, CronSchedule};
+use autodev_db::{Database, ScheduleRecord};
+
+/// Create the tasks of all due schedules, returning how many were created
+pub async fn run_due_schedules(engine: &Arc<AutoDevEngine>, db: &Database) -> Result<usize> {
+    let now = chrono::Utc::now();
+    let mut created = 0;
+
+    for schedule in db.get_due_schedules(now).await? {
+        match run_schedule(&schedule, engine, db, now).await {
+            Ok(true) => created += 1,
+            Ok(false) => {}
+            Err(e) => tracing::error!("Failed to run schedule {}: {}", schedule.id, e),
+        }
+    }
+
+    Ok(created)
+}
+
+async fn run_schedule(
+    schedule: &ScheduleRecord,
+    engine: &Arc<AutoDevEngine>,
+    db: &Database,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<bool> {
+    let Some(due_at) = schedule.next_run_at else {
+        return Ok(false);
+    };
+
+    // 잘못된 식은 생성/수정 시 거부되므로 여기서는 건너뛰기만 함
+    let cron: CronSchedule = match schedule.cron_expression.parse() {
+        Ok(cron) => cron,
+        Err(e) => {
+            tracing::warn!("Skipping schedule {}: {}", schedule.id, e);
+            return Ok(false);
+        }
+    };
+
+    let task = schedule.to_task();
+    if !db.claim_schedule_run(schedule.id, due_at, cron.next_after(now), &task.id).await? {
+        tracing::debug!("Schedule {} was already run by another worker", schedule.id);
+        return Ok(false);
+    }
+
+    // Repository first: the engine only stores the task state
+    db.save_task(&task, &schedule.repository_owner, &schedule.repository_name)
+        .await?;
+    engine.register_task(task.clone()).await?;
+
+    tracing::info!(
+        "Schedule {} ({}) created task {} for {}/{}",
+        schedule.id,
+        schedule.cron_expression,
+        task.id,
+        schedule.repository_owner,
+        schedule.repository_name
+    );
+
+    Ok(true)
+}
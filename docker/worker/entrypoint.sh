@@ -10,13 +10,17 @@ echo "[$(date -Iseconds)] =========================================="
 echo "[$(date -Iseconds)] AutoDev Worker Starting"
 echo "[$(date -Iseconds)] =========================================="
 
+# result.json 스키마 버전 (Dockerfile의 io.autodev.result-schema 라벨과 같은 값)
+RESULT_SCHEMA_VERSION="${RESULT_SCHEMA_VERSION:-2}"
+
 # Error handler function
 send_error_callback() {
   local error_msg="$1"
 
   echo "[$(date -Iseconds)] ERROR: ${error_msg}"
 
-  if [ -n "$AUTODEV_SERVER_URL" ]; then
+  # fix/resolve 모드는 태스크가 아니므로 서버 콜백 없이 결과 파일만 기록
+  if [ -n "$AUTODEV_SERVER_URL" ] && [ "${WORK_MODE:-task}" = "task" ]; then
     echo "[$(date -Iseconds)] Notifying AutoDev server of error..."
 
     PAYLOAD=$(cat <<EOF
@@ -43,6 +47,7 @@ EOF
   # Write error to output file
   cat > /output/result.json <<EOF
 {
+  "schema_version": ${RESULT_SCHEMA_VERSION},
   "has_changes": false,
   "pr_number": null,
   "pr_url": null,
@@ -61,6 +66,13 @@ trap 'send_error_callback "Script failed at line $LINENO"' ERR
 : "${GITHUB_TOKEN:?GITHUB_TOKEN is required}"
 : "${TASK_ID:?TASK_ID is required}"
 : "${TASK_TITLE:?TASK_TITLE is required}"
+
+# 프롬프트가 없으면 서버의 payload store에서 가져옴
+if [ -z "${TASK_PROMPT:-}" ] && [ -n "${AUTODEV_SERVER_URL:-}" ]; then
+  TASK_PROMPT=$(curl -fsS ${AUTODEV_API_KEY:+-H "X-API-Key: ${AUTODEV_API_KEY}"} \
+    "${AUTODEV_SERVER_URL}/tasks/${TASK_ID}/payload" \
+    | node -e 'let d="";process.stdin.on("data",c=>d+=c).on("end",()=>process.stdout.write(JSON.parse(d).prompt))')
+fi
 : "${TASK_PROMPT:?TASK_PROMPT is required}"
 : "${REPO_OWNER:?REPO_OWNER is required}"
 : "${REPO_NAME:?REPO_NAME is required}"
@@ -71,6 +83,28 @@ trap 'send_error_callback "Script failed at line $LINENO"' ERR
 # Optional: AutoDev server callback URL
 AUTODEV_SERVER_URL="${AUTODEV_SERVER_URL:-}"
 
+# task: 새 브랜치에서 작업 후 PR 생성 (기본값)
+# fix: 기존 브랜치(TARGET_BRANCH)를 직접 수정하고 푸시 (CI 실패 수정)
+# resolve: TARGET_BRANCH를 BASE_BRANCH 위로 rebase하며 충돌을 해결하고 force push
+WORK_MODE="${WORK_MODE:-task}"
+
+# 충돌이 난 커밋마다 에이전트를 실행하는 횟수 상한
+MAX_RESOLVE_STEPS="${MAX_RESOLVE_STEPS:-20}"
+
+# Claude Code 실행 (출력을 별도 로그 파일에도 저장)
+# No --max-turns limit to allow completion of complex tasks
+run_claude() {
+  claude \
+    --dangerously-skip-permissions \
+    --allowedTools "Bash,Read,Write,Edit,Glob,Grep" \
+    --model sonnet \
+    --output-format text \
+    --append-system-prompt "Make autonomous decisions and modify files directly without asking questions. Complete the task in minimal steps." \
+    "$1" 2>&1 | tee -a /output/claude.log
+
+  return "${PIPESTATUS[0]}"
+}
+
 # 인증 방식 확인 및 검증
 echo "[$(date -Iseconds)] =========================================="
 echo "[$(date -Iseconds)] Checking authentication method..."
@@ -108,18 +142,106 @@ export GH_TOKEN="${GITHUB_TOKEN}"
 
 # Git 저장소 클론
 echo "[$(date -Iseconds)] Cloning repository ${REPO_OWNER}/${REPO_NAME}..."
-git clone "https://${GITHUB_TOKEN}@github.com/${REPO_OWNER}/${REPO_NAME}.git" repo
+# 호스트의 미러가 마운트되어 있으면 그 객체를 재사용해 새 객체만 받음
+# (--dissociate: 객체를 복사해 두어 미러가 정리되어도 클론이 깨지지 않음)
+git clone --reference-if-able /cache/repo.git --dissociate \
+  "https://${GITHUB_TOKEN}@github.com/${REPO_OWNER}/${REPO_NAME}.git" repo
 cd repo
 
-# BASE_BRANCH를 부모 브랜치로 사용하고, 태스크 전용 브랜치 생성
-# 언더스코어를 사용하여 Git ref 계층 구조 충돌 회피
-TASK_BRANCH="${BASE_BRANCH}_${TASK_ID}"
+if [ "${WORK_MODE}" = "resolve" ]; then
+  TASK_BRANCH="${TARGET_BRANCH}"
+
+  echo "[$(date -Iseconds)] Rebasing ${TASK_BRANCH} onto ${BASE_BRANCH}"
+  git fetch origin "${TASK_BRANCH}" "${BASE_BRANCH}"
+  git checkout -B "${TASK_BRANCH}" "origin/${TASK_BRANCH}"
+
+  RESOLVED_FILES=""
+  STEPS=0
+
+  if ! git rebase "origin/${BASE_BRANCH}"; then
+    while true; do
+      mapfile -t CONFLICTS < <(git diff --name-only --diff-filter=U)
+
+      if [ ${#CONFLICTS[@]} -gt 0 ]; then
+        STEPS=$((STEPS + 1))
+        if [ ${STEPS} -gt "${MAX_RESOLVE_STEPS}" ]; then
+          git rebase --abort
+          send_error_callback "Gave up after ${MAX_RESOLVE_STEPS} conflicting commits"
+        fi
+
+        echo "[$(date -Iseconds)] Resolving conflicts in: ${CONFLICTS[*]}"
+        if ! run_claude "${TASK_PROMPT}
+
+## Conflicting files
+$(printf -- '- %s\n' "${CONFLICTS[@]}")"; then
+          git rebase --abort
+          send_error_callback "Claude Code failed to resolve conflicts in ${CONFLICTS[*]}"
+        fi
+
+        # 충돌 마커가 남아 있으면 해결되지 않은 것으로 봄
+        if grep -lE '^(<<<<<<<|>>>>>>>)( |$)' -- "${CONFLICTS[@]}" 2>/dev/null; then
+          git rebase --abort
+          send_error_callback "Conflict markers left in ${CONFLICTS[*]}"
+        fi
+
+        git add -A -- "${CONFLICTS[@]}"
+        RESOLVED_FILES="${RESOLVED_FILES}$(printf '%s\n' "${CONFLICTS[@]}")
+"
+      fi
+
+      if GIT_EDITOR=true git rebase --continue; then
+        break
+      fi
+
+      # 해결 후 비어 버린 커밋은 건너뜀
+      if [ -z "$(git diff --name-only --diff-filter=U)" ] && git diff --cached --quiet; then
+        if git rebase --skip; then
+          break
+        fi
+      fi
+    done
+  fi
 
-echo "[$(date -Iseconds)] Fetching parent branch: ${BASE_BRANCH}"
-git fetch origin "${BASE_BRANCH}"
+  echo "[$(date -Iseconds)] Pushing rebased branch: ${TASK_BRANCH}"
+  git push --force-with-lease origin "${TASK_BRANCH}"
 
-echo "[$(date -Iseconds)] Creating task branch: ${TASK_BRANCH} from origin/${BASE_BRANCH}"
-git checkout -b "${TASK_BRANCH}" "origin/${BASE_BRANCH}"
+  COMMIT_SHA=$(git rev-parse HEAD)
+  FILES_JSON=$(printf '%s' "${RESOLVED_FILES}" \
+    | node -e 'let s = ""; process.stdin.on("data", (d) => (s += d)).on("end", () => console.log(JSON.stringify([...new Set(s.split("\n").filter(Boolean))])))')
+
+  cat > /output/result.json <<EOF
+{
+  "schema_version": ${RESULT_SCHEMA_VERSION},
+  "has_changes": true,
+  "pr_number": null,
+  "pr_url": null,
+  "success": true,
+  "error": null,
+  "commit_sha": "${COMMIT_SHA}",
+  "files_changed": ${FILES_JSON}
+}
+EOF
+  exit 0
+fi
+
+if [ "${WORK_MODE}" = "fix" ]; then
+  # 기존 브랜치를 그대로 체크아웃하여 수정
+  TASK_BRANCH="${TARGET_BRANCH}"
+
+  echo "[$(date -Iseconds)] Fetching branch to fix: ${TASK_BRANCH}"
+  git fetch origin "${TASK_BRANCH}"
+  git checkout -B "${TASK_BRANCH}" "origin/${TASK_BRANCH}"
+else
+  # BASE_BRANCH를 부모 브랜치로 사용하고, 태스크 전용 브랜치 생성
+  # 언더스코어를 사용하여 Git ref 계층 구조 충돌 회피
+  TASK_BRANCH="${BASE_BRANCH}_${TASK_ID}"
+
+  echo "[$(date -Iseconds)] Fetching parent branch: ${BASE_BRANCH}"
+  git fetch origin "${BASE_BRANCH}"
+
+  echo "[$(date -Iseconds)] Creating task branch: ${TASK_BRANCH} from origin/${BASE_BRANCH}"
+  git checkout -b "${TASK_BRANCH}" "origin/${BASE_BRANCH}"
+fi
 
 echo ""
 echo "[$(date -Iseconds)] Executing Claude Code..."
@@ -127,17 +249,8 @@ echo "[$(date -Iseconds)] Task: ${TASK_TITLE}"
 echo "[$(date -Iseconds)] Prompt: ${TASK_PROMPT}"
 echo ""
 
-# Claude Code 실행 (출력을 별도 로그 파일에도 저장)
-# No --max-turns limit to allow completion of complex tasks
-claude \
-  --dangerously-skip-permissions \
-  --allowedTools "Bash,Read,Write,Edit,Glob,Grep" \
-  --model sonnet \
-  --output-format text \
-  --append-system-prompt "Make autonomous decisions and modify files directly without asking questions. Complete the task in minimal steps." \
-  "${TASK_PROMPT}" 2>&1 | tee /output/claude.log
-
-CLAUDE_EXIT_CODE=${PIPESTATUS[0]}
+CLAUDE_EXIT_CODE=0
+run_claude "${TASK_PROMPT}" || CLAUDE_EXIT_CODE=$?
 
 echo ""
 echo "[$(date -Iseconds)] Claude Code execution completed with exit code: ${CLAUDE_EXIT_CODE}"
@@ -155,6 +268,7 @@ if git diff --staged --quiet; then
   echo "[$(date -Iseconds)] No changes to commit"
   cat > /output/result.json <<EOF
 {
+  "schema_version": ${RESULT_SCHEMA_VERSION},
   "has_changes": false,
   "pr_number": null,
   "pr_url": null,
@@ -184,6 +298,33 @@ Co-Authored-By: Claude <noreply@anthropic.com>"
 echo "[$(date -Iseconds)] Pushing task branch to origin: ${TASK_BRANCH}"
 git push origin "${TASK_BRANCH}"
 
+# 커밋의 변경 파일과 추가/삭제 줄 수 (바이너리 파일은 줄 수 0)
+FILES_JSON=$(git diff --name-only HEAD~1 HEAD \
+  | node -e 'let s = ""; process.stdin.on("data", (d) => (s += d)).on("end", () => console.log(JSON.stringify(s.split("\n").filter(Boolean))))')
+read -r LINES_ADDED LINES_REMOVED <<< "$(git diff --numstat HEAD~1 HEAD | awk '{ a += $1; r += $2 } END { print a + 0, r + 0 }')"
+
+if [ "${WORK_MODE}" = "fix" ]; then
+  COMMIT_SHA=$(git rev-parse HEAD)
+
+  echo "[$(date -Iseconds)] Fix pushed to ${TASK_BRANCH}: ${COMMIT_SHA}"
+
+  cat > /output/result.json <<EOF
+{
+  "schema_version": ${RESULT_SCHEMA_VERSION},
+  "has_changes": true,
+  "pr_number": null,
+  "pr_url": null,
+  "success": true,
+  "error": null,
+  "commit_sha": "${COMMIT_SHA}",
+  "files_changed": ${FILES_JSON},
+  "lines_added": ${LINES_ADDED},
+  "lines_removed": ${LINES_REMOVED}
+}
+EOF
+  exit 0
+fi
+
 echo ""
 echo "[$(date -Iseconds)] Creating Pull Request..."
 echo ""
@@ -222,11 +363,15 @@ echo "[$(date -Iseconds)] PR created: $PR_URL (#${PR_NUMBER})"
 # 결과 출력
 cat > /output/result.json <<EOF
 {
+  "schema_version": ${RESULT_SCHEMA_VERSION},
   "has_changes": true,
   "pr_number": ${PR_NUMBER},
   "pr_url": "${PR_URL}",
   "success": true,
-  "error": null
+  "error": null,
+  "files_changed": ${FILES_JSON},
+  "lines_added": ${LINES_ADDED},
+  "lines_removed": ${LINES_REMOVED}
 }
 EOF
 